@@ -10,6 +10,48 @@ mod m007_notes_unique_main;
 mod m008_fix_writing_idea_links;
 mod m009_research_connectors;
 mod m010_reader_cockpit;
+mod m011_newsletter_sends;
+mod m012_writing_publications;
+mod m013_news_articles_fts;
+mod m014_embeddings;
+mod m015_reader_summaries;
+mod m016_ai_settings;
+mod m017_alert_rules;
+mod m018_article_relevance;
+mod m019_saved_searches;
+mod m020_mute_rules;
+mod m021_reading_queue;
+mod m022_reading_progress;
+mod m023_reader_snapshot_assets;
+mod m024_reader_snapshot_archive;
+mod m025_reader_watch;
+mod m026_reader_clip_labels;
+mod m027_reader_clip_settings;
+mod m028_reader_site_rules;
+mod m029_site_credentials;
+mod m030_audit_log;
+mod m031_task_run_windows;
+mod m032_task_jitter;
+mod m033_task_dependencies;
+mod m034_scheduler_pause;
+mod m035_task_history_retention;
+mod m036_task_catch_up;
+mod m037_task_run_params;
+mod m038_task_concurrency_limits;
+mod m039_storage_cleanup_task;
+mod m040_db_maintenance_task;
+mod m041_task_interval_seconds;
+mod m042_task_scheduled_runs;
+mod m043_knowledge_graph_entity_columns;
+mod m044_writing_sync_files;
+mod m045_postgres_column_types;
+mod m046_site_credential_last_used;
+mod m047_secret_setting_type;
+mod m048_note_canonical_markdown;
+mod m049_note_templates;
+mod m050_note_links;
+mod m051_idea_sort_order;
+mod m052_idea_due_dates;
 
 pub struct Migrator;
 
@@ -27,6 +69,48 @@ impl MigratorTrait for Migrator {
             Box::new(m008_fix_writing_idea_links::Migration),
             Box::new(m009_research_connectors::Migration),
             Box::new(m010_reader_cockpit::Migration),
+            Box::new(m011_newsletter_sends::Migration),
+            Box::new(m012_writing_publications::Migration),
+            Box::new(m013_news_articles_fts::Migration),
+            Box::new(m014_embeddings::Migration),
+            Box::new(m015_reader_summaries::Migration),
+            Box::new(m016_ai_settings::Migration),
+            Box::new(m017_alert_rules::Migration),
+            Box::new(m018_article_relevance::Migration),
+            Box::new(m019_saved_searches::Migration),
+            Box::new(m020_mute_rules::Migration),
+            Box::new(m021_reading_queue::Migration),
+            Box::new(m022_reading_progress::Migration),
+            Box::new(m023_reader_snapshot_assets::Migration),
+            Box::new(m024_reader_snapshot_archive::Migration),
+            Box::new(m025_reader_watch::Migration),
+            Box::new(m026_reader_clip_labels::Migration),
+            Box::new(m027_reader_clip_settings::Migration),
+            Box::new(m028_reader_site_rules::Migration),
+            Box::new(m029_site_credentials::Migration),
+            Box::new(m030_audit_log::Migration),
+            Box::new(m031_task_run_windows::Migration),
+            Box::new(m032_task_jitter::Migration),
+            Box::new(m033_task_dependencies::Migration),
+            Box::new(m034_scheduler_pause::Migration),
+            Box::new(m035_task_history_retention::Migration),
+            Box::new(m036_task_catch_up::Migration),
+            Box::new(m037_task_run_params::Migration),
+            Box::new(m038_task_concurrency_limits::Migration),
+            Box::new(m039_storage_cleanup_task::Migration),
+            Box::new(m040_db_maintenance_task::Migration),
+            Box::new(m041_task_interval_seconds::Migration),
+            Box::new(m042_task_scheduled_runs::Migration),
+            Box::new(m043_knowledge_graph_entity_columns::Migration),
+            Box::new(m044_writing_sync_files::Migration),
+            Box::new(m045_postgres_column_types::Migration),
+            Box::new(m046_site_credential_last_used::Migration),
+            Box::new(m047_secret_setting_type::Migration),
+            Box::new(m048_note_canonical_markdown::Migration),
+            Box::new(m049_note_templates::Migration),
+            Box::new(m050_note_links::Migration),
+            Box::new(m051_idea_sort_order::Migration),
+            Box::new(m052_idea_due_dates::Migration),
         ]
     }
 }
@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per recipient per send attempt (writing or digest)
+        manager
+            .create_table(
+                Table::create()
+                    .table(NewsletterSends::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NewsletterSends::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(NewsletterSends::Kind).string().not_null())
+                    .col(ColumnDef::new(NewsletterSends::WritingId).big_integer())
+                    .col(ColumnDef::new(NewsletterSends::Recipient).string().not_null())
+                    .col(ColumnDef::new(NewsletterSends::Subject).string().not_null())
+                    .col(
+                        ColumnDef::new(NewsletterSends::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(ColumnDef::new(NewsletterSends::ErrorMessage).text())
+                    .col(ColumnDef::new(NewsletterSends::SmtpResponse).text())
+                    .col(ColumnDef::new(NewsletterSends::SentAt).timestamp())
+                    .col(
+                        ColumnDef::new(NewsletterSends::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_newsletter_sends_writing")
+                            .from(NewsletterSends::Table, NewsletterSends::WritingId)
+                            .to(Writings::Table, Writings::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_newsletter_sends_writing")
+                    .table(NewsletterSends::Table)
+                    .col(NewsletterSends::WritingId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_newsletter_sends_created_at")
+                    .table(NewsletterSends::Table)
+                    .col(NewsletterSends::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NewsletterSends::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum NewsletterSends {
+    Table,
+    Id,
+    Kind,
+    WritingId,
+    Recipient,
+    Subject,
+    Status,
+    ErrorMessage,
+    SmtpResponse,
+    SentAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Writings {
+    Table,
+    Id,
+}
@@ -0,0 +1,124 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Every destination a writing was pushed to (newsletter, blog, Substack, ...)
+        manager
+            .create_table(
+                Table::create()
+                    .table(WritingPublications::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WritingPublications::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(WritingPublications::WritingId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WritingPublications::Platform).string().not_null())
+                    .col(ColumnDef::new(WritingPublications::RemoteId).string())
+                    .col(ColumnDef::new(WritingPublications::Url).string())
+                    .col(
+                        ColumnDef::new(WritingPublications::Status)
+                            .string()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(ColumnDef::new(WritingPublications::ErrorMessage).text())
+                    .col(
+                        ColumnDef::new(WritingPublications::Attempts)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(WritingPublications::PayloadJson).text())
+                    .col(ColumnDef::new(WritingPublications::PublishedAt).timestamp())
+                    .col(ColumnDef::new(WritingPublications::LastAttemptAt).timestamp())
+                    .col(
+                        ColumnDef::new(WritingPublications::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(WritingPublications::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_writing_publications_writing")
+                            .from(WritingPublications::Table, WritingPublications::WritingId)
+                            .to(Writings::Table, Writings::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_writing_publications_writing")
+                    .table(WritingPublications::Table)
+                    .col(WritingPublications::WritingId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_writing_publications_status")
+                    .table(WritingPublications::Table)
+                    .col(WritingPublications::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WritingPublications::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WritingPublications {
+    Table,
+    Id,
+    WritingId,
+    Platform,
+    RemoteId,
+    Url,
+    Status,
+    ErrorMessage,
+    Attempts,
+    PayloadJson,
+    PublishedAt,
+    LastAttemptAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Writings {
+    Table,
+    Id,
+}
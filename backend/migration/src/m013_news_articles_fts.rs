@@ -0,0 +1,84 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// External-content FTS5 index over news_articles, kept in sync by triggers.
+/// SeaQuery has no builder for virtual tables or triggers, so these go
+/// through the connection directly.
+const UP_STATEMENTS: &[&str] = &[
+    r#"CREATE VIRTUAL TABLE IF NOT EXISTS news_articles_fts USING fts5(
+        title,
+        excerpt,
+        content,
+        source_name,
+        content='news_articles',
+        content_rowid='id',
+        tokenize='porter unicode61 remove_diacritics 2'
+    )"#,
+    r#"CREATE TRIGGER IF NOT EXISTS news_articles_fts_ai AFTER INSERT ON news_articles BEGIN
+        INSERT INTO news_articles_fts(rowid, title, excerpt, content, source_name)
+        VALUES (new.id, new.title, new.excerpt, new.content, new.source_name);
+    END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS news_articles_fts_ad AFTER DELETE ON news_articles BEGIN
+        INSERT INTO news_articles_fts(news_articles_fts, rowid, title, excerpt, content, source_name)
+        VALUES ('delete', old.id, old.title, old.excerpt, old.content, old.source_name);
+    END"#,
+    r#"CREATE TRIGGER IF NOT EXISTS news_articles_fts_au
+        AFTER UPDATE OF title, excerpt, content, source_name ON news_articles BEGIN
+        INSERT INTO news_articles_fts(news_articles_fts, rowid, title, excerpt, content, source_name)
+        VALUES ('delete', old.id, old.title, old.excerpt, old.content, old.source_name);
+        INSERT INTO news_articles_fts(rowid, title, excerpt, content, source_name)
+        VALUES (new.id, new.title, new.excerpt, new.content, new.source_name);
+    END"#,
+    // Index rows that existed before this migration
+    "INSERT INTO news_articles_fts(news_articles_fts) VALUES ('rebuild')",
+];
+
+/// PostgreSQL has no FTS5; a GIN index over the weighted tsvector that
+/// feed::search queries serves instead, with no table or triggers to keep
+/// in sync. The expression must match the one in the query.
+const POSTGRES_UP_STATEMENTS: &[&str] = &[r#"CREATE INDEX IF NOT EXISTS idx_news_articles_search
+        ON news_articles USING GIN ((
+            setweight(to_tsvector('english', coalesce(title, '')), 'A')
+            || setweight(to_tsvector('english', coalesce(excerpt, '')), 'B')
+            || setweight(to_tsvector('english', coalesce(source_name, '')), 'C')
+            || setweight(to_tsvector('english', coalesce(content, '')), 'D')
+        ))"#];
+
+const POSTGRES_DOWN_STATEMENTS: &[&str] = &["DROP INDEX IF EXISTS idx_news_articles_search"];
+
+const DOWN_STATEMENTS: &[&str] = &[
+    "DROP TRIGGER IF EXISTS news_articles_fts_au",
+    "DROP TRIGGER IF EXISTS news_articles_fts_ad",
+    "DROP TRIGGER IF EXISTS news_articles_fts_ai",
+    "DROP TABLE IF EXISTS news_articles_fts",
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let statements = match manager.get_database_backend() {
+            DbBackend::Postgres => POSTGRES_UP_STATEMENTS,
+            _ => UP_STATEMENTS,
+        };
+        for sql in statements {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+        let statements = match manager.get_database_backend() {
+            DbBackend::Postgres => POSTGRES_DOWN_STATEMENTS,
+            _ => DOWN_STATEMENTS,
+        };
+        for sql in statements {
+            db.execute_unprepared(sql).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One vector per (entity, model); vectors are little-endian f32 blobs
+        manager
+            .create_table(
+                Table::create()
+                    .table(Embeddings::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Embeddings::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Embeddings::EntityType).string().not_null())
+                    .col(ColumnDef::new(Embeddings::EntityId).big_integer().not_null())
+                    .col(ColumnDef::new(Embeddings::Model).string().not_null())
+                    .col(ColumnDef::new(Embeddings::Dimensions).integer().not_null())
+                    .col(ColumnDef::new(Embeddings::Vector).blob().not_null())
+                    .col(ColumnDef::new(Embeddings::ContentHash).string().not_null())
+                    .col(
+                        ColumnDef::new(Embeddings::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(Embeddings::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_embeddings_entity_model")
+                    .table(Embeddings::Table)
+                    .col(Embeddings::EntityType)
+                    .col(Embeddings::EntityId)
+                    .col(Embeddings::Model)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_embeddings_model")
+                    .table(Embeddings::Table)
+                    .col(Embeddings::Model)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Disabled until an embeddings provider is configured
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(SystemTasks::Table)
+                    .columns([
+                        SystemTasks::Name,
+                        SystemTasks::TaskType,
+                        SystemTasks::Component,
+                        SystemTasks::FrequencyCron,
+                        SystemTasks::Enabled,
+                    ])
+                    .values_panic([
+                        "Embeddings Index".into(),
+                        "embeddings_index".into(),
+                        "system".into(),
+                        "0 15 * * * * *".into(),
+                        0.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SystemTasks::Table)
+                    .and_where(Expr::col(SystemTasks::TaskType).eq("embeddings_index"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Embeddings::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Embeddings {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    Model,
+    Dimensions,
+    Vector,
+    ContentHash,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    Name,
+    TaskType,
+    Component,
+    FrequencyCron,
+    Enabled,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one column per ALTER TABLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderSnapshots::Table)
+                    .add_column(ColumnDef::new(ReaderSnapshots::Summary).text())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderSnapshots::Table)
+                    .add_column(ColumnDef::new(ReaderSnapshots::SummaryModel).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderSnapshots::Table)
+                    .add_column(ColumnDef::new(ReaderSnapshots::SummarizedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Latest summary is mirrored on the reference for list views
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::Summary).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .drop_column(ReaderReferences::Summary)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [
+            ReaderSnapshots::SummarizedAt,
+            ReaderSnapshots::SummaryModel,
+            ReaderSnapshots::Summary,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ReaderSnapshots::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderReferences {
+    Table,
+    Summary,
+}
+
+#[derive(DeriveIden)]
+enum ReaderSnapshots {
+    Table,
+    Summary,
+    SummaryModel,
+    SummarizedAt,
+}
@@ -0,0 +1,154 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keyword alert rules, evaluated against newly synced articles.
+        // keywords/sources/languages are JSON string arrays; empty sources or
+        // languages match everything.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AlertRules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AlertRules::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AlertRules::Name).string().not_null())
+                    .col(ColumnDef::new(AlertRules::Keywords).text().not_null())
+                    .col(ColumnDef::new(AlertRules::Sources).text())
+                    .col(ColumnDef::new(AlertRules::Languages).text())
+                    .col(
+                        ColumnDef::new(AlertRules::MatchAll)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRules::Enabled)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRules::MatchCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(AlertRules::LastMatchedAt).timestamp())
+                    .col(
+                        ColumnDef::new(AlertRules::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(AlertRules::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_alert_rules_enabled")
+                    .table(AlertRules::Table)
+                    .col(AlertRules::Enabled)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Articles record the first rule that matched them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(NewsArticles::Table)
+                    .add_column(ColumnDef::new(NewsArticles::AlertRuleId).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(NewsArticles::Table)
+                    .add_column(ColumnDef::new(NewsArticles::AlertedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_news_articles_alerted_at")
+                    .table(NewsArticles::Table)
+                    .col(NewsArticles::AlertedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_news_articles_alerted_at")
+                    .table(NewsArticles::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [NewsArticles::AlertedAt, NewsArticles::AlertRuleId] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(NewsArticles::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(AlertRules::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AlertRules {
+    Table,
+    Id,
+    Name,
+    Keywords,
+    Sources,
+    Languages,
+    MatchAll,
+    Enabled,
+    MatchCount,
+    LastMatchedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum NewsArticles {
+    Table,
+    AlertRuleId,
+    AlertedAt,
+}
@@ -0,0 +1,130 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Raw engagement log (read/star/unstar/dismiss). No foreign key:
+        // signals outlive articles pruned by news sync.
+        manager
+            .create_table(
+                Table::create()
+                    .table(ArticleSignals::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ArticleSignals::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ArticleSignals::ArticleId).big_integer().not_null())
+                    .col(ColumnDef::new(ArticleSignals::Signal).string().not_null())
+                    .col(ColumnDef::new(ArticleSignals::Features).text().not_null())
+                    .col(
+                        ColumnDef::new(ArticleSignals::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_article_signals_article_id")
+                    .table(ArticleSignals::Table)
+                    .col(ArticleSignals::ArticleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Learned per-feature weights (source, category, keyword)
+        manager
+            .create_table(
+                Table::create()
+                    .table(RelevanceWeights::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RelevanceWeights::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RelevanceWeights::FeatureType).string().not_null())
+                    .col(ColumnDef::new(RelevanceWeights::Feature).string().not_null())
+                    .col(
+                        ColumnDef::new(RelevanceWeights::Weight)
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(RelevanceWeights::SignalCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(RelevanceWeights::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_relevance_weights_feature_type_feature")
+                    .table(RelevanceWeights::Table)
+                    .col(RelevanceWeights::FeatureType)
+                    .col(RelevanceWeights::Feature)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RelevanceWeights::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ArticleSignals::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ArticleSignals {
+    Table,
+    Id,
+    ArticleId,
+    Signal,
+    Features,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum RelevanceWeights {
+    Table,
+    Id,
+    FeatureType,
+    Feature,
+    Weight,
+    SignalCount,
+    UpdatedAt,
+}
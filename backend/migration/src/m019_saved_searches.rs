@@ -0,0 +1,127 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // `filters` is a JSON object mirroring list_news_articles parameters
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedSearches::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SavedSearches::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SavedSearches::Name).string().not_null())
+                    .col(ColumnDef::new(SavedSearches::Query).text())
+                    .col(ColumnDef::new(SavedSearches::Filters).text())
+                    .col(ColumnDef::new(SavedSearches::SortBy).string())
+                    .col(
+                        ColumnDef::new(SavedSearches::RunOnSchedule)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearches::PinNewMatches)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(SavedSearches::LastRunAt).timestamp())
+                    .col(
+                        ColumnDef::new(SavedSearches::LastMatchCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearches::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(SavedSearches::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Runs every saved search with run_on_schedule = 1
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(SystemTasks::Table)
+                    .columns([
+                        SystemTasks::Name,
+                        SystemTasks::TaskType,
+                        SystemTasks::Component,
+                        SystemTasks::FrequencyCron,
+                        SystemTasks::Enabled,
+                    ])
+                    .values_panic([
+                        "Saved Searches".into(),
+                        "saved_searches_run".into(),
+                        "news".into(),
+                        "0 0/30 * * * * *".into(),
+                        1.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SystemTasks::Table)
+                    .and_where(Expr::col(SystemTasks::TaskType).eq("saved_searches_run"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(SavedSearches::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SavedSearches {
+    Table,
+    Id,
+    Name,
+    Query,
+    Filters,
+    SortBy,
+    RunOnSchedule,
+    PinNewMatches,
+    LastRunAt,
+    LastMatchCount,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    Name,
+    TaskType,
+    Component,
+    FrequencyCron,
+    Enabled,
+}
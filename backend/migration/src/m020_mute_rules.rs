@@ -0,0 +1,135 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Mute rules hide matching articles from listings without deleting them.
+        // rule_type is one of: source, domain, keyword.
+        manager
+            .create_table(
+                Table::create()
+                    .table(MuteRules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MuteRules::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(MuteRules::RuleType).string().not_null())
+                    .col(ColumnDef::new(MuteRules::Pattern).string().not_null())
+                    .col(
+                        ColumnDef::new(MuteRules::Enabled)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(MuteRules::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(MuteRules::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_mute_rules_rule_type_pattern")
+                    .table(MuteRules::Table)
+                    .col(MuteRules::RuleType)
+                    .col(MuteRules::Pattern)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Articles record the rule that hid them
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(NewsArticles::Table)
+                    .add_column(ColumnDef::new(NewsArticles::MuteRuleId).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(NewsArticles::Table)
+                    .add_column(ColumnDef::new(NewsArticles::MutedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_news_articles_mute_rule_id")
+                    .table(NewsArticles::Table)
+                    .col(NewsArticles::MuteRuleId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_news_articles_mute_rule_id")
+                    .table(NewsArticles::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [NewsArticles::MutedAt, NewsArticles::MuteRuleId] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(NewsArticles::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .drop_table(Table::drop().table(MuteRules::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum MuteRules {
+    Table,
+    Id,
+    RuleType,
+    Pattern,
+    Enabled,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum NewsArticles {
+    Table,
+    MuteRuleId,
+    MutedAt,
+}
@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Read-later queue, separate from starring. item_type is
+        // "news_article" or "reader_reference"; position is 0-based and
+        // kept contiguous by the queue commands.
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReadingQueue::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReadingQueue::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReadingQueue::ItemType).string().not_null())
+                    .col(ColumnDef::new(ReadingQueue::ItemId).big_integer().not_null())
+                    .col(ColumnDef::new(ReadingQueue::Position).integer().not_null())
+                    .col(
+                        ColumnDef::new(ReadingQueue::AddedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reading_queue_item_type_item_id")
+                    .table(ReadingQueue::Table)
+                    .col(ReadingQueue::ItemType)
+                    .col(ReadingQueue::ItemId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reading_queue_position")
+                    .table(ReadingQueue::Table)
+                    .col(ReadingQueue::Position)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReadingQueue::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReadingQueue {
+    Table,
+    Id,
+    ItemType,
+    ItemId,
+    Position,
+    AddedAt,
+}
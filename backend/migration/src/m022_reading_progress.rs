@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Reading position per reference, so long reads can be resumed.
+        // SQLite only supports one column per ALTER TABLE.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::ProgressPercent).double())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::ProgressScrollOffset).integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::ProgressSnapshotId).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::LastReadAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reader_references_last_read_at")
+                    .table(ReaderReferences::Table)
+                    .col(ReaderReferences::LastReadAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_reader_references_last_read_at")
+                    .table(ReaderReferences::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [
+            ReaderReferences::LastReadAt,
+            ReaderReferences::ProgressSnapshotId,
+            ReaderReferences::ProgressScrollOffset,
+            ReaderReferences::ProgressPercent,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ReaderReferences::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderReferences {
+    Table,
+    ProgressPercent,
+    ProgressScrollOffset,
+    ProgressSnapshotId,
+    LastReadAt,
+}
@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Files downloaded for a snapshot (offline images). local_path is
+        // relative to the storage media dir; rows cascade with the snapshot
+        // and the files are removed by the reader media cleanup.
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReaderSnapshotAssets::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReaderSnapshotAssets::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReaderSnapshotAssets::SnapshotId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ReaderSnapshotAssets::OriginalUrl).text().not_null())
+                    .col(ColumnDef::new(ReaderSnapshotAssets::LocalPath).string().not_null())
+                    .col(ColumnDef::new(ReaderSnapshotAssets::ContentType).string())
+                    .col(
+                        ColumnDef::new(ReaderSnapshotAssets::ByteSize)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(ReaderSnapshotAssets::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reader_snapshot_assets_snapshot")
+                            .from(ReaderSnapshotAssets::Table, ReaderSnapshotAssets::SnapshotId)
+                            .to(ReaderSnapshots::Table, ReaderSnapshots::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reader_snapshot_assets_snapshot")
+                    .table(ReaderSnapshotAssets::Table)
+                    .col(ReaderSnapshotAssets::SnapshotId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(ReaderSnapshotAssets::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderSnapshotAssets {
+    Table,
+    Id,
+    SnapshotId,
+    OriginalUrl,
+    LocalPath,
+    ContentType,
+    ByteSize,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ReaderSnapshots {
+    Table,
+    Id,
+}
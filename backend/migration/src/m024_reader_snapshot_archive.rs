@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Self-contained HTML copy of the page, stored under the media dir.
+        // SQLite only supports one column per ALTER TABLE.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderSnapshots::Table)
+                    .add_column(ColumnDef::new(ReaderSnapshots::ArchivePath).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderSnapshots::Table)
+                    .add_column(ColumnDef::new(ReaderSnapshots::ArchiveBytes).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [ReaderSnapshots::ArchiveBytes, ReaderSnapshots::ArchivePath] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ReaderSnapshots::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderSnapshots {
+    Table,
+    ArchivePath,
+    ArchiveBytes,
+}
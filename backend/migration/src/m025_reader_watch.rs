@@ -0,0 +1,140 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Watched references are re-fetched by the reader_watch_refresh task.
+        // SQLite only supports one column per ALTER TABLE.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(
+                        ColumnDef::new(ReaderReferences::Watch)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::WatchIntervalMinutes).integer())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::LastCheckedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderReferences::Table)
+                    .add_column(ColumnDef::new(ReaderReferences::LastChangedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reader_references_watch")
+                    .table(ReaderReferences::Table)
+                    .col(ReaderReferences::Watch)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Checks which watched references are due; each has its own interval
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(SystemTasks::Table)
+                    .columns([
+                        SystemTasks::Name,
+                        SystemTasks::TaskType,
+                        SystemTasks::Component,
+                        SystemTasks::FrequencyCron,
+                        SystemTasks::Enabled,
+                    ])
+                    .values_panic([
+                        "Reader Watch".into(),
+                        "reader_watch_refresh".into(),
+                        "research".into(),
+                        "0 0/15 * * * * *".into(),
+                        1.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SystemTasks::Table)
+                    .and_where(Expr::col(SystemTasks::TaskType).eq("reader_watch_refresh"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_reader_references_watch")
+                    .table(ReaderReferences::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [
+            ReaderReferences::LastChangedAt,
+            ReaderReferences::LastCheckedAt,
+            ReaderReferences::WatchIntervalMinutes,
+            ReaderReferences::Watch,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ReaderReferences::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderReferences {
+    Table,
+    Watch,
+    WatchIntervalMinutes,
+    LastCheckedAt,
+    LastChangedAt,
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    Name,
+    TaskType,
+    Component,
+    FrequencyCron,
+    Enabled,
+}
@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Highlight color, category label (quote, evidence, ...) and a note.
+        // SQLite only supports one column per ALTER TABLE.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderClips::Table)
+                    .add_column(ColumnDef::new(ReaderClips::Color).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderClips::Table)
+                    .add_column(ColumnDef::new(ReaderClips::Label).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReaderClips::Table)
+                    .add_column(ColumnDef::new(ReaderClips::Note).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reader_clips_reference_label")
+                    .table(ReaderClips::Table)
+                    .col(ReaderClips::ReferenceId)
+                    .col(ReaderClips::Label)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .if_exists()
+                    .name("idx_reader_clips_reference_label")
+                    .table(ReaderClips::Table)
+                    .to_owned(),
+            )
+            .await?;
+        for column in [ReaderClips::Note, ReaderClips::Label, ReaderClips::Color] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(ReaderClips::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderClips {
+    Table,
+    ReferenceId,
+    Color,
+    Label,
+    Note,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-domain extraction overrides. Selectors are CSS selector lists;
+        // a rule for "example.com" also applies to its subdomains.
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReaderSiteRules::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReaderSiteRules::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReaderSiteRules::Domain).string().not_null())
+                    .col(ColumnDef::new(ReaderSiteRules::KeepSelectors).text())
+                    .col(ColumnDef::new(ReaderSiteRules::RemoveSelectors).text())
+                    .col(ColumnDef::new(ReaderSiteRules::UserAgent).string())
+                    .col(
+                        ColumnDef::new(ReaderSiteRules::Enabled)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(ReaderSiteRules::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ReaderSiteRules::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_reader_site_rules_domain")
+                    .table(ReaderSiteRules::Table)
+                    .col(ReaderSiteRules::Domain)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReaderSiteRules::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ReaderSiteRules {
+    Table,
+    Id,
+    Domain,
+    KeepSelectors,
+    RemoveSelectors,
+    UserAgent,
+    Enabled,
+    CreatedAt,
+    UpdatedAt,
+}
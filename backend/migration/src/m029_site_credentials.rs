@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Per-domain request headers and cookies for sites behind a login.
+        // Values live only in secret_encrypted; header_names_json and
+        // has_cookies let the list view show what is stored without
+        // decrypting it.
+        manager
+            .create_table(
+                Table::create()
+                    .table(SiteCredentials::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SiteCredentials::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SiteCredentials::Domain).string().not_null())
+                    .col(
+                        ColumnDef::new(SiteCredentials::SecretEncrypted)
+                            .binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SiteCredentials::HeaderNamesJson).text())
+                    .col(
+                        ColumnDef::new(SiteCredentials::HasCookies)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SiteCredentials::Enabled)
+                            .integer()
+                            .not_null()
+                            .default(1),
+                    )
+                    .col(
+                        ColumnDef::new(SiteCredentials::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(SiteCredentials::UpdatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_site_credentials_domain")
+                    .table(SiteCredentials::Table)
+                    .col(SiteCredentials::Domain)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SiteCredentials::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SiteCredentials {
+    Table,
+    Id,
+    Domain,
+    SecretEncrypted,
+    HeaderNamesJson,
+    HasCookies,
+    Enabled,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per mutating bridge command. Summaries are redacted,
+        // truncated JSON; before_summary is only filled for commands whose
+        // entity can be read back before the change.
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::Actor).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Command).string().not_null())
+                    .col(ColumnDef::new(AuditLog::EntityType).string())
+                    .col(ColumnDef::new(AuditLog::EntityId).big_integer())
+                    .col(ColumnDef::new(AuditLog::PayloadSummary).text())
+                    .col(ColumnDef::new(AuditLog::BeforeSummary).text())
+                    .col(ColumnDef::new(AuditLog::AfterSummary).text())
+                    .col(ColumnDef::new(AuditLog::Status).string().not_null())
+                    .col(ColumnDef::new(AuditLog::Error).text())
+                    .col(ColumnDef::new(AuditLog::RequestId).string())
+                    .col(
+                        ColumnDef::new(AuditLog::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_log_created_at")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_audit_log_entity")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::EntityType)
+                    .col(AuditLog::EntityId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditLog {
+    Table,
+    Id,
+    Actor,
+    Command,
+    EntityType,
+    EntityId,
+    PayloadSummary,
+    BeforeSummary,
+    AfterSummary,
+    Status,
+    Error,
+    RequestId,
+    CreatedAt,
+}
@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Local time of day a task may run in ("06:00-23:00"), and what to do
+        // with ticks outside it ("skip" or "defer"). SQLite only supports one
+        // column per ALTER TABLE.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .add_column(ColumnDef::new(SystemTasks::RunWindow).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .add_column(
+                        ColumnDef::new(SystemTasks::OutsideWindow)
+                            .string()
+                            .not_null()
+                            .default("skip"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [SystemTasks::OutsideWindow, SystemTasks::RunWindow] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(SystemTasks::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    RunWindow,
+    OutsideWindow,
+}
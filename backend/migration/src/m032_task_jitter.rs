@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Spread over 5 minutes; feed syncs all share the same default cron
+const FEED_SYNC_JITTER_SECONDS: i64 = 300;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Each tick waits a random 0..=jitter_seconds before running
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .add_column(ColumnDef::new(SystemTasks::JitterSeconds).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(SystemTasks::Table)
+                    .value(SystemTasks::JitterSeconds, FEED_SYNC_JITTER_SECONDS)
+                    .and_where(Expr::col(SystemTasks::TaskType).like("feed_sync_%"))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .drop_column(SystemTasks::JitterSeconds)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    TaskType,
+    JitterSeconds,
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // JSON array of task types (or `prefix_*` patterns) that must have
+        // succeeded before this task runs as their follow-up
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .add_column(ColumnDef::new(SystemTasks::DependsOn).text())
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .drop_column(SystemTasks::DependsOn)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    DependsOn,
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const SETTINGS: [(&str, &str, &str, &str, &str, i32); 1] = [
+    // (key, value, value_type, category, description, is_encrypted)
+    (
+        "scheduler.paused",
+        "false",
+        "boolean",
+        "advanced",
+        "Suspend all scheduled task runs (manual runs still work)",
+        0,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (key, value, value_type, category, description, is_encrypted) in SETTINGS {
+            manager
+                .exec_stmt(
+                    Query::insert()
+                        .into_table(AppSettings::Table)
+                        .columns([
+                            AppSettings::Key,
+                            AppSettings::Value,
+                            AppSettings::ValueType,
+                            AppSettings::Category,
+                            AppSettings::Description,
+                            AppSettings::IsEncrypted,
+                        ])
+                        .values_panic([
+                            key.into(),
+                            value.into(),
+                            value_type.into(),
+                            category.into(),
+                            description.into(),
+                            is_encrypted.into(),
+                        ])
+                        .on_conflict(OnConflict::column(AppSettings::Key).do_nothing().to_owned())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(AppSettings::Table)
+                    .and_where(Expr::col(AppSettings::Key).is_in(SETTINGS.iter().map(|s| s.0)))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AppSettings {
+    Table,
+    Key,
+    Value,
+    ValueType,
+    Category,
+    Description,
+    IsEncrypted,
+}
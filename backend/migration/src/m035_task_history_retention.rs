@@ -0,0 +1,122 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const SETTINGS: [(&str, &str, &str, &str, &str, i32); 2] = [
+    // (key, value, value_type, category, description, is_encrypted)
+    (
+        "scheduler.history_keep_runs",
+        "500",
+        "number",
+        "advanced",
+        "Task runs kept per task in the history (0 for no limit)",
+        0,
+    ),
+    (
+        "scheduler.history_keep_days",
+        "30",
+        "number",
+        "advanced",
+        "Days of task run history to keep (0 for no limit)",
+        0,
+    ),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (key, value, value_type, category, description, is_encrypted) in SETTINGS {
+            manager
+                .exec_stmt(
+                    Query::insert()
+                        .into_table(AppSettings::Table)
+                        .columns([
+                            AppSettings::Key,
+                            AppSettings::Value,
+                            AppSettings::ValueType,
+                            AppSettings::Category,
+                            AppSettings::Description,
+                            AppSettings::IsEncrypted,
+                        ])
+                        .values_panic([
+                            key.into(),
+                            value.into(),
+                            value_type.into(),
+                            category.into(),
+                            description.into(),
+                            is_encrypted.into(),
+                        ])
+                        .on_conflict(OnConflict::column(AppSettings::Key).do_nothing().to_owned())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // Prunes system_task_runs by the settings above
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(SystemTasks::Table)
+                    .columns([
+                        SystemTasks::Name,
+                        SystemTasks::TaskType,
+                        SystemTasks::Component,
+                        SystemTasks::FrequencyCron,
+                        SystemTasks::Enabled,
+                    ])
+                    .values_panic([
+                        "Task History Cleanup".into(),
+                        "task_history_cleanup".into(),
+                        "system".into(),
+                        "0 30 3 * * * *".into(),
+                        1.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SystemTasks::Table)
+                    .and_where(Expr::col(SystemTasks::TaskType).eq("task_history_cleanup"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(AppSettings::Table)
+                    .and_where(Expr::col(AppSettings::Key).is_in(SETTINGS.iter().map(|s| s.0)))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AppSettings {
+    Table,
+    Key,
+    Value,
+    ValueType,
+    Category,
+    Description,
+    IsEncrypted,
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    Name,
+    TaskType,
+    Component,
+    FrequencyCron,
+    Enabled,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // What to do at startup about ticks missed while the app was down:
+        // "skip" or "run_once"
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .add_column(
+                        ColumnDef::new(SystemTasks::CatchUp)
+                            .string()
+                            .not_null()
+                            .default("skip"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTasks::Table)
+                    .drop_column(SystemTasks::CatchUp)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    CatchUp,
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // JSON object of parameter overrides a manual run was given
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTaskRuns::Table)
+                    .add_column(ColumnDef::new(SystemTaskRuns::Params).text())
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SystemTaskRuns::Table)
+                    .drop_column(SystemTaskRuns::Params)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SystemTaskRuns {
+    Table,
+    Params,
+}
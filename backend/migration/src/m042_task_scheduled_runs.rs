@@ -0,0 +1,79 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One-shot runs of a task type at a given time; a row is deleted
+        // when its run starts. params holds JSON overrides as for manual runs.
+        manager
+            .create_table(
+                Table::create()
+                    .table(SystemTaskScheduledRuns::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SystemTaskScheduledRuns::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SystemTaskScheduledRuns::TaskType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SystemTaskScheduledRuns::RunAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SystemTaskScheduledRuns::Params).text())
+                    .col(
+                        ColumnDef::new(SystemTaskScheduledRuns::CreatedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_system_task_scheduled_runs_run_at")
+                    .table(SystemTaskScheduledRuns::Table)
+                    .col(SystemTaskScheduledRuns::RunAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SystemTaskScheduledRuns::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SystemTaskScheduledRuns {
+    Table,
+    Id,
+    TaskType,
+    RunAt,
+    Params,
+    CreatedAt,
+}
@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Columns the knowledge graph entities have always had but no
+        // migration created
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IdeaReferenceLinks::Table)
+                    .add_column(ColumnDef::new(IdeaReferenceLinks::Notes).text())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReferenceItems::Table)
+                    .add_column(ColumnDef::new(ReferenceItems::Summary).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        // m008 copied sort_order into link_order, but the entity kept
+        // reading and writing sort_order, so link_order is a stale copy
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WritingIdeaLinks::Table)
+                    .drop_column(WritingIdeaLinks::LinkOrder)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WritingIdeaLinks::Table)
+                    .add_column(
+                        ColumnDef::new(WritingIdeaLinks::LinkOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(WritingIdeaLinks::Table)
+                    .value(WritingIdeaLinks::LinkOrder, Expr::col(WritingIdeaLinks::SortOrder))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReferenceItems::Table)
+                    .drop_column(ReferenceItems::Summary)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(IdeaReferenceLinks::Table)
+                    .drop_column(IdeaReferenceLinks::Notes)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum IdeaReferenceLinks {
+    Table,
+    Notes,
+}
+
+#[derive(DeriveIden)]
+enum ReferenceItems {
+    Table,
+    Summary,
+}
+
+#[derive(DeriveIden)]
+enum WritingIdeaLinks {
+    Table,
+    SortOrder,
+    LinkOrder,
+}
@@ -0,0 +1,136 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The Markdown file mirroring each writing in the sync folder, with
+        // hashes of the file and of the writing as of the last sync. No
+        // foreign key: a row outliving its writing marks a deletion.
+        manager
+            .create_table(
+                Table::create()
+                    .table(WritingSyncFiles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WritingSyncFiles::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WritingSyncFiles::Folder).string().not_null())
+                    .col(
+                        ColumnDef::new(WritingSyncFiles::WritingId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WritingSyncFiles::FileName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WritingSyncFiles::FileHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WritingSyncFiles::WritingHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(WritingSyncFiles::SyncedAt)
+                            .timestamp()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_writing_sync_files_folder_file")
+                    .table(WritingSyncFiles::Table)
+                    .col(WritingSyncFiles::Folder)
+                    .col(WritingSyncFiles::FileName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Scans the folder every minute once WRITING_SYNC_DIR is set and
+        // the task enabled
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(SystemTasks::Table)
+                    .columns([
+                        SystemTasks::Name,
+                        SystemTasks::TaskType,
+                        SystemTasks::Component,
+                        SystemTasks::IntervalSeconds,
+                        SystemTasks::Enabled,
+                    ])
+                    .values_panic([
+                        "Writing Folder Sync".into(),
+                        "writing_folder_sync".into(),
+                        "writing".into(),
+                        60.into(),
+                        0.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SystemTasks::Table)
+                    .and_where(Expr::col(SystemTasks::TaskType).eq("writing_folder_sync"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(WritingSyncFiles::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WritingSyncFiles {
+    Table,
+    Id,
+    Folder,
+    WritingId,
+    FileName,
+    FileHash,
+    WritingHash,
+    SyncedAt,
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    Name,
+    TaskType,
+    Component,
+    IntervalSeconds,
+    Enabled,
+}
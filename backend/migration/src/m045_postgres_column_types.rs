@@ -0,0 +1,155 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Columns whose PostgreSQL type must change to decode into their entity
+/// field: SQLite stores any of these in the type the earlier migrations
+/// declare, but PostgreSQL only reads `DateTimeUtc` from `timestamptz`,
+/// `i64` from `bigint` and `i32` from `integer`. New columns should be
+/// declared with the matching type (`timestamp_with_time_zone()` for
+/// `DateTimeUtc`) instead of being added here.
+const COLUMN_TYPES: &[(&str, &str, &str)] = &[
+    ("alert_rules", "last_matched_at", "timestamptz"),
+    ("alert_rules", "created_at", "timestamptz"),
+    ("alert_rules", "updated_at", "timestamptz"),
+    ("app_settings", "id", "integer"),
+    ("article_signals", "created_at", "timestamptz"),
+    ("audit_log", "created_at", "timestamptz"),
+    ("embeddings", "created_at", "timestamptz"),
+    ("embeddings", "updated_at", "timestamptz"),
+    ("idea_reference_links", "created_at", "timestamptz"),
+    ("ideas", "date_added", "timestamptz"),
+    ("ideas", "date_updated", "timestamptz"),
+    ("ideas", "date_completed", "timestamptz"),
+    ("ideas", "date_removed", "timestamptz"),
+    ("mute_rules", "created_at", "timestamptz"),
+    ("mute_rules", "updated_at", "timestamptz"),
+    ("news_articles", "published_at", "timestamptz"),
+    ("news_articles", "fetched_at", "timestamptz"),
+    ("news_articles", "added_to_ideas_at", "timestamptz"),
+    ("news_articles", "dismissed_at", "timestamptz"),
+    ("news_articles", "created_at", "timestamptz"),
+    ("news_articles", "updated_at", "timestamptz"),
+    ("news_articles", "alerted_at", "timestamptz"),
+    ("news_articles", "muted_at", "timestamptz"),
+    ("news_settings", "max_stored", "bigint"),
+    ("news_settings", "max_articles", "bigint"),
+    ("news_settings", "daily_call_limit", "bigint"),
+    ("news_settings", "calls_today", "bigint"),
+    ("news_settings", "last_synced_at", "timestamptz"),
+    ("news_settings", "created_at", "timestamptz"),
+    ("news_settings", "updated_at", "timestamptz"),
+    ("news_sources", "created_at", "timestamptz"),
+    ("news_sources", "updated_at", "timestamptz"),
+    ("newsletter_sends", "sent_at", "timestamptz"),
+    ("newsletter_sends", "created_at", "timestamptz"),
+    ("notes", "created_at", "timestamptz"),
+    ("notes", "updated_at", "timestamptz"),
+    ("reference_items", "published_at", "timestamptz"),
+    ("reference_items", "created_at", "timestamptz"),
+    ("reference_items", "updated_at", "timestamptz"),
+    ("relevance_weights", "updated_at", "timestamptz"),
+    ("saved_searches", "last_run_at", "timestamptz"),
+    ("saved_searches", "created_at", "timestamptz"),
+    ("saved_searches", "updated_at", "timestamptz"),
+    ("system_task_runs", "started_at", "timestamptz"),
+    ("system_task_runs", "finished_at", "timestamptz"),
+    ("system_task_scheduled_runs", "run_at", "timestamptz"),
+    ("system_task_scheduled_runs", "created_at", "timestamptz"),
+    ("system_tasks", "frequency_seconds", "bigint"),
+    ("system_tasks", "interval_seconds", "bigint"),
+    ("system_tasks", "last_run_at", "timestamptz"),
+    ("system_tasks", "error_count", "bigint"),
+    ("system_tasks", "jitter_seconds", "bigint"),
+    ("system_tasks", "created_at", "timestamptz"),
+    ("system_tasks", "updated_at", "timestamptz"),
+    ("writing_idea_links", "created_at", "timestamptz"),
+    ("writing_publications", "published_at", "timestamptz"),
+    ("writing_publications", "last_attempt_at", "timestamptz"),
+    ("writing_publications", "created_at", "timestamptz"),
+    ("writing_publications", "updated_at", "timestamptz"),
+    ("writing_sync_files", "synced_at", "timestamptz"),
+    ("writings", "created_at", "timestamptz"),
+    ("writings", "updated_at", "timestamptz"),
+    ("writings", "published_at", "timestamptz"),
+];
+
+/// The `app_settings` timestamps are strings in the entity
+const TEXT_TIMESTAMPS: &[(&str, &str)] = &[
+    ("app_settings", "created_at"),
+    ("app_settings", "updated_at"),
+];
+
+const TEXT_TIMESTAMP_DEFAULT: &str =
+    "to_char(CURRENT_TIMESTAMP AT TIME ZONE 'UTC', 'YYYY-MM-DD HH24:MI:SS')";
+
+/// Stored timestamps are UTC
+fn using(column: &str, column_type: &str) -> String {
+    match column_type {
+        "timestamptz" | "timestamp" => format!("{} AT TIME ZONE 'UTC'", column),
+        _ => format!("{}::{}", column, column_type),
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+        let db = manager.get_connection();
+        for (table, column, column_type) in COLUMN_TYPES {
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}",
+                table,
+                column,
+                column_type,
+                using(column, column_type)
+            ))
+            .await?;
+        }
+        for (table, column) in TEXT_TIMESTAMPS {
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {table} ALTER COLUMN {column} DROP DEFAULT, \
+                 ALTER COLUMN {column} TYPE text \
+                 USING to_char({column}, 'YYYY-MM-DD HH24:MI:SS'), \
+                 ALTER COLUMN {column} SET DEFAULT {TEXT_TIMESTAMP_DEFAULT}"
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        if manager.get_database_backend() != DbBackend::Postgres {
+            return Ok(());
+        }
+        let db = manager.get_connection();
+        for (table, column) in TEXT_TIMESTAMPS {
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {table} ALTER COLUMN {column} DROP DEFAULT, \
+                 ALTER COLUMN {column} TYPE timestamp USING {column}::timestamp, \
+                 ALTER COLUMN {column} SET DEFAULT CURRENT_TIMESTAMP"
+            ))
+            .await?;
+        }
+        for (table, column, column_type) in COLUMN_TYPES {
+            let original = match *column_type {
+                "timestamptz" => "timestamp",
+                "bigint" => "integer",
+                _ => "bigint",
+            };
+            db.execute_unprepared(&format!(
+                "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}",
+                table,
+                column,
+                original,
+                using(column, original)
+            ))
+            .await?;
+        }
+        Ok(())
+    }
+}
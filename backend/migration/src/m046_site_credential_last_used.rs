@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Set whenever a request is sent with the credential, for
+        // list_credentials
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SiteCredentials::Table)
+                    .add_column(ColumnDef::new(SiteCredentials::LastUsedAt).timestamp())
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SiteCredentials::Table)
+                    .drop_column(SiteCredentials::LastUsedAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum SiteCredentials {
+    Table,
+    LastUsedAt,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Encrypted settings become the `secret` type, masked when read
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(AppSettings::Table)
+                    .value(AppSettings::ValueType, "secret")
+                    .and_where(Expr::col(AppSettings::IsEncrypted).eq(1))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(AppSettings::Table)
+                    .value(AppSettings::ValueType, "string")
+                    .and_where(Expr::col(AppSettings::ValueType).eq("secret"))
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AppSettings {
+    Table,
+    ValueType,
+    IsEncrypted,
+}
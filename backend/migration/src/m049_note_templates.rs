@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Starting content for the first note of an entity. A template
+        // without entity_type applies to every entity type; one pair of
+        // entity_type and note_type has at most one template, which the app
+        // enforces since NULLs don't clash in a unique index.
+        manager
+            .create_table(
+                Table::create()
+                    .table(NoteTemplates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NoteTemplates::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(NoteTemplates::Name).string().not_null())
+                    .col(ColumnDef::new(NoteTemplates::EntityType).string_len(32))
+                    .col(
+                        ColumnDef::new(NoteTemplates::NoteType)
+                            .string_len(32)
+                            .not_null()
+                            .default("main"),
+                    )
+                    .col(ColumnDef::new(NoteTemplates::BodyHtml).text().not_null())
+                    .col(
+                        ColumnDef::new(NoteTemplates::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(NoteTemplates::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(NoteTemplates::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum NoteTemplates {
+    Table,
+    Id,
+    Name,
+    EntityType,
+    NoteType,
+    BodyHtml,
+    CreatedAt,
+    UpdatedAt,
+}
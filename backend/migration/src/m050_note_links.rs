@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // [[Title]] links found in note bodies, resolved to the idea, writing
+        // or reference they name. Rebuilt from the body on every save, so a
+        // row lives only as long as the link does; the target is not a
+        // foreign key since it can be any of three tables.
+        manager
+            .create_table(
+                Table::create()
+                    .table(NoteLinks::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(NoteLinks::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(NoteLinks::NoteId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(NoteLinks::TargetType)
+                            .string_len(32)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(NoteLinks::TargetId).big_integer().not_null())
+                    .col(ColumnDef::new(NoteLinks::LinkText).string().not_null())
+                    .col(
+                        ColumnDef::new(NoteLinks::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_note_links_note")
+                            .from(NoteLinks::Table, NoteLinks::NoteId)
+                            .to(Notes::Table, Notes::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_note_links_target")
+                    .table(NoteLinks::Table)
+                    .col(NoteLinks::TargetType)
+                    .col(NoteLinks::TargetId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_note_links_note")
+                    .table(NoteLinks::Table)
+                    .col(NoteLinks::NoteId)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(NoteLinks::Table).if_exists().to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum NoteLinks {
+    Table,
+    Id,
+    NoteId,
+    TargetType,
+    TargetId,
+    LinkText,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Notes {
+    Table,
+    Id,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Position of an idea within its status column on the ideas board,
+        // set by reorder_ideas. Existing ideas all start at 0, so they keep
+        // their last-updated order until first reordered.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ideas::Table)
+                    .add_column(
+                        ColumnDef::new(Ideas::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ideas::Table)
+                    .drop_column(Ideas::SortOrder)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Ideas {
+    Table,
+    SortOrder,
+}
@@ -0,0 +1,119 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When an idea is due, for the overdue and due this week filters of
+        // list_ideas, and when to remind about it; remind_at is cleared once
+        // the reminder went out. One column per statement, as SQLite wants.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ideas::Table)
+                    .add_column(ColumnDef::new(Ideas::DueAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Ideas::Table)
+                    .add_column(ColumnDef::new(Ideas::RemindAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_ideas_due_at")
+                    .table(Ideas::Table)
+                    .col(Ideas::DueAt)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("idx_ideas_remind_at")
+                    .table(Ideas::Table)
+                    .col(Ideas::RemindAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Sends the reminders that came due, checking every minute
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(SystemTasks::Table)
+                    .columns([
+                        SystemTasks::Name,
+                        SystemTasks::TaskType,
+                        SystemTasks::Component,
+                        SystemTasks::IntervalSeconds,
+                        SystemTasks::Enabled,
+                    ])
+                    .values_panic([
+                        "Idea Reminders".into(),
+                        "idea_reminders".into(),
+                        "writing".into(),
+                        60.into(),
+                        1.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(SystemTasks::Table)
+                    .and_where(Expr::col(SystemTasks::TaskType).eq("idea_reminders"))
+                    .to_owned(),
+            )
+            .await?;
+        for index in ["idx_ideas_remind_at", "idx_ideas_due_at"] {
+            manager
+                .drop_index(Index::drop().name(index).table(Ideas::Table).to_owned())
+                .await?;
+        }
+        for column in [Ideas::RemindAt, Ideas::DueAt] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Ideas::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Ideas {
+    Table,
+    DueAt,
+    RemindAt,
+}
+
+#[derive(DeriveIden)]
+enum SystemTasks {
+    Table,
+    Name,
+    TaskType,
+    Component,
+    IntervalSeconds,
+    Enabled,
+}
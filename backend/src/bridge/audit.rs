@@ -0,0 +1,170 @@
+//! Audit trail for mutating bridge commands
+//!
+//! `dispatch` records every known command whose name says it changes
+//! something, whether it succeeded or not: the actor behind the request,
+//! the entity it touched, the payload and the result. For updates and
+//! deletes of entities with a getter, the entity is read first so the row
+//! also shows what it looked like before.
+
+use serde_json::{json, Value};
+use tracing::warn;
+
+use super::dispatch::{run_command, ApiError, BridgeContext};
+use super::request_id::current_request_id;
+use super::scopes::{current_actor, mutates};
+use crate::core::components::audit::{record_audit_entry, NewAuditEntry};
+
+/// Commands named like writes that only compute or log, and reading
+/// progress, which is saved on every scroll
+const NOT_AUDITED: &[&str] = &[
+    "log_frontend_error",
+    "run_saved_search",
+    "suggest_tags",
+    "suggest_tags_batch",
+    "update_reading_progress",
+];
+
+pub fn is_audited(command: &str) -> bool {
+    mutates(command) && !NOT_AUDITED.contains(&command)
+}
+
+/// Entity type, getter command and id field for commands whose target can
+/// be read back before it changes
+fn before_lookup(command: &str) -> Option<(&'static str, &'static str, &'static str)> {
+    let lookup = match command {
+        "update_idea_metadata" | "update_idea_notes" | "update_idea_article" | "archive_idea" => {
+            ("idea", "get_idea", "id")
+        }
+        "update_feed_source" | "delete_feed_source" | "toggle_feed_source" => {
+            ("feed_source", "get_feed_source", "source_id")
+        }
+        "dismiss_news_article" | "toggle_star_news_article" | "mark_news_article_read" => {
+            ("news_article", "get_news_article", "id")
+        }
+        "reader_reference_update" => ("reader_reference", "reader_reference_get", "reference_id"),
+        "kg_update_reference" | "kg_delete_reference" => ("reference", "kg_get_reference", "id"),
+        "kg_update_writing" | "kg_publish_writing" | "kg_delete_writing" => {
+            ("writing", "kg_get_writing", "id")
+        }
+        "kg_update_note" | "kg_delete_note" => ("note", "kg_get_note", "id"),
+        _ => return None,
+    };
+    Some(lookup)
+}
+
+/// Words dropped from a command name to leave the entity it acts on
+const VERBS: &[&str] = &[
+    "add", "all", "append", "archive", "batch", "clear", "cleanup", "command", "create", "delete",
+    "dismiss", "export", "fetch", "for", "from", "generate", "import", "kg", "link", "mark", "now",
+    "or", "pause", "pop", "publish", "read", "reanchor", "record", "refresh", "reload", "remove",
+    "reorder", "restore", "reveal", "resume", "retry", "rollback", "run", "save", "send", "set",
+    "star", "switch", "sync", "to", "toggle", "unlink", "update", "upsert",
+];
+
+fn entity_type(command: &str) -> Option<String> {
+    if let Some((entity, ..)) = before_lookup(command) {
+        return Some(entity.to_string());
+    }
+    let words: Vec<&str> = command
+        .split('_')
+        .filter(|word| !VERBS.contains(word))
+        .collect();
+    (!words.is_empty()).then(|| words.join("_"))
+}
+
+/// The target's id: the payload's id field, else the id of the result (new
+/// rows), else the first `*_id` in the payload
+fn entity_id(command: &str, payload: Option<&Value>, result: Option<&Value>) -> Option<i64> {
+    let field = |value: Option<&Value>, key: &str| value?.get(key)?.as_i64();
+    if let Some((_, _, key)) = before_lookup(command) {
+        return field(payload, key);
+    }
+    field(payload, "id")
+        .or_else(|| field(result, "id"))
+        .or_else(|| {
+            payload?
+                .as_object()?
+                .iter()
+                .filter(|(key, _)| key.ends_with("_id") || key.ends_with("Id"))
+                .find_map(|(_, value)| value.as_i64())
+        })
+}
+
+/// The entity as it is before `command` runs, if it can be read back
+pub async fn snapshot_before(
+    command: &str,
+    payload: Option<&Value>,
+    ctx: &BridgeContext,
+) -> Option<Value> {
+    let (_, getter, key) = before_lookup(command)?;
+    let id = payload?.get(key)?.as_i64()?;
+    run_command(getter, Some(json!({ key: id })), ctx)
+        .await
+        .ok()
+}
+
+/// Write the audit row; failures are logged, never returned to the caller
+pub async fn record(
+    command: &str,
+    payload: Option<Value>,
+    before: Option<Value>,
+    result: &Result<Value, ApiError>,
+    ctx: &BridgeContext,
+) {
+    let after = result.as_ref().ok();
+    let entry = NewAuditEntry {
+        actor: current_actor(),
+        command: command.to_string(),
+        entity_type: entity_type(command),
+        entity_id: entity_id(command, payload.as_ref(), after),
+        payload,
+        before,
+        after: after.cloned(),
+        error: result.as_ref().err().map(ToString::to_string),
+        request_id: current_request_id(),
+    };
+    if let Err(e) = record_audit_entry(&ctx.state.db, entry).await {
+        warn!(target: "api", "Failed to write audit log entry for {}: {}", command, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_targets() {
+        assert!(is_audited("create_idea"));
+        assert!(is_audited("import_database"));
+        assert!(!is_audited("list_ideas"));
+        assert!(!is_audited("log_frontend_error"));
+
+        assert_eq!(
+            entity_type("create_feed_source").as_deref(),
+            Some("feed_source")
+        );
+        assert_eq!(entity_type("kg_delete_note").as_deref(), Some("note"));
+        assert_eq!(entity_type("update_idea_notes").as_deref(), Some("idea"));
+        assert_eq!(
+            entity_type("sync_all_feed_sources").as_deref(),
+            Some("feed_sources")
+        );
+        assert_eq!(entity_type("sync_calendar").as_deref(), Some("calendar"));
+
+        let payload = json!({ "source_id": 7, "input": { "name": "x" } });
+        assert_eq!(
+            entity_id("update_feed_source", Some(&payload), None),
+            Some(7)
+        );
+        let created = json!({ "id": 12, "title": "New" });
+        assert_eq!(
+            entity_id("create_idea", Some(&json!({})), Some(&created)),
+            Some(12)
+        );
+        let link = json!({ "writing_id": 3, "idea_id": 4 });
+        assert_eq!(
+            entity_id("writing_link_idea", Some(&link), Some(&json!("ok"))),
+            Some(4)
+        );
+    }
+}
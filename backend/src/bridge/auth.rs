@@ -0,0 +1,117 @@
+//! Bearer-token check for protected bridge routes
+//!
+//! With no tokens configured the bridge is open. Once `COCKPIT_HTTP_TOKEN`
+//! or `COCKPIT_HTTP_SCOPED_TOKENS` is set, protected routes need
+//! `Authorization: Bearer <token>`. Browsers can't add headers to a plain
+//! download link, so a `token` query parameter is accepted as well. The
+//! token's grant is attached to the request for `dispatch` to check.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::dispatch::BridgeContext;
+use super::scopes::{with_grant, Grant};
+use crate::core::components::config::HttpConfig;
+
+fn bearer_token(request: &Request) -> Option<&str> {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+fn query_token(request: &Request) -> Option<&str> {
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// Compare without returning early on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Grant for a presented token, `None` if it matches no configured token
+fn grant_for(config: &HttpConfig, token: &str) -> Option<Grant> {
+    let matches = |expected: &str| constant_time_eq(token.as_bytes(), expected.as_bytes());
+    if config.api_token.as_deref().is_some_and(matches) {
+        return Some(Grant::Full);
+    }
+    config
+        .scoped_tokens
+        .iter()
+        .find(|scoped| matches(&scoped.token))
+        .map(|scoped| Grant::scoped(&scoped.token, &scoped.scopes))
+}
+
+/// Middleware: reject the request unless it carries a configured token
+pub async fn require_token(
+    State(ctx): State<BridgeContext>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = ctx.state.config.current();
+    let config = &config.http;
+    if config.api_token.is_none() && config.scoped_tokens.is_empty() {
+        return next.run(request).await;
+    }
+    let grant = bearer_token(&request)
+        .or_else(|| query_token(&request))
+        .and_then(|token| grant_for(config, token));
+    match grant {
+        Some(grant) => with_grant(Some(grant), next.run(request)).await,
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_sources() {
+        let request = Request::builder()
+            .uri("/files/backups/a.db?x=1&token=secret")
+            .header(header::AUTHORIZATION, "Bearer abc")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        assert_eq!(bearer_token(&request), Some("abc"));
+        assert_eq!(query_token(&request), Some("secret"));
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+
+    #[test]
+    fn test_grant_for() {
+        let config = HttpConfig {
+            port: 1420,
+            compression_min_bytes: 1024,
+            api_token: Some("full".to_string()),
+            scoped_tokens: vec![crate::core::components::config::ScopedToken {
+                token: "dash".to_string(),
+                scopes: vec!["read".to_string()],
+            }],
+        };
+        assert!(matches!(grant_for(&config, "full"), Some(Grant::Full)));
+        assert!(grant_for(&config, "other").is_none());
+        // The audit actor identifies the token without revealing it
+        let Some(Grant::Scoped { actor, .. }) = grant_for(&config, "dash") else {
+            unreachable!()
+        };
+        assert_eq!(actor.len(), "scoped:".len() + 8);
+        assert!(!actor.contains("dash"));
+    }
+}
@@ -1,49 +1,104 @@
+use super::audit;
+use super::jobs::{self, JobRegistry};
+use super::metrics::CommandMetrics;
+use super::request_id::current_request_id;
+use super::schema::bridge_schema;
+use super::scopes::{authorize, require, Access};
 use crate::core::commands::CurrentUser;
-use crate::core::components::events::EventEmitter;
+use crate::core::components::audit::{AuditLogFilter, ListAuditLogInput};
+use crate::core::components::embeddings::{
+    MoreLikeThisInput, ReindexEmbeddingsInput, SemanticSearchInput,
+};
+use crate::core::components::errors::{AppError, ErrorCode, Severity};
+use crate::core::components::events::{BroadcastEventEmitter, EventEmitter};
+use crate::core::components::pagination::{Listing, Page};
 use crate::core::components::setup_wizard::SetupConfig;
-use crate::core::components::storage::StorageStats;
+use crate::core::components::storage::{CreateBackupInput, ExportInput, StorageStats};
+use crate::core::components::workspaces::{WorkspaceDto, Workspaces};
+use crate::notes::components::templates::{NoteTemplateCreateInput, NoteTemplateUpdateInput};
 use crate::research::components::feed::{
-    CreateFeedSourceInput, FeedSourceDto, NewsArticleDto, NewsSettingsDto, NewsSourceDto,
-    SaveNewsSettingsInput, SyncAllResult, SyncSourceResult, UpdateFeedSourceInput,
+    CreateAlertRuleInput, CreateFeedSourceInput, CreateMuteRuleInput, CreateSavedSearchInput,
+    FeedSourceDto, NewsArticleDto, NewsSettingsDto, NewsSourceDto, PreviewMuteRuleInput,
+    SaveNewsSettingsInput, SyncAllResult, SyncSourceResult, TestAlertRuleInput,
+    UpdateAlertRuleInput, UpdateFeedSourceInput, UpdateMuteRuleInput, UpdateSavedSearchInput,
 };
 use crate::research::components::reader::{
-    ClipCreateInput, ReaderClipDto, ReaderFetchInput, ReaderReferenceDto, ReaderRefreshInput,
-    ReaderResult, ReaderSnapshotDto, ReferenceUpdateInput,
+    ClipCreateInput, ClipListFilter, ClipUpdateInput, ReaderClipDto, ReaderFetchInput,
+    ReaderReferenceDto, ReaderRefreshInput, ReaderResult, ReaderSnapshotDto, ReadingProgressInput,
+    ReferenceUpdateInput, SummarizeReferenceInput,
+};
+use crate::research::components::reader_anchor::{ClipReanchorInput, ClipReanchorResult};
+use crate::research::components::reader_batch::{ReaderFetchBatchInput, ReaderFetchBatchResult};
+use crate::research::components::reader_enex::{ReaderEnexImportResult, ReaderImportEnexInput};
+use crate::research::components::reader_site_rules::{
+    ReaderSiteRuleDto, SiteRuleCreateInput, SiteRuleTestInput, SiteRuleTestResult,
+    SiteRuleUpdateInput,
 };
+use crate::research::components::reader_watch::ReaderWatchInput;
+use crate::research::components::site_credentials::{SiteCredentialDto, SiteCredentialSaveInput};
+use crate::research::components::reading_queue::{
+    ReadingQueueAddInput, ReadingQueueItemDto, ReadingQueueReorderInput,
+};
+use crate::research::components::reading_stats::{ReadingStatsDto, ReadingStatsInput};
+use crate::research::components::tagging::{SuggestTagsBatchInput, SuggestTagsInput};
 use crate::research::dto::{
     CreateResearchAccountInput, ListResearchItemsQuery, ResearchAccountDto, ResearchItemDto,
     ResearchStreamDto, UpdateResearchAccountInput, UpsertResearchStreamInput,
 };
 use crate::system::components::scheduler::{
-    RunTaskNowResult, SystemTaskDto, TaskRunDto, UpdateTaskInput,
+    RunTaskNowResult, ScheduledRunDto, SystemTaskDto, TaskRunDto, UpdateTaskInput,
 };
 use crate::writing::components::ideas::{
-    AddReferenceInput, CreateIdeaForArticleInput, CreateIdeaInput, IdeaDto, IdeaReferenceDto,
-    LinkIdeaReferenceInput, ReaderSnapshotInput, ReferenceReaderSnapshotDto,
-    UpdateIdeaArticleInput, UpdateIdeaMetadataInput, UpdateIdeaNotesInput,
-    UpdateReferenceNotesInput,
+    AddReferenceInput, CreateIdeaForArticleInput, CreateIdeaInput, IdeaDto, IdeaMergeDto,
+    IdeaReferenceDto, LinkIdeaReferenceInput, ReaderSnapshotInput, ReferenceReaderSnapshotDto,
+    RelatedContentInput, ReorderIdeasInput, UpdateIdeaArticleInput, UpdateIdeaMetadataInput,
+    UpdateIdeaNotesInput, UpdateReferenceNotesInput,
 };
 use crate::writing::components::knowledge_graph::entities::writings;
 use crate::writing::components::knowledge_graph::{
-    CreateNoteInput, CreateReferenceInput, CreateWritingInput, LinkWritingIdeaInput, NoteDto,
-    ReferenceDto, UpdateNoteInput, UpdateReferenceInput, UpdateWritingInput, WritingDto,
+    CreateNoteInput, CreateReferenceInput, CreateWritingInput, ExportBibliographyInput,
+    LinkWritingIdeaInput, NoteDto, RecordPublicationInput, ReferenceDto, UpdateNoteInput,
+    UpdateReferenceInput, UpdateWritingInput, WritingDto,
+};
+use crate::writing::components::newsletter::{
+    ListNewsletterSendsInput, SendNewsDigestInput, SendWritingNewsletterInput,
 };
 use crate::writing::dto::{
-    CreateWritingDraftInput, GetWritingInput, LinkIdeaInput, ListLinkedIdeasInput,
-    ListWritingsQuery, PublishWritingInput, SaveDraftInput, UpdateWritingDraftMetaInput,
-    WritingDraftDto,
+    CreateWritingDraftInput, ExportDocxInput, ExportEpubInput, ExportPdfInput,
+    ExportWritingsMarkdownInput, GetWritingInput, ImportNotionInput, ImportObsidianInput,
+    LinkIdeaInput, ListLinkedIdeasInput, ListWritingsQuery, PublishWritingInput, SaveDraftInput,
+    SyncFolderInput, UpdateWritingDraftMetaInput, WritingDraftDto,
 };
+use crate::writing::git::WritingChange;
 use crate::writing::text;
 use crate::AppState;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::any::Any;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Clone)]
 pub struct BridgeContext {
     pub state: Arc<AppState>,
     pub emitter: Arc<dyn EventEmitter>,
+    /// Same emitter, kept concrete so `/ws` clients can subscribe
+    pub events: BroadcastEventEmitter,
+    pub jobs: JobRegistry,
+    pub metrics: CommandMetrics,
+    /// `state` is the default workspace's; commands take the active one's
+    pub workspaces: Workspaces,
+}
+
+impl BridgeContext {
+    /// This context with the state of the active workspace
+    pub fn in_active_workspace(&self) -> BridgeContext {
+        BridgeContext {
+            state: self.workspaces.active(),
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,18 +117,76 @@ pub struct CommandResponse {
 #[serde(rename_all = "camelCase")]
 pub struct ErrorResponse {
     pub message: String,
+    /// Stable error code such as "E7001"; branch on this, not on `message`
+    pub code: String,
+    pub status: u16,
+    pub retryable: bool,
+    pub requires_user_action: bool,
+    pub suggestion: Option<String>,
+    /// Matches the `x-request-id` header and the `request_id` log field
+    pub request_id: Option<String>,
+    /// On a conflict, the stored version the request was against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<Value>,
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum ApiError {
     #[error("bad request: {0}")]
     BadRequest(String),
+    /// The request's token doesn't carry the scope the command needs
+    #[error("forbidden: {0}")]
+    Forbidden(String),
     #[error("handler error: {0}")]
     Handler(String),
+    /// Handler failure that kept its `AppError` classification
+    #[error("{0}")]
+    App(AppError),
     #[error("serialization error: {0}")]
     Serde(String),
 }
 
+impl ApiError {
+    /// HTTP status that best describes this error
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::BadRequest(_) | ApiError::Serde(_) => 400,
+            ApiError::Forbidden(_) => 403,
+            ApiError::Handler(_) => 500,
+            ApiError::App(e) => e.http_status(),
+        }
+    }
+
+    pub fn to_response(&self) -> ErrorResponse {
+        let (code, retryable, requires_user_action, suggestion) = match self {
+            ApiError::App(e) => (
+                e.code(),
+                e.is_retryable(),
+                e.requires_user_action(),
+                e.suggestion().map(str::to_string),
+            ),
+            ApiError::BadRequest(_) | ApiError::Serde(_) => {
+                (ErrorCode::ValidationFailed, false, false, None)
+            }
+            ApiError::Forbidden(_) => (ErrorCode::PermissionDenied, false, false, None),
+            ApiError::Handler(_) => (ErrorCode::Unknown, false, false, None),
+        };
+        ErrorResponse {
+            message: self.to_string(),
+            code: code.as_string(),
+            status: self.status(),
+            retryable,
+            requires_user_action,
+            suggestion,
+            request_id: current_request_id(),
+            current: match self {
+                ApiError::App(e) => e.conflict_current().cloned(),
+                _ => None,
+            },
+        }
+    }
+}
+
 impl From<serde_json::Error> for ApiError {
     fn from(err: serde_json::Error) -> Self {
         ApiError::Serde(err.to_string())
@@ -91,8 +204,44 @@ fn into_value<T: Serialize>(data: T) -> Result<Value, ApiError> {
     serde_json::to_value(data).map_err(|e| ApiError::Serde(e.to_string()))
 }
 
-fn handler_err<E: ToString>(err: E) -> ApiError {
-    ApiError::Handler(err.to_string())
+/// Wrap a handler failure, keeping the code and status of an `AppError`
+fn handler_err<E: ToString + 'static>(err: E) -> ApiError {
+    let message = err.to_string();
+    let boxed: Box<dyn Any> = Box::new(err);
+    match boxed.downcast::<AppError>() {
+        Ok(app) => ApiError::App(*app),
+        Err(_) => ApiError::Handler(message),
+    }
+}
+
+/// File an import command should read: a server-side path, or a file staged
+/// through `POST /upload`
+///
+/// A server-side path can name any file the backend may read, so it takes
+/// admin access to the command's `module`; other tokens upload the file.
+fn import_source(
+    ctx: &BridgeContext,
+    module: &str,
+    import_path: Option<String>,
+    upload_handle: Option<String>,
+) -> Result<String, ApiError> {
+    match (import_path, upload_handle) {
+        (_, Some(handle)) => {
+            let path = crate::core::components::storage::resolve_upload(
+                &ctx.state.config.current().storage.import_dir,
+                &handle,
+            )
+            .map_err(handler_err)?;
+            Ok(path.to_string_lossy().into_owned())
+        }
+        (Some(path), None) => {
+            require(module, Access::Admin)?;
+            Ok(path)
+        }
+        (None, None) => Err(ApiError::BadRequest(
+            "a path or upload_handle is required".into(),
+        )),
+    }
 }
 
 /// Dispatch incoming command into the existing domain handlers.
@@ -100,6 +249,38 @@ pub async fn dispatch(
     command: &str,
     payload: Option<Value>,
     ctx: &BridgeContext,
+) -> Result<Value, ApiError> {
+    authorize(command)?;
+    let ctx = &ctx.in_active_workspace();
+    // Unknown names aren't recorded so they can't grow the metrics table
+    let known = bridge_schema()["commands"].get(command).is_some();
+    let audited = if known && audit::is_audited(command) {
+        let before = audit::snapshot_before(command, payload.as_ref(), ctx).await;
+        Some((payload.clone(), before))
+    } else {
+        None
+    };
+    let started = Instant::now();
+    let result = run_command(command, payload, ctx).await;
+    let elapsed = started.elapsed();
+    if known {
+        ctx.metrics.record(command, elapsed, result.is_ok());
+    }
+    if let Some((payload, before)) = audited {
+        audit::record(command, payload, before, &result, ctx).await;
+    }
+    if let Err(ApiError::App(e)) = &result {
+        ctx.state
+            .error_reporter
+            .report_app_error(command, e, current_request_id());
+    }
+    result
+}
+
+pub(super) async fn run_command(
+    command: &str,
+    payload: Option<Value>,
+    ctx: &BridgeContext,
 ) -> Result<Value, ApiError> {
     match command {
         // ---------- Core / Setup ----------
@@ -120,6 +301,15 @@ pub async fn dispatch(
                 timestamp: String,
             }
             let input: Input = parse_payload(payload)?;
+            let severity = input.severity.as_deref().unwrap_or("error");
+            ctx.state.error_reporter.report_frontend_error(
+                Severity::parse(severity).unwrap_or(Severity::Info),
+                &input.message,
+                &input.stack,
+                &input.component_stack,
+                input.action.as_deref(),
+                input.metadata.as_deref(),
+            );
             Ok(crate::util::commands::log_frontend_error(
                 input.message,
                 input.stack,
@@ -153,18 +343,45 @@ pub async fn dispatch(
                 .map_err(handler_err)?;
             into_value("ok")
         }
+        "reveal_setting" => {
+            #[derive(Deserialize)]
+            struct Input {
+                key: String,
+            }
+            let input: Input = parse_payload(payload)?;
+            let data = crate::core::components::settings::reveal_setting_handler(
+                &ctx.state.db,
+                &input.key,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(data)
+        }
         "get_storage_statistics" => {
-            let stats: StorageStats =
-                crate::core::components::storage::get_storage_stats(&ctx.state.config.storage)
-                    .map_err(handler_err)?;
+            let stats: StorageStats = crate::core::components::storage::get_storage_stats(
+                &ctx.state.config.current().storage,
+            )
+            .map_err(handler_err)?;
             into_value(stats)
         }
         "create_database_backup" => {
-            let info = crate::core::components::storage::backup_database(
-                &ctx.state.db,
-                &ctx.state.config.storage,
-            )
-            .await
+            let input: Option<CreateBackupInput> = parse_payload(payload)?;
+            let incremental = input.and_then(|i| i.incremental).unwrap_or(false);
+            let info = if incremental {
+                crate::core::components::storage::backup_database_incremental(
+                    &ctx.state.db,
+                    &ctx.state.config.current().database,
+                    &ctx.state.config.current().storage,
+                )
+                .await
+            } else {
+                crate::core::components::storage::backup_database(
+                    &ctx.state.db,
+                    &ctx.state.config.current().database,
+                    &ctx.state.config.current().storage,
+                )
+                .await
+            }
             .map_err(handler_err)?;
             into_value(info)
         }
@@ -176,16 +393,18 @@ pub async fn dispatch(
             let input: Input = parse_payload(payload)?;
             crate::core::components::storage::restore_database(
                 &ctx.state.db,
+                &ctx.state.config.current().database,
                 &input.backup_path,
-                &ctx.state.config.storage,
+                &ctx.state.config.current().storage,
             )
             .await
             .map_err(handler_err)?;
             into_value("ok")
         }
         "list_database_backups" => {
-            let backups = crate::core::components::storage::list_backups(&ctx.state.config.storage)
-                .map_err(handler_err)?;
+            let backups =
+                crate::core::components::storage::list_backups(&ctx.state.config.current().storage)
+                    .map_err(handler_err)?;
             into_value(backups)
         }
         "delete_database_backup" => {
@@ -195,17 +414,36 @@ pub async fn dispatch(
             }
             let input: Input = parse_payload(payload)?;
             crate::core::components::storage::delete_backup(
-                &ctx.state.config.storage,
+                &ctx.state.config.current().storage,
                 &input.backup_path,
             )
             .await
             .map_err(handler_err)?;
             into_value("ok")
         }
+        "verify_backup" => {
+            #[derive(Deserialize)]
+            struct Input {
+                backup_path: String,
+            }
+            let input: Input = parse_payload(payload)?;
+            let verification = crate::core::components::storage::verify_backup(
+                &ctx.state.db,
+                &ctx.state.config.current().database,
+                &input.backup_path,
+                &ctx.state.config.current().storage,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(verification)
+        }
         "export_database" => {
+            let input: Option<ExportInput> = parse_payload(payload)?;
             let info = crate::core::components::storage::export_data(
                 &ctx.state.db,
-                &ctx.state.config.storage,
+                &ctx.state.config.current().database,
+                &ctx.state.config.current().storage,
+                &input.unwrap_or_default(),
             )
             .await
             .map_err(handler_err)?;
@@ -214,11 +452,19 @@ pub async fn dispatch(
         "import_database" => {
             #[derive(Deserialize)]
             struct Input {
-                import_path: String,
+                import_path: Option<String>,
+                upload_handle: Option<String>,
+                strategy: Option<crate::core::components::storage::ImportStrategy>,
+                dry_run: Option<bool>,
             }
             let input: Input = parse_payload(payload)?;
+            let import_path = import_source(ctx, "core", input.import_path, input.upload_handle)?;
+            let options = crate::core::components::storage::ImportOptions {
+                strategy: input.strategy.unwrap_or_default(),
+                dry_run: input.dry_run.unwrap_or(false),
+            };
             let summary =
-                crate::core::components::storage::import_data(&ctx.state.db, &input.import_path)
+                crate::core::components::storage::import_data(&ctx.state.db, &import_path, options)
                     .await
                     .map_err(handler_err)?;
             into_value(summary)
@@ -230,7 +476,7 @@ pub async fn dispatch(
             }
             let input: Input = parse_payload(payload)?;
             let result = crate::core::components::storage::cleanup_old_logs(
-                &ctx.state.config.storage,
+                &ctx.state.config.current().storage,
                 input.retention_days,
             )
             .map_err(handler_err)?;
@@ -250,6 +496,153 @@ pub async fn dispatch(
             .map_err(handler_err)?;
             into_value(result)
         }
+        "run_db_maintenance" => {
+            #[derive(Deserialize)]
+            struct Input {
+                vacuum: Option<bool>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let result = crate::core::components::db::maintenance::run_db_maintenance(
+                &ctx.state.db,
+                Some(input.vacuum.unwrap_or(true)),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "get_migration_status" => {
+            let result =
+                crate::core::components::db::migrations::get_migration_status(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(result)
+        }
+        "rollback_last_migration" => {
+            let result = crate::core::components::db::migrations::rollback_last_migration(
+                &ctx.state.db,
+                &ctx.state.config.current().database,
+                &ctx.state.config.current().storage,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "check_database_integrity" => {
+            #[derive(Deserialize)]
+            struct Input {
+                full: Option<bool>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let result = crate::core::components::db::integrity::check_database_integrity(
+                &ctx.state.db,
+                input.full.unwrap_or(false),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "encrypt_database" => {
+            let result = crate::core::components::db::encryption::encrypt_database(
+                &ctx.state.config.current().database,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "rotate_master_key" => {
+            // Only the active workspace's secrets would be re-encrypted, and
+            // the others share the key
+            if ctx.workspaces.extra_count().map_err(handler_err)? > 0 {
+                return Err(ApiError::App(AppError::validation(
+                    "master_key",
+                    "The master key can't be rotated while there are workspaces besides the default one",
+                )));
+            }
+            let result = crate::core::components::key_rotation::rotate_master_key(
+                &ctx.state.config,
+                &ctx.state.db,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "list_credentials" => {
+            #[derive(Deserialize)]
+            struct Input {
+                test: Option<bool>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let result = crate::core::components::credentials::list_credentials(
+                &ctx.state.db,
+                &ctx.state.http_clients,
+                input.test.unwrap_or(true),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "export_profile" => {
+            let profile = crate::core::components::profile::export_profile(&ctx.state)
+                .await
+                .map_err(handler_err)?;
+            into_value(profile)
+        }
+        "import_profile" => {
+            #[derive(Deserialize)]
+            struct Input {
+                profile: crate::core::components::profile::Profile,
+            }
+            let input: Input = parse_payload(payload)?;
+            let report =
+                crate::core::components::profile::import_profile(&ctx.state, input.profile)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(report)
+        }
+        "list_workspaces" => {
+            let workspaces: Vec<WorkspaceDto> = ctx.workspaces.list().map_err(handler_err)?;
+            into_value(workspaces)
+        }
+        "create_workspace" => {
+            #[derive(Deserialize)]
+            struct Input {
+                name: String,
+            }
+            let input: Input = parse_payload(payload)?;
+            let workspace = ctx
+                .workspaces
+                .create(&input.name)
+                .await
+                .map_err(handler_err)?;
+            into_value(workspace)
+        }
+        "switch_workspace" => {
+            #[derive(Deserialize)]
+            struct Input {
+                name: String,
+            }
+            let input: Input = parse_payload(payload)?;
+            let workspace = ctx
+                .workspaces
+                .switch(&input.name)
+                .await
+                .map_err(handler_err)?;
+            into_value(workspace)
+        }
+        "reload_config" => {
+            let result = crate::core::components::config::reload_config(
+                &ctx.state.config,
+                &ctx.state.log_filter,
+            )
+            .map_err(handler_err)?;
+            into_value(result)
+        }
+        "check_config" => {
+            let config = ctx.state.config.current();
+            let result =
+                crate::core::components::config::check_config(&config, &ctx.state.db).await;
+            into_value(result)
+        }
         "get_application_logs" => {
             #[derive(Deserialize)]
             struct Input {
@@ -259,7 +652,7 @@ pub async fn dispatch(
             }
             let input: Input = parse_payload(payload)?;
             let logs = crate::core::components::storage::get_logs(
-                &ctx.state.config.storage,
+                &ctx.state.config.current().storage,
                 input.level_filter,
                 input.limit,
                 input.offset,
@@ -268,8 +661,10 @@ pub async fn dispatch(
             into_value(logs)
         }
         "get_application_log_stats" => {
-            let stats = crate::core::components::storage::get_log_stats(&ctx.state.config.storage)
-                .map_err(handler_err)?;
+            let stats = crate::core::components::storage::get_log_stats(
+                &ctx.state.config.current().storage,
+            )
+            .map_err(handler_err)?;
             into_value(stats)
         }
         "export_application_logs" => {
@@ -279,16 +674,18 @@ pub async fn dispatch(
             }
             let input: Input = parse_payload(payload)?;
             let path = crate::core::components::storage::export_logs(
-                &ctx.state.config.storage,
+                &ctx.state.config.current().storage,
                 input.level_filter,
             )
             .map_err(handler_err)?;
             into_value(path)
         }
         "clear_application_logs" => {
-            let result =
-                crate::core::components::storage::clear_logs(&ctx.state.config.storage, None)
-                    .map_err(handler_err)?;
+            let result = crate::core::components::storage::clear_logs(
+                &ctx.state.config.current().storage,
+                None,
+            )
+            .map_err(handler_err)?;
             into_value(result)
         }
         "check_setup_status_command" => {
@@ -318,6 +715,60 @@ pub async fn dispatch(
             into_value("ok")
         }
 
+        // Bridge metrics
+        "get_command_metrics" => into_value(ctx.metrics.snapshot()),
+
+        // Audit log
+        "list_audit_log" => {
+            let input: Option<ListAuditLogInput> = parse_payload(payload)?;
+            let res = crate::core::components::audit::list_audit_log_handler(
+                &ctx.state.db,
+                input.unwrap_or_default(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "export_audit_log" => {
+            let filter: Option<AuditLogFilter> = parse_payload(payload)?;
+            let res = crate::core::components::audit::export_audit_log_handler(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                filter.unwrap_or_default(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+
+        // Semantic search
+        "embeddings_reindex" => {
+            let input: Option<ReindexEmbeddingsInput> = parse_payload(payload)?;
+            let res = crate::core::components::embeddings::reindex_embeddings_handler(
+                input.unwrap_or_default(),
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "semantic_search" => {
+            let input: SemanticSearchInput = parse_payload(payload)?;
+            let res =
+                crate::core::components::embeddings::semantic_search_handler(input, &ctx.state)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "more_like_this" => {
+            let input: MoreLikeThisInput = parse_payload(payload)?;
+            let res =
+                crate::core::components::embeddings::more_like_this_handler(input, &ctx.state)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+
         // ---------- System Scheduler ----------
         "list_system_tasks" => {
             let tasks: Vec<SystemTaskDto> =
@@ -349,11 +800,13 @@ pub async fn dispatch(
             #[derive(Deserialize)]
             struct Input {
                 task_type: String,
+                params: Option<Value>,
             }
             let input: Input = parse_payload(payload)?;
             let res: RunTaskNowResult =
                 crate::system::components::scheduler::run_system_task_now_handler(
                     input.task_type,
+                    input.params,
                     &ctx.state,
                     ctx.emitter.as_ref(),
                 )
@@ -377,7 +830,58 @@ pub async fn dispatch(
             .map_err(handler_err)?;
             into_value(saved)
         }
+        "schedule_task_run" => {
+            #[derive(Deserialize)]
+            struct Input {
+                task_type: String,
+                run_at: String,
+                params: Option<Value>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let run: ScheduledRunDto =
+                crate::system::components::scheduler::schedule_task_run_handler(
+                    input.task_type,
+                    input.run_at,
+                    input.params,
+                    &ctx.state,
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(run)
+        }
+        "list_scheduled_runs" => {
+            let runs = crate::system::components::scheduler::list_scheduled_runs_handler(&ctx.state)
+                .await
+                .map_err(handler_err)?;
+            into_value(runs)
+        }
+        "cancel_scheduled_run" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::system::components::scheduler::cancel_scheduled_run_handler(
+                input.id,
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value("ok")
+        }
 
+        "pause_scheduler" => {
+            let status = crate::system::components::scheduler::pause_scheduler_handler(&ctx.state)
+                .await
+                .map_err(handler_err)?;
+            into_value(status)
+        }
+        "resume_scheduler" => {
+            let status = crate::system::components::scheduler::resume_scheduler_handler(&ctx.state)
+                .await
+                .map_err(handler_err)?;
+            into_value(status)
+        }
         // ---------- Research ----------
         "get_news_settings" => {
             let dto: NewsSettingsDto =
@@ -407,25 +911,64 @@ pub async fn dispatch(
                 start_date: Option<String>,
                 end_date: Option<String>,
                 sort_by: Option<String>,
+                envelope: Option<bool>,
             }
             let input: Input = parse_payload(payload)?;
+            let limit = input.limit.unwrap_or(100);
+            let offset = input.offset.unwrap_or(0);
             let articles: Vec<NewsArticleDto> =
                 crate::research::components::feed::list_news_articles_handler(
-                    input.status,
-                    input.limit,
-                    input.offset,
+                    input.status.clone(),
+                    Some(limit),
+                    Some(offset),
                     input.include_dismissed,
-                    input.search,
+                    input.search.clone(),
                     input.source_id,
                     input.starred,
-                    input.start_date,
-                    input.end_date,
-                    input.sort_by,
+                    input.start_date.clone(),
+                    input.end_date.clone(),
+                    input.sort_by.clone(),
                     &ctx.state,
                 )
                 .await
                 .map_err(handler_err)?;
-            into_value(articles)
+            if input.envelope != Some(true) {
+                return into_value(Listing::Items(articles));
+            }
+            let total = crate::research::components::feed::count_news_articles_handler(
+                input.status.as_deref(),
+                input.include_dismissed,
+                input.search.as_deref(),
+                input.source_id,
+                input.starred,
+                input.start_date.as_deref(),
+                input.end_date.as_deref(),
+                input.sort_by.as_deref(),
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(Listing::Page(Page::new(articles, total, limit, offset)))
+        }
+        "search_news_articles" => {
+            #[derive(Deserialize)]
+            struct Input {
+                query: String,
+                limit: Option<u64>,
+                offset: Option<u64>,
+                include_dismissed: Option<bool>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let hits = crate::research::components::feed::search_news_articles_handler(
+                input.query,
+                input.limit,
+                input.offset,
+                input.include_dismissed,
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(hits)
         }
         "get_news_article" => {
             #[derive(Deserialize)]
@@ -485,9 +1028,11 @@ pub async fn dispatch(
             into_value("ok")
         }
         "sync_news_now" => {
+            let started = chrono::Utc::now();
             let res = crate::research::components::feed::sync_news_now_handler(&ctx.state)
                 .await
                 .map_err(handler_err)?;
+            emit_news_alerts(ctx, started).await;
             into_value(res)
         }
         "sync_news_sources_now" => {
@@ -598,7 +1143,7 @@ pub async fn dispatch(
             let input: Input = parse_payload(payload)?;
             let res = crate::research::components::feed::test_feed_source_connection_handler(
                 &ctx.state.db,
-                &ctx.state.http_client,
+                &ctx.state.http_clients.api,
                 input.source_id,
             )
             .await
@@ -611,27 +1156,193 @@ pub async fn dispatch(
                 source_id: i64,
             }
             let input: Input = parse_payload(payload)?;
+            let started = chrono::Utc::now();
             let res: SyncSourceResult =
                 crate::research::components::feed::sync_feed_source_now_handler(
                     &ctx.state.db,
-                    &ctx.state.http_client,
+                    &ctx.state.http_clients.api,
                     input.source_id,
                 )
                 .await
                 .map_err(handler_err)?;
+            emit_news_alerts(ctx, started).await;
             into_value(res)
         }
         "sync_all_feed_sources" => {
+            let started = chrono::Utc::now();
+            // Reports per-source progress when run through /api/jobs
+            let on_progress = |done: usize, total: usize, source: &SyncSourceResult| {
+                jobs::report_progress(
+                    done as u64,
+                    Some(total as u64),
+                    Some(source.source_name.clone()),
+                )
+            };
             let res: SyncAllResult =
-                crate::research::components::feed::sync_all_feed_sources_handler(
+                crate::research::components::feed::sync_all_feed_sources_with_progress(
                     &ctx.state.db,
-                    &ctx.state.http_client,
+                    &ctx.state.http_clients.api,
+                    &on_progress,
                 )
                 .await
                 .map_err(handler_err)?;
+            emit_news_alerts(ctx, started).await;
             into_value(res)
         }
-        // Research connectors
+        // Keyword alert rules
+        "list_alert_rules" => {
+            let res = crate::research::components::feed::list_alert_rules_handler(&ctx.state.db)
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "create_alert_rule" => {
+            let input: CreateAlertRuleInput = parse_payload(payload)?;
+            let res = crate::research::components::feed::create_alert_rule_handler(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "update_alert_rule" => {
+            let input: UpdateAlertRuleInput = parse_payload(payload)?;
+            let res = crate::research::components::feed::update_alert_rule_handler(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "delete_alert_rule" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::research::components::feed::delete_alert_rule_handler(&ctx.state.db, input.id)
+                .await
+                .map_err(handler_err)?;
+            into_value("ok")
+        }
+        // Saved searches
+        "list_saved_searches" => {
+            let res =
+                crate::research::components::feed::list_saved_searches_handler(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "create_saved_search" => {
+            let input: CreateSavedSearchInput = parse_payload(payload)?;
+            let res = crate::research::components::feed::create_saved_search_handler(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "update_saved_search" => {
+            let input: UpdateSavedSearchInput = parse_payload(payload)?;
+            let res = crate::research::components::feed::update_saved_search_handler(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "delete_saved_search" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::research::components::feed::delete_saved_search_handler(&ctx.state.db, input.id)
+                .await
+                .map_err(handler_err)?;
+            into_value("ok")
+        }
+        "run_saved_search" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+                limit: Option<u64>,
+                offset: Option<u64>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res = crate::research::components::feed::run_saved_search_handler(
+                input.id,
+                input.limit,
+                input.offset,
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "test_alert_rule" => {
+            let input: Option<TestAlertRuleInput> = parse_payload(payload)?;
+            let res = crate::research::components::feed::test_alert_rule_handler(
+                &ctx.state.db,
+                input.unwrap_or_default(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        // Mute rules
+        "list_mute_rules" => {
+            let res = crate::research::components::feed::list_mute_rules_handler(&ctx.state.db)
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "create_mute_rule" => {
+            let input: CreateMuteRuleInput = parse_payload(payload)?;
+            let res = crate::research::components::feed::create_mute_rule_handler(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "update_mute_rule" => {
+            let input: UpdateMuteRuleInput = parse_payload(payload)?;
+            let res = crate::research::components::feed::update_mute_rule_handler(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "delete_mute_rule" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::research::components::feed::delete_mute_rule_handler(&ctx.state.db, input.id)
+                .await
+                .map_err(handler_err)?;
+            into_value("ok")
+        }
+        "preview_mute_rule" => {
+            let input: Option<PreviewMuteRuleInput> = parse_payload(payload)?;
+            let res = crate::research::components::feed::preview_mute_rule_handler(
+                &ctx.state.db,
+                input.unwrap_or_default(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        // Research connectors
         "research_list_accounts" => {
             let res: Vec<ResearchAccountDto> =
                 crate::research::components::connectors::list_accounts(ctx.state.as_ref())
@@ -666,6 +1377,38 @@ pub async fn dispatch(
                 .map_err(handler_err)?;
             into_value("ok")
         }
+        "research_site_credentials_list" => {
+            let res: Vec<SiteCredentialDto> =
+                crate::research::components::site_credentials::site_credentials_list(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "research_site_credential_save" => {
+            let input: SiteCredentialSaveInput = parse_payload(payload)?;
+            let res: SiteCredentialDto =
+                crate::research::components::site_credentials::site_credential_save(
+                    &ctx.state.db,
+                    input,
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "research_site_credential_delete" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::research::components::site_credentials::site_credential_delete(
+                &ctx.state.db,
+                input.id,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value("ok")
+        }
         "research_list_streams" => {
             #[derive(Deserialize)]
             struct Input {
@@ -675,7 +1418,7 @@ pub async fn dispatch(
             let res: Vec<ResearchStreamDto> =
                 crate::research::components::connectors::list_streams(
                     input.account_id,
-                    ctx.state.as_ref(),
+                    &ctx.state,
                 )
                 .await
                 .map_err(handler_err)?;
@@ -708,7 +1451,7 @@ pub async fn dispatch(
             let input: Input = parse_payload(payload)?;
             crate::research::components::connectors::sync_stream_now(
                 input.stream_id,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -732,7 +1475,7 @@ pub async fn dispatch(
             crate::research::components::connectors::set_item_status(
                 input.item_id,
                 input.status,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -747,18 +1490,47 @@ pub async fn dispatch(
             let input: ReaderFetchInput = parse_payload(payload)?;
             let res: ReaderResult = crate::research::components::reader::reader_fetch(
                 &ctx.state.db,
-                &ctx.state.http_client,
+                &ctx.state.http_clients.reader,
+                &ctx.state.config.current().storage.media_dir,
                 input,
             )
             .await
             .map_err(handler_err)?;
             into_value(res)
         }
+        "reader_fetch_batch" => {
+            let input: ReaderFetchBatchInput = parse_payload(payload)?;
+            let res: ReaderFetchBatchResult =
+                crate::research::components::reader_batch::reader_fetch_batch(
+                    &ctx.state.db,
+                    &ctx.state.http_clients.reader,
+                    &ctx.state.config.current().storage.media_dir,
+                    input,
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_import_enex" => {
+            let input: ReaderImportEnexInput = parse_payload(payload)?;
+            let import_path =
+                import_source(ctx, "research", input.import_path, input.upload_handle)?;
+            let res: ReaderEnexImportResult =
+                crate::research::components::reader_enex::import_enex(
+                    &ctx.state.db,
+                    &ctx.state.config.current().storage.media_dir,
+                    std::path::Path::new(&import_path),
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
         "reader_refresh" => {
             let input: ReaderRefreshInput = parse_payload(payload)?;
             let res: ReaderResult = crate::research::components::reader::reader_refresh(
                 &ctx.state.db,
-                &ctx.state.http_client,
+                &ctx.state.http_clients.reader,
+                &ctx.state.config.current().storage.media_dir,
                 input,
             )
             .await
@@ -795,6 +1567,41 @@ pub async fn dispatch(
             .map_err(handler_err)?;
             into_value(res)
         }
+        "update_reading_progress" => {
+            let input: ReadingProgressInput = parse_payload(payload)?;
+            let res: ReaderReferenceDto =
+                crate::research::components::reader::update_reading_progress(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_continue_reading" => {
+            #[derive(Deserialize)]
+            struct Input {
+                limit: Option<u64>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res: Vec<ReaderReferenceDto> =
+                crate::research::components::reader::continue_reading(&ctx.state.db, input.limit)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_set_watch" => {
+            let input: ReaderWatchInput = parse_payload(payload)?;
+            let res: ReaderReferenceDto =
+                crate::research::components::reader_watch::set_reference_watch(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_watched_list" => {
+            let res: Vec<ReaderReferenceDto> =
+                crate::research::components::reader_watch::watched_references(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
         "reader_snapshots_list" => {
             #[derive(Deserialize)]
             struct Input {
@@ -821,16 +1628,51 @@ pub async fn dispatch(
                     .map_err(handler_err)?;
             into_value(res)
         }
+        "reader_snapshot_delete" => {
+            #[derive(Deserialize)]
+            struct Input {
+                snapshot_id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::research::components::reader::snapshot_delete(
+                &ctx.state.db,
+                &ctx.state.config.current().storage.media_dir,
+                input.snapshot_id,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value("ok")
+        }
+        "reader_media_cleanup" => {
+            let res = crate::research::components::reader_media::cleanup_orphaned_media(
+                &ctx.state.db,
+                &ctx.state.config.current().storage.media_dir,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
         "reader_clips_list" => {
             #[derive(Deserialize)]
             struct Input {
                 reference_id: i64,
+                label: Option<String>,
+                color: Option<String>,
+                snapshot_id: Option<i64>,
             }
             let input: Input = parse_payload(payload)?;
-            let res: Vec<ReaderClipDto> =
-                crate::research::components::reader::clips_list(&ctx.state.db, input.reference_id)
-                    .await
-                    .map_err(handler_err)?;
+            let filter = ClipListFilter {
+                label: input.label,
+                color: input.color,
+                snapshot_id: input.snapshot_id,
+            };
+            let res: Vec<ReaderClipDto> = crate::research::components::reader::clips_list(
+                &ctx.state.db,
+                input.reference_id,
+                filter,
+            )
+            .await
+            .map_err(handler_err)?;
             into_value(res)
         }
         "reader_clip_create" => {
@@ -841,6 +1683,14 @@ pub async fn dispatch(
                     .map_err(handler_err)?;
             into_value(res)
         }
+        "reader_clip_update" => {
+            let input: ClipUpdateInput = parse_payload(payload)?;
+            let res: ReaderClipDto =
+                crate::research::components::reader::clip_update(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
         "reader_clip_delete" => {
             #[derive(Deserialize)]
             struct Input {
@@ -852,6 +1702,153 @@ pub async fn dispatch(
                 .map_err(handler_err)?;
             into_value("ok")
         }
+        "reader_clips_reanchor" => {
+            let input: ClipReanchorInput = parse_payload(payload)?;
+            let res: Vec<ClipReanchorResult> =
+                crate::research::components::reader_anchor::reanchor_clips(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_site_rules_list" => {
+            let res: Vec<ReaderSiteRuleDto> =
+                crate::research::components::reader_site_rules::site_rules_list(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_site_rule_create" => {
+            let input: SiteRuleCreateInput = parse_payload(payload)?;
+            let res: ReaderSiteRuleDto =
+                crate::research::components::reader_site_rules::site_rule_create(
+                    &ctx.state.db,
+                    input,
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_site_rule_update" => {
+            let input: SiteRuleUpdateInput = parse_payload(payload)?;
+            let res: ReaderSiteRuleDto =
+                crate::research::components::reader_site_rules::site_rule_update(
+                    &ctx.state.db,
+                    input,
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reader_site_rule_delete" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::research::components::reader_site_rules::site_rule_delete(
+                &ctx.state.db,
+                input.id,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value("ok")
+        }
+        "reader_site_rule_test" => {
+            let input: SiteRuleTestInput = parse_payload(payload)?;
+            let res: SiteRuleTestResult =
+                crate::research::components::reader_site_rules::site_rule_test(
+                    &ctx.state.db,
+                    &ctx.state.http_clients.reader,
+                    input,
+                )
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reading_queue_list" => {
+            let res: Vec<ReadingQueueItemDto> =
+                crate::research::components::reading_queue::queue_list(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reading_queue_add" => {
+            let input: ReadingQueueAddInput = parse_payload(payload)?;
+            let res: Vec<ReadingQueueItemDto> =
+                crate::research::components::reading_queue::queue_add(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reading_queue_remove" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res: Vec<ReadingQueueItemDto> =
+                crate::research::components::reading_queue::queue_remove(&ctx.state.db, input.id)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reading_queue_reorder" => {
+            let input: ReadingQueueReorderInput = parse_payload(payload)?;
+            let res: Vec<ReadingQueueItemDto> =
+                crate::research::components::reading_queue::queue_reorder(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reading_queue_pop" => {
+            let res: Option<ReadingQueueItemDto> =
+                crate::research::components::reading_queue::queue_pop(&ctx.state.db)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reading_stats" => {
+            let input: Option<ReadingStatsInput> = parse_payload(payload)?;
+            let res: ReadingStatsDto = crate::research::components::reading_stats::reading_stats(
+                &ctx.state.db,
+                input.unwrap_or_default(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "summarize_reference" => {
+            let input: SummarizeReferenceInput = parse_payload(payload)?;
+            let res = crate::research::components::reader::summarize_reference(
+                &ctx.state.db,
+                &ctx.state.http_clients.api,
+                &ctx.state.config.current().ai,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "suggest_tags" => {
+            let input: SuggestTagsInput = parse_payload(payload)?;
+            let res = crate::research::components::tagging::suggest_tags_handler(
+                input,
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "suggest_tags_batch" => {
+            let input: Option<SuggestTagsBatchInput> = parse_payload(payload)?;
+            let res = crate::research::components::tagging::suggest_tags_batch_handler(
+                input.unwrap_or_default(),
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
 
         // ---------- Writing ----------
         "list_ideas" => {
@@ -860,21 +1857,40 @@ pub async fn dispatch(
                 status: Option<String>,
                 search: Option<String>,
                 include_removed: Option<bool>,
+                due: Option<String>,
+                sort_by: Option<String>,
                 limit: Option<u64>,
                 offset: Option<u64>,
+                envelope: Option<bool>,
             }
             let input: Input = parse_payload(payload)?;
+            let limit = input.limit.unwrap_or(50);
+            let offset = input.offset.unwrap_or(0);
             let res: Vec<IdeaDto> = crate::writing::components::ideas::list_ideas_handler(
-                input.status,
-                input.search,
+                input.status.clone(),
+                input.search.clone(),
                 input.include_removed,
-                input.limit,
-                input.offset,
-                ctx.state.as_ref(),
+                input.due.clone(),
+                input.sort_by,
+                Some(limit),
+                Some(offset),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
-            into_value(res)
+            if input.envelope != Some(true) {
+                return into_value(Listing::Items(res));
+            }
+            let total = crate::writing::components::ideas::count_ideas_handler(
+                input.status.as_deref(),
+                input.search.as_deref(),
+                input.include_removed,
+                input.due.as_deref(),
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(Listing::Page(Page::new(res, total, limit, offset)))
         }
         "get_idea" => {
             #[derive(Deserialize)]
@@ -900,7 +1916,7 @@ pub async fn dispatch(
             let input: CreateIdeaForArticleInput = parse_payload(payload)?;
             let res = crate::writing::components::ideas::create_idea_for_article_handler(
                 input,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -916,7 +1932,7 @@ pub async fn dispatch(
             let res = crate::writing::components::ideas::update_idea_metadata_handler(
                 input.id,
                 input.input,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -932,7 +1948,7 @@ pub async fn dispatch(
             let res = crate::writing::components::ideas::update_idea_notes_handler(
                 input.id,
                 input.input,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -948,7 +1964,7 @@ pub async fn dispatch(
             let res = crate::writing::components::ideas::update_idea_article_handler(
                 input.id,
                 input.input,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -962,7 +1978,31 @@ pub async fn dispatch(
             let input: Input = parse_payload(payload)?;
             let res = crate::writing::components::ideas::archive_idea_handler(
                 input.id,
-                ctx.state.as_ref(),
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "reorder_ideas" => {
+            let input: ReorderIdeasInput = parse_payload(payload)?;
+            let res =
+                crate::writing::components::ideas::reorder_ideas_handler(input, ctx.state.as_ref())
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "merge_ideas" => {
+            #[derive(Deserialize)]
+            struct Input {
+                source_id: i64,
+                target_id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res: IdeaMergeDto = crate::writing::components::ideas::merge_ideas_handler(
+                input.source_id,
+                input.target_id,
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -982,7 +2022,7 @@ pub async fn dispatch(
             let res: Vec<IdeaReferenceDto> =
                 crate::writing::components::ideas::list_idea_references_handler(
                     input.idea_id,
-                    ctx.state.as_ref(),
+                    &ctx.state,
                 )
                 .await
                 .map_err(handler_err)?;
@@ -992,7 +2032,7 @@ pub async fn dispatch(
             let input: AddReferenceInput = parse_payload(payload)?;
             let res = crate::writing::components::ideas::add_reference_to_idea_handler(
                 input,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -1006,7 +2046,7 @@ pub async fn dispatch(
             let input: Input = parse_payload(payload)?;
             crate::writing::components::ideas::remove_reference_handler(
                 input.reference_id,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -1016,7 +2056,7 @@ pub async fn dispatch(
             let input: UpdateReferenceNotesInput = parse_payload(payload)?;
             let res = crate::writing::components::ideas::update_reference_notes_handler(
                 input,
-                ctx.state.as_ref(),
+                &ctx.state,
             )
             .await
             .map_err(handler_err)?;
@@ -1031,7 +2071,7 @@ pub async fn dispatch(
             let res: ReferenceReaderSnapshotDto =
                 crate::writing::components::ideas::get_reference_reader_snapshot_handler(
                     input.reference_id,
-                    ctx.state.as_ref(),
+                    &ctx.state,
                 )
                 .await
                 .map_err(handler_err)?;
@@ -1042,12 +2082,22 @@ pub async fn dispatch(
             let res: ReferenceReaderSnapshotDto =
                 crate::writing::components::ideas::get_reader_snapshot_for_url_handler(
                     input,
-                    ctx.state.as_ref(),
+                    &ctx.state,
                 )
                 .await
                 .map_err(handler_err)?;
             into_value(res)
         }
+        "suggest_related_content" => {
+            let input: RelatedContentInput = parse_payload(payload)?;
+            let res = crate::writing::components::ideas::suggest_related_content_handler(
+                input,
+                &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
 
         // Knowledge graph References
         "kg_list_references" => {
@@ -1057,19 +2107,32 @@ pub async fn dispatch(
                 search: Option<String>,
                 limit: Option<u64>,
                 offset: Option<u64>,
+                envelope: Option<bool>,
             }
             let input: Input = parse_payload(payload)?;
+            let limit = input.limit.unwrap_or(50);
+            let offset = input.offset.unwrap_or(0);
             let res: Vec<ReferenceDto> =
                 crate::writing::components::knowledge_graph::list_references(
                     &ctx.state.db,
-                    input.reference_type,
-                    input.search,
-                    input.limit,
-                    input.offset,
+                    input.reference_type.clone(),
+                    input.search.clone(),
+                    Some(limit),
+                    Some(offset),
                 )
                 .await
                 .map_err(handler_err)?;
-            into_value(res)
+            if input.envelope != Some(true) {
+                return into_value(Listing::Items(res));
+            }
+            let total = crate::writing::components::knowledge_graph::count_references(
+                &ctx.state.db,
+                input.reference_type.as_deref(),
+                input.search.as_deref(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(Listing::Page(Page::new(res, total, limit, offset)))
         }
         "kg_get_reference" => {
             #[derive(Deserialize)]
@@ -1313,6 +2376,70 @@ pub async fn dispatch(
             .map_err(handler_err)?;
             into_value(res)
         }
+        "kg_export_graph" => {
+            #[derive(Deserialize)]
+            struct Input {
+                format: crate::writing::components::knowledge_graph::GraphFormat,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res = crate::writing::components::knowledge_graph::export_graph(
+                &ctx.state.db,
+                input.format,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "kg_export_bibliography" => {
+            let input: ExportBibliographyInput = parse_payload(payload)?;
+            let res = crate::writing::components::knowledge_graph::export_bibliography(
+                &ctx.state.db,
+                input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+
+        // Knowledge graph publications
+        "kg_list_publications" => {
+            #[derive(Deserialize)]
+            struct Input {
+                writing_id: Option<i64>,
+                platform: Option<String>,
+                status: Option<String>,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res = crate::writing::components::knowledge_graph::list_publications(
+                &ctx.state.db,
+                input.writing_id,
+                input.platform,
+                input.status,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "kg_record_publication" => {
+            let input: RecordPublicationInput = parse_payload(payload)?;
+            let res =
+                crate::writing::components::knowledge_graph::record_publication(&ctx.state.db, input)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "kg_retry_publication" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res =
+                crate::writing::components::knowledge_graph::retry_publication(&ctx.state, input.id)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
 
         // Knowledge graph notes
         "kg_list_notes_for_entity" => {
@@ -1405,15 +2532,65 @@ pub async fn dispatch(
                 entity_type: String,
                 entity_id: i64,
                 note_type: Option<String>,
-                body_html: String,
+                body_html: Option<String>,
+                body_markdown: Option<String>,
+                store_markdown: Option<bool>,
+                expected_updated_at: Option<String>,
+            }
+            let input: Input = parse_payload(payload)?;
+            use crate::notes::components::notes::NoteBody;
+            let body = match (&input.body_html, &input.body_markdown) {
+                (Some(html), None) => NoteBody::Html(html),
+                (None, Some(markdown)) => NoteBody::Markdown(markdown),
+                _ => {
+                    return Err(ApiError::BadRequest(
+                        "exactly one of body_html or body_markdown is required".into(),
+                    ))
+                }
+            };
+            let res = crate::notes::components::notes::upsert(
+                &ctx.state.db,
+                &input.entity_type,
+                input.entity_id,
+                input.note_type.as_deref(),
+                body,
+                input.store_markdown,
+                input.expected_updated_at.as_deref(),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "notes_export_markdown" => {
+            #[derive(Deserialize)]
+            struct Input {
+                entity_type: String,
+                entity_id: i64,
+                note_type: Option<String>,
             }
             let input: Input = parse_payload(payload)?;
-            let res = crate::notes::components::notes::upsert(
+            let res = crate::notes::components::notes::export_markdown(
                 &ctx.state.db,
                 &input.entity_type,
                 input.entity_id,
                 input.note_type.as_deref(),
-                &input.body_html,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "export_entity_bundle" => {
+            #[derive(Deserialize)]
+            struct Input {
+                entity_type: String,
+                entity_id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res = crate::notes::components::bundle::export_entity_bundle(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &input.entity_type,
+                input.entity_id,
             )
             .await
             .map_err(handler_err)?;
@@ -1443,6 +2620,53 @@ pub async fn dispatch(
             .map_err(handler_err)?;
             into_value(res)
         }
+        "notes_list_backlinks" => {
+            #[derive(Deserialize)]
+            struct Input {
+                entity_type: String,
+                entity_id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            let res = crate::notes::components::links::list_backlinks(
+                &ctx.state.db,
+                &input.entity_type,
+                input.entity_id,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "notes_templates_list" => {
+            let res = crate::notes::components::templates::templates_list(&ctx.state.db)
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "notes_template_create" => {
+            let input: NoteTemplateCreateInput = parse_payload(payload)?;
+            let res = crate::notes::components::templates::template_create(&ctx.state.db, input)
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "notes_template_update" => {
+            let input: NoteTemplateUpdateInput = parse_payload(payload)?;
+            let res = crate::notes::components::templates::template_update(&ctx.state.db, input)
+                .await
+                .map_err(handler_err)?;
+            into_value(res)
+        }
+        "notes_template_delete" => {
+            #[derive(Deserialize)]
+            struct Input {
+                id: i64,
+            }
+            let input: Input = parse_payload(payload)?;
+            crate::notes::components::templates::template_delete(&ctx.state.db, input.id)
+                .await
+                .map_err(handler_err)?;
+            into_value("ok")
+        }
 
         // Writing drafts (TipTap JSON)
         "writing_create" => {
@@ -1494,7 +2718,25 @@ pub async fn dispatch(
                 "book" => Some(WritingType::Book),
                 _ => None,
             });
+            let (limit, offset) = input.limit_offset();
             let res = crate::writing::service::list_writings(
+                &ctx.state.db,
+                status.clone(),
+                writing_type.clone(),
+                input.series_name.clone(),
+                input.is_pinned,
+                input.is_featured,
+                limit,
+                Some(offset),
+            )
+            .await
+            .map_err(handler_err)?;
+            let items: Vec<WritingDraftDto> =
+                res.into_iter().map(writing_model_to_draft_dto).collect();
+            if input.envelope != Some(true) {
+                return into_value(Listing::Items(items));
+            }
+            let total = crate::writing::service::count_writings(
                 &ctx.state.db,
                 status,
                 writing_type,
@@ -1504,11 +2746,12 @@ pub async fn dispatch(
             )
             .await
             .map_err(handler_err)?;
-            into_value(
-                res.into_iter()
-                    .map(writing_model_to_draft_dto)
-                    .collect::<Vec<_>>(),
-            )
+            into_value(Listing::Page(Page::new(
+                items,
+                total,
+                limit.unwrap_or(total),
+                offset,
+            )))
         }
         "writing_update_meta" => {
             let input: UpdateWritingDraftMetaInput = parse_payload(payload)?;
@@ -1556,6 +2799,13 @@ pub async fn dispatch(
             )
             .await
             .map_err(handler_err)?;
+            crate::writing::git::commit_writing(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &res,
+                WritingChange::SaveDraft,
+            )
+            .await;
             into_value(writing_model_to_draft_dto(res))
         }
         "writing_publish" => {
@@ -1563,6 +2813,13 @@ pub async fn dispatch(
             let res = crate::writing::service::publish_writing(&ctx.state.db, input.writing_id)
                 .await
                 .map_err(handler_err)?;
+            crate::writing::git::commit_writing(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &res,
+                WritingChange::Publish,
+            )
+            .await;
             into_value(writing_model_to_draft_dto(res))
         }
         "writing_link_idea" => {
@@ -1591,6 +2848,114 @@ pub async fn dispatch(
                 .map_err(handler_err)?;
             into_value(res.into_iter().map(|link| link.idea_id).collect::<Vec<_>>())
         }
+        "export_writings_markdown" => {
+            let input: ExportWritingsMarkdownInput = parse_payload(payload)?;
+            let res = crate::writing::export::export_writings_markdown(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "writing_export_epub" => {
+            let input: ExportEpubInput = parse_payload(payload)?;
+            let res = crate::writing::export::export_writing_epub(
+                &ctx.state.db,
+                &ctx.state.http_clients.reader,
+                &ctx.state.config.current().storage,
+                &input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "writing_export_pdf" => {
+            let input: ExportPdfInput = parse_payload(payload)?;
+            let res = crate::writing::export::export_writing_pdf(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "writing_export_docx" => {
+            let input: ExportDocxInput = parse_payload(payload)?;
+            let res = crate::writing::export::export_writing_docx(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "writing_import_obsidian" => {
+            let input: ImportObsidianInput = parse_payload(payload)?;
+            let vault_path = import_source(ctx, "writing", input.vault_path, input.upload_handle)?;
+            let res = crate::writing::import::import_obsidian_vault(
+                &ctx.state.db,
+                std::path::Path::new(&vault_path),
+                &input.notes_folders,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "writing_import_notion" => {
+            let input: ImportNotionInput = parse_payload(payload)?;
+            let import_path =
+                import_source(ctx, "writing", input.import_path, input.upload_handle)?;
+            let res = crate::writing::import::import_notion_export(
+                &ctx.state.db,
+                std::path::Path::new(&import_path),
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "writing_sync_folder" => {
+            let input: SyncFolderInput = parse_payload(payload)?;
+            let res = crate::writing::sync::sync_writings_folder(
+                &ctx.state.db,
+                &ctx.state.config.current().storage,
+                &input,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+
+        // Newsletter
+        "newsletter_send_writing" => {
+            let input: SendWritingNewsletterInput = parse_payload(payload)?;
+            let res = crate::writing::components::newsletter::send_writing_newsletter_handler(
+                input, &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
+        "newsletter_send_digest" => {
+            let input: SendNewsDigestInput = parse_payload(payload)?;
+            let res =
+                crate::writing::components::newsletter::send_news_digest_handler(input, &ctx.state)
+                    .await
+                    .map_err(handler_err)?;
+            into_value(res)
+        }
+        "newsletter_list_sends" => {
+            let input: ListNewsletterSendsInput = parse_payload(payload)?;
+            let res = crate::writing::components::newsletter::list_newsletter_sends_handler(
+                input, &ctx.state,
+            )
+            .await
+            .map_err(handler_err)?;
+            into_value(res)
+        }
 
         _ => Err(ApiError::BadRequest(format!(
             "Unknown command: {}",
@@ -1599,6 +2964,19 @@ pub async fn dispatch(
     }
 }
 
+/// Emit `news_alert` events for articles flagged by a manual sync
+async fn emit_news_alerts(ctx: &BridgeContext, since: chrono::DateTime<chrono::Utc>) {
+    if let Err(e) = crate::research::components::feed::emit_news_alerts_since(
+        ctx.emitter.as_ref(),
+        &ctx.state.db,
+        since,
+    )
+    .await
+    {
+        tracing::error!(target: "news_sync", "Failed to emit news alerts: {}", e);
+    }
+}
+
 fn writing_model_to_draft_dto(w: writings::Model) -> WritingDraftDto {
     let content_json = serde_json::from_str(&w.content_markdown)
         .unwrap_or(serde_json::json!({"type": "doc", "content": []}));
@@ -1624,3 +3002,21 @@ fn writing_model_to_draft_dto(w: writings::Model) -> WritingDraftDto {
         published_at: w.published_at.map(|dt| dt.to_rfc3339()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_err_keeps_app_error_code() {
+        let err = handler_err(AppError::validation("urls", "At least one URL is required"));
+        let body = err.to_response();
+        assert_eq!(body.code, "E7001");
+        assert_eq!(body.status, 400);
+        assert!(!body.retryable);
+
+        let body = handler_err("boom".to_string()).to_response();
+        assert_eq!(body.code, "E9999");
+        assert_eq!(body.status, 500);
+    }
+}
@@ -1,19 +1,385 @@
-use super::dispatch::{
-    dispatch, ApiError, BridgeContext, CommandRequest, CommandResponse, ErrorResponse,
-};
+use super::auth::require_token;
+use super::dispatch::{dispatch, ApiError, BridgeContext, CommandRequest, CommandResponse};
+use super::jobs::{event_visible, job_event_stream, start_job, visible, JobDto};
+use super::metrics;
+use super::request_id::request_context;
+use super::rest;
+use super::schema::bridge_schema;
+use super::scopes::{authorize, current_grant, require, with_grant, Access};
+use crate::core::components::events::EventMessage;
+use crate::core::components::logging::{subscribe_logs, LogStreamFilter};
+use crate::core::components::storage::LogEntry;
+use crate::core::components::storage::{stage_upload, UploadDto};
+use crate::research::components::reader_archive::{archived_page_path, ARCHIVE_CSP};
+use crate::research::components::reader_media::{content_type_for, resolve_media_path};
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    routing::post,
+    body::Body,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        DefaultBodyLimit, Multipart, Path, Query, State,
+    },
+    http::{header, StatusCode},
+    middleware,
+    response::{
+        sse::{KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, post},
     Json, Router,
 };
+use serde::Deserialize;
 use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::io::ReaderStream;
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tracing::{debug, warn};
+
+/// Largest file `POST /upload` accepts
+const MAX_UPLOAD_BYTES: usize = 512 * 1024 * 1024;
 
 pub fn router(ctx: BridgeContext) -> Router {
-    Router::new()
+    let compression = compression_layer(ctx.state.config.current().http.compression_min_bytes);
+    let protected = Router::new()
         .route("/api/command", post(handle_command))
+        .route("/api/jobs", get(list_jobs).post(handle_start_job))
+        .route("/api/jobs/:job_id", get(get_job))
+        .route("/api/jobs/:job_id/events", get(job_events))
+        .route("/ws", get(handle_ws))
+        .route("/ws/logs", get(handle_log_ws))
+        .route("/metrics", get(get_metrics))
+        .route(
+            "/upload",
+            post(handle_upload).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)),
+        )
+        .route("/files/backups/:name", get(download_backup))
+        .route("/files/exports/:name", get(download_export))
+        .route("/media/*path", get(serve_media))
+        .route("/reader/archive/:snapshot_id", get(serve_reader_archive))
+        .merge(rest::router())
+        .route_layer(middleware::from_fn_with_state(ctx.clone(), require_token));
+    Router::new()
+        .route("/schema", get(get_schema))
+        .merge(protected)
         .with_state(ctx)
+        .layer(compression)
+        .layer(middleware::from_fn(request_context))
+}
+
+/// gzip/brotli for responses above the size threshold; SSE streams stay
+/// uncompressed so events aren't held back in the encoder
+fn compression_layer(min_bytes: u16) -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(min_bytes)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::SSE);
+    CompressionLayer::new().compress_when(predicate)
+}
+
+/// JSON Schema of every command's payload and response
+async fn get_schema() -> Json<serde_json::Value> {
+    Json(bridge_schema().clone())
+}
+
+/// Prometheus scrape endpoint
+async fn get_metrics(State(ctx): State<BridgeContext>) -> Response {
+    if let Err(e) = require("core", Access::Read) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    let body = metrics::render(&ctx).await;
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response()
+}
+
+/// Serve a file from the media dir; the CSP keeps archived HTML inert
+fn media_file_response(file: &std::path::Path) -> Response {
+    match std::fs::read(file) {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, content_type_for(file)),
+                (header::CONTENT_SECURITY_POLICY, ARCHIVE_CSP),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Serve archived reader media (snapshot images) from the storage media dir
+async fn serve_media(State(ctx): State<BridgeContext>, Path(path): Path<String>) -> Response {
+    if let Err(e) = require("research", Access::Read) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    let state = ctx.workspaces.active();
+    match resolve_media_path(&state.config.current().storage.media_dir, &path) {
+        Some(file) => media_file_response(&file),
+        None => StatusCode::BAD_REQUEST.into_response(),
+    }
+}
+
+/// A file directly inside `dir`; `None` if `name` is anything but a plain
+/// file name
+fn stored_file_path(dir: &std::path::Path, name: &str) -> Option<std::path::PathBuf> {
+    let mut components = std::path::Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(std::path::Component::Normal(_)), None) => Some(dir.join(name)),
+        _ => None,
+    }
+}
+
+/// Stream a backup or export file as an attachment
+async fn download_file(dir: &std::path::Path, name: &str) -> Response {
+    let Some(path) = stored_file_path(dir, name) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let metadata = match file.metadata().await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let disposition = format!("attachment; filename=\"{}\"", name.replace('"', ""));
+    (
+        [
+            (header::CONTENT_TYPE, "application/octet-stream".to_string()),
+            (header::CONTENT_LENGTH, metadata.len().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response()
+}
+
+async fn download_backup(State(ctx): State<BridgeContext>, Path(name): Path<String>) -> Response {
+    if let Err(e) = require("core", Access::Admin) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    // Each workspace keeps its backups apart
+    let state = ctx.workspaces.active();
+    download_file(&state.config.current().storage.backup_dir, &name).await
+}
+
+async fn download_export(State(ctx): State<BridgeContext>, Path(name): Path<String>) -> Response {
+    if let Err(e) = require("core", Access::Admin) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    let state = ctx.workspaces.active();
+    download_file(&state.config.current().storage.export_dir, &name).await
+}
+
+/// Serve a snapshot's full HTML archive
+async fn serve_reader_archive(
+    State(ctx): State<BridgeContext>,
+    Path(snapshot_id): Path<i64>,
+) -> Response {
+    if let Err(e) = require("research", Access::Read) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    let state = ctx.workspaces.active();
+    match archived_page_path(
+        &state.db,
+        &state.config.current().storage.media_dir,
+        snapshot_id,
+    )
+    .await
+    {
+        Ok(file) => media_file_response(&file),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Sent to a `/ws` client that fell too far behind and missed events
+const LAGGED_EVENT: &str = "events_lagged";
+
+#[derive(Debug, Deserialize)]
+struct WsQuery {
+    /// Comma-separated event names; all events when absent
+    events: Option<String>,
+}
+
+/// Live event stream: each emitted event is sent as a JSON text frame
+async fn handle_ws(
+    State(ctx): State<BridgeContext>,
+    Query(query): Query<WsQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if let Err(e) = require("core", Access::Read) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    let filter: Option<Vec<String>> = query.events.map(|events| {
+        events
+            .split(',')
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+            .collect()
+    });
+    // The socket task loses the request's task-locals; keep its grant so
+    // job events are filtered by it
+    let grant = current_grant();
+    ws.on_upgrade(move |socket| with_grant(grant, forward_events(socket, ctx, filter)))
+}
+
+fn wants_event(filter: Option<&[String]>, event: &str) -> bool {
+    event == LAGGED_EVENT || filter.map_or(true, |f| f.iter().any(|e| e == event))
+}
+
+async fn forward_events(mut socket: WebSocket, ctx: BridgeContext, filter: Option<Vec<String>>) {
+    let mut events = ctx.events.subscribe();
+    debug!(target: "api", "WebSocket client subscribed");
+    loop {
+        tokio::select! {
+            received = events.recv() => {
+                let message = match received {
+                    Ok(message) => message,
+                    // Tell slow clients they missed events so they can refetch
+                    Err(RecvError::Lagged(skipped)) => EventMessage {
+                        event: LAGGED_EVENT.to_string(),
+                        payload: json!({ "skipped": skipped }),
+                        emitted_at: chrono::Utc::now().to_rfc3339(),
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                if !wants_event(filter.as_deref(), &message.event) || !event_visible(&message) {
+                    continue;
+                }
+                let text = match serde_json::to_string(&message) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        warn!(target: "api", "Failed to serialize event {}: {}", message.event, e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum; other client messages are ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    debug!(target: "api", "WebSocket client disconnected");
+}
+
+#[derive(Debug, Deserialize)]
+struct LogStreamQuery {
+    /// Minimum level (`error`, `warn`, `info`, `debug`, `trace`)
+    level: Option<String>,
+    /// Comma-separated target prefixes; all targets when absent
+    target: Option<String>,
+}
+
+/// Live app log: each entry is sent as a JSON text frame shaped like
+/// `get_application_logs` results
+async fn handle_log_ws(Query(query): Query<LogStreamQuery>, ws: WebSocketUpgrade) -> Response {
+    if let Err(e) = require("core", Access::Read) {
+        return ApiErrorWrapper(e).into_response();
+    }
+    match LogStreamFilter::parse(query.level.as_deref(), query.target.as_deref()) {
+        Ok(filter) => ws.on_upgrade(move |socket| forward_logs(socket, filter)),
+        Err(e) => ApiErrorWrapper(ApiError::BadRequest(e)).into_response(),
+    }
+}
+
+async fn forward_logs(mut socket: WebSocket, filter: LogStreamFilter) {
+    let mut logs = subscribe_logs();
+    loop {
+        tokio::select! {
+            received = logs.recv() => {
+                let entry = match received {
+                    Ok(entry) if filter.matches(&entry) => entry,
+                    Ok(_) => continue,
+                    // Tell slow clients how much they missed
+                    Err(RecvError::Lagged(skipped)) => LogEntry {
+                        timestamp: chrono::Utc::now().to_rfc3339(),
+                        level: "WARN".to_string(),
+                        target: "api".to_string(),
+                        message: format!("Log stream skipped {} entries", skipped),
+                        fields: None,
+                    },
+                    Err(RecvError::Closed) => break,
+                };
+                let Ok(text) = serde_json::to_string(&entry) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Stage the multipart `file` field for an import command
+async fn handle_upload(
+    State(ctx): State<BridgeContext>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<UploadDto>), ApiErrorWrapper> {
+    require("core", Access::Write).map_err(ApiErrorWrapper)?;
+    let bad_request = |e: axum::extract::multipart::MultipartError| {
+        ApiErrorWrapper(ApiError::BadRequest(e.body_text()))
+    };
+    while let Some(field) = multipart.next_field().await.map_err(bad_request)? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or("upload").to_string();
+        let state = ctx.workspaces.active();
+        let upload = stage_upload(
+            &state.config.current().storage.import_dir,
+            &file_name,
+            field,
+        )
+        .await
+        .map_err(|e| ApiErrorWrapper(ApiError::App(e)))?;
+        return Ok((StatusCode::CREATED, Json(upload)));
+    }
+    Err(ApiErrorWrapper(ApiError::BadRequest(
+        "multipart field `file` is required".into(),
+    )))
+}
+
+/// Run a command in the background; poll the job or follow its events
+async fn handle_start_job(
+    State(ctx): State<BridgeContext>,
+    Json(req): Json<CommandRequest>,
+) -> Result<(StatusCode, Json<JobDto>), ApiErrorWrapper> {
+    authorize(&req.command).map_err(ApiErrorWrapper)?;
+    let job = start_job(&ctx, req.command, req.payload);
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Jobs the caller's token could have started
+async fn list_jobs(State(ctx): State<BridgeContext>) -> Json<Vec<JobDto>> {
+    Json(ctx.jobs.list().into_iter().filter(visible).collect())
+}
+
+async fn get_job(State(ctx): State<BridgeContext>, Path(job_id): Path<String>) -> Response {
+    match ctx.jobs.get(&job_id) {
+        Some(job) if visible(&job) => Json(job).into_response(),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Server-Sent Events for one job, ending once it finishes
+async fn job_events(State(ctx): State<BridgeContext>, Path(job_id): Path<String>) -> Response {
+    if !ctx.jobs.get(&job_id).is_some_and(|job| visible(&job)) {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    match job_event_stream(&ctx, job_id) {
+        Some(stream) => Sse::new(stream)
+            .keep_alive(KeepAlive::default())
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
 }
 
 async fn handle_command(
@@ -29,15 +395,9 @@ pub struct ApiErrorWrapper(pub ApiError);
 
 impl IntoResponse for ApiErrorWrapper {
     fn into_response(self) -> Response {
-        let status = match self.0 {
-            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
-            ApiError::Handler(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            ApiError::Serde(_) => StatusCode::BAD_REQUEST,
-        };
-        let body = Json(json!(ErrorResponse {
-            message: self.0.to_string()
-        }));
-        (status, body).into_response()
+        let body = self.0.to_response();
+        let status = StatusCode::from_u16(body.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(body)).into_response()
     }
 }
 
@@ -0,0 +1,304 @@
+//! Background jobs for long-running bridge commands
+//!
+//! `POST /api/jobs` runs any command in the background and answers at once
+//! with a job id, instead of holding the request open for minutes. Lifecycle
+//! and progress go out as `job_started`, `job_progress` and `job_finished`
+//! events (so `/ws` clients see them too), and `GET /api/jobs/:id/events`
+//! streams a single job as Server-Sent Events. A token only sees the jobs
+//! whose command it is allowed to run.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use axum::response::sse::Event;
+use chrono::{Duration, Utc};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn, Instrument};
+
+use super::dispatch::{dispatch, BridgeContext};
+use super::scopes::{authorize, current_grant, with_grant};
+use crate::core::components::events::{BroadcastEventEmitter, EventMessage};
+
+pub const JOB_STARTED_EVENT: &str = "job_started";
+pub const JOB_PROGRESS_EVENT: &str = "job_progress";
+pub const JOB_FINISHED_EVENT: &str = "job_finished";
+
+/// Finished jobs stay queryable this long
+const FINISHED_JOB_TTL_MINUTES: i64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    pub done: u64,
+    pub total: Option<u64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobDto {
+    pub id: String,
+    pub command: String,
+    /// "running", "succeeded" or "failed"
+    pub status: String,
+    pub progress: Option<JobProgress>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// In-memory job table shared by the bridge handlers
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<String, JobDto>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, id: &str) -> Option<JobDto> {
+        self.jobs.lock().ok()?.get(id).cloned()
+    }
+
+    /// Jobs still running or recently finished, newest first
+    pub fn list(&self) -> Vec<JobDto> {
+        let Ok(jobs) = self.jobs.lock() else {
+            return Vec::new();
+        };
+        let mut list: Vec<JobDto> = jobs.values().cloned().collect();
+        list.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        list
+    }
+
+    fn insert(&self, job: JobDto) {
+        let Ok(mut jobs) = self.jobs.lock() else {
+            return;
+        };
+        let cutoff = (Utc::now() - Duration::minutes(FINISHED_JOB_TTL_MINUTES)).to_rfc3339();
+        jobs.retain(|_, j| j.finished_at.as_ref().map_or(true, |f| *f > cutoff));
+        jobs.insert(job.id.clone(), job);
+    }
+
+    fn update(&self, id: &str, apply: impl FnOnce(&mut JobDto)) -> Option<JobDto> {
+        let mut jobs = self.jobs.lock().ok()?;
+        let job = jobs.get_mut(id)?;
+        apply(job);
+        Some(job.clone())
+    }
+}
+
+#[derive(Clone)]
+struct JobHandle {
+    id: String,
+    registry: JobRegistry,
+    events: BroadcastEventEmitter,
+}
+
+impl JobHandle {
+    fn publish(&self, event: &str, job: Option<JobDto>) {
+        if let Some(job) = job {
+            if let Err(e) = self.events.publish(event, job) {
+                warn!(target: "api", job_id = %self.id, "Failed to emit job event: {}", e);
+            }
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_JOB: JobHandle;
+}
+
+/// Report progress for the job running the current command
+///
+/// Does nothing when the command was called directly rather than as a job.
+pub fn report_progress(done: u64, total: Option<u64>, message: Option<String>) {
+    let _ = CURRENT_JOB.try_with(|handle| {
+        let job = handle.registry.update(&handle.id, |job| {
+            job.progress = Some(JobProgress {
+                done,
+                total,
+                message,
+            });
+        });
+        handle.publish(JOB_PROGRESS_EVENT, job);
+    });
+}
+
+/// Start a command in the background and return its job record
+pub fn start_job(ctx: &BridgeContext, command: String, payload: Option<Value>) -> JobDto {
+    let id = hex::encode(rand::random::<[u8; 8]>());
+    let job = JobDto {
+        id: id.clone(),
+        command: command.clone(),
+        status: "running".to_string(),
+        progress: None,
+        result: None,
+        error: None,
+        started_at: Utc::now().to_rfc3339(),
+        finished_at: None,
+    };
+    ctx.jobs.insert(job.clone());
+
+    let handle = JobHandle {
+        id,
+        registry: ctx.jobs.clone(),
+        events: ctx.events.clone(),
+    };
+    handle.publish(JOB_STARTED_EVENT, Some(job.clone()));
+    info!(target: "api", job_id = %handle.id, command = %command, "Job started");
+
+    let ctx = ctx.clone();
+    let grant = current_grant();
+    let run = CURRENT_JOB.scope(handle.clone(), async move {
+        // The spawned task loses the request's task-locals; keep its grant
+        let outcome = with_grant(grant, dispatch(&command, payload, &ctx)).await;
+        let finished = handle.registry.update(&handle.id, |job| {
+            job.finished_at = Some(Utc::now().to_rfc3339());
+            match outcome {
+                Ok(result) => {
+                    job.status = "succeeded".to_string();
+                    job.result = Some(result);
+                }
+                Err(e) => {
+                    job.status = "failed".to_string();
+                    job.error = Some(e.to_string());
+                }
+            }
+        });
+        if let Some(job) = &finished {
+            info!(target: "api", job_id = %job.id, status = %job.status, "Job finished");
+        }
+        handle.publish(JOB_FINISHED_EVENT, finished);
+    });
+    // Keep the starting request's span so job logs share its request id
+    tokio::spawn(run.in_current_span());
+
+    job
+}
+
+/// Whether the current request's grant could have started `job`; other
+/// callers are told it doesn't exist
+pub fn visible(job: &JobDto) -> bool {
+    authorize(&job.command).is_ok()
+}
+
+/// Whether a `job_*` event may go to the current request; other events
+/// always may
+pub fn event_visible(message: &EventMessage) -> bool {
+    if !matches!(
+        message.event.as_str(),
+        JOB_STARTED_EVENT | JOB_PROGRESS_EVENT | JOB_FINISHED_EVENT
+    ) {
+        return true;
+    }
+    message
+        .payload
+        .get("command")
+        .and_then(Value::as_str)
+        .is_some_and(|command| authorize(command).is_ok())
+}
+
+fn job_event(event: &str, job: &JobDto) -> Event {
+    Event::default()
+        .event(event)
+        .json_data(job)
+        .unwrap_or_else(|_| Event::default().event(event))
+}
+
+fn is_event_for(message: &EventMessage, job_id: &str) -> bool {
+    matches!(
+        message.event.as_str(),
+        JOB_STARTED_EVENT | JOB_PROGRESS_EVENT | JOB_FINISHED_EVENT
+    ) && message.payload.get("id").and_then(Value::as_str) == Some(job_id)
+}
+
+/// SSE stream for one job: its current state, then each update until it
+/// finishes. `None` when the job is unknown.
+pub fn job_event_stream(
+    ctx: &BridgeContext,
+    job_id: String,
+) -> Option<impl Stream<Item = Result<Event, Infallible>> + Send + 'static> {
+    // Subscribe before reading the snapshot so no update falls in between
+    let receiver = ctx.events.subscribe();
+    let job = ctx.jobs.get(&job_id)?;
+    let finished = job.finished_at.is_some();
+    let snapshot_event = if finished {
+        JOB_FINISHED_EVENT
+    } else {
+        JOB_PROGRESS_EVENT
+    };
+    let snapshot = stream::once(async move { Ok(job_event(snapshot_event, &job)) });
+
+    let registry = ctx.jobs.clone();
+    let updates = stream::unfold((receiver, finished), move |(mut receiver, done)| {
+        let job_id = job_id.clone();
+        let registry = registry.clone();
+        async move {
+            if done {
+                return None;
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(message) if is_event_for(&message, &job_id) => {
+                        let done = message.event == JOB_FINISHED_EVENT;
+                        let event = Event::default()
+                            .event(message.event.as_str())
+                            .json_data(&message.payload)
+                            .unwrap_or_else(|_| Event::default().event(message.event.as_str()));
+                        return Some((Ok(event), (receiver, done)));
+                    }
+                    Ok(_) => {}
+                    // Missed updates: resend the current state instead
+                    Err(RecvError::Lagged(_)) => {
+                        let job = registry.get(&job_id)?;
+                        let done = job.finished_at.is_some();
+                        let event = if done {
+                            JOB_FINISHED_EVENT
+                        } else {
+                            JOB_PROGRESS_EVENT
+                        };
+                        return Some((Ok(job_event(event, &job)), (receiver, done)));
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }
+    });
+
+    Some(snapshot.chain(updates))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::scopes::Grant;
+    use serde_json::json;
+
+    fn sent(event: &str, command: &str) -> bool {
+        event_visible(&EventMessage {
+            event: event.to_string(),
+            payload: json!({ "id": "1", "command": command, "result": "secret" }),
+            emitted_at: Utc::now().to_rfc3339(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_job_events_follow_the_grant() {
+        let grant = Grant::scoped("token", &["research:read".to_string()]);
+        with_grant(Some(grant), async {
+            assert!(sent(JOB_FINISHED_EVENT, "list_news_articles"));
+            assert!(!sent(JOB_FINISHED_EVENT, "reveal_setting"));
+            assert!(!sent(JOB_STARTED_EVENT, "list_ideas"));
+            assert!(sent("idea_reminder", "reveal_setting"));
+        })
+        .await;
+        assert!(sent(JOB_FINISHED_EVENT, "reveal_setting"));
+    }
+}
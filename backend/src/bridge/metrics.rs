@@ -0,0 +1,389 @@
+//! Metrics for the bridge and the app behind it
+//!
+//! `dispatch` records every known command's latency and outcome here. The
+//! numbers are in-memory since startup; `get_command_metrics` returns them as
+//! JSON. `GET /metrics` serves them in Prometheus text format together with
+//! scheduler, feed and database metrics read from the database at scrape
+//! time, so they survive restarts.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, QueryResult, Statement};
+use serde::Serialize;
+use tracing::warn;
+
+use super::dispatch::BridgeContext;
+use crate::core::components::errors::AppResult;
+
+/// Upper bounds of the latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+#[derive(Debug, Clone, Default)]
+struct CommandStats {
+    count: u64,
+    errors: u64,
+    total_seconds: f64,
+    max_seconds: f64,
+    /// Invocations per bucket (not cumulative); the last slot is `+Inf`
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyBucketDto {
+    /// Upper bound in milliseconds; `None` for the overflow bucket
+    pub le_ms: Option<f64>,
+    /// Invocations at or below `le_ms` (cumulative)
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetricsDto {
+    pub command: String,
+    pub count: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    pub buckets: Vec<LatencyBucketDto>,
+}
+
+#[derive(Clone, Default)]
+pub struct CommandMetrics {
+    commands: Arc<Mutex<BTreeMap<String, CommandStats>>>,
+}
+
+impl CommandMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, command: &str, elapsed: Duration, ok: bool) {
+        let Ok(mut commands) = self.commands.lock() else {
+            return;
+        };
+        let seconds = elapsed.as_secs_f64();
+        let stats = commands.entry(command.to_string()).or_default();
+        stats.count += 1;
+        if !ok {
+            stats.errors += 1;
+        }
+        stats.total_seconds += seconds;
+        stats.max_seconds = stats.max_seconds.max(seconds);
+        let bucket = LATENCY_BUCKETS
+            .iter()
+            .position(|le| seconds <= *le)
+            .unwrap_or(LATENCY_BUCKETS.len());
+        stats.buckets[bucket] += 1;
+    }
+
+    /// Metrics of every command called so far, by name
+    pub fn snapshot(&self) -> Vec<CommandMetricsDto> {
+        let Ok(commands) = self.commands.lock() else {
+            return Vec::new();
+        };
+        commands
+            .iter()
+            .map(|(command, stats)| {
+                let bounds = LATENCY_BUCKETS.iter().map(|le| Some(le * 1000.0));
+                let buckets = bounds
+                    .chain(std::iter::once(None))
+                    .zip(cumulative(&stats.buckets))
+                    .map(|(le_ms, count)| LatencyBucketDto { le_ms, count })
+                    .collect();
+                CommandMetricsDto {
+                    command: command.clone(),
+                    count: stats.count,
+                    errors: stats.errors,
+                    error_rate: ratio(stats.errors as f64, stats.count),
+                    mean_ms: ratio(stats.total_seconds * 1000.0, stats.count),
+                    max_ms: stats.max_seconds * 1000.0,
+                    buckets,
+                }
+            })
+            .collect()
+    }
+
+    /// Append the command metrics in Prometheus text format
+    pub fn write_prometheus(&self, out: &mut String) {
+        let Ok(commands) = self.commands.lock() else {
+            return;
+        };
+        let invocations = "cockpit_command_invocations_total";
+        family(out, invocations, "counter", "Bridge command invocations");
+        for (command, stats) in commands.iter() {
+            sample(out, invocations, &command_label(command), stats.count);
+        }
+        let errors = "cockpit_command_errors_total";
+        family(
+            out,
+            errors,
+            "counter",
+            "Bridge command invocations that failed",
+        );
+        for (command, stats) in commands.iter() {
+            sample(out, errors, &command_label(command), stats.errors);
+        }
+        let duration = "cockpit_command_duration_seconds";
+        family(out, duration, "histogram", "Bridge command latency");
+        for (command, stats) in commands.iter() {
+            let label = command_label(command);
+            let bounds = LATENCY_BUCKETS.iter().map(|le| le.to_string());
+            let bounds = bounds.chain(std::iter::once("+Inf".to_string()));
+            for (le, count) in bounds.zip(cumulative(&stats.buckets)) {
+                let labels = format!("{label},le=\"{le}\"");
+                sample(out, &format!("{duration}_bucket"), &labels, count);
+            }
+            sample(out, &format!("{duration}_sum"), &label, stats.total_seconds);
+            sample(out, &format!("{duration}_count"), &label, stats.count);
+        }
+    }
+}
+
+/// Everything `GET /metrics` exposes, in Prometheus text format
+pub async fn render(ctx: &BridgeContext) -> String {
+    let mut out = String::new();
+    ctx.metrics.write_prometheus(&mut out);
+    write_job_metrics(ctx, &mut out);
+
+    let running = ctx.state.running.lock().await.len();
+    let name = "cockpit_scheduler_tasks_running";
+    family(&mut out, name, "gauge", "Scheduled tasks currently running");
+    sample(&mut out, name, "", running);
+
+    let db = &ctx.state.db;
+    if let Err(e) = write_db_metrics(db, &mut out).await {
+        warn!(target: "api", "Failed to collect database metrics: {}", e);
+    }
+    out
+}
+
+fn write_job_metrics(ctx: &BridgeContext, out: &mut String) {
+    let mut by_status: BTreeMap<String, u64> = BTreeMap::new();
+    for job in ctx.jobs.list() {
+        *by_status.entry(job.status).or_default() += 1;
+    }
+    let name = "cockpit_bridge_jobs";
+    family(
+        out,
+        name,
+        "gauge",
+        "Background jobs kept in the job registry",
+    );
+    for (status, count) in by_status {
+        sample(out, name, &format!("status={}", quoted(&status)), count);
+    }
+}
+
+async fn query<C: ConnectionTrait>(db: &C, sql: &str) -> AppResult<Vec<QueryResult>> {
+    Ok(db
+        .query_all(Statement::from_string(db.get_database_backend(), sql))
+        .await?)
+}
+
+async fn write_db_metrics<C: ConnectionTrait>(db: &C, out: &mut String) -> AppResult<()> {
+    let postgres = db.get_database_backend() == DatabaseBackend::Postgres;
+    let run_seconds = if postgres {
+        "EXTRACT(EPOCH FROM r.finished_at - r.started_at)::float8"
+    } else {
+        "(julianday(r.finished_at) - julianday(r.started_at)) * 86400.0"
+    };
+    let runs = query(
+        db,
+        &format!(
+            "SELECT t.task_type, r.status, COUNT(*) AS runs, SUM({run_seconds}) AS seconds
+             FROM system_task_runs r JOIN system_tasks t ON t.id = r.task_id
+             GROUP BY t.task_type, r.status
+             ORDER BY t.task_type, r.status"
+        ),
+    )
+    .await?;
+    let total = "cockpit_scheduler_runs_total";
+    family(out, total, "counter", "Recorded scheduled task runs");
+    for row in &runs {
+        let task_type: String = row.try_get("", "task_type")?;
+        let status: String = row.try_get("", "status")?;
+        let labels = format!(
+            "task_type={},status={}",
+            quoted(&task_type),
+            quoted(&status)
+        );
+        sample(out, total, &labels, row.try_get::<i64>("", "runs")?);
+    }
+    let duration = "cockpit_scheduler_run_duration_seconds";
+    family(
+        out,
+        duration,
+        "summary",
+        "Time spent in scheduled task runs",
+    );
+    for row in &runs {
+        let task_type: String = row.try_get("", "task_type")?;
+        let status: String = row.try_get("", "status")?;
+        let labels = format!(
+            "task_type={},status={}",
+            quoted(&task_type),
+            quoted(&status)
+        );
+        let seconds = row.try_get::<Option<f64>>("", "seconds")?.unwrap_or(0.0);
+        sample(out, &format!("{duration}_sum"), &labels, seconds);
+        sample(
+            out,
+            &format!("{duration}_count"),
+            &labels,
+            row.try_get::<i64>("", "runs")?,
+        );
+    }
+
+    let sources = query(
+        db,
+        "SELECT id, name, CAST(article_count AS BIGINT) AS article_count,
+                CAST(error_count AS BIGINT) AS error_count,
+                CAST(api_calls_today AS BIGINT) AS api_calls_today,
+                CAST(api_quota_daily AS BIGINT) AS api_quota_daily
+         FROM feed_sources ORDER BY id",
+    )
+    .await?;
+    let gauges = [
+        (
+            "article_count",
+            "cockpit_feed_source_articles",
+            "Articles synced per feed source",
+        ),
+        (
+            "error_count",
+            "cockpit_feed_source_errors",
+            "Consecutive sync errors per feed source",
+        ),
+        (
+            "api_calls_today",
+            "cockpit_feed_source_api_calls_today",
+            "API calls made today",
+        ),
+        (
+            "api_quota_daily",
+            "cockpit_feed_source_api_quota_daily",
+            "Daily API call quota",
+        ),
+    ];
+    for (column, name, help) in gauges {
+        family(out, name, "gauge", help);
+        for row in &sources {
+            // Sources without a quota have no quota sample
+            let Some(value) = row.try_get::<Option<i64>>("", column)? else {
+                continue;
+            };
+            let id: i64 = row.try_get("", "id")?;
+            let source: String = row.try_get("", "name")?;
+            let labels = format!("source_id=\"{id}\",source={}", quoted(&source));
+            sample(out, name, &labels, value);
+        }
+    }
+
+    let articles = query(db, "SELECT COUNT(*) AS articles FROM news_articles").await?;
+    let name = "cockpit_news_articles";
+    family(out, name, "gauge", "Articles in the news feed");
+    if let Some(row) = articles.first() {
+        sample(out, name, "", row.try_get::<i64>("", "articles")?);
+    }
+
+    let size_sql = if postgres {
+        "SELECT pg_database_size(current_database()) AS bytes"
+    } else {
+        "SELECT page_count * page_size AS bytes FROM pragma_page_count(), pragma_page_size()"
+    };
+    let size = query(db, size_sql).await?;
+    let name = "cockpit_database_size_bytes";
+    family(out, name, "gauge", "Size of the database");
+    if let Some(row) = size.first() {
+        sample(out, name, "", row.try_get::<i64>("", "bytes")?);
+    }
+    Ok(())
+}
+
+/// Quote and escape a label value
+fn quoted(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn command_label(command: &str) -> String {
+    format!("command={}", quoted(command))
+}
+
+/// Write the `# HELP` and `# TYPE` lines that open a metric family
+pub(crate) fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} {kind}");
+}
+
+/// Write one sample; `labels` is the inside of the braces, or empty
+pub(crate) fn sample(out: &mut String, name: &str, labels: &str, value: impl Display) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{name} {value}");
+    } else {
+        let _ = writeln!(out, "{name}{{{labels}}} {value}");
+    }
+}
+
+fn cumulative(buckets: &[u64]) -> Vec<u64> {
+    buckets
+        .iter()
+        .scan(0, |total, count| {
+            *total += count;
+            Some(*total)
+        })
+        .collect()
+}
+
+fn ratio(value: f64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        value / count as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_snapshot() {
+        let metrics = CommandMetrics::new();
+        metrics.record("list_ideas", Duration::from_millis(3), true);
+        metrics.record("list_ideas", Duration::from_millis(40), false);
+        metrics.record("list_ideas", Duration::from_secs(60), true);
+
+        let snapshot = metrics.snapshot();
+        let ideas = &snapshot[0];
+        assert_eq!(ideas.command, "list_ideas");
+        assert_eq!(ideas.count, 3);
+        assert_eq!(ideas.errors, 1);
+        assert_eq!(ideas.buckets[0].count, 1);
+        assert_eq!(ideas.buckets[3].count, 2);
+        let overflow = ideas.buckets.last().unwrap();
+        assert_eq!((overflow.le_ms, overflow.count), (None, 3));
+
+        let mut text = String::new();
+        metrics.write_prometheus(&mut text);
+        assert!(text.contains("cockpit_command_errors_total{command=\"list_ideas\"} 1"));
+        assert!(text.contains(
+            "cockpit_command_duration_seconds_bucket{command=\"list_ideas\",le=\"+Inf\"} 3"
+        ));
+    }
+
+    #[test]
+    fn test_quoted_label() {
+        assert_eq!(quoted("Hacker News"), "\"Hacker News\"");
+        assert_eq!(quoted("a \"b\"\\\n"), r#""a \"b\"\\\n""#);
+    }
+}
@@ -1,2 +1,10 @@
+pub mod audit;
+pub mod auth;
 pub mod dispatch;
 pub mod http;
+pub mod jobs;
+pub mod metrics;
+pub mod request_id;
+pub mod rest;
+pub mod schema;
+pub mod scopes;
@@ -0,0 +1,121 @@
+//! Per-request ids and W3C trace context for bridge calls
+//!
+//! Every HTTP request gets a fresh request id, returned in `x-request-id` and
+//! recorded on the tracing span its handler runs in. An incoming
+//! `traceparent` header is honoured so the frontend's trace id shows up in
+//! backend logs; without one a new trace is started. Error bodies carry the
+//! request id too, so a failure seen in the UI can be found in the logs.
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{info_span, Instrument};
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+pub const TRACEPARENT_HEADER: HeaderName = HeaderName::from_static("traceparent");
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the bridge request being handled, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+#[derive(Debug, PartialEq)]
+struct TraceParent {
+    trace_id: String,
+    flags: String,
+}
+
+/// Parse a `traceparent` header: `00-<trace id>-<parent id>-<flags>`
+fn parse_traceparent(value: &str) -> Option<TraceParent> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    let [version, trace_id, parent_id, flags] = parts.as_slice() else {
+        return None;
+    };
+    let is_hex = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    };
+    let all_zero = |s: &str| s.bytes().all(|b| b == b'0');
+    if !is_hex(version, 2) || *version == "ff" {
+        return None;
+    }
+    if !is_hex(trace_id, 32) || all_zero(trace_id) {
+        return None;
+    }
+    if !is_hex(parent_id, 16) || all_zero(parent_id) || !is_hex(flags, 2) {
+        return None;
+    }
+    Some(TraceParent {
+        trace_id: trace_id.to_string(),
+        flags: flags.to_string(),
+    })
+}
+
+fn incoming_traceparent(headers: &HeaderMap) -> Option<TraceParent> {
+    headers
+        .get(TRACEPARENT_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_traceparent)
+}
+
+/// Middleware: assign the request id, open the request span and echo both
+/// ids back in the response headers
+pub async fn request_context(request: Request, next: Next) -> Response {
+    // The request id doubles as this hop's span id in the outgoing traceparent
+    let request_id = hex::encode(rand::random::<[u8; 8]>());
+    let trace = incoming_traceparent(request.headers()).unwrap_or_else(|| TraceParent {
+        trace_id: hex::encode(rand::random::<[u8; 16]>()),
+        flags: "01".to_string(),
+    });
+
+    let span = info_span!(
+        target: "api",
+        "bridge_request",
+        request_id = %request_id,
+        trace_id = %trace.trace_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+
+    let traceparent = format!("00-{}-{}-{}", trace.trace_id, request_id, trace.flags);
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        headers.insert(REQUEST_ID_HEADER, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&traceparent) {
+        headers.insert(TRACEPARENT_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_traceparent() {
+        let parsed =
+            parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(parsed.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(parsed.flags, "01");
+
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(
+            parse_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-01").is_none());
+        assert!(parse_traceparent("garbage").is_none());
+    }
+}
@@ -0,0 +1,343 @@
+//! REST-style routes over the command dispatcher
+//!
+//! Each route maps a conventional method and path (`GET /articles`,
+//! `POST /writings/:id/publish`) onto a dispatch command, so curl, httpie and
+//! caching proxies work without a command envelope. The payload is built from
+//! the query string, the JSON body and the `:id` path segment; query and path
+//! values are converted to the number or boolean types the command's schema
+//! expects. Responses are the bare command result.
+
+use std::collections::HashMap;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    routing::{on, MethodFilter},
+    Json, Router,
+};
+use serde_json::{json, Map, Value};
+
+use super::dispatch::{dispatch, ApiError, BridgeContext};
+use super::http::ApiErrorWrapper;
+use super::schema::payload_field_types;
+
+pub struct RestRoute {
+    pub method: &'static str,
+    pub path: &'static str,
+    pub command: &'static str,
+    /// Payload key the `:id` path segment is sent as
+    pub id_key: &'static str,
+    /// Payload key the request body is nested under; merged in when `None`
+    pub body_key: Option<&'static str>,
+}
+
+const fn route(method: &'static str, path: &'static str, command: &'static str) -> RestRoute {
+    RestRoute {
+        method,
+        path,
+        command,
+        id_key: "id",
+        body_key: None,
+    }
+}
+
+impl RestRoute {
+    const fn id(self, id_key: &'static str) -> Self {
+        Self { id_key, ..self }
+    }
+
+    const fn body(self, body_key: &'static str) -> Self {
+        Self {
+            body_key: Some(body_key),
+            ..self
+        }
+    }
+}
+
+pub const ROUTES: &[RestRoute] = &[
+    // News articles
+    route("GET", "/articles", "list_news_articles"),
+    route("DELETE", "/articles", "clear_news_articles"),
+    route("GET", "/articles/search", "search_news_articles"),
+    route("GET", "/articles/:id", "get_news_article"),
+    route("POST", "/articles/:id/dismiss", "dismiss_news_article"),
+    route("POST", "/articles/:id/read", "mark_news_article_read"),
+    route("PUT", "/articles/:id/star", "toggle_star_news_article"),
+    // Feed sources
+    route("GET", "/feed-sources", "list_feed_sources"),
+    route("POST", "/feed-sources", "create_feed_source"),
+    route("POST", "/feed-sources/sync", "sync_all_feed_sources"),
+    route("GET", "/feed-sources/:id", "get_feed_source").id("source_id"),
+    route("PATCH", "/feed-sources/:id", "update_feed_source")
+        .id("source_id")
+        .body("input"),
+    route("DELETE", "/feed-sources/:id", "delete_feed_source").id("source_id"),
+    route("PUT", "/feed-sources/:id/enabled", "toggle_feed_source").id("source_id"),
+    route("POST", "/feed-sources/:id/sync", "sync_feed_source_now").id("source_id"),
+    route(
+        "POST",
+        "/feed-sources/:id/test",
+        "test_feed_source_connection",
+    )
+    .id("source_id"),
+    // Alerts, saved searches and mutes
+    route("GET", "/alert-rules", "list_alert_rules"),
+    route("POST", "/alert-rules", "create_alert_rule"),
+    route("POST", "/alert-rules/test", "test_alert_rule"),
+    route("PATCH", "/alert-rules/:id", "update_alert_rule"),
+    route("DELETE", "/alert-rules/:id", "delete_alert_rule"),
+    route("GET", "/saved-searches", "list_saved_searches"),
+    route("POST", "/saved-searches", "create_saved_search"),
+    route("PATCH", "/saved-searches/:id", "update_saved_search"),
+    route("DELETE", "/saved-searches/:id", "delete_saved_search"),
+    route("GET", "/saved-searches/:id/articles", "run_saved_search"),
+    route("GET", "/mute-rules", "list_mute_rules"),
+    route("POST", "/mute-rules", "create_mute_rule"),
+    route("POST", "/mute-rules/preview", "preview_mute_rule"),
+    route("PATCH", "/mute-rules/:id", "update_mute_rule"),
+    route("DELETE", "/mute-rules/:id", "delete_mute_rule"),
+    // Ideas
+    route("GET", "/ideas", "list_ideas"),
+    route("POST", "/ideas", "create_idea"),
+    route("PUT", "/ideas/order", "reorder_ideas"),
+    route("GET", "/ideas/:id", "get_idea"),
+    route("PATCH", "/ideas/:id/metadata", "update_idea_metadata").body("input"),
+    route("PATCH", "/ideas/:id/notes", "update_idea_notes").body("input"),
+    route("PATCH", "/ideas/:id/article", "update_idea_article").body("input"),
+    route("POST", "/ideas/:id/archive", "archive_idea"),
+    route("POST", "/ideas/:id/merge", "merge_ideas").id("source_id"),
+    route("GET", "/ideas/:id/references", "list_idea_references").id("idea_id"),
+    route("GET", "/ideas/:id/writings", "kg_list_writings_for_idea").id("idea_id"),
+    // Knowledge graph
+    route("GET", "/references", "kg_list_references"),
+    route("POST", "/references", "kg_create_reference"),
+    route("GET", "/references/:id", "kg_get_reference"),
+    route("PATCH", "/references/:id", "kg_update_reference").body("input"),
+    route("DELETE", "/references/:id", "kg_delete_reference"),
+    route("GET", "/writings", "kg_list_writings"),
+    route("POST", "/writings", "kg_create_writing"),
+    route("GET", "/writings/:id", "kg_get_writing"),
+    route("PATCH", "/writings/:id", "kg_update_writing").body("input"),
+    route("DELETE", "/writings/:id", "kg_delete_writing"),
+    route("POST", "/writings/:id/publish", "kg_publish_writing"),
+    route("GET", "/writings/:id/ideas", "kg_list_ideas_for_writing").id("writing_id"),
+    route("GET", "/publications", "kg_list_publications"),
+    route("POST", "/publications", "kg_record_publication"),
+    route("POST", "/publications/:id/retry", "kg_retry_publication"),
+    route("GET", "/notes", "kg_list_notes_for_entity"),
+    route("POST", "/notes", "kg_create_note"),
+    route("GET", "/notes/:id", "kg_get_note"),
+    route("PATCH", "/notes/:id", "kg_update_note").body("input"),
+    route("DELETE", "/notes/:id", "kg_delete_note"),
+    // Reader
+    route("POST", "/reader/fetch", "reader_fetch"),
+    route("POST", "/reader/fetch-batch", "reader_fetch_batch"),
+    route("GET", "/reader/continue", "reader_continue_reading"),
+    route("GET", "/reader/watched", "reader_watched_list"),
+    route("GET", "/reader/references/:id", "reader_reference_get").id("reference_id"),
+    route("PATCH", "/reader/references/:id", "reader_reference_update")
+        .id("reference_id")
+        .body("input"),
+    route("POST", "/reader/references/:id/refresh", "reader_refresh").id("referenceId"),
+    route(
+        "PUT",
+        "/reader/references/:id/progress",
+        "update_reading_progress",
+    )
+    .id("referenceId"),
+    route("PUT", "/reader/references/:id/watch", "reader_set_watch").id("referenceId"),
+    route(
+        "GET",
+        "/reader/references/:id/snapshots",
+        "reader_snapshots_list",
+    )
+    .id("reference_id"),
+    route("GET", "/reader/references/:id/clips", "reader_clips_list").id("reference_id"),
+    route("GET", "/reader/snapshots/:id", "reader_snapshot_get").id("snapshot_id"),
+    route("DELETE", "/reader/snapshots/:id", "reader_snapshot_delete").id("snapshot_id"),
+    route("POST", "/reader/clips", "reader_clip_create"),
+    route("PATCH", "/reader/clips/:id", "reader_clip_update").id("clipId"),
+    route("DELETE", "/reader/clips/:id", "reader_clip_delete").id("clip_id"),
+    route("GET", "/reading-queue", "reading_queue_list"),
+    route("POST", "/reading-queue", "reading_queue_add"),
+    route("PUT", "/reading-queue/order", "reading_queue_reorder"),
+    route("POST", "/reading-queue/pop", "reading_queue_pop"),
+    route("DELETE", "/reading-queue/:id", "reading_queue_remove"),
+    route("GET", "/reading-stats", "reading_stats"),
+    // System
+    route("GET", "/settings", "get_app_settings"),
+    route("PUT", "/settings", "update_settings"),
+    route("POST", "/settings/:id/reveal", "reveal_setting").id("key"),
+    route("GET", "/tasks", "list_system_tasks"),
+    route("GET", "/tasks/history", "get_task_history"),
+    route("PATCH", "/tasks/:id", "update_system_task")
+        .id("task_type")
+        .body("input"),
+    route("POST", "/tasks/:id/run", "run_system_task_now").id("task_type"),
+    route("GET", "/tasks/scheduled", "list_scheduled_runs"),
+    route("POST", "/tasks/:id/schedule", "schedule_task_run").id("task_type"),
+    route("DELETE", "/tasks/scheduled/:id", "cancel_scheduled_run"),
+    route("POST", "/scheduler/pause", "pause_scheduler"),
+    route("POST", "/scheduler/resume", "resume_scheduler"),
+    route("GET", "/backups", "list_database_backups"),
+    route("POST", "/backups", "create_database_backup"),
+    route("POST", "/backups/verify", "verify_backup"),
+    route("POST", "/database/maintenance", "run_db_maintenance"),
+    route("GET", "/database/migrations", "get_migration_status"),
+    route(
+        "POST",
+        "/database/migrations/rollback",
+        "rollback_last_migration",
+    ),
+    route("GET", "/database/integrity", "check_database_integrity"),
+    route("POST", "/database/encrypt", "encrypt_database"),
+    route("POST", "/master-key/rotate", "rotate_master_key"),
+    route("GET", "/credentials", "list_credentials"),
+    route("GET", "/profile", "export_profile"),
+    route("POST", "/profile", "import_profile").body("profile"),
+    route("GET", "/workspaces", "list_workspaces"),
+    route("POST", "/workspaces", "create_workspace"),
+    route("POST", "/workspaces/:id/activate", "switch_workspace").id("name"),
+    route("POST", "/config/reload", "reload_config"),
+    route("GET", "/config/doctor", "check_config"),
+];
+
+fn method_filter(method: &str) -> MethodFilter {
+    match method {
+        "POST" => MethodFilter::POST,
+        "PUT" => MethodFilter::PUT,
+        "PATCH" => MethodFilter::PATCH,
+        "DELETE" => MethodFilter::DELETE,
+        _ => MethodFilter::GET,
+    }
+}
+
+/// Method, path and command of each route, for the schema document
+pub fn route_index() -> Vec<Value> {
+    ROUTES
+        .iter()
+        .map(|r| json!({ "method": r.method, "path": r.path, "command": r.command }))
+        .collect()
+}
+
+pub fn router() -> Router<BridgeContext> {
+    ROUTES.iter().fold(Router::new(), |router, rest_route| {
+        router.route(
+            rest_route.path,
+            on(
+                method_filter(rest_route.method),
+                move |State(ctx): State<BridgeContext>,
+                      path: Option<Path<HashMap<String, String>>>,
+                      Query(query): Query<HashMap<String, String>>,
+                      body: Bytes| async move {
+                    let id = path.and_then(|Path(mut params)| params.remove("id"));
+                    let payload = build_payload(rest_route, id, query, &body)?;
+                    let result = dispatch(rest_route.command, Some(payload), &ctx).await?;
+                    Ok::<_, ApiErrorWrapper>(Json(result))
+                },
+            ),
+        )
+    })
+}
+
+/// Convert a query or path string to the type the payload field expects
+fn coerce(command: &str, field: &str, raw: String) -> Value {
+    let types = payload_field_types(command, field);
+    let converted = if types.contains(&"integer") {
+        raw.parse::<i64>().ok().map(Value::from)
+    } else if types.contains(&"number") {
+        raw.parse::<f64>().ok().map(Value::from)
+    } else if types.contains(&"boolean") {
+        raw.parse::<bool>().ok().map(Value::from)
+    } else if types.contains(&"array") {
+        Some(raw.split(',').map(|v| Value::from(v.trim())).collect())
+    } else {
+        None
+    };
+    // Unparseable values go through as strings so the payload error names them
+    converted.unwrap_or(Value::String(raw))
+}
+
+fn build_payload(
+    route: &RestRoute,
+    id: Option<String>,
+    query: HashMap<String, String>,
+    body: &[u8],
+) -> Result<Value, ApiError> {
+    let body: Option<Value> = if body.iter().all(u8::is_ascii_whitespace) {
+        None
+    } else {
+        Some(serde_json::from_slice(body)?)
+    };
+
+    let mut payload = Map::new();
+    for (key, raw) in query {
+        let value = coerce(route.command, &key, raw);
+        payload.insert(key, value);
+    }
+    match (body, route.body_key) {
+        (Some(body), Some(key)) => {
+            payload.insert(key.to_string(), body);
+        }
+        (Some(Value::Object(fields)), None) => payload.extend(fields),
+        // A non-object body (e.g. a list of settings) is the whole payload
+        (Some(body), None) if payload.is_empty() && id.is_none() => return Ok(body),
+        (Some(_), None) => {
+            return Err(ApiError::BadRequest(
+                "request body must be a JSON object".to_string(),
+            ))
+        }
+        (None, _) => {}
+    }
+    if let Some(id) = id {
+        let value = coerce(route.command, route.id_key, id);
+        payload.insert(route.id_key.to_string(), value);
+    }
+    Ok(Value::Object(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::schema::bridge_schema;
+
+    #[test]
+    fn test_build_payload_coerces_by_schema() {
+        let update = ROUTES
+            .iter()
+            .find(|r| r.command == "update_feed_source")
+            .unwrap();
+        let body = br#"{"name": "Renamed"}"#;
+        let payload = build_payload(update, Some("7".into()), HashMap::new(), body).unwrap();
+        assert_eq!(
+            payload,
+            json!({ "source_id": 7, "input": { "name": "Renamed" } })
+        );
+
+        let list = ROUTES
+            .iter()
+            .find(|r| r.command == "list_news_articles")
+            .unwrap();
+        let query = HashMap::from([
+            ("limit".to_string(), "10".to_string()),
+            ("starred".to_string(), "true".to_string()),
+            ("search".to_string(), "2024".to_string()),
+        ]);
+        let payload = build_payload(list, None, query, b"").unwrap();
+        assert_eq!(
+            payload,
+            json!({ "limit": 10, "starred": true, "search": "2024" })
+        );
+    }
+
+    #[test]
+    fn test_routes_target_known_commands() {
+        for route in ROUTES {
+            assert!(
+                !bridge_schema()["commands"][route.command].is_null(),
+                "unknown command {}",
+                route.command
+            );
+        }
+    }
+}
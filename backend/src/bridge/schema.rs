@@ -0,0 +1,590 @@
+//! Machine-readable schema for the bridge commands
+//!
+//! `GET /schema` describes every command `dispatch` accepts as JSON Schema
+//! (draft 7): its payload and its response, generated from the same DTO
+//! structs the handlers use, so clients can be generated from it. Shared
+//! types are listed once under `definitions` and referenced by `$ref`; a
+//! `null` payload means the command takes none.
+//!
+//! New commands need an entry in `command_specs` as well as a dispatch arm;
+//! the test below fails until both lists match.
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+use std::sync::OnceLock;
+
+use super::metrics::CommandMetricsDto;
+use super::rest;
+
+use crate::core::commands::CurrentUser;
+use crate::core::components::audit::{
+    AuditEntryDto, AuditExportDto, AuditLogFilter, ListAuditLogInput,
+};
+use crate::core::components::config::{ConfigDoctorReport, ConfigReload};
+use crate::core::components::credentials::CredentialInfo;
+use crate::core::components::db::encryption::DatabaseEncryptionStatus;
+use crate::core::components::db::integrity::IntegrityReport;
+use crate::core::components::db::maintenance::DbMaintenanceResult;
+use crate::core::components::db::migrations::{MigrationRollback, MigrationStatus};
+use crate::core::components::embeddings::{
+    MoreLikeThisInput, ReindexEmbeddingsInput, ReindexEmbeddingsResult, SemanticSearchHit,
+    SemanticSearchInput,
+};
+use crate::core::components::key_rotation::KeyRotationReport;
+use crate::core::components::pagination::Listing;
+use crate::core::components::profile::{Profile, ProfileImportReport};
+use crate::core::components::settings::{AppSettingsDto, RevealedSetting, UpdateSettingInput};
+use crate::core::components::setup_wizard::{SetupConfig, SetupStatus};
+use crate::core::components::storage::{
+    BackupInfo, BackupVerification, CleanupSummary, CreateBackupInput, ExportInfo, ExportInput,
+    ImportStrategy, ImportSummary, LogEntry, LogStats, StorageStats,
+};
+use crate::core::components::workspaces::WorkspaceDto;
+use crate::notes::components::bundle::EntityBundleDto;
+use crate::notes::components::links::BacklinkDto;
+use crate::notes::components::notes;
+use crate::notes::components::templates::{
+    NoteTemplateCreateInput, NoteTemplateDto, NoteTemplateUpdateInput,
+};
+use crate::research::components::feed::{
+    AlertRuleDto, AlertRuleTestResult, CreateAlertRuleInput, CreateFeedSourceInput,
+    CreateMuteRuleInput, CreateSavedSearchInput, FeedSourceDto, MuteRuleDto, MuteRulePreview,
+    NewsArticleDto, NewsArticleSearchHit, NewsSettingsDto, NewsSourceDto, PreviewMuteRuleInput,
+    SaveNewsSettingsInput, SavedSearchDto, SyncAllResult, SyncSourceResult, TestAlertRuleInput,
+    UpdateAlertRuleInput, UpdateFeedSourceInput, UpdateMuteRuleInput, UpdateSavedSearchInput,
+};
+use crate::research::components::reader::{
+    ClipCreateInput, ClipUpdateInput, ReaderClipDto, ReaderFetchInput, ReaderReferenceDto,
+    ReaderRefreshInput, ReaderResult, ReaderSnapshotDto, ReadingProgressInput,
+    ReferenceSummaryResult, ReferenceUpdateInput, SummarizeReferenceInput,
+};
+use crate::research::components::reader_anchor::{ClipReanchorInput, ClipReanchorResult};
+use crate::research::components::reader_batch::{ReaderFetchBatchInput, ReaderFetchBatchResult};
+use crate::research::components::reader_enex::{ReaderEnexImportResult, ReaderImportEnexInput};
+use crate::research::components::reader_media::ReaderMediaCleanupSummary;
+use crate::research::components::reader_site_rules::{
+    ReaderSiteRuleDto, SiteRuleCreateInput, SiteRuleTestInput, SiteRuleTestResult,
+    SiteRuleUpdateInput,
+};
+use crate::research::components::reader_watch::ReaderWatchInput;
+use crate::research::components::reading_queue::{
+    ReadingQueueAddInput, ReadingQueueItemDto, ReadingQueueReorderInput,
+};
+use crate::research::components::reading_stats::{ReadingStatsDto, ReadingStatsInput};
+use crate::research::components::site_credentials::{SiteCredentialDto, SiteCredentialSaveInput};
+use crate::research::components::tagging::{
+    SuggestTagsBatchInput, SuggestTagsBatchResult, SuggestTagsInput, TagSuggestions,
+};
+use crate::research::dto::{
+    CreateResearchAccountInput, ListResearchItemsQuery, ResearchAccountDto, ResearchItemDto,
+    ResearchStreamDto, UpdateResearchAccountInput, UpsertResearchStreamInput,
+};
+use crate::system::components::scheduler::{
+    RunTaskNowResult, ScheduledRunDto, SchedulerStatusDto, SystemTaskDto, TaskRunDto,
+    UpdateTaskInput,
+};
+use crate::util::commands::{CalendarEvent, FeedItem, ScheduledJobStub};
+use crate::writing::components::ideas::{
+    AddReferenceInput, CreateIdeaForArticleInput, CreateIdeaInput, IdeaDto, IdeaMergeDto,
+    IdeaReferenceDto, ReaderSnapshotInput, ReferenceReaderSnapshotDto, RelatedContentInput,
+    ReorderIdeasInput, UpdateIdeaArticleInput, UpdateIdeaMetadataInput, UpdateIdeaNotesInput,
+    UpdateReferenceNotesInput,
+};
+use crate::writing::components::knowledge_graph::{
+    BibliographyDto, CreateNoteInput, CreateReferenceInput, CreateWritingInput,
+    ExportBibliographyInput, GraphExportDto, GraphFormat, IdeaReferenceLinkDto,
+    LinkIdeaReferenceInput, LinkWritingIdeaInput, NoteDto, RecordPublicationInput, ReferenceDto,
+    UpdateNoteInput, UpdateReferenceInput, UpdateWritingInput, WritingDto, WritingIdeaLinkDto,
+    WritingPublicationDto,
+};
+use crate::writing::components::newsletter::{
+    ListNewsletterSendsInput, NewsletterSendDto, NewsletterSendResult, SendNewsDigestInput,
+    SendWritingNewsletterInput,
+};
+use crate::writing::dto::{
+    CreateWritingDraftInput, EpubExportDto, ExportDocxInput, ExportEpubInput, ExportPdfInput,
+    ExportWritingsMarkdownInput, FolderSyncDto, GetWritingInput, ImportNotionInput,
+    ImportObsidianInput, LinkIdeaInput, ListLinkedIdeasInput, ListWritingsQuery, MarkdownExportDto,
+    NotionImportDto, ObsidianImportDto, PublishWritingInput, SaveDraftInput, SyncFolderInput,
+    UpdateWritingDraftMetaInput, WritingDraftDto,
+};
+
+/// The `"ok"` string returned by commands with nothing else to report
+struct Acknowledged;
+
+impl JsonSchema for Acknowledged {
+    fn schema_name() -> String {
+        "Acknowledged".to_string()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            const_value: Some(json!("ok")),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+struct CommandSpec {
+    name: &'static str,
+    payload: Option<Schema>,
+    response: Schema,
+}
+
+/// `_` for no payload, `(Type)` for a payload type, or `{ field: Type, .. }`
+/// mirroring an inline `Input` struct in `dispatch`
+macro_rules! payload_schema {
+    ($gen:ident, _) => {
+        None
+    };
+    ($gen:ident, { $($field:ident : $ty:ty),* $(,)? }) => {{
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Input {
+            $($field: $ty),*
+        }
+        Some(Input::json_schema($gen))
+    }};
+    ($gen:ident, ($ty:ty)) => {
+        Some($gen.subschema_for::<$ty>())
+    };
+}
+
+macro_rules! command_specs {
+    ($gen:ident, { $($name:literal : $payload:tt => $response:ty),* $(,)? }) => {
+        vec![$(CommandSpec {
+            name: $name,
+            payload: payload_schema!($gen, $payload),
+            response: $gen.subschema_for::<$response>(),
+        }),*]
+    };
+}
+
+/// Every dispatch command, in dispatch order
+fn command_specs(gen: &mut SchemaGenerator) -> Vec<CommandSpec> {
+    command_specs!(gen, {
+        // ---------- Core / Setup ----------
+        "get_current_user": _ => CurrentUser,
+        "get_system_user": _ => String,
+        "log_frontend_error": {
+            message: String,
+            stack: String,
+            component_stack: String,
+            action: Option<String>,
+            metadata: Option<String>,
+            severity: Option<String>,
+            timestamp: String,
+        } => (),
+        "get_app_settings": _ => AppSettingsDto,
+        "update_setting": (UpdateSettingInput) => Acknowledged,
+        "update_settings": (Vec<UpdateSettingInput>) => Acknowledged,
+        "reveal_setting": { key: String } => RevealedSetting,
+        "get_storage_statistics": _ => StorageStats,
+        "create_database_backup": (Option<CreateBackupInput>) => BackupInfo,
+        "restore_database_from_backup": { backup_path: String } => Acknowledged,
+        "list_database_backups": _ => Vec<BackupInfo>,
+        "delete_database_backup": { backup_path: String } => Acknowledged,
+        "verify_backup": { backup_path: String } => BackupVerification,
+        "export_database": (Option<ExportInput>) => ExportInfo,
+        "import_database": {
+            import_path: Option<String>,
+            upload_handle: Option<String>,
+            strategy: Option<ImportStrategy>,
+            dry_run: Option<bool>,
+        } => ImportSummary,
+        "cleanup_logs": { retention_days: Option<i64> } => CleanupSummary,
+        "cleanup_news": { retention_days: Option<i64> } => CleanupSummary,
+        "run_db_maintenance": { vacuum: Option<bool> } => DbMaintenanceResult,
+        "get_migration_status": _ => MigrationStatus,
+        "rollback_last_migration": _ => MigrationRollback,
+        "check_database_integrity": { full: Option<bool> } => IntegrityReport,
+        "encrypt_database": _ => DatabaseEncryptionStatus,
+        "rotate_master_key": _ => KeyRotationReport,
+        "list_credentials": { test: Option<bool> } => Vec<CredentialInfo>,
+        "export_profile": _ => Profile,
+        "import_profile": { profile: Profile } => ProfileImportReport,
+        "list_workspaces": _ => Vec<WorkspaceDto>,
+        "create_workspace": { name: String } => WorkspaceDto,
+        "switch_workspace": { name: String } => WorkspaceDto,
+        "reload_config": _ => ConfigReload,
+        "check_config": _ => ConfigDoctorReport,
+        "get_application_logs": {
+            level_filter: Option<String>,
+            limit: Option<usize>,
+            offset: Option<usize>,
+        } => Vec<LogEntry>,
+        "get_application_log_stats": _ => LogStats,
+        "export_application_logs": { level_filter: Option<String> } => String,
+        "clear_application_logs": _ => CleanupSummary,
+        "check_setup_status_command": _ => SetupStatus,
+        "generate_master_key_command": _ => String,
+        "save_setup_config_command": (SetupConfig) => Acknowledged,
+        "get_mixed_feed": _ => Vec<FeedItem>,
+        "get_upcoming_events": _ => Vec<CalendarEvent>,
+        "list_scheduled_jobs": _ => Vec<ScheduledJobStub>,
+        "sync_calendar": _ => Acknowledged,
+        // Bridge metrics
+        "get_command_metrics": _ => Vec<CommandMetricsDto>,
+        // Audit log
+        "list_audit_log": (Option<ListAuditLogInput>) => Listing<AuditEntryDto>,
+        "export_audit_log": (Option<AuditLogFilter>) => AuditExportDto,
+        // Semantic search
+        "embeddings_reindex": (Option<ReindexEmbeddingsInput>) => ReindexEmbeddingsResult,
+        "semantic_search": (SemanticSearchInput) => Vec<SemanticSearchHit>,
+        "more_like_this": (MoreLikeThisInput) => Vec<SemanticSearchHit>,
+        // ---------- System Scheduler ----------
+        "list_system_tasks": _ => Vec<SystemTaskDto>,
+        "get_task_history": {
+            task_id: Option<i64>,
+            limit: Option<u64>,
+            offset: Option<u64>,
+        } => Vec<TaskRunDto>,
+        "run_system_task_now": { task_type: String, params: Option<Value> } => RunTaskNowResult,
+        "update_system_task": { task_type: String, input: UpdateTaskInput } => SystemTaskDto,
+        "schedule_task_run": {
+            task_type: String,
+            run_at: String,
+            params: Option<Value>,
+        } => ScheduledRunDto,
+        "list_scheduled_runs": _ => Vec<ScheduledRunDto>,
+        "cancel_scheduled_run": { id: i64 } => Acknowledged,
+        "pause_scheduler": _ => SchedulerStatusDto,
+        "resume_scheduler": _ => SchedulerStatusDto,
+        // ---------- Research ----------
+        "get_news_settings": _ => NewsSettingsDto,
+        "save_news_settings": (SaveNewsSettingsInput) => NewsSettingsDto,
+        "list_news_articles": {
+            status: Option<String>,
+            limit: Option<u64>,
+            offset: Option<u64>,
+            include_dismissed: Option<bool>,
+            search: Option<String>,
+            source_id: Option<i64>,
+            starred: Option<bool>,
+            start_date: Option<String>,
+            end_date: Option<String>,
+            sort_by: Option<String>,
+            envelope: Option<bool>,
+        } => Listing<NewsArticleDto>,
+        "search_news_articles": {
+            query: String,
+            limit: Option<u64>,
+            offset: Option<u64>,
+            include_dismissed: Option<bool>,
+        } => Vec<NewsArticleSearchHit>,
+        "get_news_article": { id: i64 } => NewsArticleDto,
+        "clear_news_articles": _ => u64,
+        "dismiss_news_article": { id: i64 } => Acknowledged,
+        "toggle_star_news_article": { id: i64, starred: bool } => Acknowledged,
+        "mark_news_article_read": { id: i64 } => Acknowledged,
+        "sync_news_now": _ => RunTaskNowResult,
+        "sync_news_sources_now": _ => RunTaskNowResult,
+        "list_news_sources": {
+            country: Option<String>,
+            language: Option<String>,
+            search: Option<String>,
+        } => Vec<NewsSourceDto>,
+        "list_feed_sources": _ => Vec<FeedSourceDto>,
+        "get_feed_source": { source_id: i64 } => FeedSourceDto,
+        "create_feed_source": (CreateFeedSourceInput) => FeedSourceDto,
+        "update_feed_source": { source_id: i64, input: UpdateFeedSourceInput } => FeedSourceDto,
+        "delete_feed_source": { source_id: i64 } => Acknowledged,
+        "toggle_feed_source": { source_id: i64, enabled: bool } => FeedSourceDto,
+        "test_feed_source_connection": { source_id: i64 } => Value,
+        "sync_feed_source_now": { source_id: i64 } => SyncSourceResult,
+        "sync_all_feed_sources": _ => SyncAllResult,
+        // Keyword alert rules
+        "list_alert_rules": _ => Vec<AlertRuleDto>,
+        "create_alert_rule": (CreateAlertRuleInput) => AlertRuleDto,
+        "update_alert_rule": (UpdateAlertRuleInput) => AlertRuleDto,
+        "delete_alert_rule": { id: i64 } => Acknowledged,
+        // Saved searches
+        "list_saved_searches": _ => Vec<SavedSearchDto>,
+        "create_saved_search": (CreateSavedSearchInput) => SavedSearchDto,
+        "update_saved_search": (UpdateSavedSearchInput) => SavedSearchDto,
+        "delete_saved_search": { id: i64 } => Acknowledged,
+        "run_saved_search": {
+            id: i64,
+            limit: Option<u64>,
+            offset: Option<u64>,
+        } => Vec<NewsArticleDto>,
+        "test_alert_rule": (Option<TestAlertRuleInput>) => AlertRuleTestResult,
+        // Mute rules
+        "list_mute_rules": _ => Vec<MuteRuleDto>,
+        "create_mute_rule": (CreateMuteRuleInput) => MuteRuleDto,
+        "update_mute_rule": (UpdateMuteRuleInput) => MuteRuleDto,
+        "delete_mute_rule": { id: i64 } => Acknowledged,
+        "preview_mute_rule": (Option<PreviewMuteRuleInput>) => MuteRulePreview,
+        // Research connectors
+        "research_list_accounts": _ => Vec<ResearchAccountDto>,
+        "research_upsert_account": (CreateResearchAccountInput) => ResearchAccountDto,
+        "research_update_account": (UpdateResearchAccountInput) => ResearchAccountDto,
+        "research_delete_account": { id: i64 } => Acknowledged,
+        "research_site_credentials_list": _ => Vec<SiteCredentialDto>,
+        "research_site_credential_save": (SiteCredentialSaveInput) => SiteCredentialDto,
+        "research_site_credential_delete": { id: i64 } => Acknowledged,
+        "research_list_streams": { account_id: Option<i64> } => Vec<ResearchStreamDto>,
+        "research_upsert_stream": (UpsertResearchStreamInput) => ResearchStreamDto,
+        "research_delete_stream": { id: i64 } => Acknowledged,
+        "research_sync_stream_now": { stream_id: i64 } => Acknowledged,
+        "research_list_items": (ListResearchItemsQuery) => Vec<ResearchItemDto>,
+        "research_set_item_status": { item_id: i64, status: String } => Acknowledged,
+        // Reader
+        "reader_fetch": (ReaderFetchInput) => ReaderResult,
+        "reader_fetch_batch": (ReaderFetchBatchInput) => ReaderFetchBatchResult,
+        "reader_import_enex": (ReaderImportEnexInput) => ReaderEnexImportResult,
+        "reader_refresh": (ReaderRefreshInput) => ReaderResult,
+        "reader_reference_get": { reference_id: i64 } => ReaderReferenceDto,
+        "reader_reference_update": {
+            reference_id: i64,
+            input: ReferenceUpdateInput,
+        } => ReaderReferenceDto,
+        "update_reading_progress": (ReadingProgressInput) => ReaderReferenceDto,
+        "reader_continue_reading": { limit: Option<u64> } => Vec<ReaderReferenceDto>,
+        "reader_set_watch": (ReaderWatchInput) => ReaderReferenceDto,
+        "reader_watched_list": _ => Vec<ReaderReferenceDto>,
+        "reader_snapshots_list": { reference_id: i64 } => Vec<ReaderSnapshotDto>,
+        "reader_snapshot_get": { snapshot_id: i64 } => ReaderSnapshotDto,
+        "reader_snapshot_delete": { snapshot_id: i64 } => Acknowledged,
+        "reader_media_cleanup": _ => ReaderMediaCleanupSummary,
+        "reader_clips_list": {
+            reference_id: i64,
+            label: Option<String>,
+            color: Option<String>,
+            snapshot_id: Option<i64>,
+        } => Vec<ReaderClipDto>,
+        "reader_clip_create": (ClipCreateInput) => ReaderClipDto,
+        "reader_clip_update": (ClipUpdateInput) => ReaderClipDto,
+        "reader_clip_delete": { clip_id: i64 } => Acknowledged,
+        "reader_clips_reanchor": (ClipReanchorInput) => Vec<ClipReanchorResult>,
+        "reader_site_rules_list": _ => Vec<ReaderSiteRuleDto>,
+        "reader_site_rule_create": (SiteRuleCreateInput) => ReaderSiteRuleDto,
+        "reader_site_rule_update": (SiteRuleUpdateInput) => ReaderSiteRuleDto,
+        "reader_site_rule_delete": { id: i64 } => Acknowledged,
+        "reader_site_rule_test": (SiteRuleTestInput) => SiteRuleTestResult,
+        "reading_queue_list": _ => Vec<ReadingQueueItemDto>,
+        "reading_queue_add": (ReadingQueueAddInput) => Vec<ReadingQueueItemDto>,
+        "reading_queue_remove": { id: i64 } => Vec<ReadingQueueItemDto>,
+        "reading_queue_reorder": (ReadingQueueReorderInput) => Vec<ReadingQueueItemDto>,
+        "reading_queue_pop": _ => Option<ReadingQueueItemDto>,
+        "reading_stats": (Option<ReadingStatsInput>) => ReadingStatsDto,
+        "summarize_reference": (SummarizeReferenceInput) => ReferenceSummaryResult,
+        "suggest_tags": (SuggestTagsInput) => TagSuggestions,
+        "suggest_tags_batch": (Option<SuggestTagsBatchInput>) => SuggestTagsBatchResult,
+        // ---------- Writing ----------
+        "list_ideas": {
+            status: Option<String>,
+            search: Option<String>,
+            include_removed: Option<bool>,
+            due: Option<String>,
+            sort_by: Option<String>,
+            limit: Option<u64>,
+            offset: Option<u64>,
+            envelope: Option<bool>,
+        } => Listing<IdeaDto>,
+        "get_idea": { id: i64 } => IdeaDto,
+        "create_idea": (CreateIdeaInput) => IdeaDto,
+        "create_idea_for_article": (CreateIdeaForArticleInput) => IdeaDto,
+        "update_idea_metadata": { id: i64, input: UpdateIdeaMetadataInput } => IdeaDto,
+        "update_idea_notes": { id: i64, input: UpdateIdeaNotesInput } => IdeaDto,
+        "update_idea_article": { id: i64, input: UpdateIdeaArticleInput } => IdeaDto,
+        "archive_idea": { id: i64 } => IdeaDto,
+        "reorder_ideas": (ReorderIdeasInput) => Vec<IdeaDto>,
+        "merge_ideas": { source_id: i64, target_id: i64 } => IdeaMergeDto,
+        // Idea references
+        "list_idea_references": { idea_id: i64 } => Vec<IdeaReferenceDto>,
+        "add_reference_to_idea": (AddReferenceInput) => IdeaReferenceDto,
+        "remove_reference": { reference_id: i64 } => Acknowledged,
+        "update_reference_notes": (UpdateReferenceNotesInput) => IdeaReferenceDto,
+        "get_reference_reader_snapshot": { reference_id: i64 } => ReferenceReaderSnapshotDto,
+        "get_reader_snapshot_for_url": (ReaderSnapshotInput) => ReferenceReaderSnapshotDto,
+        "suggest_related_content": (RelatedContentInput) => Vec<SemanticSearchHit>,
+        // Knowledge graph References
+        "kg_list_references": {
+            reference_type: Option<String>,
+            search: Option<String>,
+            limit: Option<u64>,
+            offset: Option<u64>,
+            envelope: Option<bool>,
+        } => Listing<ReferenceDto>,
+        "kg_get_reference": { id: i64 } => ReferenceDto,
+        "kg_create_reference": (CreateReferenceInput) => ReferenceDto,
+        "kg_update_reference": { id: i64, input: UpdateReferenceInput } => ReferenceDto,
+        "kg_delete_reference": { id: i64 } => Acknowledged,
+        // Knowledge graph writings
+        "kg_list_writings": {
+            writing_type: Option<String>,
+            status: Option<String>,
+            search: Option<String>,
+            limit: Option<u64>,
+            offset: Option<u64>,
+        } => Vec<WritingDto>,
+        "kg_get_writing": { id: i64 } => WritingDto,
+        "kg_create_writing": (CreateWritingInput) => WritingDto,
+        "kg_update_writing": { id: i64, input: UpdateWritingInput } => WritingDto,
+        "kg_publish_writing": { id: i64 } => WritingDto,
+        "kg_delete_writing": { id: i64 } => Acknowledged,
+        // Knowledge graph links
+        "kg_link_idea_reference": (LinkIdeaReferenceInput) => IdeaReferenceLinkDto,
+        "kg_unlink_idea_reference": { idea_id: i64, reference_id: i64 } => Acknowledged,
+        "kg_list_references_for_idea": { idea_id: i64 } => Vec<IdeaReferenceLinkDto>,
+        "kg_list_ideas_for_reference": { reference_id: i64 } => Vec<IdeaReferenceLinkDto>,
+        "kg_link_writing_idea": (LinkWritingIdeaInput) => WritingIdeaLinkDto,
+        "kg_unlink_writing_idea": { writing_id: i64, idea_id: i64 } => Acknowledged,
+        "kg_list_ideas_for_writing": { writing_id: i64 } => Vec<WritingIdeaLinkDto>,
+        "kg_list_writings_for_idea": { idea_id: i64 } => Vec<WritingIdeaLinkDto>,
+        "kg_export_graph": { format: GraphFormat } => GraphExportDto,
+        "kg_export_bibliography": (ExportBibliographyInput) => BibliographyDto,
+        // Knowledge graph publications
+        "kg_list_publications": {
+            writing_id: Option<i64>,
+            platform: Option<String>,
+            status: Option<String>,
+        } => Vec<WritingPublicationDto>,
+        "kg_record_publication": (RecordPublicationInput) => WritingPublicationDto,
+        "kg_retry_publication": { id: i64 } => WritingPublicationDto,
+        // Knowledge graph notes
+        "kg_list_notes_for_entity": { entity_type: String, entity_id: i64 } => Vec<NoteDto>,
+        "kg_get_note": { id: i64 } => NoteDto,
+        "kg_create_note": (CreateNoteInput) => NoteDto,
+        "kg_update_note": { id: i64, input: UpdateNoteInput } => NoteDto,
+        "kg_delete_note": { id: i64 } => Acknowledged,
+        // Notes feature
+        "notes_get_or_create": {
+            entity_type: String,
+            entity_id: i64,
+            note_type: Option<String>,
+        } => notes::NoteDto,
+        "notes_upsert": {
+            entity_type: String,
+            entity_id: i64,
+            note_type: Option<String>,
+            body_html: Option<String>,
+            body_markdown: Option<String>,
+            store_markdown: Option<bool>,
+            expected_updated_at: Option<String>,
+        } => notes::NoteDto,
+        "notes_export_markdown": {
+            entity_type: String,
+            entity_id: i64,
+            note_type: Option<String>,
+        } => notes::NoteMarkdownDto,
+        "notes_list_backlinks": { entity_type: String, entity_id: i64 } => Vec<BacklinkDto>,
+        "notes_templates_list": _ => Vec<NoteTemplateDto>,
+        "notes_template_create": (NoteTemplateCreateInput) => NoteTemplateDto,
+        "notes_template_update": (NoteTemplateUpdateInput) => NoteTemplateDto,
+        "notes_template_delete": { id: i64 } => Acknowledged,
+        "export_entity_bundle": { entity_type: String, entity_id: i64 } => EntityBundleDto,
+        "notes_append_snippet": {
+            entity_type: String,
+            entity_id: i64,
+            note_type: Option<String>,
+            snippet_text: String,
+            source_url: Option<String>,
+            source_title: Option<String>,
+        } => notes::NoteDto,
+        // Writing drafts (TipTap JSON)
+        "writing_create": (CreateWritingDraftInput) => WritingDraftDto,
+        "writing_get": (GetWritingInput) => WritingDraftDto,
+        "writing_list": (ListWritingsQuery) => Listing<WritingDraftDto>,
+        "writing_update_meta": (UpdateWritingDraftMetaInput) => WritingDraftDto,
+        "writing_save_draft": (SaveDraftInput) => WritingDraftDto,
+        "writing_publish": (PublishWritingInput) => WritingDraftDto,
+        "writing_link_idea": (LinkIdeaInput) => Acknowledged,
+        "writing_unlink_idea": (LinkIdeaInput) => Acknowledged,
+        "writing_list_linked_ideas": (ListLinkedIdeasInput) => Vec<i64>,
+        "export_writings_markdown": (ExportWritingsMarkdownInput) => MarkdownExportDto,
+        "writing_export_epub": (ExportEpubInput) => EpubExportDto,
+        "writing_export_pdf": (ExportPdfInput) => ExportInfo,
+        "writing_export_docx": (ExportDocxInput) => ExportInfo,
+        "writing_import_obsidian": (ImportObsidianInput) => ObsidianImportDto,
+        "writing_import_notion": (ImportNotionInput) => NotionImportDto,
+        "writing_sync_folder": (SyncFolderInput) => FolderSyncDto,
+        // Newsletter
+        "newsletter_send_writing": (SendWritingNewsletterInput) => NewsletterSendResult,
+        "newsletter_send_digest": (SendNewsDigestInput) => NewsletterSendResult,
+        "newsletter_list_sends": (ListNewsletterSendsInput) => Vec<NewsletterSendDto>,
+    })
+}
+
+/// The full schema document served at `/schema`, built on first use
+pub fn bridge_schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        let mut gen = SchemaSettings::draft07().into_generator();
+        let commands: Map<String, Value> = command_specs(&mut gen)
+            .into_iter()
+            .map(|spec| {
+                let entry = json!({ "payload": spec.payload, "response": spec.response });
+                (spec.name.to_string(), entry)
+            })
+            .collect();
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Cockpit bridge commands",
+            "version": env!("CARGO_PKG_VERSION"),
+            "endpoint": "POST /api/command",
+            "commands": commands,
+            "routes": rest::route_index(),
+            "definitions": gen.definitions(),
+        })
+    })
+}
+
+/// Look through `$ref`s and `Option` wrappers to the schema they describe
+fn resolve<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
+    if let Some(name) = schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix("#/definitions/"))
+    {
+        if let Some(def) = root["definitions"].get(name) {
+            return resolve(def, root);
+        }
+    }
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        let mut concrete = variants.iter().filter(|v| v["type"] != "null");
+        if let (Some(only), None) = (concrete.next(), concrete.next()) {
+            return resolve(only, root);
+        }
+    }
+    schema
+}
+
+/// JSON types the payload of `command` allows for `field`, e.g.
+/// `["integer", "null"]`; empty when the field or command is unknown
+pub fn payload_field_types(command: &str, field: &str) -> Vec<&'static str> {
+    let root = bridge_schema();
+    let payload = resolve(&root["commands"][command]["payload"], root);
+    let property = resolve(&payload["properties"][field], root);
+    match &property["type"] {
+        Value::String(t) => vec![t.as_str()],
+        Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_schema_covers_every_dispatch_command() {
+        let arm = regex::Regex::new(r#"(?m)^        "([a-z0-9_]+)" =>"#).unwrap();
+        let dispatched: BTreeSet<String> = arm
+            .captures_iter(include_str!("dispatch.rs"))
+            .map(|c| c[1].to_string())
+            .collect();
+        let mut gen = SchemaSettings::draft07().into_generator();
+        let described: BTreeSet<String> = command_specs(&mut gen)
+            .into_iter()
+            .map(|spec| spec.name.to_string())
+            .collect();
+        assert_eq!(dispatched, described);
+    }
+}
@@ -0,0 +1,378 @@
+//! Command scopes for API tokens
+//!
+//! Every command belongs to a module (`core`, `system`, `research`,
+//! `writing`, `notes`) and needs `read`, `write` or `admin` access. A scoped
+//! token lists what it may do: `read`, `write` (implies read) and `admin`
+//! apply to every module, `research:read`, `research:write` or `research:*`
+//! to one. `dispatch` checks the grant of the token that made the request;
+//! calls that didn't come through the token check (no tokens configured,
+//! internal callers) are not restricted.
+//!
+//! The grant also names the actor recorded in the audit log.
+
+use std::future::Future;
+
+use sha2::{Digest, Sha256};
+
+use super::dispatch::ApiError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Access {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Access {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(Access::Read),
+            "write" => Some(Access::Write),
+            "admin" | "*" => Some(Access::Admin),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Access::Read => "read",
+            Access::Write => "write",
+            Access::Admin => "admin",
+        }
+    }
+}
+
+const MODULES: &[&str] = &["core", "system", "research", "writing", "notes"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    /// `None` for every module
+    module: Option<&'static str>,
+    access: Access,
+}
+
+impl Scope {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value.split_once(':') {
+            Some((module, access)) => Some(Scope {
+                module: Some(MODULES.iter().copied().find(|m| *m == module)?),
+                access: Access::parse(access)?,
+            }),
+            None => Some(Scope {
+                module: None,
+                access: Access::parse(value)?,
+            }),
+        }
+    }
+
+    fn allows(&self, module: &str, access: Access) -> bool {
+        self.module.is_none_or(|m| m == module) && access <= self.access
+    }
+}
+
+/// What the token behind the current request may do
+#[derive(Debug, Clone)]
+pub enum Grant {
+    /// The full-access `COCKPIT_HTTP_TOKEN`
+    Full,
+    Scoped {
+        /// `scoped:` and the start of the token's SHA-256, never the token
+        actor: String,
+        scopes: Vec<Scope>,
+    },
+}
+
+impl Grant {
+    /// Unknown scopes grant nothing
+    pub fn scoped(token: &str, scopes: &[String]) -> Self {
+        let digest = hex::encode(Sha256::digest(token.as_bytes()));
+        Grant::Scoped {
+            actor: format!("scoped:{}", &digest[..8]),
+            scopes: scopes.iter().filter_map(|s| Scope::parse(s)).collect(),
+        }
+    }
+
+    fn allows(&self, module: &str, access: Access) -> bool {
+        match self {
+            Grant::Full => true,
+            Grant::Scoped { scopes, .. } => scopes.iter().any(|scope| scope.allows(module, access)),
+        }
+    }
+}
+
+tokio::task_local! {
+    static GRANT: Grant;
+}
+
+/// Grant of the current request, if it went through the token check
+pub fn current_grant() -> Option<Grant> {
+    GRANT.try_with(Clone::clone).ok()
+}
+
+/// Run `fut` with `grant` as the current request's grant
+pub async fn with_grant<F: Future>(grant: Option<Grant>, fut: F) -> F::Output {
+    match grant {
+        Some(grant) => GRANT.scope(grant, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Who is making the current request, for the audit log
+pub fn current_actor() -> String {
+    match current_grant() {
+        None => "local".to_string(),
+        Some(Grant::Full) => "api-token".to_string(),
+        Some(Grant::Scoped { actor, .. }) => actor,
+    }
+}
+
+/// Reject the request unless its grant includes `access` to `module`
+pub fn require(module: &str, access: Access) -> Result<(), ApiError> {
+    match current_grant() {
+        Some(grant) if !grant.allows(module, access) => Err(ApiError::Forbidden(format!(
+            "token lacks the {}:{} scope",
+            module,
+            access.as_str()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Reject `command` unless the current grant covers it
+pub fn authorize(command: &str) -> Result<(), ApiError> {
+    let Some(grant) = current_grant() else {
+        return Ok(());
+    };
+    let access = command_access(command);
+    match command_module(command) {
+        Some(module) if grant.allows(module, access) => Ok(()),
+        // Unknown commands fall through to dispatch's own error
+        None if matches!(grant, Grant::Full) => Ok(()),
+        module => Err(ApiError::Forbidden(format!(
+            "token lacks the {}:{} scope for {}",
+            module.unwrap_or("unknown"),
+            access.as_str(),
+            command
+        ))),
+    }
+}
+
+/// Module a command belongs to; `None` for unknown commands
+fn command_module(command: &str) -> Option<&'static str> {
+    let module = match command {
+        "list_system_tasks"
+        | "get_task_history"
+        | "run_system_task_now"
+        | "update_system_task"
+        | "schedule_task_run"
+        | "list_scheduled_runs"
+        | "cancel_scheduled_run"
+        | "pause_scheduler"
+        | "resume_scheduler" => "system",
+        "get_current_user"
+        | "get_system_user"
+        | "log_frontend_error"
+        | "get_app_settings"
+        | "update_setting"
+        | "update_settings"
+        | "reveal_setting"
+        | "get_storage_statistics"
+        | "create_database_backup"
+        | "restore_database_from_backup"
+        | "list_database_backups"
+        | "delete_database_backup"
+        | "verify_backup"
+        | "export_database"
+        | "import_database"
+        | "cleanup_logs"
+        | "cleanup_news"
+        | "run_db_maintenance"
+        | "get_migration_status"
+        | "rollback_last_migration"
+        | "check_database_integrity"
+        | "encrypt_database"
+        | "rotate_master_key"
+        | "list_credentials"
+        | "export_profile"
+        | "import_profile"
+        | "list_workspaces"
+        | "create_workspace"
+        | "switch_workspace"
+        | "reload_config"
+        | "check_config"
+        | "get_application_logs"
+        | "get_application_log_stats"
+        | "export_application_logs"
+        | "clear_application_logs"
+        | "check_setup_status_command"
+        | "generate_master_key_command"
+        | "save_setup_config_command"
+        | "get_mixed_feed"
+        | "get_upcoming_events"
+        | "list_scheduled_jobs"
+        | "sync_calendar"
+        | "get_command_metrics"
+        | "list_audit_log"
+        | "export_audit_log"
+        | "embeddings_reindex"
+        | "semantic_search"
+        | "more_like_this" => "core",
+        "summarize_reference"
+        | "suggest_tags"
+        | "suggest_tags_batch"
+        | "update_reading_progress" => "research",
+        "suggest_related_content"
+        | "get_reference_reader_snapshot"
+        | "get_reader_snapshot_for_url"
+        | "export_writings_markdown" => "writing",
+        "export_entity_bundle" => "notes",
+        c if c.starts_with("notes_") => "notes",
+        c if c.starts_with("kg_") || c.starts_with("writing_") || c.starts_with("newsletter_") => {
+            "writing"
+        }
+        c if c.starts_with("research_")
+            || c.starts_with("reader_")
+            || c.starts_with("reading_")
+            || c.contains("news")
+            || c.contains("feed_source")
+            || c.contains("alert_rule")
+            || c.contains("saved_search")
+            || c.contains("mute_rule") =>
+        {
+            "research"
+        }
+        c if c.contains("idea") || c.contains("reference") => "writing",
+        _ => return None,
+    };
+    Some(module)
+}
+
+/// Destructive or system-wide commands, and those touching credentials
+const ADMIN_COMMANDS: &[&str] = &[
+    "update_setting",
+    "update_settings",
+    "reveal_setting",
+    "create_database_backup",
+    "restore_database_from_backup",
+    "delete_database_backup",
+    "verify_backup",
+    "export_database",
+    "import_database",
+    "cleanup_logs",
+    "cleanup_news",
+    "run_db_maintenance",
+    "rollback_last_migration",
+    "check_database_integrity",
+    "encrypt_database",
+    "rotate_master_key",
+    "list_credentials",
+    "export_profile",
+    "import_profile",
+    "create_workspace",
+    "switch_workspace",
+    "reload_config",
+    "export_application_logs",
+    "clear_application_logs",
+    "generate_master_key_command",
+    "save_setup_config_command",
+    "clear_news_articles",
+    "update_system_task",
+    "pause_scheduler",
+    "resume_scheduler",
+    "list_audit_log",
+    "export_audit_log",
+    "research_site_credentials_list",
+    "research_site_credential_save",
+    "research_site_credential_delete",
+];
+
+/// Read-only commands whose names don't say so
+const READ_COMMANDS: &[&str] = &[
+    "more_like_this",
+    "reading_stats",
+    "reader_continue_reading",
+    "notes_export_markdown",
+    "suggest_related_content",
+];
+
+fn command_access(command: &str) -> Access {
+    if ADMIN_COMMANDS.contains(&command) {
+        return Access::Admin;
+    }
+    name_access(command)
+}
+
+/// Whether a command changes anything, going by its name alone
+pub fn mutates(command: &str) -> bool {
+    name_access(command) != Access::Read
+}
+
+fn name_access(command: &str) -> Access {
+    if READ_COMMANDS.contains(&command) {
+        return Access::Read;
+    }
+    let words: Vec<&str> = command.split('_').collect();
+    let reads = words
+        .iter()
+        .any(|w| matches!(*w, "list" | "get" | "search" | "check" | "preview" | "test"));
+    let writes = words.iter().any(|w| {
+        matches!(
+            *w,
+            "create" | "update" | "upsert" | "save" | "set" | "delete" | "remove"
+        )
+    });
+    if reads && !writes {
+        Access::Read
+    } else {
+        Access::Write
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_dispatch_command_has_a_module() {
+        let arm = regex::Regex::new(r#"(?m)^        "([a-z0-9_]+)" =>"#).unwrap();
+        let unclassified: Vec<String> = arm
+            .captures_iter(include_str!("dispatch.rs"))
+            .map(|c| c[1].to_string())
+            .filter(|command| command_module(command).is_none())
+            .collect();
+        assert!(unclassified.is_empty(), "{unclassified:?}");
+    }
+
+    fn allowed(scopes: &[&str], command: &str) -> bool {
+        let scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+        GRANT.sync_scope(Grant::scoped("token", &scopes), || {
+            authorize(command).is_ok()
+        })
+    }
+
+    #[test]
+    fn test_scoped_grants() {
+        assert!(allowed(&["read"], "list_ideas"));
+        assert!(allowed(&["read"], "kg_get_writing"));
+        assert!(!allowed(&["read"], "create_idea"));
+        assert!(!allowed(&["read"], "notes_get_or_create"));
+        assert!(!allowed(&["read"], "delete_saved_search"));
+        assert!(!allowed(&["read"], "restore_database_from_backup"));
+
+        let research = ["research:*", "writing:read"];
+        assert!(allowed(&research, "sync_all_feed_sources"));
+        assert!(allowed(&research, "list_ideas"));
+        assert!(!allowed(&research, "archive_idea"));
+        assert!(!allowed(&research, "newsletter_send_digest"));
+
+        let write = ["write", "bogus", "nope:read"];
+        assert!(allowed(&write, "archive_idea"));
+        assert!(!allowed(&write, "import_database"));
+        assert!(!allowed(&write, "no_such_command"));
+
+        assert!(allowed(&["admin"], "import_database"));
+        assert!(GRANT.sync_scope(Grant::Full, || authorize("no_such_command").is_ok()));
+        assert!(authorize("import_database").is_ok());
+    }
+}
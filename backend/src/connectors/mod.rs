@@ -34,6 +34,11 @@ pub trait Connector: Send + Sync {
         allowed_caps: &[ResearchCapability],
     ) -> Result<(), String>;
 
+    /// Base URL of the provider's API, used to look up stored site headers.
+    fn base_url(&self) -> Option<&'static str> {
+        None
+    }
+
     /// Sync a stream (downstream ingest). Returns normalized items.
     async fn sync_stream(
         &self,
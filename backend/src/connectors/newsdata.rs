@@ -58,6 +58,10 @@ impl Connector for NewsDataConnector {
         vec![ResearchCapability::ReadStream, ResearchCapability::Search]
     }
 
+    fn base_url(&self) -> Option<&'static str> {
+        Some("https://newsdata.io")
+    }
+
     fn validate_config(
         &self,
         _config: &serde_json::Value,
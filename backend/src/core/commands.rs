@@ -4,22 +4,39 @@
 
 use tauri::State;
 use crate::AppState;
-use super::components::settings::{get_app_settings_handler, update_setting_handler, update_settings_handler, AppSettingsDto, UpdateSettingInput};
+use super::components::settings::{get_app_settings_handler, reveal_setting_handler, update_setting_handler, update_settings_handler, AppSettingsDto, RevealedSetting, UpdateSettingInput};
 use super::components::storage::{
-    get_storage_stats, backup_database, restore_database, list_backups, delete_backup,
+    get_storage_stats, backup_database, backup_database_incremental, restore_database,
+    list_backups, delete_backup,
     export_data, import_data, cleanup_old_logs, cleanup_old_news,
     get_logs, get_log_stats, export_logs, clear_logs,
-    StorageStats, BackupInfo, ExportInfo, ImportSummary, CleanupSummary,
+    StorageStats, BackupInfo, BackupVerification, ExportFormat, ExportGroup, ExportInfo,
+    ExportInput, ImportOptions, ImportStrategy, ImportSummary, CleanupSummary,
     LogEntry, LogStats
 };
+use super::components::storage::verify;
+use super::components::config::{self, reload_config as reload, ConfigDoctorReport, ConfigReload};
+use super::components::credentials::{self, CredentialInfo};
+use super::components::db::encryption::{self, DatabaseEncryptionStatus};
+use super::components::db::integrity::{self, IntegrityReport};
+use super::components::db::maintenance::{self, DbMaintenanceResult};
+use super::components::db::migrations::{self, MigrationRollback, MigrationStatus};
+use super::components::embeddings::{
+    more_like_this_handler, reindex_embeddings_handler, semantic_search_handler,
+    MoreLikeThisInput, ReindexEmbeddingsInput, ReindexEmbeddingsResult, SemanticSearchHit,
+    SemanticSearchInput,
+};
+use super::components::key_rotation::{self, KeyRotationReport};
+use super::components::profile::{self, Profile, ProfileImportReport};
 use super::components::setup_wizard::{
     check_setup_status, generate_master_key, save_setup_config,
     SetupStatus, SetupConfig
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Current user information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CurrentUser {
     pub username: String,
     pub home_dir: String,
@@ -77,6 +94,17 @@ pub async fn update_settings(
         .map_err(|e| e.to_string())
 }
 
+/// Read a secret setting in clear
+#[tauri::command]
+pub async fn reveal_setting(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<RevealedSetting, String> {
+    reveal_setting_handler(&state.db, &key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Storage Commands
 // ============================================================================
@@ -84,16 +112,24 @@ pub async fn update_settings(
 /// Get storage statistics including data, logs, cache, and backup sizes
 #[tauri::command]
 pub fn get_storage_statistics(state: State<'_, AppState>) -> Result<StorageStats, String> {
-    get_storage_stats(&state.config.storage)
+    get_storage_stats(&state.config.current().storage)
         .map_err(|e| e.to_string())
 }
 
-/// Create a backup of the database
+/// Create a backup of the database, or only the pages changed since the
+/// last one when `incremental` is set
 #[tauri::command]
-pub async fn create_database_backup(state: State<'_, AppState>) -> Result<BackupInfo, String> {
-    backup_database(&state.db, &state.config.storage)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn create_database_backup(
+    incremental: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<BackupInfo, String> {
+    let config = state.config.current();
+    if incremental.unwrap_or(false) {
+        backup_database_incremental(&state.db, &config.database, &config.storage).await
+    } else {
+        backup_database(&state.db, &config.database, &config.storage).await
+    }
+    .map_err(|e| e.to_string())
 }
 
 /// Restore database from a backup file
@@ -102,15 +138,20 @@ pub async fn restore_database_from_backup(
     backup_path: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    restore_database(&state.db, &backup_path, &state.config.storage)
-        .await
-        .map_err(|e| e.to_string())
+    restore_database(
+        &state.db,
+        &state.config.current().database,
+        &backup_path,
+        &state.config.current().storage,
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 /// List all available database backups
 #[tauri::command]
 pub fn list_database_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>, String> {
-    list_backups(&state.config.storage)
+    list_backups(&state.config.current().storage)
         .map_err(|e| e.to_string())
 }
 
@@ -120,26 +161,65 @@ pub async fn delete_database_backup(
     backup_path: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    delete_backup(&state.config.storage, &backup_path)
+    delete_backup(&state.config.current().storage, &backup_path)
         .await
         .map_err(|e| e.to_string())
 }
 
-/// Export all data to JSON file
+/// Check that a backup can be restored, without restoring it
 #[tauri::command]
-pub async fn export_database(state: State<'_, AppState>) -> Result<ExportInfo, String> {
-    export_data(&state.db, &state.config.storage)
-        .await
-        .map_err(|e| e.to_string())
+pub async fn verify_backup(
+    backup_path: String,
+    state: State<'_, AppState>,
+) -> Result<BackupVerification, String> {
+    verify::verify_backup(
+        &state.db,
+        &state.config.current().database,
+        &backup_path,
+        &state.config.current().storage,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Export data to JSON file, optionally limited to some groups and a date range
+#[tauri::command]
+pub async fn export_database(
+    groups: Option<Vec<ExportGroup>>,
+    since: Option<String>,
+    until: Option<String>,
+    format: Option<ExportFormat>,
+    state: State<'_, AppState>,
+) -> Result<ExportInfo, String> {
+    let input = ExportInput {
+        groups,
+        since,
+        until,
+        format,
+    };
+    export_data(
+        &state.db,
+        &state.config.current().database,
+        &state.config.current().storage,
+        &input,
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
-/// Import data from JSON file
+/// Import data from an export file; `dry_run` previews without writing
 #[tauri::command]
 pub async fn import_database(
     import_path: String,
+    strategy: Option<ImportStrategy>,
+    dry_run: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<ImportSummary, String> {
-    import_data(&state.db, &import_path)
+    let options = ImportOptions {
+        strategy: strategy.unwrap_or_default(),
+        dry_run: dry_run.unwrap_or(false),
+    };
+    import_data(&state.db, &import_path, options)
         .await
         .map_err(|e| e.to_string())
 }
@@ -150,7 +230,7 @@ pub fn cleanup_logs(
     retention_days: Option<i64>,
     state: State<'_, AppState>,
 ) -> Result<CleanupSummary, String> {
-    cleanup_old_logs(&state.config.storage, retention_days)
+    cleanup_old_logs(&state.config.current().storage, retention_days)
         .map_err(|e| e.to_string())
 }
 
@@ -165,6 +245,114 @@ pub async fn cleanup_news(
         .map_err(|e| e.to_string())
 }
 
+/// Run ANALYZE and VACUUM (unless `vacuum` is false), reporting reclaimed space
+#[tauri::command]
+pub async fn run_db_maintenance(
+    vacuum: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<DbMaintenanceResult, String> {
+    maintenance::run_db_maintenance(&state.db, Some(vacuum.unwrap_or(true)))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Applied and pending schema migrations
+#[tauri::command]
+pub async fn get_migration_status(state: State<'_, AppState>) -> Result<MigrationStatus, String> {
+    migrations::get_migration_status(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Back up the database, then roll back its newest migration
+#[tauri::command]
+pub async fn rollback_last_migration(
+    state: State<'_, AppState>,
+) -> Result<MigrationRollback, String> {
+    migrations::rollback_last_migration(
+        &state.db,
+        &state.config.current().database,
+        &state.config.current().storage,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Check the database for corruption and orphaned rows; `full` runs the
+/// slower `integrity_check`
+#[tauri::command]
+pub async fn check_database_integrity(
+    full: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<IntegrityReport, String> {
+    integrity::check_database_integrity(&state.db, full.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Encrypt the SQLite database with SQLCipher at the next start
+#[tauri::command]
+pub async fn encrypt_database(
+    state: State<'_, AppState>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    encryption::encrypt_database(&state.config.current().database)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the master key, re-encrypting every stored secret with the new one
+#[tauri::command]
+pub async fn rotate_master_key(state: State<'_, AppState>) -> Result<KeyRotationReport, String> {
+    key_rotation::rotate_master_key(&state.config, &state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stored secrets with their last use and, unless `test` is false, whether a
+/// test call with them is accepted
+#[tauri::command]
+pub async fn list_credentials(
+    test: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<CredentialInfo>, String> {
+    credentials::list_credentials(&state.db, &state.http_clients, test.unwrap_or(true))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The settings, feed sources, task schedules and rules of this install,
+/// without secrets
+#[tauri::command]
+pub async fn export_profile(state: State<'_, AppState>) -> Result<Profile, String> {
+    profile::export_profile(&state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Apply a profile from `export_profile`, skipping what already exists
+#[tauri::command]
+pub async fn import_profile(
+    profile: Profile,
+    state: State<'_, AppState>,
+) -> Result<ProfileImportReport, String> {
+    profile::import_profile(&state, profile)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-read the configuration and apply what can change without a restart
+#[tauri::command]
+pub fn reload_config(state: State<'_, AppState>) -> Result<ConfigReload, String> {
+    reload(&state.config, &state.log_filter).map_err(|e| e.to_string())
+}
+
+/// Check directories, master key, database, feed source API keys, HTTP port
+/// and disk space for the setup UI
+#[tauri::command]
+pub async fn check_config(state: State<'_, AppState>) -> Result<ConfigDoctorReport, String> {
+    Ok(config::check_config(&state.config.current(), &state.db).await)
+}
+
 // ============================================================================
 // Log Management Commands
 // ============================================================================
@@ -177,7 +365,7 @@ pub fn get_application_logs(
     offset: Option<usize>,
     state: State<'_, AppState>,
 ) -> Result<Vec<LogEntry>, String> {
-    get_logs(&state.config.storage, level_filter, limit, offset)
+    get_logs(&state.config.current().storage, level_filter, limit, offset)
         .map_err(|e| e.to_string())
 }
 
@@ -186,7 +374,7 @@ pub fn get_application_logs(
 pub fn get_application_log_stats(
     state: State<'_, AppState>,
 ) -> Result<LogStats, String> {
-    get_log_stats(&state.config.storage)
+    get_log_stats(&state.config.current().storage)
         .map_err(|e| e.to_string())
 }
 
@@ -196,7 +384,7 @@ pub fn export_application_logs(
     level_filter: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
-    export_logs(&state.config.storage, level_filter)
+    export_logs(&state.config.current().storage, level_filter)
         .map_err(|e| e.to_string())
 }
 
@@ -205,7 +393,7 @@ pub fn export_application_logs(
 pub fn clear_application_logs(
     state: State<'_, AppState>,
 ) -> Result<CleanupSummary, String> {
-    clear_logs(&state.config.storage, None)
+    clear_logs(&state.config.current().storage, None)
         .map_err(|e| e.to_string())
 }
 
@@ -232,3 +420,40 @@ pub fn save_setup_config_command(config: SetupConfig) -> Result<(), String> {
     save_setup_config(config)
         .map_err(|e| e.to_string())
 }
+
+// ============================================================================
+// Semantic Search Commands
+// ============================================================================
+
+/// Embed new or changed articles, references, and writings
+#[tauri::command]
+pub async fn embeddings_reindex(
+    state: State<'_, AppState>,
+    input: ReindexEmbeddingsInput,
+) -> Result<ReindexEmbeddingsResult, String> {
+    reindex_embeddings_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Rank indexed content by similarity to a free-text query
+#[tauri::command]
+pub async fn semantic_search(
+    state: State<'_, AppState>,
+    input: SemanticSearchInput,
+) -> Result<Vec<SemanticSearchHit>, String> {
+    semantic_search_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Find content similar to an indexed article, reference, or writing
+#[tauri::command]
+pub async fn more_like_this(
+    state: State<'_, AppState>,
+    input: MoreLikeThisInput,
+) -> Result<Vec<SemanticSearchHit>, String> {
+    more_like_this_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
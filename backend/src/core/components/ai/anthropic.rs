@@ -0,0 +1,97 @@
+//! Anthropic Messages API client
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::core::components::errors::{AppError, AppResult};
+
+use super::llm::{check_status, LlmClient};
+
+pub(crate) const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+const DEFAULT_MODEL: &str = "claude-3-5-haiku-latest";
+pub(crate) const API_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 1024;
+
+pub struct AnthropicClient {
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+    timeout: std::time::Duration,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+}
+
+impl AnthropicClient {
+    pub fn new(
+        http: &reqwest::Client,
+        base_url: Option<String>,
+        model: Option<String>,
+        api_key: String,
+        timeout: std::time::Duration,
+    ) -> Self {
+        Self {
+            http: http.clone(),
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            api_key,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for AnthropicClient {
+    fn provider(&self) -> &'static str {
+        "anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn chat(&self, system: &str, prompt: &str) -> AppResult<String> {
+        let endpoint = format!("{}/v1/messages", self.base_url);
+        let resp = self
+            .http
+            .post(&endpoint)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", API_VERSION)
+            .timeout(self.timeout)
+            .json(&json!({
+                "model": self.model,
+                "max_tokens": MAX_TOKENS,
+                "temperature": 0.2,
+                "system": system,
+                "messages": [{ "role": "user", "content": prompt }],
+            }))
+            .send()
+            .await?;
+
+        let body: MessagesResponse = check_status(resp, &endpoint).await?.json().await?;
+        let text: String = body
+            .content
+            .into_iter()
+            .filter(|b| b.kind == "text")
+            .filter_map(|b| b.text)
+            .collect();
+        if text.trim().is_empty() {
+            return Err(AppError::other("Anthropic returned no text content"));
+        }
+        Ok(text.trim().to_string())
+    }
+}
@@ -0,0 +1,116 @@
+//! Provider-agnostic LLM client
+//!
+//! `llm_client_from_settings` reads `ai.provider`, `ai.model`, `ai.base_url`
+//! and the encrypted API key settings; Ollama falls back to the
+//! `OLLAMA_*` environment defaults when settings are blank.
+
+use async_trait::async_trait;
+use sea_orm::DatabaseConnection;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::settings::handlers::get_setting_value;
+use crate::core::config::AiConfig;
+
+use super::anthropic::AnthropicClient;
+use super::ollama::OllamaClient;
+use super::openai::OpenAiClient;
+
+/// A chat-style text generation backend
+#[async_trait]
+pub trait LlmClient: Send + Sync {
+    /// Provider identifier ("ollama", "openai", "anthropic")
+    fn provider(&self) -> &'static str;
+
+    /// Model used for requests, recorded alongside generated content
+    fn model(&self) -> &str;
+
+    /// Run a single system + user exchange and return the reply text
+    async fn chat(&self, system: &str, prompt: &str) -> AppResult<String>;
+}
+
+/// Build the LLM client selected in settings
+pub async fn llm_client_from_settings(
+    db: &DatabaseConnection,
+    http: &reqwest::Client,
+    ai: &AiConfig,
+) -> AppResult<Box<dyn LlmClient>> {
+    let setting = |key: &'static str| async move {
+        get_setting_value(db, key)
+            .await
+            .map(|v| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()))
+    };
+
+    let provider = setting("ai.provider")
+        .await?
+        .unwrap_or_else(|| "ollama".to_string());
+    let model = setting("ai.model").await?;
+    let base_url = setting("ai.base_url").await?;
+
+    let client: Box<dyn LlmClient> = match provider.as_str() {
+        "ollama" => Box::new(OllamaClient::new(
+            http,
+            base_url.unwrap_or_else(|| ai.ollama_base_url.clone()),
+            model.unwrap_or_else(|| ai.ollama_model.clone()),
+            ai.request_timeout,
+        )),
+        "openai" => {
+            let api_key = setting("ai.openai_api_key")
+                .await?
+                .ok_or_else(|| missing_key("ai.openai_api_key", "OpenAI"))?;
+            Box::new(OpenAiClient::new(http, base_url, model, api_key, ai.request_timeout))
+        }
+        "anthropic" => {
+            let api_key = setting("ai.anthropic_api_key")
+                .await?
+                .ok_or_else(|| missing_key("ai.anthropic_api_key", "Anthropic"))?;
+            Box::new(AnthropicClient::new(http, base_url, model, api_key, ai.request_timeout))
+        }
+        other => {
+            return Err(AppError::ConfigValidation {
+                field: "ai.provider".to_string(),
+                reason: format!("Unknown LLM provider '{}'", other),
+                suggestion: Some("Use one of: ollama, openai, anthropic".to_string()),
+            })
+        }
+    };
+
+    tracing::debug!(
+        target: "ai",
+        provider = client.provider(),
+        model = client.model(),
+        "LLM client ready"
+    );
+    Ok(client)
+}
+
+fn missing_key(field: &str, provider: &str) -> AppError {
+    AppError::ConfigValidation {
+        field: field.to_string(),
+        reason: format!("{} API key is not set", provider),
+        suggestion: Some(format!("Add your {} API key in Settings → Advanced", provider)),
+    }
+}
+
+/// Map a non-2xx response to an `ApiRequest` error, logging the body
+pub(crate) async fn check_status(
+    resp: reqwest::Response,
+    endpoint: &str,
+) -> AppResult<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let body = resp.text().await.unwrap_or_default();
+    tracing::warn!(
+        target: "ai",
+        endpoint,
+        status = status.as_u16(),
+        "LLM request failed: {}",
+        body
+    );
+    Err(AppError::ApiRequest {
+        endpoint: endpoint.to_string(),
+        status: status.as_u16(),
+        source: None,
+    })
+}
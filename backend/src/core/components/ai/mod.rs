@@ -0,0 +1,21 @@
+//! AI provider layer
+//!
+//! All AI features (summaries, tagging, drafting) go through the
+//! provider-agnostic `LlmClient` trait, configured via `ai.*` settings:
+//! - llm: `LlmClient` trait + settings-driven client selection
+//! - ollama: Local Ollama server (`/api/chat`)
+//! - openai: OpenAI-compatible chat completions
+//! - anthropic: Anthropic Messages API
+//! - summarize: Summary prompt + input truncation
+//! - tags: Tag suggestion prompt + reply parsing
+
+pub mod llm;
+pub mod ollama;
+pub mod openai;
+pub mod anthropic;
+pub mod summarize;
+pub mod tags;
+
+pub use llm::{llm_client_from_settings, LlmClient};
+pub use summarize::summarize_text;
+pub use tags::suggest_tags_text;
@@ -0,0 +1,91 @@
+//! Ollama client
+//!
+//! Non-streaming `/api/chat` requests against a local Ollama server.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::core::components::errors::{AppError, AppResult};
+
+use super::llm::{check_status, LlmClient};
+
+pub struct OllamaClient {
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+    timeout: std::time::Duration,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+impl OllamaClient {
+    pub fn new(
+        http: &reqwest::Client,
+        base_url: String,
+        model: String,
+        timeout: std::time::Duration,
+    ) -> Self {
+        Self {
+            http: http.clone(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for OllamaClient {
+    fn provider(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn chat(&self, system: &str, prompt: &str) -> AppResult<String> {
+        let endpoint = format!("{}/api/chat", self.base_url);
+        let resp = self
+            .http
+            .post(&endpoint)
+            .timeout(self.timeout)
+            .json(&json!({
+                "model": self.model,
+                "stream": false,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": prompt },
+                ],
+                "options": { "temperature": 0.2 },
+            }))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    AppError::ConfigValidation {
+                        field: "ai.base_url".to_string(),
+                        reason: format!("Cannot reach Ollama at {}", self.base_url),
+                        suggestion: Some(format!(
+                            "Start Ollama and pull the model: ollama pull {}",
+                            self.model
+                        )),
+                    }
+                } else {
+                    AppError::from(e)
+                }
+            })?;
+
+        let body: ChatResponse = check_status(resp, &endpoint).await?.json().await?;
+        Ok(body.message.content.trim().to_string())
+    }
+}
@@ -0,0 +1,96 @@
+//! OpenAI chat completions client
+//!
+//! Works with any OpenAI-compatible `/chat/completions` endpoint via `ai.base_url`.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::core::components::errors::{AppError, AppResult};
+
+use super::llm::{check_status, LlmClient};
+
+pub(crate) const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_MODEL: &str = "gpt-4o-mini";
+
+pub struct OpenAiClient {
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+    api_key: String,
+    timeout: std::time::Duration,
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    content: Option<String>,
+}
+
+impl OpenAiClient {
+    pub fn new(
+        http: &reqwest::Client,
+        base_url: Option<String>,
+        model: Option<String>,
+        api_key: String,
+        timeout: std::time::Duration,
+    ) -> Self {
+        Self {
+            http: http.clone(),
+            base_url: base_url
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            api_key,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for OpenAiClient {
+    fn provider(&self) -> &'static str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn chat(&self, system: &str, prompt: &str) -> AppResult<String> {
+        let endpoint = format!("{}/chat/completions", self.base_url);
+        let resp = self
+            .http
+            .post(&endpoint)
+            .bearer_auth(&self.api_key)
+            .timeout(self.timeout)
+            .json(&json!({
+                "model": self.model,
+                "temperature": 0.2,
+                "messages": [
+                    { "role": "system", "content": system },
+                    { "role": "user", "content": prompt },
+                ],
+            }))
+            .send()
+            .await?;
+
+        let body: CompletionResponse = check_status(resp, &endpoint).await?.json().await?;
+        body.choices
+            .into_iter()
+            .next()
+            .and_then(|c| c.message.content)
+            .map(|c| c.trim().to_string())
+            .ok_or_else(|| AppError::other("OpenAI returned no choices"))
+    }
+}
@@ -0,0 +1,43 @@
+//! Summarization prompt
+
+use crate::core::components::errors::{AppError, AppResult};
+
+use super::llm::LlmClient;
+
+/// Keeps prompts inside the context window of small local models
+const MAX_INPUT_CHARS: usize = 12_000;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You summarize articles for a writer's research notes. \
+Reply with 3-5 concise bullet points in Markdown covering the main claims, evidence, and \
+conclusions. Do not add an introduction or commentary.";
+
+/// Cut `text` to at most `max_chars` characters on a char boundary
+pub(crate) fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => &text[..cut],
+        None => text,
+    }
+}
+
+/// Summarize an article body
+pub async fn summarize_text(
+    client: &dyn LlmClient,
+    title: &str,
+    text: &str,
+) -> AppResult<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AppError::validation("content", "Nothing to summarize"));
+    }
+
+    let prompt = format!(
+        "Title: {}\n\n{}",
+        title.trim(),
+        truncate_chars(text, MAX_INPUT_CHARS)
+    );
+    let summary = client.chat(SUMMARY_SYSTEM_PROMPT, &prompt).await?;
+    if summary.is_empty() {
+        return Err(AppError::other("Model returned an empty summary"));
+    }
+    Ok(summary)
+}
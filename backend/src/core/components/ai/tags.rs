@@ -0,0 +1,100 @@
+//! Tag suggestion prompt
+//!
+//! The model sees the existing tag vocabulary so it reuses tags rather than
+//! inventing near-duplicates.
+
+use crate::core::components::errors::AppResult;
+
+use super::llm::LlmClient;
+use super::summarize::truncate_chars;
+
+/// Tagging needs less context than summarizing
+const MAX_INPUT_CHARS: usize = 6_000;
+
+/// Longer "tags" are almost always sentences the model slipped in
+const MAX_TAG_CHARS: usize = 40;
+
+const TAGS_SYSTEM_PROMPT: &str = "You tag articles for a writer's research library. \
+Reply with a JSON array of short lowercase tags (1-3 words each) and nothing else. \
+Prefer tags from the existing vocabulary when they fit; only add new tags for topics \
+it does not cover.";
+
+/// Lowercase, trim quotes/hashes, and drop empty or overlong tags
+pub(crate) fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag
+        .trim()
+        .trim_matches(|c: char| c == '"' || c == '\'' || c == '#' || c == '-' || c == '*')
+        .trim()
+        .to_lowercase();
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!tag.is_empty() && tag.chars().count() <= MAX_TAG_CHARS).then_some(tag)
+}
+
+/// Parse the model reply as a JSON array, falling back to comma/line separated text
+pub(crate) fn parse_tag_reply(reply: &str) -> Vec<String> {
+    let json_tags = match (reply.find('['), reply.rfind(']')) {
+        (Some(start), Some(end)) if start < end => {
+            serde_json::from_str::<Vec<String>>(&reply[start..=end]).ok()
+        }
+        _ => None,
+    };
+    let raw = json_tags.unwrap_or_else(|| {
+        reply
+            .split(|c| c == ',' || c == '\n')
+            .map(str::to_string)
+            .collect()
+    });
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw.iter().filter_map(|t| normalize_tag(t)) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Propose up to `max_tags` tags for an article body
+pub async fn suggest_tags_text(
+    client: &dyn LlmClient,
+    title: &str,
+    text: &str,
+    vocabulary: &[String],
+    max_tags: usize,
+) -> AppResult<Vec<String>> {
+    let vocabulary = if vocabulary.is_empty() {
+        "(none yet)".to_string()
+    } else {
+        vocabulary.join(", ")
+    };
+    let prompt = format!(
+        "Existing tags: {}\n\nSuggest up to {} tags.\n\nTitle: {}\n\n{}",
+        vocabulary,
+        max_tags,
+        title.trim(),
+        truncate_chars(text.trim(), MAX_INPUT_CHARS)
+    );
+    let reply = client.chat(TAGS_SYSTEM_PROMPT, &prompt).await?;
+
+    let mut tags = parse_tag_reply(&reply);
+    tags.truncate(max_tags);
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tag_reply() {
+        assert_eq!(
+            parse_tag_reply("Sure! [\"Climate Policy\", \"#energy\", \"energy\"]"),
+            vec!["climate policy", "energy"]
+        );
+        assert_eq!(
+            parse_tag_reply("- AI\n- machine   learning\n"),
+            vec!["ai", "machine learning"]
+        );
+        assert!(parse_tag_reply(&format!("[\"{}\"]", "x".repeat(50))).is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+//! Audit Log Entity
+//! One row per mutating bridge command
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "audit_log")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// `local`, `api-token` or `scoped:<fingerprint>`
+    pub actor: String,
+    pub command: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    pub payload_summary: Option<String>,
+    pub before_summary: Option<String>,
+    pub after_summary: Option<String>,
+    pub status: String, // 'ok' | 'error'
+    pub error: Option<String>,
+    pub request_id: Option<String>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
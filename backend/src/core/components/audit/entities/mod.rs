@@ -0,0 +1,3 @@
+//! Database entities for the audit log
+
+pub mod audit_log;
@@ -0,0 +1,150 @@
+//! Writing, listing and exporting audit rows
+
+use std::fs;
+use std::io::{BufWriter, Write};
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect, Select, Set,
+};
+use serde_json::Value;
+use tracing::{info, instrument};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::pagination::{Listing, Page};
+
+use super::entities::audit_log::{self, Entity as AuditLog};
+use super::summary::summarize;
+use super::types::{
+    AuditEntryDto, AuditExportDto, AuditLogFilter, ListAuditLogInput, NewAuditEntry,
+};
+
+/// Summaries are stored as text; hand them back as JSON where they still parse
+fn summary_value(text: Option<String>) -> Option<Value> {
+    text.map(|t| serde_json::from_str(&t).unwrap_or(Value::String(t)))
+}
+
+fn to_dto(m: audit_log::Model) -> AuditEntryDto {
+    AuditEntryDto {
+        id: m.id,
+        actor: m.actor,
+        command: m.command,
+        entity_type: m.entity_type,
+        entity_id: m.entity_id,
+        payload: summary_value(m.payload_summary),
+        before: summary_value(m.before_summary),
+        after: summary_value(m.after_summary),
+        status: m.status,
+        error: m.error,
+        request_id: m.request_id,
+        created_at: m.created_at,
+    }
+}
+
+fn filtered(filter: &AuditLogFilter) -> Select<AuditLog> {
+    let mut query = AuditLog::find();
+    if let Some(command) = &filter.command {
+        query = query.filter(audit_log::Column::Command.eq(command.as_str()));
+    }
+    if let Some(actor) = &filter.actor {
+        query = query.filter(audit_log::Column::Actor.eq(actor.as_str()));
+    }
+    if let Some(entity_type) = &filter.entity_type {
+        query = query.filter(audit_log::Column::EntityType.eq(entity_type.as_str()));
+    }
+    if let Some(entity_id) = filter.entity_id {
+        query = query.filter(audit_log::Column::EntityId.eq(entity_id));
+    }
+    if let Some(status) = &filter.status {
+        query = query.filter(audit_log::Column::Status.eq(status.as_str()));
+    }
+    if let Some(since) = filter.since {
+        query = query.filter(audit_log::Column::CreatedAt.gte(since));
+    }
+    if let Some(until) = filter.until {
+        query = query.filter(audit_log::Column::CreatedAt.lt(until));
+    }
+    query
+}
+
+/// Append one row to the audit log
+pub async fn record_audit_entry(db: &DatabaseConnection, entry: NewAuditEntry) -> AppResult<()> {
+    let row = audit_log::ActiveModel {
+        actor: Set(entry.actor),
+        command: Set(entry.command),
+        entity_type: Set(entry.entity_type),
+        entity_id: Set(entry.entity_id),
+        payload_summary: Set(entry.payload.as_ref().and_then(summarize)),
+        before_summary: Set(entry.before.as_ref().and_then(summarize)),
+        after_summary: Set(entry.after.as_ref().and_then(summarize)),
+        status: Set(if entry.error.is_some() { "error" } else { "ok" }.to_string()),
+        error: Set(entry.error),
+        request_id: Set(entry.request_id),
+        created_at: Set(Utc::now()),
+        ..Default::default()
+    };
+    row.insert(db).await?;
+    Ok(())
+}
+
+/// Audit rows matching the filter, newest first
+#[instrument(skip(db))]
+pub async fn list_audit_log_handler(
+    db: &DatabaseConnection,
+    input: ListAuditLogInput,
+) -> AppResult<Listing<AuditEntryDto>> {
+    let limit = input.limit.unwrap_or(50);
+    let offset = input.offset.unwrap_or(0);
+    let rows = filtered(&input.filter)
+        .order_by_desc(audit_log::Column::Id)
+        .limit(limit)
+        .offset(offset)
+        .all(db)
+        .await?;
+    let items: Vec<AuditEntryDto> = rows.into_iter().map(to_dto).collect();
+    if input.envelope != Some(true) {
+        return Ok(Listing::Items(items));
+    }
+    let total = filtered(&input.filter).count(db).await?;
+    Ok(Listing::Page(Page::new(items, total, limit, offset)))
+}
+
+/// Write matching rows, oldest first, to a JSON Lines file under the exports
+/// directory
+#[instrument(skip(db, storage))]
+pub async fn export_audit_log_handler(
+    db: &DatabaseConnection,
+    storage: &StorageConfig,
+    filter: AuditLogFilter,
+) -> AppResult<AuditExportDto> {
+    let rows = filtered(&filter)
+        .order_by_asc(audit_log::Column::Id)
+        .all(db)
+        .await?;
+
+    fs::create_dir_all(&storage.export_dir).map_err(|e| {
+        AppError::file_operation("create directory", storage.export_dir.to_string_lossy(), e)
+    })?;
+    let filename = format!("audit_log_{}.jsonl", Utc::now().format("%Y%m%d_%H%M%S"));
+    let path = storage.export_dir.join(filename);
+    let path_str = path.to_string_lossy().to_string();
+    let write_err =
+        |e: std::io::Error| AppError::file_operation("write audit export", &path_str, e);
+
+    let file = fs::File::create(&path).map_err(write_err)?;
+    let mut out = BufWriter::new(file);
+    let count = rows.len();
+    for row in rows {
+        serde_json::to_writer(&mut out, &to_dto(row)).map_err(|e| write_err(e.into()))?;
+        writeln!(out).map_err(write_err)?;
+    }
+    out.flush().map_err(write_err)?;
+
+    info!(path = %path_str, count, "Audit log exported");
+    Ok(AuditExportDto {
+        file_path: path_str,
+        count,
+    })
+}
@@ -0,0 +1,17 @@
+//! Audit log
+//!
+//! Records who ran which mutating bridge command, on what, and when, with
+//! redacted before/after summaries:
+//! - entities: `audit_log` table
+//! - types: Inputs and DTOs
+//! - summary: Redaction and truncation of stored JSON
+//! - handlers: Recording, listing and JSON Lines export
+
+pub mod entities;
+pub mod types;
+pub mod summary;
+pub mod handlers;
+
+pub use types::{AuditEntryDto, AuditExportDto, AuditLogFilter, ListAuditLogInput, NewAuditEntry};
+
+pub use handlers::{export_audit_log_handler, list_audit_log_handler, record_audit_entry};
@@ -0,0 +1,154 @@
+//! Redacted, size-bounded JSON summaries for audit rows
+//!
+//! Payloads and results can carry credentials (setup config, site cookies,
+//! API keys) and whole article bodies. Values under secret-looking keys are
+//! replaced, long strings and arrays are cut, and the encoded summary is
+//! capped so one row stays small.
+
+use serde_json::Value;
+
+const REDACTED: &str = "[redacted]";
+const MAX_STRING_CHARS: usize = 200;
+const MAX_ARRAY_ITEMS: usize = 20;
+const MAX_DEPTH: usize = 6;
+const MAX_SUMMARY_BYTES: usize = 8 * 1024;
+
+/// Key words whose values are never stored
+const SECRET_WORDS: &[&str] = &[
+    "password",
+    "passphrase",
+    "secret",
+    "token",
+    "key",
+    "apikey",
+    "cookie",
+    "cookies",
+    "header",
+    "headers",
+    "credential",
+    "credentials",
+    "authorization",
+];
+
+/// Split `apiKey` / `api_key` / `api-key` into lowercase words
+fn key_words(key: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for c in key.chars() {
+        if c == '_' || c == '-' || c == '.' || c == ' ' {
+            words.push(std::mem::take(&mut current));
+        } else if c.is_uppercase() {
+            words.push(std::mem::take(&mut current));
+            current.extend(c.to_lowercase());
+        } else {
+            current.push(c);
+        }
+    }
+    words.push(current);
+    words.retain(|w| !w.is_empty());
+    words
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let words = key_words(key);
+    words.iter().any(|w| SECRET_WORDS.contains(&w.as_str()))
+        || SECRET_WORDS.contains(&words.concat().as_str())
+}
+
+fn shorten(value: &Value, depth: usize) -> Value {
+    match value {
+        Value::String(s) if s.chars().count() > MAX_STRING_CHARS => {
+            let cut: String = s.chars().take(MAX_STRING_CHARS).collect();
+            Value::String(format!("{}…", cut))
+        }
+        Value::Array(items) if depth >= MAX_DEPTH => {
+            Value::String(format!("[{} items]", items.len()))
+        }
+        Value::Object(map) if depth >= MAX_DEPTH => {
+            Value::String(format!("{{{} fields}}", map.len()))
+        }
+        Value::Array(items) => {
+            let mut kept: Vec<Value> = items
+                .iter()
+                .take(MAX_ARRAY_ITEMS)
+                .map(|item| shorten(item, depth + 1))
+                .collect();
+            if items.len() > MAX_ARRAY_ITEMS {
+                kept.push(Value::String(format!(
+                    "… {} more",
+                    items.len() - MAX_ARRAY_ITEMS
+                )));
+            }
+            Value::Array(kept)
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, v)| {
+                    let v = if is_secret_key(key) && !v.is_null() {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        shorten(v, depth + 1)
+                    };
+                    (key.clone(), v)
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Redacted, shortened copy of a JSON value
+pub fn redact(value: &Value) -> Value {
+    shorten(value, 0)
+}
+
+/// Redacted JSON text for an audit column; `None` for null values
+pub fn summarize(value: &Value) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let mut text = redact(value).to_string();
+    if text.len() > MAX_SUMMARY_BYTES {
+        let mut end = MAX_SUMMARY_BYTES;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push('…');
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_summarize_redacts_and_truncates() {
+        let payload = json!({
+            "domain": "example.com",
+            "headers": {"Authorization": "Bearer abc"},
+            "input": {"apiKey": "sk-123", "masterKey": "m", "title": "x".repeat(500)},
+            "newsdata_api_key": "k",
+            "tags": (0..30).collect::<Vec<_>>(),
+            "monkey": "not a key",
+        });
+        let summary: Value = serde_json::from_str(&summarize(&payload).unwrap()).unwrap();
+        assert_eq!(summary["domain"], "example.com");
+        assert_eq!(summary["headers"], REDACTED);
+        assert_eq!(summary["input"]["apiKey"], REDACTED);
+        assert_eq!(summary["input"]["masterKey"], REDACTED);
+        assert_eq!(summary["newsdata_api_key"], REDACTED);
+        assert_eq!(summary["monkey"], "not a key");
+        assert_eq!(
+            summary["input"]["title"].as_str().unwrap().chars().count(),
+            MAX_STRING_CHARS + 1
+        );
+        assert_eq!(
+            summary["tags"].as_array().unwrap().len(),
+            MAX_ARRAY_ITEMS + 1
+        );
+        assert_eq!(summarize(&Value::Null), None);
+    }
+}
@@ -0,0 +1,76 @@
+//! DTOs for the audit log
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A mutating command about to be written to the audit log
+#[derive(Debug, Clone)]
+pub struct NewAuditEntry {
+    pub actor: String,
+    pub command: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// The command's payload, i.e. the requested change
+    pub payload: Option<Value>,
+    /// The entity as it was before the command, when it could be read back
+    pub before: Option<Value>,
+    /// The command's result
+    pub after: Option<Value>,
+    pub error: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// Filters shared by listing and exporting
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogFilter {
+    pub command: Option<String>,
+    pub actor: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// `ok` or `error`
+    pub status: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Page of the audit log
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAuditLogInput {
+    #[serde(flatten)]
+    pub filter: AuditLogFilter,
+    /// Default: 50
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+    pub envelope: Option<bool>,
+}
+
+/// Audit log row, newest first in listings
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntryDto {
+    pub id: i64,
+    pub actor: String,
+    pub command: String,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+    /// Summaries are redacted and truncated JSON
+    pub payload: Option<Value>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub status: String,
+    pub error: Option<String>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Written JSON Lines file
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditExportDto {
+    pub file_path: String,
+    pub count: usize,
+}
@@ -0,0 +1,327 @@
+//! Configuration diagnostics for the setup UI
+//!
+//! `check_config` looks at what a working install needs beyond a config that
+//! parses: writable storage directories, a usable master key, a reachable
+//! database, API keys for the enabled feed sources, the HTTP port and free
+//! disk space. Every check reports `pass`, `warn` or `fail`; a failing check
+//! doesn't stop the others.
+
+use std::fs::{self, OpenOptions};
+use std::net::TcpListener;
+use std::path::Path;
+use std::time::Instant;
+
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Serialize;
+use tracing::instrument;
+
+use super::loader::Vars;
+use super::types::{AppConfig, HttpConfig};
+use crate::core::components::crypto;
+use crate::research::components::feed::entities::feed_sources::{self, Entity as FeedSources};
+use crate::research::components::feed::feed_sources::KEYED_SOURCE_TYPES;
+
+/// Free space below which `disk_space` fails
+const MIN_FREE_BYTES: u64 = 100 * 1024 * 1024;
+/// Free space below which `disk_space` warns
+const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Outcome of one check
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCheck {
+    /// `directories`, `master_key`, `database`, `feed_sources`, `http_port`
+    /// or `disk_space`
+    pub check: String,
+    /// Directory, feed source or port the check looked at
+    pub target: Option<String>,
+    pub status: CheckStatus,
+    pub message: String,
+    /// How to fix a warning or failure
+    pub suggestion: Option<String>,
+}
+
+/// Result of `check_config`
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDoctorReport {
+    /// Worst status of any check
+    pub status: CheckStatus,
+    pub checks: Vec<ConfigCheck>,
+}
+
+impl ConfigCheck {
+    fn new(check: &str, target: Option<String>, status: CheckStatus, message: String) -> Self {
+        Self {
+            check: check.to_string(),
+            target,
+            status,
+            message,
+            suggestion: None,
+        }
+    }
+
+    fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+/// Run every check against `config`, the configuration the HTTP bridge
+/// was started with
+#[instrument(skip_all)]
+pub async fn check_config(config: &AppConfig, db: &DatabaseConnection) -> ConfigDoctorReport {
+    let mut checks = check_directories(config);
+    checks.push(check_master_key());
+    checks.push(check_database(db).await);
+    checks.extend(check_feed_sources(db).await);
+    checks.push(check_http_port(config.http.port));
+    checks.extend(check_disk_space(config));
+
+    let status = checks
+        .iter()
+        .map(|c| c.status)
+        .max()
+        .unwrap_or(CheckStatus::Pass);
+    ConfigDoctorReport { status, checks }
+}
+
+fn check_directories(config: &AppConfig) -> Vec<ConfigCheck> {
+    let storage = &config.storage;
+    [
+        &storage.root,
+        &storage.data_dir,
+        &storage.logs_dir,
+        &storage.cache_dir,
+        &storage.backup_dir,
+        &storage.export_dir,
+        &storage.media_dir,
+        &storage.import_dir,
+    ]
+    .into_iter()
+    .map(|dir| check_directory(dir))
+    .collect()
+}
+
+fn check_directory(dir: &Path) -> ConfigCheck {
+    let target = Some(dir.display().to_string());
+    if !dir.is_dir() {
+        return ConfigCheck::new(
+            "directories",
+            target,
+            CheckStatus::Fail,
+            "Directory does not exist".to_string(),
+        )
+        .suggest("Create it, or restart Cockpit to have it created");
+    }
+    let probe = dir.join(".cockpit-write-test");
+    let written = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map(|_| fs::remove_file(&probe));
+    match written {
+        Ok(_) => ConfigCheck::new(
+            "directories",
+            target,
+            CheckStatus::Pass,
+            "Writable".to_string(),
+        ),
+        Err(e) => ConfigCheck::new(
+            "directories",
+            target,
+            CheckStatus::Fail,
+            format!("Not writable: {}", e),
+        )
+        .suggest("Give the user running Cockpit write access to it"),
+    }
+}
+
+fn check_master_key() -> ConfigCheck {
+    let round_trip = crypto::encrypt_api_key("check_config")
+        .and_then(|encrypted| crypto::decrypt_api_key(&encrypted));
+    match round_trip {
+        Ok(_) => ConfigCheck::new(
+            "master_key",
+            None,
+            CheckStatus::Pass,
+            "Master key is set and encrypts".to_string(),
+        ),
+        Err(e) => ConfigCheck::new("master_key", None, CheckStatus::Fail, e)
+            .suggest("Set COCKPIT_MASTER_KEY to a key from generate_master_key_command"),
+    }
+}
+
+async fn check_database(db: &DatabaseConnection) -> ConfigCheck {
+    let started = Instant::now();
+    match db.ping().await {
+        Ok(()) => ConfigCheck::new(
+            "database",
+            None,
+            CheckStatus::Pass,
+            format!(
+                "Connected ({:?}, {} ms)",
+                db.get_database_backend(),
+                started.elapsed().as_millis()
+            ),
+        ),
+        Err(e) => ConfigCheck::new(
+            "database",
+            None,
+            CheckStatus::Fail,
+            format!("Database unreachable: {}", e),
+        )
+        .suggest("Check DATABASE_URL and that the database is running"),
+    }
+}
+
+/// One check per enabled feed source whose type needs an API key
+async fn check_feed_sources(db: &DatabaseConnection) -> Vec<ConfigCheck> {
+    let sources = match FeedSources::find()
+        .filter(feed_sources::Column::Enabled.eq(1))
+        .filter(feed_sources::Column::SourceType.is_in(KEYED_SOURCE_TYPES.iter().copied()))
+        .all(db)
+        .await
+    {
+        Ok(sources) => sources,
+        Err(e) => {
+            return vec![ConfigCheck::new(
+                "feed_sources",
+                None,
+                CheckStatus::Fail,
+                format!("Failed to list feed sources: {}", e),
+            )]
+        }
+    };
+    if sources.is_empty() {
+        return vec![ConfigCheck::new(
+            "feed_sources",
+            None,
+            CheckStatus::Pass,
+            "No enabled feed source needs an API key".to_string(),
+        )];
+    }
+
+    sources
+        .into_iter()
+        .map(|source| {
+            let target = Some(format!("{} ({})", source.name, source.source_type));
+            let key = source
+                .api_key_encrypted
+                .as_deref()
+                .map(crypto::decrypt_api_key);
+            match key {
+                Some(Ok(key)) if !key.trim().is_empty() => ConfigCheck::new(
+                    "feed_sources",
+                    target,
+                    CheckStatus::Pass,
+                    "API key set".to_string(),
+                ),
+                Some(Err(e)) => ConfigCheck::new(
+                    "feed_sources",
+                    target,
+                    CheckStatus::Fail,
+                    format!("API key can't be decrypted: {}", e),
+                )
+                .suggest("Save the API key again; it was encrypted with another master key"),
+                _ => ConfigCheck::new(
+                    "feed_sources",
+                    target,
+                    CheckStatus::Fail,
+                    "No API key set".to_string(),
+                )
+                .suggest("Add the API key to the feed source, or disable it"),
+            }
+        })
+        .collect()
+}
+
+/// The bridge holds `listening`; a port changed for the next start must be
+/// free
+fn check_http_port(listening: u16) -> ConfigCheck {
+    let next = HttpConfig::from_vars(&Vars::from_env()).map_or(listening, |http| http.port);
+    let target = Some(next.to_string());
+    if next == listening {
+        return ConfigCheck::new(
+            "http_port",
+            target,
+            CheckStatus::Pass,
+            format!("HTTP bridge listening on port {}", listening),
+        );
+    }
+    match TcpListener::bind(("0.0.0.0", next)) {
+        Ok(_) => ConfigCheck::new(
+            "http_port",
+            target,
+            CheckStatus::Pass,
+            format!("Port {} is free for the next start", next),
+        ),
+        Err(e) => ConfigCheck::new(
+            "http_port",
+            target,
+            CheckStatus::Fail,
+            format!(
+                "Port {}, used from the next start, is unavailable: {}",
+                next, e
+            ),
+        )
+        .suggest("Stop whatever uses the port or change COCKPIT_HTTP_PORT"),
+    }
+}
+
+/// Free space where the database and backups are written
+fn check_disk_space(config: &AppConfig) -> Vec<ConfigCheck> {
+    let mut dirs = vec![&config.storage.data_dir, &config.storage.backup_dir];
+    dirs.dedup();
+    dirs.into_iter()
+        .map(|dir| {
+            let target = Some(dir.display().to_string());
+            let available = match fs2::available_space(dir) {
+                Ok(available) => available,
+                Err(e) => {
+                    return ConfigCheck::new(
+                        "disk_space",
+                        target,
+                        CheckStatus::Warn,
+                        format!("Free space unknown: {}", e),
+                    )
+                }
+            };
+            let message = format!("{:.2} GB free", available as f64 / 1_073_741_824.0);
+            if available < MIN_FREE_BYTES {
+                ConfigCheck::new("disk_space", target, CheckStatus::Fail, message)
+                    .suggest("Free up space; backups and migrations will fail")
+            } else if available < LOW_FREE_BYTES {
+                ConfigCheck::new("disk_space", target, CheckStatus::Warn, message)
+                    .suggest("Free up space or lower the backup retention")
+            } else {
+                ConfigCheck::new("disk_space", target, CheckStatus::Pass, message)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_directory_fails() {
+        let dir = std::env::temp_dir().join(format!("cockpit-doctor-{}", std::process::id()));
+        assert_eq!(check_directory(&dir).status, CheckStatus::Fail);
+
+        fs::create_dir_all(&dir).unwrap();
+        let check = check_directory(&dir);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
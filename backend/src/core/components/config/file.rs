@@ -0,0 +1,481 @@
+//! `~/.cockpit/config.toml`
+//!
+//! Settings can live in a TOML file grouped by section instead of one
+//! environment variable each. Every key stands for the variable in
+//! [`KEYS`] and is applied like the `.env` files are (see
+//! [`super::loader`]): only variables that are not already set, so the
+//! environment and `.env` files override the file. `COCKPIT_CONFIG` points
+//! at another file.
+//!
+//! ```toml
+//! [database]
+//! journal_mode = "wal"
+//! busy_timeout_ms = 5000
+//!
+//! [email]
+//! recipients = ["me@example.com", "editor@example.com"]
+//!
+//! [http.scoped_tokens]
+//! "ci-token" = ["read", "research:*"]
+//! ```
+//!
+//! Unknown keys and values of the wrong type are errors naming the key, and
+//! so are invalid values of settings that came from the file.
+
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+use super::loader::Vars;
+use crate::core::components::errors::AppError;
+
+/// Type a key's value must have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    String,
+    Integer,
+    Bool,
+    /// Array of strings, joined with commas
+    List,
+    /// Table of token to array of scopes, as `COCKPIT_HTTP_SCOPED_TOKENS`
+    Scopes,
+}
+
+/// File key, the environment variable it sets, and its type
+const KEYS: &[(&str, &str, Kind)] = &[
+    ("database.url", "DATABASE_URL", Kind::String),
+    ("database.path", "COCKPIT_DB_PATH", Kind::String),
+    (
+        "database.max_connections",
+        "DB_MAX_CONNECTIONS",
+        Kind::Integer,
+    ),
+    (
+        "database.min_connections",
+        "DB_MIN_CONNECTIONS",
+        Kind::Integer,
+    ),
+    ("database.encryption", "DB_ENCRYPTION", Kind::String),
+    ("database.journal_mode", "DB_JOURNAL_MODE", Kind::String),
+    ("database.synchronous", "DB_SYNCHRONOUS", Kind::String),
+    (
+        "database.busy_timeout_ms",
+        "DB_BUSY_TIMEOUT_MS",
+        Kind::Integer,
+    ),
+    ("database.cache_size_kb", "DB_CACHE_SIZE_KB", Kind::Integer),
+    ("database.mmap_size_mb", "DB_MMAP_SIZE_MB", Kind::Integer),
+    (
+        "database.startup_integrity_check",
+        "DB_STARTUP_INTEGRITY_CHECK",
+        Kind::String,
+    ),
+    (
+        "database.pre_migration_backup",
+        "DB_PRE_MIGRATION_BACKUP",
+        Kind::Bool,
+    ),
+    ("logging.level", "LOG_LEVEL", Kind::String),
+    ("logging.format", "LOG_FORMAT", Kind::String),
+    ("logging.dir", "LOGS_DIR", Kind::String),
+    ("logging.max_size_mb", "LOG_MAX_SIZE_MB", Kind::Integer),
+    ("logging.max_files", "LOG_MAX_FILES", Kind::Integer),
+    ("logging.console", "LOG_CONSOLE", Kind::Bool),
+    (
+        "logging.otlp_endpoint",
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+        Kind::String,
+    ),
+    (
+        "logging.otlp_service_name",
+        "OTEL_SERVICE_NAME",
+        Kind::String,
+    ),
+    ("newsdata.api_key", "NEWSDATA_API_KEY", Kind::String),
+    (
+        "newsdata.daily_limit",
+        "NEWSDATA_DAILY_LIMIT",
+        Kind::Integer,
+    ),
+    (
+        "newsdata.timeout_secs",
+        "NEWSDATA_TIMEOUT_SECS",
+        Kind::Integer,
+    ),
+    (
+        "newsdata.max_retries",
+        "NEWSDATA_MAX_RETRIES",
+        Kind::Integer,
+    ),
+    ("storage.root", "STORAGE_ROOT", Kind::String),
+    ("storage.max_size_gb", "STORAGE_MAX_SIZE_GB", Kind::Integer),
+    (
+        "storage.log_retention_days",
+        "STORAGE_LOG_RETENTION_DAYS",
+        Kind::Integer,
+    ),
+    (
+        "storage.cache_retention_days",
+        "STORAGE_CACHE_RETENTION_DAYS",
+        Kind::Integer,
+    ),
+    (
+        "storage.export_retention_days",
+        "STORAGE_EXPORT_RETENTION_DAYS",
+        Kind::Integer,
+    ),
+    ("storage.writing_sync_dir", "WRITING_SYNC_DIR", Kind::String),
+    (
+        "storage.writing_git_autocommit",
+        "WRITING_GIT_AUTOCOMMIT",
+        Kind::Bool,
+    ),
+    (
+        "storage.writing_git_remote",
+        "WRITING_GIT_REMOTE",
+        Kind::String,
+    ),
+    ("backup.compression", "BACKUP_COMPRESSION", Kind::String),
+    (
+        "backup.compression_level",
+        "BACKUP_COMPRESSION_LEVEL",
+        Kind::Integer,
+    ),
+    ("backup.encryption", "BACKUP_ENCRYPTION", Kind::String),
+    ("backup.passphrase", "BACKUP_PASSPHRASE", Kind::String),
+    (
+        "backup.retention_days",
+        "STORAGE_BACKUP_RETENTION_DAYS",
+        Kind::Integer,
+    ),
+    (
+        "backup.keep_last",
+        "STORAGE_BACKUP_KEEP_LAST",
+        Kind::Integer,
+    ),
+    (
+        "backup.keep_weekly",
+        "STORAGE_BACKUP_KEEP_WEEKLY",
+        Kind::Integer,
+    ),
+    (
+        "backup.keep_monthly",
+        "STORAGE_BACKUP_KEEP_MONTHLY",
+        Kind::Integer,
+    ),
+    ("crypto.master_key", "COCKPIT_MASTER_KEY", Kind::String),
+    ("email.smtp_host", "SMTP_HOST", Kind::String),
+    ("email.smtp_port", "SMTP_PORT", Kind::Integer),
+    ("email.smtp_username", "SMTP_USERNAME", Kind::String),
+    ("email.smtp_password", "SMTP_PASSWORD", Kind::String),
+    ("email.smtp_tls", "SMTP_TLS", Kind::String),
+    ("email.timeout_secs", "SMTP_TIMEOUT_SECS", Kind::Integer),
+    ("email.from", "SMTP_FROM", Kind::String),
+    ("email.recipients", "NEWSLETTER_RECIPIENTS", Kind::List),
+    (
+        "email.template_path",
+        "NEWSLETTER_TEMPLATE_PATH",
+        Kind::String,
+    ),
+    ("embeddings.provider", "EMBEDDINGS_PROVIDER", Kind::String),
+    ("embeddings.model", "EMBEDDINGS_MODEL", Kind::String),
+    (
+        "embeddings.batch_size",
+        "EMBEDDINGS_BATCH_SIZE",
+        Kind::Integer,
+    ),
+    (
+        "embeddings.timeout_secs",
+        "EMBEDDINGS_TIMEOUT_SECS",
+        Kind::Integer,
+    ),
+    ("embeddings.openai_api_key", "OPENAI_API_KEY", Kind::String),
+    (
+        "embeddings.openai_base_url",
+        "OPENAI_BASE_URL",
+        Kind::String,
+    ),
+    ("ollama.base_url", "OLLAMA_BASE_URL", Kind::String),
+    ("ai.ollama_model", "OLLAMA_MODEL", Kind::String),
+    ("ai.timeout_secs", "AI_TIMEOUT_SECS", Kind::Integer),
+    ("http.port", "COCKPIT_HTTP_PORT", Kind::Integer),
+    (
+        "http.compression_min_bytes",
+        "COCKPIT_HTTP_COMPRESSION_MIN_BYTES",
+        Kind::Integer,
+    ),
+    ("http.token", "COCKPIT_HTTP_TOKEN", Kind::String),
+    (
+        "http.scoped_tokens",
+        "COCKPIT_HTTP_SCOPED_TOKENS",
+        Kind::Scopes,
+    ),
+    (
+        "http_reader.timeout_secs",
+        "HTTP_READER_TIMEOUT_SECS",
+        Kind::Integer,
+    ),
+    (
+        "http_reader.connect_timeout_secs",
+        "HTTP_READER_CONNECT_TIMEOUT_SECS",
+        Kind::Integer,
+    ),
+    ("http_reader.proxy", "HTTP_READER_PROXY", Kind::String),
+    (
+        "http_reader.user_agent",
+        "HTTP_READER_USER_AGENT",
+        Kind::String,
+    ),
+    ("http_reader.retries", "HTTP_READER_RETRIES", Kind::Integer),
+    (
+        "http_reader.retry_backoff_ms",
+        "HTTP_READER_RETRY_BACKOFF_MS",
+        Kind::Integer,
+    ),
+    (
+        "http_api.timeout_secs",
+        "HTTP_API_TIMEOUT_SECS",
+        Kind::Integer,
+    ),
+    (
+        "http_api.connect_timeout_secs",
+        "HTTP_API_CONNECT_TIMEOUT_SECS",
+        Kind::Integer,
+    ),
+    ("http_api.proxy", "HTTP_API_PROXY", Kind::String),
+    ("http_api.user_agent", "HTTP_API_USER_AGENT", Kind::String),
+    ("http_api.retries", "HTTP_API_RETRIES", Kind::Integer),
+    (
+        "http_api.retry_backoff_ms",
+        "HTTP_API_RETRY_BACKOFF_MS",
+        Kind::Integer,
+    ),
+    ("error_reporting.sentry_dsn", "SENTRY_DSN", Kind::String),
+    (
+        "error_reporting.environment",
+        "SENTRY_ENVIRONMENT",
+        Kind::String,
+    ),
+    (
+        "error_reporting.min_severity",
+        "ERROR_REPORTING_MIN_SEVERITY",
+        Kind::String,
+    ),
+    ("export.pdf_browser_path", "PDF_BROWSER_PATH", Kind::String),
+];
+
+/// Settings read from the config file
+#[derive(Debug, Default)]
+pub(crate) struct ConfigFile {
+    pub path: Option<PathBuf>,
+    /// File key, environment variable and value, sorted by key
+    pub values: Vec<(String, &'static str, String)>,
+}
+
+impl ConfigFile {
+    /// `COCKPIT_CONFIG`, else `~/.cockpit/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        match std::env::var("COCKPIT_CONFIG") {
+            Ok(path) if !path.trim().is_empty() => Some(PathBuf::from(path)),
+            _ => dirs::home_dir().map(|home| home.join(".cockpit/config.toml")),
+        }
+    }
+
+    /// Read the config file; empty when there is none
+    pub fn load() -> Result<Self, AppError> {
+        let Some(path) = Self::default_path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::read(&path)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, AppError> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| AppError::file_operation("read", path.to_string_lossy(), e))?;
+        let table: Table = text.parse().map_err(|e: toml::de::Error| {
+            AppError::config_validation(
+                path.to_string_lossy(),
+                format!("Not valid TOML: {}", e.message()),
+            )
+        })?;
+        let mut values = Vec::new();
+        flatten(&table, "", &mut values)?;
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            values,
+        })
+    }
+
+    /// Name the file key rather than the variable in an error about a
+    /// setting that came from the file into `vars`
+    pub fn attribute(&self, error: AppError, vars: &Vars) -> AppError {
+        let AppError::ConfigValidation {
+            field,
+            reason,
+            suggestion,
+        } = error
+        else {
+            return error;
+        };
+        let from_file = self.values.iter().find(|(_, var, value)| {
+            *var == field && vars.var(var).ok().as_deref() == Some(value.as_str())
+        });
+        match (from_file, &self.path) {
+            (Some((key, _, _)), Some(path)) => AppError::ConfigValidation {
+                field: format!("{} in {}", key, path.display()),
+                reason,
+                suggestion,
+            },
+            _ => AppError::ConfigValidation {
+                field,
+                reason,
+                suggestion,
+            },
+        }
+    }
+}
+
+fn flatten(
+    table: &Table,
+    prefix: &str,
+    values: &mut Vec<(String, &'static str, String)>,
+) -> Result<(), AppError> {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match KEYS.iter().find(|(k, _, _)| *k == key) {
+            Some((_, var, kind)) => values.push((key.clone(), *var, to_env(&key, value, *kind)?)),
+            None => match value {
+                Value::Table(section) if prefix.is_empty() && is_section(&key) => {
+                    flatten(section, &key, values)?
+                }
+                _ => {
+                    let suggestion = if prefix.is_empty() {
+                        let mut sections: Vec<&str> = KEYS
+                            .iter()
+                            .filter_map(|(k, _, _)| Some(k.split_once('.')?.0))
+                            .collect();
+                        sections.dedup();
+                        format!("Sections: {}", sections.join(", "))
+                    } else {
+                        let known: Vec<&str> = KEYS
+                            .iter()
+                            .filter_map(|(k, _, _)| k.strip_prefix(prefix)?.strip_prefix('.'))
+                            .collect();
+                        format!("Settings in [{}]: {}", prefix, known.join(", "))
+                    };
+                    return Err(AppError::config_validation(key, "Unknown setting")
+                        .with_suggestion(suggestion));
+                }
+            },
+        }
+    }
+    Ok(())
+}
+
+fn is_section(name: &str) -> bool {
+    KEYS.iter()
+        .any(|(k, _, _)| k.split_once('.').map(|(s, _)| s) == Some(name))
+}
+
+/// The value as the environment variable would spell it
+fn to_env(key: &str, value: &Value, kind: Kind) -> Result<String, AppError> {
+    let strings = |value: &Value| -> Option<Vec<String>> {
+        value
+            .as_array()?
+            .iter()
+            .map(|v| v.as_str().map(str::to_string))
+            .collect()
+    };
+    let converted = match (kind, value) {
+        (Kind::String, Value::String(s)) => Some(s.clone()),
+        (Kind::Integer, Value::Integer(n)) => Some(n.to_string()),
+        (Kind::Bool, Value::Boolean(b)) => Some(b.to_string()),
+        (Kind::List, _) => strings(value).map(|items| items.join(",")),
+        (Kind::Scopes, Value::Table(tokens)) => tokens
+            .iter()
+            .map(|(token, scopes)| Some(format!("{}={}", token, strings(scopes)?.join(","))))
+            .collect::<Option<Vec<_>>>()
+            .map(|entries| entries.join(";")),
+        _ => None,
+    };
+    converted.ok_or_else(|| {
+        let expected = match kind {
+            Kind::String => "a string",
+            Kind::Integer => "an integer",
+            Kind::Bool => "true or false",
+            Kind::List => "an array of strings",
+            Kind::Scopes => "a table of token = [scopes]",
+        };
+        AppError::config_validation(
+            key,
+            format!("Expected {}, found {}", expected, value.type_str()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Vec<(String, &'static str, String)>, AppError> {
+        let mut values = Vec::new();
+        flatten(&text.parse().unwrap(), "", &mut values)?;
+        Ok(values)
+    }
+
+    #[test]
+    fn test_flatten_to_env() {
+        let values = parse(
+            r#"
+            [database]
+            journal_mode = "delete"
+            busy_timeout_ms = 5000
+
+            [email]
+            recipients = ["a@example.com", "b@example.com"]
+
+            [http.scoped_tokens]
+            ci = ["read", "research:*"]
+            "#,
+        )
+        .unwrap();
+        let env: Vec<_> = values
+            .iter()
+            .map(|(_, var, v)| (*var, v.as_str()))
+            .collect();
+        assert_eq!(
+            env,
+            [
+                ("DB_BUSY_TIMEOUT_MS", "5000"),
+                ("DB_JOURNAL_MODE", "delete"),
+                ("NEWSLETTER_RECIPIENTS", "a@example.com,b@example.com"),
+                ("COCKPIT_HTTP_SCOPED_TOKENS", "ci=read,research:*"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_key() {
+        let field = |text: &str| match parse(text) {
+            Err(AppError::ConfigValidation { field, .. }) => field,
+            other => panic!("expected a validation error, got {:?}", other),
+        };
+        assert_eq!(
+            field("[database]\nbusy_timeout_ms = \"5s\""),
+            "database.busy_timeout_ms"
+        );
+        assert_eq!(
+            field("[database]\nbusy_timeout = 5000"),
+            "database.busy_timeout"
+        );
+        assert_eq!(field("[databse]\nurl = \"sqlite:x\""), "databse");
+        assert_eq!(field("[email]\nrecipients = [1]"), "email.recipients");
+    }
+}
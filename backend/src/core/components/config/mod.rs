@@ -1,6 +1,7 @@
 //! Centralized, type-safe configuration for Architect Cockpit
 //!
-//! All configuration is loaded from environment variables with:
+//! All configuration is loaded from environment variables, optionally set
+//! from `~/.cockpit/config.toml`, with:
 //! - Type safety
 //! - Validation
 //! - Sensible defaults
@@ -8,15 +9,35 @@
 //!
 //! Refactored into focused modules:
 //! - types: Configuration struct definitions
+//! - file: The TOML config file, layered under the environment
 //! - loader: Environment variable loading logic
+//! - reload: Swapping in a re-read configuration at runtime
+//! - doctor: Checks of what the configuration points at, for the setup UI
 //! - validation: Directory setup and validation
 
+mod doctor;
+mod file;
 mod loader;
+mod reload;
 mod types;
 mod validation;
 
 // Re-export all public types
-pub use types::{AppConfig, LoggingConfig, StorageConfig};
+pub use types::{
+    is_postgres_url, AiConfig, AppConfig, BackupCompression, BackupEncryption,
+    DatabaseConfig, DatabaseEncryption, EmailConfig, EmbeddingsConfig, EmbeddingsProvider,
+    ErrorReportingConfig, HttpClientConfig, HttpClientsConfig, HttpConfig, IntegrityCheck,
+    JournalMode, LogFormat, LoggingConfig, SmtpTls, StorageConfig, Synchronous,
+};
+// Only the bridge's token tests build scoped tokens by hand
+#[cfg(test)]
+pub(crate) use types::ScopedToken;
+
+pub use doctor::{check_config, ConfigDoctorReport};
+pub use reload::{reload_config, workspaces_dir, ConfigReload, SharedConfig};
+// Only the test database builds a configuration from variables by hand
+#[cfg(test)]
+pub(crate) use loader::Vars;
 
 // Re-export utilities
 pub use validation::ensure_directories;
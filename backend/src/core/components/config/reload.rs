@@ -0,0 +1,264 @@
+//! Reloading the configuration while running
+//!
+//! `reload_config` (or SIGHUP) reads the environment, `.env` files and
+//! config.toml again and swaps the result into [`SharedConfig`]. Code takes
+//! the current config when it needs it, so most settings apply from the next
+//! request or task run, and the log level is changed through [`LogFilter`].
+//! What is only read at startup (the database pool, the HTTP listener and
+//! clients, log outputs, error reporting, the master key and the storage
+//! directories) keeps its value until a restart and is reported as
+//! `restartRequired`.
+//!
+//! A workspace's [`SharedConfig`] follows the same reloads but points the
+//! database, backups, media, exports and imports at the workspace's own
+//! files.
+
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use serde::Serialize;
+use tracing::{info, instrument};
+
+use super::types::{AppConfig, CryptoConfig, HttpConfig, LoggingConfig, StorageConfig};
+use crate::core::components::errors::AppResult;
+use crate::core::components::logging::LogFilter;
+
+/// The configuration in effect, replaced by reloads
+#[derive(Clone)]
+pub struct SharedConfig {
+    inner: Arc<RwLock<Arc<AppConfig>>>,
+    /// Workspace whose database and backups replace the configured ones
+    workspace: Option<Arc<str>>,
+}
+
+impl SharedConfig {
+    pub fn new(config: AppConfig) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Arc::new(config))),
+            workspace: None,
+        }
+    }
+
+    /// The same configuration, with the database and backups of workspace
+    /// `name`
+    pub fn for_workspace(&self, name: &str) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            workspace: Some(name.into()),
+        }
+    }
+
+    /// Snapshot of the current configuration
+    pub fn current(&self) -> Arc<AppConfig> {
+        let current = self
+            .inner
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        match &self.workspace {
+            Some(name) => Arc::new(in_workspace(&current, name)),
+            None => current,
+        }
+    }
+
+    /// Use `master_key` from now on, once `rotate_master_key` has written it
+    /// to the key file
+    pub(crate) fn set_master_key(&self, master_key: String) {
+        let mut current = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+        *current = Arc::new(AppConfig {
+            crypto: CryptoConfig { master_key },
+            ..AppConfig::clone(&current)
+        });
+    }
+}
+
+/// Directory holding the database files of the workspaces
+pub fn workspaces_dir(config: &AppConfig) -> PathBuf {
+    config.storage.data_dir.join("workspaces")
+}
+
+/// `config` with the database file and storage directories of workspace
+/// `name`
+fn in_workspace(config: &AppConfig, name: &str) -> AppConfig {
+    let path = workspaces_dir(config).join(format!("{}.sqlite", name));
+    let mut workspace = config.clone();
+    workspace.database.url = format!("sqlite:{}", path.display());
+    workspace.database.path = path;
+    workspace.storage = workspace_storage(&config.storage, name);
+    workspace
+}
+
+/// `storage` with the directories holding a workspace's own files moved to
+/// `workspaces/<name>` inside each; the default workspace never looks there
+fn workspace_storage(storage: &StorageConfig, name: &str) -> StorageConfig {
+    let own = |dir: &PathBuf| dir.join("workspaces").join(name);
+    StorageConfig {
+        backup_dir: own(&storage.backup_dir),
+        export_dir: own(&storage.export_dir),
+        media_dir: own(&storage.media_dir),
+        import_dir: own(&storage.import_dir),
+        ..storage.clone()
+    }
+}
+
+/// Settings that changed in a reload
+#[derive(Debug, Default, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReload {
+    /// Changed and in effect now
+    pub applied: Vec<String>,
+    /// Changed, but only read at startup
+    pub restart_required: Vec<String>,
+}
+
+/// Re-read and validate the configuration and apply what can change at
+/// runtime; nothing changes when it is invalid
+#[instrument(skip_all)]
+pub fn reload_config(config: &SharedConfig, log_filter: &LogFilter) -> AppResult<ConfigReload> {
+    // Held throughout so a concurrent reload or key rotation isn't lost
+    let mut current = config.inner.write().unwrap_or_else(PoisonError::into_inner);
+    let loaded = current.reload()?;
+    let (merged, report) = merge(&current, loaded);
+    if merged.logging.level != current.logging.level {
+        log_filter.set_level(&merged.logging.level)?;
+    }
+    *current = Arc::new(merged);
+
+    info!(
+        applied = ?report.applied,
+        restart_required = ?report.restart_required,
+        "Configuration reloaded"
+    );
+    Ok(report)
+}
+
+/// `loaded` with the startup-only settings of `current`, and what changed
+fn merge(current: &AppConfig, loaded: AppConfig) -> (AppConfig, ConfigReload) {
+    let logging = LoggingConfig {
+        level: loaded.logging.level.clone(),
+        ..current.logging.clone()
+    };
+    let storage = StorageConfig {
+        root: current.storage.root.clone(),
+        data_dir: current.storage.data_dir.clone(),
+        logs_dir: current.storage.logs_dir.clone(),
+        cache_dir: current.storage.cache_dir.clone(),
+        backup_dir: current.storage.backup_dir.clone(),
+        export_dir: current.storage.export_dir.clone(),
+        media_dir: current.storage.media_dir.clone(),
+        import_dir: current.storage.import_dir.clone(),
+        ..loaded.storage.clone()
+    };
+    let http = HttpConfig {
+        port: current.http.port,
+        compression_min_bytes: current.http.compression_min_bytes,
+        ..loaded.http.clone()
+    };
+
+    let mut report = ConfigReload::default();
+    for (name, changed) in [
+        ("logging.level", logging.level != current.logging.level),
+        ("newsdata", loaded.newsdata != current.newsdata),
+        ("storage", storage != current.storage),
+        ("email", loaded.email != current.email),
+        ("embeddings", loaded.embeddings != current.embeddings),
+        ("ai", loaded.ai != current.ai),
+        ("http", http != current.http),
+    ] {
+        if changed {
+            report.applied.push(name.to_string());
+        }
+    }
+    for (name, changed) in [
+        ("database", loaded.database != current.database),
+        ("logging", loaded.logging != logging),
+        ("storage.root", loaded.storage.root != current.storage.root),
+        (
+            "storage.logs_dir",
+            loaded.storage.logs_dir != current.storage.logs_dir,
+        ),
+        ("crypto", loaded.crypto != current.crypto),
+        ("http.port", loaded.http.port != current.http.port),
+        (
+            "http.compression_min_bytes",
+            loaded.http.compression_min_bytes != current.http.compression_min_bytes,
+        ),
+        ("http_clients", loaded.http_clients != current.http_clients),
+        (
+            "error_reporting",
+            loaded.error_reporting != current.error_reporting,
+        ),
+    ] {
+        if changed {
+            report.restart_required.push(name.to_string());
+        }
+    }
+
+    let merged = AppConfig {
+        database: current.database.clone(),
+        logging,
+        storage,
+        crypto: current.crypto.clone(),
+        http,
+        http_clients: current.http_clients.clone(),
+        error_reporting: current.error_reporting.clone(),
+        ..loaded
+    };
+    (merged, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::config::{BackupCompression, BackupEncryption};
+    use crate::core::components::db::migrations::test_db;
+    use crate::research::components::reader_media::{cleanup_orphaned_media, snapshot_media_dir};
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_workspace_media_survives_default_cleanup() {
+        let dir = std::env::temp_dir().join(format!("cockpit-workspace-{}", std::process::id()));
+        let storage = StorageConfig {
+            root: dir.clone(),
+            data_dir: dir.join("data"),
+            logs_dir: dir.join("logs"),
+            cache_dir: dir.join("cache"),
+            backup_dir: dir.join("backups"),
+            export_dir: dir.join("exports"),
+            media_dir: dir.join("media"),
+            import_dir: dir.join("imports"),
+            writing_sync_dir: None,
+            writing_git_autocommit: false,
+            writing_git_remote: None,
+            max_total_size_gb: None,
+            backup_compression: BackupCompression::None,
+            backup_compression_level: None,
+            backup_encryption: BackupEncryption::None,
+        };
+        let workspace = workspace_storage(&storage, "side");
+        assert_eq!(workspace.media_dir, dir.join("media/workspaces/side"));
+        assert_eq!(workspace.import_dir, dir.join("imports/workspaces/side"));
+        assert_eq!(workspace.data_dir, storage.data_dir);
+
+        // Snapshot 1 exists in neither database, so both copies are orphans
+        let default_media = snapshot_media_dir(&storage.media_dir, 1);
+        let side_media = snapshot_media_dir(&workspace.media_dir, 1);
+        for media in [&default_media, &side_media] {
+            fs::create_dir_all(media).unwrap();
+            fs::write(media.join("a.png"), b"png").unwrap();
+        }
+
+        let summary = cleanup_orphaned_media(&test_db().await, &storage.media_dir)
+            .await
+            .unwrap();
+        assert_eq!(summary.removed_snapshots, 1);
+        assert!(!default_media.exists());
+        assert!(side_media.join("a.png").exists());
+
+        cleanup_orphaned_media(&test_db().await, &workspace.media_dir)
+            .await
+            .unwrap();
+        assert!(!side_media.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
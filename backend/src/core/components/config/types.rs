@@ -5,6 +5,8 @@
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::core::components::errors::Severity;
+
 /// Main application configuration
 #[derive(Debug, Clone)]
 pub struct AppConfig {
@@ -13,19 +15,107 @@ pub struct AppConfig {
     pub newsdata: NewsDataConfig,
     pub storage: StorageConfig,
     pub crypto: CryptoConfig,
+    pub email: EmailConfig,
+    pub embeddings: EmbeddingsConfig,
+    pub ai: AiConfig,
+    pub http: HttpConfig,
+    pub http_clients: HttpClientsConfig,
+    pub error_reporting: ErrorReportingConfig,
+    /// Variables set from `.env` files or config.toml rather than the
+    /// environment the app started with; a reload reads them again
+    pub loaded_vars: Vec<String>,
 }
 
 /// Database configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DatabaseConfig {
+    /// `sqlite:<path>` or `postgres://...`
     pub url: String,
+    /// SQLite database file; unused with PostgreSQL
     pub path: PathBuf,
     pub max_connections: u32,
     pub min_connections: u32,
+    /// Encryption of the SQLite file
+    pub encryption: DatabaseEncryption,
+    /// SQLite tuning, applied to every pooled connection
+    pub journal_mode: JournalMode,
+    pub synchronous: Synchronous,
+    /// How long a connection waits for a lock before "database is locked"
+    pub busy_timeout: Duration,
+    /// Page cache per connection, in KiB
+    pub cache_size_kib: u32,
+    /// Bytes of the file read through mmap; 0 disables it
+    pub mmap_size_bytes: u64,
+    /// Integrity check run in the background after startup
+    pub startup_integrity_check: Option<IntegrityCheck>,
+    /// Back up the database before applying migrations at startup
+    pub pre_migration_backup: bool,
+}
+
+impl DatabaseConfig {
+    /// Whether the database is a PostgreSQL server rather than a SQLite file
+    pub fn is_postgres(&self) -> bool {
+        is_postgres_url(&self.url)
+    }
+
+    /// The SQLite file `url` points at; `None` for PostgreSQL
+    pub fn sqlite_path(&self) -> Option<PathBuf> {
+        sqlite_path(&self.url)
+    }
+}
+
+/// Encryption of the SQLite database file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseEncryption {
+    None,
+    /// SQLCipher, keyed from `COCKPIT_MASTER_KEY`; a plaintext file is
+    /// encrypted at the next start
+    Sqlcipher,
+}
+
+/// SQLite `journal_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalMode {
+    /// Readers don't block the writer, nor it them
+    Wal,
+    Delete,
+    Truncate,
+}
+
+/// SQLite `synchronous`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Synchronous {
+    Off,
+    /// Safe with WAL: a power loss can only drop the last commits
+    Normal,
+    Full,
+    Extra,
+}
+
+/// Depth of an integrity check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityCheck {
+    /// `PRAGMA quick_check`: pages and records, not indexes
+    Quick,
+    /// `PRAGMA integrity_check`
+    Full,
+}
+
+/// The file of a `sqlite:` URL, without its query string
+pub fn sqlite_path(url: &str) -> Option<PathBuf> {
+    let path = url.strip_prefix("sqlite:")?;
+    let path = path.strip_prefix("//").unwrap_or(path);
+    let path = path.split('?').next().unwrap_or(path);
+    (!path.is_empty() && path != ":memory:").then(|| PathBuf::from(path))
+}
+
+/// Whether `url` is a PostgreSQL connection URL
+pub fn is_postgres_url(url: &str) -> bool {
+    url.starts_with("postgres://") || url.starts_with("postgresql://")
 }
 
 /// Logging configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LoggingConfig {
     pub level: String,
     pub app_log_path: PathBuf,
@@ -33,12 +123,24 @@ pub struct LoggingConfig {
     pub error_log_path: PathBuf,
     pub max_file_size_mb: u64,
     pub max_files: usize,
-    pub structured_json: bool,
+    pub format: LogFormat,
     pub console_output: bool,
+    /// OTLP/gRPC collector for span export; `None` disables it
+    pub otlp_endpoint: Option<String>,
+    pub otlp_service_name: String,
+}
+
+/// Log file (and console) line format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    Text,
+    /// ND-JSON: one flat JSON object per event, for Loki/Elastic shippers
+    Json,
 }
 
 /// NewsData API configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NewsDataConfig {
     pub api_key: Option<String>,
     pub daily_call_limit: u32,
@@ -47,7 +149,7 @@ pub struct NewsDataConfig {
 }
 
 /// Storage configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct StorageConfig {
     pub root: PathBuf,
     pub data_dir: PathBuf,
@@ -55,11 +157,170 @@ pub struct StorageConfig {
     pub cache_dir: PathBuf,
     pub backup_dir: PathBuf,
     pub export_dir: PathBuf,
+    /// Downloaded media (offline reader images)
+    pub media_dir: PathBuf,
+    /// Files uploaded over the bridge, waiting to be imported
+    pub import_dir: PathBuf,
+    /// Folder of Markdown files kept in sync with the writings
+    pub writing_sync_dir: Option<PathBuf>,
+    /// Commit the sync folder to Git on every draft save and publish
+    pub writing_git_autocommit: bool,
+    /// Remote pushed to after each of those commits
+    pub writing_git_remote: Option<String>,
     pub max_total_size_gb: Option<u64>,
+    pub backup_compression: BackupCompression,
+    /// Compression level; `None` uses the algorithm's default
+    pub backup_compression_level: Option<i32>,
+    /// Encryption of backup and export files
+    pub backup_encryption: BackupEncryption,
+}
+
+/// Compression of backup files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupCompression {
+    None,
+    /// `.zst`, levels 1-22
+    Zstd,
+    /// `.gz`, levels 0-9
+    Gzip,
+}
+
+/// Key backup and export files are encrypted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupEncryption {
+    None,
+    /// `COCKPIT_MASTER_KEY`
+    MasterKey,
+    /// A key derived from `BACKUP_PASSPHRASE`, so files can be restored on
+    /// a machine with a different master key
+    Passphrase,
 }
 
 /// Cryptography configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CryptoConfig {
     pub master_key: String,
 }
+
+/// SMTP TLS mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain connection upgraded via STARTTLS (usually port 587)
+    StartTls,
+    /// Implicit TLS from the first byte (usually port 465)
+    Tls,
+    /// No encryption (local relays / testing only)
+    None,
+}
+
+/// Outbound email (SMTP) configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailConfig {
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_tls: SmtpTls,
+    pub from_address: Option<String>,
+    pub default_recipients: Vec<String>,
+    pub template_path: Option<PathBuf>,
+    pub send_timeout: Duration,
+}
+
+impl EmailConfig {
+    /// Whether enough settings are present to attempt a send
+    pub fn is_configured(&self) -> bool {
+        self.smtp_host.is_some() && self.from_address.is_some()
+    }
+}
+
+/// Embedding provider backing semantic search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingsProvider {
+    /// OpenAI-compatible `/embeddings` endpoint
+    OpenAi,
+    /// Local Ollama server
+    Ollama,
+    /// Semantic search disabled
+    Disabled,
+}
+
+/// Embeddings (semantic search) configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmbeddingsConfig {
+    pub provider: EmbeddingsProvider,
+    pub model: String,
+    pub openai_api_key: Option<String>,
+    pub openai_base_url: String,
+    pub ollama_base_url: String,
+    pub batch_size: usize,
+    pub request_timeout: Duration,
+}
+
+impl EmbeddingsConfig {
+    /// Whether a provider is selected
+    pub fn is_enabled(&self) -> bool {
+        self.provider != EmbeddingsProvider::Disabled
+    }
+}
+
+/// Local LLM (Ollama) configuration for summaries
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiConfig {
+    pub ollama_base_url: String,
+    pub ollama_model: String,
+    pub request_timeout: Duration,
+}
+
+/// HTTP bridge configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpConfig {
+    pub port: u16,
+    /// Responses smaller than this are sent uncompressed
+    pub compression_min_bytes: u16,
+    /// Full-access bearer token; with no tokens at all the bridge stays open
+    pub api_token: Option<String>,
+    /// Tokens limited to the listed command scopes
+    pub scoped_tokens: Vec<ScopedToken>,
+}
+
+/// Outgoing HTTP clients, one per purpose
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpClientsConfig {
+    /// Fetching pages and their images for the reader (`HTTP_READER_*`)
+    pub reader: HttpClientConfig,
+    /// Feed syncs, connectors and AI providers (`HTTP_API_*`)
+    pub api: HttpClientConfig,
+}
+
+/// One outgoing HTTP client
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpClientConfig {
+    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    /// Proxy for every request; `None` follows `HTTPS_PROXY` and friends
+    pub proxy: Option<String>,
+    /// `None` sends the client's default
+    pub user_agent: Option<String>,
+    /// Retries of timed out, unreachable or rate-limited fetches
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub retry_backoff: Duration,
+}
+
+/// Error reporting to a Sentry-compatible DSN
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReportingConfig {
+    /// `None` disables reporting
+    pub dsn: Option<String>,
+    /// Errors below this level are only logged
+    pub min_severity: Severity,
+    pub environment: String,
+}
+
+/// A bridge token limited to some command scopes (`read`, `research:*`, ..)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopedToken {
+    pub token: String,
+    pub scopes: Vec<String>,
+}
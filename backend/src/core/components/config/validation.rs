@@ -15,6 +15,7 @@ pub fn ensure_directories(config: &AppConfig) -> Result<(), AppError> {
         &config.storage.cache_dir,
         &config.storage.backup_dir,
         &config.storage.export_dir,
+        &config.storage.import_dir,
     ];
 
     for dir in dirs {
@@ -0,0 +1,427 @@
+//! Inventory of stored secrets
+//!
+//! `list_credentials` lists every secret kept in the database: the NewsData
+//! settings key, feed source API keys, site credentials, the encrypted app
+//! settings (AI provider keys) and research account auth. Each entry says
+//! what the secret is for, when it was last used and whether a test call
+//! made with it is accepted. The secrets themselves are never returned.
+
+use std::collections::HashMap;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures_util::future::join_all;
+use reqwest::{RequestBuilder, StatusCode};
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Serialize;
+use tracing::instrument;
+
+use super::ai::{anthropic, openai};
+use super::crypto;
+use super::errors::AppResult;
+use super::http::HttpClients;
+use super::settings::entities as app_settings;
+use super::settings::handlers::get_setting_value;
+use crate::research::components::feed::entities::{feed_sources, settings as news_settings};
+use crate::research::components::feed::feed_sources::KEYED_SOURCE_TYPES;
+use crate::research::components::site_credentials::credential_headers;
+use crate::research::entities::{accounts, site_credentials, streams};
+
+const NEWSDATA_TEST_URL: &str = "https://newsdata.io/api/1/latest";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStatus {
+    /// The test call was accepted
+    Valid,
+    /// The test call was refused (401 or 403): expired or revoked
+    Invalid,
+    /// Nothing stored
+    Missing,
+    /// Doesn't decrypt with the master key
+    Unreadable,
+    /// No test call was made
+    Unchecked,
+    /// The test call failed for another reason
+    Error,
+}
+
+/// One stored secret, without its value
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialInfo {
+    /// `news_settings`, `feed_sources`, `site_credentials`, `app_settings`
+    /// or `research_accounts`
+    pub source: String,
+    /// Row id in `source`
+    pub id: i64,
+    /// Service the secret is for: `newsdata`, `openai`, `anthropic`, a
+    /// connector or a site's domain
+    pub provider: String,
+    /// Feed source, setting key, domain or account name
+    pub name: String,
+    pub last_used_at: Option<String>,
+    pub status: CredentialStatus,
+    /// Outcome of the test call, or why none was made
+    pub message: Option<String>,
+}
+
+/// A test call to make with a secret
+enum Probe {
+    NewsData(String),
+    OpenAi {
+        base_url: String,
+        api_key: String,
+    },
+    Anthropic {
+        base_url: String,
+        api_key: String,
+    },
+    Site {
+        domain: String,
+        headers: Vec<(String, String)>,
+    },
+}
+
+/// An entry and, when its secret is readable and testable, its test call
+struct Pending {
+    info: CredentialInfo,
+    probe: Option<Probe>,
+}
+
+/// Why a secret can't be tested
+type Unusable = (CredentialStatus, String);
+
+impl Pending {
+    fn new(
+        source: &str,
+        id: i64,
+        provider: &str,
+        name: &str,
+        last_used_at: Option<String>,
+        secret: Result<Option<Probe>, Unusable>,
+    ) -> Self {
+        let (status, message, probe) = match secret {
+            Ok(Some(probe)) => (CredentialStatus::Unchecked, None, Some(probe)),
+            Ok(None) => (
+                CredentialStatus::Unchecked,
+                Some("No test call for this provider".to_string()),
+                None,
+            ),
+            Err((status, message)) => (status, Some(message), None),
+        };
+        Self {
+            info: CredentialInfo {
+                source: source.to_string(),
+                id,
+                provider: provider.to_string(),
+                name: name.to_string(),
+                last_used_at,
+                status,
+                message,
+            },
+            probe,
+        }
+    }
+}
+
+/// Every stored secret with its last use and, unless `test` is false, the
+/// result of a test call; NewsData counts those against the daily quota
+#[instrument(skip(db, clients))]
+pub async fn list_credentials(
+    db: &DatabaseConnection,
+    clients: &HttpClients,
+    test: bool,
+) -> AppResult<Vec<CredentialInfo>> {
+    let mut pending = news_settings_secrets(db).await?;
+    pending.extend(feed_source_secrets(db).await?);
+    pending.extend(site_credential_secrets(db).await?);
+    pending.extend(app_setting_secrets(db).await?);
+    pending.extend(research_account_secrets(db).await?);
+
+    if !test {
+        return Ok(pending.into_iter().map(|p| p.info).collect());
+    }
+    let checks = pending
+        .into_iter()
+        .map(|Pending { mut info, probe }| async move {
+            if let Some(probe) = probe {
+                let (status, message) = run_probe(&probe, clients).await;
+                info.status = status;
+                info.message = Some(message);
+            }
+            info
+        });
+    Ok(join_all(checks).await)
+}
+
+async fn news_settings_secrets(db: &DatabaseConnection) -> AppResult<Vec<Pending>> {
+    let rows = news_settings::Entity::find().all(db).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let secret = decrypt(Some(&row.api_key_encrypted))
+                .map(|key| (row.provider == "newsdata").then_some(Probe::NewsData(key)));
+            Pending::new(
+                "news_settings",
+                row.id,
+                &row.provider,
+                "News settings",
+                row.last_synced_at.map(|t| t.to_rfc3339()),
+                secret,
+            )
+        })
+        .collect())
+}
+
+/// Sources with a key, and those of a type that needs one
+async fn feed_source_secrets(db: &DatabaseConnection) -> AppResult<Vec<Pending>> {
+    let rows = feed_sources::Entity::find()
+        .filter(
+            feed_sources::Column::ApiKeyEncrypted
+                .is_not_null()
+                .or(feed_sources::Column::SourceType.is_in(KEYED_SOURCE_TYPES.iter().copied())),
+        )
+        .all(db)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let secret = decrypt(row.api_key_encrypted.as_deref())
+                .map(|key| (row.source_type == "newsdata").then_some(Probe::NewsData(key)));
+            Pending::new(
+                "feed_sources",
+                row.id,
+                &row.source_type,
+                &row.name,
+                row.last_sync_at.map(|t| t.and_utc().to_rfc3339()),
+                secret,
+            )
+        })
+        .collect())
+}
+
+async fn site_credential_secrets(db: &DatabaseConnection) -> AppResult<Vec<Pending>> {
+    let rows = site_credentials::Entity::find().all(db).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let secret = credential_headers(&row)
+                .map(|headers| {
+                    Some(Probe::Site {
+                        domain: row.domain.clone(),
+                        headers,
+                    })
+                })
+                .map_err(|e| (CredentialStatus::Unreadable, e.to_string()));
+            Pending::new(
+                "site_credentials",
+                row.id,
+                &row.domain,
+                &row.domain,
+                row.last_used_at.map(|t| t.and_utc().to_rfc3339()),
+                secret,
+            )
+        })
+        .collect())
+}
+
+/// Encrypted settings; the AI keys are tested against `ai.base_url` when
+/// their provider is the selected one
+async fn app_setting_secrets(db: &DatabaseConnection) -> AppResult<Vec<Pending>> {
+    let rows = app_settings::Entity::find()
+        .filter(app_settings::Column::IsEncrypted.eq(1))
+        .all(db)
+        .await?;
+    let ai_provider = get_setting_value(db, "ai.provider").await?;
+    let ai_base_url = get_setting_value(db, "ai.base_url")
+        .await?
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+    let base_url = |provider: &str, default: &str| match &ai_base_url {
+        Some(url) if ai_provider.as_deref() == Some(provider) => url.clone(),
+        _ => default.to_string(),
+    };
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let provider = match row.key.as_str() {
+                "news.newsdata_api_key" => "newsdata",
+                "ai.openai_api_key" => "openai",
+                "ai.anthropic_api_key" => "anthropic",
+                key => key.split('.').next().unwrap_or(key),
+            };
+            let data = STANDARD.decode(row.value.as_bytes()).map_err(|e| {
+                (
+                    CredentialStatus::Unreadable,
+                    format!("Not valid base64: {}", e),
+                )
+            });
+            let secret = data
+                .and_then(|data| decrypt(Some(&data)))
+                .map(|api_key| match provider {
+                    "newsdata" => Some(Probe::NewsData(api_key)),
+                    "openai" => Some(Probe::OpenAi {
+                        base_url: base_url("openai", openai::DEFAULT_BASE_URL),
+                        api_key,
+                    }),
+                    "anthropic" => Some(Probe::Anthropic {
+                        base_url: base_url("anthropic", anthropic::DEFAULT_BASE_URL),
+                        api_key,
+                    }),
+                    _ => None,
+                });
+            Pending::new(
+                "app_settings",
+                row.id.into(),
+                provider,
+                &row.key,
+                None,
+                secret,
+            )
+        })
+        .collect())
+}
+
+/// Account auth, last used by the latest sync of its streams
+async fn research_account_secrets(db: &DatabaseConnection) -> AppResult<Vec<Pending>> {
+    let mut last_synced = HashMap::new();
+    for stream in streams::Entity::find().all(db).await? {
+        if let Some(synced) = stream.last_sync_at {
+            let latest = last_synced.entry(stream.account_id).or_insert(synced);
+            *latest = (*latest).max(synced);
+        }
+    }
+
+    let rows = accounts::Entity::find().all(db).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let secret = read_account_auth(row.auth_encrypted.as_deref()).and_then(|auth| {
+                if row.provider != "newsdata" {
+                    return Ok(None);
+                }
+                auth.get("apiKey")
+                    .and_then(|v| v.as_str())
+                    .filter(|key| !key.trim().is_empty())
+                    .map(|key| Some(Probe::NewsData(key.to_string())))
+                    .ok_or_else(|| {
+                        (
+                            CredentialStatus::Missing,
+                            "No apiKey in the account auth".to_string(),
+                        )
+                    })
+            });
+            Pending::new(
+                "research_accounts",
+                row.id,
+                &row.provider,
+                &row.display_name,
+                last_synced.get(&row.id).map(|t| t.and_utc().to_rfc3339()),
+                secret,
+            )
+        })
+        .collect())
+}
+
+/// Account auth is JSON, stored as is or encrypted
+fn read_account_auth(data: Option<&[u8]>) -> Result<serde_json::Value, Unusable> {
+    let data = data.ok_or((CredentialStatus::Missing, "No auth stored".to_string()))?;
+    if let Ok(auth) = serde_json::from_slice(data) {
+        return Ok(auth);
+    }
+    serde_json::from_str(&decrypt(Some(data))?).map_err(|_| {
+        (
+            CredentialStatus::Unreadable,
+            "Auth is not valid JSON".to_string(),
+        )
+    })
+}
+
+fn decrypt(data: Option<&[u8]>) -> Result<String, Unusable> {
+    let missing = || (CredentialStatus::Missing, "No secret stored".to_string());
+    let data = data.filter(|d| !d.is_empty()).ok_or_else(missing)?;
+    let secret = crypto::decrypt_api_key(data).map_err(|e| {
+        (
+            CredentialStatus::Unreadable,
+            format!("Can't be decrypted: {}", e),
+        )
+    })?;
+    if secret.trim().is_empty() {
+        return Err(missing());
+    }
+    Ok(secret)
+}
+
+async fn run_probe(probe: &Probe, clients: &HttpClients) -> (CredentialStatus, String) {
+    let request: RequestBuilder = match probe {
+        Probe::NewsData(api_key) => clients
+            .api
+            .get(NEWSDATA_TEST_URL)
+            .query(&[("apikey", api_key.as_str()), ("language", "en")]),
+        Probe::OpenAi { base_url, api_key } => clients
+            .api
+            .get(format!("{}/models", base_url))
+            .bearer_auth(api_key),
+        Probe::Anthropic { base_url, api_key } => clients
+            .api
+            .get(format!("{}/v1/models", base_url))
+            .header("x-api-key", api_key)
+            .header("anthropic-version", anthropic::API_VERSION),
+        Probe::Site { domain, headers } => headers.iter().fold(
+            clients.reader.get(format!("https://{}/", domain)),
+            |request, (name, value)| request.header(name, value),
+        ),
+    };
+    match request.send().await {
+        Ok(resp) => answer_status(resp.status()),
+        // The URL may carry the key
+        Err(e) => (
+            CredentialStatus::Error,
+            format!("Test call failed: {}", e.without_url()),
+        ),
+    }
+}
+
+fn answer_status(status: StatusCode) -> (CredentialStatus, String) {
+    let outcome = match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => CredentialStatus::Invalid,
+        // Only a known key gets rate limited
+        StatusCode::TOO_MANY_REQUESTS => CredentialStatus::Valid,
+        s if s.is_success() || s.is_redirection() => CredentialStatus::Valid,
+        _ => CredentialStatus::Error,
+    };
+    (outcome, format!("Test call answered {}", status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_status() {
+        assert_eq!(answer_status(StatusCode::OK).0, CredentialStatus::Valid);
+        assert_eq!(
+            answer_status(StatusCode::UNAUTHORIZED).0,
+            CredentialStatus::Invalid
+        );
+        assert_eq!(
+            answer_status(StatusCode::TOO_MANY_REQUESTS).0,
+            CredentialStatus::Valid
+        );
+        assert_eq!(
+            answer_status(StatusCode::BAD_GATEWAY).0,
+            CredentialStatus::Error
+        );
+        assert_eq!(
+            read_account_auth(None).unwrap_err().0,
+            CredentialStatus::Missing
+        );
+        assert_eq!(
+            read_account_auth(Some(br#"{"apiKey":"k"}"#)).unwrap()["apiKey"],
+            "k"
+        );
+    }
+}
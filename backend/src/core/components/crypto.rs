@@ -14,9 +14,10 @@
 //! 
 //! Encrypted data is stored as: `[12-byte nonce][ciphertext + auth tag]`
 //! 
-//! ## Environment Variable
+//! ## Master Key
 //! 
-//! Requires `COCKPIT_MASTER_KEY` environment variable:
+//! The key is `COCKPIT_MASTER_KEY` of the configuration installed with
+//! [`use_config`] at startup:
 //! - Must be a 64-character hex string (32 bytes)
 //! - Generate with: `openssl rand -hex 32`
 //! - Store securely, never commit to version control
@@ -26,30 +27,67 @@
 //! Each encryption operation generates a fresh random nonce.
 //! For production use with high volume, consider implementing:
 //! - Nonce collision detection
-//! - Key rotation policies
 //! - Periodic master key derivation
+//!
+//! The master key itself is rotated with `rotate_master_key` (see
+//! [`super::key_rotation`]).
 
 use aes_gcm::{aead::Aead, aead::KeyInit, Aes256Gcm, Nonce};
 use rand::RngCore;
-use std::env;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use zeroize::Zeroize;
 
-const MASTER_KEY_ENV: &str = "COCKPIT_MASTER_KEY";
+use super::config::SharedConfig;
+
 const NONCE_LEN: usize = 12;
-const KEY_LEN: usize = 32;
+pub(crate) const KEY_LEN: usize = 32;
+const DATABASE_KEY_LABEL: &[u8] = b"cockpit-sqlcipher-v1";
+
+/// Configuration the master key is read from
+static CONFIG: OnceLock<SharedConfig> = OnceLock::new();
+
+/// Held shared while a secret is encrypted and stored, and exclusively
+/// while the master key is rotated
+static SECRET_WRITES: RwLock<()> = RwLock::const_new(());
 
-/// Load and validate master encryption key from environment
+/// Read the master key from `config` from now on
+///
+/// Called once at startup, before anything is encrypted; a key rotated
+/// through `config` is used from the next call on.
+pub(crate) fn use_config(config: SharedConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// Load and validate the master encryption key from the configuration
 /// 
 /// The key is expected to be a 64-character hex string representing 32 bytes.
-/// After loading, the hex string representation is zeroized for security.
-fn load_master_key() -> Result<[u8; KEY_LEN], String> {
-    let mut raw = env::var(MASTER_KEY_ENV)
-        .map_err(|_| format!("missing {MASTER_KEY_ENV} env var for encryption key"))?;
-    
-    let mut bytes = hex::decode(raw.trim()).map_err(|e| format!("invalid master key hex: {e}"))?;
-    
-    // Zeroize the hex string from memory
-    raw.zeroize();
+pub(crate) fn load_master_key() -> Result<[u8; KEY_LEN], String> {
+    let config = CONFIG.get().ok_or("no configuration to read the master key from")?;
+    parse_master_key(&config.current().crypto.master_key)
+}
+
+/// Wait until no key rotation is running, and keep the next one waiting
+/// until the guard is dropped
+///
+/// Take it before encrypting a secret and hold it until the secret is
+/// stored, so it can't be written under a key that was just replaced. Not
+/// reentrant: a rotation queued in between would wait on the outer guard.
+pub(crate) async fn secret_write() -> RwLockReadGuard<'static, ()> {
+    SECRET_WRITES.read().await
+}
+
+/// Wait for secret writes in progress to finish, and hold new ones off
+/// until the guard is dropped
+pub(crate) async fn key_rotation() -> RwLockWriteGuard<'static, ()> {
+    SECRET_WRITES.write().await
+}
+
+/// Decode a master key given as a 64-character hex string
+pub(crate) fn parse_master_key(hex_key: &str) -> Result<[u8; KEY_LEN], String> {
+    let mut bytes =
+        hex::decode(hex_key.trim()).map_err(|e| format!("invalid master key hex: {e}"))?;
     
     if bytes.len() != KEY_LEN {
         bytes.zeroize(); // Clean up before returning error
@@ -80,10 +118,16 @@ fn load_master_key() -> Result<[u8; KEY_LEN], String> {
 /// - For very high volume (>2^32 encryptions), consider key rotation
 pub fn encrypt_api_key(plaintext: &str) -> Result<Vec<u8>, String> {
     let mut key = load_master_key()?;
-    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let encrypted = encrypt_with_key(&key, plaintext);
     
-    // Zeroize key after cipher creation
+    // Zeroize key after use
     key.zeroize();
+    encrypted
+}
+
+/// Encrypt plaintext with `key` rather than the master key
+pub(crate) fn encrypt_with_key(key: &[u8; KEY_LEN], plaintext: &str) -> Result<Vec<u8>, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
 
     let mut nonce_bytes = [0u8; NONCE_LEN];
     rand::rng().fill_bytes(&mut nonce_bytes);
@@ -107,15 +151,20 @@ pub fn encrypt_api_key(plaintext: &str) -> Result<Vec<u8>, String> {
 /// - Decrypted plaintext is returned as String (caller must zeroize if needed)
 /// - Authentication tag is verified automatically by GCM mode
 pub fn decrypt_api_key(data: &[u8]) -> Result<String, String> {
-    if data.len() <= NONCE_LEN {
-        return Err("ciphertext too short".into());
-    }
-    
     let mut key = load_master_key()?;
-    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let decrypted = decrypt_with_key(&key, data);
     
-    // Zeroize key after cipher creation
+    // Zeroize key after use
     key.zeroize();
+    decrypted
+}
+
+/// Decrypt ciphertext with `key` rather than the master key
+pub(crate) fn decrypt_with_key(key: &[u8; KEY_LEN], data: &[u8]) -> Result<String, String> {
+    if data.len() <= NONCE_LEN {
+        return Err("ciphertext too short".into());
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
 
     let (nonce_bytes, cipher_text) = data.split_at(NONCE_LEN);
     let nonce = Nonce::from_slice(nonce_bytes);
@@ -124,3 +173,17 @@ pub fn decrypt_api_key(data: &[u8]) -> Result<String, String> {
         .map_err(|e| e.to_string())?;
     String::from_utf8(plain).map_err(|e| e.to_string())
 }
+
+/// SQLCipher key of the database file, as hex
+/// 
+/// Derived from the master key with SHA-256 under its own label, so the
+/// database key differs from the one encrypting API keys. The raw key is
+/// passed to SQLCipher as `x'...'`, skipping its passphrase derivation.
+pub(crate) fn database_key() -> Result<String, String> {
+    let mut key = load_master_key()?;
+    let mut hasher = Sha256::new();
+    hasher.update(DATABASE_KEY_LABEL);
+    hasher.update(key);
+    key.zeroize();
+    Ok(hex::encode(hasher.finalize()))
+}
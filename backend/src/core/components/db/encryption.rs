@@ -0,0 +1,251 @@
+//! SQLCipher encryption of the SQLite database
+//!
+//! With `DB_ENCRYPTION=sqlcipher` the database file is encrypted with a key
+//! derived from the master key (see [`crate::core::components::crypto`]).
+//! A plaintext file is encrypted once, at startup before any connection is
+//! open so no write can be lost: `sqlcipher_export` copies it into a new
+//! file, which replaces it once it opens with the key. `encrypt_database`
+//! schedules that migration for the next start without changing the config.
+//!
+//! Whether a file is encrypted is read from its header, so an encrypted
+//! database (or backup) is opened with the key whatever `DB_ENCRYPTION` says.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, Statement};
+use tracing::{info, instrument};
+
+use crate::core::components::config::{DatabaseConfig, DatabaseEncryption};
+use crate::core::components::crypto::database_key;
+use crate::core::components::errors::{AppError, AppResult};
+
+/// First bytes of every plaintext SQLite file
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// What is on disk at a database path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileState {
+    /// No file yet, or an empty one
+    Missing,
+    Plaintext,
+    Encrypted,
+}
+
+/// Whether the database is encrypted, for `encrypt_database`
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseEncryptionStatus {
+    pub database_path: String,
+    /// The file is encrypted now
+    pub encrypted: bool,
+    /// It will be encrypted at the next start
+    pub pending: bool,
+}
+
+pub fn file_state(path: &Path) -> io::Result<FileState> {
+    let mut header = Vec::with_capacity(PLAINTEXT_HEADER.len());
+    match fs::File::open(path) {
+        Ok(file) => {
+            file.take(PLAINTEXT_HEADER.len() as u64)
+                .read_to_end(&mut header)?;
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(FileState::Missing),
+        Err(e) => return Err(e),
+    }
+    Ok(if header.is_empty() {
+        FileState::Missing
+    } else if header == PLAINTEXT_HEADER {
+        FileState::Plaintext
+    } else {
+        FileState::Encrypted
+    })
+}
+
+/// Marker left by `encrypt_database` for the next start
+fn marker_path(path: &Path) -> PathBuf {
+    sibling(path, ".encrypt")
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// The value of `PRAGMA key`: the raw key as a blob literal
+pub(crate) fn key_pragma() -> AppResult<String> {
+    let key = database_key().map_err(|e| {
+        AppError::config(format!(
+            "The database is encrypted with the master key: {}",
+            e
+        ))
+    })?;
+    Ok(format!("\"x'{}'\"", key))
+}
+
+/// Options for opening the SQLite file at `path` on its own, keyed if it
+/// is encrypted
+pub(crate) fn file_options(path: &Path, read_only: bool) -> AppResult<ConnectOptions> {
+    // Databases attached to a writable connection are created with its flags
+    let mode = if read_only { "ro" } else { "rwc" };
+    let mut options = ConnectOptions::new(format!("sqlite://{}?mode={}", path.display(), mode));
+    options.sqlx_logging(false);
+    if file_state(path)? == FileState::Encrypted {
+        options.sqlcipher_key(key_pragma()?);
+    }
+    Ok(options)
+}
+
+/// Run a pending encryption of the database at `path` before it is opened;
+/// whether it must be opened with the key
+pub(crate) async fn prepare(path: &Path, encryption: DatabaseEncryption) -> AppResult<bool> {
+    let marker = marker_path(path);
+    let wanted = encryption == DatabaseEncryption::Sqlcipher || marker.exists();
+    let state = file_state(path)?;
+    if state == FileState::Plaintext && wanted {
+        encrypt_file(path).await?;
+    }
+    if marker.exists() {
+        fs::remove_file(&marker)?;
+    }
+    Ok(wanted || state == FileState::Encrypted)
+}
+
+/// Replace the plaintext database at `path` with an encrypted copy
+#[instrument]
+async fn encrypt_file(path: &Path) -> AppResult<()> {
+    info!("Encrypting the database with SQLCipher");
+    let key = key_pragma()?;
+    let target = sibling(path, ".encrypting");
+    if target.exists() {
+        fs::remove_file(&target)?;
+    }
+
+    // ATTACH holds for one connection only
+    let mut options = file_options(path, false)?;
+    options
+        .max_connections(1)
+        .min_connections(1)
+        .acquire_timeout(Duration::from_secs(30));
+    let plain = Database::connect(options).await?;
+    let export = async {
+        plain
+            .execute_unprepared(&format!(
+                "ATTACH DATABASE '{}' AS encrypted KEY {}",
+                target.to_string_lossy().replace('\'', "''"),
+                key
+            ))
+            .await?;
+        plain
+            .query_one(Statement::from_string(
+                plain.get_database_backend(),
+                "SELECT sqlcipher_export('encrypted')".to_string(),
+            ))
+            .await?;
+        plain.execute_unprepared("DETACH DATABASE encrypted").await
+    }
+    .await;
+    let _ = plain.close().await;
+    if let Err(e) = export {
+        let _ = fs::remove_file(&target);
+        return Err(AppError::database(format!(
+            "Failed to encrypt the database: {}",
+            e
+        )));
+    }
+
+    // Only replace the original once the copy reads back
+    let copy = Database::connect(file_options(&target, true)?).await?;
+    let check = copy
+        .query_one(Statement::from_string(
+            copy.get_database_backend(),
+            "PRAGMA quick_check".to_string(),
+        ))
+        .await?
+        .and_then(|row| row.try_get_by_index::<String>(0).ok());
+    let _ = copy.close().await;
+    if check.as_deref() != Some("ok") {
+        let _ = fs::remove_file(&target);
+        return Err(AppError::database(format!(
+            "The encrypted copy failed its check: {}",
+            check.unwrap_or_default()
+        )));
+    }
+
+    fs::rename(&target, path)?;
+    // Left over from the plaintext file, unreadable with the key
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(sibling(path, suffix));
+    }
+    info!("Database encrypted");
+    Ok(())
+}
+
+/// Encryption state of the configured database
+pub fn encryption_status(database: &DatabaseConfig) -> AppResult<DatabaseEncryptionStatus> {
+    let Some(path) = database.sqlite_path() else {
+        return Err(AppError::validation(
+            "database",
+            "Only SQLite databases can be encrypted",
+        ));
+    };
+    let encrypted = file_state(&path)? == FileState::Encrypted;
+    Ok(DatabaseEncryptionStatus {
+        database_path: path.to_string_lossy().into_owned(),
+        encrypted,
+        pending: !encrypted && marker_path(&path).exists(),
+    })
+}
+
+/// Encrypt the database at the next start
+///
+/// The running app keeps its connections to the plaintext file, so the
+/// migration waits for a restart; from then on the file is opened with the
+/// key. Set `DB_ENCRYPTION=sqlcipher` to also create new databases
+/// encrypted.
+#[instrument(skip(database))]
+pub async fn encrypt_database(database: &DatabaseConfig) -> AppResult<DatabaseEncryptionStatus> {
+    let mut status = encryption_status(database)?;
+    if status.encrypted || status.pending {
+        return Ok(status);
+    }
+    if !cfg!(feature = "sqlcipher") {
+        return Err(AppError::validation(
+            "database",
+            "This build has no SQLCipher support; rebuild with `--features sqlcipher`",
+        ));
+    }
+    // Fail now rather than at startup without a master key
+    key_pragma()?;
+
+    let marker = marker_path(Path::new(&status.database_path));
+    fs::write(&marker, chrono::Utc::now().to_rfc3339())
+        .map_err(|e| AppError::file_operation("write", marker.to_string_lossy(), e))?;
+    info!(database_path = %status.database_path, "Database encryption scheduled for the next start");
+    status.pending = true;
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_state() {
+        let dir = std::env::temp_dir().join(format!("cockpit-dbenc-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("db.sql");
+        assert_eq!(file_state(&path).unwrap(), FileState::Missing);
+        fs::write(&path, b"").unwrap();
+        assert_eq!(file_state(&path).unwrap(), FileState::Missing);
+        fs::write(&path, b"SQLite format 3\0rest of the header").unwrap();
+        assert_eq!(file_state(&path).unwrap(), FileState::Plaintext);
+        fs::write(&path, [0xbb; 64]).unwrap();
+        assert_eq!(file_state(&path).unwrap(), FileState::Encrypted);
+        assert_eq!(marker_path(&path), dir.join("db.sql.encrypt"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
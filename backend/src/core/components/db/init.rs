@@ -2,24 +2,34 @@
 //!
 //! Simplified database setup that delegates schema management to migrations.
 //! All schema changes should be done through migrations going forward.
+//!
+//! `DATABASE_URL` selects the backend: a `sqlite:` file (the default) or a
+//! `postgres://` server, which lets several clients share one database.
+//! `DB_ENCRYPTION=sqlcipher` encrypts a SQLite file (see [`super::encryption`]).
 
+use crate::core::components::config::{
+    is_postgres_url, DatabaseConfig, JournalMode, StorageConfig, Synchronous,
+};
 use crate::core::components::errors::AppError;
-use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, Statement};
-use std::path::PathBuf;
+use crate::core::components::storage::{backup_database, record_migration_set, MigrationSet};
+use sea_orm::sqlx::sqlite::{SqliteJournalMode, SqliteSynchronous};
+use sea_orm::{ConnectOptions, Database, DatabaseConnection};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
-use tracing::{info, warn};
-
-/// Initialize database from environment variables
-pub async fn init_db_from_env() -> Result<DatabaseConnection, AppError> {
-    let db_path = std::env::var("COCKPIT_DB_PATH").unwrap_or_else(|_| "cockpit.sqlite".into());
-    let db_url = std::env::var("DATABASE_URL").unwrap_or_else(|_| format!("sqlite:{db_path}"));
-    init_db(&db_url).await
-}
+use tracing::{error, info, warn};
 
 /// Initialize database with migration system
-pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, AppError> {
+///
+/// Unless `DB_PRE_MIGRATION_BACKUP=false`, an existing database is backed
+/// up to `storage.backup_dir` before pending migrations are applied.
+pub async fn init_db(
+    config: &DatabaseConfig,
+    storage: &StorageConfig,
+) -> Result<DatabaseConnection, AppError> {
+    let db_url = config.url.as_str();
     // Ensure SQLite database file exists
     let is_sqlite = db_url.starts_with("sqlite:");
+    let mut sqlcipher_key = None;
     if is_sqlite {
         if let Some(path) = db_url
             .trim_start_matches("sqlite:")
@@ -38,17 +48,22 @@ pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, AppError> {
                 .open(path)?;
 
             info!("Database file: {}", path);
+
+            if super::encryption::prepare(Path::new(path), config.encryption).await? {
+                sqlcipher_key = Some(super::encryption::key_pragma()?);
+            }
         }
-    } else {
+    } else if !is_postgres_url(db_url) {
         return Err(AppError::config(format!(
-            "Only SQLite is supported currently. DATABASE_URL={db_url}"
+            "Only SQLite and PostgreSQL are supported. DATABASE_URL={}",
+            redacted_url(db_url)
         )));
     }
 
     // Connect to database with optimized pool settings
     let mut opt = ConnectOptions::new(db_url.to_string());
-    opt.max_connections(5)
-        .min_connections(1)
+    opt.max_connections(config.max_connections)
+        .min_connections(config.min_connections)
         .connect_timeout(Duration::from_secs(8))
         .acquire_timeout(Duration::from_secs(8))
         .idle_timeout(Duration::from_secs(300)) // 5 minutes
@@ -59,59 +74,53 @@ pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, AppError> {
             tracing::log::LevelFilter::Warn,
             Duration::from_secs(1),
         );
+    if let Some(key) = sqlcipher_key {
+        info!("Opening the database with its SQLCipher key");
+        opt.sqlcipher_key(key);
+    }
 
-    let db = Database::connect(opt).await?;
-    info!("Database connected: {}", db_url);
-
-    // Configure SQLite PRAGMAs for better performance and data integrity
+    // SQLite PRAGMAs are per connection, so they are set as each pooled
+    // connection opens rather than once on whichever serves the query
     if is_sqlite {
-        info!("Configuring SQLite PRAGMAs...");
-
-        // Enable WAL mode for concurrent reads
-        if let Err(e) = db
-            .execute(Statement::from_string(
-                db.get_database_backend(),
-                "PRAGMA journal_mode=WAL;".to_owned(),
-            ))
-            .await
-        {
-            warn!("Failed to enable WAL mode: {}", e);
-        }
-
-        // Set synchronous to NORMAL for better write performance
-        if let Err(e) = db
-            .execute(Statement::from_string(
-                db.get_database_backend(),
-                "PRAGMA synchronous=NORMAL;".to_owned(),
-            ))
-            .await
-        {
-            warn!("Failed to set synchronous mode: {}", e);
-        }
-
-        // Enable foreign keys for referential integrity
-        if let Err(e) = db
-            .execute(Statement::from_string(
-                db.get_database_backend(),
-                "PRAGMA foreign_keys=ON;".to_owned(),
-            ))
-            .await
-        {
-            warn!("Failed to enable foreign keys: {}", e);
-        }
+        let journal_mode = match config.journal_mode {
+            JournalMode::Wal => SqliteJournalMode::Wal,
+            JournalMode::Delete => SqliteJournalMode::Delete,
+            JournalMode::Truncate => SqliteJournalMode::Truncate,
+        };
+        let synchronous = match config.synchronous {
+            Synchronous::Off => SqliteSynchronous::Off,
+            Synchronous::Normal => SqliteSynchronous::Normal,
+            Synchronous::Full => SqliteSynchronous::Full,
+            Synchronous::Extra => SqliteSynchronous::Extra,
+        };
+        let busy_timeout = config.busy_timeout;
+        // Negative: KiB rather than pages
+        let cache_size = format!("-{}", config.cache_size_kib);
+        let mmap_size = config.mmap_size_bytes.to_string();
+        opt.map_sqlx_sqlite_opts(move |sqlite| {
+            sqlite
+                .journal_mode(journal_mode)
+                .synchronous(synchronous)
+                .foreign_keys(true)
+                .busy_timeout(busy_timeout)
+                .pragma("cache_size", cache_size.clone())
+                .pragma("mmap_size", mmap_size.clone())
+        });
+        info!(
+            journal_mode = ?config.journal_mode,
+            synchronous = ?config.synchronous,
+            busy_timeout_ms = config.busy_timeout.as_millis() as u64,
+            cache_size_kib = config.cache_size_kib,
+            mmap_size_bytes = config.mmap_size_bytes,
+            "SQLite PRAGMAs configured"
+        );
+    }
 
-        // Set busy timeout to avoid immediate failures on lock
-        if let Err(e) = db
-            .execute(Statement::from_string(
-                db.get_database_backend(),
-                "PRAGMA busy_timeout=5000;".to_owned(),
-            ))
-            .await
-        {
-            warn!("Failed to set busy timeout: {}", e);
-        }
+    let db = Database::connect(opt).await?;
+    info!("Database connected: {}", redacted_url(db_url));
 
-        info!("SQLite PRAGMAs configured");
+    if config.pre_migration_backup {
+        backup_before_migrating(&db, config, storage).await?;
     }
 
     // Run schema migrations
@@ -131,3 +140,50 @@ pub async fn init_db(db_url: &str) -> Result<DatabaseConnection, AppError> {
 
     Ok(db)
 }
+
+/// Back up the database if migrations are about to change it, recording
+/// which ones next to the backup
+async fn backup_before_migrating(
+    db: &DatabaseConnection,
+    database: &DatabaseConfig,
+    storage: &StorageConfig,
+) -> Result<(), AppError> {
+    let status = super::migrations::get_migration_status(db).await?;
+    // A new database has nothing to lose
+    if status.applied.is_empty() || status.pending.is_empty() {
+        return Ok(());
+    }
+
+    info!(
+        pending = status.pending.len(),
+        "Backing up the database before migrating"
+    );
+    let mut backup = backup_database(db, database, storage).await.map_err(|e| {
+        error!("Backup before migrating failed: {}", e);
+        AppError::config_validation(
+            "DB_PRE_MIGRATION_BACKUP",
+            format!("Backup before migrating failed, nothing was migrated: {}", e),
+        )
+        .with_suggestion("Fix the backup directory, or set DB_PRE_MIGRATION_BACKUP=false to migrate without a backup")
+    })?;
+    record_migration_set(
+        &mut backup,
+        MigrationSet {
+            applied: status.current,
+            pending: status.pending,
+        },
+    )?;
+    info!(backup = %backup.file_path, "Pre-migration backup created");
+    Ok(())
+}
+
+/// `db_url` without its password, for logs
+fn redacted_url(db_url: &str) -> String {
+    match reqwest::Url::parse(db_url) {
+        Ok(mut url) if url.password().is_some() => {
+            let _ = url.set_password(Some("***"));
+            url.to_string()
+        }
+        _ => db_url.to_string(),
+    }
+}
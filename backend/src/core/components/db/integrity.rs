@@ -0,0 +1,239 @@
+//! Database integrity checks
+//!
+//! `check_database_integrity` runs SQLite's `PRAGMA quick_check` (or the
+//! slower `integrity_check`, which also verifies indexes against their
+//! tables) and looks for orphaned rows: links whose target was deleted.
+//! Declared foreign keys are checked with `PRAGMA foreign_key_check`, which
+//! finds rows written while enforcement was off; the links below have no
+//! foreign key because they point into several tables, so they are checked
+//! one by one. PostgreSQL enforces its foreign keys and has no page check,
+//! so there only those links are checked.
+//!
+//! `article_signals` and `writing_sync_files` rows outlive their article or
+//! writing on purpose and are not reported.
+
+use std::time::Instant;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+
+/// Lines of `integrity_check` output kept in the report
+const MAX_INTEGRITY_ERRORS: usize = 100;
+
+/// Links without a foreign key: table, column, target table, and the
+/// condition selecting the rows that point at that target
+const LINKS: &[(&str, &str, &str, &str)] = &[
+    ("notes", "entity_id", "ideas", "entity_type = 'idea'"),
+    (
+        "notes",
+        "entity_id",
+        "reference_items",
+        "entity_type = 'reference'",
+    ),
+    (
+        "notes",
+        "entity_id",
+        "reader_references",
+        "entity_type = 'reader_reference'",
+    ),
+    ("notes", "entity_id", "writings", "entity_type = 'writing'"),
+    (
+        "embeddings",
+        "entity_id",
+        "news_articles",
+        "entity_type = 'news_article'",
+    ),
+    (
+        "embeddings",
+        "entity_id",
+        "reader_references",
+        "entity_type = 'reader_reference'",
+    ),
+    (
+        "embeddings",
+        "entity_id",
+        "writings",
+        "entity_type = 'writing'",
+    ),
+    ("note_links", "target_id", "ideas", "target_type = 'idea'"),
+    (
+        "note_links",
+        "target_id",
+        "reference_items",
+        "target_type = 'reference'",
+    ),
+    (
+        "note_links",
+        "target_id",
+        "writings",
+        "target_type = 'writing'",
+    ),
+    (
+        "news_articles",
+        "alert_rule_id",
+        "alert_rules",
+        "alert_rule_id IS NOT NULL",
+    ),
+    (
+        "news_articles",
+        "mute_rule_id",
+        "mute_rules",
+        "mute_rule_id IS NOT NULL",
+    ),
+];
+
+/// Result of an integrity check
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// No corruption and no orphaned rows
+    pub ok: bool,
+    /// Whether `integrity_check` ran rather than `quick_check`
+    pub full: bool,
+    /// Output of the check, `["ok"]` when clean; `None` on PostgreSQL
+    pub integrity: Option<Vec<String>>,
+    pub orphans: Vec<OrphanedRows>,
+    pub duration_ms: i64,
+}
+
+/// Rows of `table` whose `column` points at a missing row of `references`
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedRows {
+    pub table: String,
+    pub column: String,
+    pub references: String,
+    pub count: i64,
+}
+
+async fn query_rows(db: &DatabaseConnection, sql: &str) -> AppResult<Vec<sea_orm::QueryResult>> {
+    db.query_all(Statement::from_string(
+        db.get_database_backend(),
+        sql.to_string(),
+    ))
+    .await
+    .map_err(|e| AppError::database(format!("{} failed: {}", sql, e)))
+}
+
+/// Check the database for corruption (SQLite) and orphaned rows
+pub async fn check_database_integrity(
+    db: &DatabaseConnection,
+    full: bool,
+) -> AppResult<IntegrityReport> {
+    let started = Instant::now();
+    let sqlite = db.get_database_backend() == DatabaseBackend::Sqlite;
+
+    let integrity = if sqlite {
+        let pragma = if full {
+            "integrity_check"
+        } else {
+            "quick_check"
+        };
+        let sql = format!("PRAGMA {}({})", pragma, MAX_INTEGRITY_ERRORS);
+        let mut lines = Vec::new();
+        for row in query_rows(db, &sql).await? {
+            lines.push(row.try_get_by_index::<String>(0)?);
+        }
+        Some(lines)
+    } else {
+        None
+    };
+
+    let mut orphans = Vec::new();
+    if sqlite {
+        let sql = "SELECT fk.\"table\", l.\"from\", fk.parent, COUNT(*) \
+                   FROM pragma_foreign_key_check() AS fk \
+                   JOIN pragma_foreign_key_list(fk.\"table\") AS l ON l.id = fk.fkid \
+                   GROUP BY 1, 2, 3 ORDER BY 1, 2";
+        for row in query_rows(db, sql).await? {
+            orphans.push(OrphanedRows {
+                table: row.try_get_by_index(0)?,
+                column: row.try_get_by_index(1)?,
+                references: row.try_get_by_index(2)?,
+                count: row.try_get_by_index(3)?,
+            });
+        }
+    }
+    for (table, column, references, filter) in LINKS {
+        let sql = format!(
+            "SELECT COUNT(*) FROM {table} WHERE {filter} \
+             AND NOT EXISTS (SELECT 1 FROM {references} WHERE {references}.id = {table}.{column})"
+        );
+        let count = match query_rows(db, &sql).await?.first() {
+            Some(row) => row.try_get_by_index::<i64>(0)?,
+            None => 0,
+        };
+        if count > 0 {
+            orphans.push(OrphanedRows {
+                table: table.to_string(),
+                column: column.to_string(),
+                references: references.to_string(),
+                count,
+            });
+        }
+    }
+
+    let report = IntegrityReport {
+        ok: integrity.as_ref().is_none_or(|lines| lines == &["ok"]) && orphans.is_empty(),
+        full,
+        integrity,
+        orphans,
+        duration_ms: started.elapsed().as_millis() as i64,
+    };
+    if report.ok {
+        info!(
+            full,
+            duration_ms = report.duration_ms,
+            "Database integrity check passed"
+        );
+    } else {
+        warn!(
+            full,
+            integrity = ?report.integrity,
+            orphaned_links = report.orphans.len(),
+            "Database integrity check found problems"
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+
+    #[tokio::test]
+    async fn test_check_database_integrity() {
+        let db = test_db().await;
+        db.execute_unprepared(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO writings (id, title) VALUES (1, 'Kept');
+             INSERT INTO ideas (id, title, status) VALUES (1, 'Kept', 'inbox');
+             INSERT INTO notes (entity_type, entity_id, body_markdown)
+                 VALUES ('writing', 1, ''), ('writing', 2, ''), ('idea', 2, '');
+             INSERT INTO writing_idea_links (writing_id, idea_id) VALUES (1, 1), (3, 1), (4, 1);",
+        )
+        .await
+        .unwrap();
+
+        let report = check_database_integrity(&db, false).await.unwrap();
+        assert!(!report.ok);
+        assert_eq!(report.integrity.as_deref(), Some(&["ok".to_string()][..]));
+        let found: Vec<_> = report
+            .orphans
+            .iter()
+            .map(|o| (o.table.as_str(), o.references.as_str(), o.count))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("writing_idea_links", "writings", 2),
+                ("notes", "ideas", 1),
+                ("notes", "writings", 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Database maintenance
+//!
+//! SQLite keeps the pages freed by deleted rows, so the file only grows after
+//! large article churn. The `db_maintenance` task runs `PRAGMA optimize` and
+//! `ANALYZE` daily and a `VACUUM` once free pages make up a tenth of the
+//! file; `run_db_maintenance` does the same on demand, vacuuming by default.
+//! On PostgreSQL, where autovacuum reuses dead rows, the task only runs
+//! `ANALYZE` unless a vacuum is asked for.
+
+use std::time::Instant;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use serde::Serialize;
+use tracing::info;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::system::components::scheduler::TaskRunResult;
+use crate::AppState;
+
+/// Share of free pages at which a scheduled run also vacuums
+const VACUUM_FREE_RATIO: f64 = 0.1;
+
+/// Outcome of a maintenance run
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DbMaintenanceResult {
+    pub vacuumed: bool,
+    pub size_before_bytes: i64,
+    pub size_after_bytes: i64,
+    pub reclaimed_bytes: i64,
+    pub duration_ms: i64,
+}
+
+async fn scalar(db: &DatabaseConnection, sql: &str) -> AppResult<i64> {
+    let row = db
+        .query_one(Statement::from_string(
+            db.get_database_backend(),
+            sql.to_string(),
+        ))
+        .await?
+        .ok_or_else(|| AppError::database(format!("{} returned no row", sql)))?;
+    Ok(row.try_get_by_index::<i64>(0)?)
+}
+
+async fn pragma_value(db: &DatabaseConnection, pragma: &str) -> AppResult<i64> {
+    scalar(db, &format!("PRAGMA {}", pragma)).await
+}
+
+async fn execute(db: &DatabaseConnection, sql: &str) -> AppResult<()> {
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        sql.to_string(),
+    ))
+    .await
+    .map_err(|e| AppError::database(format!("{} failed: {}", sql, e)))?;
+    Ok(())
+}
+
+/// Total and free pages, times the page size; PostgreSQL reports no free
+/// pages
+async fn database_size(db: &DatabaseConnection) -> AppResult<(i64, i64)> {
+    if db.get_database_backend() == DatabaseBackend::Postgres {
+        let size = scalar(db, "SELECT pg_database_size(current_database())").await?;
+        return Ok((size, 0));
+    }
+    let page_size = pragma_value(db, "page_size").await?;
+    let pages = pragma_value(db, "page_count").await?;
+    let free_pages = pragma_value(db, "freelist_count").await?;
+    Ok((pages * page_size, free_pages * page_size))
+}
+
+/// Refresh query planner statistics and vacuum when `vacuum` says so:
+/// always, never, or (`None`) once free pages pass [`VACUUM_FREE_RATIO`]
+pub async fn run_db_maintenance(
+    db: &DatabaseConnection,
+    vacuum: Option<bool>,
+) -> AppResult<DbMaintenanceResult> {
+    let started = Instant::now();
+    let sqlite = db.get_database_backend() == DatabaseBackend::Sqlite;
+    let (size_before, free_before) = database_size(db).await?;
+
+    if sqlite {
+        execute(db, "PRAGMA optimize").await?;
+    }
+    execute(db, "ANALYZE").await?;
+
+    let vacuumed = vacuum
+        .unwrap_or(size_before > 0 && free_before as f64 / size_before as f64 >= VACUUM_FREE_RATIO);
+    if vacuumed {
+        execute(db, "VACUUM").await?;
+        if sqlite {
+            // Shrink the WAL the vacuum just filled
+            execute(db, "PRAGMA wal_checkpoint(TRUNCATE)").await?;
+        }
+    }
+
+    let (size_after, _) = database_size(db).await?;
+    let result = DbMaintenanceResult {
+        vacuumed,
+        size_before_bytes: size_before,
+        size_after_bytes: size_after,
+        reclaimed_bytes: (size_before - size_after).max(0),
+        duration_ms: started.elapsed().as_millis() as i64,
+    };
+    info!(
+        vacuumed = result.vacuumed,
+        reclaimed_bytes = result.reclaimed_bytes,
+        duration_ms = result.duration_ms,
+        "Database maintenance completed"
+    );
+    Ok(result)
+}
+
+/// Scheduled task entry point
+pub async fn run_db_maintenance_task(state: &AppState) -> TaskRunResult {
+    match run_db_maintenance(&state.db, None).await {
+        Ok(result) => TaskRunResult {
+            status: "success",
+            result_json: serde_json::to_string(&result).ok(),
+            error_message: None,
+        },
+        Err(e) => TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(e.to_string()),
+        },
+    }
+}
@@ -6,10 +6,44 @@
 //! - Rollback capability
 //! - SQLite + PostgreSQL compatibility
 
-use crate::core::components::errors::AppResult;
+use crate::core::components::config::{DatabaseConfig, StorageConfig};
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::storage::{backup_database, BackupInfo};
 use sea_orm::DatabaseConnection;
 use sea_orm_migration::prelude::*;
-use tracing::info;
+use serde::Serialize;
+use tracing::{error, info};
+
+/// Applied and pending migrations, for `get_migration_status`
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationStatus {
+    /// Newest applied migration
+    pub current: Option<String>,
+    /// Applied migrations, oldest first
+    pub applied: Vec<AppliedMigration>,
+    /// Migrations the next start applies
+    pub pending: Vec<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedMigration {
+    pub name: String,
+    pub applied_at: String,
+    /// Whether this build has the migration, and so can roll it back
+    pub known: bool,
+}
+
+/// Outcome of `rollback_last_migration`
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationRollback {
+    pub rolled_back: String,
+    /// Backup taken just before, to restore if the rollback lost data
+    pub backup: BackupInfo,
+    pub status: MigrationStatus,
+}
 
 /// Apply all pending migrations using SeaORM Migrator
 pub async fn run_migrations(db: &DatabaseConnection) -> AppResult<()> {
@@ -28,6 +62,24 @@ pub async fn run_migrations(db: &DatabaseConnection) -> AppResult<()> {
     Ok(())
 }
 
+/// A migrated in-memory SQLite database for tests, with a fixed master key
+/// installed once per test binary
+#[cfg(test)]
+pub(crate) async fn test_db() -> DatabaseConnection {
+    use crate::core::components::config::{AppConfig, SharedConfig, Vars};
+    use crate::core::components::crypto;
+
+    static MASTER_KEY: std::sync::OnceLock<()> = std::sync::OnceLock::new();
+    MASTER_KEY.get_or_init(|| {
+        let vars = [("COCKPIT_MASTER_KEY".to_string(), "11".repeat(crypto::KEY_LEN))];
+        let config = AppConfig::from_vars(&vars.into_iter().collect::<Vars>()).unwrap();
+        crypto::use_config(SharedConfig::new(config));
+    });
+    let db = sea_orm::Database::connect("sqlite::memory:").await.unwrap();
+    run_migrations(&db).await.unwrap();
+    db
+}
+
 /// Get current database version
 pub async fn get_db_version(db: &DatabaseConnection) -> AppResult<Option<i32>> {
     let applied = migration::Migrator::get_applied_migrations(db).await?;
@@ -42,13 +94,80 @@ pub async fn has_pending_migrations(db: &DatabaseConnection) -> AppResult<bool>
     Ok(applied.len() < total)
 }
 
-/// Rollback the most recent migration
-#[allow(dead_code)]
-pub async fn rollback_last_migration(db: &DatabaseConnection) -> AppResult<()> {
-    info!("Rolling back last migration...");
+/// Applied migrations, including any this build doesn't know, and pending
+/// ones
+pub async fn get_migration_status(db: &DatabaseConnection) -> AppResult<MigrationStatus> {
+    let known: Vec<String> = migration::Migrator::migrations()
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect();
+    let applied: Vec<AppliedMigration> = migration::Migrator::get_migration_models(db)
+        .await?
+        .into_iter()
+        .map(|m| AppliedMigration {
+            known: known.contains(&m.version),
+            applied_at: chrono::DateTime::from_timestamp(m.applied_at, 0)
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            name: m.version,
+        })
+        .collect();
+    let pending = known
+        .into_iter()
+        .filter(|name| !applied.iter().any(|m| &m.name == name))
+        .collect();
+    Ok(MigrationStatus {
+        current: applied.last().map(|m| m.name.clone()),
+        applied,
+        pending,
+    })
+}
+
+/// Roll back the most recent migration, after a full backup
+///
+/// Nothing is rolled back when the backup fails. The next start applies
+/// the migration again, so this is for re-running a migration or for
+/// going back to the build before it.
+pub async fn rollback_last_migration(
+    db: &DatabaseConnection,
+    database: &DatabaseConfig,
+    storage: &StorageConfig,
+) -> AppResult<MigrationRollback> {
+    let status = get_migration_status(db).await?;
+    let Some(last) = status.applied.last() else {
+        return Err(AppError::validation(
+            "migration",
+            "No migration has been applied",
+        ));
+    };
+    if !last.known {
+        return Err(AppError::validation(
+            "migration",
+            format!(
+                "{} was applied by a newer version of the app, which has to roll it back",
+                last.name
+            ),
+        ));
+    }
+    let name = last.name.clone();
+
+    let backup = backup_database(db, database, storage).await.map_err(|e| {
+        error!(migration = %name, "Backup before rollback failed: {}", e);
+        AppError::other(format!(
+            "Backup before rollback failed, nothing was rolled back: {}",
+            e
+        ))
+    })?;
+
+    info!(migration = %name, backup = %backup.file_path, "Rolling back migration");
     migration::Migrator::down(db, Some(1)).await?;
-    info!("✓ Migration rolled back successfully");
-    Ok(())
+    info!(migration = %name, "Migration rolled back");
+
+    Ok(MigrationRollback {
+        rolled_back: name,
+        backup,
+        status: get_migration_status(db).await?,
+    })
 }
 
 /// Data migration: Move legacy NewsData settings to feed_sources
@@ -61,6 +180,11 @@ pub async fn migrate_newsdata_settings_to_feed_source(db: &DatabaseConnection) -
     use crate::research::components::feed::entities::feed_sources::Entity as FeedSourceEntity;
     use crate::core::components::crypto;
     
+    // Legacy settings only exist in SQLite databases
+    if db.get_database_backend() != sea_orm::DatabaseBackend::Sqlite {
+        return Ok(());
+    }
+
     info!("Checking for legacy NewsData settings to migrate...");
     
     // Check if any feed sources exist
@@ -125,6 +249,8 @@ pub async fn migrate_newsdata_settings_to_feed_source(db: &DatabaseConnection) -
         .map(|v| v == "true")
         .unwrap_or(true);
     
+    // Held until the insert, so a key rotation can't slip in between
+    let _secrets = crypto::secret_write().await;
     // Encrypt API key using feed_sources encryption
     let encrypted_key = crypto::encrypt_api_key(&api_key)
         .map_err(|e| AppError::Crypto {
@@ -159,16 +285,11 @@ pub async fn migrate_newsdata_settings_to_feed_source(db: &DatabaseConnection) -
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sea_orm::{Database, DbErr};
 
     #[tokio::test]
-    async fn test_migrations() -> Result<(), DbErr> {
-        // Test with in-memory database
-        let db = Database::connect("sqlite::memory:").await?;
-        
-        // Apply migrations
-        run_migrations(&db).await.unwrap();
-        
+    async fn test_migrations() {
+        let db = test_db().await;
+
         // Check version
         let version = get_db_version(&db).await.unwrap();
         assert!(version.is_some() && version.unwrap() > 0, "Expected migrations to be applied");
@@ -176,7 +297,5 @@ mod tests {
         // Check no pending migrations
         let pending = has_pending_migrations(&db).await.unwrap();
         assert!(!pending, "Expected no pending migrations");
-
-        Ok(())
     }
 }
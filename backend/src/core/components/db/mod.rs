@@ -5,9 +5,15 @@
 //! Organized into focused modules:
 //! - init: Database connection and initialization
 //! - migrations: Schema version management
+//! - maintenance: ANALYZE/VACUUM upkeep
+//! - encryption: SQLCipher encryption of the SQLite file
+//! - integrity: corruption and orphaned-row checks
 
+pub mod encryption;
 pub mod init;
+pub mod integrity;
+pub mod maintenance;
 pub mod migrations;
 
 // Re-export commonly used functions
-pub use init::init_db_from_env;
+pub use init::init_db;
@@ -0,0 +1,25 @@
+//! Embeddings Entity
+//! One vector per indexed entity and model, stored as little-endian f32 bytes
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "embeddings")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub entity_type: String, // 'news_article' | 'reader_reference' | 'writing'
+    pub entity_id: i64,
+    pub model: String,
+    pub dimensions: i32,
+    pub vector: Vec<u8>,
+    /// SHA-256 of the embedded text; a mismatch marks the row stale
+    pub content_hash: String,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
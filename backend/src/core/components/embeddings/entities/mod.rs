@@ -0,0 +1,3 @@
+//! Database entities for embeddings
+
+pub mod embeddings;
@@ -0,0 +1,329 @@
+//! Embedding index maintenance
+//!
+//! Builds the text for each indexable entity, hashes it, and only re-embeds
+//! entities whose text changed since the last run.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set};
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::components::feed::entities::articles::{
+    self as news_articles, Entity as NewsArticles,
+};
+use crate::research::entities::{reader_references, reader_snapshots};
+use crate::system::components::scheduler::TaskRunResult;
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::writing::text::extract_plain_text;
+use crate::AppState;
+
+use super::entities::embeddings::{self, ActiveModel as ActiveEmbedding, Entity as Embeddings};
+use super::provider::provider_from_config;
+use super::types::{
+    ReindexEmbeddingsInput, ReindexEmbeddingsResult, ENTITY_NEWS_ARTICLE,
+    ENTITY_READER_REFERENCE, ENTITY_TYPES, ENTITY_WRITING,
+};
+
+/// Longer texts are truncated; most embedding models cap input around 8k tokens
+const MAX_EMBED_CHARS: usize = 8_000;
+
+/// Text to embed for a single entity
+pub(crate) struct IndexDocument {
+    pub entity_id: i64,
+    pub text: String,
+}
+
+/// Validate requested entity types, defaulting to all of them
+pub(crate) fn resolve_entity_types(
+    requested: Option<Vec<String>>,
+) -> AppResult<Vec<&'static str>> {
+    let Some(requested) = requested.filter(|r| !r.is_empty()) else {
+        return Ok(ENTITY_TYPES.to_vec());
+    };
+    requested
+        .iter()
+        .map(|t| {
+            ENTITY_TYPES
+                .iter()
+                .copied()
+                .find(|known| *known == t.as_str())
+                .ok_or_else(|| {
+                    AppError::validation(
+                        "entityTypes",
+                        format!(
+                            "Unknown entity type '{}' (expected one of: {})",
+                            t,
+                            ENTITY_TYPES.join(", ")
+                        ),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Join non-empty parts and cap the length at a char boundary
+pub(crate) fn compose_text(parts: &[Option<&str>]) -> String {
+    let text = parts
+        .iter()
+        .flatten()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    match text.char_indices().nth(MAX_EMBED_CHARS) {
+        Some((cut, _)) => text[..cut].to_string(),
+        None => text,
+    }
+}
+
+pub(crate) fn content_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+pub(crate) fn vector_to_bytes(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub(crate) fn bytes_to_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// Writing content is TipTap JSON; older rows may still hold Markdown
+fn writing_plain_text(content: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(doc) if doc.is_object() => extract_plain_text(&doc),
+        _ => content.to_string(),
+    }
+}
+
+/// Load the current text of every indexable entity of `entity_type`
+pub(crate) async fn load_documents(
+    db: &sea_orm::DatabaseConnection,
+    entity_type: &str,
+) -> AppResult<Vec<IndexDocument>> {
+    let docs = match entity_type {
+        ENTITY_NEWS_ARTICLE => NewsArticles::find()
+            .filter(news_articles::Column::UserId.eq(1))
+            .filter(news_articles::Column::IsDismissed.eq(0))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|a| IndexDocument {
+                entity_id: a.id,
+                text: compose_text(&[
+                    Some(a.title.as_str()),
+                    a.excerpt.as_deref(),
+                    a.content.as_deref(),
+                ]),
+            })
+            .collect(),
+        ENTITY_READER_REFERENCE => {
+            // Newest snapshot per reference supplies the body text
+            let mut latest: HashMap<i64, String> = HashMap::new();
+            for snapshot in reader_snapshots::Entity::find()
+                .order_by_desc(reader_snapshots::Column::FetchedAt)
+                .all(db)
+                .await?
+            {
+                latest
+                    .entry(snapshot.reference_id)
+                    .or_insert(snapshot.content_md);
+            }
+            reader_references::Entity::find()
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|r| IndexDocument {
+                    entity_id: r.id,
+                    text: compose_text(&[
+                        Some(r.title.as_str()),
+                        r.excerpt.as_deref(),
+                        latest.get(&r.id).map(String::as_str),
+                    ]),
+                })
+                .collect()
+        }
+        ENTITY_WRITING => writings::Entity::find()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|w| {
+                let body = writing_plain_text(&w.content_markdown);
+                IndexDocument {
+                    entity_id: w.id,
+                    text: compose_text(&[
+                        Some(w.title.as_str()),
+                        w.excerpt.as_deref(),
+                        Some(body.as_str()),
+                    ]),
+                }
+            })
+            .collect(),
+        other => {
+            return Err(AppError::validation(
+                "entityType",
+                format!("Unknown entity type '{}'", other),
+            ))
+        }
+    };
+    Ok(docs)
+}
+
+/// Embed new or changed entities and drop vectors for deleted ones
+#[instrument(skip(state, input))]
+pub async fn reindex_embeddings_handler(
+    input: ReindexEmbeddingsInput,
+    state: &AppState,
+) -> AppResult<ReindexEmbeddingsResult> {
+    let config = state.config.current();
+    let config = &config.embeddings;
+    let provider = provider_from_config(config, &state.http_clients.api)?;
+    let model = provider.model().to_string();
+    let entity_types = resolve_entity_types(input.entity_types)?;
+    let force = input.force.unwrap_or(false);
+    let mut budget = input.limit.unwrap_or(usize::MAX);
+
+    let mut result = ReindexEmbeddingsResult {
+        model: model.clone(),
+        ..Default::default()
+    };
+
+    for entity_type in entity_types {
+        let docs = load_documents(&state.db, entity_type).await?;
+        let existing: HashMap<i64, (i64, String)> = Embeddings::find()
+            .filter(embeddings::Column::EntityType.eq(entity_type))
+            .filter(embeddings::Column::Model.eq(&model))
+            .all(&state.db)
+            .await?
+            .into_iter()
+            .map(|e| (e.entity_id, (e.id, e.content_hash)))
+            .collect();
+
+        // Vectors whose entity is gone (deleted, or a dismissed article)
+        let live: HashSet<i64> = docs.iter().map(|d| d.entity_id).collect();
+        let orphaned: Vec<i64> = existing
+            .iter()
+            .filter(|(entity_id, _)| !live.contains(entity_id))
+            .map(|(_, (id, _))| *id)
+            .collect();
+        if !orphaned.is_empty() {
+            result.removed += orphaned.len();
+            Embeddings::delete_many()
+                .filter(embeddings::Column::Id.is_in(orphaned))
+                .exec(&state.db)
+                .await?;
+        }
+
+        let mut pending = Vec::new();
+        for doc in docs {
+            if doc.text.is_empty() {
+                continue;
+            }
+            let hash = content_hash(&doc.text);
+            let unchanged = existing
+                .get(&doc.entity_id)
+                .is_some_and(|(_, existing_hash)| *existing_hash == hash);
+            if unchanged && !force {
+                result.unchanged += 1;
+            } else {
+                pending.push((doc, hash));
+            }
+        }
+        pending.truncate(budget);
+        budget -= pending.len();
+
+        for batch in pending.chunks(config.batch_size) {
+            let texts: Vec<String> = batch.iter().map(|(d, _)| d.text.clone()).collect();
+            let vectors = match provider.embed(&texts).await {
+                Ok(v) => v,
+                Err(e) => {
+                    warn!(
+                        target: "embeddings",
+                        entity_type,
+                        batch = batch.len(),
+                        "Embedding batch failed: {}",
+                        e
+                    );
+                    result.failed += batch.len();
+                    continue;
+                }
+            };
+
+            for ((doc, hash), vector) in batch.iter().zip(vectors) {
+                let now = Utc::now();
+                let row = ActiveEmbedding {
+                    entity_type: Set(entity_type.to_string()),
+                    entity_id: Set(doc.entity_id),
+                    model: Set(model.clone()),
+                    dimensions: Set(vector.len() as i32),
+                    vector: Set(vector_to_bytes(&vector)),
+                    content_hash: Set(hash.clone()),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                };
+                Embeddings::insert(row)
+                    .on_conflict(
+                        OnConflict::columns([
+                            embeddings::Column::EntityType,
+                            embeddings::Column::EntityId,
+                            embeddings::Column::Model,
+                        ])
+                        .update_columns([
+                            embeddings::Column::Dimensions,
+                            embeddings::Column::Vector,
+                            embeddings::Column::ContentHash,
+                            embeddings::Column::UpdatedAt,
+                        ])
+                        .to_owned(),
+                    )
+                    .exec(&state.db)
+                    .await?;
+                result.indexed += 1;
+            }
+        }
+    }
+
+    info!(
+        target: "embeddings",
+        model = %result.model,
+        indexed = result.indexed,
+        unchanged = result.unchanged,
+        removed = result.removed,
+        failed = result.failed,
+        "Embedding index updated"
+    );
+    Ok(result)
+}
+
+/// Scheduled task: incremental reindex of all entity types
+pub async fn run_embeddings_index_task(state: &AppState) -> TaskRunResult {
+    if !state.config.current().embeddings.is_enabled() {
+        return TaskRunResult {
+            status: "skipped",
+            result_json: Some("{\"reason\":\"embeddings disabled\"}".into()),
+            error_message: None,
+        };
+    }
+
+    match reindex_embeddings_handler(ReindexEmbeddingsInput::default(), state).await {
+        Ok(result) => TaskRunResult {
+            status: if result.failed == 0 { "success" } else { "error" },
+            result_json: serde_json::to_string(&result).ok(),
+            error_message: (result.failed > 0)
+                .then(|| format!("{} entities failed to embed", result.failed)),
+        },
+        Err(e) => TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(e.to_string()),
+        },
+    }
+}
@@ -0,0 +1,23 @@
+//! Embeddings and semantic search
+//!
+//! Indexes news articles, reader references, and writings into a vector
+//! table and ranks them by cosine similarity:
+//! - entities: `embeddings` vector table
+//! - types: Inputs and DTOs
+//! - provider: Pluggable embedding backends (OpenAI, Ollama)
+//! - index: Text extraction + incremental (re)indexing
+//! - search: Semantic search and "more like this"
+
+pub mod entities;
+pub mod types;
+pub mod provider;
+pub mod index;
+pub mod search;
+
+pub use types::{
+    MoreLikeThisInput, ReindexEmbeddingsInput, ReindexEmbeddingsResult, SemanticSearchHit,
+    SemanticSearchInput,
+};
+
+pub use index::{reindex_embeddings_handler, run_embeddings_index_task};
+pub use search::{more_like_this_handler, semantic_search_handler};
@@ -0,0 +1,159 @@
+//! Embedding providers
+//!
+//! Implement `EmbeddingProvider` to add a backend; `provider_from_config`
+//! picks one based on `EMBEDDINGS_PROVIDER`.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::config::{EmbeddingsConfig, EmbeddingsProvider};
+
+/// A backend that turns text into fixed-size vectors
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Model identifier stored alongside each vector
+    fn model(&self) -> &str;
+
+    /// Embed a batch of texts, returning one vector per input in order
+    async fn embed(&self, texts: &[String]) -> AppResult<Vec<Vec<f32>>>;
+}
+
+/// Build the configured provider; fails when embeddings are disabled
+pub fn provider_from_config(
+    config: &EmbeddingsConfig,
+    http: &reqwest::Client,
+) -> AppResult<Box<dyn EmbeddingProvider>> {
+    match config.provider {
+        EmbeddingsProvider::OpenAi => Ok(Box::new(OpenAiEmbeddings {
+            http: http.clone(),
+            base_url: config.openai_base_url.trim_end_matches('/').to_string(),
+            api_key: config.openai_api_key.clone().unwrap_or_default(),
+            model: config.model.clone(),
+            timeout: config.request_timeout,
+        })),
+        EmbeddingsProvider::Ollama => Ok(Box::new(OllamaEmbeddings {
+            http: http.clone(),
+            base_url: config.ollama_base_url.trim_end_matches('/').to_string(),
+            model: config.model.clone(),
+            timeout: config.request_timeout,
+        })),
+        EmbeddingsProvider::Disabled => Err(AppError::ConfigValidation {
+            field: "EMBEDDINGS_PROVIDER".to_string(),
+            reason: "Semantic search is not configured".to_string(),
+            suggestion: Some(
+                "Set EMBEDDINGS_PROVIDER=ollama or openai in ~/.cockpit/.env".to_string(),
+            ),
+        }),
+    }
+}
+
+/// OpenAI (or any OpenAI-compatible) `/embeddings` endpoint
+pub struct OpenAiEmbeddings {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    timeout: std::time::Duration,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiEmbeddings {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> AppResult<Vec<Vec<f32>>> {
+        let endpoint = format!("{}/embeddings", self.base_url);
+        let resp = self
+            .http
+            .post(&endpoint)
+            .bearer_auth(&self.api_key)
+            .timeout(self.timeout)
+            .json(&json!({ "model": self.model, "input": texts }))
+            .send()
+            .await?;
+        let body: OpenAiResponse = check_status(resp, &endpoint).await?.json().await?;
+
+        // The API doesn't promise response order; sort by input index
+        let mut data = body.data;
+        data.sort_by_key(|d| d.index);
+        expect_count(texts.len(), data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// Local Ollama server (`/api/embed`)
+pub struct OllamaEmbeddings {
+    http: reqwest::Client,
+    base_url: String,
+    model: String,
+    timeout: std::time::Duration,
+}
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddings {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> AppResult<Vec<Vec<f32>>> {
+        let endpoint = format!("{}/api/embed", self.base_url);
+        let resp = self
+            .http
+            .post(&endpoint)
+            .timeout(self.timeout)
+            .json(&json!({ "model": self.model, "input": texts }))
+            .send()
+            .await?;
+        let body: OllamaResponse = check_status(resp, &endpoint).await?.json().await?;
+        expect_count(texts.len(), body.embeddings)
+    }
+}
+
+async fn check_status(resp: reqwest::Response, endpoint: &str) -> AppResult<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let body = resp.text().await.unwrap_or_default();
+    tracing::warn!(
+        target: "embeddings",
+        endpoint,
+        status = status.as_u16(),
+        "Embedding request failed: {}",
+        body
+    );
+    Err(AppError::ApiRequest {
+        endpoint: endpoint.to_string(),
+        status: status.as_u16(),
+        source: None,
+    })
+}
+
+fn expect_count(expected: usize, vectors: Vec<Vec<f32>>) -> AppResult<Vec<Vec<f32>>> {
+    if vectors.len() != expected {
+        return Err(AppError::other(format!(
+            "Embedding provider returned {} vectors for {} inputs",
+            vectors.len(),
+            expected
+        )));
+    }
+    Ok(vectors)
+}
@@ -0,0 +1,250 @@
+//! Semantic search over the embedding index
+//!
+//! Vectors are scored by brute-force cosine similarity in memory, which is
+//! fast enough for a personal library of tens of thousands of entities.
+
+use std::collections::HashMap;
+
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use tracing::instrument;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::components::feed::entities::articles::{
+    self as news_articles, Entity as NewsArticles,
+};
+use crate::research::entities::reader_references;
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::AppState;
+
+use super::entities::embeddings::{self, Entity as Embeddings};
+use super::index::{bytes_to_vector, resolve_entity_types};
+use super::provider::provider_from_config;
+use super::types::{
+    MoreLikeThisInput, SemanticSearchHit, SemanticSearchInput, ENTITY_NEWS_ARTICLE,
+    ENTITY_READER_REFERENCE, ENTITY_WRITING,
+};
+
+/// Cosine similarity; 0.0 for mismatched or zero-length vectors
+pub(crate) fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Score every indexed entity of `entity_types` against `query`
+///
+/// Returns `(entity_type, entity_id, score)` sorted best-first. Entities in
+/// `exclude` are skipped.
+pub(crate) async fn rank_by_vector(
+    db: &sea_orm::DatabaseConnection,
+    model: &str,
+    query: &[f32],
+    entity_types: &[&str],
+    exclude: &[(&str, i64)],
+    limit: usize,
+) -> AppResult<Vec<(String, i64, f32)>> {
+    let rows = Embeddings::find()
+        .filter(embeddings::Column::Model.eq(model))
+        .filter(embeddings::Column::EntityType.is_in(entity_types.iter().copied()))
+        .filter(embeddings::Column::Dimensions.eq(query.len() as i32))
+        .all(db)
+        .await?;
+
+    let mut scored: Vec<(String, i64, f32)> = rows
+        .into_iter()
+        .filter(|row| {
+            !exclude
+                .iter()
+                .any(|(t, id)| *t == row.entity_type && *id == row.entity_id)
+        })
+        .map(|row| {
+            let score = cosine_similarity(query, &bytes_to_vector(&row.vector));
+            (row.entity_type, row.entity_id, score)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.2.total_cmp(&a.2));
+    scored.truncate(limit);
+    Ok(scored)
+}
+
+/// Attach title/excerpt/url to ranked ids, preserving order
+///
+/// Entities deleted since they were indexed are dropped.
+pub(crate) async fn hydrate_hits(
+    db: &sea_orm::DatabaseConnection,
+    ranked: Vec<(String, i64, f32)>,
+) -> AppResult<Vec<SemanticSearchHit>> {
+    let ids_of = |entity_type: &str| -> Vec<i64> {
+        ranked
+            .iter()
+            .filter(|(t, _, _)| t == entity_type)
+            .map(|(_, id, _)| *id)
+            .collect()
+    };
+
+    // (title, excerpt, url) keyed by (entity_type, id)
+    let mut details: HashMap<(&str, i64), (String, Option<String>, Option<String>)> =
+        HashMap::new();
+
+    let article_ids = ids_of(ENTITY_NEWS_ARTICLE);
+    if !article_ids.is_empty() {
+        for a in NewsArticles::find()
+            .filter(news_articles::Column::Id.is_in(article_ids))
+            .all(db)
+            .await?
+        {
+            details.insert((ENTITY_NEWS_ARTICLE, a.id), (a.title, a.excerpt, a.url));
+        }
+    }
+
+    let reference_ids = ids_of(ENTITY_READER_REFERENCE);
+    if !reference_ids.is_empty() {
+        for r in reader_references::Entity::find()
+            .filter(reader_references::Column::Id.is_in(reference_ids))
+            .all(db)
+            .await?
+        {
+            details.insert((ENTITY_READER_REFERENCE, r.id), (r.title, r.excerpt, Some(r.url)));
+        }
+    }
+
+    let writing_ids = ids_of(ENTITY_WRITING);
+    if !writing_ids.is_empty() {
+        for w in writings::Entity::find()
+            .filter(writings::Column::Id.is_in(writing_ids))
+            .all(db)
+            .await?
+        {
+            details.insert((ENTITY_WRITING, w.id), (w.title, w.excerpt, None));
+        }
+    }
+
+    Ok(ranked
+        .iter()
+        .filter_map(|(entity_type, entity_id, score)| {
+            let (title, excerpt, url) = details.remove(&(entity_type.as_str(), *entity_id))?;
+            Some(SemanticSearchHit {
+                entity_type: entity_type.clone(),
+                entity_id: *entity_id,
+                title,
+                excerpt,
+                url,
+                score: *score,
+            })
+        })
+        .collect())
+}
+
+/// Rank indexed entities by similarity to a free-text query
+#[instrument(skip(state, input), fields(query = %input.query))]
+pub async fn semantic_search_handler(
+    input: SemanticSearchInput,
+    state: &AppState,
+) -> AppResult<Vec<SemanticSearchHit>> {
+    let query = input.query.trim();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+    let entity_types = resolve_entity_types(input.entity_types)?;
+
+    let provider =
+        provider_from_config(&state.config.current().embeddings, &state.http_clients.api)?;
+    let vector = provider
+        .embed(&[query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    let mut ranked = rank_by_vector(
+        &state.db,
+        provider.model(),
+        &vector,
+        &entity_types,
+        &[],
+        input.limit.unwrap_or(20),
+    )
+    .await?;
+    if let Some(min_score) = input.min_score {
+        ranked.retain(|(_, _, score)| *score >= min_score);
+    }
+
+    hydrate_hits(&state.db, ranked).await
+}
+
+/// Find entities similar to one that is already indexed
+///
+/// Uses the stored vector rather than embedding the entity again.
+#[instrument(
+    skip(state, input),
+    fields(entity_type = %input.entity_type, entity_id = input.entity_id)
+)]
+pub async fn more_like_this_handler(
+    input: MoreLikeThisInput,
+    state: &AppState,
+) -> AppResult<Vec<SemanticSearchHit>> {
+    // Fails fast when embeddings are disabled; no request is sent
+    let provider =
+        provider_from_config(&state.config.current().embeddings, &state.http_clients.api)?;
+    let model = provider.model();
+    let source = Embeddings::find()
+        .filter(embeddings::Column::EntityType.eq(&input.entity_type))
+        .filter(embeddings::Column::EntityId.eq(input.entity_id))
+        .filter(embeddings::Column::Model.eq(model))
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| {
+            AppError::validation(
+                "entityId",
+                format!(
+                    "{} {} has no embedding for model '{}'; run embeddings_reindex first",
+                    input.entity_type, input.entity_id, model
+                ),
+            )
+        })?;
+
+    let entity_types = resolve_entity_types(input.entity_types)?;
+    let ranked = rank_by_vector(
+        &state.db,
+        model,
+        &bytes_to_vector(&source.vector),
+        &entity_types,
+        &[(input.entity_type.as_str(), input.entity_id)],
+        input.limit.unwrap_or(10),
+    )
+    .await?;
+
+    hydrate_hits(&state.db, ranked).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::embeddings::index::vector_to_bytes;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 1.0], &[-1.0, -1.0]) + 1.0).abs() < 1e-6);
+        // Mismatched dimensions and zero vectors never match
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_vector_bytes_round_trip() {
+        let vector = vec![0.5f32, -1.25, 3.0e-7, f32::MAX];
+        assert_eq!(bytes_to_vector(&vector_to_bytes(&vector)), vector);
+    }
+}
@@ -0,0 +1,71 @@
+//! DTOs for embeddings and semantic search
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Entity types that can be embedded
+pub const ENTITY_NEWS_ARTICLE: &str = "news_article";
+pub const ENTITY_READER_REFERENCE: &str = "reader_reference";
+pub const ENTITY_WRITING: &str = "writing";
+
+pub const ENTITY_TYPES: [&str; 3] = [ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE, ENTITY_WRITING];
+
+/// Input for (re)building the index
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReindexEmbeddingsInput {
+    /// Restrict to these entity types (default: all)
+    pub entity_types: Option<Vec<String>>,
+    /// Re-embed even when the content hash is unchanged
+    pub force: Option<bool>,
+    /// Max entities embedded in this run (default: unlimited)
+    pub limit: Option<usize>,
+}
+
+/// Summary of an indexing run
+#[derive(Debug, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReindexEmbeddingsResult {
+    pub model: String,
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+    pub failed: usize,
+}
+
+/// Free-text semantic search
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticSearchInput {
+    pub query: String,
+    pub entity_types: Option<Vec<String>>,
+    /// Default: 20
+    pub limit: Option<usize>,
+    /// Drop hits below this cosine similarity
+    pub min_score: Option<f32>,
+}
+
+/// Entities similar to an already indexed entity
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MoreLikeThisInput {
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// Restrict results to these entity types (default: all)
+    pub entity_types: Option<Vec<String>>,
+    /// Default: 10
+    pub limit: Option<usize>,
+}
+
+/// Single ranked result
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticSearchHit {
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub title: String,
+    pub excerpt: Option<String>,
+    pub url: Option<String>,
+    /// Cosine similarity in [-1, 1]
+    pub score: f32,
+}
@@ -0,0 +1,257 @@
+//! Optional error reporting to a Sentry-compatible DSN
+//!
+//! With `SENTRY_DSN` set, bridge commands that fail at or above
+//! `ERROR_REPORTING_MIN_SEVERITY` and errors the frontend sends to
+//! `log_frontend_error` are posted to the DSN's store endpoint (Sentry,
+//! GlitchTip, ...). Structured fields go through the audit log's redaction,
+//! and credentials are scrubbed out of messages and stack traces first.
+//! Events are sent in the background; a failed send is only logged.
+
+use std::sync::{Arc, OnceLock};
+
+use regex::Regex;
+use reqwest::{Client, Url};
+use serde_json::{json, Map, Value};
+use tracing::warn;
+
+use crate::core::components::audit::summary::redact;
+use crate::core::components::config::ErrorReportingConfig;
+use crate::core::components::errors::{AppError, Severity};
+
+/// Where and as whom to send events, taken from a DSN such as
+/// `https://<key>@o1.ingest.sentry.io/42`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dsn {
+    pub public_key: String,
+    pub store_url: String,
+}
+
+impl Dsn {
+    pub fn parse(dsn: &str) -> Option<Self> {
+        let url = Url::parse(dsn.trim()).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || url.username().is_empty() {
+            return None;
+        }
+        let path = url.path().trim_end_matches('/');
+        let (prefix, project) = path.rsplit_once('/')?;
+        if project.is_empty() {
+            return None;
+        }
+        let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+        Some(Dsn {
+            public_key: url.username().to_string(),
+            store_url: format!(
+                "{}://{}{}{}/api/{}/store/",
+                url.scheme(),
+                url.host_str()?,
+                port,
+                prefix,
+                project
+            ),
+        })
+    }
+}
+
+/// Replace credentials in a message or stack trace: bearer tokens, values
+/// of secret-looking keys and passwords in URLs
+pub fn scrub(text: &str) -> String {
+    static PATTERNS: OnceLock<[Regex; 2]> = OnceLock::new();
+    let [pairs, userinfo] = PATTERNS.get_or_init(|| {
+        [
+            Regex::new(
+                r#"(?i)((?:api[_-]?key|token|password|passphrase|secret|cookie|authorization)["']?\s*[=:]\s*["']?(?:bearer\s+)?|bearer\s+)[^\s"'&,;]+"#,
+            )
+            .expect("valid secret pattern"),
+            Regex::new(r"(://[^/\s:@]+:)[^/\s@]+@").expect("valid userinfo pattern"),
+        ]
+    });
+    let text = pairs.replace_all(text, "${1}[redacted]");
+    userinfo.replace_all(&text, "${1}[redacted]@").into_owned()
+}
+
+struct Sink {
+    dsn: Dsn,
+    client: Client,
+    min_severity: Severity,
+    environment: String,
+}
+
+/// Handle for reporting errors; does nothing when no DSN is configured
+#[derive(Clone, Default)]
+pub struct ErrorReporter {
+    sink: Option<Arc<Sink>>,
+}
+
+impl ErrorReporter {
+    pub fn new(config: &ErrorReportingConfig, client: Client) -> Self {
+        let sink = config.dsn.as_deref().and_then(Dsn::parse).map(|dsn| {
+            Arc::new(Sink {
+                dsn,
+                client,
+                min_severity: config.min_severity,
+                environment: config.environment.clone(),
+            })
+        });
+        Self { sink }
+    }
+
+    /// Report a failed command when `error` is severe enough
+    pub fn report_app_error(&self, command: &str, error: &AppError, request_id: Option<String>) {
+        let mut tags = Map::new();
+        tags.insert("command".to_string(), command.into());
+        tags.insert("code".to_string(), error.code_string().into());
+        if let Some(request_id) = request_id {
+            tags.insert("request_id".to_string(), request_id.into());
+        }
+        self.send(Event {
+            level: error.severity(),
+            logger: "bridge",
+            platform: "rust",
+            kind: error.code_string(),
+            message: error.to_string(),
+            stacks: Vec::new(),
+            tags,
+            extra: json!({}),
+        });
+    }
+
+    /// Report an error sent by the frontend through `log_frontend_error`
+    pub fn report_frontend_error(
+        &self,
+        severity: Severity,
+        message: &str,
+        stack: &str,
+        component_stack: &str,
+        action: Option<&str>,
+        metadata: Option<&str>,
+    ) {
+        let mut tags = Map::new();
+        if let Some(action) = action.filter(|a| !a.is_empty()) {
+            tags.insert("action".to_string(), action.into());
+        }
+        // Metadata is usually JSON; keep it as text otherwise
+        let metadata = metadata.map(|m| serde_json::from_str(m).unwrap_or_else(|_| json!(m)));
+        self.send(Event {
+            level: severity,
+            logger: "frontend",
+            platform: "javascript",
+            kind: "FrontendError".to_string(),
+            message: message.to_string(),
+            stacks: vec![
+                ("stack", stack.to_string()),
+                ("componentStack", component_stack.to_string()),
+            ],
+            tags,
+            extra: json!({ "metadata": metadata }),
+        });
+    }
+
+    fn send(&self, event: Event) {
+        let Some(sink) = self.sink.clone() else {
+            return;
+        };
+        if event.level < sink.min_severity {
+            return;
+        }
+        let body = event.into_payload(&sink.environment);
+        let auth = format!(
+            "Sentry sentry_version=7, sentry_key={}, sentry_client=cockpit/{}",
+            sink.dsn.public_key,
+            env!("CARGO_PKG_VERSION")
+        );
+        tokio::spawn(async move {
+            let sent = sink
+                .client
+                .post(&sink.dsn.store_url)
+                .header("X-Sentry-Auth", auth)
+                .json(&body)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status());
+            if let Err(e) = sent {
+                warn!(target: "error_reporting", "Failed to report error: {}", e);
+            }
+        });
+    }
+}
+
+struct Event {
+    level: Severity,
+    logger: &'static str,
+    platform: &'static str,
+    /// Exception type; errors are grouped by it
+    kind: String,
+    message: String,
+    /// Stack traces, kept whole but scrubbed
+    stacks: Vec<(&'static str, String)>,
+    tags: Map<String, Value>,
+    extra: Value,
+}
+
+impl Event {
+    fn into_payload(self, environment: &str) -> Value {
+        let message = scrub(&self.message);
+        let mut extra = redact(&self.extra);
+        if let Value::Object(map) = &mut extra {
+            for (name, stack) in self.stacks.iter().filter(|(_, s)| !s.is_empty()) {
+                map.insert(name.to_string(), scrub(stack).into());
+            }
+        }
+        json!({
+            "event_id": hex::encode(rand::random::<[u8; 16]>()),
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "level": self.level.as_str(),
+            "logger": self.logger,
+            "platform": self.platform,
+            "release": concat!("cockpit@", env!("CARGO_PKG_VERSION")),
+            "environment": environment,
+            "message": { "formatted": message },
+            "exception": { "values": [{ "type": self.kind, "value": message }] },
+            "tags": self.tags,
+            "extra": extra,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dsn_store_url() {
+        let dsn = Dsn::parse("https://abc123@o1.ingest.sentry.io/42").unwrap();
+        assert_eq!(dsn.public_key, "abc123");
+        assert_eq!(dsn.store_url, "https://o1.ingest.sentry.io/api/42/store/");
+
+        let dsn = Dsn::parse("http://k@localhost:8000/glitchtip/7").unwrap();
+        assert_eq!(
+            dsn.store_url,
+            "http://localhost:8000/glitchtip/api/7/store/"
+        );
+
+        assert!(Dsn::parse("https://sentry.io/42").is_none());
+        assert!(Dsn::parse("https://k@sentry.io/").is_none());
+        assert!(Dsn::parse("not a dsn").is_none());
+    }
+
+    #[test]
+    fn test_scrub_secrets() {
+        assert_eq!(
+            scrub("GET https://api.example.com/news?apikey=pub_123&q=rust failed"),
+            "GET https://api.example.com/news?apikey=[redacted]&q=rust failed"
+        );
+        assert_eq!(
+            scrub("Authorization: Bearer eyJhbGci.x.y"),
+            "Authorization: Bearer [redacted]"
+        );
+        assert_eq!(
+            scrub(r#"{"password": "hunter2", "user": "me"}"#),
+            r#"{"password": "[redacted]", "user": "me"}"#
+        );
+        assert_eq!(
+            scrub("postgres://cockpit:s3cret@db/cockpit"),
+            "postgres://cockpit:[redacted]@db/cockpit"
+        );
+        assert_eq!(scrub("Validation error: title"), "Validation error: title");
+    }
+}
@@ -37,6 +37,9 @@ pub enum ErrorCode {
     // Validation: 7xxx
     ValidationFailed = 7001,
 
+    // Conflicts: 8xxx
+    EditConflict = 8001,
+
     // Generic: 9xxx
     Unknown = 9999,
 }
@@ -52,3 +55,34 @@ impl ErrorCode {
         format!("E{:04}", self as u32)
     }
 }
+
+/// How bad an error is, in the level names error trackers use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+    Fatal,
+}
+
+impl Severity {
+    /// Parse a level name; accepts the frontend's `critical` for `fatal`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "info" => Some(Severity::Info),
+            "warning" | "warn" => Some(Severity::Warning),
+            "error" => Some(Severity::Error),
+            "fatal" | "critical" => Some(Severity::Fatal),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+            Severity::Fatal => "fatal",
+        }
+    }
+}
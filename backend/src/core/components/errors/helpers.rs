@@ -61,6 +61,14 @@ impl AppError {
         }
     }
 
+    /// Create a conflict error carrying the stored version
+    pub fn conflict(message: impl Into<String>, current: Option<serde_json::Value>) -> Self {
+        Self::Conflict {
+            message: message.into(),
+            current,
+        }
+    }
+
     /// Create a generic error
     pub fn other(message: impl Into<String>) -> Self {
         Self::Other {
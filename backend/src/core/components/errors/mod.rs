@@ -34,6 +34,7 @@ mod types;
 mod utils;
 
 // Re-export all public items
+pub use codes::{ErrorCode, Severity};
 pub use types::{AppError, AppResult};
 
 // Re-export methods are already implemented on AppError via the modules
@@ -124,6 +124,14 @@ pub enum AppError {
         invalid_value: Option<String>,
     },
 
+    // Concurrency errors
+    #[error("Conflict: {message}")]
+    Conflict {
+        message: String,
+        /// The stored version, for the client to merge its changes into
+        current: Option<serde_json::Value>,
+    },
+
     // Generic errors
     #[error("{message}")]
     Other {
@@ -3,7 +3,7 @@
 //! Provides methods to query error properties like error codes,
 //! retryability, and user action requirements.
 
-use super::codes::ErrorCode;
+use super::codes::{ErrorCode, Severity};
 use super::types::AppError;
 
 impl AppError {
@@ -26,6 +26,7 @@ impl AppError {
             Self::FileNotFound { .. } => ErrorCode::FileNotFound,
             Self::PermissionDenied { .. } => ErrorCode::PermissionDenied,
             Self::Validation { .. } => ErrorCode::ValidationFailed,
+            Self::Conflict { .. } => ErrorCode::EditConflict,
             Self::Other { .. } => ErrorCode::Unknown,
         }
     }
@@ -43,6 +44,51 @@ impl AppError {
         )
     }
 
+    /// HTTP status code for this error, used by the HTTP bridge
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Self::Validation { .. } | Self::ConfigValidation { .. } | Self::InvalidKey { .. } => {
+                400
+            }
+            Self::PermissionDenied { .. } => 403,
+            Self::Conflict { .. } => 409,
+            Self::FileNotFound { .. }
+            | Self::DatabaseQuery {
+                source: sea_orm::DbErr::RecordNotFound(_),
+                ..
+            } => 404,
+            Self::ApiRateLimit { .. } => 429,
+            Self::ApiRequest { .. } | Self::Network { .. } => 502,
+            Self::StorageLimitExceeded { .. } => 507,
+            _ => 500,
+        }
+    }
+
+    /// How serious this error is for error reporting: bad input and flaky
+    /// upstreams are warnings, broken config or schema is fatal
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Config { .. } | Self::Database { .. } | Self::DatabaseMigration { .. } => {
+                Severity::Fatal
+            }
+            Self::ConfigValidation { .. }
+            | Self::StorageLimitExceeded { .. }
+            | Self::ApiRateLimit { .. }
+            | Self::ApiRequest { .. }
+            | Self::Network { .. }
+            | Self::InvalidKey { .. }
+            | Self::FileNotFound { .. }
+            | Self::PermissionDenied { .. }
+            | Self::Validation { .. }
+            | Self::Conflict { .. }
+            | Self::DatabaseQuery {
+                source: sea_orm::DbErr::RecordNotFound(_),
+                ..
+            } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
     /// Check if this error requires user action
     pub fn requires_user_action(&self) -> bool {
         matches!(
@@ -52,6 +98,7 @@ impl AppError {
                 | Self::ApiRateLimit { .. }
                 | Self::StorageLimitExceeded { .. }
                 | Self::PermissionDenied { .. }
+                | Self::Conflict { .. }
         )
     }
 
@@ -66,4 +113,12 @@ impl AppError {
             _ => None,
         }
     }
+
+    /// The stored version a conflict was against
+    pub fn conflict_current(&self) -> Option<&serde_json::Value> {
+        match self {
+            Self::Conflict { current, .. } => current.as_ref(),
+            _ => None,
+        }
+    }
 }
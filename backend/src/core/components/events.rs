@@ -7,7 +7,11 @@
 
 use async_trait::async_trait;
 use serde::Serialize;
-use tracing::info;
+use tokio::sync::broadcast;
+use tracing::{debug, info};
+
+/// Events buffered per subscriber before slow ones start missing events
+const BROADCAST_CAPACITY: usize = 256;
 
 /// Minimal async event emitter contract.
 #[async_trait]
@@ -31,3 +35,63 @@ impl EventEmitter for NoopEventEmitter {
         Ok(())
     }
 }
+
+/// An emitted event as delivered to subscribers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventMessage {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub emitted_at: String,
+}
+
+/// Emitter that fans events out to live subscribers (the bridge's `/ws`).
+///
+/// Events emitted while nobody is subscribed are dropped, as with the no-op
+/// emitter.
+#[derive(Clone)]
+pub struct BroadcastEventEmitter {
+    sender: broadcast::Sender<EventMessage>,
+}
+
+impl BroadcastEventEmitter {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EventMessage> {
+        self.sender.subscribe()
+    }
+
+    /// Non-async emit, for callers that cannot await
+    pub fn publish<T: Serialize>(&self, event: &str, payload: T) -> Result<(), String> {
+        let payload = serde_json::to_value(&payload).map_err(|e| e.to_string())?;
+        let message = EventMessage {
+            event: event.to_string(),
+            payload,
+            emitted_at: chrono::Utc::now().to_rfc3339(),
+        };
+        // Err only means there are no subscribers right now
+        let receivers = self.sender.send(message).unwrap_or(0);
+        debug!(target: "events", receivers, "emit: {}", event);
+        Ok(())
+    }
+}
+
+impl Default for BroadcastEventEmitter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EventEmitter for BroadcastEventEmitter {
+    async fn emit<T: Serialize + Send + Sync>(
+        &self,
+        event: &str,
+        payload: T,
+    ) -> Result<(), String> {
+        self.publish(event, payload)
+    }
+}
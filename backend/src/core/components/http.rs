@@ -0,0 +1,280 @@
+//! Outgoing HTTP clients
+//!
+//! One client per purpose, built from [`HttpClientsConfig`]: `reader` fetches
+//! pages and their images, `api` talks to feed APIs, connectors and AI
+//! providers. Each has its own timeouts, proxy and user agent, and derefs to
+//! its `reqwest::Client`. Retries are opt-in: fetches that are safe to repeat
+//! go through [`HttpClient::send`].
+//!
+//! Requests carrying stored site headers follow a redirect only over HTTPS
+//! and within the headers' domain (see [`SiteHeaders`]); any other redirect
+//! is returned as the response.
+
+use std::ops::Deref;
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use reqwest::{RequestBuilder, Response, StatusCode, Url};
+use tracing::warn;
+
+use super::config::{HttpClientConfig, HttpClientsConfig};
+use super::errors::{AppError, AppResult};
+use super::reader::DEFAULT_USER_AGENT;
+
+/// The clients in `AppState`
+#[derive(Clone)]
+pub struct HttpClients {
+    pub reader: HttpClient,
+    pub api: HttpClient,
+}
+
+impl HttpClients {
+    pub fn new(config: &HttpClientsConfig) -> AppResult<Self> {
+        Ok(Self {
+            reader: HttpClient::new(&config.reader, Some(DEFAULT_USER_AGENT))?,
+            api: HttpClient::new(&config.api, None)?,
+        })
+    }
+}
+
+/// Redirects followed before giving up, as reqwest does by default
+const MAX_REDIRECTS: usize = 10;
+
+/// A `reqwest::Client` with its retry policy
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    config: HttpClientConfig,
+    user_agent: Option<String>,
+}
+
+impl HttpClient {
+    /// `default_user_agent` applies when the config names none
+    pub fn new(config: &HttpClientConfig, default_user_agent: Option<&str>) -> AppResult<Self> {
+        let user_agent = config
+            .user_agent
+            .as_deref()
+            .or(default_user_agent)
+            .map(str::to_string);
+        Self::build(config.clone(), user_agent, Policy::default())
+    }
+
+    /// The same client, following only the redirects `site` allows
+    pub fn for_site(&self, site: &SiteHeaders) -> AppResult<Self> {
+        Self::build(
+            self.config.clone(),
+            self.user_agent.clone(),
+            site.redirect_policy(),
+        )
+    }
+
+    fn build(
+        config: HttpClientConfig,
+        user_agent: Option<String>,
+        redirects: Policy,
+    ) -> AppResult<Self> {
+        let mut builder = client_builder(&config)?.redirect(redirects);
+        if let Some(user_agent) = user_agent.as_deref() {
+            builder = builder.user_agent(user_agent);
+        }
+        Ok(Self {
+            client: builder
+                .build()
+                .map_err(|e| AppError::other(format!("Failed to build HTTP client: {}", e)))?,
+            config,
+            user_agent,
+        })
+    }
+
+    /// Send `request`, retrying timeouts, connection failures and 429s
+    ///
+    /// A 429 waits for its `Retry-After` seconds when given; other retries
+    /// back off exponentially. Requests with a streaming body are sent once.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let mut attempt = 0;
+        loop {
+            let max_retries = self.config.max_retries;
+            let Some(retry) = request.try_clone().filter(|_| attempt < max_retries) else {
+                return request.send().await;
+            };
+            let backoff = self
+                .config
+                .retry_backoff
+                .saturating_mul(2u32.saturating_pow(attempt));
+            attempt += 1;
+            match retry.send().await {
+                Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    let delay = resp
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|h| h.to_str().ok())
+                        .and_then(|s| s.parse().ok())
+                        .map_or(backoff, Duration::from_secs);
+                    warn!(
+                        target: "http",
+                        "Rate limited by {}, retrying in {:?} (attempt {}/{})",
+                        resp.url().host_str().unwrap_or_default(),
+                        delay,
+                        attempt,
+                        max_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    // The URL may carry an API key
+                    let e = e.without_url();
+                    warn!(
+                        target: "http",
+                        "Request failed: {}, retrying in {:?} (attempt {}/{})",
+                        e,
+                        backoff,
+                        attempt,
+                        max_retries
+                    );
+                    tokio::time::sleep(backoff).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Deref for HttpClient {
+    type Target = reqwest::Client;
+
+    fn deref(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+/// Timeouts and proxy of `config`, for clients that add their own settings
+pub fn client_builder(config: &HttpClientConfig) -> AppResult<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(config.timeout)
+        .connect_timeout(config.connect_timeout)
+        .pool_max_idle_per_host(5)
+        .pool_idle_timeout(Duration::from_secs(90));
+    if let Some(proxy) = &config.proxy {
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|e| AppError::other(format!("Invalid proxy URL: {}", e)))?;
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+/// Stored headers for a request, and the domain they were stored for
+#[derive(Debug, Clone)]
+pub struct SiteHeaders {
+    /// Lowercase, without a leading "www."
+    pub domain: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl SiteHeaders {
+    /// Whether the headers may follow a redirect to `url`: over HTTPS, to the
+    /// domain or one of its subdomains
+    pub fn allows(&self, url: &Url) -> bool {
+        within_domain(&self.domain, url)
+    }
+
+    /// Follows the redirects `allows`, stops at the first other one
+    pub fn redirect_policy(&self) -> Policy {
+        let domain = self.domain.clone();
+        Policy::custom(move |attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if within_domain(&domain, attempt.url()) {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        })
+    }
+}
+
+fn within_domain(domain: &str, url: &Url) -> bool {
+    let Some(host) = url.host_str().filter(|_| url.scheme() == "https") else {
+        return false;
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|sub| sub.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn site(domain: &str) -> SiteHeaders {
+        SiteHeaders {
+            domain: domain.to_string(),
+            headers: vec![("x-api-key".to_string(), "secret".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_site_headers_stay_on_https_and_the_domain() {
+        let site = site("example.com");
+        let allows = |url: &str| site.allows(&Url::parse(url).unwrap());
+        assert!(allows("https://example.com/a"));
+        assert!(allows("https://www.Example.com./a"));
+        assert!(allows("https://news.example.com/a"));
+        assert!(!allows("http://example.com/a"));
+        assert!(!allows("https://example.com.evil.net/a"));
+        assert!(!allows("https://notexample.com/a"));
+        assert!(!allows("https://evil.net/?example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_site_client_stops_at_a_foreign_redirect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Redirects to itself over plain HTTP, answered 200 if followed
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            while let Ok(Ok((mut stream, _))) =
+                tokio::time::timeout(Duration::from_millis(500), listener.accept()).await
+            {
+                let mut buf = vec![0; 4096];
+                let n = stream.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let response = if request.starts_with("GET /next") {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{port}/next\r\n\
+                         Content-Length: 0\r\nConnection: close\r\n\r\n"
+                    )
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+
+        let config = HttpClientConfig {
+            timeout: Duration::from_secs(5),
+            connect_timeout: Duration::from_secs(5),
+            proxy: None,
+            user_agent: None,
+            max_retries: 0,
+            retry_backoff: Duration::ZERO,
+        };
+        let client = HttpClient::new(&config, None)
+            .unwrap()
+            .for_site(&site("127.0.0.1"))
+            .unwrap();
+        let response = client
+            .send(client.get(format!("http://127.0.0.1:{port}/start")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FOUND);
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 1, "{:?}", requests);
+    }
+}
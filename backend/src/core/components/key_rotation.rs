@@ -0,0 +1,370 @@
+//! Master key rotation
+//!
+//! `rotate_master_key` replaces `COCKPIT_MASTER_KEY` with a new random key.
+//! Every secret stored under the old key (NewsData settings, feed source API
+//! keys, site credentials, encrypted app settings and research account auth)
+//! is re-encrypted in one database transaction, and the new key is written
+//! to `~/.cockpit/.env` through a temporary file renamed over it. The
+//! transaction commits only once the key file is in place; if the commit
+//! fails, the previous key file is put back.
+//!
+//! Secret writes wait while a rotation runs, and a rotation waits for those
+//! in progress (see [`crypto::secret_write`]), so no secret is stored under
+//! the old key after its table was re-encrypted. The new key reaches the
+//! crypto module through [`SharedConfig`].
+//!
+//! The previous key file is kept as `.env.pre-rotation-<timestamp>`: backups
+//! encrypted with the master key need the old key to be restored. Secrets
+//! the old key can't decrypt, such as research account auth saved as plain
+//! JSON, are left as they are and counted as skipped.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait,
+    IntoActiveModel, QueryFilter, Set, TransactionTrait,
+};
+use serde::Serialize;
+use tracing::{error, info, instrument};
+use zeroize::Zeroizing;
+
+use super::config::SharedConfig;
+use super::crypto::{self, KEY_LEN};
+use super::db::encryption::{file_state, FileState};
+use super::errors::{AppError, AppResult};
+use super::settings::entities as app_settings;
+use super::setup::get_cockpit_home;
+use super::setup_wizard::generate_master_key;
+use crate::research::components::feed::entities::{feed_sources, settings as news_settings};
+use crate::research::entities::{accounts, site_credentials};
+
+const MASTER_KEY_VAR: &str = "COCKPIT_MASTER_KEY";
+
+/// Result of `rotate_master_key`
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationReport {
+    /// The key file, now holding the new key
+    pub key_file: String,
+    /// Copy of the key file with the old key
+    pub previous_key_file: String,
+    pub secrets: Vec<RotatedSecrets>,
+}
+
+/// Secrets of one table
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RotatedSecrets {
+    pub table: String,
+    pub reencrypted: usize,
+    /// Not readable with the old key, left unchanged
+    pub skipped: usize,
+}
+
+impl RotatedSecrets {
+    fn new(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            reencrypted: 0,
+            skipped: 0,
+        }
+    }
+}
+
+/// The old and new keys, zeroized on drop
+struct Rekey {
+    old: Zeroizing<[u8; KEY_LEN]>,
+    new: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl Rekey {
+    /// `data` encrypted with the new key, or `None` when the old key can't
+    /// decrypt it
+    fn reencrypt(&self, data: &[u8], tally: &mut RotatedSecrets) -> AppResult<Option<Vec<u8>>> {
+        let Ok(plaintext) = crypto::decrypt_with_key(&self.old, data).map(Zeroizing::new) else {
+            tally.skipped += 1;
+            return Ok(None);
+        };
+        let encrypted =
+            crypto::encrypt_with_key(&self.new, &plaintext).map_err(|reason| AppError::Crypto {
+                operation: format!("re-encrypt {}", tally.table),
+                reason,
+            })?;
+        tally.reencrypted += 1;
+        Ok(Some(encrypted))
+    }
+}
+
+/// Generate a new master key and move every stored secret and the key file
+/// to it
+///
+/// Only a key set in `~/.cockpit/.env` can be rotated, and not while the
+/// database is encrypted with SQLCipher, whose key derives from it.
+#[instrument(skip_all)]
+pub async fn rotate_master_key(
+    config: &SharedConfig,
+    db: &DatabaseConnection,
+) -> AppResult<KeyRotationReport> {
+    // Held until the new key is in use
+    let _rotation = crypto::key_rotation().await;
+    let current = config.current();
+    if let Some(path) = current.database.sqlite_path() {
+        if file_state(&path)? == FileState::Encrypted {
+            return Err(AppError::validation(
+                "database",
+                "The database is encrypted with a key derived from the master key; \
+                 rotating it is not supported",
+            ));
+        }
+    }
+
+    let key_file = get_cockpit_home()?.join(".env");
+    let contents = Zeroizing::new(
+        fs::read_to_string(&key_file)
+            .map_err(|e| AppError::file_operation("read", key_file.to_string_lossy(), e))?,
+    );
+    let new_key = Zeroizing::new(generate_master_key());
+    let Some(rotated) = replace_master_key(&contents, &current.crypto.master_key, &new_key) else {
+        return Err(AppError::validation(
+            "master_key",
+            format!(
+                "The master key in use is not the {} of {}",
+                MASTER_KEY_VAR,
+                key_file.display()
+            ),
+        )
+        .with_suggestion("Rotate the key where COCKPIT_MASTER_KEY is set"));
+    };
+    let rotated = Zeroizing::new(rotated);
+
+    let invalid_key = |reason| AppError::InvalidKey {
+        reason,
+        suggestion: "Check COCKPIT_MASTER_KEY".to_string(),
+    };
+    let rekey = Rekey {
+        old: Zeroizing::new(
+            crypto::parse_master_key(&current.crypto.master_key).map_err(invalid_key)?,
+        ),
+        new: Zeroizing::new(crypto::parse_master_key(&new_key).map_err(invalid_key)?),
+    };
+
+    // Rolled back when dropped before the commit
+    let txn = db.begin().await?;
+    let secrets = vec![
+        rekey_news_settings(&txn, &rekey).await?,
+        rekey_feed_sources(&txn, &rekey).await?,
+        rekey_site_credentials(&txn, &rekey).await?,
+        rekey_app_settings(&txn, &rekey).await?,
+        rekey_research_accounts(&txn, &rekey).await?,
+    ];
+
+    let previous = sibling(
+        &key_file,
+        &format!(
+            ".pre-rotation-{}",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        ),
+    );
+    let staged = sibling(&key_file, ".rotating");
+    fs::copy(&key_file, &previous)
+        .map_err(|e| AppError::file_operation("copy", previous.to_string_lossy(), e))?;
+    if let Err(e) = write_key_file(&staged, &rotated).and_then(|_| fs::rename(&staged, &key_file)) {
+        let _ = fs::remove_file(&staged);
+        return Err(AppError::file_operation(
+            "write",
+            key_file.to_string_lossy(),
+            e,
+        ));
+    }
+    if let Err(e) = txn.commit().await {
+        // The secrets are still under the old key
+        if let Err(restore) = fs::rename(&previous, &key_file) {
+            error!(
+                error = %restore,
+                previous = %previous.display(),
+                "Failed to restore the key file; copy it back over .env by hand"
+            );
+        }
+        return Err(e.into());
+    }
+    config.set_master_key(new_key.to_string());
+
+    info!(secrets = ?secrets, "Master key rotated");
+    Ok(KeyRotationReport {
+        key_file: key_file.to_string_lossy().into_owned(),
+        previous_key_file: previous.to_string_lossy().into_owned(),
+        secrets,
+    })
+}
+
+async fn rekey_news_settings(
+    txn: &DatabaseTransaction,
+    rekey: &Rekey,
+) -> AppResult<RotatedSecrets> {
+    let mut tally = RotatedSecrets::new("news_settings");
+    for row in news_settings::Entity::find().all(txn).await? {
+        if row.api_key_encrypted.is_empty() {
+            continue;
+        }
+        if let Some(encrypted) = rekey.reencrypt(&row.api_key_encrypted, &mut tally)? {
+            let mut active = row.into_active_model();
+            active.api_key_encrypted = Set(encrypted);
+            active.update(txn).await?;
+        }
+    }
+    Ok(tally)
+}
+
+async fn rekey_feed_sources(txn: &DatabaseTransaction, rekey: &Rekey) -> AppResult<RotatedSecrets> {
+    let mut tally = RotatedSecrets::new("feed_sources");
+    let rows = feed_sources::Entity::find()
+        .filter(feed_sources::Column::ApiKeyEncrypted.is_not_null())
+        .all(txn)
+        .await?;
+    for row in rows {
+        let Some(data) = row.api_key_encrypted.as_deref() else {
+            continue;
+        };
+        if let Some(encrypted) = rekey.reencrypt(data, &mut tally)? {
+            let mut active = row.into_active_model();
+            active.api_key_encrypted = Set(Some(encrypted));
+            active.update(txn).await?;
+        }
+    }
+    Ok(tally)
+}
+
+async fn rekey_site_credentials(
+    txn: &DatabaseTransaction,
+    rekey: &Rekey,
+) -> AppResult<RotatedSecrets> {
+    let mut tally = RotatedSecrets::new("site_credentials");
+    for row in site_credentials::Entity::find().all(txn).await? {
+        if let Some(encrypted) = rekey.reencrypt(&row.secret_encrypted, &mut tally)? {
+            let mut active = row.into_active_model();
+            active.secret_encrypted = Set(encrypted);
+            active.update(txn).await?;
+        }
+    }
+    Ok(tally)
+}
+
+/// Encrypted settings hold base64 of the ciphertext
+async fn rekey_app_settings(txn: &DatabaseTransaction, rekey: &Rekey) -> AppResult<RotatedSecrets> {
+    let mut tally = RotatedSecrets::new("app_settings");
+    let rows = app_settings::Entity::find()
+        .filter(app_settings::Column::IsEncrypted.eq(1))
+        .all(txn)
+        .await?;
+    for row in rows {
+        if row.value.is_empty() {
+            continue;
+        }
+        let Ok(data) = STANDARD.decode(row.value.as_bytes()) else {
+            tally.skipped += 1;
+            continue;
+        };
+        if let Some(encrypted) = rekey.reencrypt(&data, &mut tally)? {
+            let mut active = row.into_active_model();
+            active.value = Set(STANDARD.encode(encrypted));
+            active.update(txn).await?;
+        }
+    }
+    Ok(tally)
+}
+
+async fn rekey_research_accounts(
+    txn: &DatabaseTransaction,
+    rekey: &Rekey,
+) -> AppResult<RotatedSecrets> {
+    let mut tally = RotatedSecrets::new("research_accounts");
+    let rows = accounts::Entity::find()
+        .filter(accounts::Column::AuthEncrypted.is_not_null())
+        .all(txn)
+        .await?;
+    for row in rows {
+        let Some(data) = row.auth_encrypted.as_deref() else {
+            continue;
+        };
+        if let Some(encrypted) = rekey.reencrypt(data, &mut tally)? {
+            let mut active = row.into_active_model();
+            active.auth_encrypted = Set(Some(encrypted));
+            active.update(txn).await?;
+        }
+    }
+    Ok(tally)
+}
+
+/// `contents` of a `.env` file with its `COCKPIT_MASTER_KEY=old` line set to
+/// `new`, or `None` when no such line holds `old`
+fn replace_master_key(contents: &str, old: &str, new: &str) -> Option<String> {
+    let mut found = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            let value = line
+                .trim_start()
+                .trim_start_matches("export ")
+                .strip_prefix(MASTER_KEY_VAR)
+                .and_then(|rest| rest.trim_start().strip_prefix('='))
+                .map(|value| value.trim().trim_matches(|c| c == '"' || c == '\''));
+            if value == Some(old) {
+                found = true;
+                format!("{}={}", MASTER_KEY_VAR, new)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        return None;
+    }
+    if contents.ends_with('\n') {
+        lines.push(String::new());
+    }
+    Some(lines.join("\n"))
+}
+
+/// Write a new key file readable by its owner only
+fn write_key_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace_master_key() {
+        let contents = "# Security\nCOCKPIT_MASTER_KEY=aa11\nLOG_LEVEL=info\n";
+        assert_eq!(
+            replace_master_key(contents, "aa11", "bb22").as_deref(),
+            Some("# Security\nCOCKPIT_MASTER_KEY=bb22\nLOG_LEVEL=info\n")
+        );
+        assert_eq!(
+            replace_master_key("export COCKPIT_MASTER_KEY=\"aa11\"", "aa11", "bb22").as_deref(),
+            Some("COCKPIT_MASTER_KEY=bb22")
+        );
+        // Another key is in use than the file's
+        assert_eq!(replace_master_key(contents, "cc33", "bb22"), None);
+    }
+}
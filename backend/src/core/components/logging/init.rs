@@ -2,12 +2,35 @@
 //!
 //! Configures tracing-subscriber with multiple layers for app logs,
 //! error logs, and optional console output. Supports both JSON and
-//! human-readable formats.
+//! human-readable formats. Spans can additionally be exported over OTLP.
+//! The level filter can be swapped while running (see [`LogFilter`]).
 
-use crate::core::components::config::LoggingConfig;
+use super::otel::{self, TraceExporter};
+use super::stream::LogStreamLayer;
+use crate::core::components::config::{LogFormat, LoggingConfig};
+use crate::core::components::errors::AppError;
 use std::fs;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
+
+/// Handle to the level filter of the running subscriber
+#[derive(Clone)]
+pub struct LogFilter(reload::Handle<EnvFilter, Registry>);
+
+impl LogFilter {
+    /// Filter at `level` from now on; `RUST_LOG` still wins, as at startup
+    pub fn set_level(&self, level: &str) -> Result<(), AppError> {
+        self.0
+            .reload(env_filter(level))
+            .map_err(|e| AppError::other(format!("Failed to change the log level: {}", e)))
+    }
+}
+
+fn env_filter(level: &str) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level))
+}
 
 /// Initialize the logging system with configuration
 ///
@@ -15,16 +38,35 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, Env
 /// - App-wide logging to app.log with rotation
 /// - Error-level logging to errors.log
 /// - Console output
-/// - Optional JSON formatting
-pub fn init_logging(config: &LoggingConfig) {
+/// - Optional ND-JSON formatting (`LOG_FORMAT=json`)
+/// - Live entries for `subscribe_logs`
+/// - Optional OTLP span export; keep the returned exporter and shut it down
+///   on exit so buffered spans are sent
+///
+/// Also returns the handle for changing the level later.
+pub fn init_logging(config: &LoggingConfig) -> (Option<TraceExporter>, LogFilter) {
     // Ensure log directory exists
     if let Some(parent) = config.app_log_path.parent() {
         let _ = fs::create_dir_all(parent);
     }
 
     // Set up environment filter from config
-    let filter =
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.level));
+    let (filter, filter_handle) = reload::Layer::new(env_filter(&config.level));
+
+    // Tracing isn't up yet, so setup failures go to stderr
+    let exporter = config.otlp_endpoint.as_deref().and_then(|endpoint| {
+        match TraceExporter::new(endpoint, &config.otlp_service_name) {
+            Ok(exporter) => Some(exporter),
+            Err(e) => {
+                eprintln!("Failed to set up OTLP export to {}: {}", endpoint, e);
+                None
+            }
+        }
+    });
+    let base = tracing_subscriber::registry()
+        .with(filter)
+        .with(LogStreamLayer)
+        .with(exporter.as_ref().map(otel::layer));
 
     // Create file appender for app logs
     let app_file_appender = if let Some(parent) = config.app_log_path.parent() {
@@ -41,10 +83,14 @@ pub fn init_logging(config: &LoggingConfig) {
         RollingFileAppender::new(Rotation::NEVER, ".", "app.log")
     };
 
-    if config.structured_json {
-        // JSON format for machine parsing
+    if config.format == LogFormat::Json {
+        // ND-JSON for log shippers: event fields at the top level, plus the
+        // current span's fields (request_id, trace_id) for correlation
         let app_layer = fmt::layer()
             .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
             .with_writer(app_file_appender)
             .with_ansi(false);
 
@@ -61,6 +107,9 @@ pub fn init_logging(config: &LoggingConfig) {
 
         let error_layer = fmt::layer()
             .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
             .with_writer(error_appender)
             .with_ansi(false)
             .with_filter(EnvFilter::new("error"));
@@ -68,21 +117,18 @@ pub fn init_logging(config: &LoggingConfig) {
         if config.console_output {
             let console_layer = fmt::layer()
                 .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
                 .with_writer(std::io::stdout)
                 .with_ansi(false);
 
-            tracing_subscriber::registry()
-                .with(filter)
-                .with(app_layer)
+            base.with(app_layer)
                 .with(error_layer)
                 .with(console_layer)
                 .init();
         } else {
-            tracing_subscriber::registry()
-                .with(filter)
-                .with(app_layer)
-                .with(error_layer)
-                .init();
+            base.with(app_layer).with(error_layer).init();
         }
     } else {
         // Human-readable format
@@ -111,18 +157,14 @@ pub fn init_logging(config: &LoggingConfig) {
                 .with_writer(std::io::stdout)
                 .with_ansi(true);
 
-            tracing_subscriber::registry()
-                .with(filter)
-                .with(app_layer)
+            base.with(app_layer)
                 .with(error_layer)
                 .with(console_layer)
                 .init();
         } else {
-            tracing_subscriber::registry()
-                .with(filter)
-                .with(app_layer)
-                .with(error_layer)
-                .init();
+            base.with(app_layer).with(error_layer).init();
         }
     }
+
+    (exporter, LogFilter(filter_handle))
 }
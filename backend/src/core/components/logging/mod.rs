@@ -29,13 +29,18 @@
 //! - sanitize: Sensitive data redaction
 //! - api: API call logging
 //! - utils: Maintenance utilities
+//! - otel: Optional OTLP span export
+//! - stream: Live log entries for `/ws/logs`
 
 mod api;
 mod init;
+mod otel;
 mod rotation;
 mod sanitize;
+mod stream;
 mod utils;
 
 // Re-export public API
 pub use api::log_api_call;
-pub use init::init_logging;
+pub use init::{init_logging, LogFilter};
+pub use stream::{subscribe_logs, LogStreamFilter};
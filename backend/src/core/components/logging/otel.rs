@@ -0,0 +1,56 @@
+//! OpenTelemetry span export
+//!
+//! When `OTEL_EXPORTER_OTLP_ENDPOINT` is set, every tracing span (bridge
+//! requests, scheduled task runs, feed syncs down to single articles) is also
+//! sent over OTLP/gRPC, so a run can be inspected in Jaeger or Tempo. Log
+//! files are written as before.
+
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Batching span exporter; call `shutdown` on exit to flush it
+pub struct TraceExporter {
+    provider: TracerProvider,
+}
+
+impl TraceExporter {
+    pub fn new(endpoint: &str, service_name: &str) -> Result<Self, TraceError> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new(vec![
+                KeyValue::new("service.name", service_name.to_string()),
+                KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+            ]))
+            .build();
+        Ok(Self { provider })
+    }
+
+    fn tracer(&self) -> Tracer {
+        self.provider.tracer("cockpit")
+    }
+
+    /// Send spans still in the batch and stop exporting
+    pub fn shutdown(self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to flush OTLP spans: {}", e);
+        }
+    }
+}
+
+/// Tracing layer that hands finished spans to the exporter
+pub(super) fn layer<S>(exporter: &TraceExporter) -> OpenTelemetryLayer<S, Tracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(exporter.tracer())
+}
@@ -0,0 +1,169 @@
+//! Live log stream
+//!
+//! A tracing layer copies every event that passes the log filter onto a
+//! broadcast channel, so `/ws/logs` can tail the app log without polling the
+//! files. Entries have the same shape as `get_application_logs` returns;
+//! nothing is built while no one is subscribed.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+use crate::core::components::storage::LogEntry;
+
+/// Entries a slow subscriber may fall behind before it misses some
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Transport crates whose own events would echo every frame sent to a
+/// subscriber back into the stream
+const SKIPPED_TARGETS: &[&str] = &["tungstenite", "tokio_tungstenite", "hyper"];
+
+fn channel() -> &'static broadcast::Sender<LogEntry> {
+    static CHANNEL: OnceLock<broadcast::Sender<LogEntry>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Receive every log entry from now on
+pub fn subscribe_logs() -> broadcast::Receiver<LogEntry> {
+    channel().subscribe()
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, Value::from(format!("{:?}", value)));
+    }
+}
+
+/// Layer feeding `subscribe_logs`
+pub(super) struct LogStreamLayer;
+
+impl<S: Subscriber> Layer<S> for LogStreamLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let sender = channel();
+        let target = event.metadata().target();
+        if sender.receiver_count() == 0
+            || SKIPPED_TARGETS
+                .iter()
+                .any(|skipped| target.starts_with(skipped))
+        {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let _ = sender.send(LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            level: event.metadata().level().to_string(),
+            target: target.to_string(),
+            message: visitor.message,
+            fields: (!visitor.fields.is_empty()).then_some(visitor.fields),
+        });
+    }
+}
+
+/// Minimum level and target prefixes a stream subscriber asked for
+#[derive(Debug, Clone, Default)]
+pub struct LogStreamFilter {
+    level: Option<Level>,
+    targets: Vec<String>,
+}
+
+impl LogStreamFilter {
+    /// `level` is a level name (`warn`, `INFO`, ...); `targets` is a
+    /// comma-separated list of target prefixes
+    pub fn parse(level: Option<&str>, targets: Option<&str>) -> Result<Self, String> {
+        let level = level
+            .map(|l| {
+                l.parse::<Level>()
+                    .map_err(|_| format!("unknown log level: {}", l))
+            })
+            .transpose()?;
+        let targets = targets
+            .unwrap_or_default()
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        Ok(Self { level, targets })
+    }
+
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        // More verbose levels compare greater
+        let level_ok = match (self.level, entry.level.parse::<Level>()) {
+            (Some(min), Ok(level)) => level <= min,
+            _ => true,
+        };
+        level_ok
+            && (self.targets.is_empty() || self.targets.iter().any(|t| entry.target.starts_with(t)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_events_reach_subscribers() {
+        let mut logs = subscribe_logs();
+        let subscriber = tracing_subscriber::registry().with(LogStreamLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "scheduler", task_id = 3, "Task failed");
+        });
+        let entry = logs.try_recv().unwrap();
+        assert_eq!(entry.level, "WARN");
+        assert_eq!(entry.message, "Task failed");
+        assert_eq!(entry.fields.as_ref().unwrap()["task_id"], 3);
+
+        let filter = LogStreamFilter::parse(Some("warn"), Some("scheduler, api")).unwrap();
+        assert!(filter.matches(&entry));
+        let errors = LogStreamFilter::parse(Some("error"), None).unwrap();
+        assert!(!errors.matches(&entry));
+        let other = LogStreamFilter::parse(None, Some("research")).unwrap();
+        assert!(!other.matches(&entry));
+        assert!(LogStreamFilter::parse(Some("loud"), None).is_err());
+    }
+}
@@ -1,13 +1,24 @@
 //! Core infrastructure components
 
+pub mod ai;
+pub mod audit;
 pub mod config;
+pub mod credentials;
 pub mod crypto;
 pub mod db;
+pub mod embeddings;
+pub mod error_reporting;
 pub mod errors;
 pub mod events;
+pub mod http;
+pub mod key_rotation;
 pub mod logging;
+pub mod pagination;
+pub mod profile;
 pub mod reader;
 pub mod settings;
 pub mod setup;
 pub mod setup_wizard;
+pub mod shutdown;
 pub mod storage;
+pub mod workspaces;
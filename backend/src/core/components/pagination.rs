@@ -0,0 +1,53 @@
+//! Paginated list envelopes
+//!
+//! List commands return bare arrays by default. Callers that need page
+//! controls pass `envelope: true` and get a `Page` with the total match count
+//! instead.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Matches across all pages
+    pub total: u64,
+    pub limit: u64,
+    pub offset: u64,
+    pub has_more: bool,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: u64, limit: u64, offset: u64) -> Self {
+        let has_more = offset + (items.len() as u64) < total;
+        Self {
+            items,
+            total,
+            limit,
+            offset,
+            has_more,
+        }
+    }
+}
+
+/// A bare list, or a `Page` when the caller asked for the envelope
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(untagged)]
+pub enum Listing<T> {
+    Items(Vec<T>),
+    Page(Page<T>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_has_more() {
+        assert!(Page::new(vec![1, 2], 5, 2, 0).has_more);
+        assert!(Page::new(vec![3, 4], 5, 2, 2).has_more);
+        assert!(!Page::new(vec![5], 5, 2, 4).has_more);
+        assert!(!Page::new(Vec::<i32>::new(), 0, 50, 0).has_more);
+    }
+}
@@ -0,0 +1,413 @@
+//! Portable configuration profiles
+//!
+//! `export_profile` gathers the configuration worth carrying to another
+//! install: the non-secret app settings, feed sources without their API
+//! keys, task schedules, and mute and alert rules. `import_profile` applies
+//! one through the same handlers the UI uses, so every entry is validated as
+//! if it were entered by hand. Entries already present are skipped, which
+//! makes importing the same profile twice harmless.
+
+use std::collections::{BTreeMap, HashSet};
+
+use schemars::JsonSchema;
+use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+use super::errors::{AppError, AppResult};
+use super::settings::entities as app_settings;
+use super::settings::{registry, update_setting_handler, UpdateSettingInput};
+use crate::research::components::feed::entities::feed_sources::SourceConfig;
+use crate::research::components::feed::{
+    create_alert_rule_handler, create_feed_source_handler, create_mute_rule_handler,
+    list_alert_rules_handler, list_feed_sources_handler, list_mute_rules_handler,
+    toggle_feed_source_handler, CreateAlertRuleInput, CreateFeedSourceInput, CreateMuteRuleInput,
+};
+use crate::system::components::scheduler::{
+    list_system_tasks_handler, update_system_task_handler, UpdateTaskInput,
+};
+
+/// Format of the profiles written by this version
+pub const PROFILE_VERSION: u32 = 1;
+
+/// Feed source tasks are keyed by source id, so they travel with their source
+const FEED_TASK_PREFIX: &str = "feed_sync_";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    /// Profiles newer than [`PROFILE_VERSION`] are refused
+    pub version: u32,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    /// Setting key to value; secrets are left out
+    #[serde(default)]
+    pub settings: BTreeMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub feed_sources: Vec<ProfileFeedSource>,
+    #[serde(default)]
+    pub tasks: Vec<ProfileTask>,
+    #[serde(default)]
+    pub mute_rules: Vec<ProfileMuteRule>,
+    #[serde(default)]
+    pub alert_rules: Vec<ProfileAlertRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileFeedSource {
+    pub name: String,
+    pub source_type: String,
+    pub enabled: bool,
+    pub config: Option<SourceConfig>,
+    /// Cron expression of its sync task
+    pub schedule: Option<String>,
+    /// Had an API key, which has to be entered again after import
+    #[serde(default)]
+    pub had_api_key: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileTask {
+    pub task_type: String,
+    pub enabled: bool,
+    pub frequency_cron: Option<String>,
+    pub frequency_seconds: Option<i64>,
+    pub interval_seconds: Option<i64>,
+    pub run_window: Option<String>,
+    pub outside_window: String,
+    pub jitter_seconds: Option<i64>,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    pub catch_up: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileMuteRule {
+    pub rule_type: String,
+    pub pattern: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileAlertRule {
+    pub name: String,
+    pub keywords: Vec<String>,
+    #[serde(default)]
+    pub sources: Vec<String>,
+    #[serde(default)]
+    pub languages: Vec<String>,
+    pub match_all: bool,
+    pub enabled: bool,
+}
+
+/// Entries of one section applied and passed over
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCount {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileImportReport {
+    pub settings: ImportCount,
+    pub feed_sources: ImportCount,
+    pub tasks: ImportCount,
+    pub mute_rules: ImportCount,
+    pub alert_rules: ImportCount,
+    /// Imported feed sources whose API key has to be entered again
+    pub needs_api_key: Vec<String>,
+    /// Why each skipped entry was skipped
+    pub skipped: Vec<String>,
+}
+
+/// Count an entry as imported, or as skipped with its reason
+fn tally(count: &mut ImportCount, skipped: &mut Vec<String>, outcome: Result<(), String>) {
+    match outcome {
+        Ok(()) => count.imported += 1,
+        Err(reason) => {
+            count.skipped += 1;
+            skipped.push(reason);
+        }
+    }
+}
+
+/// The configuration of this install, without any secret
+#[instrument(skip(state))]
+pub async fn export_profile(state: &crate::AppState) -> AppResult<Profile> {
+    let db = &state.db;
+
+    // Undeclared keys can't be imported anywhere
+    let settings = app_settings::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            let spec = registry::lookup(&row.key).ok()?;
+            (!spec.is_secret()).then(|| (row.key, spec.kind.parse_stored(&row.value)))
+        })
+        .collect();
+
+    let feed_sources = list_feed_sources_handler(db)
+        .await?
+        .into_iter()
+        .map(|source| ProfileFeedSource {
+            name: source.name,
+            source_type: source.source_type,
+            enabled: source.enabled,
+            config: source.config,
+            schedule: source.schedule,
+            had_api_key: source.has_api_key,
+        })
+        .collect();
+
+    let tasks = list_system_tasks_handler(state)
+        .await?
+        .into_iter()
+        .filter(|task| !task.task_type.starts_with(FEED_TASK_PREFIX))
+        .map(|task| ProfileTask {
+            task_type: task.task_type,
+            enabled: task.enabled,
+            frequency_cron: task.frequency_cron,
+            frequency_seconds: task.frequency_seconds,
+            interval_seconds: task.interval_seconds,
+            run_window: task.run_window,
+            outside_window: task.outside_window,
+            jitter_seconds: task.jitter_seconds,
+            depends_on: task.depends_on,
+            catch_up: task.catch_up,
+        })
+        .collect();
+
+    let mute_rules = list_mute_rules_handler(db)
+        .await?
+        .into_iter()
+        .map(|rule| ProfileMuteRule {
+            rule_type: rule.rule_type,
+            pattern: rule.pattern,
+            enabled: rule.enabled,
+        })
+        .collect();
+
+    let alert_rules = list_alert_rules_handler(db)
+        .await?
+        .into_iter()
+        .map(|rule| ProfileAlertRule {
+            name: rule.name,
+            keywords: rule.keywords,
+            sources: rule.sources,
+            languages: rule.languages,
+            match_all: rule.match_all,
+            enabled: rule.enabled,
+        })
+        .collect();
+
+    Ok(Profile {
+        version: PROFILE_VERSION,
+        exported_at: Some(chrono::Utc::now().to_rfc3339()),
+        settings,
+        feed_sources,
+        tasks,
+        mute_rules,
+        alert_rules,
+    })
+}
+
+fn check_version(profile: &Profile) -> AppResult<()> {
+    if profile.version == 0 || profile.version > PROFILE_VERSION {
+        return Err(AppError::Validation {
+            field: "version".to_string(),
+            reason: format!(
+                "Unsupported profile version (this install reads versions 1 to {})",
+                PROFILE_VERSION
+            ),
+            invalid_value: Some(profile.version.to_string()),
+        });
+    }
+    Ok(())
+}
+
+/// Apply `profile`; entries that fail validation or already exist are
+/// skipped and reported, the rest are applied
+#[instrument(skip(state, profile))]
+pub async fn import_profile(
+    state: &crate::AppState,
+    profile: Profile,
+) -> AppResult<ProfileImportReport> {
+    check_version(&profile)?;
+    let db = &state.db;
+    let mut report = ProfileImportReport::default();
+
+    for (key, value) in profile.settings {
+        let outcome = match registry::lookup(&key) {
+            Ok(spec) if spec.is_secret() => {
+                Err(format!("Setting {}: secrets aren't imported", key))
+            }
+            _ => update_setting_handler(
+                db,
+                UpdateSettingInput {
+                    key: key.clone(),
+                    value,
+                },
+            )
+            .await
+            .map_err(|e| format!("Setting {}: {}", key, e)),
+        };
+        tally(&mut report.settings, &mut report.skipped, outcome);
+    }
+
+    let existing: HashSet<(String, String)> = list_feed_sources_handler(db)
+        .await?
+        .into_iter()
+        .map(|source| (source.name, source.source_type))
+        .collect();
+    for source in profile.feed_sources {
+        let name = source.name.clone();
+        let outcome = if existing.contains(&(source.name.clone(), source.source_type.clone())) {
+            Err(format!("Feed source {}: already exists", name))
+        } else {
+            import_feed_source(db, source, &mut report.needs_api_key)
+                .await
+                .map_err(|e| format!("Feed source {}: {}", name, e))
+        };
+        tally(&mut report.feed_sources, &mut report.skipped, outcome);
+    }
+
+    let task_types: HashSet<String> = list_system_tasks_handler(state)
+        .await?
+        .into_iter()
+        .map(|task| task.task_type)
+        .collect();
+    for task in profile.tasks {
+        let task_type = task.task_type.clone();
+        let outcome = if !task_types.contains(&task_type) {
+            Err(format!("Task {}: no such task in this install", task_type))
+        } else {
+            update_system_task_handler(task_type.clone(), task_input(task), state)
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Task {}: {}", task_type, e))
+        };
+        tally(&mut report.tasks, &mut report.skipped, outcome);
+    }
+
+    for rule in profile.mute_rules {
+        let label = format!("Mute rule {} {}", rule.rule_type, rule.pattern);
+        let input = CreateMuteRuleInput {
+            rule_type: rule.rule_type,
+            pattern: rule.pattern,
+            enabled: Some(rule.enabled),
+        };
+        let outcome = create_mute_rule_handler(db, input)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("{}: {}", label, e));
+        tally(&mut report.mute_rules, &mut report.skipped, outcome);
+    }
+
+    let alert_names: HashSet<String> = list_alert_rules_handler(db)
+        .await?
+        .into_iter()
+        .map(|rule| rule.name)
+        .collect();
+    for rule in profile.alert_rules {
+        let name = rule.name.clone();
+        let outcome = if alert_names.contains(name.trim()) {
+            Err(format!("Alert rule {}: already exists", name))
+        } else {
+            let input = CreateAlertRuleInput {
+                name: rule.name,
+                keywords: rule.keywords,
+                sources: Some(rule.sources),
+                languages: Some(rule.languages),
+                match_all: Some(rule.match_all),
+                enabled: Some(rule.enabled),
+            };
+            create_alert_rule_handler(db, input)
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Alert rule {}: {}", name, e))
+        };
+        tally(&mut report.alert_rules, &mut report.skipped, outcome);
+    }
+
+    if !report.skipped.is_empty() {
+        warn!(
+            skipped = report.skipped.len(),
+            "Some profile entries were skipped"
+        );
+    }
+    info!(
+        settings = report.settings.imported,
+        feed_sources = report.feed_sources.imported,
+        tasks = report.tasks.imported,
+        mute_rules = report.mute_rules.imported,
+        alert_rules = report.alert_rules.imported,
+        "Profile imported"
+    );
+    Ok(report)
+}
+
+async fn import_feed_source(
+    db: &sea_orm::DatabaseConnection,
+    source: ProfileFeedSource,
+    needs_api_key: &mut Vec<String>,
+) -> AppResult<()> {
+    let input = CreateFeedSourceInput {
+        name: source.name.clone(),
+        source_type: source.source_type,
+        api_key: None,
+        config: source.config,
+        schedule: source.schedule,
+    };
+    let created = create_feed_source_handler(db, input).await?;
+    if !source.enabled {
+        toggle_feed_source_handler(db, created.id, false).await?;
+    }
+    if source.had_api_key {
+        needs_api_key.push(source.name);
+    }
+    Ok(())
+}
+
+/// Every schedule field is sent, so the imported task ends up with exactly
+/// the profile's schedule
+fn task_input(task: ProfileTask) -> UpdateTaskInput {
+    UpdateTaskInput {
+        enabled: Some(task.enabled),
+        frequency_seconds: Some(task.frequency_seconds),
+        frequency_cron: Some(task.frequency_cron),
+        interval_seconds: Some(task.interval_seconds),
+        name: None,
+        run_window: Some(task.run_window),
+        outside_window: Some(task.outside_window),
+        jitter_seconds: Some(task.jitter_seconds),
+        depends_on: Some(task.depends_on),
+        catch_up: Some(task.catch_up),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_sections_are_optional() {
+        let profile: Profile =
+            serde_json::from_str(r#"{"version":1,"settings":{"app.theme":"light"}}"#).unwrap();
+        assert!(check_version(&profile).is_ok());
+        assert_eq!(profile.settings["app.theme"], "light");
+        assert!(profile.feed_sources.is_empty() && profile.alert_rules.is_empty());
+
+        let newer = Profile {
+            version: PROFILE_VERSION + 1,
+            ..profile
+        };
+        assert!(check_version(&newer).is_err());
+    }
+}
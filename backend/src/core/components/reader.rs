@@ -1,6 +1,9 @@
 //! Shared reader extraction helpers
 
+use std::collections::HashSet;
+
 use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::{HttpClient, SiteHeaders};
 use ammonia::Builder;
 use html2md::parse_html;
 use regex::Regex;
@@ -8,6 +11,22 @@ use reqwest::Url;
 use scraper::{Html, Selector};
 
 const MAX_HTML_BYTES: usize = 15 * 1024 * 1024;
+/// Sent by the reader client unless `HTTP_READER_USER_AGENT` is set
+pub(crate) const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) CockpitReader/1.0";
+
+/// Site-specific overrides for a page that extracts badly
+#[derive(Debug, Clone, Default)]
+pub struct ExtractionRule {
+    /// CSS selector list for the content to keep; falls back to the default
+    /// article/main/body search when nothing matches
+    pub keep_selectors: Option<String>,
+    /// CSS selector list for elements stripped before extraction
+    pub remove_selectors: Option<String>,
+    pub user_agent: Option<String>,
+    /// Extra request headers, such as stored site cookies
+    pub headers: Option<SiteHeaders>,
+}
 
 pub struct ReaderExtracted {
     pub title: String,
@@ -16,33 +35,47 @@ pub struct ReaderExtracted {
     pub content_html: String,
     pub content_text: String,
     pub content_md: String,
+    /// Page as fetched, before extraction
+    pub raw_html: String,
 }
 
 pub async fn extract_reader_content(
-    http_client: &reqwest::Client,
+    http_client: &HttpClient,
     url: &str,
     title_override: Option<String>,
+    rule: Option<&ExtractionRule>,
 ) -> AppResult<ReaderExtracted> {
     let normalized_url = normalize_reader_url(url)?;
-    let raw_html = fetch_html(http_client, &normalized_url).await?;
+    let raw_html = fetch_html(http_client, &normalized_url, rule).await?;
+    Ok(extract_from_html(normalized_url, raw_html, title_override, rule))
+}
+
+/// Run extraction over an already fetched page
+pub fn extract_from_html(
+    final_url: String,
+    raw_html: String,
+    title_override: Option<String>,
+    rule: Option<&ExtractionRule>,
+) -> ReaderExtracted {
     let title = title_override
         .map(|t| t.trim().to_string())
         .filter(|t| !t.is_empty())
         .or_else(|| extract_title(&raw_html))
         .unwrap_or_else(|| "Untitled reference".to_string());
     let excerpt = Some(extract_excerpt(&raw_html));
-    let main_html = extract_main_html(&raw_html).unwrap_or(raw_html);
+    let main_html = extract_main_html(&raw_html, rule).unwrap_or_else(|| raw_html.clone());
     let content_html = sanitize_html(&main_html);
     let content_text = html_to_text(&content_html);
     let content_md = parse_html(&content_html);
-    Ok(ReaderExtracted {
+    ReaderExtracted {
         title,
         excerpt,
-        final_url: normalized_url,
+        final_url,
         content_html,
         content_text,
         content_md,
-    })
+        raw_html,
+    }
 }
 
 pub fn normalize_reader_url(raw: &str) -> AppResult<String> {
@@ -62,15 +95,25 @@ pub fn normalize_reader_url(raw: &str) -> AppResult<String> {
         .map_err(|e| AppError::other(format!("Reference URL is invalid: {}", e)))
 }
 
-async fn fetch_html(http_client: &reqwest::Client, url: &str) -> AppResult<String> {
-    let response = http_client
-        .get(url)
-        .header(
-            reqwest::header::USER_AGENT,
-            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) CockpitReader/1.0",
-        )
-        .send()
-        .await?;
+/// Fetch a page's HTML, applying the rule's user agent and headers
+///
+/// With headers, redirects leaving HTTPS or their domain aren't followed.
+pub async fn fetch_html(
+    http_client: &HttpClient,
+    url: &str,
+    rule: Option<&ExtractionRule>,
+) -> AppResult<String> {
+    let site = rule.and_then(|r| r.headers.as_ref());
+    let site_client = site.map(|site| http_client.for_site(site)).transpose()?;
+    let http_client = site_client.as_ref().unwrap_or(http_client);
+    let mut request = http_client.get(url);
+    if let Some(user_agent) = rule.and_then(|r| r.user_agent.as_deref()) {
+        request = request.header(reqwest::header::USER_AGENT, user_agent);
+    }
+    for (name, value) in site.map(|s| s.headers.as_slice()).unwrap_or_default() {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = http_client.send(request).await?;
 
     if !response.status().is_success() {
         return Err(AppError::other(format!(
@@ -109,8 +152,46 @@ fn extract_title(html: &str) -> Option<String> {
         .filter(|title| !title.is_empty())
 }
 
-fn extract_main_html(html: &str) -> Option<String> {
-    let document = Html::parse_document(html);
+/// Check that a CSS selector list parses
+pub fn check_selectors(selectors: &str) -> Result<(), String> {
+    Selector::parse(selectors)
+        .map(|_| ())
+        .map_err(|_| format!("Invalid CSS selector: {}", selectors))
+}
+
+fn selector_list(selectors: Option<&str>) -> Option<Selector> {
+    selectors
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .and_then(|s| Selector::parse(s).ok())
+}
+
+fn extract_main_html(html: &str, rule: Option<&ExtractionRule>) -> Option<String> {
+    let mut document = Html::parse_document(html);
+
+    if let Some(remove) = selector_list(rule.and_then(|r| r.remove_selectors.as_deref())) {
+        let ids: Vec<_> = document.select(&remove).map(|node| node.id()).collect();
+        for id in ids {
+            if let Some(mut node) = document.tree.get_mut(id) {
+                node.detach();
+            }
+        }
+    }
+
+    if let Some(keep) = selector_list(rule.and_then(|r| r.keep_selectors.as_deref())) {
+        let matched: Vec<_> = document.select(&keep).collect();
+        let ids: HashSet<_> = matched.iter().map(|node| node.id()).collect();
+        // Nested matches are already part of their matched ancestor
+        let kept: Vec<String> = matched
+            .iter()
+            .filter(|node| !node.ancestors().any(|a| ids.contains(&a.id())))
+            .map(|node| node.html())
+            .collect();
+        if !kept.is_empty() {
+            return Some(kept.join("\n"));
+        }
+    }
+
     let selectors = ["article", "main", "body"];
     for selector_str in selectors {
         if let Ok(selector) = Selector::parse(selector_str) {
@@ -149,3 +230,37 @@ fn extract_excerpt(html: &str) -> String {
         format!("{}...", &text[..220])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_main_html_with_rule() {
+        let html = r#"<html><body><nav>Menu</nav><div class="post"><p>Body</p>
+<div class="ad">Buy</div><div class="post">Inner</div></div><footer>Foot</footer></body></html>"#;
+
+        let rule = ExtractionRule {
+            keep_selectors: Some(".post".into()),
+            remove_selectors: Some(".ad, nav".into()),
+            ..Default::default()
+        };
+        let kept = extract_main_html(html, Some(&rule)).unwrap();
+        assert!(kept.contains("Body"));
+        assert!(!kept.contains("Buy"));
+        assert!(!kept.contains("Foot"));
+        assert_eq!(kept.matches("Inner").count(), 1);
+
+        // Falls back to the default search when the keep selector misses
+        let rule = ExtractionRule {
+            keep_selectors: Some(".missing".into()),
+            remove_selectors: Some("footer".into()),
+            ..Default::default()
+        };
+        let fallback = extract_main_html(html, Some(&rule)).unwrap();
+        assert!(fallback.contains("Menu"));
+        assert!(!fallback.contains("Foot"));
+
+        assert!(check_selectors("div >> [").is_err());
+    }
+}
@@ -3,8 +3,9 @@
 //! Provides functions for reading and updating application settings
 //! with encryption, validation, and audit logging.
 
-use super::entities::{Column, Entity};
-use super::types::{AppSettingsDto, SettingValue, UpdateSettingInput};
+use super::entities::{ActiveModel, Column, Entity};
+use super::registry;
+use super::types::{AppSettingsDto, RevealedSetting, SettingValue, UpdateSettingInput};
 use super::validation::validate_setting_value;
 use crate::core::components::crypto;
 use crate::core::components::errors::AppError;
@@ -15,10 +16,14 @@ use sea_orm::{
 use std::collections::HashMap;
 use tracing::{error, info, instrument, warn};
 
+/// Value of a set secret in `AppSettingsDto`
+pub const SECRET_MASK: &str = "********";
+
 /// Get all app settings grouped by category
 ///
-/// Retrieves all settings from the database, decrypts encrypted values,
-/// and groups them by category (general, news, writing, appearance, advanced).
+/// Retrieves all settings from the database, masks secrets, and groups
+/// them by category (general, news, writing, appearance, advanced).
+/// Secret values are read with [`reveal_setting_handler`].
 #[instrument(skip(db), fields(otel.name = "get_app_settings"))]
 pub async fn get_app_settings_handler(
     db: &DatabaseConnection,
@@ -44,31 +49,13 @@ pub async fn get_app_settings_handler(
     };
 
     for setting in settings {
-        let mut value_str = setting.value.clone();
-
-        // Decrypt if encrypted
-        if setting.is_encrypted == 1 && !value_str.is_empty() {
-            // Assume it's base64 encoded encrypted data
-            if let Ok(decoded) = base64::Engine::decode(
-                &base64::engine::general_purpose::STANDARD,
-                value_str.as_bytes(),
-            ) {
-                match crypto::decrypt_api_key(&decoded) {
-                    Ok(decrypted) => {
-                        info!(setting_key = %setting.key, "Successfully decrypted setting value");
-                        value_str = decrypted;
-                    }
-                    Err(e) => {
-                        warn!(
-                            setting_key = %setting.key,
-                            error = %e,
-                            "Failed to decrypt setting, using empty value"
-                        );
-                        value_str = String::new();
-                    }
-                }
-            }
-        }
+        // Secrets are never sent back; an empty value still says "unset"
+        let is_secret = setting.is_encrypted == 1 || setting.value_type == "secret";
+        let value_str = if is_secret && !setting.value.is_empty() {
+            SECRET_MASK.to_string()
+        } else {
+            setting.value.clone()
+        };
 
         // Parse value based on type
         let parsed_value = match setting.value_type.as_str() {
@@ -111,6 +98,7 @@ pub async fn get_app_settings_handler(
 /// Update a single setting
 ///
 /// Updates a setting in the database with validation and encryption.
+/// The key must be declared in the settings registry.
 /// Logs all changes for audit trail.
 #[instrument(skip(db, input), fields(setting_key = %input.key))]
 pub async fn update_setting_handler(
@@ -119,50 +107,57 @@ pub async fn update_setting_handler(
 ) -> Result<(), AppError> {
     info!(setting_key = %input.key, "Updating app setting");
 
-    // Find existing setting
-    let existing = Entity::find()
+    // Unknown keys and bad values are refused before touching the database
+    let spec = registry::lookup(&input.key)?;
+    let value_str = validate_setting_value(spec, &input.value)?;
+
+    // The mask from `get_app_settings` sent back unchanged keeps the secret
+    if spec.is_secret() && value_str == SECRET_MASK {
+        info!(setting_key = %input.key, "Secret left unchanged");
+        return Ok(());
+    }
+
+    // Find existing setting; declared keys without a row get one
+    let existing = match Entity::find()
         .filter(Column::Key.eq(&input.key))
         .one(db)
         .await
         .map_err(|e| {
             error!(error = %e, setting_key = %input.key, "Database error fetching setting");
             AppError::database(e.to_string())
-        })?
-        .ok_or_else(|| {
-            error!(setting_key = %input.key, "Setting not found in database");
-            AppError::validation("key", format!("Setting '{}' not found", input.key))
-        })?;
+        })? {
+        Some(existing) => existing,
+        None => {
+            warn!(setting_key = %input.key, "Setting missing from database, creating it");
+            let now = chrono::Utc::now().to_rfc3339();
+            ActiveModel {
+                key: ActiveValue::Set(spec.key.to_string()),
+                value: ActiveValue::Set(spec.default.to_string()),
+                value_type: ActiveValue::Set(spec.kind.value_type().to_string()),
+                category: ActiveValue::Set(spec.category.to_string()),
+                description: ActiveValue::Set(Some(spec.description.to_string())),
+                is_encrypted: ActiveValue::Set(spec.is_secret() as i32),
+                created_at: ActiveValue::Set(now.clone()),
+                updated_at: ActiveValue::Set(now),
+                ..Default::default()
+            }
+            .insert(db)
+            .await
+            .map_err(|e| {
+                error!(error = %e, setting_key = %input.key, "Failed to create setting");
+                AppError::database(e.to_string())
+            })?
+        }
+    };
 
     info!(
         setting_key = %input.key,
         category = %existing.category,
-        value_type = %existing.value_type,
-        is_encrypted = existing.is_encrypted == 1,
+        value_type = %spec.kind.value_type(),
+        is_encrypted = spec.is_secret(),
         "Found existing setting"
     );
 
-    // Validate the value
-    validate_setting_value(&input.key, &input.value, &existing.value_type, &existing.category)?;
-
-    // Convert value to string based on type
-    let value_str = match existing.value_type.as_str() {
-        "boolean" => input.value.as_bool().map(|b| b.to_string()),
-        "number" => input
-            .value
-            .as_f64()
-            .or(input.value.as_i64().map(|i| i as f64))
-            .map(|n| n.to_string()),
-        "json" => Some(serde_json::to_string(&input.value).unwrap_or_default()),
-        _ => input.value.as_str().map(|s| s.to_string()),
-    }
-    .ok_or_else(|| {
-        error!(setting_key = %input.key, value_type = %existing.value_type, "Invalid value type conversion");
-        AppError::validation(
-            "value",
-            format!("Invalid value type for setting '{}'", input.key),
-        )
-    })?;
-
     info!(
         setting_key = %input.key,
         old_value_length = existing.value.len(),
@@ -170,8 +165,11 @@ pub async fn update_setting_handler(
         "Converted value to string"
     );
 
+    // Held until the update, so a key rotation can't slip in between
+    let _secrets = crypto::secret_write().await;
+
     // Encrypt if needed
-    let final_value = if existing.is_encrypted == 1 && !value_str.is_empty() {
+    let final_value = if spec.is_secret() && !value_str.is_empty() {
         info!(setting_key = %input.key, "Encrypting setting value");
         let encrypted = crypto::encrypt_api_key(&value_str).map_err(|e| {
             error!(error = %e, setting_key = %input.key, "Encryption failed");
@@ -191,6 +189,8 @@ pub async fn update_setting_handler(
     // Update the setting
     let mut active = existing.clone().into_active_model();
     active.value = ActiveValue::Set(final_value);
+    active.value_type = ActiveValue::Set(spec.kind.value_type().to_string());
+    active.is_encrypted = ActiveValue::Set(spec.is_secret() as i32);
     active.updated_at = ActiveValue::Set(chrono::Utc::now().to_rfc3339());
 
     active.update(db).await.map_err(|e| {
@@ -261,3 +261,125 @@ pub async fn update_settings_handler(
     );
     Ok(())
 }
+
+/// Read a single setting's raw string value, decrypting it if needed
+///
+/// Returns `None` when the key doesn't exist. Empty values are returned as-is
+/// so callers can treat them as "use the default".
+#[instrument(skip(db))]
+pub(crate) async fn get_setting_value(
+    db: &DatabaseConnection,
+    key: &str,
+) -> Result<Option<String>, AppError> {
+    let Some(setting) = Entity::find()
+        .filter(Column::Key.eq(key))
+        .one(db)
+        .await
+        .map_err(|e| AppError::database(e.to_string()))?
+    else {
+        return Ok(None);
+    };
+
+    if setting.is_encrypted != 1 || setting.value.is_empty() {
+        return Ok(Some(setting.value));
+    }
+
+    let decoded = base64::Engine::decode(
+        &base64::engine::general_purpose::STANDARD,
+        setting.value.as_bytes(),
+    )
+    .map_err(|e| AppError::Crypto {
+        operation: "decode setting".to_string(),
+        reason: e.to_string(),
+    })?;
+    let decrypted = crypto::decrypt_api_key(&decoded).map_err(|reason| AppError::Crypto {
+        operation: "decrypt setting".to_string(),
+        reason,
+    })?;
+    Ok(Some(decrypted))
+}
+
+/// Read a secret setting in clear
+///
+/// Only declared secrets can be revealed; the value is empty when unset.
+#[instrument(skip(db))]
+pub async fn reveal_setting_handler(
+    db: &DatabaseConnection,
+    key: &str,
+) -> Result<RevealedSetting, AppError> {
+    let spec = registry::lookup(key)?;
+    if !spec.is_secret() {
+        return Err(AppError::validation(
+            "key",
+            format!(
+                "Setting '{}' is not a secret, read it with get_app_settings",
+                key
+            ),
+        ));
+    }
+    info!(setting_key = %key, "Revealing secret setting");
+    let secret = get_setting_value(db, key).await?.unwrap_or_default();
+    Ok(RevealedSetting {
+        key: key.to_string(),
+        secret,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use serde_json::json;
+
+    const KEY: &str = "news.newsdata_api_key";
+
+    async fn set(db: &DatabaseConnection, key: &str, value: serde_json::Value) {
+        update_setting_handler(
+            db,
+            UpdateSettingInput {
+                key: key.to_string(),
+                value,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_secrets_are_masked() {
+        let db = test_db().await;
+        set(&db, KEY, json!("nd-secret")).await;
+
+        let stored = Entity::find()
+            .filter(Column::Key.eq(KEY))
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.is_encrypted, 1);
+        assert!(!stored.value.contains("nd-secret"));
+
+        let settings = get_app_settings_handler(&db).await.unwrap();
+        assert_eq!(settings.news[KEY].value, json!(SECRET_MASK));
+    }
+
+    #[tokio::test]
+    async fn test_posting_the_mask_keeps_the_secret() {
+        let db = test_db().await;
+        set(&db, KEY, json!("nd-secret")).await;
+        set(&db, KEY, json!(SECRET_MASK)).await;
+        assert_eq!(
+            reveal_setting_handler(&db, KEY).await.unwrap().secret,
+            "nd-secret"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reveal_rejects_plain_settings() {
+        let db = test_db().await;
+        let err = reveal_setting_handler(&db, "news.auto_sync")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a secret"), "{}", err);
+    }
+}
@@ -2,17 +2,22 @@
 //!
 //! Refactored from monolithic settings.rs into:
 //! - entities: Database model for app_settings table
+//! - registry: Namespaced schema of every setting (type, default, rules)
 //! - types: DTOs and input structures
 //! - validation: Business rules validation
 //! - handlers: Get and update operations with encryption support
 
 pub mod entities;
 pub mod handlers;
+pub mod registry;
 pub mod types;
 pub mod validation;
 
 // Re-export DTOs for API responses
-pub use types::{AppSettingsDto, UpdateSettingInput};
+pub use types::{AppSettingsDto, RevealedSetting, UpdateSettingInput};
 
 // Re-export handlers for Tauri commands
-pub use handlers::{get_app_settings_handler, update_setting_handler, update_settings_handler};
+pub use handlers::{
+    get_app_settings_handler, reveal_setting_handler, update_setting_handler,
+    update_settings_handler,
+};
@@ -0,0 +1,443 @@
+//! Schema of the app settings
+//!
+//! Every key of `app_settings` is declared here, in one namespace per module
+//! (`app`, `news`, `writing`, `storage`, `logging`, `ai`, `scheduler`), with
+//! its type, default, category and validation rule. Updates are checked
+//! against it, so a key missing here can't be written.
+
+use crate::core::components::errors::AppError;
+
+/// Type of a setting and the values it accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    Boolean,
+    /// Whole number in `min..=max`
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// Any text, or one of `choices` when there are some
+    String {
+        choices: &'static [&'static str],
+    },
+    /// Object mapping names to whole numbers
+    Limits,
+    /// Text encrypted with the master key, masked when settings are read
+    Secret,
+}
+
+impl SettingKind {
+    /// `value_type` stored next to the value
+    pub fn value_type(&self) -> &'static str {
+        match self {
+            SettingKind::Boolean => "boolean",
+            SettingKind::Integer { .. } => "number",
+            SettingKind::String { .. } => "string",
+            SettingKind::Limits => "json",
+            SettingKind::Secret => "secret",
+        }
+    }
+
+    /// A stored value as the JSON `validate_setting_value` takes
+    pub fn parse_stored(&self, stored: &str) -> serde_json::Value {
+        match self {
+            SettingKind::Boolean => (stored == "true").into(),
+            SettingKind::Integer { .. } => stored
+                .parse::<i64>()
+                .ok()
+                .or_else(|| stored.parse::<f64>().ok().map(|n| n as i64))
+                .map_or(serde_json::Value::Null, Into::into),
+            SettingKind::String { .. } | SettingKind::Secret => stored.into(),
+            SettingKind::Limits => serde_json::from_str(stored).unwrap_or_default(),
+        }
+    }
+
+    /// What the setting accepts, for error messages
+    pub fn expected(&self) -> String {
+        match self {
+            SettingKind::Boolean => "true or false".to_string(),
+            SettingKind::Integer { min, max } => {
+                format!("a whole number between {} and {}", min, max)
+            }
+            SettingKind::String { choices: [] } | SettingKind::Secret => "a string".to_string(),
+            SettingKind::String { choices } => format!("one of: {}", choices.join(", ")),
+            SettingKind::Limits => "an object mapping names to whole numbers".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SettingSpec {
+    pub key: &'static str,
+    pub kind: SettingKind,
+    /// Stored value of a new row
+    pub default: &'static str,
+    /// Group in `AppSettingsDto`
+    pub category: &'static str,
+    pub description: &'static str,
+}
+
+impl SettingSpec {
+    /// Stored encrypted and never returned by `get_app_settings`
+    pub fn is_secret(&self) -> bool {
+        self.kind == SettingKind::Secret
+    }
+}
+
+/// The settings of one module, keyed `<name>.<setting>`
+pub struct Namespace {
+    pub name: &'static str,
+    pub settings: &'static [SettingSpec],
+}
+
+const fn spec(
+    key: &'static str,
+    kind: SettingKind,
+    default: &'static str,
+    category: &'static str,
+    description: &'static str,
+) -> SettingSpec {
+    SettingSpec {
+        key,
+        kind,
+        default,
+        category,
+        description,
+    }
+}
+
+/// A secret, empty when unset
+const fn secret(
+    key: &'static str,
+    category: &'static str,
+    description: &'static str,
+) -> SettingSpec {
+    spec(key, SettingKind::Secret, "", category, description)
+}
+
+const BOOLEAN: SettingKind = SettingKind::Boolean;
+const TEXT: SettingKind = SettingKind::String { choices: &[] };
+
+const fn integer(min: i64, max: i64) -> SettingKind {
+    SettingKind::Integer { min, max }
+}
+
+const fn one_of(choices: &'static [&'static str]) -> SettingKind {
+    SettingKind::String { choices }
+}
+
+pub const NAMESPACES: &[Namespace] = &[
+    Namespace {
+        name: "app",
+        settings: &[
+            spec(
+                "app.theme",
+                one_of(&["light", "dark", "cyberpunk"]),
+                "dark",
+                "appearance",
+                "App theme: light, dark, or cyberpunk",
+            ),
+            spec(
+                "app.auto_start",
+                BOOLEAN,
+                "false",
+                "general",
+                "Launch app on system startup",
+            ),
+            spec(
+                "app.minimize_to_tray",
+                BOOLEAN,
+                "true",
+                "general",
+                "Minimize to system tray instead of taskbar",
+            ),
+            spec(
+                "app.notifications_enabled",
+                BOOLEAN,
+                "true",
+                "general",
+                "Show desktop notifications",
+            ),
+        ],
+    },
+    Namespace {
+        name: "news",
+        settings: &[
+            spec(
+                "news.auto_sync",
+                BOOLEAN,
+                "true",
+                "news",
+                "Automatically sync news articles",
+            ),
+            spec(
+                "news.sync_interval_minutes",
+                integer(15, 1440),
+                "45",
+                "news",
+                "Minutes between automatic syncs",
+            ),
+            spec(
+                "news.max_articles",
+                integer(100, 10000),
+                "4000",
+                "news",
+                "Maximum articles to store",
+            ),
+            spec(
+                "news.auto_dismiss_read",
+                BOOLEAN,
+                "false",
+                "news",
+                "Auto-dismiss articles after reading",
+            ),
+            secret("news.newsdata_api_key", "news", "NewsData.io API key"),
+        ],
+    },
+    Namespace {
+        name: "writing",
+        settings: &[
+            spec(
+                "writing.auto_save",
+                BOOLEAN,
+                "true",
+                "writing",
+                "Automatically save drafts while typing",
+            ),
+            spec(
+                "writing.auto_save_delay_ms",
+                integer(100, 5000),
+                "600",
+                "writing",
+                "Milliseconds to wait before auto-saving",
+            ),
+            spec(
+                "writing.default_status",
+                one_of(&["in_progress", "stalled", "complete"]),
+                "in_progress",
+                "writing",
+                "Default status for new ideas",
+            ),
+            spec(
+                "writing.spell_check",
+                BOOLEAN,
+                "true",
+                "writing",
+                "Enable spell checking",
+            ),
+            spec(
+                "writing.auto_append_clips",
+                BOOLEAN,
+                "false",
+                "writing",
+                "Append new reader clips to the notes of ideas that link the clipped page",
+            ),
+        ],
+    },
+    Namespace {
+        name: "storage",
+        settings: &[
+            spec(
+                "storage.auto_cleanup",
+                BOOLEAN,
+                "true",
+                "advanced",
+                "Automatically clean up old data",
+            ),
+            spec(
+                "storage.cleanup_days",
+                integer(7, 365),
+                "90",
+                "advanced",
+                "Days to keep old articles",
+            ),
+            spec(
+                "storage.auto_backup",
+                BOOLEAN,
+                "true",
+                "advanced",
+                "Automatically create backups",
+            ),
+            spec(
+                "storage.backup_interval_days",
+                integer(1, 30),
+                "7",
+                "advanced",
+                "Days between automatic backups",
+            ),
+            spec(
+                "storage.max_backup_count",
+                integer(1, 50),
+                "10",
+                "advanced",
+                "Maximum number of backups to keep",
+            ),
+        ],
+    },
+    Namespace {
+        name: "logging",
+        settings: &[
+            spec(
+                "logging.level",
+                one_of(&["trace", "debug", "info", "warn", "error"]),
+                "info",
+                "advanced",
+                "Log level: trace, debug, info, warn, error",
+            ),
+            spec(
+                "logging.max_file_size_mb",
+                integer(1, 100),
+                "50",
+                "advanced",
+                "Maximum log file size in MB",
+            ),
+            spec(
+                "logging.max_files",
+                integer(1, 20),
+                "5",
+                "advanced",
+                "Maximum number of log files to keep",
+            ),
+        ],
+    },
+    Namespace {
+        name: "ai",
+        settings: &[
+            spec(
+                "ai.provider",
+                one_of(&["ollama", "openai", "anthropic"]),
+                "ollama",
+                "advanced",
+                "LLM provider for AI features: ollama, openai, or anthropic",
+            ),
+            spec(
+                "ai.model",
+                TEXT,
+                "",
+                "advanced",
+                "LLM model name (blank uses the provider default)",
+            ),
+            spec(
+                "ai.base_url",
+                TEXT,
+                "",
+                "advanced",
+                "LLM API base URL (blank uses the provider default)",
+            ),
+            secret("ai.openai_api_key", "advanced", "OpenAI API key"),
+            secret("ai.anthropic_api_key", "advanced", "Anthropic API key"),
+        ],
+    },
+    Namespace {
+        name: "scheduler",
+        settings: &[
+            spec(
+                "scheduler.paused",
+                BOOLEAN,
+                "false",
+                "advanced",
+                "Suspend all scheduled task runs (manual runs still work)",
+            ),
+            spec(
+                "scheduler.history_keep_runs",
+                integer(0, 100000),
+                "500",
+                "advanced",
+                "Task runs kept per task in the history (0 for no limit)",
+            ),
+            spec(
+                "scheduler.history_keep_days",
+                integer(0, 3650),
+                "30",
+                "advanced",
+                "Days of task run history to keep (0 for no limit)",
+            ),
+            spec(
+                "scheduler.max_concurrent_tasks",
+                integer(0, 64),
+                "4",
+                "advanced",
+                "Scheduler tasks allowed to run at once (0 for no limit)",
+            ),
+            spec(
+                "scheduler.component_limits",
+                SettingKind::Limits,
+                "{\"research\":2}",
+                "advanced",
+                "Tasks allowed to run at once per component, e.g. {\"research\": 2}",
+            ),
+        ],
+    },
+];
+
+/// Every declared setting
+pub fn all_settings() -> impl Iterator<Item = &'static SettingSpec> {
+    NAMESPACES.iter().flat_map(|ns| ns.settings.iter())
+}
+
+/// The spec of `key`; unknown keys are a validation error listing the
+/// namespaces, or the settings of the key's namespace
+pub fn lookup(key: &str) -> Result<&'static SettingSpec, AppError> {
+    let prefix = key.split_once('.').map_or(key, |(prefix, _)| prefix);
+    let Some(namespace) = NAMESPACES.iter().find(|ns| ns.name == prefix) else {
+        let names: Vec<_> = NAMESPACES.iter().map(|ns| ns.name).collect();
+        return Err(AppError::validation(
+            "key",
+            format!(
+                "Unknown setting '{}': settings are named <namespace>.<name> with namespace one of: {}",
+                key,
+                names.join(", ")
+            ),
+        ));
+    };
+    namespace
+        .settings
+        .iter()
+        .find(|spec| spec.key == key)
+        .ok_or_else(|| {
+            let keys: Vec<_> = namespace.settings.iter().map(|spec| spec.key).collect();
+            AppError::validation(
+                "key",
+                format!(
+                    "Unknown setting '{}'; the {} settings are: {}",
+                    key,
+                    namespace.name,
+                    keys.join(", ")
+                ),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::settings::validation::validate_setting_value;
+
+    #[test]
+    fn test_registry_is_consistent() {
+        let mut seen = std::collections::HashSet::new();
+        for namespace in NAMESPACES {
+            for spec in namespace.settings {
+                assert!(seen.insert(spec.key), "{} declared twice", spec.key);
+                assert!(spec.key.starts_with(&format!("{}.", namespace.name)));
+                let default = spec.kind.parse_stored(spec.default);
+                assert_eq!(
+                    validate_setting_value(spec, &default).unwrap(),
+                    spec.default
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(
+            lookup("scheduler.paused").unwrap().kind,
+            SettingKind::Boolean
+        );
+        let err = lookup("scheduler.pause").unwrap_err().to_string();
+        assert!(err.contains("scheduler.paused"), "{}", err);
+        let err = lookup("theme").unwrap_err().to_string();
+        assert!(err.contains("app, news"), "{}", err);
+    }
+}
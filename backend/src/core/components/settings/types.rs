@@ -2,11 +2,12 @@
 //!
 //! Defines DTOs and input structures for settings management.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// DTO for app settings grouped by category
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AppSettingsDto {
     pub general: HashMap<String, SettingValue>,
@@ -17,18 +18,28 @@ pub struct AppSettingsDto {
 }
 
 /// Individual setting value with metadata
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingValue {
+    /// Masked for `secret` settings
     pub value: serde_json::Value,
     pub value_type: String,
     pub description: Option<String>,
 }
 
 /// Input for updating a single setting
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateSettingInput {
     pub key: String,
     pub value: serde_json::Value,
 }
+
+/// A secret setting in clear, from `reveal_setting`
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RevealedSetting {
+    pub key: String,
+    /// Empty when unset
+    pub secret: String,
+}
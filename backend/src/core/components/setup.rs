@@ -71,10 +71,18 @@ COCKPIT_MASTER_KEY={}
 
 # Logging Configuration
 LOG_LEVEL=info
-LOG_JSON=true
+# text or json (ND-JSON, one event per line, for Loki/Elastic)
+LOG_FORMAT=json
 LOG_CONSOLE=true
 LOG_MAX_SIZE_MB=10
 LOG_MAX_FILES=5
+# Export tracing spans to an OTLP/gRPC collector (Jaeger, Tempo, ...)
+# OTEL_EXPORTER_OTLP_ENDPOINT=http://localhost:4317
+# OTEL_SERVICE_NAME=cockpit
+# Report errors to Sentry or GlitchTip (levels: info, warning, error, fatal)
+# SENTRY_DSN=https://<key>@<host>/<project id>
+# SENTRY_ENVIRONMENT=production
+# ERROR_REPORTING_MIN_SEVERITY=error
 
 # Database Configuration
 DB_MAX_CONNECTIONS=5
@@ -85,6 +93,38 @@ STORAGE_MAX_SIZE_GB=50
 
 # News API Configuration
 NEWSDATA_DAILY_LIMIT=180
+
+# Email / Newsletter (Optional)
+# SMTP_HOST=smtp.example.com
+# SMTP_PORT=587
+# SMTP_TLS=starttls
+# SMTP_USERNAME=
+# SMTP_PASSWORD=
+# SMTP_FROM=Cockpit <newsletter@example.com>
+# NEWSLETTER_RECIPIENTS=reader@example.com
+
+# Semantic Search Embeddings (Optional: openai, ollama, none)
+# EMBEDDINGS_PROVIDER=ollama
+# EMBEDDINGS_MODEL=nomic-embed-text
+# OLLAMA_BASE_URL=http://localhost:11434
+# OPENAI_API_KEY=
+
+# Local LLM Summaries (Optional, via Ollama)
+# OLLAMA_MODEL=llama3.2
+
+# HTTP Bridge
+# COCKPIT_HTTP_PORT=1420
+# COCKPIT_HTTP_COMPRESSION_MIN_BYTES=1024
+# COCKPIT_HTTP_TOKEN=
+# COCKPIT_HTTP_SCOPED_TOKENS=dashboard-token=read;sync-token=research:*
+
+# Outgoing HTTP (HTTP_READER_* for page fetches, HTTP_API_* for feeds and APIs)
+# HTTP_READER_TIMEOUT_SECS=30
+# HTTP_READER_PROXY=socks5://127.0.0.1:9050
+# HTTP_READER_USER_AGENT=
+# HTTP_API_CONNECT_TIMEOUT_SECS=10
+# HTTP_API_RETRIES=3
+# HTTP_API_RETRY_BACKOFF_MS=1000
 "#,
         cockpit_home.to_string_lossy(),
         cockpit_home.to_string_lossy(),
@@ -246,6 +286,7 @@ pub fn get_storage_root() -> Result<PathBuf, AppError> {
 pub async fn initialize_default_settings(db: &sea_orm::DatabaseConnection) -> Result<(), AppError> {
     use sea_orm::{EntityTrait, ActiveModelTrait, Set};
     use crate::core::components::settings::entities::{Entity as AppSettings, ActiveModel};
+    use crate::core::components::settings::registry;
     use tracing::info;
     
     info!("Initializing default application settings...");
@@ -262,36 +303,16 @@ pub async fn initialize_default_settings(db: &sea_orm::DatabaseConnection) -> Re
         return Ok(());
     }
     
-    // Create default settings (key, value, value_type, category, description)
-    let default_settings = vec![
-        // App settings
-        ("app.theme", "dark", "string", "app", "Application theme"),
-        ("app.language", "en", "string", "app", "Application language"),
-        ("app.auto_save", "true", "boolean", "app", "Auto-save editor content"),
-        
-        // News settings
-        ("news.fetch_interval", "3600", "number", "news", "News fetch interval in seconds (1 hour)"),
-        ("news.max_articles", "100", "number", "news", "Maximum articles to keep"),
-        ("news.auto_dismiss_days", "7", "number", "news", "Auto-dismiss articles older than N days"),
-        
-        // Logging settings
-        ("logging.level", "info", "string", "logging", "Logging level (debug, info, warn, error)"),
-        ("logging.max_size_mb", "10", "number", "logging", "Maximum log file size in MB"),
-        ("logging.max_files", "5", "number", "logging", "Maximum number of log files to keep"),
-        
-        // Storage settings
-        ("storage.max_size_gb", "50", "number", "storage", "Maximum storage size in GB"),
-        ("storage.backup_retention_days", "30", "number", "storage", "Backup retention period in days"),
-    ];
-    
-    for (key, value, value_type, category, description) in default_settings {
+    // Create default settings from the registry
+    for spec in registry::all_settings() {
+        let (key, value) = (spec.key, spec.default);
         let setting = ActiveModel {
             key: Set(key.to_string()),
             value: Set(value.to_string()),
-            value_type: Set(value_type.to_string()),
-            category: Set(category.to_string()),
-            description: Set(Some(description.to_string())),
-            is_encrypted: Set(0),
+            value_type: Set(spec.kind.value_type().to_string()),
+            category: Set(spec.category.to_string()),
+            description: Set(Some(spec.description.to_string())),
+            is_encrypted: Set(spec.is_secret() as i32),
             ..Default::default()
         };
         
@@ -1,11 +1,12 @@
 use std::fs;
 use std::path::PathBuf;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 use super::errors::{AppError};
 use super::setup::get_cockpit_home;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SetupStatus {
     pub is_complete: bool,
     pub has_master_key: bool,
@@ -13,7 +14,7 @@ pub struct SetupStatus {
     pub cockpit_home: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SetupConfig {
     pub master_key: String,
     pub newsdata_api_key: Option<String>,
@@ -100,7 +101,7 @@ pub fn save_setup_config(config: SetupConfig) -> Result<(), AppError> {
     env_content.push_str("# Logging Configuration\n");
     let log_level = config.log_level.unwrap_or_else(|| "info".to_string());
     env_content.push_str(&format!("LOG_LEVEL={}\n", log_level));
-    env_content.push_str("LOG_JSON=true\n");
+    env_content.push_str("LOG_FORMAT=json\n");
     env_content.push_str("LOG_CONSOLE=true\n");
     env_content.push_str("LOG_MAX_SIZE_MB=10\n");
     env_content.push_str("LOG_MAX_FILES=5\n\n");
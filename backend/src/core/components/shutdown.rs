@@ -0,0 +1,122 @@
+//! Graceful shutdown coordination
+//!
+//! On SIGINT/SIGTERM the bridge stops accepting requests and the scheduler
+//! stops starting runs (`draining`). Runs already in flight get a grace period
+//! to finish; whatever is still going after that is told to abort, which
+//! records it in the task history as `aborted` instead of leaving it half done.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// How long in-flight task runs may take to finish before they are aborted
+pub const DRAIN_GRACE: Duration = Duration::from_secs(20);
+
+/// How long aborted runs get to write their history rows
+const ABORT_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Default)]
+pub struct Shutdown {
+    draining: CancellationToken,
+    abort: CancellationToken,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting new work
+    pub fn begin(&self) {
+        self.draining.cancel();
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.is_cancelled()
+    }
+
+    /// Resolves once shutdown has begun
+    pub async fn draining(&self) {
+        self.draining.cancelled().await
+    }
+
+    /// Resolves once in-flight work should give up
+    pub async fn aborted(&self) {
+        self.abort.cancelled().await
+    }
+
+    /// Wait for the running set to empty, aborting whatever outlives `grace`
+    pub async fn drain(&self, running: &Arc<Mutex<HashSet<i64>>>, grace: Duration) {
+        self.begin();
+        if wait_until_idle(running, grace).await {
+            return;
+        }
+        let remaining = running.lock().await.len();
+        info!(target: "scheduler", "Aborting {} task run(s) still in flight", remaining);
+        self.abort.cancel();
+        wait_until_idle(running, ABORT_GRACE).await;
+    }
+}
+
+async fn wait_until_idle(running: &Arc<Mutex<HashSet<i64>>>, timeout: Duration) -> bool {
+    let idle = async {
+        while !running.lock().await.is_empty() {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    tokio::time::timeout(timeout, idle).await.is_ok()
+}
+
+/// Resolves on Ctrl+C, or SIGTERM on Unix
+pub async fn signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_aborts_stragglers() {
+        let shutdown = Shutdown::new();
+        let running = Arc::new(Mutex::new(HashSet::from([1])));
+
+        // A run that only stops when told to abort
+        let worker = {
+            let shutdown = shutdown.clone();
+            let running = running.clone();
+            tokio::spawn(async move {
+                shutdown.aborted().await;
+                running.lock().await.remove(&1);
+            })
+        };
+
+        shutdown.drain(&running, Duration::from_millis(50)).await;
+        assert!(shutdown.is_draining());
+        assert!(running.lock().await.is_empty());
+        worker.await.unwrap();
+    }
+}
@@ -1,33 +1,174 @@
 //! Database backup and restore operations
 //!
 //! Provides backup/restore functionality using SQLite's VACUUM INTO
-//! for consistent, point-in-time backups.
+//! for consistent, point-in-time backups. Incremental backups build on
+//! the newest full backup; see [`super::incremental`]. A PostgreSQL
+//! database is backed up with `pg_dump` instead, and only in full; see
+//! [`super::pg_dump`]. Backups are
+//! compressed and encrypted as configured; see [`super::compression`] and
+//! [`super::encryption`]. A backup taken before startup migrations has a
+//! `<backup>.migrations.json` next to it naming the migrations it predates.
 
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 use chrono::Utc;
 use tracing::{info, warn, error, instrument};
 use sea_orm::ConnectionTrait;
 
-use crate::core::components::config::StorageConfig;
+use crate::core::components::config::{
+    BackupCompression, BackupEncryption, DatabaseConfig, StorageConfig,
+};
 use crate::core::components::errors::AppError;
+use super::cleanup::rotate_backups;
+use super::compression::{compress_backup, open_backup, plain_name};
+use super::encryption::{encrypt_file, is_encrypted};
+use super::pg_dump;
+use crate::core::components::db::encryption::{file_options, file_state, FileState};
+use super::incremental::{
+    self, ChainIncrement, ChainManifest, INCREMENT_EXTENSION, MAX_CHAIN_LENGTH,
+};
 
 /// Backup result information
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BackupInfo {
     pub file_path: String,
     pub file_size: u64,
     pub timestamp: String,
+    /// "full" or "incremental"
+    pub kind: String,
+    /// For an incremental backup, the full backup its chain starts from
+    pub base: Option<String>,
+    /// "none", "zstd" or "gzip"
+    pub compression: String,
+    pub encrypted: bool,
+    /// For a backup taken before startup migrations, which ones
+    pub migrations: Option<MigrationSet>,
+}
+
+/// Schema of a backup taken just before migrating
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationSet {
+    /// Newest migration applied in the backup
+    pub applied: Option<String>,
+    /// Migrations applied right after it was taken
+    pub pending: Vec<String>,
+}
+
+/// Suffix of the file recording a backup's [`MigrationSet`]
+pub(crate) const MIGRATIONS_SUFFIX: &str = ".migrations.json";
+
+fn migrations_path(backup_path: &Path) -> PathBuf {
+    let mut name = backup_path.as_os_str().to_owned();
+    name.push(MIGRATIONS_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Record next to `backup` the migrations it was taken before
+pub fn record_migration_set(backup: &mut BackupInfo, set: MigrationSet) -> Result<(), AppError> {
+    let path = migrations_path(Path::new(&backup.file_path));
+    let json = serde_json::to_string_pretty(&set)
+        .map_err(|e| AppError::other(format!("Failed to serialize migration set: {}", e)))?;
+    fs::write(&path, json)
+        .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+    backup.migrations = Some(set);
+    Ok(())
+}
+
+/// Options for `create_database_backup`
+#[derive(Debug, Default, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBackupInput {
+    /// Store only the pages changed since the last backup (default: false)
+    pub incremental: Option<bool>,
+}
+
+/// Write a consistent copy of the database to `path`
+async fn vacuum_into(db: &sea_orm::DatabaseConnection, path: &Path) -> Result<(), AppError> {
+    let path_str = path.to_str()
+        .ok_or_else(|| AppError::storage_operation("backup", "Invalid backup path"))?;
+
+    let sql = format!("VACUUM INTO '{}'", path_str);
+
+    db.execute(sea_orm::Statement::from_string(
+        sea_orm::DatabaseBackend::Sqlite,
+        sql,
+    ))
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Database backup failed");
+        AppError::database(format!("Failed to create database backup: {}", e))
+    })?;
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Extension of PostgreSQL backups
+pub(crate) const DUMP_EXTENSION: &str = "dump";
+
+/// Whether `file_name` is a PostgreSQL backup, compressed or not
+pub(crate) fn is_dump(file_name: &str) -> bool {
+    Path::new(plain_name(file_name))
+        .extension()
+        .is_some_and(|e| e == DUMP_EXTENSION)
+}
+
+/// Why the backup `file_name` can't be restored into `database`, if it can't
+pub(crate) fn backend_mismatch(file_name: &str, database: &DatabaseConfig) -> Option<&'static str> {
+    match (is_dump(file_name), database.is_postgres()) {
+        (true, false) => Some("PostgreSQL backups can only be restored into PostgreSQL"),
+        (false, true) => Some("SQLite backups can't be restored into PostgreSQL"),
+        _ => None,
+    }
+}
+
+/// "full" or "incremental" for backup file names, compressed or not
+fn backup_kind(file_name: &str) -> Option<&'static str> {
+    match Path::new(plain_name(file_name))
+        .extension()
+        .and_then(|s| s.to_str())
+    {
+        Some("db") | Some(DUMP_EXTENSION) => Some("full"),
+        Some(INCREMENT_EXTENSION) => Some("incremental"),
+        _ => None,
+    }
+}
+
+/// Compress, then encrypt a finished backup as configured; returns where
+/// it now is
+fn seal(path: &Path, config: &StorageConfig) -> Result<PathBuf, AppError> {
+    compress_backup(path, config)
+        .map_err(|e| AppError::file_operation("compress", path.to_string_lossy(), e))
+        .and_then(|path| {
+            encrypt_file(&path, config.backup_encryption)
+                .map_err(|e| AppError::file_operation("encrypt", path.to_string_lossy(), e))
+        })
+}
+
+/// [`seal`] off the async runtime
+async fn seal_backup(path: PathBuf, storage_config: &StorageConfig) -> Result<PathBuf, AppError> {
+    let config = storage_config.clone();
+    tokio::task::spawn_blocking(move || seal(&path, &config))
+        .await
+        .map_err(|e| AppError::storage_operation("backup", e.to_string()))?
 }
 
 /// Create a database backup using SQLite VACUUM INTO
 /// 
 /// This ensures a consistent backup by using SQLite's built-in backup mechanism.
 /// The backup is stored in storage/backups/ with a timestamp.
-#[instrument(skip(db, storage_config))]
+/// A PostgreSQL database is dumped with `pg_dump` instead.
+#[instrument(skip(db, database, storage_config))]
 pub async fn backup_database(
     db: &sea_orm::DatabaseConnection,
+    database: &DatabaseConfig,
     storage_config: &StorageConfig,
 ) -> Result<BackupInfo, AppError> {
     info!("Starting database backup");
@@ -39,24 +180,21 @@ pub async fn backup_database(
     
     // Generate backup filename with timestamp
     let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
-    let backup_filename = format!("backup_{}.db", timestamp);
+    let extension = if database.is_postgres() { DUMP_EXTENSION } else { "db" };
+    let backup_filename = format!("backup_{}.{}", timestamp, extension);
     let backup_path = backup_dir.join(&backup_filename);
     
-    // Use VACUUM INTO for consistent backup
-    let backup_path_str = backup_path.to_str()
-        .ok_or_else(|| AppError::storage_operation("backup", "Invalid backup path"))?;
-    
-    let sql = format!("VACUUM INTO '{}'", backup_path_str);
-    
-    db.execute(sea_orm::Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
-        sql,
-    ))
-    .await
-    .map_err(|e| {
-        error!(error = %e, "Database backup failed");
-        AppError::database(format!("Failed to create database backup: {}", e))
-    })?;
+    if database.is_postgres() {
+        pg_dump::dump_archive(&database.url, &backup_path).await.map_err(|e| {
+            error!(error = %e, "Database backup failed");
+            let _ = fs::remove_file(&backup_path);
+            AppError::database(format!("Failed to create database backup: {}", e))
+        })?;
+    } else {
+        // Use VACUUM INTO for consistent backup
+        vacuum_into(db, &backup_path).await?;
+    }
+    let backup_path = seal_backup(backup_path, storage_config).await?;
     
     // Get backup file size
     let metadata = fs::metadata(&backup_path)
@@ -66,6 +204,11 @@ pub async fn backup_database(
         file_path: backup_path.to_string_lossy().to_string(),
         file_size: metadata.len(),
         timestamp: Utc::now().to_rfc3339(),
+        kind: "full".to_string(),
+        base: None,
+        compression: storage_config.backup_compression.name().to_string(),
+        encrypted: storage_config.backup_encryption != BackupEncryption::None,
+        migrations: None,
     };
     
     info!(
@@ -73,30 +216,210 @@ pub async fn backup_database(
         size_bytes = backup_info.file_size,
         "Database backup completed successfully"
     );
+    rotate_backups(storage_config);
     
     Ok(backup_info)
 }
 
+/// Create an incremental backup on top of the newest full backup
+///
+/// Only the pages changed since the last backup of its chain are stored.
+/// Without a full backup to build on, once the chain has
+/// [`MAX_CHAIN_LENGTH`] increments, or when the page size has changed, the
+/// new snapshot is kept as a full backup instead. PostgreSQL and
+/// SQLCipher-encrypted databases are always backed up in full.
+#[instrument(skip(db, database, storage_config))]
+pub async fn backup_database_incremental(
+    db: &sea_orm::DatabaseConnection,
+    database: &DatabaseConfig,
+    storage_config: &StorageConfig,
+) -> Result<BackupInfo, AppError> {
+    if database.is_postgres() {
+        info!("Incremental backups are SQLite-only, creating a full backup");
+        return backup_database(db, database, storage_config).await;
+    }
+    // Pages of an encrypted file can't be diffed against its header
+    if let Some(path) = database.sqlite_path() {
+        if file_state(&path)? == FileState::Encrypted {
+            info!("Incremental backups need a plaintext database, creating a full backup");
+            return backup_database(db, database, storage_config).await;
+        }
+    }
+    let Some(base) = list_backups(storage_config)?
+        .into_iter()
+        .find(|b| b.kind == "full" && !is_dump(&file_name(Path::new(&b.file_path))))
+    else {
+        info!("No full backup to build on, creating a full backup");
+        return backup_database(db, database, storage_config).await;
+    };
+    let backup_dir = storage_config.backup_dir.clone();
+    let base_name = file_name(Path::new(&base.file_path));
+    let chain = ChainManifest::load(&backup_dir, &base_name)?;
+    if chain
+        .as_ref()
+        .is_some_and(|c| c.increments.len() >= MAX_CHAIN_LENGTH)
+    {
+        info!(base = %base_name, "Backup chain is full, creating a full backup");
+        return backup_database(db, database, storage_config).await;
+    }
+
+    info!(base = %base_name, "Starting incremental database backup");
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+    let snapshot = backup_dir.join(format!(".snapshot_{}.tmp", timestamp));
+    vacuum_into(db, &snapshot).await?;
+
+    let increment_name = format!("backup_{}.{}", timestamp, INCREMENT_EXTENSION);
+    let full_name = format!("backup_{}.db", timestamp);
+    let dir = backup_dir.clone();
+    let config = storage_config.clone();
+    let kept_as = tokio::task::spawn_blocking(move || -> Result<String, AppError> {
+        let page_size = incremental::page_size(&snapshot)
+            .map_err(|e| AppError::file_operation("read", snapshot.to_string_lossy(), e))?;
+        let mut chain = chain.unwrap_or_else(|| ChainManifest::new(base_name.clone(), page_size));
+        if chain.page_size != page_size {
+            warn!("Page size changed since the last full backup, keeping a full backup");
+            fs::rename(&snapshot, dir.join(&full_name))
+                .map_err(|e| AppError::file_operation("rename", snapshot.to_string_lossy(), e))?;
+            return seal(&dir.join(&full_name), &config).map(|stored| file_name(&stored));
+        }
+
+        // The database as of the last backup of the chain
+        let previous = dir.join(format!(".previous_{}.tmp", timestamp));
+        let last = chain
+            .increments
+            .last()
+            .map_or(chain.base.clone(), |i| i.file.clone());
+        let diff = incremental::materialize(&dir, &chain, &last, &previous).and_then(|_| {
+            let out = dir.join(&increment_name);
+            incremental::write_increment(&previous, &snapshot, &out)
+                .map_err(|e| AppError::file_operation("write increment", out.to_string_lossy(), e))
+        });
+        let _ = fs::remove_file(&previous);
+        let _ = fs::remove_file(&snapshot);
+        let (page_count, changed_pages) = diff?;
+        let increment_name = file_name(&seal(&dir.join(&increment_name), &config)?);
+
+        chain.increments.push(ChainIncrement {
+            file: increment_name.clone(),
+            created_at: Utc::now().to_rfc3339(),
+            page_count,
+            changed_pages,
+        });
+        chain.save(&dir)?;
+        info!(
+            changed_pages,
+            page_count, "Incremental database backup completed"
+        );
+        Ok(increment_name)
+    })
+    .await
+    .map_err(|e| AppError::storage_operation("backup", e.to_string()))??;
+    rotate_backups(storage_config);
+
+    list_backups(storage_config)?
+        .into_iter()
+        .find(|b| file_name(Path::new(&b.file_path)) == kept_as)
+        .ok_or_else(|| AppError::storage_operation("backup", "Backup file missing after write"))
+}
+
+/// The plain database file behind `requested`: the file itself, or
+/// `scratch` once an increment's chain is replayed or a compressed or
+/// encrypted backup unpacked into it
+pub(crate) async fn unpack_backup<'a>(
+    requested: &'a Path,
+    scratch: &'a Path,
+    storage_config: &StorageConfig,
+) -> Result<&'a Path, AppError> {
+    let name = file_name(requested);
+    if backup_kind(&name) == Some("incremental") {
+        let chain =
+            incremental::find_chain(&storage_config.backup_dir, &name)?.ok_or_else(|| {
+                AppError::validation("backup_path", "No backup chain lists this increment")
+            })?;
+        info!(base = %chain.base, "Replaying backup chain");
+        let (dir, dest) = (storage_config.backup_dir.clone(), scratch.to_path_buf());
+        tokio::task::spawn_blocking(move || incremental::materialize(&dir, &chain, &name, &dest))
+            .await
+            .map_err(|e| AppError::storage_operation("unpack backup", e.to_string()))??;
+        Ok(scratch)
+    } else if plain_name(&name) != name {
+        let (source, dest) = (requested.to_path_buf(), scratch.to_path_buf());
+        tokio::task::spawn_blocking(move || {
+            open_backup(&source)
+                .and_then(|mut reader| io::copy(&mut reader, &mut fs::File::create(&dest)?))
+        })
+        .await
+        .map_err(|e| AppError::storage_operation("unpack backup", e.to_string()))?
+        .map_err(|e| AppError::file_operation("unpack", requested.to_string_lossy(), e))?;
+        Ok(scratch)
+    } else {
+        Ok(requested)
+    }
+}
+
 /// Restore database from a backup file
 /// 
 /// IMPORTANT: This requires closing all database connections and restarting the application.
-/// The backup file is validated before restore.
-#[instrument(skip(_db))]
+/// The backup file is validated before restore. A PostgreSQL backup is
+/// loaded with `pg_restore` in one transaction, replacing the live tables.
+#[instrument(skip(_db, database))]
 pub async fn restore_database(
     _db: &sea_orm::DatabaseConnection,
+    database: &DatabaseConfig,
     backup_path: &str,
     storage_config: &StorageConfig,
 ) -> Result<(), AppError> {
     info!(backup_path = %backup_path, "Starting database restore");
     
-    let backup_file = Path::new(backup_path);
+    let requested = Path::new(backup_path);
     
     // Validate backup file exists
-    if !backup_file.exists() {
+    if !requested.exists() {
         error!(backup_path = %backup_path, "Backup file not found");
         return Err(AppError::validation("backup_path", "Backup file not found"));
     }
+    let name = file_name(requested);
+    if let Some(reason) = backend_mismatch(&name, database) {
+        return Err(AppError::validation("backup_path", reason));
+    }
     
+    let prepared = storage_config.data_dir.join("db.sql.restore_source");
+    let result = match unpack_backup(requested, &prepared, storage_config).await {
+        Ok(backup_file) if is_dump(&name) => restore_dump(database, backup_file, backup_path).await,
+        Ok(backup_file) => restore_file(backup_file, backup_path, storage_config).await,
+        Err(e) => Err(e),
+    };
+    if prepared.exists() {
+        let _ = fs::remove_file(&prepared);
+    }
+    result
+}
+
+/// Validate the archive `backup_file` and load it into the database
+async fn restore_dump(
+    database: &DatabaseConfig,
+    backup_file: &Path,
+    backup_path: &str,
+) -> Result<(), AppError> {
+    pg_dump::list_archive(backup_file)
+        .await
+        .map_err(|_| AppError::validation("backup_file", "Invalid PostgreSQL backup file"))?;
+    info!(backup_path = %backup_path, "Backup file validated successfully");
+    
+    pg_dump::restore_archive(&database.url, backup_file).await.map_err(|e| {
+        error!(error = %e, "Failed to restore database");
+        AppError::database(format!("Failed to restore database: {}", e))
+    })?;
+    info!("Database restored successfully");
+    Ok(())
+}
+
+/// Validate `backup_file` and copy it over the database
+async fn restore_file(
+    backup_file: &Path,
+    backup_path: &str,
+    storage_config: &StorageConfig,
+) -> Result<(), AppError> {
     // Validate backup file is readable
     let backup_metadata = fs::metadata(backup_file)
         .map_err(|e| AppError::file_operation("read metadata", backup_path, e))?;
@@ -107,8 +430,7 @@ pub async fn restore_database(
     }
     
     // Validate backup is a valid SQLite database by trying to open it
-    let backup_url = format!("sqlite://{}?mode=ro", backup_file.to_string_lossy());
-    let test_db = sea_orm::Database::connect(&backup_url).await
+    let test_db = sea_orm::Database::connect(file_options(backup_file, true)?).await
         .map_err(|_| {
             AppError::validation("backup_file", "Invalid SQLite database file")
         })?;
@@ -172,6 +494,7 @@ pub fn list_backups(storage_config: &StorageConfig) -> Result<Vec<BackupInfo>, A
     }
     
     let mut backups = Vec::new();
+    let chains = incremental::load_chains(backup_dir)?;
     
     let entries = fs::read_dir(backup_dir)
         .map_err(|e| AppError::file_operation("read directory", backup_dir.to_string_lossy(), e))?;
@@ -180,21 +503,35 @@ pub fn list_backups(storage_config: &StorageConfig) -> Result<Vec<BackupInfo>, A
         let entry = entry.map_err(|e| AppError::file_operation("read directory entry", backup_dir.to_string_lossy(), e))?;
         let path = entry.path();
         
-        if path.extension().and_then(|s| s.to_str()) == Some("db") {
-            let metadata = fs::metadata(&path)
-                .map_err(|e| AppError::file_operation("read metadata", path.to_string_lossy(), e))?;
-            
-            let modified = metadata.modified()
-                .map_err(|e| AppError::file_operation("get modified time", path.to_string_lossy(), e))?;
-            
-            let timestamp = chrono::DateTime::<Utc>::from(modified).to_rfc3339();
-            
-            backups.push(BackupInfo {
-                file_path: path.to_string_lossy().to_string(),
-                file_size: metadata.len(),
-                timestamp,
-            });
-        }
+        let name = file_name(&path);
+        let Some(kind) = backup_kind(&name) else {
+            continue;
+        };
+        let metadata = fs::metadata(&path)
+            .map_err(|e| AppError::file_operation("read metadata", path.to_string_lossy(), e))?;
+        
+        let modified = metadata.modified()
+            .map_err(|e| AppError::file_operation("get modified time", path.to_string_lossy(), e))?;
+        
+        let timestamp = chrono::DateTime::<Utc>::from(modified).to_rfc3339();
+        
+        let base = chains
+            .iter()
+            .find(|c| kind == "incremental" && c.contains(&name))
+            .map(|c| backup_dir.join(&c.base).to_string_lossy().to_string());
+        
+        backups.push(BackupInfo {
+            file_path: path.to_string_lossy().to_string(),
+            file_size: metadata.len(),
+            timestamp,
+            kind: kind.to_string(),
+            base,
+            compression: BackupCompression::of(&name).name().to_string(),
+            encrypted: is_encrypted(&name),
+            migrations: fs::read(migrations_path(&path))
+                .ok()
+                .and_then(|json| serde_json::from_slice(&json).ok()),
+        });
     }
     
     // Sort by timestamp descending (newest first)
@@ -223,9 +560,33 @@ pub async fn delete_backup(config: &StorageConfig, backup_path: &str) -> Result<
         ));
     }
     
+    // Keep chains replayable: only the newest backup of a chain can go
+    let name = file_name(&absolute_backup);
+    if let Some(mut chain) = incremental::find_chain(&absolute_backup_dir, &name)? {
+        let newest = chain
+            .increments
+            .last()
+            .map_or(chain.base.as_str(), |i| i.file.as_str());
+        if newest != name {
+            return Err(AppError::validation(
+                "backup_path",
+                "Newer incremental backups depend on this backup; delete them first",
+            ));
+        }
+        if chain.increments.pop().is_some() {
+            chain.save(&absolute_backup_dir)?;
+        } else {
+            let manifest = ChainManifest::path(&absolute_backup_dir, &chain.base);
+            fs::remove_file(&manifest).map_err(|e| {
+                AppError::file_operation("delete backup chain", manifest.to_string_lossy(), e)
+            })?;
+        }
+    }
+    
     // Delete the backup file
     fs::remove_file(&backup_file)
         .map_err(|e| AppError::file_operation("delete backup", backup_path, e))?;
+    let _ = fs::remove_file(migrations_path(&backup_file));
     
     info!(backup_path = backup_path, "Backup file deleted successfully");
     Ok(())
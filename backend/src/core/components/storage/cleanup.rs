@@ -1,18 +1,26 @@
 //! Storage cleanup operations
 //!
 //! Handles cleanup of old logs and dismissed news articles
-//! based on configurable retention policies.
+//! based on configurable retention policies. The `storage_cleanup`
+//! system task applies the full [`CleanupPolicy`] to the storage
+//! directories on a schedule.
 
+use std::collections::HashMap;
 use std::fs;
-use chrono::{Duration, Utc};
+use std::path::Path;
+use chrono::{DateTime, Datelike, Duration, Utc};
 use tracing::{info, warn, instrument};
 use sea_orm::{ConnectionTrait, Statement};
 
 use crate::core::components::config::StorageConfig;
 use crate::core::components::errors::AppError;
+use super::backup::MIGRATIONS_SUFFIX;
+use super::incremental::{self, ChainManifest};
+use crate::system::components::scheduler::TaskRunResult;
+use crate::AppState;
 
 /// Summary of cleanup operation
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CleanupSummary {
     pub files_deleted: usize,
@@ -103,7 +111,7 @@ pub async fn cleanup_old_news(
     );
     
     let count_result = db.query_one(Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
+        db.get_database_backend(),
         count_sql
     ))
     .await
@@ -131,7 +139,7 @@ pub async fn cleanup_old_news(
     );
     
     db.execute(Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
+        db.get_database_backend(),
         delete_sql
     ))
     .await
@@ -139,7 +147,7 @@ pub async fn cleanup_old_news(
     
     // Run VACUUM to reclaim space
     db.execute(Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
+        db.get_database_backend(),
         "VACUUM".to_string()
     ))
     .await
@@ -156,3 +164,405 @@ pub async fn cleanup_old_news(
         retention_days: retention,
     })
 }
+
+/// Retention rules for the storage directories
+#[derive(Debug, Clone)]
+pub struct CleanupPolicy {
+    /// Remove log files older than this many days
+    pub log_retention_days: i64,
+    /// Remove cache files older than this many days
+    pub cache_retention_days: i64,
+    /// Remove backups older than this many days (the newest is kept, and
+    /// incremental chains go as a whole)
+    pub backup_retention_days: i64,
+    /// Keep the newest this many backups. With any of the `backup_keep_*`
+    /// rules set, they decide which backups stay instead of their age
+    pub backup_keep_last: usize,
+    /// Also keep the newest backup of each of the last this many weeks
+    pub backup_keep_weekly: usize,
+    /// Also keep the newest backup of each of the last this many months
+    pub backup_keep_monthly: usize,
+    /// Remove export files older than this many days
+    pub export_retention_days: i64,
+    /// Maximum number of rotated log files to keep (per log type)
+    pub max_rotated_logs: usize,
+}
+
+impl Default for CleanupPolicy {
+    fn default() -> Self {
+        Self {
+            log_retention_days: 30,
+            cache_retention_days: 7,
+            backup_retention_days: 90,
+            backup_keep_last: 0,
+            backup_keep_weekly: 0,
+            backup_keep_monthly: 0,
+            export_retention_days: 30,
+            max_rotated_logs: 5,
+        }
+    }
+}
+
+impl CleanupPolicy {
+    /// Load cleanup policy from environment variables with defaults
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|s| s.parse().ok());
+        Self {
+            log_retention_days: var("STORAGE_LOG_RETENTION_DAYS")
+                .unwrap_or(defaults.log_retention_days),
+            cache_retention_days: var("STORAGE_CACHE_RETENTION_DAYS")
+                .unwrap_or(defaults.cache_retention_days),
+            backup_retention_days: var("STORAGE_BACKUP_RETENTION_DAYS")
+                .unwrap_or(defaults.backup_retention_days),
+            backup_keep_last: var("STORAGE_BACKUP_KEEP_LAST")
+                .map_or(defaults.backup_keep_last, |n: i64| n.max(0) as usize),
+            backup_keep_weekly: var("STORAGE_BACKUP_KEEP_WEEKLY")
+                .map_or(defaults.backup_keep_weekly, |n: i64| n.max(0) as usize),
+            backup_keep_monthly: var("STORAGE_BACKUP_KEEP_MONTHLY")
+                .map_or(defaults.backup_keep_monthly, |n: i64| n.max(0) as usize),
+            export_retention_days: var("STORAGE_EXPORT_RETENTION_DAYS")
+                .unwrap_or(defaults.export_retention_days),
+            max_rotated_logs: std::env::var("LOG_MAX_FILES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.max_rotated_logs),
+        }
+    }
+
+    /// Whether backups are rotated by count rather than age
+    pub fn rotates_backups(&self) -> bool {
+        self.backup_keep_last + self.backup_keep_weekly + self.backup_keep_monthly > 0
+    }
+}
+
+/// Files removed and bytes freed by a full storage cleanup
+#[derive(Debug, Clone, Default, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageCleanupSummary {
+    pub rotated_logs_deleted: usize,
+    pub logs_deleted: usize,
+    pub cache_deleted: usize,
+    pub backups_deleted: usize,
+    pub exports_deleted: usize,
+    pub space_freed_bytes: u64,
+}
+
+/// Remove the files in `dir` older than `max_age_days`, sparing the newest
+/// `keep_newest`; returns the count removed and the bytes freed
+fn cleanup_old_files(dir: &Path, max_age_days: i64, keep_newest: usize) -> (usize, u64) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .filter_map(|e| {
+            let metadata = e.metadata().ok().filter(|m| m.is_file())?;
+            let modified = chrono::DateTime::<Utc>::from(metadata.modified().ok()?);
+            Some((e.path(), modified, metadata.len()))
+        })
+        .collect();
+    // Newest first, so the spared files come first
+    files.sort_by_key(|f| std::cmp::Reverse(f.1));
+
+    let cutoff = Utc::now() - Duration::days(max_age_days);
+    let mut removed = (0, 0);
+    for (path, _, size) in files.into_iter().skip(keep_newest).filter(|f| f.1 < cutoff) {
+        match fs::remove_file(&path) {
+            Ok(_) => {
+                info!("Removed old file: {}", path.display());
+                removed.0 += 1;
+                removed.1 += size;
+            }
+            Err(e) => warn!(error = %e, path = %path.display(), "Failed to remove file"),
+        }
+    }
+    removed
+}
+
+/// Which of the backups taken at `times` (newest first) the keep-last,
+/// weekly and monthly rules of `policy` keep
+fn rotation_keeps(times: &[DateTime<Utc>], policy: &CleanupPolicy) -> Vec<bool> {
+    // Keeps the newest backup of each of the latest `count` periods
+    fn newest_per_period<P: PartialEq>(
+        times: &[DateTime<Utc>],
+        keep: &mut [bool],
+        count: usize,
+        period: impl Fn(&DateTime<Utc>) -> P,
+    ) {
+        let mut periods: Vec<P> = Vec::new();
+        for (i, time) in times.iter().enumerate() {
+            let p = period(time);
+            if periods.last() != Some(&p) {
+                if periods.len() == count {
+                    break;
+                }
+                periods.push(p);
+                keep[i] = true;
+            }
+        }
+    }
+
+    let mut keep = vec![false; times.len()];
+    keep.iter_mut()
+        .take(policy.backup_keep_last)
+        .for_each(|k| *k = true);
+    newest_per_period(times, &mut keep, policy.backup_keep_weekly, |t| {
+        (t.iso_week().year(), t.iso_week().week())
+    });
+    newest_per_period(times, &mut keep, policy.backup_keep_monthly, |t| {
+        (t.year(), t.month())
+    });
+    keep
+}
+
+/// Remove the backups `policy` doesn't keep: with [`CleanupPolicy::rotates_backups`]
+/// those outside its keep rules, otherwise those older than its retention
+/// period, always sparing the newest. A chain of incremental backups counts
+/// as one backup as old as its newest file, since its increments can't be
+/// restored without the full backup they build on.
+fn cleanup_old_backups(dir: &Path, policy: &CleanupPolicy) -> (usize, u64) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    let chains = incremental::load_chains(dir).unwrap_or_default();
+    let mut units: HashMap<String, Vec<_>> = HashMap::new();
+    for e in entries.flatten() {
+        let Some(metadata) = e.metadata().ok().filter(|m| m.is_file()) else {
+            continue;
+        };
+        let Ok(modified) = metadata.modified() else {
+            continue;
+        };
+        let mut name = e.file_name().to_string_lossy().into_owned();
+        // Goes with the backup it describes
+        if let Some(backup) = name.strip_suffix(MIGRATIONS_SUFFIX) {
+            name = backup.to_string();
+        }
+        let chain = chains
+            .iter()
+            .find(|c| c.contains(&name) || ChainManifest::path(dir, &c.base) == e.path());
+        units
+            .entry(chain.map_or(name, |c| c.base.clone()))
+            .or_default()
+            .push((
+                e.path(),
+                chrono::DateTime::<Utc>::from(modified),
+                metadata.len(),
+            ));
+    }
+    let mut units: Vec<_> = units
+        .into_values()
+        .filter_map(|files| Some((files.iter().map(|f| f.1).max()?, files)))
+        .collect();
+    // Newest first, so the spared backup comes first
+    units.sort_by_key(|u| std::cmp::Reverse(u.0));
+
+    let keep = if policy.rotates_backups() {
+        let times: Vec<_> = units.iter().map(|u| u.0).collect();
+        rotation_keeps(&times, policy)
+    } else {
+        let cutoff = Utc::now() - Duration::days(policy.backup_retention_days);
+        units
+            .iter()
+            .enumerate()
+            .map(|(i, u)| i == 0 || u.0 >= cutoff)
+            .collect()
+    };
+    let mut removed = (0, 0);
+    for ((_, files), _) in units.into_iter().zip(keep).filter(|(_, keep)| !keep) {
+        for (path, _, size) in files {
+            match fs::remove_file(&path) {
+                Ok(_) => {
+                    info!("Removed old backup: {}", path.display());
+                    removed.0 += 1;
+                    removed.1 += size;
+                }
+                Err(e) => warn!(error = %e, path = %path.display(), "Failed to remove backup"),
+            }
+        }
+    }
+    removed
+}
+
+/// Apply the keep-last/weekly/monthly backup rules from the environment,
+/// if any are set; run after each backup
+pub(crate) fn rotate_backups(config: &StorageConfig) {
+    let policy = CleanupPolicy::from_env();
+    if !policy.rotates_backups() {
+        return;
+    }
+    let (deleted, freed) = cleanup_old_backups(&config.backup_dir, &policy);
+    if deleted > 0 {
+        info!(
+            files_deleted = deleted,
+            space_freed_mb = freed / (1024 * 1024),
+            "Rotated backups"
+        );
+    }
+}
+
+/// Keep the newest `max_files` rotated copies of each log (`app.20251211_153144`)
+fn cleanup_rotated_logs(logs_dir: &Path, max_files: usize) -> (usize, u64) {
+    let mut removed = (0, 0);
+    for log_type in ["app", "api_calls", "errors"] {
+        let Ok(entries) = fs::read_dir(logs_dir) else {
+            return removed;
+        };
+        let active = format!("{}.log", log_type);
+        let prefix = format!("{}.", log_type);
+        let mut rotated: Vec<_> = entries
+            .flatten()
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                name.starts_with(&prefix) && name != active
+            })
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                Some((e.path(), metadata.modified().ok()?, metadata.len()))
+            })
+            .collect();
+
+        // Oldest first
+        rotated.sort_by_key(|f| f.1);
+        let excess = rotated.len().saturating_sub(max_files);
+        for (path, _, size) in rotated.into_iter().take(excess) {
+            match fs::remove_file(&path) {
+                Ok(_) => {
+                    info!("Removed old rotated log: {}", path.display());
+                    removed.0 += 1;
+                    removed.1 += size;
+                }
+                Err(e) => warn!(error = %e, path = %path.display(), "Failed to remove rotated log"),
+            }
+        }
+    }
+    removed
+}
+
+/// Apply `policy` to the log, cache, backup and export directories
+#[instrument(skip(config))]
+pub fn cleanup_storage(config: &StorageConfig, policy: &CleanupPolicy) -> StorageCleanupSummary {
+    info!("Starting storage cleanup");
+
+    let rotated = cleanup_rotated_logs(&config.logs_dir, policy.max_rotated_logs);
+    let logs = cleanup_old_files(&config.logs_dir, policy.log_retention_days, 0);
+    let cache = cleanup_old_files(&config.cache_dir, policy.cache_retention_days, 0);
+    let backups = cleanup_old_backups(&config.backup_dir, policy);
+    let exports = cleanup_old_files(&config.export_dir, policy.export_retention_days, 0);
+
+    let summary = StorageCleanupSummary {
+        rotated_logs_deleted: rotated.0,
+        logs_deleted: logs.0,
+        cache_deleted: cache.0,
+        backups_deleted: backups.0,
+        exports_deleted: exports.0,
+        space_freed_bytes: rotated.1 + logs.1 + cache.1 + backups.1 + exports.1,
+    };
+    info!(
+        files_deleted = rotated.0 + logs.0 + cache.0 + backups.0 + exports.0,
+        space_freed_mb = summary.space_freed_bytes / (1024 * 1024),
+        "Storage cleanup completed"
+    );
+    summary
+}
+
+/// Scheduled task: apply the cleanup policy from the environment
+pub async fn run_storage_cleanup_task(state: &AppState) -> TaskRunResult {
+    let config = state.config.current().storage.clone();
+    let cleanup =
+        tokio::task::spawn_blocking(move || cleanup_storage(&config, &CleanupPolicy::from_env()))
+            .await;
+    match cleanup {
+        Ok(summary) => TaskRunResult {
+            status: "success",
+            result_json: serde_json::to_string(&summary).ok(),
+            error_message: None,
+        },
+        Err(e) => TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(format!("Storage cleanup failed: {}", e)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_cleanup_old_files_keeps_newest() {
+        let dir = std::env::temp_dir().join(format!("cockpit-cleanup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["a.bak", "b.bak"] {
+            fs::write(dir.join(name), b"backup").unwrap();
+        }
+
+        // Everything is older than -1 days; one file is spared
+        assert_eq!(cleanup_old_files(&dir, -1, 1), (1, 6));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        assert_eq!(cleanup_old_files(&dir, 30, 0), (0, 0));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migration_set_rotates_with_its_backup() {
+        let dir = std::env::temp_dir().join(format!("cockpit-rotate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let now = std::time::SystemTime::now();
+        let hour = std::time::Duration::from_secs(3600);
+        for (name, age) in [
+            ("backup_20250101_000000.db", 48),
+            ("backup_20250103_000000.db", 1),
+            ("backup_20250103_000000.db.migrations.json", 0),
+        ] {
+            let file = fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(now - hour * age).unwrap();
+        }
+
+        let policy = CleanupPolicy {
+            backup_keep_last: 1,
+            ..CleanupPolicy::default()
+        };
+        assert_eq!(cleanup_old_backups(&dir, &policy).0, 1);
+        let mut left: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "backup_20250103_000000.db",
+                "backup_20250103_000000.db.migrations.json"
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotation_keeps() {
+        let policy = CleanupPolicy {
+            backup_keep_last: 2,
+            backup_keep_weekly: 2,
+            backup_keep_monthly: 3,
+            ..CleanupPolicy::default()
+        };
+        // Daily backups from 2025-03-10 (a Monday) back to 2025-01-01
+        let newest = Utc.with_ymd_and_hms(2025, 3, 10, 2, 0, 0).unwrap();
+        let times: Vec<_> = (0..69).map(|d| newest - Duration::days(d)).collect();
+        let kept: Vec<_> = rotation_keeps(&times, &policy)
+            .into_iter()
+            .zip(&times)
+            .filter(|(keep, _)| *keep)
+            .map(|(_, t)| t.format("%m-%d").to_string())
+            .collect();
+        // Last two, then Sunday 03-09 closes the previous week, and the
+        // newest of February and January
+        assert_eq!(kept, ["03-10", "03-09", "02-28", "01-31"]);
+    }
+}
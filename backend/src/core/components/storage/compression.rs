@@ -0,0 +1,161 @@
+//! Backup file compression
+//!
+//! With `BACKUP_COMPRESSION` set to `zstd` or `gzip`, each backup is written
+//! uncompressed and then compressed next to it (`backup_<ts>.db.zst`,
+//! `backup_<ts>.inc.gz`), and the uncompressed file is removed. Readers go
+//! through [`open_backup`], which decrypts and decompresses by file
+//! extension, so restores and incremental chains don't care how a file was
+//! stored.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::encryption::{is_encrypted, DecryptReader, ENCRYPTED_SUFFIX};
+use crate::core::components::config::{BackupCompression, StorageConfig};
+
+impl BackupCompression {
+    /// Extension added to compressed files, with its dot
+    pub fn suffix(self) -> Option<&'static str> {
+        match self {
+            BackupCompression::None => None,
+            BackupCompression::Zstd => Some(".zst"),
+            BackupCompression::Gzip => Some(".gz"),
+        }
+    }
+
+    /// Compression used for `file_name`, judged by its extension
+    pub fn of(file_name: &str) -> Self {
+        let file_name = file_name
+            .strip_suffix(ENCRYPTED_SUFFIX)
+            .unwrap_or(file_name);
+        [BackupCompression::Zstd, BackupCompression::Gzip]
+            .into_iter()
+            .find(|c| c.suffix().is_some_and(|s| file_name.ends_with(s)))
+            .unwrap_or(BackupCompression::None)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BackupCompression::None => "none",
+            BackupCompression::Zstd => "zstd",
+            BackupCompression::Gzip => "gzip",
+        }
+    }
+}
+
+/// `file_name` without its encryption and compression extensions
+pub(crate) fn plain_name(file_name: &str) -> &str {
+    let file_name = file_name
+        .strip_suffix(ENCRYPTED_SUFFIX)
+        .unwrap_or(file_name);
+    BackupCompression::of(file_name)
+        .suffix()
+        .and_then(|s| file_name.strip_suffix(s))
+        .unwrap_or(file_name)
+}
+
+/// Read a backup or export file, decrypting and decompressing it as its name
+/// says
+pub(crate) fn open_backup(path: &Path) -> io::Result<Box<dyn Read>> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut file: Box<dyn Read> = Box::new(BufReader::new(File::open(path)?));
+    if is_encrypted(&name) {
+        file = Box::new(BufReader::new(DecryptReader::new(file)?));
+    }
+    Ok(match BackupCompression::of(&name) {
+        BackupCompression::None => file,
+        BackupCompression::Zstd => Box::new(zstd::Decoder::new(file)?),
+        BackupCompression::Gzip => Box::new(flate2::read::GzDecoder::new(file)),
+    })
+}
+
+/// Compress the finished backup at `path` as configured, replacing it;
+/// returns where the backup now is
+pub(crate) fn compress_backup(path: &Path, config: &StorageConfig) -> io::Result<PathBuf> {
+    let compression = config.backup_compression;
+    let Some(suffix) = compression.suffix() else {
+        return Ok(path.to_path_buf());
+    };
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    let dest = path.with_file_name(name);
+
+    let mut input = BufReader::new(File::open(path)?);
+    let output = BufWriter::new(File::create(&dest)?);
+    let written = match compression {
+        BackupCompression::Zstd => {
+            let level = config.backup_compression_level.unwrap_or(3);
+            zstd::Encoder::new(output, level).and_then(|mut encoder| {
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?.flush()
+            })
+        }
+        BackupCompression::Gzip => {
+            let level = config.backup_compression_level.unwrap_or(6) as u32;
+            let mut encoder =
+                flate2::write::GzEncoder::new(output, flate2::Compression::new(level));
+            io::copy(&mut input, &mut encoder).and_then(|_| encoder.finish()?.flush())
+        }
+        BackupCompression::None => Ok(()),
+    };
+    if let Err(e) = written {
+        let _ = fs::remove_file(&dest);
+        return Err(e);
+    }
+    fs::remove_file(path)?;
+    Ok(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::config::BackupEncryption;
+
+    #[test]
+    fn test_compress_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("cockpit-compress-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let data: Vec<u8> = (0..100_000u32)
+            .flat_map(|n| (n % 251).to_le_bytes())
+            .collect();
+
+        for compression in [BackupCompression::Zstd, BackupCompression::Gzip] {
+            let config = StorageConfig {
+                root: dir.clone(),
+                data_dir: dir.clone(),
+                logs_dir: dir.clone(),
+                cache_dir: dir.clone(),
+                backup_dir: dir.clone(),
+                export_dir: dir.clone(),
+                media_dir: dir.clone(),
+                import_dir: dir.clone(),
+                writing_sync_dir: None,
+                writing_git_autocommit: false,
+                writing_git_remote: None,
+                max_total_size_gb: None,
+                backup_compression: compression,
+                backup_compression_level: None,
+                backup_encryption: BackupEncryption::None,
+            };
+            let path = dir.join("backup_1.db");
+            fs::write(&path, &data).unwrap();
+
+            let stored = compress_backup(&path, &config).unwrap();
+            assert!(!path.exists());
+            let name = stored.file_name().unwrap().to_string_lossy().into_owned();
+            assert_eq!(BackupCompression::of(&name), compression);
+            assert_eq!(plain_name(&name), "backup_1.db");
+            assert!(fs::metadata(&stored).unwrap().len() < data.len() as u64);
+
+            let mut restored = Vec::new();
+            open_backup(&stored)
+                .unwrap()
+                .read_to_end(&mut restored)
+                .unwrap();
+            assert_eq!(restored, data);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,283 @@
+//! Backup and export encryption
+//!
+//! With `BACKUP_ENCRYPTION` set to `master_key` or `passphrase`, finished
+//! backups and exports are encrypted next to themselves with a `.enc`
+//! extension (`backup_<ts>.db.zst.enc`, `export_<ts>.json.enc`), after any
+//! compression, and the plain file is removed. Copies synced off the machine
+//! then expose nothing without `COCKPIT_MASTER_KEY` or `BACKUP_PASSPHRASE`.
+//!
+//! ## Format
+//!
+//! `CKPTENC1`, the key source (1 master key, 2 passphrase), a 16-byte salt
+//! and a 7-byte nonce prefix, then the contents as AES-256-GCM chunks of up
+//! to 1 MiB, each its ciphertext length (u32 LE) and ciphertext. A chunk's
+//! nonce is the prefix, the chunk number (u32 BE) and 1 for the last chunk,
+//! 0 otherwise, so reordered, dropped or truncated chunks fail to decrypt.
+//! The header is authenticated with every chunk. Passphrase keys are derived
+//! with Argon2id from the salt.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use zeroize::Zeroize;
+
+use crate::core::components::config::BackupEncryption;
+use crate::core::components::crypto::load_master_key;
+
+pub(crate) const ENCRYPTED_SUFFIX: &str = ".enc";
+const PASSPHRASE_ENV: &str = "BACKUP_PASSPHRASE";
+const MAGIC: &[u8; 8] = b"CKPTENC1";
+const HEADER_LEN: usize = 8 + 1 + 16 + 7;
+const CHUNK_LEN: usize = 1 << 20;
+const TAG_LEN: usize = 16;
+
+impl BackupEncryption {
+    pub fn name(self) -> &'static str {
+        match self {
+            BackupEncryption::None => "none",
+            BackupEncryption::MasterKey => "master_key",
+            BackupEncryption::Passphrase => "passphrase",
+        }
+    }
+}
+
+/// Whether `file_name` is an encrypted backup or export
+pub(crate) fn is_encrypted(file_name: &str) -> bool {
+    file_name.ends_with(ENCRYPTED_SUFFIX)
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// The AES key for `source` (the header byte) and `salt`
+fn file_key(source: u8, salt: &[u8]) -> io::Result<Aes256Gcm> {
+    let mut key = match source {
+        1 => load_master_key().map_err(io::Error::other)?,
+        2 => {
+            let mut passphrase = std::env::var(PASSPHRASE_ENV)
+                .map_err(|_| io::Error::other(format!("missing {PASSPHRASE_ENV} env var")))?;
+            let mut key = [0u8; 32];
+            let derived = argon2::Argon2::default()
+                .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                .map_err(|e| io::Error::other(format!("key derivation failed: {e}")));
+            passphrase.zeroize();
+            derived?;
+            key
+        }
+        _ => return Err(invalid("unknown encryption key source")),
+    };
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| io::Error::other(e.to_string()));
+    key.zeroize();
+    cipher
+}
+
+fn nonce(prefix: &[u8], chunk: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..7].copy_from_slice(prefix);
+    nonce[7..11].copy_from_slice(&chunk.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Read until `buf` is full or the input ends; returns the bytes read
+fn fill(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypt the finished file at `path` with `encryption`, replacing it;
+/// returns where the file now is
+pub(crate) fn encrypt_file(path: &Path, encryption: BackupEncryption) -> io::Result<PathBuf> {
+    let source = match encryption {
+        BackupEncryption::None => return Ok(path.to_path_buf()),
+        BackupEncryption::MasterKey => 1,
+        BackupEncryption::Passphrase => 2,
+    };
+    let mut header = [0u8; HEADER_LEN];
+    header[..8].copy_from_slice(MAGIC);
+    header[8] = source;
+    rand::rng().fill_bytes(&mut header[9..]);
+    let cipher = file_key(source, &header[9..25])?;
+
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(ENCRYPTED_SUFFIX);
+    let dest = path.with_file_name(name);
+    let written = (|| {
+        let mut input = BufReader::new(File::open(path)?);
+        let mut output = BufWriter::new(File::create(&dest)?);
+        output.write_all(&header)?;
+
+        // One chunk of lookahead, so the last chunk is known when written
+        let (mut current, mut next) = (vec![0u8; CHUNK_LEN], vec![0u8; CHUNK_LEN]);
+        let mut len = fill(&mut input, &mut current)?;
+        for chunk in 0u32.. {
+            let next_len = fill(&mut input, &mut next)?;
+            let last = next_len == 0;
+            let sealed = cipher
+                .encrypt(
+                    Nonce::from_slice(&nonce(&header[25..], chunk, last)),
+                    Payload {
+                        msg: &current[..len],
+                        aad: &header,
+                    },
+                )
+                .map_err(|e| io::Error::other(e.to_string()))?;
+            output.write_all(&(sealed.len() as u32).to_le_bytes())?;
+            output.write_all(&sealed)?;
+            if last {
+                break;
+            }
+            std::mem::swap(&mut current, &mut next);
+            len = next_len;
+        }
+        output.flush()
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_file(&dest);
+        return Err(e);
+    }
+    fs::remove_file(path)?;
+    Ok(dest)
+}
+
+/// Decrypts a file written by [`encrypt_file`] as it is read
+pub(crate) struct DecryptReader<R> {
+    inner: R,
+    cipher: Aes256Gcm,
+    header: [u8; HEADER_LEN],
+    chunk: u32,
+    /// Length of the next chunk, read to tell whether the current is last
+    next_len: Option<u32>,
+    plain: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<R: Read> DecryptReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut header = [0u8; HEADER_LEN];
+        inner.read_exact(&mut header)?;
+        if &header[..8] != MAGIC {
+            return Err(invalid("not an encrypted backup"));
+        }
+        let cipher = file_key(header[8], &header[9..25])?;
+        let mut reader = Self {
+            inner,
+            cipher,
+            header,
+            chunk: 0,
+            next_len: None,
+            plain: Vec::new(),
+            pos: 0,
+            done: false,
+        };
+        reader.next_len = reader.read_len()?;
+        Ok(reader)
+    }
+
+    /// The next chunk length, `None` at the end of the file
+    fn read_len(&mut self) -> io::Result<Option<u32>> {
+        let mut len = [0u8; 4];
+        match fill(&mut self.inner, &mut len)? {
+            0 => Ok(None),
+            4 => Ok(Some(u32::from_le_bytes(len))),
+            _ => Err(invalid("encrypted file is truncated")),
+        }
+    }
+
+    fn next_chunk(&mut self) -> io::Result<()> {
+        let len = self
+            .next_len
+            .ok_or_else(|| invalid("encrypted file is truncated"))? as usize;
+        if len > CHUNK_LEN + TAG_LEN {
+            return Err(invalid("encrypted chunk is too large"));
+        }
+        let mut sealed = vec![0u8; len];
+        self.inner.read_exact(&mut sealed)?;
+        self.next_len = self.read_len()?;
+        let last = self.next_len.is_none();
+        self.plain = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce(&self.header[25..], self.chunk, last)),
+                Payload {
+                    msg: &sealed,
+                    aad: &self.header,
+                },
+            )
+            .map_err(|_| invalid("decryption failed: wrong key or damaged file"))?;
+        self.pos = 0;
+        self.chunk += 1;
+        self.done = last;
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for DecryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.plain.len() {
+            if self.done {
+                return Ok(0);
+            }
+            self.next_chunk()?;
+        }
+        let n = buf.len().min(self.plain.len() - self.pos);
+        buf[..n].copy_from_slice(&self.plain[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("cockpit-encrypt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        std::env::set_var(PASSPHRASE_ENV, "correct horse battery staple");
+
+        for size in [0, CHUNK_LEN, CHUNK_LEN * 2 + 7] {
+            let data: Vec<u8> = (0..size).map(|n| (n % 251) as u8).collect();
+            let path = dir.join("export_1.json");
+            fs::write(&path, &data).unwrap();
+
+            let stored = encrypt_file(&path, BackupEncryption::Passphrase).unwrap();
+            assert!(!path.exists());
+            assert!(is_encrypted(&stored.to_string_lossy()));
+
+            let mut plain = Vec::new();
+            DecryptReader::new(File::open(&stored).unwrap())
+                .unwrap()
+                .read_to_end(&mut plain)
+                .unwrap();
+            assert_eq!(plain, data);
+
+            // Dropping the last chunk is caught
+            let sealed = fs::read(&stored).unwrap();
+            if size > CHUNK_LEN {
+                let cut = HEADER_LEN + 4 + CHUNK_LEN + TAG_LEN;
+                let result = DecryptReader::new(&sealed[..cut])
+                    .and_then(|mut r| r.read_to_end(&mut Vec::new()));
+                assert!(result.is_err());
+            }
+            fs::remove_file(&stored).unwrap();
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,16 +1,51 @@
-//! Data export and import module
+//! Data export module
 //! 
-//! Handles exporting all application data to JSON and importing it back.
-//! Supports ideas, news articles, and app settings.
+//! Handles exporting application data to JSON.
+//! An export is a dump of every user table, by entity group, with all
+//! columns; it covers all groups or only those asked for, optionally limited
+//! to rows created in a date range. Derived and local-only data (the audit
+//! log, task run history, embeddings, search indexes and site credentials)
+//! and encrypted secrets are left out. Large datasets can be exported as
+//! JSONL instead (see [`super::jsonl`]); [`super::import`] reads both back.
+//! Exports are encrypted like backups when `BACKUP_ENCRYPTION` is set; see
+//! [`super::encryption`]. A PostgreSQL database is exported as the SQL
+//! `pg_dump` writes for the same tables instead; see [`super::pg_dump`].
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use sea_orm::sea_query::{Alias, Expr, Func, Order, SelectStatement};
+use sea_orm::ConnectionTrait;
+use sea_orm_migration::MigratorTrait;
 use serde_json::Value as JsonValue;
-use tracing::{info, warn, error, instrument};
+use tracing::{info, error, instrument};
 
-use crate::core::components::config::StorageConfig;
+use crate::core::components::config::{BackupEncryption, DatabaseConfig, StorageConfig};
 use crate::core::components::errors::AppError;
+use super::encryption::encrypt_file;
+use super::jsonl::{self, ExportManifest, JSONL_SUFFIX, MANIFEST_SUFFIX};
+use super::pg_dump;
+use super::tables::ExportTable;
+use crate::core::components::settings::entities as app_settings;
+use crate::research::components::feed::entities::{
+    alert_rules, article_signals, articles, feed_sources, mute_rules, relevance_weights,
+    saved_searches, settings as news_settings, sources as news_sources,
+};
+use crate::research::entities::{
+    accounts, items, reader_clips, reader_references, reader_site_rules, reader_snapshot_assets,
+    reader_snapshots, reading_queue, streams,
+};
+use crate::system::components::scheduler::entities as system_tasks;
+use crate::writing::components::ideas::entities::idea_references;
+use crate::writing::components::ideas::types as ideas;
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, notes, reference_items, writing_idea_links, writing_publications,
+    writings,
+};
+use crate::writing::components::newsletter::entities::newsletter_sends;
+
+/// Current export format version
+pub const EXPORT_VERSION: &str = "2.0";
 
 /// Export data structure
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -18,23 +53,296 @@ use crate::core::components::errors::AppError;
 pub struct ExportData {
     pub version: String,
     pub exported_at: String,
+    /// Newest migration applied to the exported database
+    #[serde(default)]
+    pub schema_version: Option<String>,
+    /// Groups the file holds; files from before 1.1 hold ideas, articles and
+    /// settings
+    #[serde(default)]
+    pub groups: Vec<ExportGroup>,
+    #[serde(default)]
+    pub since: Option<String>,
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Rows of every exported table, by table
+    #[serde(default)]
+    pub tables: BTreeMap<String, Vec<JsonValue>>,
+    /// Ideas, articles and settings of 1.x files, which held only some
+    /// columns; 2.0 files have them in `tables`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub ideas: Vec<JsonValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub news_articles: Vec<JsonValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub app_settings: Vec<JsonValue>,
 }
 
+/// Entity groups an export can be limited to
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportGroup {
+    Ideas,
+    Articles,
+    Settings,
+    KnowledgeGraph,
+    Reader,
+    Research,
+    Writings,
+    Notes,
+    Feeds,
+    Tasks,
+}
+
+impl ExportGroup {
+    /// Every group, in the order they are imported: groups and tables come
+    /// after those their rows refer to
+    pub const ALL: [ExportGroup; 10] = [
+        ExportGroup::Settings,
+        ExportGroup::Tasks,
+        ExportGroup::Feeds,
+        ExportGroup::Articles,
+        ExportGroup::Ideas,
+        ExportGroup::Writings,
+        ExportGroup::KnowledgeGraph,
+        ExportGroup::Reader,
+        ExportGroup::Research,
+        ExportGroup::Notes,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportGroup::Ideas => "ideas",
+            ExportGroup::Articles => "articles",
+            ExportGroup::Settings => "settings",
+            ExportGroup::KnowledgeGraph => "knowledge_graph",
+            ExportGroup::Reader => "reader",
+            ExportGroup::Research => "research",
+            ExportGroup::Writings => "writings",
+            ExportGroup::Notes => "notes",
+            ExportGroup::Feeds => "feeds",
+            ExportGroup::Tasks => "tasks",
+        }
+    }
+
+    /// The group's tables, each with the column a date range applies to;
+    /// configuration without one is always exported whole
+    pub(super) fn tables(self) -> Vec<ExportTable> {
+        match self {
+            ExportGroup::Ideas => vec![
+                ExportTable::of::<ideas::Entity>(Some(ideas::Column::DateAdded)),
+            ],
+            ExportGroup::Articles => vec![
+                ExportTable::of::<articles::Entity>(Some(articles::Column::FetchedAt)),
+                ExportTable::of::<article_signals::Entity>(
+                    Some(article_signals::Column::CreatedAt),
+                ),
+                ExportTable::of::<relevance_weights::Entity>(None),
+            ],
+            ExportGroup::Settings => vec![ExportTable::of::<app_settings::Entity>(None)],
+            ExportGroup::KnowledgeGraph => vec![
+                ExportTable::of::<reference_items::Entity>(
+                    Some(reference_items::Column::CreatedAt),
+                ),
+                ExportTable::of::<idea_references::Entity>(
+                    Some(idea_references::Column::AddedAt),
+                ),
+                ExportTable::of::<idea_reference_links::Entity>(
+                    Some(idea_reference_links::Column::CreatedAt),
+                ),
+                ExportTable::of::<writing_idea_links::Entity>(
+                    Some(writing_idea_links::Column::CreatedAt),
+                ),
+            ],
+            ExportGroup::Reader => vec![
+                ExportTable::of::<reader_references::Entity>(
+                    Some(reader_references::Column::CreatedAt),
+                ),
+                ExportTable::of::<reader_snapshots::Entity>(
+                    Some(reader_snapshots::Column::FetchedAt),
+                ),
+                ExportTable::of::<reader_snapshot_assets::Entity>(
+                    Some(reader_snapshot_assets::Column::CreatedAt),
+                ),
+                ExportTable::of::<reader_clips::Entity>(Some(reader_clips::Column::CreatedAt)),
+                ExportTable::of::<reading_queue::Entity>(Some(reading_queue::Column::AddedAt)),
+                ExportTable::of::<reader_site_rules::Entity>(None),
+            ],
+            ExportGroup::Research => vec![
+                ExportTable::of::<accounts::Entity>(None),
+                ExportTable::of::<streams::Entity>(None),
+                ExportTable::of::<items::Entity>(Some(items::Column::CreatedAt)),
+            ],
+            ExportGroup::Writings => vec![
+                ExportTable::of::<writings::Entity>(Some(writings::Column::CreatedAt)),
+                ExportTable::of::<writing_publications::Entity>(
+                    Some(writing_publications::Column::CreatedAt),
+                ),
+                ExportTable::of::<newsletter_sends::Entity>(
+                    Some(newsletter_sends::Column::CreatedAt),
+                ),
+            ],
+            ExportGroup::Notes => vec![
+                ExportTable::of::<notes::Entity>(Some(notes::Column::CreatedAt)),
+            ],
+            ExportGroup::Feeds => vec![
+                ExportTable::of::<feed_sources::Entity>(None),
+                ExportTable::of::<news_sources::Entity>(None),
+                ExportTable::of::<news_settings::Entity>(None),
+                ExportTable::of::<saved_searches::Entity>(None),
+                ExportTable::of::<alert_rules::Entity>(None),
+                ExportTable::of::<mute_rules::Entity>(None),
+            ],
+            ExportGroup::Tasks => vec![ExportTable::of::<system_tasks::Entity>(None)],
+        }
+    }
+}
+
+/// Layout of an export file
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// One JSON document, built in memory
+    #[default]
+    Json,
+    /// One row per line, streamed to disk, with a manifest next to it
+    Jsonl,
+    /// `pg_dump` SQL of the rows, for loading with `psql`; PostgreSQL only
+    Sql,
+    /// A rendered writing or digest; not a database export format
+    Pdf,
+    /// A Word document of a writing; not a database export format
+    Docx,
+}
+
+/// Options for `export_database`
+#[derive(Debug, Default, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportInput {
+    /// Entity groups to export (default: all)
+    pub groups: Option<Vec<ExportGroup>>,
+    /// Only rows created at or after this RFC 3339 timestamp or `YYYY-MM-DD` date
+    pub since: Option<String>,
+    /// Only rows created before this timestamp; a date includes that day
+    pub until: Option<String>,
+    /// File layout (default: json, or sql with PostgreSQL)
+    pub format: Option<ExportFormat>,
+}
+
+/// Creation time bounds of an export, as UTC SQLite datetimes
+#[derive(Debug, Default)]
+pub(super) struct DateRange {
+    since: Option<String>,
+    until: Option<String>,
+}
+
+impl DateRange {
+    fn parse(input: &ExportInput) -> Result<Self, AppError> {
+        Ok(Self {
+            since: input.since.as_deref()
+                .map(|v| parse_bound("since", v, false))
+                .transpose()?,
+            until: input.until.as_deref()
+                .map(|v| parse_bound("until", v, true))
+                .transpose()?,
+        })
+    }
+
+    /// Limit `query` to rows whose `column` is in the range
+    pub(super) fn restrict(&self, query: &mut SelectStatement, column: &str) {
+        let at = || {
+            Expr::expr(Func::cust(Alias::new("datetime")).arg(Expr::col(Alias::new(column))))
+        };
+        if let Some(since) = &self.since {
+            query.and_where(at().gte(since.as_str()));
+        }
+        if let Some(until) = &self.until {
+            query.and_where(at().lt(until.as_str()));
+        }
+    }
+}
+
+/// An RFC 3339 timestamp or `YYYY-MM-DD` date as a UTC SQLite datetime; a
+/// date ending a range stands for the end of that day
+fn parse_bound(field: &str, value: &str, end: bool) -> Result<String, AppError> {
+    let at = if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        at.with_timezone(&Utc).naive_utc()
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let date = if end { date.succ_opt().unwrap_or(date) } else { date };
+        date.and_time(NaiveTime::MIN)
+    } else {
+        return Err(AppError::validation(
+            field,
+            "Expected an RFC 3339 timestamp or a YYYY-MM-DD date",
+        ));
+    };
+    Ok(at.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Rows of `table` as JSON objects, limited to `range`
+async fn dump_table(
+    db: &sea_orm::DatabaseConnection,
+    table: &ExportTable,
+    range: &DateRange,
+) -> Result<Vec<JsonValue>, AppError> {
+    let mut query = table.select(range);
+    query.order_by(Alias::new("rowid"), Order::Asc);
+    let rows = db.query_all(db.get_database_backend().build(&query)).await
+        .map_err(|e| {
+            error!(error = %e, table = %table.name, "Failed to export table");
+            AppError::database(format!("Failed to export {}: {}", table.name, e))
+        })?;
+    rows.iter()
+        .map(|row| {
+            let json = row.try_get::<String>("", "row")?;
+            serde_json::from_str(&json)
+                .map_err(|e| AppError::other(format!("Failed to read {} row: {}", table.name, e)))
+        })
+        .collect()
+}
+
+/// Rows of each of `tables`
+async fn count_rows(
+    db: &sea_orm::DatabaseConnection,
+    tables: &[ExportTable],
+) -> Result<BTreeMap<String, usize>, AppError> {
+    let mut counts = BTreeMap::new();
+    for table in tables {
+        let sql = format!("SELECT COUNT(*) FROM \"{}\"", table.name);
+        let count = match db
+            .query_one(sea_orm::Statement::from_string(db.get_database_backend(), sql))
+            .await?
+        {
+            Some(row) => row.try_get_by_index::<i64>(0)?,
+            None => 0,
+        };
+        counts.insert(table.name.clone(), count as usize);
+    }
+    Ok(counts)
+}
+
 /// Export information
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportInfo {
     pub file_path: String,
     pub file_size: u64,
     pub timestamp: String,
+    pub format: ExportFormat,
+    /// Manifest of a JSONL export
+    pub manifest_path: Option<String>,
     pub record_counts: ExportCounts,
+    /// Rows exported per group
+    pub group_counts: BTreeMap<String, usize>,
+    pub encrypted: bool,
 }
 
 /// Record counts in export
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportCounts {
     pub ideas: usize,
@@ -42,132 +350,159 @@ pub struct ExportCounts {
     pub app_settings: usize,
 }
 
-/// Import summary
-#[derive(Debug, Clone, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ImportSummary {
-    pub records_added: usize,
-    pub records_skipped: usize,
-    pub errors: Vec<String>,
-}
-
-/// Export all data to JSON file
-#[instrument(skip(db))]
+/// Export the groups selected by `input` to a JSON file
+#[instrument(skip(db, database))]
 pub async fn export_data(
     db: &sea_orm::DatabaseConnection,
+    database: &DatabaseConfig,
     storage_config: &StorageConfig,
+    input: &ExportInput,
 ) -> Result<ExportInfo, AppError> {
-    use sea_orm::{ConnectionTrait, Statement};
+    let groups: Vec<ExportGroup> = match &input.groups {
+        Some(selected) => ExportGroup::ALL.into_iter()
+            .filter(|g| selected.contains(g))
+            .collect(),
+        None => ExportGroup::ALL.to_vec(),
+    };
+    if groups.is_empty() {
+        return Err(AppError::validation("groups", "Select at least one group to export"));
+    }
+    let format = input.format.unwrap_or(if database.is_postgres() {
+        ExportFormat::Sql
+    } else {
+        ExportFormat::Json
+    });
+    match format {
+        ExportFormat::Pdf | ExportFormat::Docx => {
+            return Err(AppError::validation("format", "Database exports are json, jsonl or sql"));
+        }
+        ExportFormat::Sql if !database.is_postgres() => {
+            return Err(AppError::validation("format", "SQL exports need a PostgreSQL database"));
+        }
+        ExportFormat::Json | ExportFormat::Jsonl if database.is_postgres() => {
+            return Err(AppError::validation("format", "PostgreSQL databases are exported as sql"));
+        }
+        ExportFormat::Sql if input.since.is_some() || input.until.is_some() => {
+            return Err(AppError::validation(
+                "since",
+                "SQL exports can't be limited to a date range",
+            ));
+        }
+        _ => {}
+    }
+    let range = DateRange::parse(input)?;
     
-    info!("Starting data export");
+    info!(groups = ?groups, since = ?range.since, until = ?range.until, "Starting data export");
     
     // Create exports directory if it doesn't exist
     let export_dir = &storage_config.export_dir;
     fs::create_dir_all(export_dir)
         .map_err(|e| AppError::file_operation("create directory", export_dir.to_string_lossy(), e))?;
     
-    // Export ideas
-    let ideas_sql = "SELECT * FROM ideas WHERE date_removed IS NULL";
-    let ideas_result = db.query_all(Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
-        ideas_sql.to_string(),
-    )).await
-        .map_err(|e| {
-            error!(error = %e, "Failed to export ideas");
-            AppError::database(format!("Failed to export ideas: {}", e))
-        })?;
-    
-    let ideas_json: Vec<JsonValue> = ideas_result.iter()
-        .map(|row| {
-            serde_json::json!({
-                "id": row.try_get::<i32>("", "id").ok(),
-                "title": row.try_get::<String>("", "title").ok(),
-                "status": row.try_get::<String>("", "status").ok(),
-                "priority": row.try_get::<i32>("", "priority").ok(),
-                "is_pinned": row.try_get::<bool>("", "is_pinned").ok(),
-                "notes_markdown": row.try_get::<String>("", "notes_markdown").ok(),
-                "article_title": row.try_get::<String>("", "article_title").ok(),
-                "article_markdown": row.try_get::<String>("", "article_markdown").ok(),
-                "source_url": row.try_get::<String>("", "source_url").ok(),
-                "news_article_id": row.try_get::<i32>("", "news_article_id").ok(),
-                "date_created": row.try_get::<String>("", "date_created").ok(),
-                "date_updated": row.try_get::<String>("", "date_updated").ok(),
-            })
-        })
-        .collect();
+    let schema_version = migration::Migrator::get_applied_migrations(db).await?
+        .last()
+        .map(|m| m.name().to_string());
     
-    // Export news articles (only non-dismissed)
-    let news_sql = "SELECT * FROM news_articles WHERE is_dismissed = 0";
-    let news_result = db.query_all(Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
-        news_sql.to_string(),
-    )).await
-        .map_err(|e| {
-            error!(error = %e, "Failed to export news articles");
-            AppError::database(format!("Failed to export news articles: {}", e))
-        })?;
-    
-    let news_json: Vec<JsonValue> = news_result.iter()
-        .map(|row| {
-            serde_json::json!({
-                "article_id": row.try_get::<String>("", "article_id").ok(),
-                "title": row.try_get::<String>("", "title").ok(),
-                "description": row.try_get::<String>("", "description").ok(),
-                "content": row.try_get::<String>("", "content").ok(),
-                "url": row.try_get::<String>("", "url").ok(),
-                "source_id": row.try_get::<String>("", "source_id").ok(),
-                "source_name": row.try_get::<String>("", "source_name").ok(),
-                "published_at": row.try_get::<String>("", "published_at").ok(),
-                "fetched_at": row.try_get::<String>("", "fetched_at").ok(),
-                "is_read": row.try_get::<bool>("", "is_read").ok(),
-                "is_starred": row.try_get::<bool>("", "is_starred").ok(),
-            })
-        })
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
+    let group_tables: Vec<_> = groups.iter()
+        .flat_map(|g| g.tables())
         .collect();
     
-    // Export app settings
-    let settings_sql = "SELECT * FROM app_settings";
-    let settings_result = db.query_all(Statement::from_string(
-        sea_orm::DatabaseBackend::Sqlite,
-        settings_sql.to_string(),
-    )).await
-        .map_err(|e| {
-            error!(error = %e, "Failed to export settings");
-            AppError::database(format!("Failed to export settings: {}", e))
-        })?;
+    let (export_path, table_counts) = match format {
+        ExportFormat::Json => {
+            // Export each group table by table
+            let mut tables = BTreeMap::new();
+            for table in &group_tables {
+                let rows = dump_table(db, table, &range).await?;
+                tables.insert(table.name.clone(), rows);
+            }
+            let table_counts: BTreeMap<_, _> = tables.iter()
+                .map(|(table, rows)| (table.clone(), rows.len()))
+                .collect();
+            
+            // Create export data structure
+            let export_data = ExportData {
+                version: EXPORT_VERSION.to_string(),
+                exported_at: Utc::now().to_rfc3339(),
+                schema_version: schema_version.clone(),
+                groups: groups.clone(),
+                since: input.since.clone(),
+                until: input.until.clone(),
+                tables,
+                ideas: Vec::new(),
+                news_articles: Vec::new(),
+                app_settings: Vec::new(),
+            };
+            
+            // Write to file
+            let export_path = export_dir.join(format!("export_{}.json", timestamp));
+            let json_string = serde_json::to_string_pretty(&export_data)
+                .map_err(|e| AppError::other(format!("Failed to serialize export data: {}", e)))?;
+            
+            fs::write(&export_path, json_string)
+                .map_err(|e| AppError::file_operation("write", export_path.to_string_lossy(), e))?;
+            (export_path, table_counts)
+        }
+        ExportFormat::Jsonl => {
+            let export_path = export_dir.join(format!("export_{}{}", timestamp, JSONL_SUFFIX));
+            let table_counts = jsonl::write_rows(db, &export_path, &group_tables, &range).await?;
+            (export_path, table_counts)
+        }
+        ExportFormat::Sql => {
+            let export_path = export_dir.join(format!("export_{}.sql", timestamp));
+            let names: Vec<_> = group_tables.iter().map(|t| t.name.clone()).collect();
+            let table_counts = count_rows(db, &group_tables).await?;
+            pg_dump::dump_data(&database.url, &names, &export_path).await.map_err(|e| {
+                error!(error = %e, "Data export failed");
+                let _ = fs::remove_file(&export_path);
+                AppError::database(format!("Failed to export data: {}", e))
+            })?;
+            (export_path, table_counts)
+        }
+        ExportFormat::Pdf | ExportFormat::Docx => unreachable!("rejected above"),
+    };
     
-    let settings_json: Vec<JsonValue> = settings_result.iter()
-        .map(|row| {
-            serde_json::json!({
-                "key": row.try_get::<String>("", "key").ok(),
-                "value": row.try_get::<String>("", "value").ok(),
-                "value_type": row.try_get::<String>("", "value_type").ok(),
-                "category": row.try_get::<String>("", "category").ok(),
-                "description": row.try_get::<String>("", "description").ok(),
-            })
+    let group_counts = groups.iter()
+        .map(|g| {
+            let count = g.tables().iter()
+                .map(|table| table_counts.get(&table.name).copied().unwrap_or(0))
+                .sum();
+            (g.name().to_string(), count)
         })
         .collect();
-    
-    // Create export data structure
-    let export_data = ExportData {
-        version: "1.0".to_string(),
-        exported_at: Utc::now().to_rfc3339(),
-        ideas: ideas_json.clone(),
-        news_articles: news_json.clone(),
-        app_settings: settings_json.clone(),
+    let table_len = |table: &str| table_counts.get(table).copied().unwrap_or(0);
+    let record_counts = ExportCounts {
+        ideas: table_len("ideas"),
+        news_articles: table_len("news_articles"),
+        app_settings: table_len("app_settings"),
     };
     
-    // Generate export filename with timestamp
-    let timestamp = Utc::now().format("%Y%m%d_%H%M%S").to_string();
-    let export_filename = format!("export_{}.json", timestamp);
-    let export_path = export_dir.join(&export_filename);
-    
-    // Write to file
-    let json_string = serde_json::to_string_pretty(&export_data)
-        .map_err(|e| AppError::other(format!("Failed to serialize export data: {}", e)))?;
+    let export_path = encrypt_file(&export_path, storage_config.backup_encryption)
+        .map_err(|e| AppError::file_operation("encrypt", export_path.to_string_lossy(), e))?;
+    let encrypted = storage_config.backup_encryption != BackupEncryption::None;
     
-    fs::write(&export_path, json_string)
-        .map_err(|e| AppError::file_operation("write", export_path.to_string_lossy(), e))?;
+    let manifest_path = match format {
+        ExportFormat::Json | ExportFormat::Sql | ExportFormat::Pdf | ExportFormat::Docx => None,
+        ExportFormat::Jsonl => {
+            let manifest = ExportManifest {
+                version: EXPORT_VERSION.to_string(),
+                exported_at: Utc::now().to_rfc3339(),
+                schema_version,
+                groups: groups.clone(),
+                since: input.since.clone(),
+                until: input.until.clone(),
+                data_file: export_path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                encrypted,
+                tables: table_counts,
+            };
+            let path = export_dir.join(format!("export_{}{}", timestamp, MANIFEST_SUFFIX));
+            manifest.save(&path)?;
+            Some(path.to_string_lossy().to_string())
+        }
+    };
     
     // Get file size
     let metadata = fs::metadata(&export_path)
@@ -177,11 +512,11 @@ pub async fn export_data(
         file_path: export_path.to_string_lossy().to_string(),
         file_size: metadata.len(),
         timestamp: Utc::now().to_rfc3339(),
-        record_counts: ExportCounts {
-            ideas: ideas_json.len(),
-            news_articles: news_json.len(),
-            app_settings: settings_json.len(),
-        },
+        format,
+        manifest_path,
+        record_counts,
+        group_counts,
+        encrypted,
     };
     
     info!(
@@ -190,192 +525,78 @@ pub async fn export_data(
         ideas = export_info.record_counts.ideas,
         news_articles = export_info.record_counts.news_articles,
         settings = export_info.record_counts.app_settings,
+        groups = ?export_info.group_counts,
         "Data export completed successfully"
     );
     
     Ok(export_info)
 }
 
-/// Import data from JSON file
-#[instrument(skip(db))]
-pub async fn import_data(
-    db: &sea_orm::DatabaseConnection,
-    import_path: &str,
-) -> Result<ImportSummary, AppError> {
-    use sea_orm::{ConnectionTrait, Statement, TransactionTrait};
-    
-    info!(import_path = %import_path, "Starting data import");
-    
-    let import_file = Path::new(import_path);
-    
-    // Validate import file exists
-    if !import_file.exists() {
-        error!(import_path = %import_path, "Import file not found");
-        return Err(AppError::validation("import_path", "Import file not found"));
-    }
-    
-    // Read and parse JSON file
-    let json_string = fs::read_to_string(import_file)
-        .map_err(|e| AppError::file_operation("read", import_path, e))?;
-    
-    let export_data: ExportData = serde_json::from_str(&json_string)
-        .map_err(|e| AppError::validation("import_file", format!("Invalid JSON format: {}", e)))?;
-    
-    info!(
-        version = %export_data.version,
-        exported_at = %export_data.exported_at,
-        ideas_count = export_data.ideas.len(),
-        news_count = export_data.news_articles.len(),
-        settings_count = export_data.app_settings.len(),
-        "Import file parsed successfully"
-    );
-    
-    let mut summary = ImportSummary {
-        records_added: 0,
-        records_skipped: 0,
-        errors: Vec::new(),
-    };
-    
-    // Use transaction for atomic import
-    let txn = db.begin().await
-        .map_err(|e| AppError::database(format!("Failed to start transaction: {}", e)))?;
-    
-    // Helper function to escape SQL strings
-    let escape_sql = |s: &str| s.replace("'", "''");
-    
-    // Import ideas (skip if ID exists)
-    info!("Importing ideas...");
-    for idea in export_data.ideas {
-        if let Some(id) = idea.get("id").and_then(|v| v.as_i64()) {
-            // Check if idea already exists
-            let check_sql = format!("SELECT COUNT(*) as count FROM ideas WHERE id = {}", id);
-            let result = txn.query_one(Statement::from_string(
-                sea_orm::DatabaseBackend::Sqlite,
-                check_sql,
-            )).await;
-            
-            if let Ok(Some(row)) = result {
-                if let Ok(count) = row.try_get::<i32>("", "count") {
-                    if count > 0 {
-                        summary.records_skipped += 1;
-                        continue;
-                    }
-                }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use sea_orm::Statement;
+
+    /// Tables no group exports: derived or local-only data
+    const NOT_EXPORTED: &[&str] = &[
+        "audit_log",
+        "embeddings",
+        "seaql_migrations",
+        "site_credentials",
+        "system_task_runs",
+        "system_task_scheduled_runs",
+        "writing_sync_files",
+    ];
+
+    #[tokio::test]
+    async fn test_groups_cover_schema() {
+        let db = test_db().await;
+        let exported: Vec<_> = ExportGroup::ALL
+            .iter()
+            .flat_map(|g| g.tables())
+            .collect();
+
+        let rows = db
+            .query_all(Statement::from_string(
+                db.get_database_backend(),
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'"
+                    .to_string(),
+            ))
+            .await
+            .unwrap();
+        for row in rows {
+            let table: String = row.try_get_by_index(0).unwrap();
+            // Search index tables are rebuilt from the rows they index
+            if table.contains("_fts") || NOT_EXPORTED.contains(&table.as_str()) {
+                continue;
             }
-            
-            // Build insert statement with proper escaping
-            let title = idea.get("title").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let status = idea.get("status").and_then(|v| v.as_str()).map(|s| format!("'{}'", s)).unwrap_or("'in_progress'".to_string());
-            let priority = idea.get("priority").and_then(|v| v.as_i64()).unwrap_or(0);
-            let is_pinned = idea.get("is_pinned").and_then(|v| v.as_bool()).map(|b| if b { 1 } else { 0 }).unwrap_or(0);
-            let notes = idea.get("notes_markdown").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let article_title = idea.get("article_title").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let article_md = idea.get("article_markdown").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let source_url = idea.get("source_url").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let news_id = idea.get("news_article_id").and_then(|v| v.as_i64()).map(|n| n.to_string()).unwrap_or("NULL".to_string());
-            let created = idea.get("date_created").and_then(|v| v.as_str()).map(|s| format!("'{}'", s)).unwrap_or_else(|| format!("'{}'", Utc::now().to_rfc3339()));
-            let updated = idea.get("date_updated").and_then(|v| v.as_str()).map(|s| format!("'{}'", s)).unwrap_or_else(|| format!("'{}'", Utc::now().to_rfc3339()));
-            
-            let insert_sql = format!(
-                "INSERT INTO ideas (id, title, status, priority, is_pinned, notes_markdown, article_title, article_markdown, source_url, news_article_id, date_created, date_updated) VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, {})",
-                id, title, status, priority, is_pinned, notes, article_title, article_md, source_url, news_id, created, updated
+            assert!(
+                exported.iter().any(|t| t.name == table),
+                "{} is in no export group",
+                table
             );
-            
-            match txn.execute(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, insert_sql)).await {
-                Ok(_) => summary.records_added += 1,
-                Err(e) => {
-                    warn!(error = %e, id = id, "Failed to import idea");
-                    summary.errors.push(format!("Idea {}: {}", id, e));
-                }
-            }
         }
-    }
-    
-    // Import news articles (skip if article_id exists)
-    info!("Importing news articles...");
-    for article in export_data.news_articles {
-        if let Some(article_id) = article.get("article_id").and_then(|v| v.as_str()) {
-            // Check if article already exists
-            let check_sql = format!("SELECT COUNT(*) as count FROM news_articles WHERE article_id = '{}'", escape_sql(article_id));
-            let result = txn.query_one(Statement::from_string(
-                sea_orm::DatabaseBackend::Sqlite,
-                check_sql,
-            )).await;
-            
-            if let Ok(Some(row)) = result {
-                if let Ok(count) = row.try_get::<i32>("", "count") {
-                    if count > 0 {
-                        summary.records_skipped += 1;
-                        continue;
-                    }
-                }
-            }
-            
-            // Build insert statement
-            let title = article.get("title").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let description = article.get("description").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let content = article.get("content").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let url = article.get("url").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let source_id = article.get("source_id").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let source_name = article.get("source_name").and_then(|v| v.as_str()).map(|s| format!("'{}'", escape_sql(s))).unwrap_or("NULL".to_string());
-            let published = article.get("published_at").and_then(|v| v.as_str()).map(|s| format!("'{}'", s)).unwrap_or("NULL".to_string());
-            let fetched = article.get("fetched_at").and_then(|v| v.as_str()).map(|s| format!("'{}'", s)).unwrap_or_else(|| format!("'{}'", Utc::now().to_rfc3339()));
-            let is_read = article.get("is_read").and_then(|v| v.as_bool()).map(|b| if b { 1 } else { 0 }).unwrap_or(0);
-            let is_starred = article.get("is_starred").and_then(|v| v.as_bool()).map(|b| if b { 1 } else { 0 }).unwrap_or(0);
-            
-            let insert_sql = format!(
-                "INSERT INTO news_articles (article_id, title, description, content, url, source_id, source_name, published_at, fetched_at, is_read, is_starred, is_dismissed, dismissed_at) VALUES ('{}', {}, {}, {}, {}, {}, {}, {}, {}, {}, {}, 0, NULL)",
-                escape_sql(article_id), title, description, content, url, source_id, source_name, published, fetched, is_read, is_starred
-            );
-            
-            match txn.execute(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, insert_sql)).await {
-                Ok(_) => summary.records_added += 1,
-                Err(e) => {
-                    warn!(error = %e, article_id = article_id, "Failed to import news article");
-                    summary.errors.push(format!("Article {}: {}", article_id, e));
-                }
-            }
+
+        for table in &exported {
+            dump_table(&db, table, &DateRange::default()).await.unwrap();
         }
     }
-    
-    // Import app settings (update existing, insert new)
-    info!("Importing app settings...");
-    for setting in export_data.app_settings {
-        if let Some(key) = setting.get("key").and_then(|v| v.as_str()) {
-            let value = setting.get("value").and_then(|v| v.as_str()).map(|s| escape_sql(s)).unwrap_or_default();
-            let value_type = setting.get("value_type").and_then(|v| v.as_str()).map(|s| escape_sql(s)).unwrap_or_else(|| "string".to_string());
-            let category = setting.get("category").and_then(|v| v.as_str()).map(|s| escape_sql(s)).unwrap_or_default();
-            let description = setting.get("description").and_then(|v| v.as_str()).map(|s| escape_sql(s)).unwrap_or_default();
-            
-            // Use INSERT OR REPLACE for settings (upsert)
-            let upsert_sql = format!(
-                "INSERT OR REPLACE INTO app_settings (key, value, value_type, category, description) VALUES ('{}', '{}', '{}', '{}', '{}')",
-                escape_sql(key), value, value_type, category, description
-            );
-            
-            match txn.execute(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, upsert_sql)).await {
-                Ok(_) => summary.records_added += 1,
-                Err(e) => {
-                    warn!(error = %e, key = key, "Failed to import setting");
-                    summary.errors.push(format!("Setting {}: {}", key, e));
-                }
-            }
-        }
+
+    #[test]
+    fn test_parse_bound() {
+        assert_eq!(
+            parse_bound("since", "2025-03-01", false).unwrap(),
+            "2025-03-01 00:00:00"
+        );
+        assert_eq!(
+            parse_bound("until", "2025-03-31", true).unwrap(),
+            "2025-04-01 00:00:00"
+        );
+        assert_eq!(
+            parse_bound("until", "2025-03-01T12:30:00+02:00", true).unwrap(),
+            "2025-03-01 10:30:00"
+        );
+        assert!(parse_bound("since", "last week", false).is_err());
     }
-    
-    // Commit transaction
-    txn.commit().await
-        .map_err(|e| {
-            error!(error = %e, "Failed to commit import transaction");
-            AppError::database(format!("Failed to commit transaction: {}", e))
-        })?;
-    
-    info!(
-        added = summary.records_added,
-        skipped = summary.records_skipped,
-        errors = summary.errors.len(),
-        "Data import completed"
-    );
-    
-    Ok(summary)
 }
@@ -0,0 +1,682 @@
+//! Data import
+//!
+//! `import_data` reads a JSON export, or a JSONL export's manifest or rows
+//! (see [`super::jsonl`]), and writes the rows back table by table in one
+//! transaction. Each row is matched against the database: news articles by
+//! URL, writings by slug, settings by key and everything else by id. A
+//! matched row is kept or overwritten as the [`ImportStrategy`] says and
+//! listed in the summary's conflicts; an unmatched one is inserted, under its
+//! own id unless that is taken, in which case rows referring to it are
+//! pointed at the new id. Foreign keys are checked once all rows are in.
+//! With `dry_run` the transaction is rolled back, so the summary previews
+//! the import without changing anything. PostgreSQL databases load their
+//! SQL exports with `psql` instead.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use sea_orm::sea_query::{Alias, Expr, Func, Query, SimpleExpr};
+use sea_orm::{ConnectionTrait, DatabaseTransaction, Statement, TransactionTrait, Value};
+use sea_orm_migration::MigratorTrait;
+use serde_json::{Map, Value as JsonValue};
+use tracing::{error, info, instrument, warn};
+
+use super::compression::{open_backup, plain_name};
+use super::encryption::is_encrypted;
+use super::export::{ExportData, ExportGroup, EXPORT_VERSION};
+use super::jsonl::{self, ExportManifest, JSONL_SUFFIX, MANIFEST_SUFFIX};
+use super::tables::ExportTable;
+use crate::core::components::errors::AppError;
+
+/// Columns rows are matched on instead of `id`, when they have a value
+const MATCH_COLUMNS: &[(&str, &str)] = &[
+    ("news_articles", "url"),
+    ("writings", "slug"),
+    ("app_settings", "key"),
+];
+
+/// Columns referring to tables whose rows can be stored under a new id
+const REFERENCES: &[(&str, &str)] = &[
+    ("news_article_id", "news_articles"),
+    ("article_id", "news_articles"),
+    ("writing_id", "writings"),
+];
+
+/// Conflicts listed per table; more are only counted
+const MAX_CONFLICTS: usize = 500;
+
+/// What to do with an imported row that matches an existing one
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStrategy {
+    /// Keep the existing row
+    #[default]
+    Skip,
+    /// Replace the existing row's columns with the imported ones
+    Overwrite,
+    /// Overwrite only if the imported row was updated more recently
+    MergeNewer,
+}
+
+/// Options for `import_database`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImportOptions {
+    pub strategy: ImportStrategy,
+    /// Roll back instead of committing
+    pub dry_run: bool,
+}
+
+/// Import summary
+#[derive(Debug, Clone, Default, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub records_added: usize,
+    /// Existing rows overwritten by imported ones
+    pub records_updated: usize,
+    pub records_skipped: usize,
+    pub errors: Vec<String>,
+    /// Nothing was written; the counts are what an import would do
+    pub dry_run: bool,
+    /// Imported rows that matched existing ones, by table (at most 500 each)
+    pub conflicts: BTreeMap<String, Vec<ImportConflict>>,
+}
+
+/// An imported row that matched an existing one
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportConflict {
+    /// What matched, e.g. `slug=my-post`
+    pub key: String,
+    pub existing_id: i64,
+    pub resolution: ConflictResolution,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Skipped,
+    Overwritten,
+}
+
+/// Writes exported rows into the database
+pub(super) struct RowImporter {
+    strategy: ImportStrategy,
+    /// Tables seen so far
+    tables: HashMap<String, ExportTable>,
+    /// Imported ids stored under another id, by table
+    new_ids: HashMap<&'static str, HashMap<i64, i64>>,
+    pub summary: ImportSummary,
+}
+
+impl RowImporter {
+    pub fn new(options: ImportOptions) -> Self {
+        Self {
+            strategy: options.strategy,
+            tables: HashMap::new(),
+            new_ids: HashMap::new(),
+            summary: ImportSummary {
+                dry_run: options.dry_run,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Import one exported row of `table`, recording any failure
+    pub async fn import(&mut self, txn: &DatabaseTransaction, table: &str, row: &JsonValue) {
+        if let Err(e) = self.try_import(txn, table, row).await {
+            let id = row.get("id").map(|v| v.to_string()).unwrap_or_default();
+            warn!(error = %e, table = table, id = %id, "Failed to import row");
+            self.summary.errors.push(format!("{} {}: {}", table, id, e));
+        }
+    }
+
+    async fn try_import(
+        &mut self,
+        txn: &DatabaseTransaction,
+        table: &str,
+        row: &JsonValue,
+    ) -> Result<(), AppError> {
+        let Some(row) = row.as_object() else {
+            return Err(AppError::validation("row", "Expected a JSON object"));
+        };
+        let table = self.table(table)?;
+        // Columns the table no longer has are dropped
+        let mut row: Map<String, JsonValue> = row
+            .iter()
+            .filter(|(column, _)| table.column(column).is_some())
+            .map(|(column, value)| (column.clone(), value.clone()))
+            .collect();
+        for (column, target) in REFERENCES {
+            let old = row.get(*column).and_then(|v| v.as_i64());
+            if let Some(new) = old.and_then(|id| self.new_ids.get(target)?.get(&id)) {
+                row.insert(column.to_string(), (*new).into());
+            }
+        }
+        let imported_id = row.get("id").and_then(|v| v.as_i64());
+
+        let key = MATCH_COLUMNS
+            .iter()
+            .find(|(t, _)| *t == table.name)
+            .and_then(|(_, column)| Some((*column, row.get(*column).filter(|v| !v.is_null())?)))
+            .or_else(|| Some(("id", row.get("id")?)))
+            .map(|(column, value)| (column, value.clone()));
+        let existing = match &key {
+            Some((column, value)) => find_id(txn, &table, column, value).await?,
+            None => None,
+        };
+
+        let Some(existing_id) = existing else {
+            // Keep the imported id unless another row has it
+            let keep_id = match imported_id {
+                Some(id) => find_id(txn, &table, "id", &id.into()).await?.is_none(),
+                None => false,
+            };
+            if !keep_id {
+                row.remove("id");
+            }
+            let new_id = insert(txn, &table, &row).await?;
+            self.remember(&table.name, imported_id, new_id);
+            self.summary.records_added += 1;
+            return Ok(());
+        };
+        self.remember(&table.name, imported_id, existing_id);
+
+        let overwrite = match self.strategy {
+            ImportStrategy::Skip => false,
+            ImportStrategy::Overwrite => true,
+            ImportStrategy::MergeNewer => is_newer(txn, &table, existing_id, &row).await?,
+        };
+        let resolution = if overwrite {
+            row.remove("id");
+            update(txn, &table, existing_id, &row).await?;
+            self.summary.records_updated += 1;
+            ConflictResolution::Overwritten
+        } else {
+            self.summary.records_skipped += 1;
+            ConflictResolution::Skipped
+        };
+        let conflicts = self
+            .summary
+            .conflicts
+            .entry(table.name.clone())
+            .or_default();
+        if let Some((column, value)) = key.filter(|_| conflicts.len() < MAX_CONFLICTS) {
+            let value = value
+                .as_str()
+                .map_or_else(|| value.to_string(), str::to_string);
+            conflicts.push(ImportConflict {
+                key: format!("{}={}", column, value),
+                existing_id,
+                resolution,
+            });
+        }
+        Ok(())
+    }
+
+    /// The table called `name`, which must be one exports write
+    fn table(&mut self, name: &str) -> Result<ExportTable, AppError> {
+        if let Some(table) = self.tables.get(name) {
+            return Ok(table.clone());
+        }
+        let table = ExportTable::named(name).ok_or_else(|| {
+            AppError::validation("table", format!("Can't import into '{}'", name))
+        })?;
+        self.tables.insert(name.to_string(), table.clone());
+        Ok(table)
+    }
+
+    fn remember(&mut self, table: &str, imported_id: Option<i64>, id: i64) {
+        let Some(target) = REFERENCES.iter().map(|(_, t)| *t).find(|t| *t == table) else {
+            return;
+        };
+        if let Some(imported_id) = imported_id.filter(|imported| *imported != id) {
+            self.new_ids
+                .entry(target)
+                .or_default()
+                .insert(imported_id, id);
+        }
+    }
+
+    /// Rows violating foreign keys, as messages
+    async fn foreign_key_errors(&self, txn: &DatabaseTransaction) -> Result<Vec<String>, AppError> {
+        let rows = txn
+            .query_all(Statement::from_string(
+                txn.get_database_backend(),
+                "PRAGMA foreign_key_check".to_string(),
+            ))
+            .await?;
+        let mut missing: BTreeMap<(String, String), usize> = BTreeMap::new();
+        for row in &rows {
+            let table: String = row.try_get_by_index(0)?;
+            let parent: String = row.try_get_by_index(2)?;
+            *missing.entry((table, parent)).or_default() += 1;
+        }
+        Ok(missing
+            .into_iter()
+            .map(|((table, parent), count)| {
+                format!(
+                    "{} rows of {} refer to missing {} rows",
+                    count, table, parent
+                )
+            })
+            .collect())
+    }
+}
+
+/// `value` as a parameter for `column` of `table`
+fn column_value(table: &ExportTable, column: &str, value: &JsonValue) -> Result<Value, AppError> {
+    let kind = table
+        .column(column)
+        .ok_or_else(|| AppError::validation(column, format!("{} has no such column", table.name)))?
+        .kind;
+    kind.value(value)
+        .map_err(|e| AppError::validation(column, e))
+}
+
+/// The columns of `row` with their values as parameters
+fn row_values(
+    table: &ExportTable,
+    row: &Map<String, JsonValue>,
+) -> Result<Vec<(Alias, Value)>, AppError> {
+    row.iter()
+        .map(|(column, value)| Ok((Alias::new(column), column_value(table, column, value)?)))
+        .collect()
+}
+
+/// Id of the row of `table` whose `column` is `value`
+async fn find_id(
+    txn: &DatabaseTransaction,
+    table: &ExportTable,
+    column: &str,
+    value: &JsonValue,
+) -> Result<Option<i64>, AppError> {
+    let query = Query::select()
+        .column(Alias::new("id"))
+        .from(Alias::new(&table.name))
+        .and_where(Expr::col(Alias::new(column)).eq(column_value(table, column, value)?))
+        .limit(1)
+        .to_owned();
+    let row = txn
+        .query_one(txn.get_database_backend().build(&query))
+        .await?;
+    Ok(row.map(|r| r.try_get::<i64>("", "id")).transpose()?)
+}
+
+async fn insert(
+    txn: &DatabaseTransaction,
+    table: &ExportTable,
+    row: &Map<String, JsonValue>,
+) -> Result<i64, AppError> {
+    let (columns, values): (Vec<_>, Vec<_>) = row_values(table, row)?.into_iter().unzip();
+    let query = Query::insert()
+        .into_table(Alias::new(&table.name))
+        .columns(columns)
+        .values(values.into_iter().map(SimpleExpr::from))
+        .map_err(|e| AppError::other(e.to_string()))?
+        .to_owned();
+    let result = txn
+        .execute(txn.get_database_backend().build(&query))
+        .await?;
+    Ok(result.last_insert_id() as i64)
+}
+
+async fn update(
+    txn: &DatabaseTransaction,
+    table: &ExportTable,
+    id: i64,
+    row: &Map<String, JsonValue>,
+) -> Result<(), AppError> {
+    if row.is_empty() {
+        return Ok(());
+    }
+    let query = Query::update()
+        .table(Alias::new(&table.name))
+        .values(
+            row_values(table, row)?
+                .into_iter()
+                .map(|(c, v)| (c, SimpleExpr::from(v))),
+        )
+        .and_where(Expr::col(Alias::new("id")).eq(id))
+        .to_owned();
+    txn.execute(txn.get_database_backend().build(&query))
+        .await?;
+    Ok(())
+}
+
+/// Whether the imported `row` was updated after the existing row `id`; rows
+/// without an update time never are
+async fn is_newer(
+    txn: &DatabaseTransaction,
+    table: &ExportTable,
+    id: i64,
+    row: &Map<String, JsonValue>,
+) -> Result<bool, AppError> {
+    let Some((column, updated)) = ["updated_at", "date_updated"]
+        .into_iter()
+        .find_map(|c| Some((c, row.get(c)?.as_str()?)))
+    else {
+        return Ok(false);
+    };
+    let datetime = |arg: SimpleExpr| Func::cust(Alias::new("datetime")).arg(arg);
+    let query = Query::select()
+        .expr_as(
+            Expr::expr(datetime(Expr::val(updated).into()))
+                .gt(datetime(Expr::col(Alias::new(column)).into())),
+            Alias::new("newer"),
+        )
+        .from(Alias::new(&table.name))
+        .and_where(Expr::col(Alias::new("id")).eq(id))
+        .to_owned();
+    let newer = txn
+        .query_one(txn.get_database_backend().build(&query))
+        .await?;
+    Ok(newer
+        .and_then(|r| r.try_get::<Option<bool>>("", "newer").ok().flatten())
+        .unwrap_or(false))
+}
+
+/// Reject exports this build can't read: unknown format versions, and
+/// databases migrated further than this build knows
+fn check_version(version: &str, schema_version: Option<&str>) -> Result<(), AppError> {
+    let parse = |v: &str| -> Option<(u32, u32)> {
+        let (major, minor) = v.split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    };
+    let readable = parse(version).is_some_and(|v| v >= (1, 0) && Some(v) <= parse(EXPORT_VERSION));
+    if !readable {
+        return Err(AppError::validation(
+            "import_file",
+            format!(
+                "Unsupported export version '{}'; this app reads 1.0 to {}",
+                version, EXPORT_VERSION
+            ),
+        ));
+    }
+    if let Some(schema_version) = schema_version {
+        let known = migration::Migrator::migrations()
+            .iter()
+            .any(|m| m.name() == schema_version);
+        if !known {
+            return Err(AppError::validation(
+                "import_file",
+                format!(
+                    "Exported by a newer version of the app (migration {}); update it first",
+                    schema_version
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Read a JSON export, decrypting an encrypted one
+fn read_export(import_file: &Path, import_path: &str) -> Result<ExportData, AppError> {
+    // Read and parse JSON file, decrypting an encrypted export
+    let json_string = if is_encrypted(import_path) {
+        let mut json = String::new();
+        open_backup(import_file)
+            .and_then(|mut reader| reader.read_to_string(&mut json))
+            .map_err(|e| AppError::file_operation("decrypt", import_path, e))?;
+        json
+    } else {
+        fs::read_to_string(import_file)
+            .map_err(|e| AppError::file_operation("read", import_path, e))?
+    };
+
+    let mut export_data: ExportData = serde_json::from_str(&json_string)
+        .map_err(|e| AppError::validation("import_file", format!("Invalid JSON format: {}", e)))?;
+    check_version(&export_data.version, export_data.schema_version.as_deref())?;
+
+    // 1.x files keep these outside `tables`, with some of the columns
+    for (table, rows) in [
+        ("ideas", &mut export_data.ideas),
+        ("news_articles", &mut export_data.news_articles),
+        ("app_settings", &mut export_data.app_settings),
+    ] {
+        if !rows.is_empty() {
+            export_data
+                .tables
+                .insert(table.to_string(), std::mem::take(rows));
+        }
+    }
+
+    info!(
+        version = %export_data.version,
+        exported_at = %export_data.exported_at,
+        tables = export_data.tables.len(),
+        "Import file parsed successfully"
+    );
+
+    Ok(export_data)
+}
+
+/// Import data from a JSON export, or a JSONL export's manifest or rows
+#[instrument(skip(db))]
+pub async fn import_data(
+    db: &sea_orm::DatabaseConnection,
+    import_path: &str,
+    options: ImportOptions,
+) -> Result<ImportSummary, AppError> {
+    info!(import_path = %import_path, "Starting data import");
+    if db.get_database_backend() == sea_orm::DatabaseBackend::Postgres {
+        return Err(AppError::validation(
+            "import_path",
+            "PostgreSQL databases import SQL exports with psql",
+        ));
+    }
+
+    let import_file = Path::new(import_path);
+
+    // Validate import file exists
+    if !import_file.exists() {
+        error!(import_path = %import_path, "Import file not found");
+        return Err(AppError::validation("import_path", "Import file not found"));
+    }
+
+    // A JSONL export is imported through its manifest or from its rows alone
+    let name = import_file
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let (data_file, manifest) = if name.ends_with(MANIFEST_SUFFIX) {
+        let manifest = ExportManifest::load(import_file)?;
+        check_version(&manifest.version, manifest.schema_version.as_deref())?;
+        (
+            Some(import_file.with_file_name(&manifest.data_file)),
+            Some(manifest),
+        )
+    } else if plain_name(&name).ends_with(JSONL_SUFFIX) {
+        (Some(import_file.to_path_buf()), None)
+    } else {
+        (None, None)
+    };
+    let export_data = match data_file {
+        Some(_) => None,
+        None => Some(read_export(import_file, import_path)?),
+    };
+
+    let mut importer = RowImporter::new(options);
+
+    // Use transaction for atomic import; foreign keys are checked at the end,
+    // so rows may refer to rows later in the file
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| AppError::database(format!("Failed to start transaction: {}", e)))?;
+    txn.execute(Statement::from_string(
+        txn.get_database_backend(),
+        "PRAGMA defer_foreign_keys = ON".to_string(),
+    ))
+    .await?;
+
+    if let Some(data_file) = &data_file {
+        jsonl::import_rows(&txn, data_file, manifest.as_ref(), &mut importer).await?;
+    } else if let Some(export_data) = &export_data {
+        // Referenced tables come first, so new ids are known when rows refer
+        // to them
+        for group in ExportGroup::ALL {
+            for table in group.tables() {
+                let Some(rows) = export_data.tables.get(&table.name) else {
+                    continue;
+                };
+                info!(table = %table.name, rows = rows.len(), "Importing rows...");
+                for row in rows {
+                    importer.import(&txn, &table.name, row).await;
+                }
+            }
+        }
+    }
+
+    let foreign_key_errors = importer.foreign_key_errors(&txn).await?;
+    let mut summary = importer.summary;
+    if options.dry_run {
+        summary.errors.extend(foreign_key_errors);
+        txn.rollback()
+            .await
+            .map_err(|e| AppError::database(format!("Failed to roll back transaction: {}", e)))?;
+    } else if !foreign_key_errors.is_empty() {
+        let _ = txn.rollback().await;
+        return Err(AppError::validation(
+            "import_file",
+            format!("Nothing imported: {}", foreign_key_errors.join("; ")),
+        ));
+    } else {
+        // Commit transaction
+        txn.commit().await.map_err(|e| {
+            error!(error = %e, "Failed to commit import transaction");
+            AppError::database(format!("Failed to commit transaction: {}", e))
+        })?;
+    }
+
+    info!(
+        added = summary.records_added,
+        updated = summary.records_updated,
+        skipped = summary.records_skipped,
+        errors = summary.errors.len(),
+        dry_run = options.dry_run,
+        "Data import completed"
+    );
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use serde_json::json;
+
+    async fn writings(txn: &DatabaseTransaction) -> Vec<(i64, String)> {
+        txn.query_all(Statement::from_string(
+            txn.get_database_backend(),
+            "SELECT id, title FROM writings ORDER BY id".to_string(),
+        ))
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r.try_get("", "id").unwrap(),
+                r.try_get("", "title").unwrap(),
+            )
+        })
+        .collect()
+    }
+
+    fn writing(id: i64, slug: &str, title: &str, updated: &str) -> JsonValue {
+        json!({ "id": id, "slug": slug, "title": title, "updated_at": updated })
+    }
+
+    #[tokio::test]
+    async fn test_import_strategies() {
+        let db = test_db().await;
+        let txn = db.begin().await.unwrap();
+
+        let mut importer = RowImporter::new(ImportOptions::default());
+        importer
+            .import(
+                &txn,
+                "writings",
+                &writing(1, "first", "First", "2025-01-01 00:00:00"),
+            )
+            .await;
+        // Same slug under another id: matched, and its link follows it
+        importer
+            .import(
+                &txn,
+                "writings",
+                &writing(7, "first", "Renamed", "2025-02-01 00:00:00"),
+            )
+            .await;
+        importer
+            .import(
+                &txn,
+                "writing_publications",
+                &json!({ "id": 1, "writing_id": 7, "platform": "blog" }),
+            )
+            .await;
+        assert_eq!(importer.summary.records_added, 2);
+        assert_eq!(importer.summary.records_skipped, 1);
+        assert_eq!(importer.summary.conflicts["writings"][0].key, "slug=first");
+        assert_eq!(writings(&txn).await, [(1, "First".to_string())]);
+        let linked = txn
+            .query_one(Statement::from_string(
+                txn.get_database_backend(),
+                "SELECT writing_id FROM writing_publications".to_string(),
+            ))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(linked.try_get::<i64>("", "writing_id").unwrap(), 1);
+
+        let mut importer = RowImporter::new(ImportOptions {
+            strategy: ImportStrategy::MergeNewer,
+            dry_run: false,
+        });
+        importer
+            .import(
+                &txn,
+                "writings",
+                &writing(1, "first", "Older", "2024-01-01 00:00:00"),
+            )
+            .await;
+        importer
+            .import(
+                &txn,
+                "writings",
+                &writing(1, "first", "Newer", "2025-06-01T00:00:00Z"),
+            )
+            .await;
+        assert_eq!(importer.summary.records_skipped, 1);
+        assert_eq!(importer.summary.records_updated, 1);
+        assert_eq!(writings(&txn).await, [(1, "Newer".to_string())]);
+
+        importer
+            .import(&txn, "audit_log", &json!({ "id": 1 }))
+            .await;
+        assert_eq!(importer.summary.errors.len(), 1);
+        assert!(importer.foreign_key_errors(&txn).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_check_version() {
+        assert!(check_version("1.0", None).is_ok());
+        assert!(check_version(EXPORT_VERSION, Some("m001_initial_schema")).is_ok());
+        assert!(check_version("2.9", None).is_err());
+        assert!(check_version("3.0", None).is_err());
+        assert!(check_version("latest", None).is_err());
+        assert!(check_version("2.0", Some("m999_from_the_future")).is_err());
+    }
+}
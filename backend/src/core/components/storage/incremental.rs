@@ -0,0 +1,294 @@
+//! Incremental backups
+//!
+//! A full backup (`backup_<ts>.db`) can be followed by increments
+//! (`backup_<ts>.inc`) holding only the pages that changed since the
+//! previous backup of its chain. Both are made from `VACUUM INTO` snapshots,
+//! so an increment is a page diff of two snapshots; rows added or removed
+//! early in the file shift the pages after them and make for a larger
+//! increment. The chain manifest (`backup_<ts>.chain.json`, named after the
+//! full backup) lists the increments in order, and restoring an increment
+//! replays the chain from the full backup up to it.
+//!
+//! An increment file is `CKPTINC1`, the page size and page count (u32 LE),
+//! then each changed page as its 1-based number (u32 LE) and contents. Full
+//! backups and increments may be compressed; see [`super::compression`].
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::compression::{open_backup, plain_name};
+use crate::core::components::errors::AppError;
+
+pub(crate) const INCREMENT_EXTENSION: &str = "inc";
+const MANIFEST_SUFFIX: &str = ".chain.json";
+const MAGIC: &[u8; 8] = b"CKPTINC1";
+
+/// Increments per chain before the next incremental backup is a full one
+pub(crate) const MAX_CHAIN_LENGTH: usize = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainIncrement {
+    pub file: String,
+    pub created_at: String,
+    pub page_count: u32,
+    pub changed_pages: u32,
+}
+
+/// A full backup and the increments built on it, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainManifest {
+    /// File name of the full backup
+    pub base: String,
+    pub page_size: u32,
+    pub increments: Vec<ChainIncrement>,
+}
+
+impl ChainManifest {
+    pub fn new(base: String, page_size: u32) -> Self {
+        Self {
+            base,
+            page_size,
+            increments: Vec::new(),
+        }
+    }
+
+    pub fn path(backup_dir: &Path, base: &str) -> PathBuf {
+        let base = plain_name(base);
+        let stem = base.strip_suffix(".db").unwrap_or(base);
+        backup_dir.join(format!("{}{}", stem, MANIFEST_SUFFIX))
+    }
+
+    pub fn load(backup_dir: &Path, base: &str) -> Result<Option<Self>, AppError> {
+        let path = Self::path(backup_dir, base);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(&path)
+            .map_err(|e| AppError::file_operation("read", path.to_string_lossy(), e))?;
+        serde_json::from_str(&raw).map(Some).map_err(|e| {
+            AppError::storage_operation("read backup chain", format!("{}: {}", path.display(), e))
+        })
+    }
+
+    /// Write the manifest, replacing the old one only once fully written
+    pub fn save(&self, backup_dir: &Path) -> Result<(), AppError> {
+        let path = Self::path(backup_dir, &self.base);
+        let tmp = path.with_extension("json.tmp");
+        let raw = serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::storage_operation("write backup chain", e.to_string()))?;
+        fs::write(&tmp, raw)
+            .map_err(|e| AppError::file_operation("write", tmp.to_string_lossy(), e))?;
+        fs::rename(&tmp, &path)
+            .map_err(|e| AppError::file_operation("rename", path.to_string_lossy(), e))
+    }
+
+    /// Whether `file_name` is the full backup or one of the increments
+    pub fn contains(&self, file_name: &str) -> bool {
+        self.base == file_name || self.increments.iter().any(|i| i.file == file_name)
+    }
+}
+
+/// Every chain manifest in `backup_dir`
+pub(crate) fn load_chains(backup_dir: &Path) -> Result<Vec<ChainManifest>, AppError> {
+    let Ok(entries) = fs::read_dir(backup_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut chains = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if let Some(stem) = name.strip_suffix(MANIFEST_SUFFIX) {
+            if let Some(chain) = ChainManifest::load(backup_dir, &format!("{}.db", stem))? {
+                chains.push(chain);
+            }
+        }
+    }
+    Ok(chains)
+}
+
+/// The chain `file_name` (a full backup or an increment) belongs to
+pub(crate) fn find_chain(
+    backup_dir: &Path,
+    file_name: &str,
+) -> Result<Option<ChainManifest>, AppError> {
+    Ok(load_chains(backup_dir)?
+        .into_iter()
+        .find(|c| c.contains(file_name)))
+}
+
+/// Page size from a SQLite database header
+pub(crate) fn page_size(path: &Path) -> io::Result<u32> {
+    let mut header = [0u8; 18];
+    File::open(path)?.read_exact(&mut header)?;
+    // Stored big-endian at offset 16; 1 means 65536
+    Ok(match u16::from_be_bytes([header[16], header[17]]) {
+        1 => 65536,
+        size => size as u32,
+    })
+}
+
+/// Fill `buf` with the next page; `false` once the file has no more
+fn read_page(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Write the pages of `current` that differ from `previous` to `out`;
+/// returns the page count of `current` and how many pages were written
+pub(crate) fn write_increment(
+    previous: &Path,
+    current: &Path,
+    out: &Path,
+) -> io::Result<(u32, u32)> {
+    let size = page_size(current)?;
+    if page_size(previous)? != size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "page size differs from the previous backup",
+        ));
+    }
+    let page_count = (fs::metadata(current)?.len() / size as u64) as u32;
+
+    let mut previous = BufReader::new(File::open(previous)?);
+    let mut current = BufReader::new(File::open(current)?);
+    let mut writer = BufWriter::new(File::create(out)?);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&size.to_le_bytes())?;
+    writer.write_all(&page_count.to_le_bytes())?;
+
+    let mut old = vec![0u8; size as usize];
+    let mut new = vec![0u8; size as usize];
+    let mut has_old = true;
+    let mut changed = 0;
+    for page in 1..=page_count {
+        current.read_exact(&mut new)?;
+        has_old = has_old && read_page(&mut previous, &mut old)?;
+        if !has_old || old != new {
+            writer.write_all(&page.to_le_bytes())?;
+            writer.write_all(&new)?;
+            changed += 1;
+        }
+    }
+    writer.flush()?;
+    Ok((page_count, changed))
+}
+
+/// Apply an increment to the database copy at `target`
+pub(crate) fn apply_increment(target: &Path, increment: &Path) -> io::Result<()> {
+    let mut reader = open_backup(increment)?;
+    let mut header = [0u8; 16];
+    reader.read_exact(&mut header)?;
+    if &header[..8] != MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an incremental backup",
+        ));
+    }
+    let word = |at: usize| {
+        u32::from_le_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]]) as u64
+    };
+    let (size, page_count) = (word(8), word(12));
+
+    let mut file = OpenOptions::new().write(true).open(target)?;
+    let mut number = [0u8; 4];
+    let mut page = vec![0u8; size as usize];
+    while read_page(&mut reader, &mut number)? {
+        reader.read_exact(&mut page)?;
+        let offset = (u32::from_le_bytes(number) as u64 - 1) * size;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(&page)?;
+    }
+    file.set_len(page_count * size)?;
+    file.sync_all()
+}
+
+/// Rebuild the database as of `upto`, the full backup or one of the
+/// increments of `chain`, at `dest`
+pub(crate) fn materialize(
+    backup_dir: &Path,
+    chain: &ChainManifest,
+    upto: &str,
+    dest: &Path,
+) -> Result<(), AppError> {
+    let base = backup_dir.join(&chain.base);
+    open_backup(&base)
+        .and_then(|mut reader| io::copy(&mut reader, &mut File::create(dest)?))
+        .map_err(|e| AppError::file_operation("copy", base.to_string_lossy(), e))?;
+    if upto == chain.base {
+        return Ok(());
+    }
+    for increment in &chain.increments {
+        let path = backup_dir.join(&increment.file);
+        apply_increment(dest, &path)
+            .map_err(|e| AppError::file_operation("apply increment", path.to_string_lossy(), e))?;
+        if increment.file == upto {
+            return Ok(());
+        }
+    }
+    Err(AppError::validation(
+        "backup_path",
+        format!(
+            "'{}' is not part of the backup chain of {}",
+            upto, chain.base
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: usize = 512;
+
+    /// A fake database of `pages` pages; page `n` is filled with `fill(n)`
+    fn database(path: &Path, pages: usize, fill: impl Fn(usize) -> u8) {
+        let mut bytes = Vec::new();
+        for n in 1..=pages {
+            bytes.extend(std::iter::repeat_n(fill(n), PAGE));
+        }
+        bytes[16..18].copy_from_slice(&(PAGE as u16).to_be_bytes());
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_increment_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("cockpit-incremental-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (previous, current, increment, restored) = (
+            dir.join("previous.db"),
+            dir.join("current.db"),
+            dir.join("current.inc"),
+            dir.join("restored.db"),
+        );
+
+        // Page 2 changes and page 5 is new
+        database(&previous, 4, |n| n as u8);
+        database(&current, 5, |n| if n == 2 { 0xff } else { n as u8 });
+        assert_eq!(
+            write_increment(&previous, &current, &increment).unwrap(),
+            (5, 2)
+        );
+        fs::copy(&previous, &restored).unwrap();
+        apply_increment(&restored, &increment).unwrap();
+        assert_eq!(fs::read(&restored).unwrap(), fs::read(&current).unwrap());
+
+        // Shrinking truncates the copy
+        database(&current, 3, |n| n as u8);
+        assert_eq!(
+            write_increment(&previous, &current, &increment).unwrap(),
+            (3, 0)
+        );
+        fs::copy(&previous, &restored).unwrap();
+        apply_increment(&restored, &increment).unwrap();
+        assert_eq!(fs::read(&restored).unwrap(), fs::read(&current).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
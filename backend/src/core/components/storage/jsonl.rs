@@ -0,0 +1,258 @@
+//! Streaming JSONL exports
+//!
+//! With `format: "jsonl"`, `export_database` writes one row per line
+//! (`{"table":"ideas","row":{...}}`) to `export_<ts>.jsonl`, reading each
+//! table in chunks of [`CHUNK_ROWS`] rows, so memory use doesn't grow with
+//! the dataset. Next to it, `export_<ts>.manifest.json` holds what the JSON
+//! export keeps in its header (version, schema version, groups and range)
+//! and the row count of every table. Importing either file streams the rows
+//! back line by line; with the manifest, row counts are checked against it,
+//! so a truncated file is reported.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use sea_orm::sea_query::{Alias, Expr, Order};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DatabaseTransaction};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tracing::{info, warn};
+
+use super::compression::open_backup;
+use super::export::{DateRange, ExportGroup};
+use super::import::RowImporter;
+use super::tables::ExportTable;
+use crate::core::components::errors::AppError;
+
+pub(crate) const JSONL_SUFFIX: &str = ".jsonl";
+pub(crate) const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// Rows read from the database per query
+const CHUNK_ROWS: u64 = 1000;
+
+/// Header of a JSONL export, written next to its rows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub version: String,
+    pub exported_at: String,
+    pub schema_version: Option<String>,
+    pub groups: Vec<ExportGroup>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// File name of the rows, in the manifest's directory
+    pub data_file: String,
+    pub encrypted: bool,
+    /// Rows written per table
+    pub tables: BTreeMap<String, usize>,
+}
+
+impl ExportManifest {
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let raw = fs::read_to_string(path)
+            .map_err(|e| AppError::file_operation("read", path.to_string_lossy(), e))?;
+        serde_json::from_str(&raw).map_err(|e| {
+            AppError::validation("import_file", format!("Invalid export manifest: {}", e))
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        let raw = serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::other(format!("Failed to serialize export manifest: {}", e)))?;
+        fs::write(path, raw)
+            .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))
+    }
+}
+
+/// One line of a JSONL export
+#[derive(Debug, Deserialize)]
+struct Line {
+    table: String,
+    row: JsonValue,
+}
+
+/// Stream the rows of `tables` in `range` to `path`; returns the rows
+/// written per table
+pub(super) async fn write_rows(
+    db: &DatabaseConnection,
+    path: &Path,
+    tables: &[ExportTable],
+    range: &DateRange,
+) -> Result<BTreeMap<String, usize>, AppError> {
+    let written = stream_tables(db, path, tables, range).await;
+    if written.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    written
+}
+
+async fn stream_tables(
+    db: &DatabaseConnection,
+    path: &Path,
+    tables: &[ExportTable],
+    range: &DateRange,
+) -> Result<BTreeMap<String, usize>, AppError> {
+    let write_err = |e| AppError::file_operation("write", path.to_string_lossy(), e);
+    let mut out = BufWriter::new(File::create(path).map_err(write_err)?);
+    let mut counts = BTreeMap::new();
+    for table in tables {
+        let mut count = 0;
+        // Keyset pagination on rowid, so each chunk is an index seek
+        let mut after = 0i64;
+        loop {
+            let mut query = table.select(range);
+            query
+                .and_where(Expr::col(Alias::new("rowid")).gt(after))
+                .order_by(Alias::new("rowid"), Order::Asc)
+                .limit(CHUNK_ROWS);
+            let rows = db
+                .query_all(db.get_database_backend().build(&query))
+                .await
+                .map_err(|e| {
+                    AppError::database(format!("Failed to export {}: {}", table.name, e))
+                })?;
+            for row in &rows {
+                after = row.try_get("", "rid")?;
+                // `json_object` output is already compact JSON
+                let json: String = row.try_get("", "row")?;
+                writeln!(out, "{{\"table\":\"{}\",\"row\":{}}}", table.name, json)
+                    .map_err(write_err)?;
+            }
+            count += rows.len();
+            if (rows.len() as u64) < CHUNK_ROWS {
+                break;
+            }
+        }
+        counts.insert(table.name.clone(), count);
+    }
+    out.flush().map_err(write_err)?;
+    Ok(counts)
+}
+
+/// Import the rows of the JSONL file at `path` line by line, checking the
+/// row counts against `manifest` if there is one
+pub(super) async fn import_rows(
+    txn: &DatabaseTransaction,
+    path: &Path,
+    manifest: Option<&ExportManifest>,
+    importer: &mut RowImporter,
+) -> Result<(), AppError> {
+    let read_err = |e| AppError::file_operation("read", path.to_string_lossy(), e);
+    let reader = BufReader::new(open_backup(path).map_err(read_err)?);
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line.map_err(read_err)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line: Line = serde_json::from_str(&line).map_err(|e| {
+            AppError::validation(
+                "import_file",
+                format!("Invalid JSONL on line {}: {}", number + 1, e),
+            )
+        })?;
+        importer.import(txn, &line.table, &line.row).await;
+        *counts.entry(line.table).or_default() += 1;
+    }
+    info!(tables = ?counts, "Imported JSONL rows");
+
+    if let Some(manifest) = manifest {
+        for (table, expected) in &manifest.tables {
+            let found = counts.get(table).copied().unwrap_or(0);
+            if found != *expected {
+                warn!(table = %table, expected, found, "Export file doesn't match its manifest");
+                importer.summary.errors.push(format!(
+                    "{}: manifest lists {} rows, file has {}",
+                    table, expected, found
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use crate::core::components::storage::import::ImportOptions;
+    use sea_orm::{Statement, TransactionTrait};
+
+    async fn titles<C: ConnectionTrait>(db: &C) -> Vec<String> {
+        db.query_all(Statement::from_string(
+            db.get_database_backend(),
+            "SELECT title FROM writings ORDER BY id".to_string(),
+        ))
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| r.try_get("", "title").unwrap())
+        .collect()
+    }
+
+    fn manifest(tables: BTreeMap<String, usize>) -> ExportManifest {
+        ExportManifest {
+            version: "2.0".to_string(),
+            exported_at: "2025-01-01T00:00:00Z".to_string(),
+            schema_version: None,
+            groups: Vec::new(),
+            since: None,
+            until: None,
+            data_file: "export.jsonl".to_string(),
+            encrypted: false,
+            tables,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("cockpit-jsonl-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (path, truncated) = (dir.join("export.jsonl"), dir.join("truncated.jsonl"));
+
+        let source = test_db().await;
+        source
+            .execute_unprepared(
+                "INSERT INTO writings (slug, title) VALUES \
+                 ('one', 'One'), ('two', 'Two'), ('three', 'Three')",
+            )
+            .await
+            .unwrap();
+        let tables = [ExportTable::named("writings").unwrap()];
+        let counts = write_rows(&source, &path, &tables, &DateRange::default())
+            .await
+            .unwrap();
+        assert_eq!(counts["writings"], 3);
+        let manifest = manifest(counts);
+
+        let target = test_db().await;
+        let txn = target.begin().await.unwrap();
+        let mut importer = RowImporter::new(ImportOptions::default());
+        import_rows(&txn, &path, Some(&manifest), &mut importer)
+            .await
+            .unwrap();
+        assert!(importer.summary.errors.is_empty());
+        assert_eq!(importer.summary.records_added, 3);
+        assert_eq!(titles(&txn).await, ["One", "Two", "Three"]);
+        txn.rollback().await.unwrap();
+
+        // A file cut short imports what it has, but no longer matches
+        let raw = fs::read_to_string(&path).unwrap();
+        let lines: Vec<_> = raw.lines().collect();
+        fs::write(&truncated, lines[..2].join("\n")).unwrap();
+        let txn = target.begin().await.unwrap();
+        let mut importer = RowImporter::new(ImportOptions::default());
+        import_rows(&txn, &truncated, Some(&manifest), &mut importer)
+            .await
+            .unwrap();
+        assert_eq!(importer.summary.records_added, 2);
+        assert_eq!(
+            importer.summary.errors,
+            ["writings: manifest lists 3 rows, file has 2"]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
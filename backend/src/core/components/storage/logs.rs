@@ -15,7 +15,7 @@ use crate::core::components::errors::AppError;
 use super::cleanup::CleanupSummary;
 
 /// Log entry structure
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LogEntry {
     pub timestamp: String,
@@ -26,7 +26,7 @@ pub struct LogEntry {
 }
 
 /// Log statistics
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LogStats {
     pub total_count: usize,
@@ -38,6 +38,96 @@ pub struct LogStats {
     pub last_30d_count: usize,
 }
 
+const LEVELS: [&str; 5] = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+
+/// Keys of a JSON log line that become `LogEntry` fields of their own
+const ENTRY_KEYS: [&str; 5] = ["timestamp", "level", "target", "message", "msg"];
+
+/// Parse one log line in either format the logger writes
+///
+/// - ND-JSON, flat (`{"timestamp", "level", "target", "message", ...fields}`)
+///   or with event fields nested under `fields`
+/// - Text: `2025-12-12T19:15:18.764156Z  INFO span{a=1}: target: message`
+///
+/// Returns `None` for lines that are neither, such as wrapped messages.
+pub(crate) fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        let json: JsonValue = serde_json::from_str(line).ok()?;
+        return parse_json_line(json.as_object()?);
+    }
+    parse_text_line(line)
+}
+
+fn parse_json_line(json: &serde_json::Map<String, JsonValue>) -> Option<LogEntry> {
+    let str_of = |key: &str| json.get(key).and_then(|v| v.as_str());
+    let nested = json.get("fields").and_then(|f| f.as_object());
+    let message = str_of("message")
+        .or_else(|| str_of("msg"))
+        .or_else(|| nested?.get("message")?.as_str())
+        .or_else(|| nested?.get("msg")?.as_str())
+        .unwrap_or("");
+
+    let mut fields = serde_json::Map::new();
+    for (k, v) in json {
+        if k == "fields" && v.is_object() {
+            continue;
+        }
+        if !ENTRY_KEYS.contains(&k.as_str()) {
+            fields.insert(k.clone(), v.clone());
+        }
+    }
+    for (k, v) in nested.into_iter().flatten() {
+        if k != "message" && k != "msg" {
+            fields.insert(k.clone(), v.clone());
+        }
+    }
+
+    Some(LogEntry {
+        timestamp: str_of("timestamp").unwrap_or("").to_string(),
+        level: str_of("level").unwrap_or("INFO").to_uppercase(),
+        target: str_of("target").unwrap_or("").to_string(),
+        message: message.to_string(),
+        fields: (!fields.is_empty()).then_some(fields),
+    })
+}
+
+fn parse_text_line(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.split_once(char::is_whitespace)?;
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if !LEVELS.contains(&level) {
+        return None;
+    }
+
+    // Spans come first ("request{request_id=..}:sync_source{..}: "), then the target
+    let mut rest = rest.trim_start();
+    let mut spans = None;
+    if let Some((head, tail)) = rest.split_once(": ") {
+        if head.contains('{') {
+            spans = Some(head.to_string());
+            rest = tail;
+        }
+    }
+    let (target, message) = match rest.split_once(": ") {
+        Some((target, message)) if !target.contains(char::is_whitespace) => (target, message),
+        _ => ("", rest),
+    };
+
+    let fields = spans.map(|spans| {
+        let mut fields = serde_json::Map::new();
+        fields.insert("spans".to_string(), JsonValue::String(spans));
+        fields
+    });
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        target: target.to_string(),
+        message: message.trim().to_string(),
+        fields,
+    })
+}
+
 /// Get logs with optional filters
 #[instrument(skip(config))]
 pub fn get_logs(
@@ -78,86 +168,21 @@ pub fn get_logs(
         let reader = BufReader::new(file);
         
         for line in reader.lines().flatten() {
-            // Try to parse as JSON first
-            if let Ok(json) = serde_json::from_str::<JsonValue>(&line) {
-                // Extract fields from JSON
-                let timestamp = json.get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                
-                let level = json.get("level")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("INFO")
-                    .to_string();
-                
-                // Apply level filter
-                if let Some(ref filter) = level_filter {
-                    if !level.eq_ignore_ascii_case(filter) {
-                        continue;
-                    }
-                }
-                
-                let target = json.get("target")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                
-                // Handle message in both formats:
-                // - Top-level: {"message": "text"}
-                // - Nested: {"fields": {"message": "text"}}
-                let message = json.get("message")
-                    .or_else(|| json.get("msg"))
-                    .or_else(|| json.get("fields").and_then(|f| f.get("message")))
-                    .or_else(|| json.get("fields").and_then(|f| f.get("msg")))
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                
-                let mut fields = serde_json::Map::new();
-                if let Some(obj) = json.as_object() {
-                    for (k, v) in obj {
-                        if !["timestamp", "level", "target", "message", "msg"].contains(&k.as_str()) {
-                            fields.insert(k.clone(), v.clone());
-                        }
-                    }
-                }
-                
-                all_entries.push(LogEntry {
-                    timestamp,
-                    level,
-                    target,
-                    message,
-                    fields: if fields.is_empty() { None } else { Some(fields) },
-                });
-            } else {
-                // Parse plain text format: "2025-12-12T19:15:18.764156Z  INFO backend::target: message"
-                let parts: Vec<&str> = line.splitn(4, ' ').collect();
-                if parts.len() >= 4 {
-                    let timestamp = parts[0].trim().to_string();
-                    let level = parts[1].trim().to_string();
-                    let target = parts[2].trim().trim_end_matches(':').to_string();
-                    let message = parts[3].trim().to_string();
-                    
-                    // Apply level filter
-                    if let Some(ref filter) = level_filter {
-                        if !level.eq_ignore_ascii_case(filter) {
-                            continue;
-                        }
-                    }
-                    
-                    all_entries.push(LogEntry {
-                        timestamp,
-                        level,
-                        target,
-                        message,
-                        fields: None,
-                    });
+            let Some(entry) = parse_log_line(&line) else {
+                continue;
+            };
+
+            // Apply level filter
+            if let Some(ref filter) = level_filter {
+                if !entry.level.eq_ignore_ascii_case(filter) {
+                    continue;
                 }
             }
+            
+            all_entries.push(entry);
         }
     }
-    
+
     // Sort by timestamp descending (newest first)
     all_entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
     
@@ -227,29 +252,11 @@ pub fn get_log_stats(config: &StorageConfig) -> Result<LogStats, AppError> {
         let reader = BufReader::new(file);
         
         for line in reader.lines().flatten() {
-            let (level, timestamp_str) = if let Ok(json) = serde_json::from_str::<JsonValue>(&line) {
-                // JSON format
-                let level = json.get("level")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("INFO")
-                    .to_uppercase();
-                let ts = json.get("timestamp")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-                (level, ts)
-            } else {
-                // Plain text format: "2025-12-12T19:15:18.764156Z  INFO backend::target: message"
-                let parts: Vec<&str> = line.splitn(4, ' ').collect();
-                if parts.len() >= 4 {
-                    let ts = parts[0].trim().to_string();
-                    let level = parts[1].trim().to_uppercase();
-                    (level, ts)
-                } else {
-                    continue;
-                }
+            let Some(entry) = parse_log_line(&line) else {
+                continue;
             };
-            
+            let (level, timestamp_str) = (entry.level, entry.timestamp);
+
             stats.total_count += 1;
             
             // Count by level
@@ -386,3 +393,45 @@ pub fn clear_logs(config: &StorageConfig, level_filter: Option<String>) -> Resul
         retention_days: 0,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_line_formats() {
+        let flat = parse_log_line(
+            r#"{"timestamp":"2025-12-12T19:15:18Z","level":"WARN","message":"Task failed","task_id":3,"target":"scheduler","span":{"name":"run"}}"#,
+        )
+        .unwrap();
+        assert_eq!(flat.level, "WARN");
+        assert_eq!(flat.target, "scheduler");
+        assert_eq!(flat.message, "Task failed");
+        let fields = flat.fields.unwrap();
+        assert_eq!(fields["task_id"], 3);
+        assert_eq!(fields["span"]["name"], "run");
+
+        let nested = parse_log_line(
+            r#"{"timestamp":"2025-12-12T19:15:18Z","level":"INFO","fields":{"message":"Synced","count":2},"target":"research"}"#,
+        )
+        .unwrap();
+        assert_eq!(nested.message, "Synced");
+        assert_eq!(nested.fields.unwrap()["count"], 2);
+
+        let text = parse_log_line(
+            "2025-12-12T19:15:18.764156Z  INFO request{request_id=ab}: cockpit::bridge: Handled: list_ideas",
+        )
+        .unwrap();
+        assert_eq!(text.level, "INFO");
+        assert_eq!(text.target, "cockpit::bridge");
+        assert_eq!(text.message, "Handled: list_ideas");
+        assert_eq!(text.fields.unwrap()["spans"], "request{request_id=ab}");
+
+        let plain = parse_log_line("2025-12-12T19:15:18Z ERROR scheduler: Task failed").unwrap();
+        assert_eq!(plain.level, "ERROR");
+        assert_eq!(plain.target, "scheduler");
+
+        assert!(parse_log_line("    at src/main.rs:10").is_none());
+        assert!(parse_log_line("").is_none());
+    }
+}
@@ -3,15 +3,33 @@
 //! Provides centralized storage operations organized by responsibility:
 //! - **stats**: Storage statistics and monitoring
 //! - **backup**: Database backup and restore operations
+//! - **pg_dump**: Backups and exports of PostgreSQL databases
+//! - **incremental**: Page diffs and chain manifests for incremental backups
+//! - **compression**: zstd/gzip compression of backup files
+//! - **encryption**: Encryption of backup and export files
+//! - **verify**: Restorability checks for backups
 //! - **cleanup**: Cleanup policies for logs and old data
 //! - **logs**: Log reading, statistics, and export
-//! - **export**: Data export/import to JSON
+//! - **export**: Data export to JSON
+//! - **jsonl**: Streaming JSONL exports and imports
+//! - **import**: Import of exports, with merge strategies and dry runs
+//! - **tables**: Entities of the tables exports cover
+//! - **uploads**: Files staged over HTTP for import commands
 
 pub mod stats;
 pub mod backup;
+pub mod pg_dump;
+pub mod incremental;
+pub mod compression;
+pub mod encryption;
+pub mod verify;
 pub mod cleanup;
 pub mod logs;
 pub mod export;
+pub mod jsonl;
+pub mod import;
+pub mod tables;
+pub mod uploads;
 
 // Re-export commonly used types and functions
 pub use stats::{
@@ -22,7 +40,11 @@ pub use stats::{
 
 pub use backup::{
     BackupInfo,
+    CreateBackupInput,
+    MigrationSet,
+    record_migration_set,
     backup_database,
+    backup_database_incremental,
     restore_database,
     list_backups,
     delete_backup,
@@ -32,6 +54,7 @@ pub use cleanup::{
     CleanupSummary,
     cleanup_old_logs,
     cleanup_old_news,
+    run_storage_cleanup_task,
 };
 
 pub use logs::{
@@ -43,9 +66,29 @@ pub use logs::{
     clear_logs,
 };
 
+pub use verify::{
+    BackupVerification,
+    verify_backup,
+};
+
 pub use export::{
+    ExportCounts,
+    ExportFormat,
+    ExportGroup,
     ExportInfo,
-    ImportSummary,
+    ExportInput,
     export_data,
+};
+
+pub use import::{
+    ImportOptions,
+    ImportStrategy,
+    ImportSummary,
     import_data,
 };
+
+pub use uploads::{
+    UploadDto,
+    resolve_upload,
+    stage_upload,
+};
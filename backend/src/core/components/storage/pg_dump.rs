@@ -0,0 +1,175 @@
+//! PostgreSQL backups through `pg_dump` and `pg_restore`
+//!
+//! With a PostgreSQL database, backups are `pg_dump` custom-format archives
+//! (`.dump`) and exports plain SQL, since VACUUM INTO and the JSON exports
+//! are SQLite-only. The client tools must be on the PATH; the password is
+//! passed through `PGPASSWORD` so it never shows up in the process list.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Output;
+use std::time::Duration;
+
+use reqwest::Url;
+use tokio::process::Command;
+
+use crate::core::components::errors::{AppError, AppResult};
+
+/// Dumps and restores of a large database take a while
+const DUMP_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Write a custom-format archive of the whole database to `out`
+pub async fn dump_archive(url: &str, out: &Path) -> AppResult<()> {
+    run(
+        "pg_dump",
+        url,
+        &["--format=custom", "--no-owner", "--file"],
+        Some(out),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Write the rows of `tables` to `out` as SQL, for loading with `psql`
+pub async fn dump_data(url: &str, tables: &[String], out: &Path) -> AppResult<()> {
+    let mut args: Vec<String> = vec![
+        "--data-only".into(),
+        "--format=plain".into(),
+        "--no-owner".into(),
+    ];
+    args.extend(tables.iter().map(|t| format!("--table=\"{}\"", t)));
+    args.push("--file".into());
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    run("pg_dump", url, &args, Some(out)).await?;
+    Ok(())
+}
+
+/// Replace the database's objects with those of the archive at `archive`,
+/// all or nothing
+pub async fn restore_archive(url: &str, archive: &Path) -> AppResult<()> {
+    run(
+        "pg_restore",
+        url,
+        &[
+            "--clean",
+            "--if-exists",
+            "--no-owner",
+            "--single-transaction",
+        ],
+        Some(archive),
+    )
+    .await?;
+    Ok(())
+}
+
+/// The table of contents of the archive at `archive`; fails if it isn't one
+pub async fn list_archive(archive: &Path) -> AppResult<String> {
+    let output = tool(
+        "pg_restore",
+        &[OsStr::new("--list"), archive.as_os_str()],
+        None,
+    )
+    .await?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The rows of `table` in the archive at `archive`, tab-separated as in
+/// `COPY ... FROM stdin`
+pub async fn archive_rows(archive: &Path, table: &str) -> AppResult<Vec<String>> {
+    let table = format!("--table={}", table);
+    let output = tool(
+        "pg_restore",
+        &[
+            OsStr::new("--data-only"),
+            OsStr::new(&table),
+            OsStr::new("--file=-"),
+            archive.as_os_str(),
+        ],
+        None,
+    )
+    .await?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip_while(|line| !line.starts_with("COPY "))
+        .skip(1)
+        .take_while(|line| *line != "\\.")
+        .map(str::to_string)
+        .collect())
+}
+
+/// Tables whose rows the archive's table of contents lists
+pub fn archive_tables(listing: &str) -> Vec<String> {
+    let mut tables: Vec<String> = listing
+        .lines()
+        .filter(|line| !line.starts_with(';'))
+        .filter_map(|line| {
+            let (_, entry) = line.split_once(" TABLE DATA ")?;
+            // "<schema> <table> <owner>"
+            entry.split_whitespace().nth(1).map(str::to_string)
+        })
+        .collect();
+    tables.sort();
+    tables
+}
+
+/// Run `program` against the database at `url`, with `path` as its last
+/// argument
+async fn run(program: &str, url: &str, args: &[&str], path: Option<&Path>) -> AppResult<Output> {
+    let mut url =
+        Url::parse(url).map_err(|e| AppError::other(format!("Invalid database URL: {}", e)))?;
+    let password = url.password().map(str::to_string);
+    let _ = url.set_password(None);
+    let dbname = format!("--dbname={}", url);
+
+    let mut all = vec![OsStr::new(&dbname)];
+    all.extend(args.iter().map(OsStr::new));
+    if let Some(path) = path {
+        all.push(path.as_os_str());
+    }
+    tool(program, &all, password.as_deref()).await
+}
+
+/// Run a PostgreSQL client tool, failing on a non-zero exit
+async fn tool(program: &str, args: &[&OsStr], password: Option<&str>) -> AppResult<Output> {
+    let mut command = Command::new(program);
+    command.args(args).kill_on_drop(true);
+    if let Some(password) = password {
+        command.env("PGPASSWORD", password);
+    }
+    let output = tokio::time::timeout(DUMP_TIMEOUT, command.output())
+        .await
+        .map_err(|_| AppError::other(format!("{} timed out", program)))?
+        .map_err(|e| AppError::other(format!("Failed to run {}: {}", program, e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("no output");
+        return Err(AppError::other(format!(
+            "{} failed ({}): {}",
+            program, output.status, reason
+        )));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_tables() {
+        let listing = "\
+;
+; Archive created at 2026-01-01 00:00:00 UTC
+;
+215; 1259 16390 TABLE public ideas postgres
+3390; 0 16390 TABLE DATA public ideas postgres
+3391; 0 16401 TABLE DATA public app_settings postgres
+3210; 2606 16410 CONSTRAINT public ideas ideas_pkey postgres
+";
+        assert_eq!(archive_tables(listing), ["app_settings", "ideas"]);
+    }
+}
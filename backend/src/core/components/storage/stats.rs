@@ -11,7 +11,7 @@ use crate::core::components::config::StorageConfig;
 use crate::core::components::errors::AppError;
 
 /// Storage statistics
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageStats {
     pub total_bytes: u64,
@@ -20,6 +20,7 @@ pub struct StorageStats {
     pub cache_bytes: u64,
     pub backup_bytes: u64,
     pub export_bytes: u64,
+    pub media_bytes: u64,
 }
 
 impl StorageStats {
@@ -92,8 +93,10 @@ pub fn get_storage_stats(config: &StorageConfig) -> Result<StorageStats, AppErro
     let cache_bytes = calculate_dir_size(&config.cache_dir)?;
     let backup_bytes = calculate_dir_size(&config.backup_dir)?;
     let export_bytes = calculate_dir_size(&config.export_dir)?;
+    let media_bytes = calculate_dir_size(&config.media_dir)?;
     
-    let total_bytes = data_bytes + logs_bytes + cache_bytes + backup_bytes + export_bytes;
+    let total_bytes =
+        data_bytes + logs_bytes + cache_bytes + backup_bytes + export_bytes + media_bytes;
     
     Ok(StorageStats {
         total_bytes,
@@ -102,6 +105,7 @@ pub fn get_storage_stats(config: &StorageConfig) -> Result<StorageStats, AppErro
         cache_bytes,
         backup_bytes,
         export_bytes,
+        media_bytes,
     })
 }
 
@@ -116,6 +120,7 @@ pub fn log_storage_stats(config: &StorageConfig) -> Result<(), AppError> {
     info!("  Cache: {:.2} MB", stats.cache_bytes as f64 / 1_048_576.0);
     info!("  Backups: {:.2} MB", stats.backup_bytes as f64 / 1_048_576.0);
     info!("  Exports: {:.2} MB", stats.export_bytes as f64 / 1_048_576.0);
+    info!("  Media: {:.2} MB", stats.media_bytes as f64 / 1_048_576.0);
     
     if let Some(max_gb) = config.max_total_size_gb {
         let usage_percent = (stats.total_gb() / max_gb as f64) * 100.0;
@@ -140,6 +145,7 @@ pub fn initialize_storage(config: &super::super::config::AppConfig) -> Result<()
     fs::create_dir_all(&config.storage.cache_dir)?;
     fs::create_dir_all(&config.storage.backup_dir)?;
     fs::create_dir_all(&config.storage.export_dir)?;
+    fs::create_dir_all(&config.storage.media_dir)?;
     
     // Log storage stats on initialization
     log_storage_stats(&config.storage)?;
@@ -0,0 +1,204 @@
+//! Tables covered by exports
+//!
+//! Exports and imports reach every table through its SeaORM entity: the
+//! table and column names, and the type an imported value must have, come
+//! from the entity, and statements are built with the query builder, with
+//! values bound as parameters. A migration that changes a table without its
+//! entity fails `test_entities_match_schema` instead of leaving columns out
+//! of exports.
+
+use sea_orm::sea_query::{Alias, Expr, Func, Query, SelectStatement, SimpleExpr};
+use sea_orm::{ColumnTrait, ColumnType, EntityTrait, IdenStatic, Iterable, Value};
+use serde_json::Value as JsonValue;
+
+use super::export::{DateRange, ExportGroup};
+
+/// How values of a column are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ColumnKind {
+    Bool,
+    Integer,
+    Real,
+    Text,
+    /// Encrypted secrets, which are never exported
+    Blob,
+}
+
+impl ColumnKind {
+    fn of(column_type: &ColumnType) -> Self {
+        match column_type {
+            ColumnType::Boolean => ColumnKind::Bool,
+            ColumnType::TinyInteger
+            | ColumnType::SmallInteger
+            | ColumnType::Integer
+            | ColumnType::BigInteger
+            | ColumnType::TinyUnsigned
+            | ColumnType::SmallUnsigned
+            | ColumnType::Unsigned
+            | ColumnType::BigUnsigned => ColumnKind::Integer,
+            ColumnType::Float | ColumnType::Double | ColumnType::Decimal(_) => ColumnKind::Real,
+            ColumnType::Blob | ColumnType::Binary(_) | ColumnType::VarBinary(_) => ColumnKind::Blob,
+            // Strings, enums, JSON and dates, which SQLite stores as text
+            _ => ColumnKind::Text,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ColumnKind::Bool => "a boolean",
+            ColumnKind::Integer => "an integer",
+            ColumnKind::Real => "a number",
+            ColumnKind::Text => "text",
+            ColumnKind::Blob => "binary data",
+        }
+    }
+
+    /// An exported value as a parameter for a column of this kind
+    pub fn value(self, value: &JsonValue) -> Result<Value, String> {
+        let mismatch = || format!("expected {}, found {}", self.name(), value);
+        Ok(match (self, value) {
+            (ColumnKind::Bool, JsonValue::Null) => Value::Bool(None),
+            (ColumnKind::Integer, JsonValue::Null) => Value::BigInt(None),
+            (ColumnKind::Real, JsonValue::Null) => Value::Double(None),
+            (ColumnKind::Text, JsonValue::Null) => Value::String(None),
+            (ColumnKind::Bool, JsonValue::Bool(b)) => (*b).into(),
+            // SQLite has no booleans; `json_object` gives them as 0 and 1
+            (ColumnKind::Bool, JsonValue::Number(n)) => match n.as_i64() {
+                Some(0) => false.into(),
+                Some(1) => true.into(),
+                _ => return Err(mismatch()),
+            },
+            (ColumnKind::Integer, JsonValue::Bool(b)) => i64::from(*b).into(),
+            (ColumnKind::Integer, JsonValue::Number(n)) => n.as_i64().ok_or_else(mismatch)?.into(),
+            (ColumnKind::Real, JsonValue::Number(n)) => n.as_f64().ok_or_else(mismatch)?.into(),
+            (ColumnKind::Text, JsonValue::String(s)) => s.clone().into(),
+            (ColumnKind::Text, JsonValue::Number(n)) => n.to_string().into(),
+            // JSON columns
+            (ColumnKind::Text, JsonValue::Array(_) | JsonValue::Object(_)) => {
+                value.to_string().into()
+            }
+            _ => return Err(mismatch()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct TableColumn {
+    pub name: String,
+    pub kind: ColumnKind,
+}
+
+/// A table exports cover, described by its entity
+#[derive(Debug, Clone)]
+pub(super) struct ExportTable {
+    pub name: String,
+    /// Column a date range applies to; tables without one are exported whole
+    pub date_column: Option<String>,
+    pub columns: Vec<TableColumn>,
+}
+
+impl ExportTable {
+    pub fn of<E: EntityTrait>(date_column: Option<E::Column>) -> Self {
+        Self {
+            name: E::default().table_name().to_string(),
+            date_column: date_column.map(|c| c.as_str().to_string()),
+            columns: E::Column::iter()
+                .map(|c| TableColumn {
+                    name: c.as_str().to_string(),
+                    kind: ColumnKind::of(c.def().get_column_type()),
+                })
+                .collect(),
+        }
+    }
+
+    /// The exported table called `name`
+    pub fn named(name: &str) -> Option<Self> {
+        ExportGroup::ALL
+            .iter()
+            .flat_map(|g| g.tables())
+            .find(|t| t.name == name)
+    }
+
+    /// The column called `name`, unless it is a blob
+    pub fn column(&self, name: &str) -> Option<&TableColumn> {
+        self.columns
+            .iter()
+            .find(|c| c.name == name && c.kind != ColumnKind::Blob)
+    }
+
+    /// A query for the rows as JSON objects (`row`) with their rowid (`rid`),
+    /// limited to `range`; blob columns are left out
+    pub fn select(&self, range: &DateRange) -> SelectStatement {
+        let fields = self
+            .columns
+            .iter()
+            .filter(|c| c.kind != ColumnKind::Blob)
+            .flat_map(|c| -> [SimpleExpr; 2] {
+                [
+                    Expr::val(c.name.as_str()).into(),
+                    Expr::col(Alias::new(&c.name)).into(),
+                ]
+            });
+        let mut query = Query::select();
+        query
+            .expr_as(Expr::col(Alias::new("rowid")), Alias::new("rid"))
+            .expr_as(
+                Func::cust(Alias::new("json_object")).args(fields),
+                Alias::new("row"),
+            )
+            .from(Alias::new(&self.name));
+        if let Some(column) = &self.date_column {
+            range.restrict(&mut query, column);
+        }
+        query
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use sea_orm::{ConnectionTrait, Statement};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_entities_match_schema() {
+        let db = test_db().await;
+
+        for table in ExportGroup::ALL.iter().flat_map(|g| g.tables()) {
+            let mut columns: Vec<String> = db
+                .query_all(Statement::from_string(
+                    db.get_database_backend(),
+                    format!("PRAGMA table_info(\"{}\")", table.name),
+                ))
+                .await
+                .unwrap()
+                .iter()
+                .map(|row| row.try_get("", "name").unwrap())
+                .collect();
+            let mut entity_columns: Vec<_> = table.columns.iter().map(|c| c.name.clone()).collect();
+            columns.sort();
+            entity_columns.sort();
+            assert_eq!(entity_columns, columns, "{} entity", table.name);
+            if let Some(date_column) = &table.date_column {
+                assert!(table.column(date_column).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_column_values() {
+        assert_eq!(ColumnKind::Bool.value(&json!(1)), Ok(true.into()));
+        assert_eq!(ColumnKind::Integer.value(&json!(7)), Ok(7i64.into()));
+        assert_eq!(
+            ColumnKind::Text.value(&json!(null)),
+            Ok(Value::String(None))
+        );
+        assert_eq!(
+            ColumnKind::Text.value(&json!(["a"])),
+            Ok(r#"["a"]"#.to_string().into())
+        );
+        assert!(ColumnKind::Integer.value(&json!("7")).is_err());
+        assert!(ColumnKind::Bool.value(&json!(2)).is_err());
+    }
+}
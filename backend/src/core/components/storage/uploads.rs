@@ -0,0 +1,188 @@
+//! Staged uploads for import commands
+//!
+//! Importers read from a server-side path, which a remote frontend can't
+//! provide. `POST /upload` streams the file into the import staging dir
+//! instead and returns a handle; import commands accept that handle in place
+//! of a path. Each upload lives in its own `<handle>/` folder under its
+//! original (sanitized) file name, and staged files are removed after a day.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::Utc;
+use futures_util::{pin_mut, Stream, StreamExt};
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+
+/// Staged uploads older than this are removed
+const UPLOAD_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadDto {
+    /// Pass as `upload_handle` to an import command
+    pub handle: String,
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub uploaded_at: String,
+}
+
+/// Keep only the final path component, with anything unusual replaced
+fn sanitize_file_name(name: &str) -> String {
+    let base = Path::new(name)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() {
+        "upload".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+fn is_valid_handle(handle: &str) -> bool {
+    handle.len() == 32
+        && handle
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Write an upload stream into the staging dir and return its handle
+pub async fn stage_upload<S, T, E>(
+    import_dir: &Path,
+    file_name: &str,
+    chunks: S,
+) -> AppResult<UploadDto>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]>,
+    E: Display,
+{
+    purge_expired_uploads(import_dir);
+
+    let handle = hex::encode(rand::random::<[u8; 16]>());
+    let file_name = sanitize_file_name(file_name);
+    let dir = import_dir.join(&handle);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| AppError::file_operation("create directory", dir.to_string_lossy(), e))?;
+    let path = dir.join(&file_name);
+
+    let written = write_chunks(&path, chunks).await;
+    let size_bytes = match written {
+        Ok(size) => size,
+        Err(e) => {
+            let _ = tokio::fs::remove_dir_all(&dir).await;
+            return Err(e);
+        }
+    };
+
+    info!(handle = %handle, file_name = %file_name, size_bytes, "Upload staged");
+    Ok(UploadDto {
+        handle,
+        file_name,
+        size_bytes,
+        uploaded_at: Utc::now().to_rfc3339(),
+    })
+}
+
+async fn write_chunks<S, T, E>(path: &Path, chunks: S) -> AppResult<u64>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: AsRef<[u8]>,
+    E: Display,
+{
+    let path_str = path.to_string_lossy();
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| AppError::file_operation("create", path_str.clone(), e))?;
+    let mut size = 0u64;
+    pin_mut!(chunks);
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|e| AppError::storage_operation("upload", e.to_string()))?;
+        let bytes = chunk.as_ref();
+        file.write_all(bytes)
+            .await
+            .map_err(|e| AppError::file_operation("write", path_str.clone(), e))?;
+        size += bytes.len() as u64;
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::file_operation("write", path_str.clone(), e))?;
+    Ok(size)
+}
+
+/// Path of a staged upload, for import commands given a handle
+pub fn resolve_upload(import_dir: &Path, handle: &str) -> AppResult<PathBuf> {
+    let unknown = || AppError::validation("upload_handle", "Unknown or expired upload");
+    if !is_valid_handle(handle) {
+        return Err(unknown());
+    }
+    let dir = import_dir.join(handle);
+    let entries = std::fs::read_dir(&dir).map_err(|_| unknown())?;
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| path.is_file())
+        .ok_or_else(unknown)
+}
+
+/// Remove staged uploads past their TTL
+fn purge_expired_uploads(import_dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(import_dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let expired = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > UPLOAD_TTL);
+        if !expired || !entry.path().is_dir() {
+            continue;
+        }
+        if let Err(e) = std::fs::remove_dir_all(entry.path()) {
+            warn!(path = %entry.path().display(), "Failed to remove expired upload: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name("export.json"), "export.json");
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(
+            sanitize_file_name("my export (1).json"),
+            "my_export__1_.json"
+        );
+        assert_eq!(sanitize_file_name(".."), "upload");
+        assert_eq!(sanitize_file_name(""), "upload");
+    }
+
+    #[test]
+    fn test_resolve_upload_rejects_bad_handles() {
+        let dir = std::env::temp_dir();
+        assert!(resolve_upload(&dir, "../secrets").is_err());
+        assert!(resolve_upload(&dir, "0123456789abcdef0123456789abcdef").is_err());
+    }
+}
@@ -0,0 +1,317 @@
+//! Backup verification
+//!
+//! `verify_backup` checks that a backup would restore cleanly without
+//! touching the live database: the backup (replayed or unpacked first if it
+//! is an increment, compressed or encrypted) is opened read-only, run
+//! through `PRAGMA integrity_check`, and its tables and applied migrations
+//! are compared with what this build expects. A PostgreSQL backup is read
+//! with `pg_restore` instead, which gives its tables and migrations but no
+//! row counts.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use sea_orm::{ConnectionTrait, DatabaseBackend, DatabaseConnection, Statement};
+use sea_orm_migration::MigratorTrait;
+use tracing::{info, instrument, warn};
+
+use super::backup::{backend_mismatch, is_dump, unpack_backup};
+use super::pg_dump;
+use crate::core::components::config::{DatabaseConfig, StorageConfig};
+use crate::core::components::db::encryption::file_options;
+use crate::core::components::errors::AppError;
+
+/// Rows of `integrity_check` output kept in the report
+const MAX_INTEGRITY_ERRORS: usize = 20;
+
+/// Result of checking a backup
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupVerification {
+    pub file_path: String,
+    /// Whether nothing below stands in the way of restoring it
+    pub restorable: bool,
+    /// `PRAGMA integrity_check` output, or `pg_restore`'s complaint about a
+    /// PostgreSQL backup; `["ok"]` for a sound file
+    pub integrity: Vec<String>,
+    /// Newest migration applied to the backup
+    pub migration_version: Option<String>,
+    /// Migrations the app will apply after restoring it
+    pub pending_migrations: usize,
+    /// Tables of the live database the backup doesn't have
+    pub missing_tables: Vec<String>,
+    pub row_counts: BTreeMap<String, i64>,
+    /// Why the backup isn't restorable
+    pub problems: Vec<String>,
+}
+
+async fn query_strings(db: &DatabaseConnection, sql: &str) -> Result<Vec<String>, AppError> {
+    let rows = db
+        .query_all(Statement::from_string(
+            db.get_database_backend(),
+            sql.to_string(),
+        ))
+        .await?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| row.try_get_by_index::<String>(0).ok())
+        .collect())
+}
+
+async fn table_names(db: &DatabaseConnection) -> Result<Vec<String>, AppError> {
+    let sql = match db.get_database_backend() {
+        DatabaseBackend::Postgres => {
+            "SELECT table_name::text FROM information_schema.tables \
+             WHERE table_schema = current_schema() AND table_type = 'BASE TABLE' \
+             ORDER BY table_name"
+        }
+        _ => {
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' \
+             ORDER BY name"
+        }
+    };
+    query_strings(db, sql).await
+}
+
+/// What a backup holds, as far as verification looks
+struct Contents {
+    integrity: Vec<String>,
+    tables: Vec<String>,
+    /// Applied migrations, oldest first
+    applied: Vec<String>,
+    row_counts: BTreeMap<String, i64>,
+    problems: Vec<String>,
+}
+
+/// Check the backup at `backup_path` against the live database `db`
+#[instrument(skip(db, database, storage_config))]
+pub async fn verify_backup(
+    db: &DatabaseConnection,
+    database: &DatabaseConfig,
+    backup_path: &str,
+    storage_config: &StorageConfig,
+) -> Result<BackupVerification, AppError> {
+    let requested = Path::new(backup_path);
+    if !requested.exists() {
+        return Err(AppError::validation("backup_path", "Backup file not found"));
+    }
+
+    let mismatch = backend_mismatch(backup_path, database);
+    let scratch = storage_config.data_dir.join("db.sql.verify_source");
+    let result = match unpack_backup(requested, &scratch, storage_config).await {
+        Ok(backup_file) => check(db, backup_file, backup_path, mismatch).await,
+        Err(e) => Err(e),
+    };
+    if scratch.exists() {
+        let _ = fs::remove_file(&scratch);
+    }
+    result
+}
+
+/// Check the unpacked `backup_file`; `mismatch` is why it can't be restored
+/// into this kind of database, if it can't
+async fn check(
+    db: &DatabaseConnection,
+    backup_file: &Path,
+    backup_path: &str,
+    mismatch: Option<&str>,
+) -> Result<BackupVerification, AppError> {
+    let mut contents = if is_dump(backup_path) {
+        read_dump(backup_file).await?
+    } else {
+        read_sqlite(backup_file).await?
+    };
+    contents.problems.extend(mismatch.map(str::to_string));
+    report(db, contents, backup_path).await
+}
+
+async fn read_sqlite(backup_file: &Path) -> Result<Contents, AppError> {
+    let backup = sea_orm::Database::connect(file_options(backup_file, true)?)
+        .await
+        .map_err(|_| AppError::validation("backup_file", "Invalid SQLite database file"))?;
+
+    let mut problems = Vec::new();
+    let integrity = match query_strings(&backup, "PRAGMA integrity_check").await {
+        Ok(rows) => rows.into_iter().take(MAX_INTEGRITY_ERRORS).collect(),
+        Err(e) => vec![e.to_string()],
+    };
+    if integrity != ["ok"] {
+        problems.push("Integrity check failed".to_string());
+    }
+
+    let tables = table_names(&backup).await.unwrap_or_default();
+    let mut applied = Vec::new();
+    if tables.iter().any(|t| t == "seaql_migrations") {
+        applied = query_strings(
+            &backup,
+            "SELECT version FROM seaql_migrations ORDER BY version",
+        )
+        .await
+        .unwrap_or_default();
+    }
+
+    let mut row_counts = BTreeMap::new();
+    for table in &tables {
+        let sql = format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\""));
+        match backup
+            .query_one(Statement::from_string(backup.get_database_backend(), sql))
+            .await
+        {
+            Ok(Some(row)) => {
+                row_counts.insert(table.clone(), row.try_get_by_index::<i64>(0)?);
+            }
+            Ok(None) => {}
+            Err(e) => problems.push(format!("Table {} is unreadable: {}", table, e)),
+        }
+    }
+    let _ = backup.close().await;
+
+    Ok(Contents {
+        integrity,
+        tables,
+        applied,
+        row_counts,
+        problems,
+    })
+}
+
+/// Read a `pg_dump` archive's table of contents and migration history
+async fn read_dump(backup_file: &Path) -> Result<Contents, AppError> {
+    let listing = pg_dump::list_archive(backup_file)
+        .await
+        .map_err(|_| AppError::validation("backup_file", "Invalid PostgreSQL backup file"))?;
+    let tables = pg_dump::archive_tables(&listing);
+
+    let (mut integrity, mut applied) = (vec!["ok".to_string()], Vec::new());
+    let mut problems = Vec::new();
+    if tables.iter().any(|t| t == "seaql_migrations") {
+        match pg_dump::archive_rows(backup_file, "seaql_migrations").await {
+            Ok(rows) => {
+                applied = rows
+                    .iter()
+                    .filter_map(|row| row.split('\t').next())
+                    .map(str::to_string)
+                    .collect();
+                applied.sort();
+            }
+            Err(e) => {
+                integrity = vec![e.to_string()];
+                problems.push("Integrity check failed".to_string());
+            }
+        }
+    }
+
+    Ok(Contents {
+        integrity,
+        tables,
+        applied,
+        row_counts: BTreeMap::new(),
+        problems,
+    })
+}
+
+/// Compare what a backup holds with the live database `db`
+async fn report(
+    db: &DatabaseConnection,
+    contents: Contents,
+    backup_path: &str,
+) -> Result<BackupVerification, AppError> {
+    let Contents {
+        integrity,
+        tables,
+        applied,
+        row_counts,
+        mut problems,
+    } = contents;
+    if !tables.iter().any(|t| t == "seaql_migrations") {
+        problems.push("No migration history; not a Cockpit database".to_string());
+    }
+    let known: Vec<_> = migration::Migrator::migrations()
+        .iter()
+        .map(|m| m.name().to_string())
+        .collect();
+    let unknown: Vec<_> = applied.iter().filter(|v| !known.contains(v)).collect();
+    if !unknown.is_empty() {
+        problems.push(format!(
+            "Made by a newer version of the app (unknown migrations: {})",
+            unknown
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let pending_migrations = known.iter().filter(|m| !applied.contains(m)).count();
+
+    // Tables that pending migrations would create aren't missing yet
+    let missing_tables: Vec<_> = table_names(db)
+        .await?
+        .into_iter()
+        .filter(|t| !tables.contains(t))
+        .collect();
+    if pending_migrations == 0 && !missing_tables.is_empty() {
+        problems.push(format!("Missing tables: {}", missing_tables.join(", ")));
+    }
+
+    let verification = BackupVerification {
+        file_path: backup_path.to_string(),
+        restorable: problems.is_empty(),
+        integrity,
+        migration_version: applied.last().cloned(),
+        pending_migrations,
+        missing_tables,
+        row_counts,
+        problems,
+    };
+    if verification.restorable {
+        info!(backup_path = %backup_path, "Backup verified");
+    } else {
+        warn!(
+            backup_path = %backup_path,
+            problems = ?verification.problems,
+            "Backup failed verification"
+        );
+    }
+    Ok(verification)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::run_migrations;
+    use sea_orm::Database;
+
+    #[tokio::test]
+    async fn test_verify_backup() {
+        let dir = std::env::temp_dir().join(format!("cockpit-verify-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        // A file, since VACUUM INTO from an in-memory database stays in memory
+        let db = Database::connect(format!(
+            "sqlite://{}?mode=rwc",
+            dir.join("db.sql").display()
+        ))
+        .await
+        .unwrap();
+        run_migrations(&db).await.unwrap();
+
+        let path = dir.join("backup_1.db");
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            format!("VACUUM INTO '{}'", path.display()),
+        ))
+        .await
+        .unwrap();
+        let report = check(&db, &path, "backup_1.db", None).await.unwrap();
+        assert!(report.restorable, "{:?}", report.problems);
+        assert_eq!(report.integrity, ["ok"]);
+        assert_eq!(report.pending_migrations, 0);
+        assert_eq!(report.row_counts.get("ideas"), Some(&0));
+
+        fs::write(&path, b"not a database").unwrap();
+        let report = check(&db, &path, "backup_1.db", None).await;
+        assert!(!report.is_ok_and(|r| r.restorable));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
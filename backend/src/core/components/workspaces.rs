@@ -0,0 +1,353 @@
+//! Workspaces: isolated sets of data in one running instance
+//!
+//! Each workspace is its own SQLite file under `<data dir>/workspaces`, with
+//! its own backups, media, exports, uploads, settings, feeds, ideas and
+//! writings; `default` is the configured database. Commands run against the
+//! active workspace, chosen with `switch_workspace` and remembered across
+//! restarts. A workspace stays open once used, so its scheduled tasks keep
+//! running while another is active. Workspaces need SQLite: a PostgreSQL
+//! server is shared by design.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tracing::{error, info, instrument, warn};
+
+use super::config::workspaces_dir;
+use super::db::init::init_db;
+use super::errors::{AppError, AppResult};
+use super::events::EventEmitter;
+use super::setup::initialize_default_settings;
+use crate::system::components::scheduler::start_scheduler;
+use crate::AppState;
+
+/// The workspace of the configured database
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Longest workspace name
+const MAX_NAME_LEN: usize = 40;
+
+/// File in the workspaces directory naming the active workspace
+const ACTIVE_FILE: &str = "active";
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDto {
+    pub name: String,
+    /// Commands run against this workspace
+    pub active: bool,
+    /// Opened since startup, with its scheduled tasks running
+    pub open: bool,
+    /// The database file
+    pub path: Option<String>,
+}
+
+/// The open workspaces and which one is active
+#[derive(Clone)]
+pub struct Workspaces {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    /// State of the default workspace, which the others share all but the
+    /// database with
+    base: Arc<AppState>,
+    emitter: Arc<dyn EventEmitter>,
+    open: RwLock<HashMap<String, Arc<AppState>>>,
+    active: RwLock<String>,
+    /// Held while a workspace is created or opened
+    opening: Mutex<()>,
+}
+
+impl Workspaces {
+    /// Workspaces with `base` as the default one, which is active
+    pub fn new(base: Arc<AppState>, emitter: Arc<dyn EventEmitter>) -> Self {
+        let open = HashMap::from([(DEFAULT_WORKSPACE.to_string(), base.clone())]);
+        Self {
+            inner: Arc::new(Inner {
+                base,
+                emitter,
+                open: RwLock::new(open),
+                active: RwLock::new(DEFAULT_WORKSPACE.to_string()),
+                opening: Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Make the workspace active at the last shutdown active again; the
+    /// default one stays active when it can't be opened
+    pub async fn restore_active(&self) {
+        let Some(name) = self.saved_active() else {
+            return;
+        };
+        if name == DEFAULT_WORKSPACE {
+            return;
+        }
+        match self.open_workspace(&name, false).await {
+            Ok(_) => self.set_active(&name),
+            Err(e) => warn!(target: "workspaces", workspace = %name, "Failed to reopen: {}", e),
+        }
+    }
+
+    /// State of the active workspace
+    pub fn active(&self) -> Arc<AppState> {
+        let name = self.active_name();
+        let open = self
+            .inner
+            .open
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        open.get(&name)
+            .cloned()
+            .unwrap_or_else(|| self.inner.base.clone())
+    }
+
+    pub fn active_name(&self) -> String {
+        self.inner
+            .active
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// State of every open workspace, the default one included
+    pub fn all_open(&self) -> Vec<Arc<AppState>> {
+        let open = self
+            .inner
+            .open
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        open.values().cloned().collect()
+    }
+
+    /// How many workspaces there are besides the default one
+    pub fn extra_count(&self) -> AppResult<usize> {
+        Ok(self.list()?.len() - 1)
+    }
+
+    /// The default workspace, then the others by name
+    pub fn list(&self) -> AppResult<Vec<WorkspaceDto>> {
+        let config = self.inner.base.config.current();
+        let dir = workspaces_dir(&config);
+        let mut names = Vec::new();
+        if dir.exists() {
+            let entries = fs::read_dir(&dir).map_err(|e| {
+                AppError::file_operation("read directory", dir.to_string_lossy(), e)
+            })?;
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "sqlite") {
+                    if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                        if validate_name(name).is_ok() {
+                            names.push(name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        names.sort();
+
+        let active = self.active_name();
+        let open = self
+            .inner
+            .open
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let default_path = config
+            .database
+            .sqlite_path()
+            .map(|path| path.to_string_lossy().into_owned());
+        Ok(
+            std::iter::once((DEFAULT_WORKSPACE.to_string(), default_path))
+                .chain(names.into_iter().map(|name| {
+                    let path = dir.join(format!("{}.sqlite", name));
+                    (name, Some(path.to_string_lossy().into_owned()))
+                }))
+                .map(|(name, path)| WorkspaceDto {
+                    active: name == active,
+                    open: open.contains_key(&name),
+                    name,
+                    path,
+                })
+                .collect(),
+        )
+    }
+
+    /// Create an empty workspace with the default settings; it doesn't
+    /// become active
+    #[instrument(skip(self))]
+    pub async fn create(&self, name: &str) -> AppResult<WorkspaceDto> {
+        validate_name(name)?;
+        if name == DEFAULT_WORKSPACE || self.database_file(name)?.exists() {
+            return Err(AppError::validation(
+                "name",
+                format!("Workspace '{}' already exists", name),
+            ));
+        }
+        self.open_workspace(name, true).await?;
+        info!(target: "workspaces", workspace = %name, "Workspace created");
+        self.find(name)
+    }
+
+    /// Run commands against workspace `name` from now on
+    #[instrument(skip(self))]
+    pub async fn switch(&self, name: &str) -> AppResult<WorkspaceDto> {
+        if name != DEFAULT_WORKSPACE {
+            validate_name(name)?;
+            if !self.database_file(name)?.exists() {
+                return Err(
+                    AppError::validation("name", format!("No workspace named '{}'", name))
+                        .with_suggestion("Create it with create_workspace first"),
+                );
+            }
+            self.open_workspace(name, false).await?;
+        }
+        self.set_active(name);
+        self.save_active(name)?;
+        info!(target: "workspaces", workspace = %name, "Switched workspace");
+        self.find(name)
+    }
+
+    fn find(&self, name: &str) -> AppResult<WorkspaceDto> {
+        self.list()?
+            .into_iter()
+            .find(|workspace| workspace.name == name)
+            .ok_or_else(|| AppError::other(format!("Workspace '{}' disappeared", name)))
+    }
+
+    fn set_active(&self, name: &str) {
+        *self
+            .inner
+            .active
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = name.to_string();
+    }
+
+    fn database_file(&self, name: &str) -> AppResult<PathBuf> {
+        let config = self.inner.base.config.current();
+        if config.database.is_postgres() {
+            return Err(AppError::validation(
+                "workspace",
+                "Workspaces are separate SQLite files and are not available with PostgreSQL",
+            ));
+        }
+        Ok(workspaces_dir(&config).join(format!("{}.sqlite", name)))
+    }
+
+    /// Connect to the database of workspace `name` and start its scheduler,
+    /// unless it is open already
+    async fn open_workspace(&self, name: &str, create: bool) -> AppResult<Arc<AppState>> {
+        let _opening = self.inner.opening.lock().await;
+        let existing = self
+            .inner
+            .open
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .cloned();
+        if let Some(state) = existing {
+            return Ok(state);
+        }
+
+        let path = self.database_file(name)?;
+        if !create && !path.exists() {
+            return Err(AppError::validation(
+                "name",
+                format!("No workspace named '{}'", name),
+            ));
+        }
+        let base = &self.inner.base;
+        let config = base.config.for_workspace(name);
+        let current = config.current();
+        let db = init_db(&current.database, &current.storage).await?;
+        if create {
+            initialize_default_settings(&db).await?;
+        }
+        let state = Arc::new(AppState {
+            db,
+            running: Default::default(),
+            config,
+            ..AppState::clone(base)
+        });
+
+        let scheduler_state = state.clone();
+        let emitter = self.inner.emitter.clone();
+        let workspace = name.to_string();
+        tokio::spawn(async move {
+            if let Err(err) = start_scheduler(scheduler_state, emitter).await {
+                error!(target: "scheduler", workspace = %workspace, "Failed to start: {}", err);
+            }
+        });
+
+        self.inner
+            .open
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name.to_string(), state.clone());
+        info!(target: "workspaces", workspace = %name, "Workspace opened");
+        Ok(state)
+    }
+
+    fn active_file(&self) -> PathBuf {
+        workspaces_dir(&self.inner.base.config.current()).join(ACTIVE_FILE)
+    }
+
+    fn saved_active(&self) -> Option<String> {
+        let name = fs::read_to_string(self.active_file()).ok()?;
+        let name = name.trim();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    fn save_active(&self, name: &str) -> AppResult<()> {
+        let path = self.active_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| {
+                AppError::file_operation("create directory", dir.to_string_lossy(), e)
+            })?;
+        }
+        fs::write(&path, name)
+            .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))
+    }
+}
+
+/// Workspace names become file names: lowercase letters, digits, `-` and `_`
+fn validate_name(name: &str) -> AppResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with(['-', '_'])
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::validation(
+            "name",
+            format!(
+                "'{}' is not a valid workspace name: use up to {} lowercase letters, digits, '-' and '_'",
+                name, MAX_NAME_LEN
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("personal-writing_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Work").is_err());
+        assert!(validate_name("../default").is_err());
+        assert!(validate_name("-x").is_err());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+}
@@ -10,14 +10,20 @@ mod system;
 mod util;
 mod writing;
 
-use crate::core::components::events::{EventEmitter, NoopEventEmitter};
+use crate::core::components::error_reporting::ErrorReporter;
+use crate::core::components::events::{BroadcastEventEmitter, EventEmitter};
+use crate::core::components::http::HttpClients;
+use crate::core::components::shutdown::{self, Shutdown};
+use crate::core::components::workspaces::Workspaces;
 use bridge::dispatch::BridgeContext;
+use bridge::jobs::JobRegistry;
+use bridge::metrics::CommandMetrics;
 use reqwest::Client;
 use sea_orm::DatabaseConnection;
 use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use system::scheduler::limits::TaskLimiter;
 use system::scheduler::start_scheduler;
 use tokio::sync::Mutex;
 use tracing::{error, info, warn};
@@ -26,8 +32,16 @@ use tracing::{error, info, warn};
 pub struct AppState {
     pub db: DatabaseConnection,
     pub running: Arc<Mutex<HashSet<i64>>>,
-    pub config: Arc<core::config::AppConfig>,
-    pub http_client: Client,
+    /// Replaced by `reload_config`; take `current()` when needed
+    pub config: core::config::SharedConfig,
+    pub log_filter: core::logging::LogFilter,
+    /// Outgoing HTTP clients by purpose (reader fetches, API calls)
+    pub http_clients: HttpClients,
+    pub shutdown: Shutdown,
+    /// Forwards serious errors to `SENTRY_DSN`; a no-op without one
+    pub error_reporter: ErrorReporter,
+    /// Caps how many scheduler tasks run at once
+    pub task_limiter: TaskLimiter,
 }
 
 // ========== Main Application Setup ==========
@@ -44,21 +58,11 @@ async fn main() {
         }
     };
 
-    // Load .env file from ~/.cockpit first (for production) or current directory (for development)
-    if let Some(home) = dirs::home_dir() {
-        let prod_env = home.join(".cockpit/.env");
-        if prod_env.exists() {
-            let _ = dotenvy::from_path(&prod_env);
-        }
-    }
-    // Fallback to current directory .env for development
-    let _ = dotenvy::dotenv();
-
-    // Load and validate configuration
+    // Load and validate configuration (environment, .env files, config.toml)
     let config = match core::config::AppConfig::from_env() {
         Ok(cfg) => cfg,
         Err(e) => {
-            error!(target: "config", "Configuration error: {}. Please check your .env file or environment variables.", e);
+            error!(target: "config", "Configuration error: {}. Please check ~/.cockpit/config.toml, your .env file or environment variables.", e);
             std::process::exit(1);
         }
     };
@@ -69,16 +73,20 @@ async fn main() {
         std::process::exit(1);
     }
 
-    core::logging::init_logging(&config.logging);
+    let (trace_exporter, log_filter) = core::logging::init_logging(&config.logging);
 
     // Initialize storage management
     if let Err(e) = core::storage::initialize_storage(&config) {
         warn!(target: "storage", "Storage initialization warning: {}", e);
         // Don't exit - this is not critical
     }
-    let config_arc = Arc::new(config);
 
-    let db = match core::db::init_db_from_env().await {
+    // Secrets are encrypted with the master key of the shared configuration,
+    // which key rotation updates
+    let shared_config = core::config::SharedConfig::new(config.clone());
+    core::components::crypto::use_config(shared_config.clone());
+
+    let db = match core::db::init_db(&config.database, &config.storage).await {
         Ok(db) => db,
         Err(e) => {
             error!(target: "db", "Failed to connect to database: {}", e);
@@ -94,23 +102,32 @@ async fn main() {
         }
     }
 
-    // Configure shared HTTP client with connection pooling and timeouts
-    let http_client = Client::builder()
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(10))
-        .pool_max_idle_per_host(5)
-        .pool_idle_timeout(Duration::from_secs(90))
-        .build()
-        .expect("failed to build http client");
+    // Outgoing HTTP clients, pooled, with the timeouts and proxies configured
+    let http_clients = match HttpClients::new(&config.http_clients) {
+        Ok(clients) => clients,
+        Err(e) => {
+            error!(target: "config", "Failed to build HTTP clients: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let error_reporter =
+        ErrorReporter::new(&config.error_reporting, Client::clone(&http_clients.api));
 
     let state = Arc::new(AppState {
         db,
         running: Arc::new(Mutex::new(HashSet::new())),
-        config: config_arc.clone(),
-        http_client,
+        config: shared_config,
+        log_filter,
+        http_clients,
+        shutdown: Shutdown::new(),
+        error_reporter,
+        task_limiter: TaskLimiter::default(),
     });
 
-    let emitter: Arc<dyn EventEmitter> = Arc::new(NoopEventEmitter);
+    // Events reach the frontend over the bridge's /ws endpoint
+    let events = BroadcastEventEmitter::new();
+    let emitter: Arc<dyn EventEmitter> = Arc::new(events.clone());
 
     // Kick off scheduler
     let scheduler_state = state.clone();
@@ -121,19 +138,94 @@ async fn main() {
         }
     });
 
+    // Reopen the workspace that was active at the last shutdown
+    let workspaces = Workspaces::new(state.clone(), emitter.clone());
+    workspaces.restore_active().await;
+
+    // Check the database in the background, warning subscribers on problems
+    if let Some(check) = state.config.current().database.startup_integrity_check {
+        let db = state.db.clone();
+        let integrity_emitter = emitter.clone();
+        tokio::spawn(async move {
+            let full = check == core::config::IntegrityCheck::Full;
+            match core::db::integrity::check_database_integrity(&db, full).await {
+                Ok(report) if !report.ok => {
+                    if let Err(e) = integrity_emitter
+                        .emit("database_integrity_warning", &report)
+                        .await
+                    {
+                        warn!(target: "db", "Failed to emit integrity warning: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!(target: "db", "Integrity check failed: {}", e),
+            }
+        });
+    }
+
+    // SIGHUP reloads the configuration, like `reload_config`
+    #[cfg(unix)]
+    {
+        let reload_state = state.clone();
+        tokio::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            let Ok(mut hangup) = signal(SignalKind::hangup()) else {
+                return;
+            };
+            while hangup.recv().await.is_some() {
+                if let Err(e) =
+                    core::config::reload_config(&reload_state.config, &reload_state.log_filter)
+                {
+                    error!(target: "config", "Configuration reload failed: {}", e);
+                }
+            }
+        });
+    }
+
     // Start Axum command bridge
-    let port = std::env::var("COCKPIT_HTTP_PORT")
-        .ok()
-        .and_then(|v| v.parse::<u16>().ok())
-        .unwrap_or(1420);
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let addr = SocketAddr::from(([0, 0, 0, 0], state.config.current().http.port));
     let router = bridge::http::router(BridgeContext {
         state: state.clone(),
         emitter,
+        events,
+        jobs: JobRegistry::new(),
+        metrics: CommandMetrics::new(),
+        workspaces: workspaces.clone(),
     });
+    let listener = match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!(target: "api", "Failed to bind {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
     info!(target: "api", "HTTP bridge listening on http://{}", addr);
-    axum::Server::bind(&addr)
-        .serve(router.into_make_service())
-        .await
-        .expect("failed to run HTTP bridge");
+
+    // Stop accepting requests on SIGINT/SIGTERM; in-flight ones still finish
+    let draining = state.shutdown.clone();
+    let served = axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            shutdown::signal().await;
+            info!(target: "api", "Shutdown requested, draining");
+            draining.begin();
+        })
+        .await;
+    if let Err(e) = served {
+        error!(target: "api", "HTTP bridge failed: {}", e);
+    }
+
+    // Let scheduled runs finish (or abort them) so none is left half recorded
+    for workspace in workspaces.all_open() {
+        workspace
+            .shutdown
+            .drain(&workspace.running, shutdown::DRAIN_GRACE)
+            .await;
+        if let Err(e) = workspace.db.close_by_ref().await {
+            warn!(target: "db", "Failed to close database: {}", e);
+        }
+    }
+    info!(target: "api", "Shutdown complete");
+    if let Some(exporter) = trace_exporter {
+        exporter.shutdown();
+    }
 }
@@ -30,14 +30,52 @@ pub async fn notes_upsert(
     entity_type: String,
     entity_id: i64,
     note_type: Option<String>,
-    body_html: String,
+    body_html: Option<String>,
+    body_markdown: Option<String>,
+    store_markdown: Option<bool>,
+    expected_updated_at: Option<String>,
 ) -> Result<components::notes::NoteDto, String> {
+    let body = match (&body_html, &body_markdown) {
+        (Some(html), None) => components::notes::NoteBody::Html(html),
+        (None, Some(markdown)) => components::notes::NoteBody::Markdown(markdown),
+        _ => return Err("Exactly one of body_html or body_markdown is required".to_string()),
+    };
     components::notes::upsert(
         &state.db,
         &entity_type,
         entity_id,
         note_type.as_deref(),
-        &body_html,
+        body,
+        store_markdown,
+        expected_updated_at.as_deref(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn notes_export_markdown(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: i64,
+    note_type: Option<String>,
+) -> Result<components::notes::NoteMarkdownDto, String> {
+    components::notes::export_markdown(&state.db, &entity_type, entity_id, note_type.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn export_entity_bundle(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<components::bundle::EntityBundleDto, String> {
+    components::bundle::export_entity_bundle(
+        &state.db,
+        &state.config.current().storage,
+        &entity_type,
+        entity_id,
     )
     .await
     .map_err(|e| e.to_string())
@@ -65,3 +103,50 @@ pub async fn notes_append_snippet(
     .await
     .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn notes_list_backlinks(
+    state: State<'_, AppState>,
+    entity_type: String,
+    entity_id: i64,
+) -> Result<Vec<components::links::BacklinkDto>, String> {
+    components::links::list_backlinks(&state.db, &entity_type, entity_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn notes_templates_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<components::templates::NoteTemplateDto>, String> {
+    components::templates::templates_list(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn notes_template_create(
+    state: State<'_, AppState>,
+    input: components::templates::NoteTemplateCreateInput,
+) -> Result<components::templates::NoteTemplateDto, String> {
+    components::templates::template_create(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn notes_template_update(
+    state: State<'_, AppState>,
+    input: components::templates::NoteTemplateUpdateInput,
+) -> Result<components::templates::NoteTemplateDto, String> {
+    components::templates::template_update(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn notes_template_delete(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    components::templates::template_delete(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
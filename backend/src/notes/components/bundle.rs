@@ -0,0 +1,567 @@
+//! Research bundles: one entity with everything around it, as a zip
+//!
+//! A bundle is a self-contained packet to share or archive. For an idea,
+//! reference, reader reference or writing it holds:
+//!
+//! - `README.md`: what is in the bundle, with links to each file
+//! - `entity.json`: the entity's metadata (and, for a writing, its ideas)
+//! - `writing.md`: a writing's content, in the Markdown export format
+//! - `notes/<note type>.md`: the entity's notes as Markdown
+//! - `references/NN-<title>.md`: each linked reference with its latest reader
+//!   snapshot and its clips
+//!
+//! An idea's references are those linked in the knowledge graph and those
+//! attached to the idea directly; a writing's are those of its ideas.
+//! Snapshots and clips are found through the reader reference with the same
+//! URL. Images in snapshots stay links to the media they were saved as.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{info, instrument};
+
+use super::markdown::html_to_markdown;
+use super::notes::parse_entity_type;
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::reader::normalize_reader_url;
+use crate::research::components::feed::entities::articles;
+use crate::research::entities::{reader_clips, reader_references, reader_snapshots};
+use crate::writing::components::ideas::entities::idea_references;
+use crate::writing::components::ideas::types as ideas;
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, notes, reference_items, writing_idea_links, writings,
+};
+use crate::writing::export::markdown::{markdown_file, push_field, write_zip, yaml_string};
+use crate::writing::export::{export_dir, slugify, timestamp};
+
+/// Result of a bundle export
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityBundleDto {
+    /// The zip archive in the export directory
+    pub path: String,
+    pub file_size: u64,
+    pub note_count: usize,
+    pub reference_count: usize,
+    pub snapshot_count: usize,
+    pub clip_count: usize,
+}
+
+/// The entity a bundle is built around
+struct Subject {
+    title: String,
+    metadata: serde_json::Value,
+    /// Files beyond the common ones, such as a writing's content
+    files: Vec<(String, String)>,
+    sources: Vec<Source>,
+}
+
+/// A reference bundled with the entity
+#[derive(Default)]
+struct Source {
+    title: String,
+    kind: String,
+    url: Option<String>,
+    author: Option<String>,
+    publisher: Option<String>,
+    description: Option<String>,
+    /// Role of the reference for the idea linking it
+    role: Option<String>,
+    /// Reader reference holding its snapshots and clips
+    reader_id: Option<i64>,
+}
+
+/// Export an entity, its notes and its references with their snapshots and
+/// clips as a zip in the export directory
+#[instrument(skip(db, storage_config))]
+pub async fn export_entity_bundle(
+    db: &DatabaseConnection,
+    storage_config: &StorageConfig,
+    entity_type: &str,
+    entity_id: i64,
+) -> AppResult<EntityBundleDto> {
+    let kind = parse_entity_type(entity_type)?;
+    let mut subject = match kind {
+        notes::EntityType::Idea => idea_subject(db, entity_id).await?,
+        notes::EntityType::Reference => reference_subject(db, entity_id).await?,
+        notes::EntityType::ReaderReference => reader_reference_subject(db, entity_id).await?,
+        notes::EntityType::Writing => writing_subject(db, entity_id).await?,
+    };
+    find_reader_references(db, &mut subject.sources).await?;
+
+    let mut files = Vec::new();
+    files.push((
+        "entity.json".to_string(),
+        serde_json::to_string_pretty(&subject.metadata).unwrap_or_default(),
+    ));
+    files.append(&mut subject.files);
+
+    let entity_notes = notes::Entity::find()
+        .filter(notes::Column::EntityType.eq(kind))
+        .filter(notes::Column::EntityId.eq(entity_id))
+        .order_by_asc(notes::Column::CreatedAt)
+        .all(db)
+        .await?;
+    let mut note_files = Vec::new();
+    for note in &entity_notes {
+        let note_type = note.note_type.as_deref().unwrap_or("main");
+        let markdown = match &note.canonical_markdown {
+            Some(markdown) => markdown.clone(),
+            None => html_to_markdown(&note.body_html),
+        };
+        let name = format!("notes/{}.md", slugify(note_type));
+        note_files.push(name.clone());
+        files.push((name, format!("{}\n", markdown.trim_end())));
+    }
+
+    let (mut snapshot_count, mut clip_count) = (0, 0);
+    let mut reference_files = Vec::new();
+    for (i, source) in subject.sources.iter().enumerate() {
+        let (snapshot, clips) = match source.reader_id {
+            Some(reader_id) => reader_material(db, reader_id).await?,
+            None => (None, Vec::new()),
+        };
+        snapshot_count += usize::from(snapshot.is_some());
+        clip_count += clips.len();
+        let slug = Some(short_slug(&source.title, 60)).filter(|s| !s.is_empty());
+        let name = format!(
+            "references/{:02}-{}.md",
+            i + 1,
+            slug.as_deref().unwrap_or("reference")
+        );
+        reference_files.push((name.clone(), source.title.clone()));
+        files.push((name, reference_markdown(source, snapshot.as_ref(), &clips)));
+    }
+
+    files.insert(
+        0,
+        (
+            "README.md".to_string(),
+            readme(entity_type, &subject.title, &note_files, &reference_files),
+        ),
+    );
+
+    let folder = short_slug(&format!("{} {}", entity_type, subject.title), 80);
+    let path = export_dir(storage_config)?.join(format!(
+        "bundle_{}_{}_{}.zip",
+        entity_type,
+        entity_id,
+        timestamp()
+    ));
+    write_zip(&path, &folder, &files)
+        .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+    let file_size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+    info!(
+        path = %path.display(),
+        notes = entity_notes.len(),
+        references = subject.sources.len(),
+        "Exported entity bundle"
+    );
+
+    Ok(EntityBundleDto {
+        path: path.to_string_lossy().to_string(),
+        file_size,
+        note_count: entity_notes.len(),
+        reference_count: subject.sources.len(),
+        snapshot_count,
+        clip_count,
+    })
+}
+
+async fn idea_subject(db: &DatabaseConnection, id: i64) -> AppResult<Subject> {
+    let idea = ideas::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Idea {} not found", id)))?;
+
+    let mut sources = linked_references(db, &[id]).await?;
+    let attached = idea_references::Entity::find()
+        .filter(idea_references::Column::IdeaId.eq(id))
+        .order_by_asc(idea_references::Column::AddedAt)
+        .all(db)
+        .await?;
+    for reference in attached {
+        let article = match reference.news_article_id {
+            Some(article_id) => articles::Entity::find_by_id(article_id).one(db).await?,
+            None => None,
+        };
+        let title = reference
+            .title
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| article.as_ref().map(|a| a.title.clone()))
+            .unwrap_or_else(|| "Untitled reference".to_string());
+        sources.push(Source {
+            title,
+            kind: reference.reference_type,
+            url: reference.url.or_else(|| article.and_then(|a| a.url)),
+            description: reference.description,
+            ..Default::default()
+        });
+    }
+
+    Ok(Subject {
+        metadata: json!({
+            "type": "idea",
+            "id": idea.id,
+            "title": idea.title,
+            "summary": idea.summary,
+            "status": idea.status.to_string(),
+            "target": idea.target,
+            "tags": idea.tags,
+            "priority": idea.priority,
+            "dateAdded": idea.date_added.to_rfc3339(),
+            "dateUpdated": idea.date_updated.to_rfc3339(),
+            "dateCompleted": idea.date_completed.map(|d| d.to_rfc3339()),
+        }),
+        title: idea.title,
+        files: Vec::new(),
+        sources,
+    })
+}
+
+async fn reference_subject(db: &DatabaseConnection, id: i64) -> AppResult<Subject> {
+    let reference = reference_items::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Reference {} not found", id)))?;
+    Ok(Subject {
+        title: reference.title.clone(),
+        metadata: json!({
+            "type": "reference",
+            "id": reference.id,
+            "title": reference.title,
+            "referenceType": reference.reference_type.to_string(),
+            "url": reference.url,
+            "source": reference.source,
+            "author": reference.author,
+            "publishedAt": reference.published_date.map(|d| d.to_rfc3339()),
+            "summary": reference.summary,
+            "createdAt": reference.created_at.to_rfc3339(),
+            "updatedAt": reference.updated_at.to_rfc3339(),
+        }),
+        files: Vec::new(),
+        sources: vec![reference_source(reference, None)],
+    })
+}
+
+async fn reader_reference_subject(db: &DatabaseConnection, id: i64) -> AppResult<Subject> {
+    let reference = reader_references::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Reference {} not found", id)))?;
+    Ok(Subject {
+        title: reference.title.clone(),
+        metadata: json!({
+            "type": "reader_reference",
+            "id": reference.id,
+            "title": reference.title,
+            "url": reference.url,
+            "byline": reference.byline,
+            "excerpt": reference.excerpt,
+            "tags": reference.tags_json,
+            "summary": reference.summary,
+            "createdAt": reference.created_at.and_utc().to_rfc3339(),
+            "updatedAt": reference.updated_at.and_utc().to_rfc3339(),
+        }),
+        files: Vec::new(),
+        sources: vec![Source {
+            title: reference.title,
+            kind: "reader".to_string(),
+            url: Some(reference.url),
+            author: reference.byline,
+            description: reference.excerpt,
+            reader_id: Some(reference.id),
+            ..Default::default()
+        }],
+    })
+}
+
+async fn writing_subject(db: &DatabaseConnection, id: i64) -> AppResult<Subject> {
+    let writing = writings::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Writing {} not found", id)))?;
+
+    let links = writing_idea_links::Entity::find()
+        .filter(writing_idea_links::Column::WritingId.eq(id))
+        .order_by_asc(writing_idea_links::Column::LinkOrder)
+        .all(db)
+        .await?;
+    let idea_ids: Vec<i64> = links.iter().map(|link| link.idea_id).collect();
+    let titles: HashMap<i64, String> = ideas::Entity::find()
+        .filter(ideas::Column::Id.is_in(idea_ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|idea| (idea.id, idea.title))
+        .collect();
+    let linked_ideas: Vec<_> = links
+        .iter()
+        .filter_map(|link| {
+            let title = titles.get(&link.idea_id)?;
+            Some(json!({ "id": link.idea_id, "title": title, "purpose": link.purpose }))
+        })
+        .collect();
+
+    Ok(Subject {
+        title: writing.title.clone(),
+        metadata: json!({
+            "type": "writing",
+            "id": writing.id,
+            "title": writing.title,
+            "slug": writing.slug,
+            "writingType": writing.r#type.to_string(),
+            "status": writing.status.to_string(),
+            "tags": writing.tags,
+            "wordCount": writing.word_count,
+            "createdAt": writing.created_at.to_rfc3339(),
+            "updatedAt": writing.updated_at.to_rfc3339(),
+            "publishedAt": writing.published_at.map(|d| d.to_rfc3339()),
+            "ideas": linked_ideas,
+        }),
+        files: vec![("writing.md".to_string(), markdown_file(&writing, true))],
+        sources: linked_references(db, &idea_ids).await?,
+    })
+}
+
+/// References linked to the ideas in the knowledge graph, in link order
+async fn linked_references(db: &DatabaseConnection, idea_ids: &[i64]) -> AppResult<Vec<Source>> {
+    let links = idea_reference_links::Entity::find()
+        .filter(idea_reference_links::Column::IdeaId.is_in(idea_ids.iter().copied()))
+        .order_by_asc(idea_reference_links::Column::LinkOrder)
+        .all(db)
+        .await?;
+    let mut references: HashMap<i64, reference_items::Model> = reference_items::Entity::find()
+        .filter(reference_items::Column::Id.is_in(links.iter().map(|link| link.reference_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|reference| (reference.id, reference))
+        .collect();
+    Ok(links
+        .into_iter()
+        .filter_map(|link| {
+            let reference = references.remove(&link.reference_id)?;
+            Some(reference_source(reference, Some(link.role.to_string())))
+        })
+        .collect())
+}
+
+fn reference_source(reference: reference_items::Model, role: Option<String>) -> Source {
+    Source {
+        title: reference.title,
+        kind: reference.reference_type.to_string(),
+        url: reference.url,
+        author: reference.author,
+        publisher: reference.source,
+        description: reference.summary,
+        role,
+        reader_id: None,
+    }
+}
+
+/// Pair sources with the reader reference saved for their URL, dropping
+/// sources listed twice
+async fn find_reader_references(
+    db: &DatabaseConnection,
+    sources: &mut Vec<Source>,
+) -> AppResult<()> {
+    let mut seen = HashSet::new();
+    let mut kept = Vec::new();
+    for mut source in sources.drain(..) {
+        let normalized = source
+            .url
+            .as_deref()
+            .and_then(|url| normalize_reader_url(url).ok());
+        let key = normalized
+            .clone()
+            .unwrap_or_else(|| source.title.to_lowercase());
+        if !seen.insert(key) {
+            continue;
+        }
+        if source.reader_id.is_none() {
+            if let Some(url) = normalized {
+                source.reader_id = reader_references::Entity::find()
+                    .filter(reader_references::Column::Url.eq(url))
+                    .one(db)
+                    .await?
+                    .map(|reference| reference.id);
+            }
+        }
+        kept.push(source);
+    }
+    *sources = kept;
+    Ok(())
+}
+
+/// The latest snapshot of a reader reference and all its clips
+async fn reader_material(
+    db: &DatabaseConnection,
+    reader_id: i64,
+) -> AppResult<(Option<reader_snapshots::Model>, Vec<reader_clips::Model>)> {
+    let snapshot = reader_snapshots::Entity::find()
+        .filter(reader_snapshots::Column::ReferenceId.eq(reader_id))
+        .order_by_desc(reader_snapshots::Column::FetchedAt)
+        .one(db)
+        .await?;
+    let clips = reader_clips::Entity::find()
+        .filter(reader_clips::Column::ReferenceId.eq(reader_id))
+        .order_by_asc(reader_clips::Column::CreatedAt)
+        .all(db)
+        .await?;
+    Ok((snapshot, clips))
+}
+
+/// A reference as front matter, its description, snapshot and clips
+fn reference_markdown(
+    source: &Source,
+    snapshot: Option<&reader_snapshots::Model>,
+    clips: &[reader_clips::Model],
+) -> String {
+    let mut out = String::from("---\n");
+    push_field(&mut out, "title", &yaml_string(&source.title));
+    push_field(&mut out, "type", &source.kind);
+    for (key, value) in [
+        ("url", &source.url),
+        ("author", &source.author),
+        ("source", &source.publisher),
+        ("role", &source.role),
+    ] {
+        if let Some(value) = value {
+            push_field(&mut out, key, &yaml_string(value));
+        }
+    }
+    if let Some(snapshot) = snapshot {
+        push_field(
+            &mut out,
+            "snapshot_fetched_at",
+            &snapshot.fetched_at.and_utc().to_rfc3339(),
+        );
+    }
+    out.push_str("---\n\n");
+    out.push_str(&format!("# {}\n", source.title));
+    if let Some(description) = source
+        .description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty())
+    {
+        out.push_str(&format!("\n{}\n", description.trim()));
+    }
+
+    if !clips.is_empty() {
+        out.push_str("\n## Clips\n");
+        for clip in clips {
+            out.push('\n');
+            out.push_str(&clip_markdown(clip));
+        }
+    }
+    if let Some(snapshot) = snapshot {
+        out.push_str("\n## Snapshot\n\n");
+        out.push_str(snapshot.content_md.trim());
+        out.push('\n');
+    }
+    out
+}
+
+/// A clip as a block quote, followed by its label and note
+fn clip_markdown(clip: &reader_clips::Model) -> String {
+    let mut out: String = clip
+        .quote
+        .trim()
+        .lines()
+        .map(|line| match line.trim() {
+            "" => ">\n".to_string(),
+            _ => format!("> {}\n", line),
+        })
+        .collect();
+    let mut details = Vec::new();
+    if let Some(label) = clip.label.as_deref().filter(|l| !l.is_empty()) {
+        details.push(format!("**{}**", label));
+    }
+    if let Some(note) = clip.note.as_deref().filter(|n| !n.trim().is_empty()) {
+        details.push(note.trim().to_string());
+    }
+    if !details.is_empty() {
+        out.push_str(&format!("\n{}\n", details.join(" — ")));
+    }
+    out
+}
+
+/// `slugify` cut to at most `max` characters
+fn short_slug(text: &str, max: usize) -> String {
+    let slug: String = slugify(text).chars().take(max).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+fn readme(
+    entity_type: &str,
+    title: &str,
+    note_files: &[String],
+    reference_files: &[(String, String)],
+) -> String {
+    let mut out = format!(
+        "# {}\n\nResearch bundle for the {} \"{}\", exported {}.\n\n",
+        title,
+        entity_type.replace('_', " "),
+        title,
+        chrono::Utc::now().format("%Y-%m-%d")
+    );
+    out.push_str("Metadata is in [entity.json](entity.json).\n");
+    if entity_type == "writing" {
+        out.push_str("The writing itself is in [writing.md](writing.md).\n");
+    }
+    if !note_files.is_empty() {
+        out.push_str("\n## Notes\n\n");
+        for name in note_files {
+            out.push_str(&format!("- [{}]({})\n", name, name));
+        }
+    }
+    if !reference_files.is_empty() {
+        out.push_str("\n## References\n\n");
+        for (name, title) in reference_files {
+            out.push_str(&format!("- [{}]({})\n", title, name));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_reference_markdown() {
+        let source = Source {
+            title: "On Writing".to_string(),
+            kind: "book".to_string(),
+            author: Some("Stephen King".to_string()),
+            role: Some("supporting".to_string()),
+            ..Default::default()
+        };
+        let clip = reader_clips::Model {
+            id: 1,
+            reference_id: 2,
+            snapshot_id: 3,
+            quote: "The road to hell\n\nis paved with adverbs.".to_string(),
+            anchor: None,
+            created_at: Utc::now().naive_utc(),
+            color: None,
+            label: Some("quote".to_string()),
+            note: Some("Use in the intro".to_string()),
+        };
+        let markdown = reference_markdown(&source, None, &[clip]);
+        assert!(markdown.starts_with("---\ntitle: \"On Writing\"\ntype: book\n"));
+        assert!(markdown.contains("author: \"Stephen King\"\n"));
+        assert!(!markdown.contains("url:"));
+        assert!(markdown.contains("> The road to hell\n>\n> is paved with adverbs.\n"));
+        assert!(markdown.contains("**quote** — Use in the intro"));
+        assert!(!markdown.contains("## Snapshot"));
+    }
+}
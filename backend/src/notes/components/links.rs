@@ -0,0 +1,308 @@
+//! Wiki links in notes
+//!
+//! `[[Title]]` in a note body links the note's entity to the idea, writing
+//! or reference with that title, matched without regard to case; when
+//! titles clash, ideas win over writings and writings over references. A
+//! prefix picks the kind (`[[reference:Title]]`), and text after `|` or `#`
+//! is only for display (`[[Title|shown text]]`). Links are rebuilt every
+//! time a note is saved and kept in `note_links`, so `list_backlinks` can
+//! find the notes that mention an entity. Links inside code, and links to
+//! titles that don't exist yet, are left out.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use regex::Regex;
+use schemars::JsonSchema;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::entities::reader_references;
+use crate::writing::components::ideas::types as ideas;
+use crate::writing::components::knowledge_graph::entities::{
+    note_links, notes, reference_items, writings,
+};
+
+/// Kinds of entity a link can point at, in the order clashing titles resolve
+const TARGET_TYPES: [&str; 3] = ["idea", "writing", "reference"];
+
+/// A `[[...]]` link as written in a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WikiLink {
+    /// Text between the brackets
+    pub text: String,
+    /// Kind named by a prefix, if any
+    pub target_type: Option<&'static str>,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklinkDto {
+    pub note_id: i64,
+    /// The entity the linking note belongs to
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub note_type: String,
+    /// Title of that entity; `None` once it is gone
+    pub title: Option<String>,
+    pub link_text: String,
+    pub updated_at: String,
+}
+
+/// The distinct wiki links of a note body, in order of appearance
+pub fn parse_wiki_links(html: &str) -> Vec<WikiLink> {
+    let code = Regex::new(r"(?is)<pre\b.*?</pre>|<code\b.*?</code>").expect("valid code regex");
+    let link = Regex::new(r"\[\[([^\[\]<>\n]+)\]\]").expect("valid wiki link regex");
+
+    let prose = code.replace_all(html, " ");
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for caps in link.captures_iter(&prose) {
+        let text = decode_entities(caps[1].trim());
+        let target = text.split(['|', '#']).next().unwrap_or_default();
+        let prefixed = target.split_once(':').and_then(|(prefix, rest)| {
+            let target_type = TARGET_TYPES.into_iter().find(|t| *t == prefix.trim())?;
+            Some((Some(target_type), rest.trim()))
+        });
+        let (target_type, title) = prefixed.unwrap_or((None, target.trim()));
+        if title.is_empty() || !seen.insert((target_type, title.to_lowercase())) {
+            continue;
+        }
+        links.push(WikiLink {
+            title: title.to_string(),
+            target_type,
+            text,
+        });
+    }
+    links
+}
+
+/// Replace the stored links of `note` with those in its body
+pub async fn sync_note_links<C: ConnectionTrait>(db: &C, note: &notes::Model) -> AppResult<usize> {
+    note_links::Entity::delete_many()
+        .filter(note_links::Column::NoteId.eq(note.id))
+        .exec(db)
+        .await?;
+
+    let links = parse_wiki_links(&note.body_html);
+    if links.is_empty() {
+        return Ok(0);
+    }
+
+    let titles = title_index(db).await?;
+    let own_type = note.entity_type.to_string();
+    let now = Utc::now();
+    let mut targets = HashSet::new();
+    let mut rows = Vec::new();
+    for link in links {
+        let key = link.title.to_lowercase();
+        let found = TARGET_TYPES
+            .into_iter()
+            .filter(|t| link.target_type.map_or(true, |wanted| wanted == *t))
+            .find_map(|t| titles.get(&(t, key.clone())).map(|id| (t, *id)));
+        let Some((target_type, target_id)) = found else {
+            debug!(note_id = note.id, title = %link.title, "Wiki link matches nothing");
+            continue;
+        };
+        // A note mentioning its own entity, or one entity twice, links once
+        let own = target_type == own_type && target_id == note.entity_id;
+        if own || !targets.insert((target_type, target_id)) {
+            continue;
+        }
+        rows.push(note_links::ActiveModel {
+            note_id: Set(note.id),
+            target_type: Set(target_type.to_string()),
+            target_id: Set(target_id),
+            link_text: Set(link.text),
+            created_at: Set(now),
+            ..Default::default()
+        });
+    }
+
+    let count = rows.len();
+    if count > 0 {
+        note_links::Entity::insert_many(rows).exec(db).await?;
+    }
+    Ok(count)
+}
+
+/// Notes linking to an idea, writing or reference, most recently edited first
+pub async fn list_backlinks(
+    db: &DatabaseConnection,
+    entity_type: &str,
+    entity_id: i64,
+) -> AppResult<Vec<BacklinkDto>> {
+    if !TARGET_TYPES.contains(&entity_type) {
+        return Err(AppError::validation(
+            "entity_type",
+            format!(
+                "Invalid value '{}'. Must be one of: {}",
+                entity_type,
+                TARGET_TYPES.join(", ")
+            ),
+        ));
+    }
+
+    let links = note_links::Entity::find()
+        .filter(note_links::Column::TargetType.eq(entity_type))
+        .filter(note_links::Column::TargetId.eq(entity_id))
+        .all(db)
+        .await?;
+    if links.is_empty() {
+        return Ok(Vec::new());
+    }
+    let link_text: HashMap<i64, String> = links
+        .into_iter()
+        .map(|link| (link.note_id, link.link_text))
+        .collect();
+
+    let sources = notes::Entity::find()
+        .filter(notes::Column::Id.is_in(link_text.keys().copied()))
+        .order_by_desc(notes::Column::UpdatedAt)
+        .all(db)
+        .await?;
+
+    let mut titles = HashMap::new();
+    for source in &sources {
+        let key = (source.entity_type.to_string(), source.entity_id);
+        if !titles.contains_key(&key) {
+            let title = entity_title(db, &source.entity_type, source.entity_id).await?;
+            titles.insert(key, title);
+        }
+    }
+
+    Ok(sources
+        .into_iter()
+        .map(|source| {
+            let entity_type = source.entity_type.to_string();
+            BacklinkDto {
+                note_id: source.id,
+                title: titles
+                    .get(&(entity_type.clone(), source.entity_id))
+                    .cloned()
+                    .flatten(),
+                entity_type,
+                entity_id: source.entity_id,
+                note_type: source.note_type.unwrap_or_else(|| "main".to_string()),
+                link_text: link_text.get(&source.id).cloned().unwrap_or_default(),
+                updated_at: source.updated_at.to_rfc3339(),
+            }
+        })
+        .collect())
+}
+
+/// Lowercased titles of every link target; the oldest of a kind wins when
+/// titles repeat
+async fn title_index<C: ConnectionTrait>(
+    db: &C,
+) -> AppResult<HashMap<(&'static str, String), i64>> {
+    let idea_titles: Vec<(i64, String)> = ideas::Entity::find()
+        .select_only()
+        .column(ideas::Column::Id)
+        .column(ideas::Column::Title)
+        .filter(ideas::Column::DateRemoved.is_null())
+        .order_by_asc(ideas::Column::Id)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let writing_titles: Vec<(i64, String)> = writings::Entity::find()
+        .select_only()
+        .column(writings::Column::Id)
+        .column(writings::Column::Title)
+        .order_by_asc(writings::Column::Id)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let reference_titles: Vec<(i64, String)> = reference_items::Entity::find()
+        .select_only()
+        .column(reference_items::Column::Id)
+        .column(reference_items::Column::Title)
+        .order_by_asc(reference_items::Column::Id)
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut index = HashMap::new();
+    for (target_type, titles) in
+        TARGET_TYPES
+            .into_iter()
+            .zip([idea_titles, writing_titles, reference_titles])
+    {
+        for (id, title) in titles {
+            index
+                .entry((target_type, title.trim().to_lowercase()))
+                .or_insert(id);
+        }
+    }
+    Ok(index)
+}
+
+async fn entity_title(
+    db: &DatabaseConnection,
+    entity_type: &notes::EntityType,
+    entity_id: i64,
+) -> AppResult<Option<String>> {
+    use notes::EntityType;
+
+    Ok(match entity_type {
+        EntityType::Idea => ideas::Entity::find_by_id(entity_id)
+            .one(db)
+            .await?
+            .map(|m| m.title),
+        EntityType::Reference => reference_items::Entity::find_by_id(entity_id)
+            .one(db)
+            .await?
+            .map(|m| m.title),
+        EntityType::ReaderReference => reader_references::Entity::find_by_id(entity_id)
+            .one(db)
+            .await?
+            .map(|m| m.title),
+        EntityType::Writing => writings::Entity::find_by_id(entity_id)
+            .one(db)
+            .await?
+            .map(|m| m.title),
+    })
+}
+
+/// Undo the escaping TipTap applies to text
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wiki_links() {
+        let html = "<p>See [[Slow Web]] and [[reference:On Writing|King]], \
+                    [[slow web]] again, [[Tom &amp; Jerry#Plot]] and [[ ]].</p>\
+                    <pre><code>[[Not a link]]</code></pre>";
+        let links = parse_wiki_links(html);
+        let found: Vec<_> = links
+            .iter()
+            .map(|l| (l.target_type, l.title.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (None, "Slow Web"),
+                (Some("reference"), "On Writing"),
+                (None, "Tom & Jerry"),
+            ]
+        );
+        assert_eq!(links[1].text, "reference:On Writing|King");
+    }
+}
@@ -0,0 +1,36 @@
+//! Markdown conversion for notes
+//!
+//! Notes are stored as HTML for TipTap. These convert them to Markdown for
+//! export, and Markdown written elsewhere back to HTML for the editor.
+
+use crate::writing::text;
+
+/// Markdown for a note's HTML
+pub fn html_to_markdown(html: &str) -> String {
+    html2md::parse_html(html).trim().to_string()
+}
+
+/// HTML for the editor from Markdown
+pub fn markdown_to_html(markdown: &str) -> String {
+    text::markdown_to_html(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let markdown =
+            "# Title\n\nSome **bold** text with a [link](https://example.com).\n\n* one\n* two";
+        let html = markdown_to_html(markdown);
+        assert!(html.contains("<h1>Title</h1>"), "{}", html);
+        assert!(html.contains("<strong>bold</strong>"), "{}", html);
+
+        let back = html_to_markdown(&html);
+        assert!(back.contains("Title"), "{}", back);
+        assert!(back.contains("**bold**"), "{}", back);
+        assert!(back.contains("[link](https://example.com)"), "{}", back);
+        assert_eq!(html_to_markdown(""), "");
+    }
+}
@@ -1 +1,5 @@
+pub mod bundle;
+pub mod links;
+pub mod markdown;
 pub mod notes;
+pub mod templates;
@@ -2,16 +2,26 @@
 //!
 //! Handles polymorphic notes attached to ideas, references, and writings.
 //! Notes are 1:1 per entity (per note_type), with "main" being the primary note document.
+//! A note can keep canonical Markdown next to its HTML, refreshed on every save.
 
-use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr,
+    EntityTrait, QueryFilter,
+};
 use serde::{Deserialize, Serialize};
 
+use super::links::sync_note_links;
+use super::markdown::{html_to_markdown, markdown_to_html};
+use super::templates::template_body;
 use crate::core::components::errors::{AppError, AppResult};
 use crate::writing::components::knowledge_graph::entities::notes::{self, Entity as Notes};
 
 /// DTO for note responses (camelCase for frontend compatibility)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteDto {
     pub id: i64,
@@ -19,10 +29,33 @@ pub struct NoteDto {
     pub entity_id: i64,
     pub note_type: String,
     pub body_html: String,
+    /// Stored Markdown, for notes that keep one
+    pub canonical_markdown: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// A note's content as Markdown, from `notes_export_markdown`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMarkdownDto {
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub note_type: String,
+    pub markdown: String,
+    /// The note's stored Markdown rather than a conversion of its HTML
+    pub canonical: bool,
+}
+
+/// New content for a note
+#[derive(Debug, Clone, Copy)]
+pub enum NoteBody<'a> {
+    /// HTML from the editor
+    Html(&'a str),
+    /// Markdown, converted to HTML for the editor
+    Markdown(&'a str),
+}
+
 impl From<notes::Model> for NoteDto {
     fn from(model: notes::Model) -> Self {
         Self {
@@ -31,6 +64,7 @@ impl From<notes::Model> for NoteDto {
             entity_id: model.entity_id,
             note_type: model.note_type.unwrap_or_else(|| "main".to_string()),
             body_html: model.body_html,
+            canonical_markdown: model.canonical_markdown,
             created_at: model.created_at.to_rfc3339(),
             updated_at: model.updated_at.to_rfc3339(),
         }
@@ -39,7 +73,8 @@ impl From<notes::Model> for NoteDto {
 
 /// Get or create a note for an entity
 ///
-/// If the note doesn't exist, creates an empty one.
+/// If the note doesn't exist, creates one from the matching template, or an
+/// empty one without a template.
 /// Always returns a note (never None).
 pub async fn get_or_create(
     db: &DatabaseConnection,
@@ -64,13 +99,16 @@ pub async fn get_or_create(
         return Ok(note.into());
     }
     
-    // Create new empty note
+    // Create new note, starting from the template if there is one
+    let body_html = template_body(db, entity_type, note_type)
+        .await?
+        .unwrap_or_default();
     let now = Utc::now();
     let new_note = notes::ActiveModel {
         entity_type: ActiveValue::Set(entity_type_enum),
         entity_id: ActiveValue::Set(entity_id),
         note_type: ActiveValue::Set(Some(note_type.to_string())),
-        body_html: ActiveValue::Set(String::new()),
+        body_html: ActiveValue::Set(body_html),
         created_at: ActiveValue::Set(now),
         updated_at: ActiveValue::Set(now),
         ..Default::default()
@@ -79,19 +117,29 @@ pub async fn get_or_create(
     let result = new_note
         .insert(db)
         .await?;
+    sync_note_links(db, &result).await?;
     
     Ok(result.into())
 }
 
 /// Upsert a note's content
 ///
-/// Creates a new note if it doesn't exist, or updates the existing one.
+/// Creates a new note if it doesn't exist, or updates the existing one, and
+/// refreshes the note's wiki links. `store_markdown` turns keeping canonical
+/// Markdown on or off; when `None`, notes that keep it and content given as
+/// Markdown do.
+///
+/// With `expected_updated_at`, the `updatedAt` of the note as the caller
+/// loaded it, the save only goes through if the note hasn't been saved since;
+/// otherwise it fails with a conflict carrying the stored note.
 pub async fn upsert(
     db: &DatabaseConnection,
     entity_type: &str,
     entity_id: i64,
     note_type: Option<&str>,
-    body_html: &str,
+    body: NoteBody<'_>,
+    store_markdown: Option<bool>,
+    expected_updated_at: Option<&str>,
 ) -> AppResult<NoteDto> {
     let note_type = note_type.unwrap_or("main");
     
@@ -105,25 +153,58 @@ pub async fn upsert(
         .filter(notes::Column::NoteType.eq(note_type))
         .one(db)
         .await?;
-    
+
+    if let Some(expected) = expected_updated_at {
+        let expected = parse_updated_at(expected)?;
+        if existing.as_ref().map(|note| note.updated_at) != Some(expected) {
+            return Err(edit_conflict(existing));
+        }
+    }
+
+    let (body_html, given_markdown) = match body {
+        NoteBody::Html(html) => (html.to_string(), None),
+        NoteBody::Markdown(markdown) => (markdown_to_html(markdown), Some(markdown)),
+    };
+    let keeps_markdown = existing
+        .as_ref()
+        .is_some_and(|note| note.canonical_markdown.is_some());
+    let canonical_markdown = store_markdown
+        .unwrap_or(keeps_markdown || given_markdown.is_some())
+        .then(|| match given_markdown {
+            Some(markdown) => markdown.to_string(),
+            None => html_to_markdown(&body_html),
+        });
+
     let now = Utc::now();
     
     let result = if let Some(existing_note) = existing {
         // Update existing note
+        let (id, stored_at) = (existing_note.id, existing_note.updated_at);
         let mut active: notes::ActiveModel = existing_note.into();
-        active.body_html = ActiveValue::Set(body_html.to_string());
+        active.body_html = ActiveValue::Set(body_html);
+        active.canonical_markdown = ActiveValue::Set(canonical_markdown);
         active.updated_at = ActiveValue::Set(now);
         
-        active
-            .update(db)
-            .await?
+        let mut update = Notes::update(active);
+        if expected_updated_at.is_some() {
+            // A save landing between the check above and this one wins
+            update = update.filter(notes::Column::UpdatedAt.eq(stored_at));
+        }
+        match update.exec(db).await {
+            Ok(note) => note,
+            Err(DbErr::RecordNotUpdated) => {
+                return Err(edit_conflict(Notes::find_by_id(id).one(db).await?));
+            }
+            Err(e) => return Err(e.into()),
+        }
     } else {
         // Create new note
         let new_note = notes::ActiveModel {
             entity_type: ActiveValue::Set(entity_type_enum),
             entity_id: ActiveValue::Set(entity_id),
             note_type: ActiveValue::Set(Some(note_type.to_string())),
-            body_html: ActiveValue::Set(body_html.to_string()),
+            body_html: ActiveValue::Set(body_html),
+            canonical_markdown: ActiveValue::Set(canonical_markdown),
             created_at: ActiveValue::Set(now),
             updated_at: ActiveValue::Set(now),
             ..Default::default()
@@ -133,10 +214,49 @@ pub async fn upsert(
             .insert(db)
             .await?
     };
+    sync_note_links(db, &result).await?;
     
     Ok(result.into())
 }
 
+/// Export a note as Markdown
+///
+/// Returns the stored canonical Markdown when the note keeps one, otherwise
+/// converts its HTML. An entity without the note exports as empty.
+pub async fn export_markdown(
+    db: &DatabaseConnection,
+    entity_type: &str,
+    entity_id: i64,
+    note_type: Option<&str>,
+) -> AppResult<NoteMarkdownDto> {
+    let note_type = note_type.unwrap_or("main");
+    let entity_type_enum = parse_entity_type(entity_type)?;
+
+    let note = Notes::find()
+        .filter(notes::Column::EntityType.eq(entity_type_enum))
+        .filter(notes::Column::EntityId.eq(entity_id))
+        .filter(notes::Column::NoteType.eq(note_type))
+        .one(db)
+        .await?;
+
+    let (markdown, canonical) = match note {
+        Some(notes::Model {
+            canonical_markdown: Some(markdown),
+            ..
+        }) => (markdown, true),
+        Some(note) => (html_to_markdown(&note.body_html), false),
+        None => (String::new(), false),
+    };
+
+    Ok(NoteMarkdownDto {
+        entity_type: entity_type.to_string(),
+        entity_id,
+        note_type: note_type.to_string(),
+        markdown,
+        canonical,
+    })
+}
+
 /// Append a snippet to a note with divider
 ///
 /// If the note is empty, just adds the snippet.
@@ -181,11 +301,124 @@ pub async fn append_snippet(
     let updated_html = format!("{}{}", note.body_html, new_content);
     
     // Save back
-    upsert(db, entity_type, entity_id, Some(note_type), &updated_html).await
+    upsert(
+        db,
+        entity_type,
+        entity_id,
+        Some(note_type),
+        NoteBody::Html(&updated_html),
+        None,
+        None,
+    )
+    .await
+}
+
+/// Move the notes of an entity to another entity of the same type
+///
+/// A note moves as it is when the other entity has none of its type;
+/// otherwise it is appended to that one after an `<hr />` divider and
+/// removed. The wiki links of every note touched are refreshed. Returns how
+/// many notes moved and how many were appended.
+pub async fn merge_notes<C: ConnectionTrait>(
+    db: &C,
+    entity_type: &str,
+    from_id: i64,
+    into_id: i64,
+) -> AppResult<(usize, usize)> {
+    let entity_type = parse_entity_type(entity_type)?;
+    let of_entity = |entity_id: i64| {
+        Notes::find()
+            .filter(notes::Column::EntityType.eq(entity_type.clone()))
+            .filter(notes::Column::EntityId.eq(entity_id))
+    };
+    let note_type =
+        |note: &notes::Model| note.note_type.clone().unwrap_or_else(|| "main".to_string());
+
+    let mut targets: HashMap<String, notes::Model> = of_entity(into_id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|note| (note_type(&note), note))
+        .collect();
+    let sources = of_entity(from_id).all(db).await?;
+
+    let now = Utc::now();
+    let (mut moved, mut appended) = (0, 0);
+    for source in sources {
+        let key = note_type(&source);
+        let merged = match targets.remove(&key) {
+            Some(target) => {
+                let source_markdown = source
+                    .canonical_markdown
+                    .clone()
+                    .unwrap_or_else(|| html_to_markdown(&source.body_html));
+                let canonical_markdown = target
+                    .canonical_markdown
+                    .as_deref()
+                    .map(|markdown| join_with_divider(markdown, "\n\n---\n\n", &source_markdown));
+                let body_html =
+                    join_with_divider(&target.body_html, "\n<hr />\n", &source.body_html);
+
+                let mut active: notes::ActiveModel = target.into();
+                active.body_html = ActiveValue::Set(body_html);
+                active.canonical_markdown = ActiveValue::Set(canonical_markdown);
+                active.updated_at = ActiveValue::Set(now);
+                let merged = active.update(db).await?;
+                Notes::delete_by_id(source.id).exec(db).await?;
+                appended += 1;
+                merged
+            }
+            None => {
+                let mut active: notes::ActiveModel = source.into();
+                active.entity_id = ActiveValue::Set(into_id);
+                active.updated_at = ActiveValue::Set(now);
+                moved += 1;
+                active.update(db).await?
+            }
+        };
+        sync_note_links(db, &merged).await?;
+        targets.insert(key, merged);
+    }
+    Ok((moved, appended))
+}
+
+/// `first` then `second`, with `divider` between them when both have content
+fn join_with_divider(first: &str, divider: &str, second: &str) -> String {
+    match (first.trim().is_empty(), second.trim().is_empty()) {
+        (_, true) => first.to_string(),
+        (true, false) => second.to_string(),
+        (false, false) => format!("{}{}{}", first.trim_end(), divider, second),
+    }
+}
+
+/// A note's `updatedAt` as sent back by a client
+fn parse_updated_at(updated_at: &str) -> AppResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(updated_at)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| {
+            AppError::validation(
+                "expected_updated_at",
+                format!("'{}' is not an RFC 3339 timestamp", updated_at),
+            )
+        })
+}
+
+/// The error for a save against a version of the note that is no longer
+/// stored; `current` is the note as it is now, if it still exists
+fn edit_conflict(current: Option<notes::Model>) -> AppError {
+    let message = match &current {
+        Some(note) => format!(
+            "The note was saved elsewhere at {}; merge with the current content and save again",
+            note.updated_at.to_rfc3339()
+        ),
+        None => "The note no longer exists".to_string(),
+    };
+    let current = current.and_then(|note| serde_json::to_value(NoteDto::from(note)).ok());
+    AppError::conflict(message, current)
 }
 
 /// Parse entity type string to enum
-fn parse_entity_type(entity_type: &str) -> AppResult<notes::EntityType> {
+pub(crate) fn parse_entity_type(entity_type: &str) -> AppResult<notes::EntityType> {
     use crate::writing::components::knowledge_graph::entities::notes::EntityType;
     
     match entity_type {
@@ -231,4 +464,38 @@ mod tests {
         assert!(parse_entity_type("writing").is_ok());
         assert!(parse_entity_type("invalid").is_err());
     }
+
+    #[test]
+    fn test_edit_conflict() {
+        let saved = Utc::now();
+        assert_eq!(parse_updated_at(&saved.to_rfc3339()).unwrap(), saved);
+        assert!(parse_updated_at("yesterday").is_err());
+
+        let note = notes::Model {
+            id: 7,
+            entity_type: notes::EntityType::Idea,
+            entity_id: 3,
+            note_type: Some("main".to_string()),
+            body_html: "<p>theirs</p>".to_string(),
+            canonical_markdown: None,
+            created_at: saved,
+            updated_at: saved,
+        };
+        let err = edit_conflict(Some(note));
+        assert_eq!(err.http_status(), 409);
+        let current = err.conflict_current().unwrap();
+        assert_eq!(current["bodyHtml"], "<p>theirs</p>");
+        assert!(edit_conflict(None).conflict_current().is_none());
+    }
+
+    #[test]
+    fn test_join_with_divider() {
+        let divider = "\n<hr />\n";
+        assert_eq!(
+            join_with_divider("<p>a</p>\n", divider, "<p>b</p>"),
+            "<p>a</p>\n<hr />\n<p>b</p>"
+        );
+        assert_eq!(join_with_divider("", divider, "<p>b</p>"), "<p>b</p>");
+        assert_eq!(join_with_divider("<p>a</p>", divider, " "), "<p>a</p>");
+    }
 }
@@ -0,0 +1,233 @@
+//! Note templates
+//!
+//! A template is the HTML a note starts with when `notes_get_or_create`
+//! creates the first note of its type for an entity, such as a review
+//! skeleton for references or a brief outline for ideas. Templates are kept
+//! per entity type and note type; one without an entity type applies to
+//! every entity type the more specific ones don't cover.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use super::notes::parse_entity_type;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::knowledge_graph::entities::note_templates;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTemplateDto {
+    pub id: i64,
+    pub name: String,
+    /// `None` when the template applies to every entity type
+    pub entity_type: Option<String>,
+    pub note_type: String,
+    pub body_html: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTemplateCreateInput {
+    pub name: String,
+    /// idea, reference, reader_reference or writing; every type when absent
+    pub entity_type: Option<String>,
+    /// Defaults to "main"
+    pub note_type: Option<String>,
+    pub body_html: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteTemplateUpdateInput {
+    pub id: i64,
+    /// Fields left out are unchanged; an empty entity type means every type
+    pub name: Option<String>,
+    pub entity_type: Option<String>,
+    pub note_type: Option<String>,
+    pub body_html: Option<String>,
+}
+
+fn template_to_dto(model: note_templates::Model) -> NoteTemplateDto {
+    NoteTemplateDto {
+        id: model.id,
+        name: model.name,
+        entity_type: model.entity_type,
+        note_type: model.note_type,
+        body_html: model.body_html,
+        created_at: model.created_at.to_rfc3339(),
+        updated_at: model.updated_at.to_rfc3339(),
+    }
+}
+
+fn clean_name(name: &str) -> AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "A template name is required"));
+    }
+    Ok(name.to_string())
+}
+
+/// A valid entity type, or `None` for every type
+fn clean_entity_type(entity_type: Option<&str>) -> AppResult<Option<String>> {
+    match entity_type.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(entity_type) => {
+            parse_entity_type(entity_type)?;
+            Ok(Some(entity_type.to_string()))
+        }
+        None => Ok(None),
+    }
+}
+
+fn clean_note_type(note_type: Option<&str>) -> String {
+    note_type
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("main")
+        .to_string()
+}
+
+/// Refuse a second template for the same entity type and note type
+async fn check_unique(
+    db: &DatabaseConnection,
+    entity_type: Option<&str>,
+    note_type: &str,
+    except: Option<i64>,
+) -> AppResult<()> {
+    let mut query =
+        note_templates::Entity::find().filter(note_templates::Column::NoteType.eq(note_type));
+    query = match entity_type {
+        Some(entity_type) => query.filter(note_templates::Column::EntityType.eq(entity_type)),
+        None => query.filter(note_templates::Column::EntityType.is_null()),
+    };
+    if let Some(id) = except {
+        query = query.filter(note_templates::Column::Id.ne(id));
+    }
+    if let Some(clash) = query.one(db).await? {
+        return Err(AppError::validation(
+            "noteType",
+            format!(
+                "Template '{}' already covers {} notes of {}",
+                clash.name,
+                note_type,
+                entity_type.unwrap_or("every entity type")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn templates_list(db: &DatabaseConnection) -> AppResult<Vec<NoteTemplateDto>> {
+    let templates = note_templates::Entity::find()
+        .order_by_asc(note_templates::Column::Name)
+        .all(db)
+        .await?;
+    Ok(templates.into_iter().map(template_to_dto).collect())
+}
+
+pub async fn template_create(
+    db: &DatabaseConnection,
+    input: NoteTemplateCreateInput,
+) -> AppResult<NoteTemplateDto> {
+    let name = clean_name(&input.name)?;
+    let entity_type = clean_entity_type(input.entity_type.as_deref())?;
+    let note_type = clean_note_type(input.note_type.as_deref());
+    check_unique(db, entity_type.as_deref(), &note_type, None).await?;
+
+    let now = Utc::now();
+    let template = note_templates::ActiveModel {
+        name: Set(name),
+        entity_type: Set(entity_type),
+        note_type: Set(note_type),
+        body_html: Set(input.body_html),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(template_to_dto(template))
+}
+
+pub async fn template_update(
+    db: &DatabaseConnection,
+    input: NoteTemplateUpdateInput,
+) -> AppResult<NoteTemplateDto> {
+    let template = note_templates::Entity::find_by_id(input.id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Note template {} not found", input.id)))?;
+
+    let entity_type = match input.entity_type.as_deref() {
+        Some(entity_type) => clean_entity_type(Some(entity_type))?,
+        None => template.entity_type.clone(),
+    };
+    let note_type = match input.note_type.as_deref() {
+        Some(note_type) => clean_note_type(Some(note_type)),
+        None => template.note_type.clone(),
+    };
+    check_unique(db, entity_type.as_deref(), &note_type, Some(input.id)).await?;
+
+    let mut active = template.into_active_model();
+    if let Some(name) = input.name {
+        active.name = Set(clean_name(&name)?);
+    }
+    active.entity_type = Set(entity_type);
+    active.note_type = Set(note_type);
+    if let Some(body_html) = input.body_html {
+        active.body_html = Set(body_html);
+    }
+    active.updated_at = Set(Utc::now());
+    Ok(template_to_dto(active.update(db).await?))
+}
+
+pub async fn template_delete(db: &DatabaseConnection, id: i64) -> AppResult<()> {
+    note_templates::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Body of the template for a new note: the entity type's own, else the
+/// one for every type
+pub(crate) async fn template_body(
+    db: &DatabaseConnection,
+    entity_type: &str,
+    note_type: &str,
+) -> AppResult<Option<String>> {
+    let templates = note_templates::Entity::find()
+        .filter(note_templates::Column::NoteType.eq(note_type))
+        .filter(
+            note_templates::Column::EntityType
+                .eq(entity_type)
+                .or(note_templates::Column::EntityType.is_null()),
+        )
+        .all(db)
+        .await?;
+    Ok(templates
+        .iter()
+        .find(|t| t.entity_type.is_some())
+        .or_else(|| templates.first())
+        .map(|t| t.body_html.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_fields() {
+        assert_eq!(
+            clean_entity_type(Some(" idea ")).unwrap().as_deref(),
+            Some("idea")
+        );
+        assert_eq!(clean_entity_type(Some("")).unwrap(), None);
+        assert!(clean_entity_type(Some("tweet")).is_err());
+        assert_eq!(clean_note_type(None), "main");
+        assert_eq!(clean_note_type(Some("highlight")), "highlight");
+        assert!(clean_name("  ").is_err());
+    }
+}
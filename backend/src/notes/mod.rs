@@ -11,6 +11,12 @@
 //! - notes_get_or_create: Get existing note or create empty one
 //! - notes_upsert: Create or update note content
 //! - notes_append_snippet: Append content with <hr /> divider
+//! - notes_export_markdown: A note as Markdown, stored or converted
+//! - notes_list_backlinks: Notes whose [[Title]] links point at an entity
+//! - export_entity_bundle: An entity with its notes, references, snapshots
+//!   and clips as a zip
+//! - notes_templates_list / notes_template_create / notes_template_update /
+//!   notes_template_delete: Starting content for new notes
 
 pub mod commands;
 pub mod components;
@@ -4,7 +4,10 @@ use tauri::{AppHandle, State};
 
 use crate::AppState;
 use crate::research::components::cockpit::ResearchCockpitOpenInput;
-use crate::research::components::{cockpit, connectors, reader};
+use crate::research::components::{
+    cockpit, connectors, reader, reader_anchor, reader_batch, reader_enex, reader_media,
+    reader_site_rules, reader_watch, site_credentials,
+};
 use crate::research::components::feed::{
     clear_news_articles_handler, dismiss_news_article_handler,
     get_news_article_handler, get_news_settings_handler, list_feed_sources_handler,
@@ -14,13 +17,41 @@ use crate::research::components::feed::{
     toggle_star_news_article_handler, mark_news_article_read_handler,
     toggle_feed_source_handler, update_feed_source_handler,
     delete_feed_source_handler, create_feed_source_handler, get_feed_source_handler,
+    search_news_articles_handler, NewsArticleSearchHit,
+    list_alert_rules_handler, create_alert_rule_handler, update_alert_rule_handler,
+    delete_alert_rule_handler, test_alert_rule_handler,
+    AlertRuleDto, AlertRuleTestResult, CreateAlertRuleInput, TestAlertRuleInput,
+    UpdateAlertRuleInput,
+    list_saved_searches_handler, create_saved_search_handler, update_saved_search_handler,
+    delete_saved_search_handler, run_saved_search_handler,
+    SavedSearchDto, CreateSavedSearchInput, UpdateSavedSearchInput,
+    list_mute_rules_handler, create_mute_rule_handler, update_mute_rule_handler,
+    delete_mute_rule_handler, preview_mute_rule_handler,
+    MuteRuleDto, MuteRulePreview, CreateMuteRuleInput, UpdateMuteRuleInput, PreviewMuteRuleInput,
     NewsArticleDto, NewsSettingsDto, SaveNewsSettingsInput, NewsSourceDto,
     FeedSourceDto, CreateFeedSourceInput, UpdateFeedSourceInput,
     SyncSourceResult, SyncAllResult,
 };
 use crate::research::components::reader::{
-    ClipCreateInput, ReaderClipDto, ReaderFetchInput, ReaderRefreshInput, ReaderReferenceDto,
-    ReaderResult, ReaderSnapshotDto, ReferenceUpdateInput,
+    ClipCreateInput, ClipListFilter, ClipUpdateInput, ReaderClipDto, ReaderFetchInput,
+    ReaderRefreshInput, ReaderReferenceDto, ReaderResult, ReaderSnapshotDto, ReadingProgressInput,
+    ReferenceSummaryResult, ReferenceUpdateInput, SummarizeReferenceInput,
+};
+use crate::research::components::reader_anchor::{ClipReanchorInput, ClipReanchorResult};
+use crate::research::components::reader_batch::{ReaderFetchBatchInput, ReaderFetchBatchResult};
+use crate::research::components::reader_enex::{ReaderEnexImportResult, ReaderImportEnexInput};
+use crate::research::components::reader_site_rules::{
+    ReaderSiteRuleDto, SiteRuleCreateInput, SiteRuleTestInput, SiteRuleTestResult,
+    SiteRuleUpdateInput,
+};
+use crate::research::components::reader_watch::ReaderWatchInput;
+use crate::research::components::reading_queue::{
+    self, ReadingQueueAddInput, ReadingQueueItemDto, ReadingQueueReorderInput,
+};
+use crate::research::components::reading_stats::{self, ReadingStatsDto, ReadingStatsInput};
+use crate::research::components::site_credentials::{SiteCredentialDto, SiteCredentialSaveInput};
+use crate::research::components::tagging::{
+    self, SuggestTagsBatchInput, SuggestTagsBatchResult, SuggestTagsInput, TagSuggestions,
 };
 use crate::research::dto::{
     CreateResearchAccountInput, ListResearchItemsQuery, ResearchAccountDto,
@@ -64,9 +95,48 @@ pub async fn reader_fetch(
     input: ReaderFetchInput,
     state: State<'_, AppState>,
 ) -> Result<ReaderResult, String> {
-    reader::reader_fetch(&state.db, &state.http_client, input)
-        .await
-        .map_err(|e| e.to_string())
+    reader::reader_fetch(
+        &state.db,
+        &state.http_clients.reader,
+        &state.config.current().storage.media_dir,
+        input,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Import a list of URLs, skipping ones that already have a reference
+#[tauri::command]
+pub async fn reader_fetch_batch(
+    input: ReaderFetchBatchInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderFetchBatchResult, String> {
+    reader_batch::reader_fetch_batch(
+        &state.db,
+        &state.http_clients.reader,
+        &state.config.current().storage.media_dir,
+        input,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Import an Evernote export as references, ideas and notes
+#[tauri::command]
+pub async fn reader_import_enex(
+    input: ReaderImportEnexInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderEnexImportResult, String> {
+    let import_path = input
+        .import_path
+        .ok_or_else(|| "import_path is required".to_string())?;
+    reader_enex::import_enex(
+        &state.db,
+        &state.config.current().storage.media_dir,
+        std::path::Path::new(&import_path),
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -74,9 +144,14 @@ pub async fn reader_refresh(
     input: ReaderRefreshInput,
     state: State<'_, AppState>,
 ) -> Result<ReaderResult, String> {
-    reader::reader_refresh(&state.db, &state.http_client, input)
-        .await
-        .map_err(|e| e.to_string())
+    reader::reader_refresh(
+        &state.db,
+        &state.http_clients.reader,
+        &state.config.current().storage.media_dir,
+        input,
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -100,6 +175,48 @@ pub async fn reader_reference_update(
         .map_err(|e| e.to_string())
 }
 
+/// Save scroll/percentage progress for a reference
+#[tauri::command]
+pub async fn update_reading_progress(
+    input: ReadingProgressInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderReferenceDto, String> {
+    reader::update_reading_progress(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// References that were started but not finished, most recent first
+#[tauri::command]
+pub async fn reader_continue_reading(
+    limit: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReaderReferenceDto>, String> {
+    reader::continue_reading(&state.db, limit)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Turn scheduled re-fetching on or off for a reference
+#[tauri::command]
+pub async fn reader_set_watch(
+    input: ReaderWatchInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderReferenceDto, String> {
+    reader_watch::set_reference_watch(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reader_watched_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<ReaderReferenceDto>, String> {
+    reader_watch::watched_references(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn reader_snapshots_list(
     reference_id: i64,
@@ -120,12 +237,36 @@ pub async fn reader_snapshot_get(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn reader_snapshot_delete(
+    snapshot_id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    reader::snapshot_delete(
+        &state.db,
+        &state.config.current().storage.media_dir,
+        snapshot_id,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reader_media_cleanup(
+    state: State<'_, AppState>,
+) -> Result<reader_media::ReaderMediaCleanupSummary, String> {
+    reader_media::cleanup_orphaned_media(&state.db, &state.config.current().storage.media_dir)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn reader_clips_list(
     reference_id: i64,
+    filter: Option<ClipListFilter>,
     state: State<'_, AppState>,
 ) -> Result<Vec<ReaderClipDto>, String> {
-    reader::clips_list(&state.db, reference_id)
+    reader::clips_list(&state.db, reference_id, filter.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())
 }
@@ -140,6 +281,16 @@ pub async fn reader_clip_create(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn reader_clip_update(
+    input: ClipUpdateInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderClipDto, String> {
+    reader::clip_update(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn reader_clip_delete(
     clip_id: i64,
@@ -150,6 +301,162 @@ pub async fn reader_clip_delete(
         .map_err(|e| e.to_string())
 }
 
+/// Move clips onto another snapshot of their reference, re-locating the quotes
+#[tauri::command]
+pub async fn reader_clips_reanchor(
+    input: ClipReanchorInput,
+    state: State<'_, AppState>,
+) -> Result<Vec<ClipReanchorResult>, String> {
+    reader_anchor::reanchor_clips(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reader_site_rules_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<ReaderSiteRuleDto>, String> {
+    reader_site_rules::site_rules_list(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reader_site_rule_create(
+    input: SiteRuleCreateInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderSiteRuleDto, String> {
+    reader_site_rules::site_rule_create(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reader_site_rule_update(
+    input: SiteRuleUpdateInput,
+    state: State<'_, AppState>,
+) -> Result<ReaderSiteRuleDto, String> {
+    reader_site_rules::site_rule_update(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reader_site_rule_delete(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    reader_site_rules::site_rule_delete(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Try a rule against a live page without saving anything
+#[tauri::command]
+pub async fn reader_site_rule_test(
+    input: SiteRuleTestInput,
+    state: State<'_, AppState>,
+) -> Result<SiteRuleTestResult, String> {
+    reader_site_rules::site_rule_test(&state.db, &state.http_clients.reader, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn summarize_reference(
+    input: SummarizeReferenceInput,
+    state: State<'_, AppState>,
+) -> Result<ReferenceSummaryResult, String> {
+    reader::summarize_reference(
+        &state.db,
+        &state.http_clients.api,
+        &state.config.current().ai,
+        input,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reading_queue_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<ReadingQueueItemDto>, String> {
+    reading_queue::queue_list(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Queue an article or reference (or move it when `position` is given)
+#[tauri::command]
+pub async fn reading_queue_add(
+    input: ReadingQueueAddInput,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReadingQueueItemDto>, String> {
+    reading_queue::queue_add(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reading_queue_remove(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReadingQueueItemDto>, String> {
+    reading_queue::queue_remove(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reading_queue_reorder(
+    input: ReadingQueueReorderInput,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReadingQueueItemDto>, String> {
+    reading_queue::queue_reorder(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Take the next item off the front of the queue
+#[tauri::command]
+pub async fn reading_queue_pop(
+    state: State<'_, AppState>,
+) -> Result<Option<ReadingQueueItemDto>, String> {
+    reading_queue::queue_pop(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Articles read per day/week, reading minutes, top sources, and streaks
+#[tauri::command]
+pub async fn reading_stats(
+    input: Option<ReadingStatsInput>,
+    state: State<'_, AppState>,
+) -> Result<ReadingStatsDto, String> {
+    reading_stats::reading_stats(&state.db, input.unwrap_or_default())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tag candidates for an article or reader reference, for the UI to accept
+#[tauri::command]
+pub async fn suggest_tags(
+    input: SuggestTagsInput,
+    state: State<'_, AppState>,
+) -> Result<TagSuggestions, String> {
+    tagging::suggest_tags_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Backfill tag suggestions for untagged items (`apply` writes them back)
+#[tauri::command]
+pub async fn suggest_tags_batch(
+    input: Option<SuggestTagsBatchInput>,
+    state: State<'_, AppState>,
+) -> Result<SuggestTagsBatchResult, String> {
+    tagging::suggest_tags_batch_handler(input.unwrap_or_default(), &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn open_live_page_window(
     app: AppHandle,
@@ -222,6 +529,29 @@ pub async fn list_news_articles(
     .map_err(|e| e.to_string())
 }
 
+/// Ranked full-text search over news articles
+///
+/// # Parameters
+/// - `query`: Free-form search text (terms are ANDed, last term prefix-matches)
+/// - `limit`: Max number of results (default: 50)
+/// - `offset`: Pagination offset (default: 0)
+/// - `include_dismissed`: Include dismissed articles (default: false)
+///
+/// # Returns
+/// Hits ordered by relevance with a body snippet and highlight offsets
+#[tauri::command]
+pub async fn search_news_articles(
+    query: String,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    include_dismissed: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<NewsArticleSearchHit>, String> {
+    search_news_articles_handler(query, limit, offset, include_dismissed, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn clear_news_articles(state: State<'_, AppState>) -> Result<u64, String> {
     clear_news_articles_handler(&state)
@@ -272,6 +602,36 @@ pub async fn research_test_account(
     Err("research_test_account is not available yet".into())
 }
 
+/// Stored per-domain headers and cookies (names only)
+#[tauri::command]
+pub async fn research_site_credentials_list(
+    state: State<'_, AppState>,
+) -> Result<Vec<SiteCredentialDto>, String> {
+    site_credentials::site_credentials_list(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn research_site_credential_save(
+    input: SiteCredentialSaveInput,
+    state: State<'_, AppState>,
+) -> Result<SiteCredentialDto, String> {
+    site_credentials::site_credential_save(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn research_site_credential_delete(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    site_credentials::site_credential_delete(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn research_list_streams(
     account_id: Option<i64>,
@@ -478,7 +838,7 @@ pub async fn test_feed_source_connection(
     source_id: i64,
     state: State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
-    test_feed_source_connection_handler(&state.db, &state.http_client, source_id)
+    test_feed_source_connection_handler(&state.db, &state.http_clients.api, source_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -488,7 +848,7 @@ pub async fn sync_feed_source_now(
     source_id: i64,
     state: State<'_, AppState>,
 ) -> Result<SyncSourceResult, String> {
-    sync_feed_source_now_handler(&state.db, &state.http_client, source_id)
+    sync_feed_source_now_handler(&state.db, &state.http_clients.api, source_id)
         .await
         .map_err(|e| e.to_string())
 }
@@ -497,7 +857,152 @@ pub async fn sync_feed_source_now(
 pub async fn sync_all_feed_sources(
     state: State<'_, AppState>,
 ) -> Result<SyncAllResult, String> {
-    sync_all_feed_sources_handler(&state.db, &state.http_client)
+    sync_all_feed_sources_handler(&state.db, &state.http_clients.api)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ===== Keyword Alert Rule Commands =====
+
+#[tauri::command]
+pub async fn list_alert_rules(state: State<'_, AppState>) -> Result<Vec<AlertRuleDto>, String> {
+    list_alert_rules_handler(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_alert_rule(
+    input: CreateAlertRuleInput,
+    state: State<'_, AppState>,
+) -> Result<AlertRuleDto, String> {
+    create_alert_rule_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_alert_rule(
+    input: UpdateAlertRuleInput,
+    state: State<'_, AppState>,
+) -> Result<AlertRuleDto, String> {
+    update_alert_rule_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_alert_rule(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    delete_alert_rule_handler(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Dry-run a rule against recent articles without flagging them
+#[tauri::command]
+pub async fn test_alert_rule(
+    input: TestAlertRuleInput,
+    state: State<'_, AppState>,
+) -> Result<AlertRuleTestResult, String> {
+    test_alert_rule_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ===== Saved Search Commands =====
+
+#[tauri::command]
+pub async fn list_saved_searches(
+    state: State<'_, AppState>,
+) -> Result<Vec<SavedSearchDto>, String> {
+    list_saved_searches_handler(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_saved_search(
+    input: CreateSavedSearchInput,
+    state: State<'_, AppState>,
+) -> Result<SavedSearchDto, String> {
+    create_saved_search_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_saved_search(
+    input: UpdateSavedSearchInput,
+    state: State<'_, AppState>,
+) -> Result<SavedSearchDto, String> {
+    update_saved_search_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_saved_search(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    delete_saved_search_handler(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Execute a saved search with its stored query, filters, and sort
+#[tauri::command]
+pub async fn run_saved_search(
+    id: i64,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<NewsArticleDto>, String> {
+    run_saved_search_handler(id, limit, offset, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ===== Mute Rule Commands =====
+
+#[tauri::command]
+pub async fn list_mute_rules(state: State<'_, AppState>) -> Result<Vec<MuteRuleDto>, String> {
+    list_mute_rules_handler(&state.db)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_mute_rule(
+    input: CreateMuteRuleInput,
+    state: State<'_, AppState>,
+) -> Result<MuteRuleDto, String> {
+    create_mute_rule_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn update_mute_rule(
+    input: UpdateMuteRuleInput,
+    state: State<'_, AppState>,
+) -> Result<MuteRuleDto, String> {
+    update_mute_rule_handler(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_mute_rule(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    delete_mute_rule_handler(&state.db, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Preview which recent articles a rule would hide without muting them
+#[tauri::command]
+pub async fn preview_mute_rule(
+    input: PreviewMuteRuleInput,
+    state: State<'_, AppState>,
+) -> Result<MuteRulePreview, String> {
+    preview_mute_rule_handler(&state.db, input)
         .await
         .map_err(|e| e.to_string())
 }
@@ -11,6 +11,7 @@ use crate::research::dto::{
     CreateResearchAccountInput, ListResearchItemsQuery, ResearchAccountDto, ResearchCapability,
     ResearchItemDto, ResearchStreamDto, UpdateResearchAccountInput, UpsertResearchStreamInput,
 };
+use crate::research::components::site_credentials::{client_with_headers, headers_for_url};
 use crate::research::entities::{accounts, items, streams};
 use crate::research::helpers::format_naive;
 
@@ -349,8 +350,21 @@ pub async fn sync_stream_now(stream_id: i64, state: &AppState) -> Result<(), Str
         .and_then(|c| serde_json::from_str(c).ok())
         .unwrap_or_else(|| json!({}));
 
+    // Stored site headers go on a dedicated client so connectors need no changes
+    let site_headers = match connector.base_url() {
+        Some(url) => headers_for_url(&state.db, url)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+    let client = match site_headers {
+        Some(site) => client_with_headers(&state.config.current().http_clients.api, &site)
+            .map_err(|e| e.to_string())?,
+        None => reqwest::Client::clone(&state.http_clients.api),
+    };
+
     let items = connector
-        .sync_stream(&account_auth, &stream_cfg, &state.http_client)
+        .sync_stream(&account_auth, &stream_cfg, &client)
         .await?;
 
     let count = items.len();
@@ -0,0 +1,506 @@
+//! Keyword alert rules module
+//!
+//! Rules are evaluated against articles inserted by a sync. Matching
+//! articles are flagged with the first matching rule, and `news_alert`
+//! events are emitted once the sync finishes.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel,
+    QueryFilter, QueryOrder, QuerySelect, Set,
+};
+use tracing::{error, info, instrument};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::events::EventEmitter;
+
+use super::articles::article_to_dto;
+use super::entities::alert_rules::{self, Entity as AlertRuleEntity};
+use super::entities::articles::{self as news_articles, Entity as EntityNewsArticles};
+use super::types::{
+    parse_vec, AlertRuleDto, AlertRuleTestResult, CreateAlertRuleInput, NewsAlertArticle,
+    NewsAlertEvent, TestAlertRuleInput, UpdateAlertRuleInput,
+};
+
+/// Event name emitted for each rule that matched new articles
+pub const NEWS_ALERT_EVENT: &str = "news_alert";
+
+const DEFAULT_TEST_LIMIT: u64 = 200;
+
+/// Rule with lowercased terms, ready for matching
+#[derive(Debug, Default)]
+pub(crate) struct AlertMatcher {
+    keywords: Vec<String>,
+    sources: Vec<String>,
+    languages: Vec<String>,
+    match_all: bool,
+}
+
+impl AlertMatcher {
+    pub(crate) fn new(
+        keywords: &[String],
+        sources: &[String],
+        languages: &[String],
+        match_all: bool,
+    ) -> Self {
+        let normalize = |values: &[String]| -> Vec<String> {
+            values
+                .iter()
+                .map(|v| v.trim().to_lowercase())
+                .filter(|v| !v.is_empty())
+                .collect()
+        };
+        Self {
+            keywords: normalize(keywords),
+            sources: normalize(sources),
+            languages: normalize(languages),
+            match_all,
+        }
+    }
+
+    fn from_rule(rule: &alert_rules::Model) -> Self {
+        Self::new(
+            &parse_vec(&Some(rule.keywords.clone())),
+            &parse_vec(&rule.sources),
+            &parse_vec(&rule.languages),
+            rule.match_all == 1,
+        )
+    }
+
+    /// Keywords are case-insensitive substrings of title, excerpt, or content
+    pub(crate) fn matches(&self, article: &news_articles::Model) -> bool {
+        if self.keywords.is_empty() {
+            return false;
+        }
+
+        if !self.sources.is_empty() {
+            let source_match = [&article.source_id, &article.source_name, &article.source_domain]
+                .into_iter()
+                .flatten()
+                .any(|s| self.sources.contains(&s.to_lowercase()));
+            if !source_match {
+                return false;
+            }
+        }
+
+        if !self.languages.is_empty() {
+            let language = article.language.as_deref().unwrap_or_default().to_lowercase();
+            if !self.languages.contains(&language) {
+                return false;
+            }
+        }
+
+        let haystack = [
+            Some(article.title.as_str()),
+            article.excerpt.as_deref(),
+            article.content.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase();
+
+        if self.match_all {
+            self.keywords.iter().all(|k| haystack.contains(k.as_str()))
+        } else {
+            self.keywords.iter().any(|k| haystack.contains(k.as_str()))
+        }
+    }
+}
+
+fn rule_to_dto(m: alert_rules::Model) -> AlertRuleDto {
+    AlertRuleDto {
+        id: m.id,
+        name: m.name,
+        keywords: parse_vec(&Some(m.keywords)),
+        sources: parse_vec(&m.sources),
+        languages: parse_vec(&m.languages),
+        match_all: m.match_all == 1,
+        enabled: m.enabled == 1,
+        match_count: m.match_count,
+        last_matched_at: m.last_matched_at.map(|d| d.to_rfc3339()),
+        created_at: m.created_at.to_rfc3339(),
+        updated_at: m.updated_at.to_rfc3339(),
+    }
+}
+
+/// Trim and drop blank keywords; a rule needs at least one
+fn clean_keywords(keywords: Vec<String>) -> AppResult<String> {
+    let keywords: Vec<String> = keywords
+        .into_iter()
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .collect();
+    if keywords.is_empty() {
+        return Err(AppError::validation("keywords", "At least one keyword is required"));
+    }
+    Ok(serde_json::to_string(&keywords).unwrap_or_else(|_| "[]".into()))
+}
+
+/// Empty lists are stored as NULL (match everything)
+fn optional_list(values: Vec<String>) -> Option<String> {
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+    (!values.is_empty()).then(|| serde_json::to_string(&values).unwrap_or_else(|_| "[]".into()))
+}
+
+async fn find_rule(db: &DatabaseConnection, id: i64) -> AppResult<alert_rules::Model> {
+    AlertRuleEntity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Validation {
+            field: "id".to_string(),
+            reason: "Alert rule not found".to_string(),
+            invalid_value: Some(id.to_string()),
+        })
+}
+
+/// List all alert rules
+#[instrument(skip(db))]
+pub async fn list_alert_rules_handler(db: &DatabaseConnection) -> AppResult<Vec<AlertRuleDto>> {
+    let rules = AlertRuleEntity::find()
+        .order_by_asc(alert_rules::Column::Name)
+        .all(db)
+        .await?;
+    Ok(rules.into_iter().map(rule_to_dto).collect())
+}
+
+/// Create an alert rule
+#[instrument(skip(db, input), fields(name = %input.name))]
+pub async fn create_alert_rule_handler(
+    db: &DatabaseConnection,
+    input: CreateAlertRuleInput,
+) -> AppResult<AlertRuleDto> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Name is required"));
+    }
+
+    let now = Utc::now();
+    let rule = alert_rules::ActiveModel {
+        name: Set(name),
+        keywords: Set(clean_keywords(input.keywords)?),
+        sources: Set(optional_list(input.sources.unwrap_or_default())),
+        languages: Set(optional_list(input.languages.unwrap_or_default())),
+        match_all: Set(input.match_all.unwrap_or(false) as i32),
+        enabled: Set(input.enabled.unwrap_or(true) as i32),
+        match_count: Set(0),
+        last_matched_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    info!(rule_id = rule.id, "Alert rule created");
+    Ok(rule_to_dto(rule))
+}
+
+/// Update an alert rule
+#[instrument(skip(db, input), fields(rule_id = input.id))]
+pub async fn update_alert_rule_handler(
+    db: &DatabaseConnection,
+    input: UpdateAlertRuleInput,
+) -> AppResult<AlertRuleDto> {
+    let mut active = find_rule(db, input.id).await?.into_active_model();
+
+    if let Some(name) = input.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::validation("name", "Name is required"));
+        }
+        active.name = Set(name);
+    }
+    if let Some(keywords) = input.keywords {
+        active.keywords = Set(clean_keywords(keywords)?);
+    }
+    if let Some(sources) = input.sources {
+        active.sources = Set(optional_list(sources));
+    }
+    if let Some(languages) = input.languages {
+        active.languages = Set(optional_list(languages));
+    }
+    if let Some(match_all) = input.match_all {
+        active.match_all = Set(match_all as i32);
+    }
+    if let Some(enabled) = input.enabled {
+        active.enabled = Set(enabled as i32);
+    }
+    active.updated_at = Set(Utc::now());
+
+    Ok(rule_to_dto(active.update(db).await?))
+}
+
+/// Delete an alert rule; flagged articles keep their flag
+#[instrument(skip(db))]
+pub async fn delete_alert_rule_handler(db: &DatabaseConnection, id: i64) -> AppResult<()> {
+    let res = AlertRuleEntity::delete_by_id(id).exec(db).await?;
+    if res.rows_affected == 0 {
+        return Err(AppError::Validation {
+            field: "id".to_string(),
+            reason: "Alert rule not found".to_string(),
+            invalid_value: Some(id.to_string()),
+        });
+    }
+    Ok(())
+}
+
+/// Dry-run a rule against the most recent articles without flagging anything
+#[instrument(skip(db, input), fields(rule_id = ?input.rule_id))]
+pub async fn test_alert_rule_handler(
+    db: &DatabaseConnection,
+    input: TestAlertRuleInput,
+) -> AppResult<AlertRuleTestResult> {
+    let saved = match input.rule_id {
+        Some(id) => Some(find_rule(db, id).await?),
+        None => None,
+    };
+    let saved_list = |f: fn(&alert_rules::Model) -> Option<String>| {
+        saved.as_ref().map(|r| parse_vec(&f(r))).unwrap_or_default()
+    };
+
+    let keywords = input
+        .keywords
+        .unwrap_or_else(|| saved_list(|r| Some(r.keywords.clone())));
+    let sources = input.sources.unwrap_or_else(|| saved_list(|r| r.sources.clone()));
+    let languages = input
+        .languages
+        .unwrap_or_else(|| saved_list(|r| r.languages.clone()));
+    let match_all = input
+        .match_all
+        .unwrap_or_else(|| saved.as_ref().is_some_and(|r| r.match_all == 1));
+
+    let matcher = AlertMatcher::new(&keywords, &sources, &languages, match_all);
+    if matcher.keywords.is_empty() {
+        return Err(AppError::validation("keywords", "At least one keyword is required"));
+    }
+
+    let articles = EntityNewsArticles::find()
+        .filter(news_articles::Column::UserId.eq(1))
+        .order_by_desc(news_articles::Column::FetchedAt)
+        .limit(input.limit.unwrap_or(DEFAULT_TEST_LIMIT))
+        .all(db)
+        .await?;
+
+    let scanned = articles.len();
+    let matched = articles
+        .into_iter()
+        .filter(|a| matcher.matches(a))
+        .map(article_to_dto)
+        .collect();
+    Ok(AlertRuleTestResult { scanned, matched })
+}
+
+/// Flag newly inserted articles that match an enabled rule
+///
+/// Returns the number of articles flagged. Articles that are already
+/// flagged or have been muted are left alone.
+pub(crate) async fn evaluate_alert_rules(
+    db: &DatabaseConnection,
+    article_ids: &[i64],
+) -> AppResult<usize> {
+    if article_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let rules = AlertRuleEntity::find()
+        .filter(alert_rules::Column::Enabled.eq(1))
+        .order_by_asc(alert_rules::Column::Id)
+        .all(db)
+        .await?;
+    if rules.is_empty() {
+        return Ok(0);
+    }
+    let matchers: Vec<(alert_rules::Model, AlertMatcher)> = rules
+        .into_iter()
+        .map(|r| {
+            let matcher = AlertMatcher::from_rule(&r);
+            (r, matcher)
+        })
+        .collect();
+
+    let articles = EntityNewsArticles::find()
+        .filter(news_articles::Column::Id.is_in(article_ids.to_vec()))
+        .filter(news_articles::Column::AlertRuleId.is_null())
+        .filter(news_articles::Column::MuteRuleId.is_null())
+        .all(db)
+        .await?;
+
+    let now = Utc::now();
+    let mut hits: BTreeMap<i64, i32> = BTreeMap::new();
+    for article in articles {
+        let Some((rule, _)) = matchers.iter().find(|(_, m)| m.matches(&article)) else {
+            continue;
+        };
+        let rule_id = rule.id;
+        let mut active = article.into_active_model();
+        active.alert_rule_id = Set(Some(rule_id));
+        active.alerted_at = Set(Some(now));
+        active.update(db).await?;
+        *hits.entry(rule_id).or_default() += 1;
+    }
+
+    for (rule, _) in matchers {
+        let Some(count) = hits.get(&rule.id).copied() else {
+            continue;
+        };
+        let match_count = rule.match_count + count;
+        let mut active = rule.into_active_model();
+        active.match_count = Set(match_count);
+        active.last_matched_at = Set(Some(now));
+        active.update(db).await?;
+    }
+
+    let flagged = hits.values().sum::<i32>() as usize;
+    if flagged > 0 {
+        info!(target: "news_sync", flagged, rules = hits.len(), "Alert rules matched");
+    }
+    Ok(flagged)
+}
+
+/// Evaluate rules after a sync, logging instead of failing the sync
+pub(crate) async fn evaluate_alert_rules_logged(
+    db: &DatabaseConnection,
+    article_ids: &[i64],
+) -> usize {
+    match evaluate_alert_rules(db, article_ids).await {
+        Ok(flagged) => flagged,
+        Err(e) => {
+            error!(target: "news_sync", "Alert rule evaluation failed: {}", e);
+            0
+        }
+    }
+}
+
+/// Emit one `news_alert` event per rule for articles flagged since `since`
+///
+/// Called by the scheduler and the bridge after a sync completes.
+pub async fn emit_news_alerts_since(
+    emitter: &(dyn EventEmitter),
+    db: &DatabaseConnection,
+    since: DateTime<Utc>,
+) -> AppResult<usize> {
+    let articles = EntityNewsArticles::find()
+        .filter(news_articles::Column::AlertedAt.gte(since))
+        .filter(news_articles::Column::AlertRuleId.is_not_null())
+        .order_by_asc(news_articles::Column::Id)
+        .all(db)
+        .await?;
+    if articles.is_empty() {
+        return Ok(0);
+    }
+
+    let mut by_rule: BTreeMap<i64, Vec<NewsAlertArticle>> = BTreeMap::new();
+    for a in articles {
+        if let Some(rule_id) = a.alert_rule_id {
+            by_rule.entry(rule_id).or_default().push(NewsAlertArticle {
+                id: a.id,
+                title: a.title,
+                url: a.url,
+            });
+        }
+    }
+
+    let names: BTreeMap<i64, String> = AlertRuleEntity::find()
+        .filter(alert_rules::Column::Id.is_in(by_rule.keys().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.id, r.name))
+        .collect();
+
+    let mut emitted = 0;
+    for (rule_id, articles) in by_rule {
+        let event = NewsAlertEvent {
+            rule_id,
+            rule_name: names.get(&rule_id).cloned().unwrap_or_default(),
+            articles,
+        };
+        if let Err(e) = emitter.emit(NEWS_ALERT_EVENT, event).await {
+            error!(target: "news_sync", "Failed to emit news alert event: {}", e);
+        } else {
+            emitted += 1;
+        }
+    }
+    Ok(emitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, source_id: &str, language: &str) -> news_articles::Model {
+        let now = Utc::now();
+        news_articles::Model {
+            id: 1,
+            user_id: 1,
+            provider: "newsdata".into(),
+            provider_article_id: None,
+            source_name: None,
+            source_domain: None,
+            source_id: Some(source_id.into()),
+            title: title.into(),
+            excerpt: None,
+            content: None,
+            tags: None,
+            url: None,
+            image_url: None,
+            language: Some(language.into()),
+            category: None,
+            country: None,
+            published_at: None,
+            fetched_at: now,
+            added_via: "sync".into(),
+            is_starred: 0,
+            is_dismissed: 0,
+            is_read: 0,
+            added_to_ideas_at: None,
+            dismissed_at: None,
+            is_pinned: 0,
+            created_at: now,
+            updated_at: now,
+            feed_source_id: None,
+            alert_rule_id: None,
+            alerted_at: None,
+            mute_rule_id: None,
+            muted_at: None,
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_alert_matcher() {
+        let a = article("Senate passes Climate bill", "bbc", "english");
+
+        let any = AlertMatcher::new(&strings(&["climate", "tariffs"]), &[], &[], false);
+        assert!(any.matches(&a));
+
+        let all = AlertMatcher::new(&strings(&["climate", "tariffs"]), &[], &[], true);
+        assert!(!all.matches(&a));
+
+        let wrong_source =
+            AlertMatcher::new(&strings(&["climate"]), &strings(&["CNN"]), &[], false);
+        assert!(!wrong_source.matches(&a));
+
+        let scoped = AlertMatcher::new(
+            &strings(&["climate"]),
+            &strings(&["BBC"]),
+            &strings(&["english"]),
+            false,
+        );
+        assert!(scoped.matches(&a));
+
+        // A rule with no keywords never fires
+        assert!(!AlertMatcher::new(&[], &[], &[], false).matches(&a));
+    }
+}
@@ -5,8 +5,8 @@
 
 use tracing::instrument;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, QuerySelect,
-    Set,
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, IntoActiveModel,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select, Set,
 };
 use sea_orm::prelude::Expr;
 
@@ -14,6 +14,8 @@ use crate::core::components::errors::{AppError, AppResult};
 use super::entities::articles::{self as news_articles, Entity as EntityNewsArticles};
 use super::entities::feed_sources::{self as feed_sources, Entity as FeedSourceEntity};
 
+use super::relevance::{record_signal_logged, sort_by_relevance, Signal, RELEVANCE_CANDIDATES};
+use super::search::search_condition;
 use super::types::{NewsArticleDto, parse_vec};
 
 /// Convert article model to DTO
@@ -40,31 +42,34 @@ pub(crate) fn article_to_dto(m: news_articles::Model) -> NewsArticleDto {
         published_at: m.published_at.map(|d| d.to_rfc3339()),
         added_to_ideas_at: m.added_to_ideas_at.map(|d| d.to_rfc3339()),
         dismissed_at: m.dismissed_at.map(|d| d.to_rfc3339()),
+        alert_rule_id: m.alert_rule_id,
+        alerted_at: m.alerted_at.map(|d| d.to_rfc3339()),
+        mute_rule_id: m.mute_rule_id,
     }
 }
 
-/// List news articles with filtering, search, and pagination
-/// 
-/// Supports filtering by read status, dismissal, search text, source, date range, starred.
-/// Returns articles sorted by specified order (default: newest first).
-#[instrument(skip(state), fields(limit = ?limit, offset = ?offset))]
-pub async fn list_news_articles_handler(
-    status: Option<String>,
-    limit: Option<u64>,
-    offset: Option<u64>,
+/// Articles matching the list filters, before sorting and pagination
+fn filtered_articles(
+    backend: DatabaseBackend,
+    status: Option<&str>,
     include_dismissed: Option<bool>,
-    search: Option<String>,
+    search: Option<&str>,
     source_id: Option<i64>,
     starred: Option<bool>,
-    start_date: Option<String>,
-    end_date: Option<String>,
-    sort_by: Option<String>,
-    state: &crate::AppState,
-) -> AppResult<Vec<NewsArticleDto>> {
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Select<EntityNewsArticles> {
     let mut query = EntityNewsArticles::find().filter(news_articles::Column::UserId.eq(1));
+
+    // Muted articles only appear in the muted view
+    if status == Some("muted") {
+        query = query.filter(news_articles::Column::MuteRuleId.is_not_null());
+    } else {
+        query = query.filter(news_articles::Column::MuteRuleId.is_null());
+    }
     
-    // Status filter (unread, dismissed, ideas, all)
-    match status.as_deref() {
+    // Status filter (unread, dismissed, ideas, alerts, muted, all)
+    match status {
         Some("unread") => {
             query = query
                 .filter(news_articles::Column::DismissedAt.is_null())
@@ -74,6 +79,7 @@ pub async fn list_news_articles_handler(
         }
         Some("dismissed") => query = query.filter(news_articles::Column::IsDismissed.eq(1)),
         Some("ideas") => query = query.filter(news_articles::Column::AddedToIdeasAt.is_not_null()),
+        Some("alerts") => query = query.filter(news_articles::Column::AlertedAt.is_not_null()),
         _ => {
             if include_dismissed != Some(true) {
                 query = query.filter(news_articles::Column::IsDismissed.eq(0));
@@ -98,30 +104,69 @@ pub async fn list_news_articles_handler(
     
     // Date range filters
     if let Some(start) = start_date {
-        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&start) {
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(start) {
             query = query.filter(news_articles::Column::PublishedAt.gte(dt.with_timezone(&chrono::Utc)));
         }
     }
     if let Some(end) = end_date {
-        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&end) {
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(end) {
             query = query.filter(news_articles::Column::PublishedAt.lte(dt.with_timezone(&chrono::Utc)));
         }
     }
     
-    // Search filter
-    if let Some(term) = search {
-        if !term.trim().is_empty() {
-            let like = format!("%{}%", term.trim());
-            query = query.filter(
-                news_articles::Column::Title
-                    .like(like.clone())
-                    .or(news_articles::Column::Excerpt.like(like.clone()))
-                    .or(news_articles::Column::Content.like(like)),
-            );
-        }
+    // Search filter (full-text index, see search module)
+    if let Some(condition) = search.and_then(|input| search_condition(backend, input)) {
+        query = query.filter(condition);
     }
-    let mut items_query = query;
+    query
+}
+
+/// List news articles with filtering, search, and pagination
+/// 
+/// Supports filtering by read status, dismissal, search text, source, date range, starred.
+/// Returns articles sorted by specified order (default: newest first). `relevance`
+/// ranks the newest `RELEVANCE_CANDIDATES` matches by learned engagement weights.
+#[instrument(skip(state), fields(limit = ?limit, offset = ?offset))]
+pub async fn list_news_articles_handler(
+    status: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    include_dismissed: Option<bool>,
+    search: Option<String>,
+    source_id: Option<i64>,
+    starred: Option<bool>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    sort_by: Option<String>,
+    state: &crate::AppState,
+) -> AppResult<Vec<NewsArticleDto>> {
+    let mut items_query = filtered_articles(
+        state.db.get_database_backend(),
+        status.as_deref(),
+        include_dismissed,
+        search.as_deref(),
+        source_id,
+        starred,
+        start_date.as_deref(),
+        end_date.as_deref(),
+    );
     
+    if sort_by.as_deref() == Some("relevance") {
+        let candidates = items_query
+            .order_by_desc(news_articles::Column::PublishedAt)
+            .order_by_desc(news_articles::Column::FetchedAt)
+            .limit(RELEVANCE_CANDIDATES)
+            .all(&state.db)
+            .await?;
+        let ranked = sort_by_relevance(&state.db, candidates).await?;
+        return Ok(ranked
+            .into_iter()
+            .skip(offset.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(100) as usize)
+            .map(article_to_dto)
+            .collect());
+    }
+
     // Sorting
     match sort_by.as_deref() {
         Some("oldest") => {
@@ -152,6 +197,40 @@ pub async fn list_news_articles_handler(
     Ok(items.into_iter().map(article_to_dto).collect())
 }
 
+/// Number of articles `list_news_articles_handler` would page through
+///
+/// Takes the same filters; `relevance` sorting only ever ranks the newest
+/// `RELEVANCE_CANDIDATES` matches, so the count is capped to match.
+#[allow(clippy::too_many_arguments)]
+pub async fn count_news_articles_handler(
+    status: Option<&str>,
+    include_dismissed: Option<bool>,
+    search: Option<&str>,
+    source_id: Option<i64>,
+    starred: Option<bool>,
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+    sort_by: Option<&str>,
+    state: &crate::AppState,
+) -> AppResult<u64> {
+    let total = filtered_articles(
+        state.db.get_database_backend(),
+        status,
+        include_dismissed,
+        search,
+        source_id,
+        starred,
+        start_date,
+        end_date,
+    )
+    .count(&state.db)
+    .await?;
+    if sort_by == Some("relevance") {
+        return Ok(total.min(RELEVANCE_CANDIDATES));
+    }
+    Ok(total)
+}
+
 /// Delete all news articles for the current user and reset feed source counters.
 pub async fn clear_news_articles_handler(
     state: &crate::AppState,
@@ -192,6 +271,9 @@ pub async fn dismiss_news_article_handler(id: i64, state: &crate::AppState) -> A
         return Err(AppError::other("Article not found"));
     };
     
+    if m.is_dismissed == 0 {
+        record_signal_logged(&state.db, &m, Signal::Dismiss).await;
+    }
     let mut active = m.into_active_model();
     active.dismissed_at = Set(Some(chrono::Utc::now()));
     active.is_dismissed = Set(1);
@@ -217,6 +299,10 @@ pub async fn toggle_star_news_article_handler(
         return Err(AppError::other("Article not found"));
     };
     
+    if (m.is_starred == 1) != starred {
+        let signal = if starred { Signal::Star } else { Signal::Unstar };
+        record_signal_logged(&state.db, &m, signal).await;
+    }
     let mut active = m.into_active_model();
     active.is_starred = Set(if starred { 1 } else { 0 });
     active.updated_at = Set(chrono::Utc::now());
@@ -242,6 +328,7 @@ pub async fn mark_news_article_read_handler(id: i64, state: &crate::AppState) ->
         return Ok(());
     }
     
+    record_signal_logged(&state.db, &m, Signal::Read).await;
     let read_at = chrono::Utc::now();
     let mut active = m.into_active_model();
     active.is_read = Set(1);
@@ -0,0 +1,31 @@
+//! Alert rules entity model
+//!
+//! Keyword rules evaluated against newly synced articles. `keywords`,
+//! `sources` and `languages` hold JSON string arrays.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "alert_rules")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub keywords: String,
+    /// Source ids/names/domains; empty matches every source
+    pub sources: Option<String>,
+    /// Language codes; empty matches every language
+    pub languages: Option<String>,
+    /// 1 = every keyword must appear, 0 = any keyword
+    pub match_all: i32,
+    pub enabled: i32,
+    pub match_count: i32,
+    pub last_matched_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+//! Article engagement signals entity model
+//!
+//! `features` is a JSON snapshot of the article's features at signal time,
+//! so weights can be rebuilt after the article itself is pruned.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "article_signals")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub article_id: i64,
+    /// read, star, unstar, dismiss
+    pub signal: String,
+    pub features: String,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
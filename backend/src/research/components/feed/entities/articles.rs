@@ -32,6 +32,11 @@ pub struct Model {
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
     pub feed_source_id: Option<i64>,
+    pub alert_rule_id: Option<i64>,
+    pub alerted_at: Option<DateTimeUtc>,
+    /// Mute rule that hid this article from listings
+    pub mute_rule_id: Option<i64>,
+    pub muted_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -4,6 +4,7 @@
 //! Represents feed source plugins (NewsData.io, Reddit, RSS, etc.)
 //! Each feed source has its own system_task for scheduled syncing
 
+use schemars::JsonSchema;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -104,7 +105,7 @@ impl SourceType {
 }
 
 /// Source configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SourceConfig {
     /// Sync schedule (cron expression)
     pub schedule: Option<String>,
@@ -122,7 +123,7 @@ pub struct SourceConfig {
     pub rss: Option<RssConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct NewsDataConfig {
     pub language: Option<String>,
     pub countries: Option<Vec<String>>,
@@ -132,7 +133,7 @@ pub struct NewsDataConfig {
     pub query: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RedditConfig {
     pub subreddits: Vec<String>,
     pub sort: Option<String>, // hot, new, top, rising
@@ -140,7 +141,7 @@ pub struct RedditConfig {
     pub limit: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RssConfig {
     pub feed_url: String,
     pub update_interval_minutes: Option<u32>,
@@ -3,8 +3,13 @@
 //! Database entity definitions using SeaORM.
 //! These represent the core database tables for the news feed system.
 
+pub mod alert_rules;
+pub mod article_signals;
 pub mod articles;
 pub mod feed_sources;
+pub mod mute_rules;
+pub mod relevance_weights;
+pub mod saved_searches;
 pub mod settings;
 pub mod sources;
 
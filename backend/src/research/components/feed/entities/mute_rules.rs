@@ -0,0 +1,24 @@
+//! Mute rules entity model
+//!
+//! Rules that hide matching articles from listings. `rule_type` is
+//! `source`, `domain` or `keyword`.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "mute_rules")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub rule_type: String,
+    /// Stored lowercased
+    pub pattern: String,
+    pub enabled: i32,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+//! Learned relevance weights entity model
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "relevance_weights")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// source, category, keyword
+    pub feature_type: String,
+    pub feature: String,
+    pub weight: f64,
+    pub signal_count: i32,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
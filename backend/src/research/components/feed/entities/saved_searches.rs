@@ -0,0 +1,29 @@
+//! Saved searches entity model
+//!
+//! `filters` holds a JSON `SavedSearchFilters` object.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "saved_searches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub query: Option<String>,
+    pub filters: Option<String>,
+    pub sort_by: Option<String>,
+    /// Executed by the `saved_searches_run` system task
+    pub run_on_schedule: i32,
+    /// Pin articles that newly match on a scheduled run
+    pub pin_new_matches: i32,
+    pub last_run_at: Option<DateTimeUtc>,
+    pub last_match_count: i32,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
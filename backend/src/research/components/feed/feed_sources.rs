@@ -5,16 +5,20 @@
 
 use crate::core::components::crypto;
 use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
 use crate::research::components::feed::entities::feed_sources::{
     self, ActiveModel as ActiveFeedSource, Entity as FeedSourceEntity,
 };
 use crate::research::components::feed::entities::articles::{
     ActiveModel as ActiveNewsArticle, Entity as NewsArticleEntity, Column as NewsArticleColumn,
 };
-use crate::research::components::feed::plugin::FeedSource;
+use crate::research::components::feed::alerts::evaluate_alert_rules_logged;
+use crate::research::components::feed::mutes::apply_mute_rules_logged;
+use crate::research::components::feed::plugin::{FeedArticle, FeedSource};
 use crate::research::components::feed::plugins::NewsDataPlugin;
 use crate::research::components::feed::types::{
-    CreateFeedSourceInput, FeedSourceDto, SyncAllResult, SyncSourceResult, UpdateFeedSourceInput,
+    CreateFeedSourceInput, FeedSourceDto, FeedSyncParams, SyncAllResult, SyncSourceResult,
+    UpdateFeedSourceInput,
 };
 use crate::system::components::scheduler::entities::{
     ActiveModel as ActiveTask, Entity as TaskEntity,
@@ -25,6 +29,9 @@ use sea_orm::{
 };
 use tracing::{info, instrument, warn};
 
+/// Source types whose plugin can't fetch without an API key
+pub const KEYED_SOURCE_TYPES: &[&str] = &["newsdata"];
+
 /// List all feed sources with metadata
 #[instrument(skip(db))]
 pub async fn list_feed_sources_handler(db: &DatabaseConnection) -> AppResult<Vec<FeedSourceDto>> {
@@ -164,6 +171,8 @@ pub async fn create_feed_source_handler(
         });
     }
 
+    // Held until the insert, so a key rotation can't slip in between
+    let _secrets = crypto::secret_write().await;
     // Encrypt API key if provided
     let api_key_encrypted = if let Some(api_key) = &input.api_key {
         Some(crypto::encrypt_api_key(api_key).map_err(|e| AppError::Crypto {
@@ -323,6 +332,8 @@ pub async fn update_feed_source_handler(
         }
     }
 
+    // Held until the update, so a key rotation can't slip in between
+    let _secrets = crypto::secret_write().await;
     if let Some(api_key) = input.api_key {
         let encrypted = crypto::encrypt_api_key(&api_key).map_err(|e| AppError::Crypto {
             operation: "encrypt API key".to_string(),
@@ -357,6 +368,7 @@ pub async fn update_feed_source_handler(
                 .into_active_model();
 
             task_active.frequency_cron = Set(Some(schedule));
+            task_active.interval_seconds = Set(None);
             task_active.updated_at = Set(chrono::Utc::now());
             task_active.update(db).await.map_err(|e| AppError::DatabaseQuery {
                 operation: "update task schedule".to_string(),
@@ -452,7 +464,7 @@ pub async fn toggle_feed_source_handler(
 #[instrument(skip(db, http_client), fields(source_id = source_id))]
 pub async fn test_feed_source_connection_handler(
     db: &DatabaseConnection,
-    http_client: &reqwest::Client,
+    http_client: &HttpClient,
     source_id: i64,
 ) -> AppResult<serde_json::Value> {
     info!("Testing feed source connection");
@@ -503,7 +515,7 @@ pub async fn test_feed_source_connection_handler(
 #[instrument(skip(db, http_client), fields(source_id = source_id))]
 pub async fn sync_feed_source_now_handler(
     db: &DatabaseConnection,
-    http_client: &reqwest::Client,
+    http_client: &HttpClient,
     source_id: i64,
 ) -> AppResult<SyncSourceResult> {
     info!("Manual sync triggered for feed source");
@@ -606,68 +618,17 @@ pub async fn sync_feed_source_now_handler(
 
     // Store articles in database
     let mut added_count = 0;
+    let mut new_ids: Vec<i64> = Vec::new();
     for article in articles.articles {
-        // Use provider_article_id for uniqueness check
-        let provider_id = article.provider_article_id.clone().unwrap_or_else(|| format!("{}_{}", article.url.clone().unwrap_or_default(), article.title));
-        
-        let existing = NewsArticleEntity::find()
-            .filter(NewsArticleColumn::ProviderArticleId.eq(Some(provider_id.clone())))
-            .filter(NewsArticleColumn::Provider.eq(&source.source_type))
-            .one(db)
-            .await
-            .map_err(|e| AppError::DatabaseQuery {
-                operation: "check existing article".to_string(),
-                source: e,
-            })?;
-
-        if existing.is_none() {
-            let now = chrono::Utc::now();
-            let tags_json = if !article.tags.is_empty() {
-                Some(serde_json::to_string(&article.tags).unwrap_or_default())
-            } else {
-                None
-            };
-            
-            let new_article = ActiveNewsArticle {
-                user_id: Set(1), // TODO: Get from context
-                feed_source_id: Set(Some(source.id)),
-                provider: Set(source.source_type.clone()),
-                provider_article_id: Set(Some(provider_id)),
-                title: Set(article.title),
-                excerpt: Set(article.excerpt),
-                content: Set(article.content),
-                url: Set(article.url),
-                image_url: Set(article.image_url),
-                source_name: Set(article.source_name),
-                source_domain: Set(article.source_domain),
-                source_id: Set(article.source_id),
-                tags: Set(tags_json),
-                category: Set(article.category),
-                language: Set(article.language),
-                country: Set(article.country),
-                published_at: Set(article.published_at),
-                fetched_at: Set(now),
-                added_via: Set(via.clone()),
-                is_starred: Set(0),
-                is_dismissed: Set(0),
-                is_read: Set(0),
-                is_pinned: Set(0),
-                added_to_ideas_at: Set(None),
-                dismissed_at: Set(None),
-                created_at: Set(now),
-                updated_at: Set(now),
-                ..Default::default()
-            };
-
-            new_article.insert(db).await.map_err(|e| AppError::DatabaseQuery {
-                operation: "insert article".to_string(),
-                source: e,
-            })?;
-
+        if let Some(id) = store_feed_article(db, &source, &via, article).await? {
+            new_ids.push(id);
             added_count += 1;
         }
     }
 
+    apply_mute_rules_logged(db, &new_ids).await;
+    evaluate_alert_rules_logged(db, &new_ids).await;
+
     // Update feed source stats
     FeedSourceEntity::update_many()
         .col_expr(feed_sources::Column::ArticleCount, Expr::value(source.article_count + added_count))
@@ -693,11 +654,101 @@ pub async fn sync_feed_source_now_handler(
     })
 }
 
+/// Insert one fetched article unless the provider already gave it to us;
+/// returns the new row id
+#[instrument(skip_all, fields(provider_id = tracing::field::Empty))]
+async fn store_feed_article(
+    db: &DatabaseConnection,
+    source: &feed_sources::Model,
+    via: &str,
+    article: FeedArticle,
+) -> AppResult<Option<i64>> {
+    // Use provider_article_id for uniqueness check
+    let provider_id = article.provider_article_id.clone().unwrap_or_else(|| {
+        format!(
+            "{}_{}",
+            article.url.clone().unwrap_or_default(),
+            article.title
+        )
+    });
+    tracing::Span::current().record("provider_id", provider_id.as_str());
+
+    let existing = NewsArticleEntity::find()
+        .filter(NewsArticleColumn::ProviderArticleId.eq(Some(provider_id.clone())))
+        .filter(NewsArticleColumn::Provider.eq(&source.source_type))
+        .one(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery {
+            operation: "check existing article".to_string(),
+            source: e,
+        })?;
+    if existing.is_some() {
+        return Ok(None);
+    }
+
+    let now = chrono::Utc::now();
+    let tags_json = if !article.tags.is_empty() {
+        Some(serde_json::to_string(&article.tags).unwrap_or_default())
+    } else {
+        None
+    };
+
+    let new_article = ActiveNewsArticle {
+        user_id: Set(1), // TODO: Get from context
+        feed_source_id: Set(Some(source.id)),
+        provider: Set(source.source_type.clone()),
+        provider_article_id: Set(Some(provider_id)),
+        title: Set(article.title),
+        excerpt: Set(article.excerpt),
+        content: Set(article.content),
+        url: Set(article.url),
+        image_url: Set(article.image_url),
+        source_name: Set(article.source_name),
+        source_domain: Set(article.source_domain),
+        source_id: Set(article.source_id),
+        tags: Set(tags_json),
+        category: Set(article.category),
+        language: Set(article.language),
+        country: Set(article.country),
+        published_at: Set(article.published_at),
+        fetched_at: Set(now),
+        added_via: Set(via.to_string()),
+        is_starred: Set(0),
+        is_dismissed: Set(0),
+        is_read: Set(0),
+        is_pinned: Set(0),
+        added_to_ideas_at: Set(None),
+        dismissed_at: Set(None),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    let inserted = new_article
+        .insert(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery {
+            operation: "insert article".to_string(),
+            source: e,
+        })?;
+    Ok(Some(inserted.id))
+}
+
 /// Sync all enabled feed sources
 #[instrument(skip(db, http_client))]
 pub async fn sync_all_feed_sources_handler(
     db: &DatabaseConnection,
-    http_client: &reqwest::Client,
+    http_client: &HttpClient,
+) -> AppResult<SyncAllResult> {
+    sync_all_feed_sources_with_progress(db, http_client, &|_, _, _| {}).await
+}
+
+/// Sync all enabled sources, calling `on_progress(done, total, result)`
+/// after each one
+pub async fn sync_all_feed_sources_with_progress(
+    db: &DatabaseConnection,
+    http_client: &HttpClient,
+    on_progress: &(dyn Fn(usize, usize, &SyncSourceResult) + Send + Sync),
 ) -> AppResult<SyncAllResult> {
     info!("Syncing all enabled feed sources");
 
@@ -710,6 +761,36 @@ pub async fn sync_all_feed_sources_handler(
             source: e,
         })?;
 
+    sync_sources(db, http_client, sources, on_progress).await
+}
+
+/// Sync the given sources, enabled or not
+#[instrument(skip(db, http_client))]
+pub async fn sync_selected_feed_sources_handler(
+    db: &DatabaseConnection,
+    http_client: &HttpClient,
+    source_ids: &[i64],
+) -> AppResult<SyncAllResult> {
+    info!("Syncing {} selected feed sources", source_ids.len());
+
+    let sources = FeedSourceEntity::find()
+        .filter(feed_sources::Column::Id.is_in(source_ids.iter().copied()))
+        .all(db)
+        .await
+        .map_err(|e| AppError::DatabaseQuery {
+            operation: "list selected feed sources".to_string(),
+            source: e,
+        })?;
+
+    sync_sources(db, http_client, sources, &|_, _, _| {}).await
+}
+
+async fn sync_sources(
+    db: &DatabaseConnection,
+    http_client: &HttpClient,
+    sources: Vec<feed_sources::Model>,
+    on_progress: &(dyn Fn(usize, usize, &SyncSourceResult) + Send + Sync),
+) -> AppResult<SyncAllResult> {
     let total_sources = sources.len() as i32;
     let mut successful = 0;
     let mut failed = 0;
@@ -724,11 +805,12 @@ pub async fn sync_all_feed_sources_handler(
         } else {
             failed += 1;
         }
+        on_progress(results.len() + 1, total_sources as usize, &result);
         results.push(result);
     }
 
     info!(
-        "Sync all complete: {}/{} successful, {} articles",
+        "Sync complete: {}/{} successful, {} articles",
         successful, total_sources, total_articles
     );
 
@@ -760,7 +842,7 @@ pub async fn run_feed_source_sync_task(
     
     info!("Running scheduled sync for feed source {}", source_id);
     
-    match sync_feed_source_now_handler(&state.db, &state.http_client, source_id).await {
+    match sync_feed_source_now_handler(&state.db, &state.http_clients.api, source_id).await {
         Ok(result) => {
             let result_json = serde_json::json!({
                 "source_id": result.source_id,
@@ -792,11 +874,24 @@ pub async fn run_feed_source_sync_task(
 /// 
 /// Called by scheduler for batch sync of all sources.
 /// Task type: `feed_sources_sync_all`
-#[instrument(skip(state))]
-pub async fn run_feed_sources_sync_all_task(state: &crate::AppState) -> TaskRunResult {
-    info!("Running scheduled sync for all feed sources");
-    
-    match sync_all_feed_sources_handler(&state.db, &state.http_client).await {
+/// `params.source_ids` narrows a manual run to those sources.
+#[instrument(skip(state, params))]
+pub async fn run_feed_sources_sync_all_task(
+    state: &crate::AppState,
+    params: FeedSyncParams,
+) -> TaskRunResult {
+    let synced = match params.source_ids {
+        Some(source_ids) => {
+            sync_selected_feed_sources_handler(&state.db, &state.http_clients.api, &source_ids)
+                .await
+        }
+        None => {
+            info!("Running scheduled sync for all feed sources");
+            sync_all_feed_sources_handler(&state.db, &state.http_clients.api).await
+        }
+    };
+
+    match synced {
         Ok(result) => {
             let result_json = serde_json::json!({
                 "total_sources": result.total_sources,
@@ -10,6 +10,11 @@
 //! - **articles**: Article CRUD operations (list, get, dismiss, star, read)
 //! - **sources**: News source management and syncing (outlets like CNN, BBC, etc.)
 //! - **sync**: News article syncing from API with rate limiting
+//! - **search**: Ranked full-text search (SQLite FTS5)
+//! - **alerts**: Keyword alert rules evaluated during sync
+//! - **relevance**: Engagement signals and learned relevance ranking
+//! - **saved_searches**: Saved searches and their scheduled runs
+//! - **mutes**: Mute rules that hide articles by source, domain, or keyword
 
 pub mod entities;
 pub mod types;
@@ -20,13 +25,32 @@ pub mod settings;
 pub mod articles;
 pub mod sources;
 pub mod sync;
+pub mod search;
+pub mod alerts;
+pub mod relevance;
+pub mod saved_searches;
+pub mod mutes;
 
 // Re-export public APIs
 pub use types::{
     NewsArticleDto,
+    NewsArticleSearchHit,
     NewsSettingsDto,
     SaveNewsSettingsInput,
     NewsSourceDto,
+    AlertRuleDto,
+    CreateAlertRuleInput,
+    UpdateAlertRuleInput,
+    TestAlertRuleInput,
+    AlertRuleTestResult,
+    SavedSearchDto,
+    CreateSavedSearchInput,
+    UpdateSavedSearchInput,
+    MuteRuleDto,
+    CreateMuteRuleInput,
+    UpdateMuteRuleInput,
+    PreviewMuteRuleInput,
+    MuteRulePreview,
 };
 
 pub use settings::{
@@ -36,6 +60,7 @@ pub use settings::{
 
 pub use articles::{
     list_news_articles_handler,
+    count_news_articles_handler,
     get_news_article_handler,
     dismiss_news_article_handler,
     toggle_star_news_article_handler,
@@ -43,6 +68,34 @@ pub use articles::{
     clear_news_articles_handler,
 };
 
+pub use search::search_news_articles_handler;
+
+pub use alerts::{
+    list_alert_rules_handler,
+    create_alert_rule_handler,
+    update_alert_rule_handler,
+    delete_alert_rule_handler,
+    test_alert_rule_handler,
+    emit_news_alerts_since,
+};
+
+pub use saved_searches::{
+    list_saved_searches_handler,
+    create_saved_search_handler,
+    update_saved_search_handler,
+    delete_saved_search_handler,
+    run_saved_search_handler,
+    run_saved_searches_task,
+};
+
+pub use mutes::{
+    list_mute_rules_handler,
+    create_mute_rule_handler,
+    update_mute_rule_handler,
+    delete_mute_rule_handler,
+    preview_mute_rule_handler,
+};
+
 pub use sources::{
     list_news_sources_handler,
     sync_news_sources_now_handler,
@@ -65,6 +118,7 @@ pub use feed_sources::{
     test_feed_source_connection_handler,
     sync_feed_source_now_handler,
     sync_all_feed_sources_handler,
+    sync_all_feed_sources_with_progress,
     run_feed_source_sync_task,
     run_feed_sources_sync_all_task,
 };
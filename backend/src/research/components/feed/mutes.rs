@@ -0,0 +1,501 @@
+//! Mute rules module
+//!
+//! Mute rules hide articles from muted sources, domains, or keywords without
+//! deleting them. New articles are checked during sync; creating, editing or
+//! deleting a rule re-applies all rules to stored articles. Listings exclude
+//! muted articles unless `status = "muted"` is requested.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use reqwest::Url;
+use sea_orm::prelude::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use tracing::{error, info, instrument};
+
+use crate::core::components::errors::{AppError, AppResult};
+
+use super::articles::article_to_dto;
+use super::entities::articles::{self as news_articles, Entity as EntityNewsArticles};
+use super::entities::mute_rules::{self, Entity as MuteRuleEntity};
+use super::types::{
+    CreateMuteRuleInput, MuteRuleDto, MuteRulePreview, PreviewMuteRuleInput, UpdateMuteRuleInput,
+};
+
+const RULE_TYPES: [&str; 3] = ["source", "domain", "keyword"];
+
+const DEFAULT_PREVIEW_LIMIT: u64 = 500;
+
+/// Lowercase and trim a pattern; domains are reduced to a bare host
+pub(crate) fn normalize_pattern(rule_type: &str, pattern: &str) -> String {
+    let pattern = pattern.trim().to_lowercase();
+    if rule_type != "domain" {
+        return pattern;
+    }
+    let host = Url::parse(&pattern)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| pattern.split('/').next().unwrap_or_default().to_string());
+    host.trim_start_matches("www.").to_string()
+}
+
+/// Rule ready for matching
+#[derive(Debug)]
+pub(crate) struct MuteMatcher {
+    rule_type: String,
+    pattern: String,
+}
+
+impl MuteMatcher {
+    pub(crate) fn new(rule_type: &str, pattern: &str) -> Self {
+        Self {
+            rule_type: rule_type.to_string(),
+            pattern: normalize_pattern(rule_type, pattern),
+        }
+    }
+
+    /// Domains match subdomains too; sources match the provider's source id or
+    /// name exactly; keywords are case-insensitive substrings of title or excerpt
+    pub(crate) fn matches(&self, article: &news_articles::Model) -> bool {
+        if self.pattern.is_empty() {
+            return false;
+        }
+        match self.rule_type.as_str() {
+            "domain" => {
+                let url_host = article
+                    .url
+                    .as_deref()
+                    .and_then(|u| Url::parse(u).ok())
+                    .and_then(|u| u.host_str().map(str::to_string));
+                [article.source_domain.clone(), url_host]
+                    .into_iter()
+                    .flatten()
+                    .map(|h| normalize_pattern("domain", &h))
+                    .any(|host| {
+                        host == self.pattern || host.ends_with(&format!(".{}", self.pattern))
+                    })
+            }
+            "source" => [&article.source_id, &article.source_name]
+                .into_iter()
+                .flatten()
+                .any(|s| s.trim().to_lowercase() == self.pattern),
+            "keyword" => [Some(article.title.as_str()), article.excerpt.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|text| text.to_lowercase().contains(&self.pattern)),
+            _ => false,
+        }
+    }
+}
+
+fn rule_to_dto(m: mute_rules::Model, hidden_count: i64) -> MuteRuleDto {
+    MuteRuleDto {
+        id: m.id,
+        rule_type: m.rule_type,
+        pattern: m.pattern,
+        enabled: m.enabled == 1,
+        hidden_count,
+        created_at: m.created_at.to_rfc3339(),
+        updated_at: m.updated_at.to_rfc3339(),
+    }
+}
+
+fn validate_rule(rule_type: &str, pattern: &str) -> AppResult<()> {
+    if !RULE_TYPES.contains(&rule_type) {
+        return Err(AppError::validation(
+            "ruleType",
+            format!(
+                "Unknown rule type '{}' (expected one of: {})",
+                rule_type,
+                RULE_TYPES.join(", ")
+            ),
+        ));
+    }
+    if pattern.is_empty() {
+        return Err(AppError::validation("pattern", "Pattern is required"));
+    }
+    Ok(())
+}
+
+async fn find_rule(db: &DatabaseConnection, id: i64) -> AppResult<mute_rules::Model> {
+    MuteRuleEntity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Validation {
+            field: "id".to_string(),
+            reason: "Mute rule not found".to_string(),
+            invalid_value: Some(id.to_string()),
+        })
+}
+
+async fn ensure_unique(
+    db: &DatabaseConnection,
+    rule_type: &str,
+    pattern: &str,
+    exclude_id: Option<i64>,
+) -> AppResult<()> {
+    let mut query = MuteRuleEntity::find()
+        .filter(mute_rules::Column::RuleType.eq(rule_type))
+        .filter(mute_rules::Column::Pattern.eq(pattern));
+    if let Some(id) = exclude_id {
+        query = query.filter(mute_rules::Column::Id.ne(id));
+    }
+    if query.one(db).await?.is_some() {
+        return Err(AppError::validation(
+            "pattern",
+            format!("A {} rule for '{}' already exists", rule_type, pattern),
+        ));
+    }
+    Ok(())
+}
+
+/// Number of articles currently hidden by each rule
+async fn hidden_counts(db: &DatabaseConnection) -> AppResult<HashMap<i64, i64>> {
+    let rows: Vec<(i64, i64)> = EntityNewsArticles::find()
+        .select_only()
+        .column(news_articles::Column::MuteRuleId)
+        .column_as(news_articles::Column::Id.count(), "hidden")
+        .filter(news_articles::Column::MuteRuleId.is_not_null())
+        .group_by(news_articles::Column::MuteRuleId)
+        .into_tuple()
+        .all(db)
+        .await?;
+    Ok(rows.into_iter().collect())
+}
+
+async fn rule_with_count(
+    db: &DatabaseConnection,
+    rule: mute_rules::Model,
+) -> AppResult<MuteRuleDto> {
+    let hidden = hidden_counts(db).await?.get(&rule.id).copied().unwrap_or(0);
+    Ok(rule_to_dto(rule, hidden))
+}
+
+/// List all mute rules with the number of articles each one hides
+#[instrument(skip(db))]
+pub async fn list_mute_rules_handler(db: &DatabaseConnection) -> AppResult<Vec<MuteRuleDto>> {
+    let rules = MuteRuleEntity::find()
+        .order_by_asc(mute_rules::Column::RuleType)
+        .order_by_asc(mute_rules::Column::Pattern)
+        .all(db)
+        .await?;
+    let counts = hidden_counts(db).await?;
+    Ok(rules
+        .into_iter()
+        .map(|r| {
+            let hidden = counts.get(&r.id).copied().unwrap_or(0);
+            rule_to_dto(r, hidden)
+        })
+        .collect())
+}
+
+/// Create a mute rule and hide matching stored articles
+#[instrument(skip(db, input), fields(rule_type = %input.rule_type))]
+pub async fn create_mute_rule_handler(
+    db: &DatabaseConnection,
+    input: CreateMuteRuleInput,
+) -> AppResult<MuteRuleDto> {
+    let rule_type = input.rule_type.trim().to_lowercase();
+    let pattern = normalize_pattern(&rule_type, &input.pattern);
+    validate_rule(&rule_type, &pattern)?;
+    ensure_unique(db, &rule_type, &pattern, None).await?;
+
+    let now = Utc::now();
+    let rule = mute_rules::ActiveModel {
+        rule_type: Set(rule_type),
+        pattern: Set(pattern),
+        enabled: Set(input.enabled.unwrap_or(true) as i32),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    info!(rule_id = rule.id, "Mute rule created");
+    reapply_mute_rules(db).await?;
+    rule_with_count(db, rule).await
+}
+
+/// Update a mute rule and re-apply rules to stored articles
+#[instrument(skip(db, input), fields(rule_id = input.id))]
+pub async fn update_mute_rule_handler(
+    db: &DatabaseConnection,
+    input: UpdateMuteRuleInput,
+) -> AppResult<MuteRuleDto> {
+    let existing = find_rule(db, input.id).await?;
+
+    let rule_type = input
+        .rule_type
+        .map(|t| t.trim().to_lowercase())
+        .unwrap_or_else(|| existing.rule_type.clone());
+    let pattern = normalize_pattern(
+        &rule_type,
+        input.pattern.as_deref().unwrap_or(&existing.pattern),
+    );
+    validate_rule(&rule_type, &pattern)?;
+    ensure_unique(db, &rule_type, &pattern, Some(existing.id)).await?;
+
+    let mut active = existing.into_active_model();
+    active.rule_type = Set(rule_type);
+    active.pattern = Set(pattern);
+    if let Some(enabled) = input.enabled {
+        active.enabled = Set(enabled as i32);
+    }
+    active.updated_at = Set(Utc::now());
+    let rule = active.update(db).await?;
+
+    reapply_mute_rules(db).await?;
+    rule_with_count(db, rule).await
+}
+
+/// Delete a mute rule; articles it hid become visible again unless another
+/// rule matches them
+#[instrument(skip(db))]
+pub async fn delete_mute_rule_handler(db: &DatabaseConnection, id: i64) -> AppResult<()> {
+    let res = MuteRuleEntity::delete_by_id(id).exec(db).await?;
+    if res.rows_affected == 0 {
+        return Err(AppError::Validation {
+            field: "id".to_string(),
+            reason: "Mute rule not found".to_string(),
+            invalid_value: Some(id.to_string()),
+        });
+    }
+    reapply_mute_rules(db).await?;
+    Ok(())
+}
+
+/// Show which recent articles a rule would hide without muting anything
+#[instrument(skip(db, input), fields(rule_id = ?input.rule_id))]
+pub async fn preview_mute_rule_handler(
+    db: &DatabaseConnection,
+    input: PreviewMuteRuleInput,
+) -> AppResult<MuteRulePreview> {
+    let saved = match input.rule_id {
+        Some(id) => Some(find_rule(db, id).await?),
+        None => None,
+    };
+    let rule_type = input
+        .rule_type
+        .map(|t| t.trim().to_lowercase())
+        .or_else(|| saved.as_ref().map(|r| r.rule_type.clone()))
+        .unwrap_or_default();
+    let pattern = input
+        .pattern
+        .or_else(|| saved.as_ref().map(|r| r.pattern.clone()))
+        .unwrap_or_default();
+
+    let matcher = MuteMatcher::new(&rule_type, &pattern);
+    validate_rule(&rule_type, &matcher.pattern)?;
+
+    let articles = EntityNewsArticles::find()
+        .filter(news_articles::Column::UserId.eq(1))
+        .order_by_desc(news_articles::Column::FetchedAt)
+        .limit(input.limit.unwrap_or(DEFAULT_PREVIEW_LIMIT))
+        .all(db)
+        .await?;
+
+    let scanned = articles.len();
+    let hidden = articles
+        .into_iter()
+        .filter(|a| a.is_starred == 0 && matcher.matches(a))
+        .map(article_to_dto)
+        .collect();
+    Ok(MuteRulePreview { scanned, hidden })
+}
+
+async fn enabled_matchers(db: &DatabaseConnection) -> AppResult<Vec<(i64, MuteMatcher)>> {
+    Ok(MuteRuleEntity::find()
+        .filter(mute_rules::Column::Enabled.eq(1))
+        .order_by_asc(mute_rules::Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.id, MuteMatcher::new(&r.rule_type, &r.pattern)))
+        .collect())
+}
+
+/// First enabled rule that hides the article; starred articles are never muted
+fn matching_rule(matchers: &[(i64, MuteMatcher)], article: &news_articles::Model) -> Option<i64> {
+    if article.is_starred == 1 {
+        return None;
+    }
+    matchers
+        .iter()
+        .find(|(_, m)| m.matches(article))
+        .map(|(id, _)| *id)
+}
+
+/// Mute newly inserted articles that match an enabled rule
+///
+/// Returns the number of articles muted.
+pub(crate) async fn apply_mute_rules(
+    db: &DatabaseConnection,
+    article_ids: &[i64],
+) -> AppResult<usize> {
+    if article_ids.is_empty() {
+        return Ok(0);
+    }
+    let matchers = enabled_matchers(db).await?;
+    if matchers.is_empty() {
+        return Ok(0);
+    }
+
+    let articles = EntityNewsArticles::find()
+        .filter(news_articles::Column::Id.is_in(article_ids.to_vec()))
+        .filter(news_articles::Column::MuteRuleId.is_null())
+        .all(db)
+        .await?;
+
+    let now = Utc::now();
+    let mut muted = 0;
+    for article in articles {
+        let Some(rule_id) = matching_rule(&matchers, &article) else {
+            continue;
+        };
+        let mut active = article.into_active_model();
+        active.mute_rule_id = Set(Some(rule_id));
+        active.muted_at = Set(Some(now));
+        active.update(db).await?;
+        muted += 1;
+    }
+
+    if muted > 0 {
+        info!(target: "news_sync", muted, "Mute rules hid new articles");
+    }
+    Ok(muted)
+}
+
+/// Apply mute rules after a sync, logging instead of failing the sync
+pub(crate) async fn apply_mute_rules_logged(db: &DatabaseConnection, article_ids: &[i64]) -> usize {
+    match apply_mute_rules(db, article_ids).await {
+        Ok(muted) => muted,
+        Err(e) => {
+            error!(target: "news_sync", "Mute rule evaluation failed: {}", e);
+            0
+        }
+    }
+}
+
+/// Re-evaluate every stored article against the current rules
+///
+/// Returns the number of articles whose muted state changed.
+async fn reapply_mute_rules(db: &DatabaseConnection) -> AppResult<usize> {
+    let matchers = enabled_matchers(db).await?;
+
+    let now = Utc::now();
+    let mut changed = 0;
+    if matchers.is_empty() {
+        changed = EntityNewsArticles::update_many()
+            .col_expr(
+                news_articles::Column::MuteRuleId,
+                Expr::value(Option::<i64>::None),
+            )
+            .col_expr(
+                news_articles::Column::MutedAt,
+                Expr::value(Option::<chrono::DateTime<Utc>>::None),
+            )
+            .filter(news_articles::Column::MuteRuleId.is_not_null())
+            .exec(db)
+            .await?
+            .rows_affected as usize;
+    } else {
+        let articles = EntityNewsArticles::find()
+            .filter(news_articles::Column::UserId.eq(1))
+            .all(db)
+            .await?;
+        for article in articles {
+            let rule_id = matching_rule(&matchers, &article);
+            if rule_id == article.mute_rule_id {
+                continue;
+            }
+            let mut active = article.into_active_model();
+            active.mute_rule_id = Set(rule_id);
+            active.muted_at = Set(rule_id.map(|_| now));
+            active.update(db).await?;
+            changed += 1;
+        }
+    }
+
+    info!(target: "news", changed, "Mute rules re-applied");
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, url: &str, source_id: &str) -> news_articles::Model {
+        let now = Utc::now();
+        news_articles::Model {
+            id: 1,
+            user_id: 1,
+            provider: "newsdata".into(),
+            provider_article_id: None,
+            source_name: None,
+            source_domain: None,
+            source_id: Some(source_id.into()),
+            title: title.into(),
+            excerpt: None,
+            content: None,
+            tags: None,
+            url: Some(url.into()),
+            image_url: None,
+            language: None,
+            category: None,
+            country: None,
+            published_at: None,
+            fetched_at: now,
+            added_via: "sync".into(),
+            is_starred: 0,
+            is_dismissed: 0,
+            is_read: 0,
+            added_to_ideas_at: None,
+            dismissed_at: None,
+            is_pinned: 0,
+            created_at: now,
+            updated_at: now,
+            feed_source_id: None,
+            alert_rule_id: None,
+            alerted_at: None,
+            mute_rule_id: None,
+            muted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_pattern() {
+        assert_eq!(
+            normalize_pattern("domain", " https://www.Example.com/news "),
+            "example.com"
+        );
+        assert_eq!(
+            normalize_pattern("domain", "example.com/path"),
+            "example.com"
+        );
+        assert_eq!(normalize_pattern("keyword", "  Crypto "), "crypto");
+    }
+
+    #[test]
+    fn test_mute_matcher() {
+        let a = article(
+            "Crypto prices tumble",
+            "https://markets.example.com/a/1",
+            "bbc",
+        );
+
+        assert!(MuteMatcher::new("domain", "example.com").matches(&a));
+        assert!(!MuteMatcher::new("domain", "ample.com").matches(&a));
+        assert!(MuteMatcher::new("source", "BBC").matches(&a));
+        assert!(!MuteMatcher::new("source", "cnn").matches(&a));
+        assert!(MuteMatcher::new("keyword", "CRYPTO").matches(&a));
+        assert!(!MuteMatcher::new("keyword", "").matches(&a));
+
+        let starred = news_articles::Model { is_starred: 1, ..a };
+        let matchers = vec![(7, MuteMatcher::new("keyword", "crypto"))];
+        assert_eq!(matching_rule(&matchers, &starred), None);
+    }
+}
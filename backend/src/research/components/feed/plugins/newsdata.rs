@@ -4,6 +4,7 @@
 //! Supports latest news and archive endpoints with full filtering capabilities.
 
 use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
 use crate::research::components::feed::plugin::{
     ConnectionTestResult, FeedArticle, FeedSource, FetchResult, SourceMetadata,
 };
@@ -116,13 +117,13 @@ impl Default for NewsDataConfig {
 
 /// NewsData.io feed source plugin
 pub struct NewsDataPlugin {
-    http_client: reqwest::Client,
+    http_client: HttpClient,
     api_key: String,
 }
 
 impl NewsDataPlugin {
     /// Create a new NewsData plugin instance
-    pub fn new(api_key: String, http_client: reqwest::Client) -> Self {
+    pub fn new(api_key: String, http_client: HttpClient) -> Self {
         Self {
             api_key,
             http_client,
@@ -137,53 +138,6 @@ impl NewsDataPlugin {
             .and_then(|s| s.split('/').next())
             .map(|s| s.to_string())
     }
-    
-    /// Retry request with exponential backoff
-    async fn retry_request<F, Fut>(&self, mut f: F) -> Result<reqwest::Response, reqwest::Error>
-    where
-        F: FnMut() -> Fut,
-        Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
-    {
-        let max_retries = 3;
-        let mut attempt = 0;
-        
-        loop {
-            match f().await {
-                Ok(resp) => {
-                    if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                        if attempt >= max_retries {
-                            warn!("NewsData: Rate limited after {} retries", max_retries);
-                            return Ok(resp);
-                        }
-                        
-                        let delay = resp
-                            .headers()
-                            .get("retry-after")
-                            .and_then(|h| h.to_str().ok())
-                            .and_then(|s| s.parse::<u64>().ok())
-                            .unwrap_or(1u64 << attempt);
-                        
-                        warn!("NewsData: Rate limited, retrying in {}s (attempt {}/{})", delay, attempt + 1, max_retries);
-                        tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                        attempt += 1;
-                        continue;
-                    }
-                    
-                    return Ok(resp);
-                }
-                Err(e) => {
-                    if attempt >= max_retries || !e.is_timeout() && !e.is_connect() {
-                        return Err(e);
-                    }
-                    
-                    let delay = 1u64 << attempt;
-                    warn!("NewsData: Request failed: {}, retrying in {}s", e, delay);
-                    tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                    attempt += 1;
-                }
-            }
-        }
-    }
 }
 
 #[async_trait]
@@ -237,12 +191,10 @@ impl FeedSource for NewsDataPlugin {
     async fn test_connection(&self) -> AppResult<ConnectionTestResult> {
         info!("NewsData: Testing connection...");
         
-        let response = self.retry_request(|| {
-            self.http_client
-                .get("https://newsdata.io/api/1/latest")
-                .query(&[("apikey", self.api_key.as_str()), ("language", "en")])
-                .send()
-        }).await;
+        let request = self.http_client
+            .get("https://newsdata.io/api/1/latest")
+            .query(&[("apikey", self.api_key.as_str()), ("language", "en")]);
+        let response = self.http_client.send(request).await;
         
         match response {
             Ok(resp) if resp.status().is_success() => {
@@ -354,7 +306,7 @@ impl FeedSource for NewsDataPlugin {
                 req = req.query(&[("page", page.as_str())]);
             }
             
-            let response = self.retry_request(|| req.try_clone().unwrap().send()).await
+            let response = self.http_client.send(req).await
                 .map_err(|e| AppError::Network {
                     message: "Request failed".to_string(),
                     source: e,
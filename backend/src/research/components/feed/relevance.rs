@@ -0,0 +1,313 @@
+//! Personalized article relevance
+//!
+//! Read/star/dismiss actions are logged as signals and folded into
+//! per-feature weights (source, category, title keyword). `list_news_articles`
+//! with `sort_by = "relevance"` scores recent articles by those weights plus
+//! a recency boost.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, OnConflict};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::core::components::errors::AppResult;
+
+use super::entities::article_signals;
+use super::entities::articles as news_articles;
+use super::entities::relevance_weights::{self, Entity as RelevanceWeights};
+use super::types::parse_vec;
+
+/// Articles considered for relevance sorting (newest first)
+pub(crate) const RELEVANCE_CANDIDATES: u64 = 500;
+
+/// Recency boost for a brand-new article; halves every `RECENCY_HALF_LIFE_HOURS`
+const RECENCY_WEIGHT: f64 = 2.0;
+const RECENCY_HALF_LIFE_HOURS: f64 = 24.0;
+
+const MAX_TITLE_KEYWORDS: usize = 10;
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "again", "against", "also", "amid", "before", "being", "between", "could",
+    "during", "from", "have", "into", "just", "more", "most", "over", "said", "says", "some",
+    "than", "that", "their", "them", "then", "there", "these", "they", "this", "those", "under",
+    "what", "when", "where", "which", "while", "will", "with", "would", "your",
+];
+
+/// Engagement signal recorded for an article
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Read,
+    Star,
+    Unstar,
+    Dismiss,
+}
+
+impl Signal {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Signal::Read => "read",
+            Signal::Star => "star",
+            Signal::Unstar => "unstar",
+            Signal::Dismiss => "dismiss",
+        }
+    }
+
+    /// Weight change applied to every feature of the article
+    fn delta(&self) -> f64 {
+        match self {
+            Signal::Read => 1.0,
+            Signal::Star => 3.0,
+            // Undoes a star
+            Signal::Unstar => -3.0,
+            Signal::Dismiss => -2.0,
+        }
+    }
+}
+
+/// Features an article contributes to the model
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ArticleFeatures {
+    pub source: Option<String>,
+    pub category: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+impl ArticleFeatures {
+    pub(crate) fn from_article(article: &news_articles::Model) -> Self {
+        let source = [&article.source_domain, &article.source_id, &article.source_name]
+            .into_iter()
+            .flatten()
+            .map(|s| s.trim().to_lowercase())
+            .find(|s| !s.is_empty());
+        let category = article
+            .category
+            .as_ref()
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty());
+
+        let mut seen = HashSet::new();
+        let mut keywords: Vec<String> = article
+            .title
+            .split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .filter(|w| w.chars().count() >= 4 && !STOPWORDS.contains(&w.as_str()))
+            .filter(|w| !w.chars().all(|c| c.is_ascii_digit()))
+            .filter(|w| seen.insert(w.clone()))
+            .take(MAX_TITLE_KEYWORDS)
+            .collect();
+        for tag in parse_vec(&article.tags) {
+            let tag = tag.trim().to_lowercase();
+            if !tag.is_empty() && seen.insert(tag.clone()) {
+                keywords.push(tag);
+            }
+        }
+
+        Self {
+            source,
+            category,
+            keywords,
+        }
+    }
+
+    /// `(feature_type, feature, type_factor)` triples
+    fn entries(&self) -> Vec<(&'static str, &str, f64)> {
+        let mut entries = Vec::with_capacity(self.keywords.len() + 2);
+        if let Some(source) = &self.source {
+            entries.push(("source", source.as_str(), 1.0));
+        }
+        if let Some(category) = &self.category {
+            entries.push(("category", category.as_str(), 0.5));
+        }
+        for keyword in &self.keywords {
+            entries.push(("keyword", keyword.as_str(), 0.3));
+        }
+        entries
+    }
+}
+
+/// Log a signal and update feature weights
+pub(crate) async fn record_signal(
+    db: &DatabaseConnection,
+    article: &news_articles::Model,
+    signal: Signal,
+) -> AppResult<()> {
+    let features = ArticleFeatures::from_article(article);
+    let now = Utc::now();
+
+    article_signals::ActiveModel {
+        article_id: Set(article.id),
+        signal: Set(signal.as_str().to_string()),
+        features: Set(serde_json::to_string(&features).unwrap_or_else(|_| "{}".into())),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    let delta = signal.delta();
+    for (feature_type, feature, _) in features.entries() {
+        let row = relevance_weights::ActiveModel {
+            feature_type: Set(feature_type.to_string()),
+            feature: Set(feature.to_string()),
+            weight: Set(delta),
+            signal_count: Set(1),
+            updated_at: Set(now),
+            ..Default::default()
+        };
+        RelevanceWeights::insert(row)
+            .on_conflict(
+                OnConflict::columns([
+                    relevance_weights::Column::FeatureType,
+                    relevance_weights::Column::Feature,
+                ])
+                .value(
+                    relevance_weights::Column::Weight,
+                    Expr::col((RelevanceWeights, relevance_weights::Column::Weight)).add(delta),
+                )
+                .value(
+                    relevance_weights::Column::SignalCount,
+                    Expr::col((RelevanceWeights, relevance_weights::Column::SignalCount)).add(1),
+                )
+                .value(relevance_weights::Column::UpdatedAt, now)
+                .to_owned(),
+            )
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Record a signal without failing the user action that triggered it
+pub(crate) async fn record_signal_logged(
+    db: &DatabaseConnection,
+    article: &news_articles::Model,
+    signal: Signal,
+) {
+    if let Err(e) = record_signal(db, article, signal).await {
+        warn!(
+            target: "news",
+            article_id = article.id,
+            "Failed to record {} signal: {}",
+            signal.as_str(),
+            e
+        );
+    }
+}
+
+/// Compress large weights so one heavily-read source can't dominate
+fn damp(weight: f64) -> f64 {
+    weight.signum() * weight.abs().ln_1p()
+}
+
+pub(crate) fn score_article(
+    article: &news_articles::Model,
+    weights: &HashMap<(String, String), f64>,
+    now: chrono::DateTime<Utc>,
+) -> f64 {
+    let features = ArticleFeatures::from_article(article);
+    let affinity: f64 = features
+        .entries()
+        .into_iter()
+        .filter_map(|(feature_type, feature, factor)| {
+            weights
+                .get(&(feature_type.to_string(), feature.to_string()))
+                .map(|w| factor * damp(*w))
+        })
+        .sum();
+
+    let published = article.published_at.unwrap_or(article.fetched_at);
+    let age_hours = (now - published).num_minutes().max(0) as f64 / 60.0;
+    let recency = RECENCY_WEIGHT * 0.5f64.powf(age_hours / RECENCY_HALF_LIFE_HOURS);
+
+    affinity + recency
+}
+
+/// Sort articles best-first by learned relevance
+pub(crate) async fn sort_by_relevance(
+    db: &DatabaseConnection,
+    articles: Vec<news_articles::Model>,
+) -> AppResult<Vec<news_articles::Model>> {
+    let weights: HashMap<(String, String), f64> = RelevanceWeights::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|w| ((w.feature_type, w.feature), w.weight))
+        .collect();
+
+    let now = Utc::now();
+    let mut scored: Vec<(f64, news_articles::Model)> = articles
+        .into_iter()
+        .map(|a| (score_article(&a, &weights, now), a))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(scored.into_iter().map(|(_, a)| a).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, source_domain: &str) -> news_articles::Model {
+        let now = Utc::now();
+        news_articles::Model {
+            id: 1,
+            user_id: 1,
+            provider: "newsdata".into(),
+            provider_article_id: None,
+            source_name: None,
+            source_domain: Some(source_domain.into()),
+            source_id: None,
+            title: title.into(),
+            excerpt: None,
+            content: None,
+            tags: Some("[\"Energy\"]".into()),
+            url: None,
+            image_url: None,
+            language: None,
+            category: Some("Science".into()),
+            country: None,
+            published_at: Some(now),
+            fetched_at: now,
+            added_via: "sync".into(),
+            is_starred: 0,
+            is_dismissed: 0,
+            is_read: 0,
+            added_to_ideas_at: None,
+            dismissed_at: None,
+            is_pinned: 0,
+            created_at: now,
+            updated_at: now,
+            feed_source_id: None,
+            alert_rule_id: None,
+            alerted_at: None,
+            mute_rule_id: None,
+            muted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_article_features() {
+        let a = article("Fusion reactor hits record, says team", "BBC.com");
+        let features = ArticleFeatures::from_article(&a);
+        assert_eq!(features.source.as_deref(), Some("bbc.com"));
+        assert_eq!(features.category.as_deref(), Some("science"));
+        assert_eq!(
+            features.keywords,
+            vec!["fusion", "reactor", "hits", "record", "team", "energy"]
+        );
+    }
+
+    #[test]
+    fn test_learned_weights_outrank_recency_ties() {
+        let now = Utc::now();
+        let liked = article("Fusion breakthrough", "liked.com");
+        let other = article("Fusion breakthrough", "other.com");
+        let mut weights = HashMap::new();
+        weights.insert(("source".to_string(), "liked.com".to_string()), 5.0);
+        weights.insert(("source".to_string(), "other.com".to_string()), -4.0);
+        assert!(score_article(&liked, &weights, now) > score_article(&other, &weights, now));
+    }
+}
@@ -0,0 +1,317 @@
+//! Saved searches module
+//!
+//! A saved search stores a query plus list_news_articles filters and sort.
+//! Searches flagged `run_on_schedule` are executed by the
+//! `saved_searches_run` task, which can pin articles that newly match.
+
+use chrono::Utc;
+use sea_orm::prelude::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use tracing::{error, info, instrument};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::system::components::scheduler::TaskRunResult;
+
+use super::articles::list_news_articles_handler;
+use super::entities::articles::{self as news_articles, Entity as EntityNewsArticles};
+use super::entities::saved_searches::{self, Entity as SavedSearchEntity};
+use super::types::{
+    CreateSavedSearchInput, NewsArticleDto, SavedSearchDto, SavedSearchFilters,
+    UpdateSavedSearchInput,
+};
+
+/// Articles scanned per scheduled run of a search
+const SCHEDULED_RUN_LIMIT: u64 = 200;
+
+const SORT_OPTIONS: [&str; 4] = ["latest", "oldest", "starred", "relevance"];
+
+fn search_to_dto(m: saved_searches::Model) -> SavedSearchDto {
+    SavedSearchDto {
+        id: m.id,
+        name: m.name,
+        query: m.query,
+        filters: parse_filters(&m.filters),
+        sort_by: m.sort_by,
+        run_on_schedule: m.run_on_schedule == 1,
+        pin_new_matches: m.pin_new_matches == 1,
+        last_run_at: m.last_run_at.map(|d| d.to_rfc3339()),
+        last_match_count: m.last_match_count,
+        created_at: m.created_at.to_rfc3339(),
+        updated_at: m.updated_at.to_rfc3339(),
+    }
+}
+
+fn parse_filters(raw: &Option<String>) -> SavedSearchFilters {
+    raw.as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+fn filters_to_json(filters: &SavedSearchFilters) -> Option<String> {
+    serde_json::to_string(filters).ok()
+}
+
+/// Blank strings clear the field
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn validate_sort(sort_by: &Option<String>) -> AppResult<()> {
+    match sort_by.as_deref() {
+        Some(s) if !SORT_OPTIONS.contains(&s) => Err(AppError::validation(
+            "sortBy",
+            format!("Unknown sort '{}' (expected one of: {})", s, SORT_OPTIONS.join(", ")),
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn find_search(db: &DatabaseConnection, id: i64) -> AppResult<saved_searches::Model> {
+    SavedSearchEntity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::Validation {
+            field: "id".to_string(),
+            reason: "Saved search not found".to_string(),
+            invalid_value: Some(id.to_string()),
+        })
+}
+
+/// List all saved searches
+#[instrument(skip(db))]
+pub async fn list_saved_searches_handler(
+    db: &DatabaseConnection,
+) -> AppResult<Vec<SavedSearchDto>> {
+    let searches = SavedSearchEntity::find()
+        .order_by_asc(saved_searches::Column::Name)
+        .all(db)
+        .await?;
+    Ok(searches.into_iter().map(search_to_dto).collect())
+}
+
+/// Create a saved search
+#[instrument(skip(db, input), fields(name = %input.name))]
+pub async fn create_saved_search_handler(
+    db: &DatabaseConnection,
+    input: CreateSavedSearchInput,
+) -> AppResult<SavedSearchDto> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "Name is required"));
+    }
+    let sort_by = non_blank(input.sort_by);
+    validate_sort(&sort_by)?;
+
+    let now = Utc::now();
+    let search = saved_searches::ActiveModel {
+        name: Set(name),
+        query: Set(non_blank(input.query)),
+        filters: Set(filters_to_json(&input.filters.unwrap_or_default())),
+        sort_by: Set(sort_by),
+        run_on_schedule: Set(input.run_on_schedule.unwrap_or(false) as i32),
+        pin_new_matches: Set(input.pin_new_matches.unwrap_or(false) as i32),
+        last_run_at: Set(None),
+        last_match_count: Set(0),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    info!(search_id = search.id, "Saved search created");
+    Ok(search_to_dto(search))
+}
+
+/// Update a saved search
+#[instrument(skip(db, input), fields(search_id = input.id))]
+pub async fn update_saved_search_handler(
+    db: &DatabaseConnection,
+    input: UpdateSavedSearchInput,
+) -> AppResult<SavedSearchDto> {
+    let mut active = find_search(db, input.id).await?.into_active_model();
+
+    if let Some(name) = input.name {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::validation("name", "Name is required"));
+        }
+        active.name = Set(name);
+    }
+    if input.query.is_some() {
+        active.query = Set(non_blank(input.query));
+    }
+    if let Some(filters) = input.filters {
+        active.filters = Set(filters_to_json(&filters));
+    }
+    if input.sort_by.is_some() {
+        let sort_by = non_blank(input.sort_by);
+        validate_sort(&sort_by)?;
+        active.sort_by = Set(sort_by);
+    }
+    if let Some(run_on_schedule) = input.run_on_schedule {
+        active.run_on_schedule = Set(run_on_schedule as i32);
+    }
+    if let Some(pin_new_matches) = input.pin_new_matches {
+        active.pin_new_matches = Set(pin_new_matches as i32);
+    }
+    active.updated_at = Set(Utc::now());
+
+    Ok(search_to_dto(active.update(db).await?))
+}
+
+/// Delete a saved search
+#[instrument(skip(db))]
+pub async fn delete_saved_search_handler(db: &DatabaseConnection, id: i64) -> AppResult<()> {
+    let res = SavedSearchEntity::delete_by_id(id).exec(db).await?;
+    if res.rows_affected == 0 {
+        return Err(AppError::Validation {
+            field: "id".to_string(),
+            reason: "Saved search not found".to_string(),
+            invalid_value: Some(id.to_string()),
+        });
+    }
+    Ok(())
+}
+
+async fn execute(
+    search: &saved_searches::Model,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    state: &crate::AppState,
+) -> AppResult<Vec<NewsArticleDto>> {
+    let filters = parse_filters(&search.filters);
+    list_news_articles_handler(
+        filters.status,
+        limit,
+        offset,
+        filters.include_dismissed,
+        search.query.clone(),
+        filters.source_id,
+        filters.starred,
+        filters.start_date,
+        filters.end_date,
+        search.sort_by.clone(),
+        state,
+    )
+    .await
+}
+
+/// Execute a saved search by id
+#[instrument(skip(state), fields(search_id = id))]
+pub async fn run_saved_search_handler(
+    id: i64,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    state: &crate::AppState,
+) -> AppResult<Vec<NewsArticleDto>> {
+    let search = find_search(&state.db, id).await?;
+    execute(&search, limit, offset, state).await
+}
+
+/// Scheduled task: run searches flagged `run_on_schedule`
+///
+/// Articles fetched since a search's previous run count as new matches and
+/// are pinned when the search has `pin_new_matches` set. The first run of a
+/// search only records a baseline.
+#[instrument(skip(state))]
+pub async fn run_saved_searches_task(state: &crate::AppState) -> TaskRunResult {
+    let searches = match SavedSearchEntity::find()
+        .filter(saved_searches::Column::RunOnSchedule.eq(1))
+        .all(&state.db)
+        .await
+    {
+        Ok(s) => s,
+        Err(e) => {
+            return TaskRunResult {
+                status: "error",
+                result_json: None,
+                error_message: Some(e.to_string()),
+            }
+        }
+    };
+    if searches.is_empty() {
+        return TaskRunResult {
+            status: "skipped",
+            result_json: Some("{\"reason\":\"no scheduled searches\"}".into()),
+            error_message: None,
+        };
+    }
+
+    let mut new_matches = 0;
+    let mut pinned = 0;
+    let mut failed = 0;
+    for search in &searches {
+        match run_scheduled(search, state).await {
+            Ok((found, pinned_now)) => {
+                new_matches += found;
+                pinned += pinned_now;
+            }
+            Err(e) => {
+                error!(target: "news", search_id = search.id, "Saved search run failed: {}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    TaskRunResult {
+        status: if failed == 0 { "success" } else { "error" },
+        result_json: Some(
+            serde_json::json!({
+                "searches": searches.len(),
+                "newMatches": new_matches,
+                "pinned": pinned,
+                "failed": failed,
+            })
+            .to_string(),
+        ),
+        error_message: (failed > 0).then(|| format!("{} saved searches failed", failed)),
+    }
+}
+
+/// Returns `(new_matches, pinned)`
+async fn run_scheduled(
+    search: &saved_searches::Model,
+    state: &crate::AppState,
+) -> AppResult<(usize, u64)> {
+    let run_at = Utc::now();
+    let results = execute(search, Some(SCHEDULED_RUN_LIMIT), None, state).await?;
+
+    let new_ids: Vec<i64> = results
+        .iter()
+        .filter(|a| match (search.last_run_at, a.fetched_at.as_deref()) {
+            (Some(last_run), Some(fetched)) => chrono::DateTime::parse_from_rfc3339(fetched)
+                .is_ok_and(|f| f.with_timezone(&Utc) > last_run),
+            // The first run only records a baseline
+            _ => false,
+        })
+        .map(|a| a.id)
+        .collect();
+
+    let mut pinned = 0;
+    if search.pin_new_matches == 1 && !new_ids.is_empty() {
+        pinned = EntityNewsArticles::update_many()
+            .col_expr(news_articles::Column::IsPinned, Expr::value(1))
+            .filter(news_articles::Column::Id.is_in(new_ids.clone()))
+            .exec(&state.db)
+            .await?
+            .rows_affected;
+    }
+
+    let mut active = search.clone().into_active_model();
+    active.last_run_at = Set(Some(run_at));
+    active.last_match_count = Set(new_ids.len() as i32);
+    active.update(&state.db).await?;
+
+    info!(
+        target: "news",
+        search_id = search.id,
+        new_matches = new_ids.len(),
+        pinned,
+        "Saved search ran"
+    );
+    Ok((new_ids.len(), pinned))
+}
@@ -0,0 +1,277 @@
+//! News article full-text search
+//!
+//! Ranked search over the `news_articles_fts` FTS5 index (see migration
+//! m013), returning snippets with highlight offsets for the UI. PostgreSQL
+//! has no FTS5; there the search runs over a weighted tsvector of the same
+//! columns, backed by a GIN index from the same migration.
+
+use sea_orm::prelude::Expr;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseBackend, EntityTrait, QueryFilter, Statement};
+use tracing::instrument;
+
+use crate::core::components::errors::AppResult;
+use super::articles::article_to_dto;
+use super::entities::articles::{self as news_articles, Entity as EntityNewsArticles};
+use super::types::{HighlightRange, NewsArticleSearchHit};
+
+// Control characters never appear in article text, so they make safe markers
+const MARK_START: char = '\u{2}';
+const MARK_END: char = '\u{3}';
+
+/// The document PostgreSQL searches, weighted like the FTS5 ranking; must
+/// stay the expression of the `idx_news_articles_search` index (m013)
+const PG_SEARCH_VECTOR: &str = "(setweight(to_tsvector('english', coalesce(title, '')), 'A') \
+     || setweight(to_tsvector('english', coalesce(excerpt, '')), 'B') \
+     || setweight(to_tsvector('english', coalesce(source_name, '')), 'C') \
+     || setweight(to_tsvector('english', coalesce(content, '')), 'D'))";
+
+/// Build an FTS5 MATCH expression from free-form user input
+///
+/// Each term is quoted so punctuation and FTS operators in user input can't
+/// produce syntax errors; terms are ANDed and the last one prefix-matches
+/// to support search-as-you-type. Returns None when nothing searchable remains.
+pub(crate) fn build_fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| t.replace('"', ""))
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+
+    let (last, rest) = terms.split_last()?;
+    let mut query = rest.join(" ");
+    if !query.is_empty() {
+        query.push(' ');
+    }
+    query.push_str(last);
+    query.push('*');
+    Some(query)
+}
+
+/// Build a PostgreSQL `to_tsquery` expression from free-form user input
+///
+/// Like [`build_fts_query`]: terms are quoted and ANDed and the last one
+/// prefix-matches. Characters other than letters and digits are dropped.
+pub(crate) fn build_tsquery(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| {
+            t.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("'{}'", t))
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{}:*", terms.join(" & ")))
+}
+
+/// Condition limiting news articles to those matching `input`
+pub(crate) fn search_condition(backend: DatabaseBackend, input: &str) -> Option<SimpleExpr> {
+    match backend {
+        DatabaseBackend::Postgres => build_tsquery(input).map(|query| {
+            Expr::cust_with_values(
+                format!("{} @@ to_tsquery('english', ?)", PG_SEARCH_VECTOR),
+                [query],
+            )
+        }),
+        _ => build_fts_query(input).map(|query| {
+            Expr::cust_with_values(
+                "news_articles.id IN \
+                 (SELECT rowid FROM news_articles_fts WHERE news_articles_fts MATCH ?)",
+                [query],
+            )
+        }),
+    }
+}
+
+/// Strip highlight markers, returning the clean text and highlighted ranges
+///
+/// Offsets are UTF-16 code units so they can be used directly with
+/// JavaScript string indices.
+pub(crate) fn extract_highlights(marked: &str) -> (String, Vec<HighlightRange>) {
+    let mut text = String::with_capacity(marked.len());
+    let mut ranges = Vec::new();
+    let mut pos = 0usize;
+    let mut start = None;
+
+    for ch in marked.chars() {
+        match ch {
+            MARK_START => start = Some(pos),
+            MARK_END => {
+                if let Some(s) = start.take() {
+                    ranges.push(HighlightRange { start: s, end: pos });
+                }
+            }
+            _ => {
+                text.push(ch);
+                pos += ch.len_utf16();
+            }
+        }
+    }
+
+    (text, ranges)
+}
+
+/// Ranked full-text search over news articles
+///
+/// Results are ordered by BM25 with title matches weighted highest.
+#[instrument(skip(state), fields(query = %query))]
+pub async fn search_news_articles_handler(
+    query: String,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    include_dismissed: Option<bool>,
+    state: &crate::AppState,
+) -> AppResult<Vec<NewsArticleSearchHit>> {
+    let backend = state.db.get_database_backend();
+    let fts_query = match backend {
+        DatabaseBackend::Postgres => build_tsquery(&query),
+        _ => build_fts_query(&query),
+    };
+    let Some(fts_query) = fts_query else {
+        return Ok(vec![]);
+    };
+
+    let dismissed_clause = if include_dismissed == Some(true) {
+        ""
+    } else {
+        "AND a.is_dismissed = 0 AND a.mute_rule_id IS NULL"
+    };
+    let sql = match backend {
+        // Negated so that, as with bm25(), lower ranks better
+        DatabaseBackend::Postgres => format!(
+            r#"SELECT a.id AS id,
+                      -ts_rank({vector}, q)::float8 AS rank,
+                      ts_headline('english', a.title, q,
+                          'HighlightAll=true, StartSel=' || chr(2) || ', StopSel=' || chr(3))
+                          AS title_marked,
+                      ts_headline('english', coalesce(a.content, a.excerpt, ''), q,
+                          'MaxWords=32, MinWords=12, StartSel=' || chr(2) || ', StopSel=' || chr(3))
+                          AS snippet_marked
+               FROM news_articles a, to_tsquery('english', $1) q
+               WHERE {vector} @@ q AND a.user_id = 1 {dismissed_clause}
+               ORDER BY rank
+               LIMIT $2 OFFSET $3"#,
+            vector = PG_SEARCH_VECTOR,
+        ),
+        _ => format!(
+            r#"SELECT a.id AS id,
+                      bm25(news_articles_fts, 10.0, 4.0, 1.0, 2.0) AS rank,
+                      highlight(news_articles_fts, 0, char(2), char(3)) AS title_marked,
+                      snippet(news_articles_fts, -1, char(2), char(3), '…', 32) AS snippet_marked
+               FROM news_articles_fts
+               JOIN news_articles a ON a.id = news_articles_fts.rowid
+               WHERE news_articles_fts MATCH ? AND a.user_id = 1 {}
+               ORDER BY rank
+               LIMIT ? OFFSET ?"#,
+            dismissed_clause
+        ),
+    };
+
+    let rows = state
+        .db
+        .query_all(Statement::from_sql_and_values(
+            backend,
+            sql,
+            [
+                fts_query.into(),
+                (limit.unwrap_or(50) as i64).into(),
+                (offset.unwrap_or(0) as i64).into(),
+            ],
+        ))
+        .await?;
+
+    struct Matched {
+        id: i64,
+        rank: f64,
+        title_marked: String,
+        snippet_marked: String,
+    }
+    let matched: Vec<Matched> = rows
+        .iter()
+        .map(|row| -> AppResult<Matched> {
+            Ok(Matched {
+                id: row.try_get("", "id")?,
+                rank: row.try_get("", "rank")?,
+                title_marked: row.try_get::<Option<String>>("", "title_marked")?.unwrap_or_default(),
+                snippet_marked: row
+                    .try_get::<Option<String>>("", "snippet_marked")?
+                    .unwrap_or_default(),
+            })
+        })
+        .collect::<AppResult<_>>()?;
+
+    if matched.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let ids: Vec<i64> = matched.iter().map(|m| m.id).collect();
+    let mut models: std::collections::HashMap<i64, news_articles::Model> = EntityNewsArticles::find()
+        .filter(news_articles::Column::Id.is_in(ids))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|m| (m.id, m))
+        .collect();
+
+    // Preserve rank order from the FTS query
+    let hits = matched
+        .into_iter()
+        .filter_map(|m| {
+            let model = models.remove(&m.id)?;
+            let (_, title_highlights) = extract_highlights(&m.title_marked);
+            let (snippet, snippet_highlights) = extract_highlights(&m.snippet_marked);
+            Some(NewsArticleSearchHit {
+                article: article_to_dto(model),
+                // Ranks are lower-is-better; flip so higher means more relevant
+                score: -m.rank,
+                snippet,
+                title_highlights,
+                snippet_highlights,
+            })
+        })
+        .collect();
+
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_fts_query_quotes_terms_and_prefixes_last() {
+        assert_eq!(build_fts_query("rust async"), Some("\"rust\" \"async\"*".to_string()));
+        assert_eq!(build_fts_query("  \"NOT\" OR-"), Some("\"NOT\" \"OR-\"*".to_string()));
+        assert_eq!(build_fts_query("   "), None);
+    }
+
+    #[test]
+    fn test_build_tsquery_quotes_terms_and_prefixes_last() {
+        assert_eq!(
+            build_tsquery("rust async"),
+            Some("'rust' & 'async':*".to_string())
+        );
+        assert_eq!(
+            build_tsquery("it's  C++ '"),
+            Some("'its' & 'C':*".to_string())
+        );
+        assert_eq!(build_tsquery(" -- "), None);
+    }
+
+    #[test]
+    fn test_extract_highlights_utf16_offsets() {
+        let marked = format!("caf\u{e9} {}r\u{fc}st{} \u{1F980} {}crab{}", MARK_START, MARK_END, MARK_START, MARK_END);
+        let (text, ranges) = extract_highlights(&marked);
+        assert_eq!(text, "caf\u{e9} r\u{fc}st \u{1F980} crab");
+        assert_eq!(ranges.len(), 2);
+        assert_eq!((ranges[0].start, ranges[0].end), (5, 9));
+        // The crab emoji is two UTF-16 code units
+        assert_eq!((ranges[1].start, ranges[1].end), (13, 17));
+    }
+}
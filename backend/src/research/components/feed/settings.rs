@@ -87,6 +87,7 @@ pub async fn get_news_settings_handler(state: &crate::AppState) -> AppResult<New
     let mut existing = if let Some(m) = model {
         m
     } else {
+        let _secrets = crypto::secret_write().await;
         let mut active = ensure_news_settings_defaults(None);
         if let Some(env_key) = env_news_api_key() {
             info!("news_settings: seeding from env NEWSDATA_API_KEY");
@@ -104,6 +105,7 @@ pub async fn get_news_settings_handler(state: &crate::AppState) -> AppResult<New
     if existing.api_key_encrypted.is_empty() {
         if let Some(env_key) = env_news_api_key() {
             info!("news_settings: hydrating empty api_key from env");
+            let _secrets = crypto::secret_write().await;
             let cipher = crypto::encrypt_api_key(&env_key)
                 .map_err(|e| AppError::Crypto {
                     operation: "encrypt_api_key".to_string(),
@@ -132,6 +134,8 @@ pub async fn save_news_settings_handler(
         .filter(news_settings::Column::Provider.eq("newsdata"))
         .one(&state.db)
         .await?;
+    // Held until the save, so a key rotation can't slip in between
+    let _secrets = crypto::secret_write().await;
     let mut active = ensure_news_settings_defaults(model);
     if let Some(api) = input.api_key {
         let cipher = crypto::encrypt_api_key(&api)
@@ -13,7 +13,6 @@ use super::entities::sources::{self as news_sources, Entity as EntityNewsSources
 use crate::system::components::scheduler::TaskRunResult;
 
 use super::types::{NewsSourceDto, NewsSourceApiResponse, StringOrVec, parse_vec, to_json_vec};
-use super::sync::retry_request;
 
 /// List news sources with optional filtering
 /// 
@@ -87,7 +86,7 @@ pub async fn sync_news_sources_now_handler(
 /// Fetches available news sources and updates local database.
 #[instrument(skip(state))]
 pub async fn run_news_sources_sync_task(state: &crate::AppState) -> TaskRunResult {
-    let client = &state.http_client;
+    let client = &state.http_clients.api;
     let settings = EntityNewsSettings::find()
         .filter(news_settings::Column::UserId.eq(1))
         .filter(news_settings::Column::Provider.eq("newsdata"))
@@ -150,7 +149,7 @@ pub async fn run_news_sources_sync_task(state: &crate::AppState) -> TaskRunResul
         }
 
         // Use retry logic for transient errors and rate limits
-        let resp = match retry_request(|| req.try_clone().unwrap().send()).await {
+        let resp = match client.send(req).await {
             Ok(r) => r,
             Err(e) => {
                 use super::types::sanitize_error_for_logging;
@@ -180,7 +179,7 @@ pub async fn run_news_sources_sync_task(state: &crate::AppState) -> TaskRunResul
                 "https://newsdata.io/api/1/sources",
                 status,
                 &preview,
-                &state.config.logging.api_log_path,
+                &state.config.current().logging.api_log_path,
             );
             return TaskRunResult {
                 status: "error",
@@ -197,7 +196,7 @@ pub async fn run_news_sources_sync_task(state: &crate::AppState) -> TaskRunResul
                     "https://newsdata.io/api/1/sources",
                     status,
                     &preview,
-                    &state.config.logging.api_log_path,
+                    &state.config.current().logging.api_log_path,
                 );
                 return TaskRunResult {
                     status: "error",
@@ -211,7 +210,7 @@ pub async fn run_news_sources_sync_task(state: &crate::AppState) -> TaskRunResul
             "https://newsdata.io/api/1/sources",
             status,
             &text.chars().take(512).collect::<String>(),
-            &state.config.logging.api_log_path,
+            &state.config.current().logging.api_log_path,
         );
 
         if let Some(list) = body.results {
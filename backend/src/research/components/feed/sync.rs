@@ -5,6 +5,7 @@
 
 use tracing::{error, info, instrument, warn};
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use sea_orm::prelude::Expr;
 
 use crate::core::components::crypto;
 use crate::core::components::logging;
@@ -12,72 +13,17 @@ use super::entities::articles::{self as news_articles, Entity as EntityNewsArtic
 use super::entities::settings::{self as news_settings, Entity as EntityNewsSettings};
 use crate::system::components::scheduler::TaskRunResult;
 
-use super::types::{NewsApiResponse, StringOrVec, env_news_api_key, parse_vec, sanitize_error_for_logging, to_json_vec};
+use super::types::{NewsApiResponse, NewsSyncParams, StringOrVec, env_news_api_key, parse_vec, sanitize_error_for_logging, to_json_vec};
 use super::settings::ensure_news_settings_defaults;
-
-/// Retry an HTTP request with exponential backoff
-/// 
-/// Retries up to 3 times with delays of 1s, 2s, 4s
-/// Handles transient network errors and rate limits (429)
-pub(crate) async fn retry_request<F, Fut>(mut f: F) -> Result<reqwest::Response, reqwest::Error>
-where
-    F: FnMut() -> Fut,
-    Fut: std::future::Future<Output = Result<reqwest::Response, reqwest::Error>>,
-{
-    let max_retries = 3;
-    let mut attempt = 0;
-    
-    loop {
-        match f().await {
-            Ok(resp) => {
-                // Check for rate limiting
-                if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                    if attempt >= max_retries {
-                        warn!(target: "news", "Rate limited after {} retries", max_retries);
-                        return Ok(resp);
-                    }
-                    
-                    // Check Retry-After header
-                    let delay = if let Some(retry_after) = resp.headers().get("retry-after") {
-                        retry_after
-                            .to_str()
-                            .ok()
-                            .and_then(|s| s.parse::<u64>().ok())
-                            .unwrap_or(1u64 << attempt)
-                    } else {
-                        1u64 << attempt // Exponential backoff: 1s, 2s, 4s
-                    };
-                    
-                    warn!(target: "news", "Rate limited, retrying in {}s (attempt {}/{})", delay, attempt + 1, max_retries);
-                    tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                    attempt += 1;
-                    continue;
-                }
-                
-                return Ok(resp);
-            }
-            Err(e) => {
-                // Only retry on transient errors
-                if attempt >= max_retries || !e.is_timeout() && !e.is_connect() {
-                    return Err(e);
-                }
-                
-                let delay = 1u64 << attempt; // Exponential backoff
-                let sanitized_error = sanitize_error_for_logging(&e);
-                warn!(target: "news", "Request failed: {}, retrying in {}s (attempt {}/{})", sanitized_error, delay, attempt + 1, max_retries);
-                tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-                attempt += 1;
-            }
-        }
-    }
-}
+use super::alerts::evaluate_alert_rules_logged;
+use super::mutes::apply_mute_rules_logged;
 
 /// Manual trigger for news sync
 pub async fn sync_news_now_handler(
     state: &crate::AppState,
 ) -> crate::core::components::errors::AppResult<crate::system::scheduler::RunTaskNowResult> {
     info!("news_sync: manual trigger invoked");
-    let res = run_news_sync_task(state).await;
+    let res = run_news_sync_task(state, NewsSyncParams::default()).await;
     let finished_at = chrono::Utc::now().to_rfc3339();
     match res.status {
         "success" => info!("news_sync: completed ok"),
@@ -95,10 +41,21 @@ pub async fn sync_news_now_handler(
 /// Scheduled task: Fetch latest news articles from NewsData.io API
 /// 
 /// Runs periodically to sync new articles based on user settings.
-/// Respects daily API call quotas and handles rate limiting.
-#[instrument(skip(state))]
-pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
-    let client = &state.http_client;
+/// Respects daily API call quotas and handles rate limiting. A date range
+/// in `params` replaces the one from settings for this run only.
+#[instrument(skip(state, params))]
+pub async fn run_news_sync_task(state: &crate::AppState, params: NewsSyncParams) -> TaskRunResult {
+    for date in [&params.from_date, &params.to_date].into_iter().flatten() {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return TaskRunResult {
+                status: "error",
+                result_json: None,
+                error_message: Some(format!("Invalid date '{}', expected YYYY-MM-DD", date)),
+            };
+        }
+    }
+
+    let client = &state.http_clients.api;
     let provider = "newsdata".to_string();
     let maybe_settings = EntityNewsSettings::find()
         .filter(news_settings::Column::UserId.eq(1))
@@ -112,6 +69,7 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
             // seed from env if available
             if let Some(env_key) = env_news_api_key() {
                 info!("news_sync: seeding settings from env NEWSDATA_API_KEY");
+                let _secrets = crypto::secret_write().await;
                 let mut active = ensure_news_settings_defaults(None);
                 let cipher = match crypto::encrypt_api_key(&env_key) {
                     Ok(c) => c,
@@ -154,6 +112,7 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
     if settings.api_key_encrypted.is_empty() {
         if let Some(env_key) = env_news_api_key() {
             info!("news_sync: hydrating empty api_key from env");
+            let _secrets = crypto::secret_write().await;
             let cipher = match crypto::encrypt_api_key(&env_key) {
                 Ok(c) => c,
                 Err(e) => {
@@ -186,6 +145,12 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
         }
     }
 
+    // Only the fields changed below are saved, so the override isn't persisted
+    if params.from_date.is_some() || params.to_date.is_some() {
+        settings.from_date = params.from_date;
+        settings.to_date = params.to_date;
+    }
+
     let endpoint = if settings.from_date.is_some() || settings.to_date.is_some() {
         "https://newsdata.io/api/1/archive"
     } else {
@@ -228,6 +193,7 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
 
     let mut inserted = 0;
     let mut updated = 0;
+    let mut new_ids: Vec<i64> = Vec::new();
     let mut calls_used = 0;
     let mut next_page: Option<String> = None;
 
@@ -272,7 +238,7 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
         }
 
         // Use retry logic for transient errors and rate limits
-        let resp = match retry_request(|| req.try_clone().unwrap().send()).await {
+        let resp = match client.send(req).await {
             Ok(r) => r,
             Err(e) => {
                 let sanitized_error = sanitize_error_for_logging(&e);
@@ -298,7 +264,13 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
             Ok(b) => b,
             Err(e) => {
                 let preview = text.chars().take(320).collect::<String>();
-                logging::log_api_call("news_sync", endpoint, status_code, &preview, &state.config.logging.api_log_path);
+                logging::log_api_call(
+                    "news_sync",
+                    endpoint,
+                    status_code,
+                    &preview,
+                    &state.config.current().logging.api_log_path,
+                );
                 return TaskRunResult {
                     status: "error",
                     result_json: None,
@@ -314,7 +286,7 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
             endpoint,
             status_code,
             &text.chars().take(512).collect::<String>(),
-            &state.config.logging.api_log_path,
+            &state.config.current().logging.api_log_path,
         );
         calls_used += 1;
         if let Some(res_list) = body.results {
@@ -423,10 +395,12 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
                         updated_at: Set(chrono::Utc::now()),
                         ..Default::default()
                     };
-                    if let Err(e) = active.insert(&state.db).await {
-                        error!(target: "news_sync", "Article insert failed: {}", e);
-                    } else {
-                        inserted += 1;
+                    match active.insert(&state.db).await {
+                        Ok(m) => {
+                            inserted += 1;
+                            new_ids.push(m.id);
+                        }
+                        Err(e) => error!(target: "news_sync", "Article insert failed: {}", e),
                     }
                 }
             }
@@ -439,6 +413,10 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
         }
     }
 
+    // Mute, then flag, before pruning so rules aren't evaluated after deletion
+    let muted = apply_mute_rules_logged(&state.db, &new_ids).await;
+    let alerts = evaluate_alert_rules_logged(&state.db, &new_ids).await;
+
     let max_keep = settings.max_stored.unwrap_or(settings.max_articles);
 
     if let Ok(total) = EntityNewsArticles::find()
@@ -455,6 +433,11 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
                 .filter(news_articles::Column::IsDismissed.eq(0))
                 .filter(news_articles::Column::AddedToIdeasAt.is_null())
                 .filter(news_articles::Column::DismissedAt.is_null())
+                // Never prune articles waiting in the reading queue
+                .filter(Expr::cust(
+                    "news_articles.id NOT IN \
+                     (SELECT item_id FROM reading_queue WHERE item_type = 'news_article')",
+                ))
                 .order_by_asc(news_articles::Column::PublishedAt)
                 .order_by_asc(news_articles::Column::FetchedAt)
                 .limit(to_delete as u64)
@@ -488,8 +471,14 @@ pub async fn run_news_sync_task(state: &crate::AppState) -> TaskRunResult {
     TaskRunResult {
         status: "success",
         result_json: Some(
-            serde_json::json!({"inserted": inserted, "updated": updated, "callsUsed": calls_used})
-                .to_string(),
+            serde_json::json!({
+                "inserted": inserted,
+                "updated": updated,
+                "callsUsed": calls_used,
+                "alerts": alerts,
+                "muted": muted,
+            })
+            .to_string(),
         ),
         error_message: None,
     }
@@ -6,7 +6,7 @@
 use super::entities::feed_sources::SourceConfig;
 
 /// Feed source data transfer object
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeedSourceDto {
     pub id: i64,
@@ -28,7 +28,7 @@ pub struct FeedSourceDto {
 }
 
 /// Create feed source input
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateFeedSourceInput {
     pub name: String,
@@ -39,7 +39,7 @@ pub struct CreateFeedSourceInput {
 }
 
 /// Update feed source input
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateFeedSourceInput {
     pub name: Option<String>,
@@ -50,7 +50,7 @@ pub struct UpdateFeedSourceInput {
 }
 
 /// Sync result for a single feed source
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncSourceResult {
     pub source_id: i64,
@@ -61,7 +61,7 @@ pub struct SyncSourceResult {
 }
 
 /// Sync all sources result
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncAllResult {
     pub total_sources: i32,
@@ -71,8 +71,216 @@ pub struct SyncAllResult {
     pub results: Vec<SyncSourceResult>,
 }
 
+/// Parameter overrides for a manual `news_sync` run
+#[derive(serde::Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NewsSyncParams {
+    /// `YYYY-MM-DD`; either date switches the run to the archive endpoint
+    pub from_date: Option<String>,
+    pub to_date: Option<String>,
+}
+
+/// Parameter overrides for a manual `feed_sources_sync_all` run
+#[derive(serde::Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FeedSyncParams {
+    /// Sync only these sources, enabled or not
+    pub source_ids: Option<Vec<i64>>,
+}
+
+/// Keyword alert rule data transfer object
+#[derive(serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleDto {
+    pub id: i64,
+    pub name: String,
+    pub keywords: Vec<String>,
+    pub sources: Vec<String>,
+    pub languages: Vec<String>,
+    pub match_all: bool,
+    pub enabled: bool,
+    pub match_count: i32,
+    pub last_matched_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Create alert rule input
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAlertRuleInput {
+    pub name: String,
+    pub keywords: Vec<String>,
+    pub sources: Option<Vec<String>>,
+    pub languages: Option<Vec<String>>,
+    pub match_all: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+/// Update alert rule input (omitted fields are left unchanged)
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAlertRuleInput {
+    pub id: i64,
+    pub name: Option<String>,
+    pub keywords: Option<Vec<String>>,
+    pub sources: Option<Vec<String>>,
+    pub languages: Option<Vec<String>>,
+    pub match_all: Option<bool>,
+    pub enabled: Option<bool>,
+}
+
+/// Dry-run a rule against recent articles
+///
+/// Uses the saved rule when `rule_id` is set; inline fields override it.
+#[derive(Default, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestAlertRuleInput {
+    pub rule_id: Option<i64>,
+    pub keywords: Option<Vec<String>>,
+    pub sources: Option<Vec<String>>,
+    pub languages: Option<Vec<String>>,
+    pub match_all: Option<bool>,
+    /// Number of most recent articles to scan (default: 200)
+    pub limit: Option<u64>,
+}
+
+/// Alert rule dry-run result
+#[derive(serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleTestResult {
+    pub scanned: usize,
+    pub matched: Vec<NewsArticleDto>,
+}
+
+/// Payload of the `news_alert` event
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsAlertEvent {
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub articles: Vec<NewsAlertArticle>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsAlertArticle {
+    pub id: i64,
+    pub title: String,
+    pub url: Option<String>,
+}
+
+/// Mute rule data transfer object
+#[derive(serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MuteRuleDto {
+    pub id: i64,
+    /// `source`, `domain` or `keyword`
+    pub rule_type: String,
+    pub pattern: String,
+    pub enabled: bool,
+    /// Articles currently hidden by this rule
+    pub hidden_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Create mute rule input
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMuteRuleInput {
+    pub rule_type: String,
+    pub pattern: String,
+    pub enabled: Option<bool>,
+}
+
+/// Update mute rule input (omitted fields are left unchanged)
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMuteRuleInput {
+    pub id: i64,
+    pub rule_type: Option<String>,
+    pub pattern: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+/// Preview which articles a rule would hide
+///
+/// Uses the saved rule when `rule_id` is set; inline fields override it.
+#[derive(Default, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewMuteRuleInput {
+    pub rule_id: Option<i64>,
+    pub rule_type: Option<String>,
+    pub pattern: Option<String>,
+    /// Number of most recent articles to scan (default: 500)
+    pub limit: Option<u64>,
+}
+
+/// Mute rule preview result
+#[derive(serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MuteRulePreview {
+    pub scanned: usize,
+    pub hidden: Vec<NewsArticleDto>,
+}
+
+/// Stored filters of a saved search (same meaning as list_news_articles parameters)
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchFilters {
+    pub status: Option<String>,
+    pub source_id: Option<i64>,
+    pub starred: Option<bool>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub include_dismissed: Option<bool>,
+}
+
+/// Saved search data transfer object
+#[derive(serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSearchDto {
+    pub id: i64,
+    pub name: String,
+    pub query: Option<String>,
+    pub filters: SavedSearchFilters,
+    pub sort_by: Option<String>,
+    pub run_on_schedule: bool,
+    pub pin_new_matches: bool,
+    pub last_run_at: Option<String>,
+    pub last_match_count: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Create saved search input
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSavedSearchInput {
+    pub name: String,
+    pub query: Option<String>,
+    pub filters: Option<SavedSearchFilters>,
+    pub sort_by: Option<String>,
+    pub run_on_schedule: Option<bool>,
+    pub pin_new_matches: Option<bool>,
+}
+
+/// Update saved search input (omitted fields are left unchanged)
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSavedSearchInput {
+    pub id: i64,
+    pub name: Option<String>,
+    pub query: Option<String>,
+    pub filters: Option<SavedSearchFilters>,
+    pub sort_by: Option<String>,
+    pub run_on_schedule: Option<bool>,
+    pub pin_new_matches: Option<bool>,
+}
+
 /// News article data transfer object
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewsArticleDto {
     pub id: i64,
@@ -96,10 +304,38 @@ pub struct NewsArticleDto {
     pub is_read: bool,
     pub added_to_ideas_at: Option<String>,
     pub dismissed_at: Option<String>,
+    /// First alert rule that matched this article
+    pub alert_rule_id: Option<i64>,
+    pub alerted_at: Option<String>,
+    /// Mute rule that hid this article
+    pub mute_rule_id: Option<i64>,
+}
+
+/// Highlighted range within a snippet (UTF-16 code unit offsets)
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Full-text search hit for a news article
+#[derive(serde::Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsArticleSearchHit {
+    pub article: NewsArticleDto,
+    /// Relevance score (higher is better)
+    pub score: f64,
+    /// Best-matching excerpt of the article body
+    pub snippet: String,
+    /// Matches within `article.title`
+    pub title_highlights: Vec<HighlightRange>,
+    /// Matches within `snippet`
+    pub snippet_highlights: Vec<HighlightRange>,
 }
 
 /// News settings data transfer object
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewsSettingsDto {
     pub user_id: i64,
@@ -123,7 +359,7 @@ pub struct NewsSettingsDto {
 }
 
 /// Input for saving news settings
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveNewsSettingsInput {
     pub api_key: Option<String>,
@@ -142,7 +378,7 @@ pub struct SaveNewsSettingsInput {
 }
 
 /// News source data transfer object
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, schemars::JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NewsSourceDto {
     pub id: i64,
@@ -4,3 +4,14 @@ pub mod feed;
 pub mod cockpit;
 pub mod connectors;
 pub mod reader;
+pub mod reader_anchor;
+pub mod reader_archive;
+pub mod reader_batch;
+pub mod reader_enex;
+pub mod reader_media;
+pub mod reader_site_rules;
+pub mod reader_watch;
+pub mod reading_queue;
+pub mod reading_stats;
+pub mod site_credentials;
+pub mod tagging;
@@ -1,22 +1,45 @@
 //! Reader cockpit services (references, snapshots, clips)
 
+use std::path::Path;
+
 use chrono::Utc;
+use schemars::JsonSchema;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    QuerySelect, Set,
 };
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Url, WebviewUrl, WebviewWindowBuilder};
+use tracing::warn;
 
+use crate::core::components::ai::{llm_client_from_settings, summarize_text};
 use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
 use crate::core::components::reader::{extract_reader_content, normalize_reader_url};
+use crate::core::components::settings::handlers::get_setting_value;
+use crate::notes::components::notes::append_snippet;
+use crate::research::components::reader_anchor::{anchor_quote, ClipAnchor};
+use crate::research::components::reader_archive::{archive_snapshot_page, archive_url};
+use crate::research::components::reader_media::{
+    archive_snapshot_images, remove_snapshot_media, ArchivedImagesSummary,
+};
+use crate::research::components::reader_site_rules::extraction_rule_for_url;
 use crate::research::entities::{
     reader_clips, reader_references, reader_snapshots,
 };
+use crate::core::config::AiConfig;
 use crate::research::RESEARCH_LIVE_PAGE_WINDOW_LABEL;
+use crate::writing::components::ideas::idea_ids_linking_url;
 
 const WORDS_PER_MINUTE: i32 = 200;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Progress at or above this counts as finished for "continue reading"
+const FINISHED_PERCENT: f64 = 98.0;
+const DEFAULT_CONTINUE_READING_LIMIT: u64 = 20;
+/// Named highlight colors; `#rrggbb` is accepted as well
+const CLIP_COLORS: [&str; 6] = ["yellow", "green", "blue", "pink", "purple", "orange"];
+const AUTO_APPEND_CLIPS_SETTING: &str = "writing.auto_append_clips";
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderFetchInput {
     pub url: String,
@@ -24,15 +47,21 @@ pub struct ReaderFetchInput {
     pub reference_id: Option<i64>,
     pub idea_id: Option<i64>,
     pub writing_id: Option<i64>,
+    /// Download images into the media dir so the snapshot reads offline
+    pub archive_images: Option<bool>,
+    /// Also store a self-contained HTML copy of the page
+    pub full_archive: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderRefreshInput {
     pub reference_id: i64,
+    pub archive_images: Option<bool>,
+    pub full_archive: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderResult {
     pub reference_id: i64,
@@ -44,9 +73,13 @@ pub struct ReaderResult {
     pub content_md: String,
     pub word_count: Option<i32>,
     pub reading_time_minutes: Option<i32>,
+    /// Present when images were archived
+    pub archived_images: Option<ArchivedImagesSummary>,
+    /// Bridge URL of the full HTML archive, when one was stored
+    pub archive_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderReferenceDto {
     pub id: i64,
@@ -55,11 +88,21 @@ pub struct ReaderReferenceDto {
     pub byline: Option<String>,
     pub excerpt: Option<String>,
     pub tags: Vec<String>,
+    pub summary: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    /// Reading progress, 0-100
+    pub progress_percent: Option<f64>,
+    pub progress_scroll_offset: Option<i32>,
+    pub progress_snapshot_id: Option<i64>,
+    pub last_read_at: Option<String>,
+    pub watch: bool,
+    pub watch_interval_minutes: Option<i32>,
+    pub last_checked_at: Option<String>,
+    pub last_changed_at: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderSnapshotDto {
     pub id: i64,
@@ -72,9 +115,13 @@ pub struct ReaderSnapshotDto {
     pub content_md: Option<String>,
     pub word_count: Option<i32>,
     pub reading_time_minutes: Option<i32>,
+    pub summary: Option<String>,
+    pub summary_model: Option<String>,
+    pub summarized_at: Option<String>,
+    pub archive_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderClipDto {
     pub id: i64,
@@ -83,27 +130,91 @@ pub struct ReaderClipDto {
     pub quote: String,
     pub anchor: Option<String>,
     pub created_at: String,
+    pub color: Option<String>,
+    pub label: Option<String>,
+    pub note: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReferenceUpdateInput {
     pub title: Option<String>,
     pub tags: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingProgressInput {
+    pub reference_id: i64,
+    /// Snapshot being read (defaults to the previously recorded one)
+    pub snapshot_id: Option<i64>,
+    /// 0-100; values outside the range are clamped
+    pub percent: f64,
+    /// Scroll position in pixels, for restoring the exact spot
+    pub scroll_offset: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ClipCreateInput {
     pub reference_id: i64,
     pub snapshot_id: i64,
     pub quote: String,
+    /// Replaced by a computed anchor when the quote is found in the snapshot;
+    /// a JSON `ClipAnchor` here picks between repeated occurrences
     pub anchor: Option<String>,
+    pub color: Option<String>,
+    /// Category such as "quote", "evidence" or "counterpoint"
+    pub label: Option<String>,
+    pub note: Option<String>,
+    /// Overrides the `writing.auto_append_clips` setting for this clip
+    pub append_to_ideas: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipUpdateInput {
+    pub clip_id: i64,
+    /// Fields left out are unchanged; an empty string clears the field
+    pub color: Option<String>,
+    pub label: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipListFilter {
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub snapshot_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SummarizeReferenceInput {
+    pub reference_id: i64,
+    /// Defaults to the latest snapshot
+    pub snapshot_id: Option<i64>,
+    /// Regenerate even if the snapshot already has a summary
+    pub force: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceSummaryResult {
+    pub reference_id: i64,
+    pub snapshot_id: i64,
+    pub summary: String,
+    pub model: String,
+    pub summarized_at: String,
+    /// True when an existing summary was returned without calling the model
+    pub cached: bool,
 }
 
 pub async fn reader_fetch(
     db: &sea_orm::DatabaseConnection,
-    http_client: &reqwest::Client,
+    http_client: &HttpClient,
+    media_dir: &Path,
     input: ReaderFetchInput,
 ) -> AppResult<ReaderResult> {
     let normalized_url = normalize_reader_url(&input.url)?;
@@ -124,8 +235,17 @@ pub async fn reader_fetch(
                 byline: None,
                 excerpt: None,
                 tags_json: None,
+                summary: None,
                 created_at: Utc::now().naive_utc(),
                 updated_at: Utc::now().naive_utc(),
+                progress_percent: None,
+                progress_scroll_offset: None,
+                progress_snapshot_id: None,
+                last_read_at: None,
+                watch: 0,
+                watch_interval_minutes: None,
+                last_checked_at: None,
+                last_changed_at: None,
             })
     };
 
@@ -146,7 +266,10 @@ pub async fn reader_fetch(
         reference
     };
 
-    let extracted = extract_reader_content(http_client, &reference.url, input.title.clone()).await?;
+    let rule = extraction_rule_for_url(db, &reference.url).await?;
+    let extracted =
+        extract_reader_content(http_client, &reference.url, input.title.clone(), rule.as_ref())
+            .await?;
     let (word_count, reading_time_minutes) = compute_reading_stats(&extracted.content_text);
 
     let now = Utc::now().naive_utc();
@@ -165,6 +288,44 @@ pub async fn reader_fetch(
     .insert(db)
     .await?;
 
+    let mut content_md = extracted.content_md;
+    let mut archived_images = None;
+    if input.archive_images.unwrap_or(false) {
+        let (rewritten, summary) = archive_snapshot_images(
+            db,
+            http_client,
+            media_dir,
+            snapshot.id,
+            &extracted.final_url,
+            &content_md,
+        )
+        .await?;
+        if summary.archived > 0 {
+            let mut active_snapshot = snapshot.clone().into_active_model();
+            active_snapshot.content_md = Set(rewritten.clone());
+            active_snapshot.update(db).await?;
+            content_md = rewritten;
+        }
+        archived_images = Some(summary);
+    }
+
+    let mut snapshot_archive_url = None;
+    if input.full_archive.unwrap_or(false) {
+        let archived = archive_snapshot_page(
+            http_client,
+            media_dir,
+            snapshot.id,
+            &extracted.final_url,
+            &extracted.raw_html,
+        )
+        .await?;
+        let mut active_snapshot = snapshot.clone().into_active_model();
+        active_snapshot.archive_path = Set(Some(archived.path));
+        active_snapshot.archive_bytes = Set(Some(archived.bytes as i64));
+        active_snapshot.update(db).await?;
+        snapshot_archive_url = Some(archive_url(snapshot.id));
+    }
+
     let mut active_reference: reader_references::ActiveModel = reference.into_active_model();
     if let Some(title) = input.title.clone().filter(|t| !t.trim().is_empty()) {
         active_reference.title = Set(title);
@@ -185,15 +346,18 @@ pub async fn reader_fetch(
         title: extracted.title,
         byline: None,
         excerpt: extracted.excerpt,
-        content_md: extracted.content_md,
+        content_md,
         word_count,
         reading_time_minutes,
+        archived_images,
+        archive_url: snapshot_archive_url,
     })
 }
 
 pub async fn reader_refresh(
     db: &sea_orm::DatabaseConnection,
-    http_client: &reqwest::Client,
+    http_client: &HttpClient,
+    media_dir: &Path,
     input: ReaderRefreshInput,
 ) -> AppResult<ReaderResult> {
     let reference = reader_references::Entity::find_by_id(input.reference_id)
@@ -204,12 +368,15 @@ pub async fn reader_refresh(
     reader_fetch(
         db,
         http_client,
+        media_dir,
         ReaderFetchInput {
             url: reference.url.clone(),
             title: Some(reference.title.clone()),
             reference_id: Some(reference.id),
             idea_id: None,
             writing_id: None,
+            archive_images: input.archive_images,
+            full_archive: input.full_archive,
         },
     )
     .await
@@ -248,6 +415,55 @@ pub async fn reference_update(
     Ok(reference_to_dto(updated))
 }
 
+/// Save how far a reference has been read
+///
+/// Doesn't touch `updated_at`; reading isn't an edit.
+pub async fn update_reading_progress(
+    db: &sea_orm::DatabaseConnection,
+    input: ReadingProgressInput,
+) -> AppResult<ReaderReferenceDto> {
+    if !input.percent.is_finite() {
+        return Err(AppError::validation("percent", "Progress must be a number"));
+    }
+    let reference = reader_references::Entity::find_by_id(input.reference_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Reference {} not found", input.reference_id)))?;
+
+    if let Some(snapshot_id) = input.snapshot_id {
+        reader_snapshots::Entity::find_by_id(snapshot_id)
+            .filter(reader_snapshots::Column::ReferenceId.eq(reference.id))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::other(format!("Snapshot {} not found", snapshot_id)))?;
+    }
+
+    let mut active: reader_references::ActiveModel = reference.into_active_model();
+    active.progress_percent = Set(Some(input.percent.clamp(0.0, 100.0)));
+    active.progress_scroll_offset = Set(input.scroll_offset.map(|o| o.max(0)));
+    if input.snapshot_id.is_some() {
+        active.progress_snapshot_id = Set(input.snapshot_id);
+    }
+    active.last_read_at = Set(Some(Utc::now().naive_utc()));
+    let updated = active.update(db).await?;
+    Ok(reference_to_dto(updated))
+}
+
+/// Started but unfinished references, most recently read first
+pub async fn continue_reading(
+    db: &sea_orm::DatabaseConnection,
+    limit: Option<u64>,
+) -> AppResult<Vec<ReaderReferenceDto>> {
+    let references = reader_references::Entity::find()
+        .filter(reader_references::Column::ProgressPercent.gt(0.0))
+        .filter(reader_references::Column::ProgressPercent.lt(FINISHED_PERCENT))
+        .order_by_desc(reader_references::Column::LastReadAt)
+        .limit(limit.unwrap_or(DEFAULT_CONTINUE_READING_LIMIT))
+        .all(db)
+        .await?;
+    Ok(references.into_iter().map(reference_to_dto).collect())
+}
+
 pub async fn snapshots_list(
     db: &sea_orm::DatabaseConnection,
     reference_id: i64,
@@ -272,12 +488,73 @@ pub async fn snapshot_get(
     Ok(snapshot_to_dto_with_content(snapshot))
 }
 
+/// Delete a snapshot with its clips and archived media
+pub async fn snapshot_delete(
+    db: &sea_orm::DatabaseConnection,
+    media_dir: &Path,
+    snapshot_id: i64,
+) -> AppResult<()> {
+    let result = reader_snapshots::Entity::delete_by_id(snapshot_id)
+        .exec(db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::other(format!("Snapshot {} not found", snapshot_id)));
+    }
+    remove_snapshot_media(media_dir, snapshot_id)?;
+    Ok(())
+}
+
+/// Trimmed and lowercased; empty means none
+fn normalize_clip_label(label: Option<String>) -> Option<String> {
+    label
+        .map(|l| l.trim().to_lowercase())
+        .filter(|l| !l.is_empty())
+}
+
+fn normalize_clip_color(color: Option<String>) -> AppResult<Option<String>> {
+    let Some(color) = color
+        .map(|c| c.trim().to_lowercase())
+        .filter(|c| !c.is_empty())
+    else {
+        return Ok(None);
+    };
+    let is_hex = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !is_hex && !CLIP_COLORS.contains(&color.as_str()) {
+        return Err(AppError::validation(
+            "color",
+            format!(
+                "Unknown color '{}' (expected #rrggbb or one of: {})",
+                color,
+                CLIP_COLORS.join(", ")
+            ),
+        ));
+    }
+    Ok(Some(color))
+}
+
+fn normalize_clip_note(note: Option<String>) -> Option<String> {
+    note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+}
+
 pub async fn clips_list(
     db: &sea_orm::DatabaseConnection,
     reference_id: i64,
+    filter: ClipListFilter,
 ) -> AppResult<Vec<ReaderClipDto>> {
-    let clips = reader_clips::Entity::find()
-        .filter(reader_clips::Column::ReferenceId.eq(reference_id))
+    let mut query =
+        reader_clips::Entity::find().filter(reader_clips::Column::ReferenceId.eq(reference_id));
+    if let Some(label) = normalize_clip_label(filter.label) {
+        query = query.filter(reader_clips::Column::Label.eq(label));
+    }
+    if let Some(color) = normalize_clip_color(filter.color)? {
+        query = query.filter(reader_clips::Column::Color.eq(color));
+    }
+    if let Some(snapshot_id) = filter.snapshot_id {
+        query = query.filter(reader_clips::Column::SnapshotId.eq(snapshot_id));
+    }
+    let clips = query
         .order_by_desc(reader_clips::Column::CreatedAt)
         .all(db)
         .await?;
@@ -291,20 +568,110 @@ pub async fn clip_create(
     if input.quote.trim().is_empty() {
         return Err(AppError::other("Clip quote is required"));
     }
+    let color = normalize_clip_color(input.color)?;
+    let snapshot = reader_snapshots::Entity::find_by_id(input.snapshot_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Snapshot {} not found", input.snapshot_id)))?;
+    // A client-side anchor only serves as a position hint
+    let hint = input
+        .anchor
+        .as_deref()
+        .and_then(|a| serde_json::from_str::<ClipAnchor>(a).ok())
+        .map(|a| a.start);
+    let anchor = anchor_quote(&snapshot.content_md, &input.quote, hint)
+        .map(|a| a.to_json())
+        .or(input.anchor);
+
     let now = Utc::now().naive_utc();
     let clip = reader_clips::ActiveModel {
         reference_id: Set(input.reference_id),
         snapshot_id: Set(input.snapshot_id),
         quote: Set(input.quote.trim().to_string()),
-        anchor: Set(input.anchor),
+        anchor: Set(anchor),
         created_at: Set(now),
+        color: Set(color),
+        label: Set(normalize_clip_label(input.label)),
+        note: Set(normalize_clip_note(input.note)),
         ..Default::default()
     }
     .insert(db)
     .await?;
+
+    let append = match input.append_to_ideas {
+        Some(append) => append,
+        None => get_setting_value(db, AUTO_APPEND_CLIPS_SETTING)
+            .await?
+            .is_some_and(|v| v.trim() == "true"),
+    };
+    if append {
+        append_clip_to_ideas(db, &clip).await;
+    }
     Ok(clip_to_dto(clip))
 }
 
+/// Append a clip to the notes of every idea linking its page
+///
+/// Failures are logged rather than returned; the clip itself is saved.
+async fn append_clip_to_ideas(db: &sea_orm::DatabaseConnection, clip: &reader_clips::Model) {
+    let reference = match reader_references::Entity::find_by_id(clip.reference_id)
+        .one(db)
+        .await
+    {
+        Ok(Some(reference)) => reference,
+        Ok(None) => return,
+        Err(e) => {
+            warn!(target: "reader", clip_id = clip.id, "Failed to load clip reference: {}", e);
+            return;
+        }
+    };
+    let idea_ids = match idea_ids_linking_url(db, &reference.url).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            warn!(target: "reader", clip_id = clip.id, "Failed to find linked ideas: {}", e);
+            return;
+        }
+    };
+
+    for idea_id in idea_ids {
+        if let Err(e) = append_snippet(
+            db,
+            "idea",
+            idea_id,
+            None,
+            &clip.quote,
+            Some(&reference.url),
+            Some(&reference.title),
+        )
+        .await
+        {
+            warn!(target: "reader", clip_id = clip.id, idea_id, "Failed to append clip: {}", e);
+        }
+    }
+}
+
+pub async fn clip_update(
+    db: &sea_orm::DatabaseConnection,
+    input: ClipUpdateInput,
+) -> AppResult<ReaderClipDto> {
+    let clip = reader_clips::Entity::find_by_id(input.clip_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Clip {} not found", input.clip_id)))?;
+
+    let mut active = clip.into_active_model();
+    if input.color.is_some() {
+        active.color = Set(normalize_clip_color(input.color)?);
+    }
+    if input.label.is_some() {
+        active.label = Set(normalize_clip_label(input.label));
+    }
+    if input.note.is_some() {
+        active.note = Set(normalize_clip_note(input.note));
+    }
+    Ok(clip_to_dto(active.update(db).await?))
+}
+
 pub async fn clip_delete(
     db: &sea_orm::DatabaseConnection,
     clip_id: i64,
@@ -316,6 +683,80 @@ pub async fn clip_delete(
     Ok(())
 }
 
+/// Summarize a reference snapshot with the local LLM
+///
+/// The summary is stored on the snapshot and mirrored on the reference.
+pub async fn summarize_reference(
+    db: &sea_orm::DatabaseConnection,
+    http_client: &reqwest::Client,
+    ai: &AiConfig,
+    input: SummarizeReferenceInput,
+) -> AppResult<ReferenceSummaryResult> {
+    let reference = reader_references::Entity::find_by_id(input.reference_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Reference {} not found", input.reference_id)))?;
+
+    let snapshot = match input.snapshot_id {
+        Some(snapshot_id) => reader_snapshots::Entity::find_by_id(snapshot_id)
+            .filter(reader_snapshots::Column::ReferenceId.eq(reference.id))
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::other(format!("Snapshot {} not found", snapshot_id)))?,
+        None => reader_snapshots::Entity::find()
+            .filter(reader_snapshots::Column::ReferenceId.eq(reference.id))
+            .order_by_desc(reader_snapshots::Column::FetchedAt)
+            .one(db)
+            .await?
+            .ok_or_else(|| {
+                AppError::other(format!(
+                    "Reference {} has no snapshot yet; fetch it in reader mode first",
+                    reference.id
+                ))
+            })?,
+    };
+
+    if input.force != Some(true) {
+        if let (Some(summary), Some(summarized_at)) = (&snapshot.summary, snapshot.summarized_at) {
+            return Ok(ReferenceSummaryResult {
+                reference_id: reference.id,
+                snapshot_id: snapshot.id,
+                summary: summary.clone(),
+                model: snapshot.summary_model.clone().unwrap_or_default(),
+                summarized_at: summarized_at.to_string(),
+                cached: true,
+            });
+        }
+    }
+
+    let client = llm_client_from_settings(db, http_client, ai).await?;
+    let title = snapshot.title.clone().unwrap_or_else(|| reference.title.clone());
+    let summary = summarize_text(client.as_ref(), &title, &snapshot.content_md).await?;
+    let model = client.model().to_string();
+
+    let now = Utc::now().naive_utc();
+    let snapshot_id = snapshot.id;
+    let mut active_snapshot = snapshot.into_active_model();
+    active_snapshot.summary = Set(Some(summary.clone()));
+    active_snapshot.summary_model = Set(Some(model.clone()));
+    active_snapshot.summarized_at = Set(Some(now));
+    active_snapshot.update(db).await?;
+
+    let mut active_reference = reference.into_active_model();
+    active_reference.summary = Set(Some(summary.clone()));
+    active_reference.updated_at = Set(now);
+    let reference = active_reference.update(db).await?;
+
+    Ok(ReferenceSummaryResult {
+        reference_id: reference.id,
+        snapshot_id,
+        summary,
+        model,
+        summarized_at: now.to_string(),
+        cached: false,
+    })
+}
+
 pub fn open_live_page_window(app: &AppHandle, url: &str) -> Result<(), String> {
     let normalized = normalize_reader_url(url).map_err(|e| e.to_string())?;
     let parsed = Url::parse(&normalized).map_err(|e| e.to_string())?;
@@ -342,7 +783,7 @@ pub fn open_live_page_window(app: &AppHandle, url: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn reference_to_dto(model: reader_references::Model) -> ReaderReferenceDto {
+pub(crate) fn reference_to_dto(model: reader_references::Model) -> ReaderReferenceDto {
     ReaderReferenceDto {
         id: model.id,
         url: model.url,
@@ -350,8 +791,17 @@ fn reference_to_dto(model: reader_references::Model) -> ReaderReferenceDto {
         byline: model.byline,
         excerpt: model.excerpt,
         tags: parse_tags(&model.tags_json),
+        summary: model.summary,
         created_at: model.created_at.to_string(),
         updated_at: model.updated_at.to_string(),
+        progress_percent: model.progress_percent,
+        progress_scroll_offset: model.progress_scroll_offset,
+        progress_snapshot_id: model.progress_snapshot_id,
+        last_read_at: model.last_read_at.map(|dt| dt.to_string()),
+        watch: model.watch == 1,
+        watch_interval_minutes: model.watch_interval_minutes,
+        last_checked_at: model.last_checked_at.map(|dt| dt.to_string()),
+        last_changed_at: model.last_changed_at.map(|dt| dt.to_string()),
     }
 }
 
@@ -367,6 +817,10 @@ fn snapshot_to_dto(model: reader_snapshots::Model) -> ReaderSnapshotDto {
         content_md: None,
         word_count: model.word_count,
         reading_time_minutes: model.reading_time_minutes,
+        summary: model.summary,
+        summary_model: model.summary_model,
+        summarized_at: model.summarized_at.map(|dt| dt.to_string()),
+        archive_url: model.archive_path.as_ref().map(|_| archive_url(model.id)),
     }
 }
 
@@ -377,7 +831,7 @@ fn snapshot_to_dto_with_content(model: reader_snapshots::Model) -> ReaderSnapsho
     }
 }
 
-fn clip_to_dto(model: reader_clips::Model) -> ReaderClipDto {
+pub(crate) fn clip_to_dto(model: reader_clips::Model) -> ReaderClipDto {
     ReaderClipDto {
         id: model.id,
         reference_id: model.reference_id,
@@ -385,6 +839,9 @@ fn clip_to_dto(model: reader_clips::Model) -> ReaderClipDto {
         quote: model.quote,
         anchor: model.anchor,
         created_at: model.created_at.to_string(),
+        color: model.color,
+        label: model.label,
+        note: model.note,
     }
 }
 
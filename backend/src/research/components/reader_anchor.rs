@@ -0,0 +1,382 @@
+//! Clip anchoring
+//!
+//! A clip's `anchor` column holds a JSON [`ClipAnchor`]: the quoted text, up to
+//! 32 characters of context on each side, and character offsets into the
+//! snapshot's `content_md`. Re-anchoring finds the quote in another snapshot:
+//!
+//! 1. `exact` - the text is still at the recorded offsets
+//! 2. `moved` - the text occurs elsewhere; the occurrence whose surrounding
+//!    text best matches the stored context wins, then the closest one
+//! 3. `fuzzy` - the text was edited; the window of words sharing the most
+//!    words with the quote is used if it shares at least 75% of them
+//!
+//! Anything else is `orphaned` and the clip stays on its old snapshot. Offsets
+//! count Unicode scalar values, not bytes.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::components::reader::{clip_to_dto, ReaderClipDto};
+use crate::research::entities::{reader_clips, reader_snapshots};
+
+const CONTEXT_CHARS: usize = 32;
+const MIN_FUZZY_SCORE: f64 = 0.75;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipAnchor {
+    pub exact: String,
+    pub prefix: String,
+    pub suffix: String,
+    /// Character offsets into the snapshot's markdown
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ClipAnchor {
+    /// Parse a stored anchor; legacy free-form anchors fall back to the quote
+    pub fn from_stored(anchor: Option<&str>, quote: &str) -> Self {
+        anchor
+            .and_then(|a| serde_json::from_str(a).ok())
+            .unwrap_or_else(|| ClipAnchor {
+                exact: quote.to_string(),
+                prefix: String::new(),
+                suffix: String::new(),
+                start: 0,
+                end: quote.chars().count(),
+            })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AnchorStatus {
+    Exact,
+    Moved,
+    Fuzzy,
+    Orphaned,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorMatch {
+    pub anchor: ClipAnchor,
+    pub status: AnchorStatus,
+    /// 1.0 for exact text matches, word overlap for fuzzy ones
+    pub score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipReanchorInput {
+    pub reference_id: i64,
+    /// Only this clip (default: all clips of the reference)
+    pub clip_id: Option<i64>,
+    /// Target snapshot (default: the latest one)
+    pub snapshot_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipReanchorResult {
+    pub clip: ReaderClipDto,
+    pub status: AnchorStatus,
+    pub score: f64,
+}
+
+fn byte_at(text: &str, char_offset: usize) -> usize {
+    text.char_indices()
+        .nth(char_offset)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len())
+}
+
+fn char_at(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].chars().count()
+}
+
+/// Anchor for the text between two byte offsets of `content`
+fn anchor_at(content: &str, start: usize, end: usize) -> ClipAnchor {
+    let prefix: String = {
+        let before: Vec<char> = content[..start].chars().rev().take(CONTEXT_CHARS).collect();
+        before.into_iter().rev().collect()
+    };
+    let suffix: String = content[end..].chars().take(CONTEXT_CHARS).collect();
+    ClipAnchor {
+        exact: content[start..end].to_string(),
+        prefix,
+        suffix,
+        start: char_at(content, start),
+        end: char_at(content, end),
+    }
+}
+
+fn common_suffix_len(a: &str, b: &str) -> usize {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
+/// Word window of `content` sharing the most words with `quote`
+///
+/// Returns byte offsets and the share of quote words found in the window.
+fn fuzzy_window(content: &str, quote: &str) -> Option<(usize, usize, f64)> {
+    let word_re = Regex::new(r"\w+").expect("valid word regex");
+    let mut wanted: HashMap<String, usize> = HashMap::new();
+    let mut quote_len = 0;
+    for m in word_re.find_iter(quote) {
+        *wanted.entry(m.as_str().to_lowercase()).or_insert(0) += 1;
+        quote_len += 1;
+    }
+    if quote_len == 0 {
+        return None;
+    }
+    let words: Vec<(usize, usize, String)> = word_re
+        .find_iter(content)
+        .map(|m| (m.start(), m.end(), m.as_str().to_lowercase()))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    // Sliding window with room for a few inserted words; `matched` is the
+    // multiset overlap with the quote
+    let window = (quote_len + quote_len / 4).min(words.len());
+    let mut in_window: HashMap<&str, usize> = HashMap::new();
+    let mut matched = 0;
+    let mut best: Option<(usize, usize)> = None;
+    for (i, (_, _, word)) in words.iter().enumerate() {
+        if let Some(&limit) = wanted.get(word) {
+            let count = in_window.entry(word.as_str()).or_insert(0);
+            if *count < limit {
+                matched += 1;
+            }
+            *count += 1;
+        }
+        if i >= window {
+            let leaving = words[i - window].2.as_str();
+            if let Some(&limit) = wanted.get(leaving) {
+                let count = in_window.get_mut(leaving).expect("counted on entry");
+                *count -= 1;
+                if *count < limit {
+                    matched -= 1;
+                }
+            }
+        }
+        if i + 1 >= window && best.map_or(true, |(_, m)| matched > m) {
+            best = Some((i + 1 - window, matched));
+        }
+    }
+
+    let (first, matched) = best?;
+    let score = matched as f64 / quote_len as f64;
+    // Trim unmatched words at the window edges
+    let is_wanted = |w: &(usize, usize, String)| wanted.contains_key(&w.2);
+    let slice = &words[first..first + window];
+    let head = slice.iter().position(is_wanted)?;
+    let tail = slice.iter().rposition(is_wanted)?;
+    Some((slice[head].0, slice[tail].1, score))
+}
+
+/// Anchor a newly clipped quote; `near` is a character offset hint
+pub fn anchor_quote(content: &str, quote: &str, near: Option<usize>) -> Option<ClipAnchor> {
+    let quote = quote.trim();
+    if quote.is_empty() {
+        return None;
+    }
+    let near_byte = near.map(|c| byte_at(content, c)).unwrap_or(0);
+    let best = content
+        .match_indices(quote)
+        .map(|(i, _)| i)
+        .min_by_key(|i| i.abs_diff(near_byte));
+    match best {
+        Some(start) => Some(anchor_at(content, start, start + quote.len())),
+        None => fuzzy_window(content, quote)
+            .filter(|(_, _, score)| *score >= MIN_FUZZY_SCORE)
+            .map(|(start, end, _)| anchor_at(content, start, end)),
+    }
+}
+
+/// Find an anchor's text in (possibly different) snapshot content
+pub fn locate(content: &str, anchor: &ClipAnchor) -> AnchorMatch {
+    let orphaned = AnchorMatch {
+        anchor: anchor.clone(),
+        status: AnchorStatus::Orphaned,
+        score: 0.0,
+    };
+    if anchor.exact.trim().is_empty() {
+        return orphaned;
+    }
+
+    let start = byte_at(content, anchor.start);
+    let end = byte_at(content, anchor.end);
+    if content.get(start..end) == Some(anchor.exact.as_str()) {
+        return AnchorMatch {
+            anchor: anchor_at(content, start, end),
+            status: AnchorStatus::Exact,
+            score: 1.0,
+        };
+    }
+
+    let best = content
+        .match_indices(anchor.exact.as_str())
+        .map(|(i, _)| {
+            let context = common_suffix_len(&content[..i], &anchor.prefix)
+                + common_prefix_len(&content[i + anchor.exact.len()..], &anchor.suffix);
+            (i, context)
+        })
+        .max_by(|(a, ca), (b, cb)| {
+            ca.cmp(cb)
+                .then_with(|| b.abs_diff(start).cmp(&a.abs_diff(start)))
+        });
+    if let Some((i, _)) = best {
+        return AnchorMatch {
+            anchor: anchor_at(content, i, i + anchor.exact.len()),
+            status: AnchorStatus::Moved,
+            score: 1.0,
+        };
+    }
+
+    match fuzzy_window(content, &anchor.exact) {
+        Some((start, end, score)) if score >= MIN_FUZZY_SCORE => AnchorMatch {
+            anchor: anchor_at(content, start, end),
+            status: AnchorStatus::Fuzzy,
+            score,
+        },
+        _ => orphaned,
+    }
+}
+
+/// Re-anchor a reference's clips onto a snapshot
+///
+/// Clips that are found move to the target snapshot with a fresh anchor and
+/// quote; orphaned clips are left where they are.
+pub async fn reanchor_clips(
+    db: &sea_orm::DatabaseConnection,
+    input: ClipReanchorInput,
+) -> AppResult<Vec<ClipReanchorResult>> {
+    let target = match input.snapshot_id {
+        Some(id) => reader_snapshots::Entity::find_by_id(id).one(db).await?,
+        None => {
+            reader_snapshots::Entity::find()
+                .filter(reader_snapshots::Column::ReferenceId.eq(input.reference_id))
+                .order_by_desc(reader_snapshots::Column::FetchedAt)
+                .one(db)
+                .await?
+        }
+    }
+    .filter(|s| s.reference_id == input.reference_id)
+    .ok_or_else(|| {
+        AppError::other(format!(
+            "No snapshot found for reference {}",
+            input.reference_id
+        ))
+    })?;
+
+    let mut query = reader_clips::Entity::find()
+        .filter(reader_clips::Column::ReferenceId.eq(input.reference_id));
+    if let Some(clip_id) = input.clip_id {
+        query = query.filter(reader_clips::Column::Id.eq(clip_id));
+    }
+    let clips = query
+        .order_by_asc(reader_clips::Column::CreatedAt)
+        .all(db)
+        .await?;
+    if let Some(clip_id) = input.clip_id.filter(|_| clips.is_empty()) {
+        return Err(AppError::other(format!("Clip {} not found", clip_id)));
+    }
+
+    let mut results = Vec::with_capacity(clips.len());
+    for clip in clips {
+        let stored = ClipAnchor::from_stored(clip.anchor.as_deref(), &clip.quote);
+        let found = locate(&target.content_md, &stored);
+
+        let clip = if found.status == AnchorStatus::Orphaned {
+            clip
+        } else {
+            let mut active = clip.into_active_model();
+            active.snapshot_id = Set(target.id);
+            active.quote = Set(found.anchor.exact.clone());
+            active.anchor = Set(Some(found.anchor.to_json()));
+            active.update(db).await?
+        };
+        results.push(ClipReanchorResult {
+            clip: clip_to_dto(clip),
+            status: found.status,
+            score: found.score,
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "Intro paragraph.\n\nThe central bank raised rates by half a point \
+                            on Tuesday, citing inflation.\n\nClosing remarks.";
+
+    #[test]
+    fn test_anchor_and_locate_exact() {
+        let anchor = anchor_quote(ORIGINAL, "raised rates by half a point", None).unwrap();
+        assert_eq!(anchor.exact, "raised rates by half a point");
+        assert!(anchor.prefix.ends_with("The central bank "));
+        assert!(anchor.suffix.starts_with(" on Tuesday"));
+
+        let found = locate(ORIGINAL, &anchor);
+        assert_eq!(found.status, AnchorStatus::Exact);
+        assert_eq!(found.anchor, anchor);
+    }
+
+    #[test]
+    fn test_locate_moved_prefers_context() {
+        let anchor = anchor_quote(ORIGINAL, "raised rates", None).unwrap();
+        let updated = "Update: markets raised rates expectations.\n\nIntro paragraph.\n\n\
+                       The central bank raised rates by half a point on Tuesday.";
+        let found = locate(updated, &anchor);
+        assert_eq!(found.status, AnchorStatus::Moved);
+        assert!(found.anchor.prefix.ends_with("The central bank "));
+    }
+
+    #[test]
+    fn test_locate_fuzzy_and_orphaned() {
+        let anchor = anchor_quote(
+            ORIGINAL,
+            "The central bank raised rates by half a point",
+            None,
+        )
+        .unwrap();
+        let edited = "Intro.\n\nThe central bank raised interest rates by half a point today.";
+        let found = locate(edited, &anchor);
+        assert_eq!(found.status, AnchorStatus::Fuzzy);
+        assert!(found.anchor.exact.starts_with("The central bank"));
+        assert!(found.anchor.exact.ends_with("point"));
+
+        let unrelated = "A completely different article about gardening.";
+        assert_eq!(locate(unrelated, &anchor).status, AnchorStatus::Orphaned);
+    }
+
+    #[test]
+    fn test_offsets_are_characters() {
+        let content = "Café déjà vu — naïve résumé";
+        let anchor = anchor_quote(content, "naïve", None).unwrap();
+        assert_eq!((anchor.start, anchor.end), (15, 20));
+        assert_eq!(locate(content, &anchor).status, AnchorStatus::Exact);
+    }
+}
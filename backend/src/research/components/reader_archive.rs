@@ -0,0 +1,376 @@
+//! Full-page HTML archives for reader snapshots
+//!
+//! The fetched page is made self-contained: scripts are stripped, stylesheets
+//! become inline `<style>` blocks, and images plus CSS `url(...)` assets are
+//! embedded as data URIs. The archive is written to the snapshot's media dir
+//! (so snapshot deletion removes it) and served by the bridge under
+//! `/reader/archive/<snapshot_id>` with a CSP that blocks scripts and network
+//! loads.
+
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use regex::Regex;
+use reqwest::Url;
+use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
+use crate::research::components::reader_media::{
+    download_resource, resolve_media_path, snapshot_media_dir, snapshot_media_path,
+};
+use crate::research::entities::reader_snapshots;
+
+/// Bridge route serving archived pages by snapshot id
+pub const ARCHIVE_ROUTE_PREFIX: &str = "/reader/archive/";
+
+/// Archived pages may only use what they embed
+pub const ARCHIVE_CSP: &str = "default-src 'none'; img-src data:; style-src 'unsafe-inline'; \
+                               font-src data:; media-src data:";
+
+const ARCHIVE_FILE_NAME: &str = "archive.html";
+const MAX_ARCHIVE_RESOURCES: usize = 150;
+/// Embedded resource budget per page, before base64
+const MAX_ARCHIVE_RESOURCE_BYTES: usize = 40 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedPageSummary {
+    /// Path relative to the media dir
+    pub path: String,
+    pub bytes: u64,
+    pub inlined: usize,
+    pub failed: usize,
+}
+
+pub fn archive_url(snapshot_id: i64) -> String {
+    format!("{}{}", ARCHIVE_ROUTE_PREFIX, snapshot_id)
+}
+
+/// Replace byte ranges of `source`; ranges must be sorted and non-overlapping
+fn apply_replacements(source: &str, replacements: Vec<(Range<usize>, String)>) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut last = 0;
+    for (range, replacement) in replacements {
+        out.push_str(&source[last..range.start]);
+        out.push_str(&replacement);
+        last = range.end;
+    }
+    out.push_str(&source[last..]);
+    out
+}
+
+/// Drop scripts, meta refreshes and `<base>`; returns the page's base href
+fn strip_active_content(html: &str) -> (String, Option<String>) {
+    let base_href = Regex::new(r#"(?is)<base\b[^>]*\bhref\s*=\s*["']([^"']+)["']"#)
+        .expect("valid base regex")
+        .captures(html)
+        .map(|c| c[1].to_string());
+
+    let mut html = html.to_string();
+    for pattern in [
+        r"(?is)<script\b.*?</script\s*>",
+        r"(?is)<script\b[^>]*/>",
+        r"(?is)<noscript\b.*?</noscript\s*>",
+        r#"(?is)<meta\b[^>]*http-equiv\s*=\s*["']?refresh[^>]*>"#,
+        r"(?is)<base\b[^>]*>",
+    ] {
+        let re = Regex::new(pattern).expect("valid strip regex");
+        html = re.replace_all(&html, "").into_owned();
+    }
+    (html, base_href)
+}
+
+/// Fetches and embeds resources, sharing a cache and size budget per page
+struct Inliner<'a> {
+    http_client: &'a HttpClient,
+    cache: HashMap<String, Option<String>>,
+    fetched: usize,
+    embedded_bytes: usize,
+    inlined: usize,
+    failed: usize,
+}
+
+impl<'a> Inliner<'a> {
+    fn new(http_client: &'a HttpClient) -> Self {
+        Self {
+            http_client,
+            cache: HashMap::new(),
+            fetched: 0,
+            embedded_bytes: 0,
+            inlined: 0,
+            failed: 0,
+        }
+    }
+
+    async fn fetch(&mut self, url: &Url) -> Option<(Vec<u8>, String)> {
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return None;
+        }
+        if self.fetched >= MAX_ARCHIVE_RESOURCES
+            || self.embedded_bytes >= MAX_ARCHIVE_RESOURCE_BYTES
+        {
+            self.failed += 1;
+            return None;
+        }
+        self.fetched += 1;
+        match download_resource(self.http_client, url).await {
+            Ok((bytes, _)) if self.embedded_bytes + bytes.len() > MAX_ARCHIVE_RESOURCE_BYTES => {
+                self.failed += 1;
+                None
+            }
+            Ok((bytes, content_type)) => {
+                self.embedded_bytes += bytes.len();
+                self.inlined += 1;
+                Some((bytes, content_type))
+            }
+            Err(e) => {
+                warn!(url = %url, "Archive resource download failed: {}", e);
+                self.failed += 1;
+                None
+            }
+        }
+    }
+
+    /// `data:` URI for a resource, or None when it could not be fetched
+    async fn data_uri(&mut self, url: &Url) -> Option<String> {
+        if let Some(cached) = self.cache.get(url.as_str()) {
+            return cached.clone();
+        }
+        let uri = self.fetch(url).await.map(|(bytes, content_type)| {
+            let mime = content_type.split(';').next().unwrap_or_default().trim();
+            let mime = if mime.is_empty() {
+                "application/octet-stream"
+            } else {
+                mime
+            };
+            format!(
+                "data:{};base64,{}",
+                mime,
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            )
+        });
+        self.cache.insert(url.to_string(), uri.clone());
+        uri
+    }
+
+    /// Embed `url(...)` assets of a stylesheet resolved against `base`
+    async fn inline_css_urls(&mut self, css: &str, base: &Url) -> String {
+        let re = Regex::new(r#"url\(\s*["']?([^"')]+?)["']?\s*\)"#).expect("valid css url regex");
+        let targets: Vec<(Range<usize>, Url)> = re
+            .captures_iter(css)
+            .filter_map(|caps| {
+                let raw = caps[1].trim();
+                if raw.starts_with("data:") || raw.starts_with('#') {
+                    return None;
+                }
+                Some((caps.get(0)?.range(), base.join(raw).ok()?))
+            })
+            .collect();
+        let mut replacements = Vec::new();
+        for (range, url) in targets {
+            let replacement = match self.data_uri(&url).await {
+                Some(uri) => format!("url(\"{}\")", uri),
+                None => "url(\"\")".to_string(),
+            };
+            replacements.push((range, replacement));
+        }
+        apply_replacements(css, replacements)
+    }
+
+    /// Stylesheet text with its `@import`s (one level) and assets embedded
+    async fn stylesheet(&mut self, url: &Url) -> Option<String> {
+        let (bytes, _) = self.fetch(url).await?;
+        let css = String::from_utf8_lossy(&bytes).into_owned();
+
+        let import_re = Regex::new(r#"@import\s+(?:url\(\s*)?["']?([^"')\s;]+)["']?\s*\)?[^;]*;"#)
+            .expect("valid import regex");
+        let imports: Vec<(Range<usize>, Option<Url>)> = import_re
+            .captures_iter(&css)
+            .map(|caps| (caps.get(0).unwrap().range(), url.join(&caps[1]).ok()))
+            .collect();
+        let mut replacements = Vec::new();
+        for (range, import_url) in imports {
+            let imported = match import_url {
+                Some(import_url) => match self.fetch(&import_url).await {
+                    Some((bytes, _)) => {
+                        let text = String::from_utf8_lossy(&bytes).into_owned();
+                        self.inline_css_urls(&text, &import_url).await
+                    }
+                    None => String::new(),
+                },
+                None => String::new(),
+            };
+            replacements.push((range, imported));
+        }
+        let css = apply_replacements(&css, replacements);
+        Some(self.inline_css_urls(&css, url).await)
+    }
+}
+
+/// Make a fetched page self-contained
+async fn build_archive(inliner: &mut Inliner<'_>, page_url: &str, raw_html: &str) -> String {
+    let (html, base_href) = strip_active_content(raw_html);
+    let page = Url::parse(page_url).ok();
+    let base = match (&page, base_href) {
+        (Some(page), Some(href)) => page.join(&href).ok().or_else(|| Some(page.clone())),
+        (page, _) => page.clone(),
+    };
+    let Some(base) = base else {
+        return html;
+    };
+
+    // Stylesheet links become <style> blocks; unreachable ones are dropped
+    let link_re = Regex::new(r"(?is)<link\b[^>]*>").expect("valid link regex");
+    let rel_re =
+        Regex::new(r#"(?i)\brel\s*=\s*["']?[^"'>]*\bstylesheet\b"#).expect("valid rel regex");
+    let href_re = Regex::new(r#"(?i)\bhref\s*=\s*["']([^"']+)["']"#).expect("valid href regex");
+    let links: Vec<(Range<usize>, Option<Url>)> = link_re
+        .find_iter(&html)
+        .filter(|link| rel_re.is_match(link.as_str()))
+        .map(|link| {
+            let href = href_re
+                .captures(link.as_str())
+                .and_then(|c| base.join(&c[1]).ok());
+            (link.range(), href)
+        })
+        .collect();
+    let mut replacements = Vec::new();
+    for (range, href) in links {
+        let css = match href {
+            Some(url) => inliner.stylesheet(&url).await,
+            None => None,
+        };
+        let replacement = css
+            .map(|css| format!("<style>{}</style>", css))
+            .unwrap_or_default();
+        replacements.push((range, replacement));
+    }
+    let html = apply_replacements(&html, replacements);
+
+    // Inline <style> blocks
+    let style_re =
+        Regex::new(r"(?is)(<style\b[^>]*>)(.*?)(</style\s*>)").expect("valid style regex");
+    let blocks: Vec<Range<usize>> = style_re
+        .captures_iter(&html)
+        .filter_map(|caps| caps.get(2))
+        .filter(|body| body.as_str().contains("url("))
+        .map(|body| body.range())
+        .collect();
+    let mut replacements = Vec::new();
+    for range in blocks {
+        let css = inliner.inline_css_urls(&html[range.clone()], &base).await;
+        replacements.push((range, css));
+    }
+    let html = apply_replacements(&html, replacements);
+
+    // Images; srcset would bypass the embedded src, so it is dropped
+    let srcset_re = Regex::new(r#"(?is)\s(?:data-)?srcset\s*=\s*("[^"]*"|'[^']*')"#)
+        .expect("valid srcset regex");
+    let html = srcset_re.replace_all(&html, "").into_owned();
+    let img_re =
+        Regex::new(r#"(?is)<img\b[^>]*?\bsrc\s*=\s*["']([^"']+)["']"#).expect("valid img regex");
+    let images: Vec<(Range<usize>, Url)> = img_re
+        .captures_iter(&html)
+        .filter_map(|caps| caps.get(1))
+        .filter(|src| !src.as_str().starts_with("data:"))
+        .filter_map(|src| Some((src.range(), base.join(src.as_str()).ok()?)))
+        .collect();
+    let mut replacements = Vec::new();
+    for (range, url) in images {
+        if let Some(uri) = inliner.data_uri(&url).await {
+            replacements.push((range, uri));
+        }
+    }
+    let html = apply_replacements(&html, replacements);
+
+    // Links keep pointing at the original site
+    let base_tag = format!("<base href=\"{}\">", base.as_str().replace('"', "%22"));
+    let head_re = Regex::new(r"(?i)<head\b[^>]*>").expect("valid head regex");
+    match head_re.find(&html) {
+        Some(head) => format!("{}{}{}", &html[..head.end()], base_tag, &html[head.end()..]),
+        None => format!("{}{}", base_tag, html),
+    }
+}
+
+/// Build and store a self-contained copy of a fetched page
+pub async fn archive_snapshot_page(
+    http_client: &HttpClient,
+    media_dir: &Path,
+    snapshot_id: i64,
+    page_url: &str,
+    raw_html: &str,
+) -> AppResult<ArchivedPageSummary> {
+    let mut inliner = Inliner::new(http_client);
+    let html = build_archive(&mut inliner, page_url, raw_html).await;
+
+    let dir = snapshot_media_dir(media_dir, snapshot_id);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(ARCHIVE_FILE_NAME), html.as_bytes())?;
+
+    let summary = ArchivedPageSummary {
+        path: snapshot_media_path(snapshot_id, ARCHIVE_FILE_NAME),
+        bytes: html.len() as u64,
+        inlined: inliner.inlined,
+        failed: inliner.failed,
+    };
+    info!(
+        snapshot_id,
+        bytes = summary.bytes,
+        inlined = summary.inlined,
+        failed = summary.failed,
+        "Archived snapshot page"
+    );
+    Ok(summary)
+}
+
+/// Location of a snapshot's archived page
+pub async fn archived_page_path(
+    db: &sea_orm::DatabaseConnection,
+    media_dir: &Path,
+    snapshot_id: i64,
+) -> AppResult<PathBuf> {
+    let snapshot = reader_snapshots::Entity::find_by_id(snapshot_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Snapshot {} not found", snapshot_id)))?;
+    snapshot
+        .archive_path
+        .as_deref()
+        .and_then(|path| resolve_media_path(media_dir, path))
+        .ok_or_else(|| AppError::other(format!("Snapshot {} has no archive", snapshot_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_active_content() {
+        let html = r#"<html><head><base href="/en/"><meta http-equiv="refresh" content="0">
+<script src="a.js"></script><script>alert(1)</script></head>
+<body><p>Text</p><noscript><img src="t.gif"></noscript></body></html>"#;
+        let (stripped, base) = strip_active_content(html);
+        assert_eq!(base.as_deref(), Some("/en/"));
+        assert!(!stripped.contains("script"));
+        assert!(!stripped.contains("refresh"));
+        assert!(!stripped.contains("<base"));
+        assert!(stripped.contains("<p>Text</p>"));
+    }
+
+    #[test]
+    fn test_apply_replacements() {
+        assert_eq!(
+            apply_replacements(
+                "a url(x) b url(y)",
+                vec![(2..8, "U1".into()), (11..17, "U2".into())]
+            ),
+            "a U1 b U2"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Batch URL import into the reader
+//!
+//! Takes a pasted list of links, skips duplicates and pages that already have
+//! a reference, and fetches the rest a few at a time. Every input URL gets a
+//! result, in input order, so the caller can show what happened to each.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
+use crate::core::components::reader::normalize_reader_url;
+use crate::research::components::reader::{reader_fetch, ReaderFetchInput};
+use crate::research::entities::reader_references;
+
+const MAX_BATCH_URLS: usize = 200;
+const DEFAULT_CONCURRENCY: usize = 4;
+const MAX_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderFetchBatchInput {
+    pub urls: Vec<String>,
+    /// Pages fetched at once (default 4, max 8)
+    pub concurrency: Option<usize>,
+    /// Fetch a new snapshot for URLs that already have a reference
+    pub refetch_existing: Option<bool>,
+    pub archive_images: Option<bool>,
+    pub full_archive: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderBatchItemResult {
+    /// As given
+    pub url: String,
+    /// "fetched", "existing", "duplicate", "invalid" or "failed"
+    pub status: String,
+    pub reference_id: Option<i64>,
+    pub snapshot_id: Option<i64>,
+    pub title: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderFetchBatchResult {
+    pub results: Vec<ReaderBatchItemResult>,
+    pub fetched: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Planned {
+    Fetch(String),
+    Duplicate,
+    Invalid(String),
+}
+
+/// Normalize each URL, marking repeats of an earlier entry as duplicates
+fn plan_batch(urls: &[String]) -> Vec<Planned> {
+    let mut seen = HashSet::new();
+    urls.iter()
+        .map(|raw| match normalize_reader_url(raw) {
+            Ok(url) if seen.insert(url.clone()) => Planned::Fetch(url),
+            Ok(_) => Planned::Duplicate,
+            Err(e) => Planned::Invalid(e.to_string()),
+        })
+        .collect()
+}
+
+fn item(url: &str, status: &str) -> ReaderBatchItemResult {
+    ReaderBatchItemResult {
+        url: url.to_string(),
+        status: status.to_string(),
+        reference_id: None,
+        snapshot_id: None,
+        title: None,
+        error: None,
+    }
+}
+
+pub async fn reader_fetch_batch(
+    db: &sea_orm::DatabaseConnection,
+    http_client: &HttpClient,
+    media_dir: &Path,
+    input: ReaderFetchBatchInput,
+) -> AppResult<ReaderFetchBatchResult> {
+    let urls: Vec<String> = input
+        .urls
+        .into_iter()
+        .map(|u| u.trim().to_string())
+        .filter(|u| !u.is_empty())
+        .collect();
+    if urls.is_empty() {
+        return Err(AppError::validation("urls", "At least one URL is required"));
+    }
+    if urls.len() > MAX_BATCH_URLS {
+        return Err(AppError::validation(
+            "urls",
+            format!("At most {} URLs per batch", MAX_BATCH_URLS),
+        ));
+    }
+    let concurrency = input
+        .concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, MAX_CONCURRENCY);
+
+    let plan = plan_batch(&urls);
+    let normalized: Vec<String> = plan
+        .iter()
+        .filter_map(|p| match p {
+            Planned::Fetch(url) => Some(url.clone()),
+            _ => None,
+        })
+        .collect();
+    let existing: HashMap<String, reader_references::Model> = reader_references::Entity::find()
+        .filter(reader_references::Column::Url.is_in(normalized))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|r| (r.url.clone(), r))
+        .collect();
+    let refetch_existing = input.refetch_existing.unwrap_or(false);
+
+    let mut results: Vec<ReaderBatchItemResult> = Vec::with_capacity(urls.len());
+    let mut queue = Vec::new();
+    for (index, (raw, planned)) in urls.iter().zip(plan).enumerate() {
+        let result = match planned {
+            Planned::Duplicate => item(raw, "duplicate"),
+            Planned::Invalid(error) => ReaderBatchItemResult {
+                error: Some(error),
+                ..item(raw, "invalid")
+            },
+            Planned::Fetch(url) => match existing.get(&url) {
+                Some(reference) if !refetch_existing => ReaderBatchItemResult {
+                    reference_id: Some(reference.id),
+                    title: Some(reference.title.clone()),
+                    ..item(raw, "existing")
+                },
+                reference => {
+                    queue.push((index, url, reference.map(|r| r.id)));
+                    item(raw, "pending")
+                }
+            },
+        };
+        results.push(result);
+    }
+
+    // JoinSet keeps at most `concurrency` fetches in flight
+    let mut tasks = JoinSet::new();
+    let mut queue = queue.into_iter();
+    loop {
+        while tasks.len() < concurrency {
+            let Some((index, url, reference_id)) = queue.next() else {
+                break;
+            };
+            let db = db.clone();
+            let http_client = http_client.clone();
+            let media_dir: PathBuf = media_dir.to_path_buf();
+            let fetch_input = ReaderFetchInput {
+                url,
+                title: None,
+                reference_id,
+                idea_id: None,
+                writing_id: None,
+                archive_images: input.archive_images,
+                full_archive: input.full_archive,
+            };
+            tasks.spawn(async move {
+                let res = reader_fetch(&db, &http_client, &media_dir, fetch_input).await;
+                (index, res)
+            });
+        }
+        let Some(joined) = tasks.join_next().await else {
+            break;
+        };
+        let (index, res) = match joined {
+            Ok(done) => done,
+            Err(e) => {
+                warn!(target: "reader", "Batch fetch task failed: {}", e);
+                continue;
+            }
+        };
+        let result = &mut results[index];
+        match res {
+            Ok(fetched) => {
+                result.status = "fetched".to_string();
+                result.reference_id = Some(fetched.reference_id);
+                result.snapshot_id = Some(fetched.snapshot_id);
+                result.title = Some(fetched.title);
+            }
+            Err(e) => {
+                warn!(target: "reader", url = %result.url, "Batch fetch failed: {}", e);
+                result.status = "failed".to_string();
+                result.error = Some(e.to_string());
+            }
+        }
+    }
+    // A panicked task leaves its entry pending
+    for result in results.iter_mut().filter(|r| r.status == "pending") {
+        result.status = "failed".to_string();
+        result.error = Some("Fetch task stopped unexpectedly".to_string());
+    }
+
+    let fetched = results.iter().filter(|r| r.status == "fetched").count();
+    let failed = results
+        .iter()
+        .filter(|r| r.status == "failed" || r.status == "invalid")
+        .count();
+    let skipped = results.len() - fetched - failed;
+    info!(target: "reader", fetched, skipped, failed, "Reader batch import finished");
+
+    Ok(ReaderFetchBatchResult {
+        results,
+        fetched,
+        skipped,
+        failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_batch_dedupes() {
+        let urls = vec![
+            "https://example.com/a".to_string(),
+            "example.com/a".to_string(),
+            "https://example.com/b".to_string(),
+            "https://exa mple.com".to_string(),
+        ];
+        let plan = plan_batch(&urls);
+        assert_eq!(plan[0], Planned::Fetch("https://example.com/a".into()));
+        assert_eq!(plan[1], Planned::Duplicate);
+        assert_eq!(plan[2], Planned::Fetch("https://example.com/b".into()));
+        assert!(matches!(plan[3], Planned::Invalid(_)));
+    }
+}
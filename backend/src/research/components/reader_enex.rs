@@ -0,0 +1,524 @@
+//! Evernote ENEX import into the reader
+//!
+//! Each note with a source URL becomes a reader reference, with the note's
+//! content as its main note; a note without one becomes an idea with a main
+//! note instead. Attachments are saved under
+//! `<media_dir>/evernote/<kind>/<id>/` and shown in place of the note's
+//! `<en-media>` tags, images inline and other files as links; attachments the
+//! note doesn't show are listed at its end.
+//!
+//! References and ideas keep the note's creation and update times. Notes
+//! whose URL already has a reference, or whose title already names an idea,
+//! are skipped, as are repeats of a URL within the file.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use md5::{Digest, Md5};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
+    TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::reader::{html_to_text, normalize_reader_url, sanitize_html};
+use crate::research::components::reader_media::MEDIA_ROUTE_PREFIX;
+use crate::research::entities::reader_references;
+use crate::writing::components::ideas::types::{self as ideas, tags_to_json, IdeaStatus};
+use crate::writing::components::knowledge_graph::entities::notes;
+
+const ENEX_MEDIA_SUBDIR: &str = "evernote";
+const EXCERPT_CHARS: usize = 220;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderImportEnexInput {
+    /// The `.enex` file
+    pub import_path: Option<String>,
+    /// A file staged through `POST /upload`, in place of `import_path`
+    pub upload_handle: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderEnexImportResult {
+    pub references_created: usize,
+    /// From notes without a source URL
+    pub ideas_created: usize,
+    pub attachments_saved: usize,
+    /// Notes whose URL or idea title already exists
+    pub skipped: usize,
+}
+
+/// A note of the export
+#[derive(Debug, Default)]
+struct EnexNote {
+    title: String,
+    /// ENML
+    content: String,
+    created: Option<DateTime<Utc>>,
+    updated: Option<DateTime<Utc>>,
+    tags: Vec<String>,
+    source_url: Option<String>,
+    author: Option<String>,
+    resources: Vec<EnexResource>,
+}
+
+#[derive(Debug, Default)]
+struct EnexResource {
+    data: Vec<u8>,
+    mime: String,
+    file_name: Option<String>,
+}
+
+impl EnexResource {
+    /// The MD5 `<en-media>` tags refer to the resource by
+    fn hash(&self) -> String {
+        hex::encode(Md5::digest(&self.data))
+    }
+}
+
+/// Import the notes of an Evernote export
+#[instrument(skip(db, media_dir))]
+pub async fn import_enex(
+    db: &DatabaseConnection,
+    media_dir: &Path,
+    enex_path: &Path,
+) -> AppResult<ReaderEnexImportResult> {
+    let file = File::open(enex_path)
+        .map_err(|e| AppError::file_operation("open", enex_path.to_string_lossy(), e))?;
+    let enex_notes = parse_enex(BufReader::new(file))?;
+
+    let mut taken_urls: HashSet<String> = reader_references::Entity::find()
+        .select_only()
+        .column(reader_references::Column::Url)
+        .into_tuple::<String>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+    let mut taken_titles: HashSet<String> = ideas::Entity::find()
+        .filter(ideas::Column::DateRemoved.is_null())
+        .select_only()
+        .column(ideas::Column::Title)
+        .into_tuple::<String>()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|title| title.trim().to_lowercase())
+        .collect();
+
+    let tx = db.begin().await?;
+    let now = Utc::now();
+    let mut result = ReaderEnexImportResult::default();
+    for note in &enex_notes {
+        let created = note.created.unwrap_or(now);
+        let updated = note.updated.unwrap_or(created);
+        let title = Some(note.title.trim())
+            .filter(|t| !t.is_empty())
+            .unwrap_or("Untitled note")
+            .to_string();
+        let url = note
+            .source_url
+            .as_deref()
+            .and_then(|url| normalize_reader_url(url).ok());
+
+        let (entity_type, entity_id, kind) = match url {
+            Some(url) => {
+                if !taken_urls.insert(url.clone()) {
+                    debug!(url = %url, "Reader reference already exists");
+                    result.skipped += 1;
+                    continue;
+                }
+                let text = html_to_text(&enml_to_html(&note.content, &HashMap::new()));
+                let reference = reader_references::ActiveModel {
+                    url: Set(url),
+                    title: Set(title),
+                    byline: Set(note.author.clone()),
+                    excerpt: Set(Some(excerpt(&text)).filter(|e| !e.is_empty())),
+                    tags_json: Set(tags_to_json(&note.tags)),
+                    created_at: Set(created.naive_utc()),
+                    updated_at: Set(updated.naive_utc()),
+                    ..Default::default()
+                }
+                .insert(&tx)
+                .await?;
+                result.references_created += 1;
+                (
+                    notes::EntityType::ReaderReference,
+                    reference.id,
+                    "references",
+                )
+            }
+            None => {
+                if !taken_titles.insert(title.to_lowercase()) {
+                    debug!(title = %title, "Idea already exists");
+                    result.skipped += 1;
+                    continue;
+                }
+                let idea = ideas::ActiveModel {
+                    title: Set(title),
+                    status: Set(IdeaStatus::InProgress),
+                    tags: Set(tags_to_json(&note.tags)),
+                    date_added: Set(created),
+                    date_updated: Set(updated),
+                    priority: Set(0),
+                    is_pinned: Set(0),
+                    ..Default::default()
+                }
+                .insert(&tx)
+                .await?;
+                result.ideas_created += 1;
+                (notes::EntityType::Idea, idea.id, "ideas")
+            }
+        };
+
+        // Attachments first, so the note can show them
+        let relative_dir = format!("{}/{}/{}", ENEX_MEDIA_SUBDIR, kind, entity_id);
+        let mut links = HashMap::new();
+        for resource in &note.resources {
+            let hash = resource.hash();
+            if links.contains_key(&hash) {
+                continue;
+            }
+            let dir = media_dir.join(&relative_dir);
+            let name = attachment_name(resource, &hash);
+            let path = dir.join(&name);
+            match fs::create_dir_all(&dir).and_then(|_| fs::write(&path, &resource.data)) {
+                Ok(()) => {
+                    let link = format!("{}{}/{}", MEDIA_ROUTE_PREFIX, relative_dir, name);
+                    links.insert(hash, (link, resource));
+                    result.attachments_saved += 1;
+                }
+                Err(e) => warn!(path = %path.display(), error = %e, "Failed to save attachment"),
+            }
+        }
+
+        notes::ActiveModel {
+            entity_type: Set(entity_type),
+            entity_id: Set(entity_id),
+            note_type: Set(Some(notes::NoteType::Main.to_string())),
+            body_html: Set(enml_to_html(&note.content, &links)),
+            created_at: Set(created),
+            updated_at: Set(updated),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    info!(
+        path = %enex_path.display(),
+        references = result.references_created,
+        ideas = result.ideas_created,
+        attachments = result.attachments_saved,
+        skipped = result.skipped,
+        "Imported Evernote export"
+    );
+    Ok(result)
+}
+
+/// The notes of an ENEX document
+fn parse_enex<R: std::io::BufRead>(source: R) -> AppResult<Vec<EnexNote>> {
+    let invalid = |e: quick_xml::Error| {
+        AppError::validation("import_path", format!("Not a valid ENEX file: {}", e))
+    };
+    let mut reader = Reader::from_reader(source);
+    reader.config_mut().expand_empty_elements = true;
+
+    let mut notes = Vec::new();
+    let mut note: Option<EnexNote> = None;
+    let mut resource: Option<EnexResource> = None;
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut buf = Vec::new();
+    let mut saw_export = false;
+    loop {
+        match reader.read_event_into(&mut buf).map_err(invalid)? {
+            Event::Start(e) => {
+                let name = e.local_name().into_inner().to_string();
+                match name.as_str() {
+                    "en-export" => saw_export = true,
+                    "note" => note = Some(EnexNote::default()),
+                    "resource" => resource = Some(EnexResource::default()),
+                    _ => {}
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Text(e) => text.push_str(&e.xml10_content()),
+            Event::CData(e) => text.push_str(&e.xml10_content()),
+            Event::GeneralRef(e) => match e.resolve_char_ref() {
+                Ok(Some(ch)) => text.push(ch),
+                _ => text.push_str(match &*e {
+                    "amp" => "&",
+                    "lt" => "<",
+                    "gt" => ">",
+                    "quot" => "\"",
+                    "apos" => "'",
+                    _ => "",
+                }),
+            },
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str).unwrap_or_default();
+                let value = std::mem::take(&mut text);
+                match (resource.as_mut(), note.as_mut()) {
+                    (Some(res), _) => match (name.as_str(), parent) {
+                        ("data", "resource") => {
+                            let data: String =
+                                value.chars().filter(|c| !c.is_whitespace()).collect();
+                            res.data = base64::engine::general_purpose::STANDARD
+                                .decode(data)
+                                .unwrap_or_default();
+                        }
+                        ("mime", "resource") => res.mime = value.trim().to_string(),
+                        ("file-name", "resource-attributes") => {
+                            res.file_name =
+                                Some(value.trim().to_string()).filter(|n| !n.is_empty());
+                        }
+                        ("resource", _) => {
+                            if let (Some(done), Some(note)) = (resource.take(), note.as_mut()) {
+                                if !done.data.is_empty() {
+                                    note.resources.push(done);
+                                }
+                            }
+                        }
+                        _ => {}
+                    },
+                    (None, Some(current)) => match (name.as_str(), parent) {
+                        ("title", "note") => current.title = value,
+                        ("content", "note") => current.content = value,
+                        ("created", "note") => current.created = parse_enex_date(&value),
+                        ("updated", "note") => current.updated = parse_enex_date(&value),
+                        ("tag", "note") if !value.trim().is_empty() => {
+                            current.tags.push(value.trim().to_string());
+                        }
+                        ("source-url", "note-attributes") => {
+                            current.source_url = Some(value).filter(|u| !u.trim().is_empty());
+                        }
+                        ("author", "note-attributes") => {
+                            current.author = Some(value).filter(|a| !a.trim().is_empty());
+                        }
+                        ("note", _) => notes.extend(note.take()),
+                        _ => {}
+                    },
+                    (None, None) => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !saw_export {
+        return Err(AppError::validation("import_path", "Not an ENEX file"));
+    }
+    Ok(notes)
+}
+
+/// `20240105T093000Z`, the ENEX timestamp format
+fn parse_enex_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+/// Sanitized HTML for an ENML note, showing its attachments
+fn enml_to_html(enml: &str, attachments: &HashMap<String, (String, &EnexResource)>) -> String {
+    let prolog = Regex::new(r"(?is)<\?xml.*?\?>|<!DOCTYPE[^>]*>").expect("valid prolog regex");
+    let note_tag = Regex::new(r"(?i)<(/?)en-note\b[^>]*>").expect("valid en-note regex");
+    let media = Regex::new(r"(?is)<en-media\b([^>]*?)/?>(?:\s*</en-media>)?")
+        .expect("valid en-media regex");
+    let todo =
+        Regex::new(r"(?is)<en-todo\b([^>]*?)/?>(?:\s*</en-todo>)?").expect("valid en-todo regex");
+    let crypt = Regex::new(r"(?is)<en-crypt\b.*?</en-crypt>").expect("valid en-crypt regex");
+    let hash_attr =
+        Regex::new(r#"(?i)\bhash\s*=\s*["']([0-9a-f]+)["']"#).expect("valid hash regex");
+    let checked = Regex::new(r#"(?i)\bchecked\s*=\s*["']true["']"#).expect("valid checked regex");
+
+    let html = prolog.replace_all(enml, "");
+    let html = note_tag.replace_all(&html, "<${1}div>");
+    let html = crypt.replace_all(&html, "");
+    let html = todo.replace_all(&html, |caps: &Captures| {
+        if checked.is_match(&caps[1]) {
+            "\u{2611} "
+        } else {
+            "\u{2610} "
+        }
+    });
+    let mut shown = HashSet::new();
+    let mut html = media
+        .replace_all(&html, |caps: &Captures| {
+            let hash = hash_attr
+                .captures(&caps[1])
+                .map(|h| h[1].to_lowercase())
+                .unwrap_or_default();
+            match attachments.get(&hash) {
+                Some((link, resource)) => {
+                    shown.insert(hash);
+                    attachment_html(link, resource)
+                }
+                None => String::new(),
+            }
+        })
+        .into_owned();
+
+    let mut unshown: Vec<_> = attachments
+        .iter()
+        .filter(|(hash, _)| !shown.contains(*hash))
+        .map(|(_, (link, resource))| attachment_html(link, resource))
+        .collect();
+    if !unshown.is_empty() {
+        unshown.sort();
+        html.push_str("<ul>");
+        for item in unshown {
+            html.push_str(&format!("<li>{}</li>", item));
+        }
+        html.push_str("</ul>");
+    }
+    sanitize_html(&html)
+}
+
+fn attachment_html(link: &str, resource: &EnexResource) -> String {
+    let label = resource.file_name.as_deref().unwrap_or("attachment");
+    if resource.mime.starts_with("image/") {
+        format!(
+            "<img src=\"{}\" alt=\"{}\">",
+            link,
+            crate::writing::text::escape_html(label)
+        )
+    } else {
+        format!(
+            "<a href=\"{}\">{}</a>",
+            link,
+            crate::writing::text::escape_html(label)
+        )
+    }
+}
+
+/// A safe file name for an attachment, unique within its note
+fn attachment_name(resource: &EnexResource, hash: &str) -> String {
+    let name: String = resource
+        .file_name
+        .as_deref()
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        format!("{}.{}", &hash[..16], extension_for(&resource.mime))
+    } else {
+        format!("{}-{}", &hash[..8], name)
+    }
+}
+
+fn extension_for(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "application/pdf" => "pdf",
+        "audio/mpeg" => "mp3",
+        "audio/wav" => "wav",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    match text.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}...", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_enex() {
+        let data = base64::engine::general_purpose::STANDARD.encode(b"PNGDATA");
+        let hash = hex::encode(Md5::digest(b"PNGDATA"));
+        let enex = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE en-export SYSTEM "http://xml.evernote.com/pub/evernote-export3.dtd">
+<en-export export-date="20240101T000000Z" application="Evernote">
+  <note>
+    <title>Cats &amp; dogs</title>
+    <created>20240105T093000Z</created>
+    <tag>pets</tag><tag>clips</tag>
+    <note-attributes>
+      <source-url>https://example.com/cats?a=1&amp;b=2</source-url>
+      <author>Ann</author>
+    </note-attributes>
+    <content><![CDATA[<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE en-note SYSTEM "http://xml.evernote.com/pub/enml2.dtd"><en-note><div><en-todo checked="true"/>Fed <b>cats</b></div><en-media hash="{hash}" type="image/png"/><script>x()</script></en-note>]]></content>
+    <resource>
+      <data encoding="base64">
+{data}
+      </data>
+      <mime>image/png</mime>
+      <resource-attributes><file-name>../cat photo.png</file-name></resource-attributes>
+    </resource>
+  </note>
+  <note><title>Plain</title><content><![CDATA[<en-note>Just text</en-note>]]></content></note>
+</en-export>"#
+        );
+
+        let notes = parse_enex(enex.as_bytes()).unwrap();
+        assert_eq!(notes.len(), 2);
+        let note = &notes[0];
+        assert_eq!(note.title, "Cats & dogs");
+        assert_eq!(note.tags, vec!["pets", "clips"]);
+        assert_eq!(
+            note.source_url.as_deref(),
+            Some("https://example.com/cats?a=1&b=2")
+        );
+        assert_eq!(note.author.as_deref(), Some("Ann"));
+        assert_eq!(
+            note.created.unwrap().to_rfc3339(),
+            "2024-01-05T09:30:00+00:00"
+        );
+        assert_eq!(note.resources.len(), 1);
+        assert_eq!(note.resources[0].data, b"PNGDATA");
+        assert_eq!(note.resources[0].hash(), hash);
+        assert_eq!(notes[1].source_url, None);
+
+        let resource = &note.resources[0];
+        let name = attachment_name(resource, &hash);
+        assert_eq!(name, format!("{}-cat_photo.png", &hash[..8]));
+        let links = HashMap::from([(
+            hash.clone(),
+            ("/media/evernote/references/1/x.png".to_string(), resource),
+        )]);
+        assert_eq!(
+            enml_to_html(&note.content, &links),
+            "<div><div>\u{2611} Fed <b>cats</b></div><img src=\"/media/evernote/references/1/x.png\" alt=\"../cat photo.png\"></div>"
+        );
+
+        assert!(parse_enex("<html></html>".as_bytes()).is_err());
+    }
+}
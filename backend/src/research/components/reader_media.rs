@@ -0,0 +1,319 @@
+//! Offline media for reader snapshots
+//!
+//! Images referenced by a snapshot's markdown are downloaded into
+//! `<media_dir>/reader/<snapshot_id>/`, tracked in `reader_snapshot_assets`,
+//! and the markdown is rewritten to the bridge's `/media/` route. Each
+//! snapshot owns its directory, so deleting a snapshot removes its files.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use chrono::Utc;
+use regex::Regex;
+use reqwest::Url;
+use schemars::JsonSchema;
+use sea_orm::{ActiveModelTrait, EntityTrait, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
+use crate::core::components::storage::stats::calculate_dir_size;
+use crate::research::entities::{reader_snapshot_assets, reader_snapshots};
+
+/// Bridge route serving files from the media dir
+pub const MEDIA_ROUTE_PREFIX: &str = "/media/";
+
+const READER_MEDIA_SUBDIR: &str = "reader";
+const MAX_IMAGES_PER_SNAPSHOT: usize = 50;
+const MAX_RESOURCE_BYTES: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedImagesSummary {
+    pub archived: usize,
+    pub failed: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderMediaCleanupSummary {
+    pub removed_snapshots: usize,
+    pub freed_bytes: u64,
+}
+
+/// Image URLs in markdown image syntax and inline `<img>` tags, as written
+pub(crate) fn image_urls(content_md: &str) -> Vec<String> {
+    let markdown = Regex::new(r#"!\[[^\]]*\]\(\s*<?([^)\s>]+)>?(?:\s+"[^"]*")?\s*\)"#)
+        .expect("valid markdown image regex");
+    let html = Regex::new(r#"<img[^>]+src\s*=\s*["']([^"']+)["']"#).expect("valid img regex");
+
+    let mut seen = HashSet::new();
+    markdown
+        .captures_iter(content_md)
+        .chain(html.captures_iter(content_md))
+        .filter_map(|c| c.get(1).map(|m| m.as_str().to_string()))
+        .filter(|u| !u.starts_with("data:") && !u.starts_with(MEDIA_ROUTE_PREFIX))
+        .filter(|u| seen.insert(u.clone()))
+        .collect()
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "image/avif" => "avif",
+        _ => "img",
+    }
+}
+
+/// MIME type for a file served from the media dir
+pub fn content_type_for(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+    {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "avif" => "image/avif",
+        "html" => "text/html; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Resolve a path under the media dir, rejecting anything that escapes it
+pub fn resolve_media_path(media_dir: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(media_dir.join(relative))
+}
+
+pub(crate) fn snapshot_media_dir(media_dir: &Path, snapshot_id: i64) -> PathBuf {
+    media_dir
+        .join(READER_MEDIA_SUBDIR)
+        .join(snapshot_id.to_string())
+}
+
+/// Path of a snapshot file relative to the media dir, as stored and served
+pub(crate) fn snapshot_media_path(snapshot_id: i64, file_name: &str) -> String {
+    format!("{}/{}/{}", READER_MEDIA_SUBDIR, snapshot_id, file_name)
+}
+
+/// Download a resource, returning its bytes and content type
+pub(crate) async fn download_resource(
+    http_client: &HttpClient,
+    url: &Url,
+) -> AppResult<(Vec<u8>, String)> {
+    let resp = http_client.send(http_client.get(url.clone())).await?;
+    if !resp.status().is_success() {
+        return Err(AppError::other(format!("HTTP {}", resp.status())));
+    }
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if resp
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_RESOURCE_BYTES)
+    {
+        return Err(AppError::other("Resource too large"));
+    }
+    let bytes = resp.bytes().await?;
+    if bytes.len() > MAX_RESOURCE_BYTES {
+        return Err(AppError::other("Resource too large"));
+    }
+    Ok((bytes.to_vec(), content_type))
+}
+
+async fn download_image(http_client: &HttpClient, url: &Url) -> AppResult<(Vec<u8>, String)> {
+    let (bytes, content_type) = download_resource(http_client, url).await?;
+    if !content_type.starts_with("image/") {
+        return Err(AppError::other(format!("Not an image ({})", content_type)));
+    }
+    Ok((bytes, content_type))
+}
+
+/// Download a snapshot's images and return the rewritten markdown
+///
+/// Relative URLs are resolved against `base_url`. Images that fail to
+/// download keep their remote URL.
+pub async fn archive_snapshot_images(
+    db: &sea_orm::DatabaseConnection,
+    http_client: &HttpClient,
+    media_dir: &Path,
+    snapshot_id: i64,
+    base_url: &str,
+    content_md: &str,
+) -> AppResult<(String, ArchivedImagesSummary)> {
+    let mut summary = ArchivedImagesSummary::default();
+    let urls = image_urls(content_md);
+    if urls.is_empty() {
+        return Ok((content_md.to_string(), summary));
+    }
+
+    let base = Url::parse(base_url).ok();
+    let dir = snapshot_media_dir(media_dir, snapshot_id);
+    fs::create_dir_all(&dir)?;
+
+    let mut rewritten = content_md.to_string();
+    for raw in urls.into_iter().take(MAX_IMAGES_PER_SNAPSHOT) {
+        let resolved = match &base {
+            Some(base) => base.join(&raw),
+            None => Url::parse(&raw),
+        };
+        let Ok(url) = resolved else {
+            summary.failed += 1;
+            continue;
+        };
+        if url.scheme() != "http" && url.scheme() != "https" {
+            summary.failed += 1;
+            continue;
+        }
+
+        let (bytes, content_type) = match download_image(http_client, &url).await {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                warn!(snapshot_id, url = %url, "Image download failed: {}", e);
+                summary.failed += 1;
+                continue;
+            }
+        };
+
+        let digest = hex::encode(Sha256::digest(url.as_str().as_bytes()));
+        let file_name = format!("{}.{}", &digest[..16], extension_for(&content_type));
+        fs::write(dir.join(&file_name), &bytes)?;
+
+        let local_path = snapshot_media_path(snapshot_id, &file_name);
+        reader_snapshot_assets::ActiveModel {
+            snapshot_id: Set(snapshot_id),
+            original_url: Set(url.to_string()),
+            local_path: Set(local_path.clone()),
+            content_type: Set(Some(content_type)),
+            byte_size: Set(bytes.len() as i64),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        rewritten = rewritten.replace(&raw, &format!("{}{}", MEDIA_ROUTE_PREFIX, local_path));
+        summary.archived += 1;
+        summary.bytes += bytes.len() as u64;
+    }
+
+    info!(
+        snapshot_id,
+        archived = summary.archived,
+        failed = summary.failed,
+        "Archived snapshot images"
+    );
+    Ok((rewritten, summary))
+}
+
+/// Remove a snapshot's media directory
+pub fn remove_snapshot_media(media_dir: &Path, snapshot_id: i64) -> AppResult<u64> {
+    let dir = snapshot_media_dir(media_dir, snapshot_id);
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let freed = calculate_dir_size(&dir)?;
+    fs::remove_dir_all(&dir)?;
+    Ok(freed)
+}
+
+/// Remove media directories whose snapshot no longer exists
+///
+/// Snapshots removed without going through `snapshot_delete` (for example
+/// by a reference cascade) lose their asset rows but not their files.
+pub async fn cleanup_orphaned_media(
+    db: &sea_orm::DatabaseConnection,
+    media_dir: &Path,
+) -> AppResult<ReaderMediaCleanupSummary> {
+    let mut summary = ReaderMediaCleanupSummary::default();
+    let root = media_dir.join(READER_MEDIA_SUBDIR);
+    if !root.exists() {
+        return Ok(summary);
+    }
+
+    let existing: HashSet<i64> = reader_snapshots::Entity::find()
+        .select_only()
+        .column(reader_snapshots::Column::Id)
+        .into_tuple::<i64>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    for entry in fs::read_dir(&root)?.flatten() {
+        let Some(snapshot_id) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<i64>().ok())
+        else {
+            continue;
+        };
+        if existing.contains(&snapshot_id) {
+            continue;
+        }
+        summary.freed_bytes += remove_snapshot_media(media_dir, snapshot_id)?;
+        summary.removed_snapshots += 1;
+    }
+
+    if summary.removed_snapshots > 0 {
+        info!(
+            removed = summary.removed_snapshots,
+            freed_bytes = summary.freed_bytes,
+            "Removed orphaned reader media"
+        );
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_urls() {
+        let md = r#"Intro ![chart](https://example.com/a.png "Chart")
+![dup](https://example.com/a.png) ![rel](/img/b.jpg)
+<img class="x" src='https://cdn.example.com/c.webp'>
+![inline](data:image/png;base64,AAAA) ![done](/media/reader/1/abc.png)"#;
+        assert_eq!(
+            image_urls(md),
+            vec![
+                "https://example.com/a.png",
+                "/img/b.jpg",
+                "https://cdn.example.com/c.webp",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_media_path_rejects_traversal() {
+        let root = Path::new("/storage/media");
+        assert_eq!(
+            resolve_media_path(root, "reader/1/a.png"),
+            Some(PathBuf::from("/storage/media/reader/1/a.png"))
+        );
+        assert_eq!(resolve_media_path(root, "../secrets"), None);
+        assert_eq!(resolve_media_path(root, "/etc/passwd"), None);
+    }
+}
@@ -0,0 +1,377 @@
+//! Per-domain extraction rules for the reader
+//!
+//! Some sites extract badly with the default article/main/body search. A
+//! site rule names the elements to keep or strip and can swap the user agent.
+//! A rule for "example.com" covers its subdomains too; the most specific
+//! enabled rule wins.
+
+use chrono::Utc;
+use reqwest::Url;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
+use crate::core::components::reader::{
+    check_selectors, extract_from_html, fetch_html, html_to_text, normalize_reader_url,
+    ExtractionRule,
+};
+use crate::research::components::site_credentials::headers_for_url;
+use crate::research::entities::reader_site_rules;
+
+/// Characters of extracted markdown returned by a rule test
+const TEST_PREVIEW_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderSiteRuleDto {
+    pub id: i64,
+    pub domain: String,
+    pub keep_selectors: Option<String>,
+    pub remove_selectors: Option<String>,
+    pub user_agent: Option<String>,
+    pub enabled: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteRuleCreateInput {
+    /// Domain or any URL on the site
+    pub domain: String,
+    pub keep_selectors: Option<String>,
+    pub remove_selectors: Option<String>,
+    pub user_agent: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteRuleUpdateInput {
+    pub id: i64,
+    pub domain: Option<String>,
+    /// Fields left out are unchanged; an empty string clears the field
+    pub keep_selectors: Option<String>,
+    pub remove_selectors: Option<String>,
+    pub user_agent: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteRuleTestInput {
+    pub url: String,
+    /// Test a saved rule; otherwise the selectors below are used, and when
+    /// those are empty too, the rule that would apply to the URL
+    pub rule_id: Option<i64>,
+    pub keep_selectors: Option<String>,
+    pub remove_selectors: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteRuleTestResult {
+    pub url: String,
+    /// Domain of the saved rule that was tested, if any
+    pub rule_domain: Option<String>,
+    pub title: String,
+    pub word_count: usize,
+    /// Word count with default extraction, for comparison
+    pub default_word_count: usize,
+    pub content_md: String,
+    pub truncated: bool,
+}
+
+fn rule_to_dto(model: reader_site_rules::Model) -> ReaderSiteRuleDto {
+    ReaderSiteRuleDto {
+        id: model.id,
+        domain: model.domain,
+        keep_selectors: model.keep_selectors,
+        remove_selectors: model.remove_selectors,
+        user_agent: model.user_agent,
+        enabled: model.enabled != 0,
+        created_at: model.created_at.to_string(),
+        updated_at: model.updated_at.to_string(),
+    }
+}
+
+fn to_extraction_rule(model: &reader_site_rules::Model) -> ExtractionRule {
+    ExtractionRule {
+        keep_selectors: model.keep_selectors.clone(),
+        remove_selectors: model.remove_selectors.clone(),
+        user_agent: model.user_agent.clone(),
+        headers: None,
+    }
+}
+
+/// Lowercase host without a leading "www."
+fn host_key(host: &str) -> String {
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    host.strip_prefix("www.")
+        .map(str::to_string)
+        .unwrap_or(host)
+}
+
+/// Normalize a domain or URL into the form rules are stored under
+pub(crate) fn normalize_rule_domain(raw: &str) -> AppResult<String> {
+    let raw = raw.trim();
+    let host = if raw.contains("://") {
+        Url::parse(raw)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+    } else {
+        Some(raw.split('/').next().unwrap_or_default().to_string())
+    };
+    let domain = host.map(|h| host_key(&h)).unwrap_or_default();
+    if domain.is_empty() || domain.contains(char::is_whitespace) {
+        return Err(AppError::validation(
+            "domain",
+            "A domain such as example.com is required",
+        ));
+    }
+    Ok(domain)
+}
+
+/// The host and each parent domain, most specific first
+pub(crate) fn domain_candidates(host: &str) -> Vec<String> {
+    let host = host_key(host);
+    let labels: Vec<&str> = host.split('.').collect();
+    (0..labels.len().saturating_sub(1).max(1))
+        .map(|i| labels[i..].join("."))
+        .collect()
+}
+
+fn clean_selectors(field: &str, selectors: Option<String>) -> AppResult<Option<String>> {
+    let Some(selectors) = selectors
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+    else {
+        return Ok(None);
+    };
+    check_selectors(&selectors).map_err(|reason| AppError::validation(field, reason))?;
+    Ok(Some(selectors))
+}
+
+fn clean_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// The enabled rule that applies to a URL, if any
+pub async fn rule_for_url(
+    db: &sea_orm::DatabaseConnection,
+    url: &str,
+) -> AppResult<Option<reader_site_rules::Model>> {
+    let Some(host) = Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+    else {
+        return Ok(None);
+    };
+    let candidates = domain_candidates(&host);
+    let rules = reader_site_rules::Entity::find()
+        .filter(reader_site_rules::Column::Enabled.eq(1))
+        .filter(reader_site_rules::Column::Domain.is_in(candidates.clone()))
+        .all(db)
+        .await?;
+    Ok(candidates
+        .iter()
+        .find_map(|domain| rules.iter().find(|r| &r.domain == domain).cloned()))
+}
+
+/// Extraction overrides for a URL: its site rule plus stored site headers
+pub async fn extraction_rule_for_url(
+    db: &sea_orm::DatabaseConnection,
+    url: &str,
+) -> AppResult<Option<ExtractionRule>> {
+    let rule = rule_for_url(db, url).await?;
+    let headers = headers_for_url(db, url).await?;
+    if rule.is_none() && headers.is_none() {
+        return Ok(None);
+    }
+    let mut extraction = rule.as_ref().map(to_extraction_rule).unwrap_or_default();
+    extraction.headers = headers;
+    Ok(Some(extraction))
+}
+
+pub async fn site_rules_list(
+    db: &sea_orm::DatabaseConnection,
+) -> AppResult<Vec<ReaderSiteRuleDto>> {
+    let rules = reader_site_rules::Entity::find()
+        .order_by_asc(reader_site_rules::Column::Domain)
+        .all(db)
+        .await?;
+    Ok(rules.into_iter().map(rule_to_dto).collect())
+}
+
+pub async fn site_rule_create(
+    db: &sea_orm::DatabaseConnection,
+    input: SiteRuleCreateInput,
+) -> AppResult<ReaderSiteRuleDto> {
+    let domain = normalize_rule_domain(&input.domain)?;
+    let existing = reader_site_rules::Entity::find()
+        .filter(reader_site_rules::Column::Domain.eq(&domain))
+        .one(db)
+        .await?;
+    if existing.is_some() {
+        return Err(AppError::validation(
+            "domain",
+            format!("A rule for {} already exists", domain),
+        ));
+    }
+
+    let now = Utc::now().naive_utc();
+    let rule = reader_site_rules::ActiveModel {
+        domain: Set(domain),
+        keep_selectors: Set(clean_selectors("keepSelectors", input.keep_selectors)?),
+        remove_selectors: Set(clean_selectors("removeSelectors", input.remove_selectors)?),
+        user_agent: Set(clean_text(input.user_agent)),
+        enabled: Set(input.enabled.unwrap_or(true) as i32),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok(rule_to_dto(rule))
+}
+
+pub async fn site_rule_update(
+    db: &sea_orm::DatabaseConnection,
+    input: SiteRuleUpdateInput,
+) -> AppResult<ReaderSiteRuleDto> {
+    let rule = reader_site_rules::Entity::find_by_id(input.id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Site rule {} not found", input.id)))?;
+
+    let mut active = rule.into_active_model();
+    if let Some(domain) = input.domain {
+        let domain = normalize_rule_domain(&domain)?;
+        let clash = reader_site_rules::Entity::find()
+            .filter(reader_site_rules::Column::Domain.eq(&domain))
+            .filter(reader_site_rules::Column::Id.ne(input.id))
+            .one(db)
+            .await?;
+        if clash.is_some() {
+            return Err(AppError::validation(
+                "domain",
+                format!("A rule for {} already exists", domain),
+            ));
+        }
+        active.domain = Set(domain);
+    }
+    if input.keep_selectors.is_some() {
+        active.keep_selectors = Set(clean_selectors("keepSelectors", input.keep_selectors)?);
+    }
+    if input.remove_selectors.is_some() {
+        active.remove_selectors = Set(clean_selectors("removeSelectors", input.remove_selectors)?);
+    }
+    if input.user_agent.is_some() {
+        active.user_agent = Set(clean_text(input.user_agent));
+    }
+    if let Some(enabled) = input.enabled {
+        active.enabled = Set(enabled as i32);
+    }
+    active.updated_at = Set(Utc::now().naive_utc());
+    Ok(rule_to_dto(active.update(db).await?))
+}
+
+pub async fn site_rule_delete(db: &sea_orm::DatabaseConnection, id: i64) -> AppResult<()> {
+    reader_site_rules::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+fn word_count(content_html: &str) -> usize {
+    html_to_text(content_html).split_whitespace().count()
+}
+
+/// Fetch a URL once and extract it with a rule and with the defaults
+///
+/// Nothing is saved, so selectors can be tried before a rule is stored.
+pub async fn site_rule_test(
+    db: &sea_orm::DatabaseConnection,
+    http_client: &HttpClient,
+    input: SiteRuleTestInput,
+) -> AppResult<SiteRuleTestResult> {
+    let url = normalize_reader_url(&input.url)?;
+    let saved = match input.rule_id {
+        Some(rule_id) => Some(
+            reader_site_rules::Entity::find_by_id(rule_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| AppError::other(format!("Site rule {} not found", rule_id)))?,
+        ),
+        None => None,
+    };
+    let draft = ExtractionRule {
+        keep_selectors: clean_selectors("keepSelectors", input.keep_selectors)?,
+        remove_selectors: clean_selectors("removeSelectors", input.remove_selectors)?,
+        user_agent: clean_text(input.user_agent),
+        headers: None,
+    };
+    let is_draft = draft.keep_selectors.is_some()
+        || draft.remove_selectors.is_some()
+        || draft.user_agent.is_some();
+    let saved = match saved {
+        Some(rule) => Some(rule),
+        None if !is_draft => rule_for_url(db, &url).await?,
+        None => None,
+    };
+    let mut rule = saved.as_ref().map(to_extraction_rule).unwrap_or(draft);
+    rule.headers = headers_for_url(db, &url).await?;
+
+    let raw_html = fetch_html(http_client, &url, Some(&rule)).await?;
+    let default_extracted = extract_from_html(url.clone(), raw_html.clone(), None, None);
+    let extracted = extract_from_html(url.clone(), raw_html, None, Some(&rule));
+
+    let truncated = extracted.content_md.chars().count() > TEST_PREVIEW_CHARS;
+    let content_md = if truncated {
+        extracted
+            .content_md
+            .chars()
+            .take(TEST_PREVIEW_CHARS)
+            .collect()
+    } else {
+        extracted.content_md
+    };
+    Ok(SiteRuleTestResult {
+        url,
+        rule_domain: saved.map(|r| r.domain),
+        title: extracted.title,
+        word_count: word_count(&extracted.content_html),
+        default_word_count: word_count(&default_extracted.content_html),
+        content_md,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_matching() {
+        assert_eq!(
+            normalize_rule_domain("https://WWW.Example.com/post/1").unwrap(),
+            "example.com"
+        );
+        assert_eq!(
+            normalize_rule_domain("blog.example.com/").unwrap(),
+            "blog.example.com"
+        );
+        assert!(normalize_rule_domain("  ").is_err());
+
+        assert_eq!(
+            domain_candidates("www.news.blog.example.co"),
+            vec!["news.blog.example.co", "blog.example.co", "example.co"]
+        );
+        assert_eq!(domain_candidates("localhost"), vec!["localhost"]);
+    }
+}
@@ -0,0 +1,288 @@
+//! Watched reader references
+//!
+//! A watched reference is re-fetched by the `reader_watch_refresh` system task
+//! once its interval has elapsed. The new snapshot is kept only when its text
+//! differs meaningfully from the previous one; otherwise it is discarded so
+//! polling does not pile up identical snapshots. Changes are announced with a
+//! `reader_reference_changed` event.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use regex::Regex;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::events::EventEmitter;
+use crate::core::components::http::HttpClient;
+use crate::research::components::reader::{
+    reader_refresh, reference_to_dto, snapshot_delete, ReaderReferenceDto, ReaderRefreshInput,
+};
+use crate::research::entities::{reader_references, reader_snapshots};
+use crate::system::components::scheduler::TaskRunResult;
+
+pub const READER_CHANGED_EVENT: &str = "reader_reference_changed";
+
+const DEFAULT_WATCH_INTERVAL_MINUTES: i32 = 24 * 60;
+/// The watch task runs every 15 minutes, so shorter intervals would not apply
+const MIN_WATCH_INTERVAL_MINUTES: i32 = 15;
+/// Share of words that must differ for a refresh to count as a change
+const MEANINGFUL_CHANGE_RATIO: f64 = 0.05;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderWatchInput {
+    pub reference_id: i64,
+    pub watch: bool,
+    /// Minutes between checks (default: daily, minimum 15)
+    pub interval_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReaderReferenceChangedEvent {
+    pub reference_id: i64,
+    pub title: String,
+    pub url: String,
+    pub snapshot_id: i64,
+    pub previous_snapshot_id: Option<i64>,
+    /// Share of words that changed, 0-1
+    pub change_ratio: f64,
+}
+
+/// Lowercased words, ignoring link and image targets so re-hosted media does
+/// not count as a change
+fn words(content_md: &str) -> HashMap<String, usize> {
+    let targets = Regex::new(r"\]\([^)]*\)").expect("valid link target regex");
+    let text = targets.replace_all(content_md, "]");
+    let mut counts = HashMap::new();
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Share of words that differ between two versions, 0 (same) to 1
+pub(crate) fn change_ratio(old_md: &str, new_md: &str) -> f64 {
+    let old = words(old_md);
+    let new = words(new_md);
+    let old_total: usize = old.values().sum();
+    let new_total: usize = new.values().sum();
+    let longest = old_total.max(new_total);
+    if longest == 0 {
+        return 0.0;
+    }
+    let common: usize = old
+        .iter()
+        .map(|(word, count)| (*count).min(new.get(word).copied().unwrap_or(0)))
+        .sum();
+    1.0 - common as f64 / longest as f64
+}
+
+fn is_due(reference: &reader_references::Model, now: NaiveDateTime) -> bool {
+    let interval = reference
+        .watch_interval_minutes
+        .unwrap_or(DEFAULT_WATCH_INTERVAL_MINUTES);
+    match reference.last_checked_at {
+        Some(checked) => checked + Duration::minutes(interval as i64) <= now,
+        None => true,
+    }
+}
+
+/// Turn watching on or off for a reference
+pub async fn set_reference_watch(
+    db: &sea_orm::DatabaseConnection,
+    input: ReaderWatchInput,
+) -> AppResult<ReaderReferenceDto> {
+    if let Some(minutes) = input.interval_minutes {
+        if minutes < MIN_WATCH_INTERVAL_MINUTES {
+            return Err(AppError::validation(
+                "intervalMinutes",
+                format!("Must be at least {} minutes", MIN_WATCH_INTERVAL_MINUTES),
+            ));
+        }
+    }
+    let reference = reader_references::Entity::find_by_id(input.reference_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Reference {} not found", input.reference_id)))?;
+
+    let mut active = reference.into_active_model();
+    active.watch = Set(input.watch as i32);
+    if input.interval_minutes.is_some() {
+        active.watch_interval_minutes = Set(input.interval_minutes);
+    }
+    Ok(reference_to_dto(active.update(db).await?))
+}
+
+/// References being watched, most recently changed first
+pub async fn watched_references(
+    db: &sea_orm::DatabaseConnection,
+) -> AppResult<Vec<ReaderReferenceDto>> {
+    let references = reader_references::Entity::find()
+        .filter(reader_references::Column::Watch.eq(1))
+        .order_by_desc(reader_references::Column::LastChangedAt)
+        .order_by_asc(reader_references::Column::Title)
+        .all(db)
+        .await?;
+    Ok(references.into_iter().map(reference_to_dto).collect())
+}
+
+/// Re-fetch one watched reference; returns the change when there was one
+async fn check_reference(
+    db: &sea_orm::DatabaseConnection,
+    http_client: &HttpClient,
+    media_dir: &Path,
+    reference: reader_references::Model,
+) -> AppResult<Option<ReaderReferenceChangedEvent>> {
+    // Marked first so a failing page waits a full interval before the retry
+    let now = Utc::now().naive_utc();
+    let mut active = reader_references::ActiveModel {
+        id: Set(reference.id),
+        ..Default::default()
+    };
+    active.last_checked_at = Set(Some(now));
+    active.update(db).await?;
+
+    let previous = reader_snapshots::Entity::find()
+        .filter(reader_snapshots::Column::ReferenceId.eq(reference.id))
+        .order_by_desc(reader_snapshots::Column::FetchedAt)
+        .one(db)
+        .await?;
+
+    let result = reader_refresh(
+        db,
+        http_client,
+        media_dir,
+        ReaderRefreshInput {
+            reference_id: reference.id,
+            archive_images: None,
+            // Keep full archives for pages that had one
+            full_archive: previous.as_ref().map(|s| s.archive_path.is_some()),
+        },
+    )
+    .await?;
+
+    let ratio = previous
+        .as_ref()
+        .map(|s| change_ratio(&s.content_md, &result.content_md))
+        .unwrap_or(1.0);
+    let changed = ratio >= MEANINGFUL_CHANGE_RATIO;
+    if changed {
+        let mut active = reader_references::ActiveModel {
+            id: Set(reference.id),
+            ..Default::default()
+        };
+        active.last_changed_at = Set(Some(now));
+        active.update(db).await?;
+    } else {
+        snapshot_delete(db, media_dir, result.snapshot_id).await?;
+    }
+
+    Ok(changed.then(|| ReaderReferenceChangedEvent {
+        reference_id: reference.id,
+        title: result.title,
+        url: result.final_url,
+        snapshot_id: result.snapshot_id,
+        previous_snapshot_id: previous.map(|s| s.id),
+        change_ratio: ratio,
+    }))
+}
+
+/// Scheduler entry point: refresh watched references that are due
+pub async fn run_reader_watch_task(
+    emitter: &(dyn EventEmitter),
+    state: &crate::AppState,
+) -> TaskRunResult {
+    let references = match reader_references::Entity::find()
+        .filter(reader_references::Column::Watch.eq(1))
+        .all(&state.db)
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            return TaskRunResult {
+                status: "error",
+                result_json: None,
+                error_message: Some(e.to_string()),
+            }
+        }
+    };
+    let now = Utc::now().naive_utc();
+    let due: Vec<_> = references.into_iter().filter(|r| is_due(r, now)).collect();
+    if due.is_empty() {
+        return TaskRunResult {
+            status: "skipped",
+            result_json: Some("{\"reason\":\"no watched references due\"}".into()),
+            error_message: None,
+        };
+    }
+
+    let checked = due.len();
+    let mut changed = 0;
+    let mut failed = 0;
+    for reference in due {
+        let reference_id = reference.id;
+        match check_reference(
+            &state.db,
+            &state.http_clients.reader,
+            &state.config.current().storage.media_dir,
+            reference,
+        )
+        .await
+        {
+            Ok(Some(event)) => {
+                changed += 1;
+                if let Err(e) = emitter.emit(READER_CHANGED_EVENT, event).await {
+                    error!(target: "reader", "Failed to emit reader change event: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!(target: "reader", reference_id, "Watched reference refresh failed: {}", e);
+                failed += 1;
+            }
+        }
+    }
+    info!(target: "reader", checked, changed, failed, "Watched references checked");
+
+    TaskRunResult {
+        status: if failed == 0 { "success" } else { "error" },
+        result_json: Some(
+            serde_json::json!({
+                "checked": checked,
+                "changed": changed,
+                "failed": failed,
+            })
+            .to_string(),
+        ),
+        error_message: (failed > 0).then(|| format!("{} watched references failed", failed)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_ratio() {
+        let old = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(change_ratio(old, old), 0.0);
+        assert_eq!(change_ratio("", ""), 0.0);
+        assert!(change_ratio(old, "Completely different text here") > 0.9);
+
+        // Re-hosted images are not a change
+        let remote = "Intro ![chart](https://example.com/a.png) text";
+        let local = "Intro ![chart](/media/reader/4/abc.png) text";
+        assert_eq!(change_ratio(remote, local), 0.0);
+    }
+}
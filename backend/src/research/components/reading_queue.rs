@@ -0,0 +1,389 @@
+//! Read-later queue for news articles and reader references
+//!
+//! Separate from starring: starring marks something worth keeping, the queue
+//! holds what to read next, in an order the user controls. Positions are
+//! 0-based and rewritten on every change so they stay contiguous.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::embeddings::types::{ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE};
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::components::feed::entities::articles::{
+    self as news_articles, Entity as NewsArticles,
+};
+use crate::research::entities::{reader_references, reading_queue};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueueItemDto {
+    pub id: i64,
+    /// "news_article" or "reader_reference"
+    pub item_type: String,
+    pub item_id: i64,
+    pub position: i32,
+    pub title: String,
+    pub url: Option<String>,
+    pub excerpt: Option<String>,
+    pub added_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueueAddInput {
+    pub item_type: String,
+    pub item_id: i64,
+    /// 0-based insert position (default: end of queue). Moves the item if it
+    /// is already queued.
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingQueueReorderInput {
+    /// Queue entry ids in the new order; entries not listed keep their
+    /// relative order after the listed ones
+    pub ids: Vec<i64>,
+}
+
+/// Title/url/excerpt of a queued item
+struct ItemInfo {
+    title: String,
+    url: Option<String>,
+    excerpt: Option<String>,
+}
+
+fn validate_item_type(item_type: &str) -> AppResult<()> {
+    if item_type != ENTITY_NEWS_ARTICLE && item_type != ENTITY_READER_REFERENCE {
+        return Err(AppError::validation(
+            "itemType",
+            format!(
+                "Unsupported item type '{}' (expected {} or {})",
+                item_type, ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE
+            ),
+        ));
+    }
+    Ok(())
+}
+
+async fn load_queue<C: ConnectionTrait>(db: &C) -> AppResult<Vec<reading_queue::Model>> {
+    Ok(reading_queue::Entity::find()
+        .order_by_asc(reading_queue::Column::Position)
+        .order_by_asc(reading_queue::Column::Id)
+        .all(db)
+        .await?)
+}
+
+/// Persist `entries` order as contiguous positions, touching only rows that moved
+async fn write_positions<C: ConnectionTrait>(
+    db: &C,
+    entries: Vec<reading_queue::Model>,
+) -> AppResult<()> {
+    for (index, entry) in entries.into_iter().enumerate() {
+        let position = index as i32;
+        if entry.position != position {
+            let mut active = entry.into_active_model();
+            active.position = Set(position);
+            active.update(db).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Look up display info for queued items; missing items are absent from the map
+async fn item_info<C: ConnectionTrait>(
+    db: &C,
+    entries: &[reading_queue::Model],
+) -> AppResult<HashMap<(String, i64), ItemInfo>> {
+    let ids_of = |item_type: &str| -> Vec<i64> {
+        entries
+            .iter()
+            .filter(|e| e.item_type == item_type)
+            .map(|e| e.item_id)
+            .collect()
+    };
+    let mut info = HashMap::new();
+
+    let article_ids = ids_of(ENTITY_NEWS_ARTICLE);
+    if !article_ids.is_empty() {
+        for a in NewsArticles::find()
+            .filter(news_articles::Column::Id.is_in(article_ids))
+            .all(db)
+            .await?
+        {
+            info.insert(
+                (ENTITY_NEWS_ARTICLE.to_string(), a.id),
+                ItemInfo {
+                    title: a.title,
+                    url: a.url,
+                    excerpt: a.excerpt,
+                },
+            );
+        }
+    }
+
+    let reference_ids = ids_of(ENTITY_READER_REFERENCE);
+    if !reference_ids.is_empty() {
+        for r in reader_references::Entity::find()
+            .filter(reader_references::Column::Id.is_in(reference_ids))
+            .all(db)
+            .await?
+        {
+            info.insert(
+                (ENTITY_READER_REFERENCE.to_string(), r.id),
+                ItemInfo {
+                    title: r.title,
+                    url: Some(r.url),
+                    excerpt: r.excerpt,
+                },
+            );
+        }
+    }
+
+    Ok(info)
+}
+
+fn entry_to_dto(entry: reading_queue::Model, info: ItemInfo) -> ReadingQueueItemDto {
+    ReadingQueueItemDto {
+        id: entry.id,
+        item_type: entry.item_type,
+        item_id: entry.item_id,
+        position: entry.position,
+        title: info.title,
+        url: info.url,
+        excerpt: info.excerpt,
+        added_at: entry.added_at.to_string(),
+    }
+}
+
+/// List the queue in reading order
+///
+/// Entries whose article or reference has since been deleted are dropped.
+pub async fn queue_list(db: &sea_orm::DatabaseConnection) -> AppResult<Vec<ReadingQueueItemDto>> {
+    let entries = load_queue(db).await?;
+    let mut info = item_info(db, &entries).await?;
+
+    let mut kept = Vec::with_capacity(entries.len());
+    let mut stale = Vec::new();
+    for entry in entries {
+        if info.contains_key(&(entry.item_type.clone(), entry.item_id)) {
+            kept.push(entry);
+        } else {
+            stale.push(entry.id);
+        }
+    }
+    if !stale.is_empty() {
+        reading_queue::Entity::delete_many()
+            .filter(reading_queue::Column::Id.is_in(stale))
+            .exec(db)
+            .await?;
+        write_positions(db, kept.clone()).await?;
+        kept = load_queue(db).await?;
+    }
+
+    Ok(kept
+        .into_iter()
+        .filter_map(|entry| {
+            let item = info.remove(&(entry.item_type.clone(), entry.item_id))?;
+            Some(entry_to_dto(entry, item))
+        })
+        .collect())
+}
+
+/// Add an article or reference to the queue (or move it if already queued)
+pub async fn queue_add(
+    db: &sea_orm::DatabaseConnection,
+    input: ReadingQueueAddInput,
+) -> AppResult<Vec<ReadingQueueItemDto>> {
+    let item_type = input.item_type.trim().to_string();
+    validate_item_type(&item_type)?;
+
+    let missing = if item_type == ENTITY_NEWS_ARTICLE {
+        NewsArticles::find_by_id(input.item_id)
+            .one(db)
+            .await?
+            .is_none()
+            .then(|| format!("Article {} not found", input.item_id))
+    } else {
+        reader_references::Entity::find_by_id(input.item_id)
+            .one(db)
+            .await?
+            .is_none()
+            .then(|| format!("Reference {} not found", input.item_id))
+    };
+    if let Some(message) = missing {
+        return Err(AppError::other(message));
+    }
+
+    let txn = db.begin().await?;
+    let mut entries = load_queue(&txn).await?;
+    let existing = entries
+        .iter()
+        .position(|e| e.item_type == item_type && e.item_id == input.item_id);
+
+    let entry = match existing {
+        // Already queued and no new position: keep its place
+        Some(_) if input.position.is_none() => {
+            txn.commit().await?;
+            return queue_list(db).await;
+        }
+        Some(index) => entries.remove(index),
+        None => {
+            reading_queue::ActiveModel {
+                item_type: Set(item_type),
+                item_id: Set(input.item_id),
+                position: Set(entries.len() as i32),
+                added_at: Set(Utc::now().naive_utc()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?
+        }
+    };
+
+    let index = input
+        .position
+        .map(|p| p.clamp(0, entries.len() as i32) as usize)
+        .unwrap_or(entries.len());
+    entries.insert(index, entry);
+    write_positions(&txn, entries).await?;
+    txn.commit().await?;
+
+    queue_list(db).await
+}
+
+/// Remove a queue entry
+pub async fn queue_remove(
+    db: &sea_orm::DatabaseConnection,
+    id: i64,
+) -> AppResult<Vec<ReadingQueueItemDto>> {
+    let txn = db.begin().await?;
+    let result = reading_queue::Entity::delete_by_id(id).exec(&txn).await?;
+    if result.rows_affected == 0 {
+        return Err(AppError::other(format!("Queue entry {} not found", id)));
+    }
+    let entries = load_queue(&txn).await?;
+    write_positions(&txn, entries).await?;
+    txn.commit().await?;
+
+    queue_list(db).await
+}
+
+/// Reorder the queue
+pub async fn queue_reorder(
+    db: &sea_orm::DatabaseConnection,
+    input: ReadingQueueReorderInput,
+) -> AppResult<Vec<ReadingQueueItemDto>> {
+    let txn = db.begin().await?;
+    let mut remaining = load_queue(&txn).await?;
+
+    let mut ordered = Vec::with_capacity(remaining.len());
+    for id in input.ids {
+        let Some(index) = remaining.iter().position(|e| e.id == id) else {
+            return Err(AppError::Validation {
+                field: "ids".to_string(),
+                reason: "Queue entry not found".to_string(),
+                invalid_value: Some(id.to_string()),
+            });
+        };
+        ordered.push(remaining.remove(index));
+    }
+    ordered.extend(remaining);
+
+    write_positions(&txn, ordered).await?;
+    txn.commit().await?;
+
+    queue_list(db).await
+}
+
+/// Remove and return the item at the front of the queue
+pub async fn queue_pop(db: &sea_orm::DatabaseConnection) -> AppResult<Option<ReadingQueueItemDto>> {
+    // Listing first drops stale entries, so the head is always readable
+    let Some(head) = queue_list(db).await?.into_iter().next() else {
+        return Ok(None);
+    };
+
+    let txn = db.begin().await?;
+    reading_queue::Entity::delete_by_id(head.id)
+        .exec(&txn)
+        .await?;
+    let entries = load_queue(&txn).await?;
+    write_positions(&txn, entries).await?;
+    txn.commit().await?;
+
+    Ok(Some(head))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use sea_orm::DatabaseConnection;
+
+    /// A database with references 1, 2 and 3 queued in that order
+    async fn queued_db() -> DatabaseConnection {
+        let db = test_db().await;
+        db.execute_unprepared(
+            "INSERT INTO reader_references (id, url, title) VALUES \
+             (1, 'https://a.example', 'A'), (2, 'https://b.example', 'B'), \
+             (3, 'https://c.example', 'C')",
+        )
+        .await
+        .unwrap();
+        for item_id in 1..=3 {
+            let input = ReadingQueueAddInput {
+                item_type: ENTITY_READER_REFERENCE.to_string(),
+                item_id,
+                position: None,
+            };
+            queue_add(&db, input).await.unwrap();
+        }
+        db
+    }
+
+    /// (item id, position) of each entry, in queue order
+    fn order(items: &[ReadingQueueItemDto]) -> Vec<(i64, i32)> {
+        items.iter().map(|i| (i.item_id, i.position)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_queue_reorder() {
+        let db = queued_db().await;
+        let entries = queue_list(&db).await.unwrap();
+        assert_eq!(order(&entries), [(1, 0), (2, 1), (3, 2)]);
+
+        // Unlisted entries follow the listed ones
+        let input = ReadingQueueReorderInput {
+            ids: vec![entries[2].id, entries[0].id],
+        };
+        let reordered = queue_reorder(&db, input).await.unwrap();
+        assert_eq!(order(&reordered), [(3, 0), (1, 1), (2, 2)]);
+
+        let input = ReadingQueueReorderInput { ids: vec![999] };
+        assert!(matches!(
+            queue_reorder(&db, input).await,
+            Err(AppError::Validation { .. })
+        ));
+        assert_eq!(order(&queue_list(&db).await.unwrap()), [(3, 0), (1, 1), (2, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_pop() {
+        let db = queued_db().await;
+        let head = queue_pop(&db).await.unwrap().unwrap();
+        assert_eq!(head.item_id, 1);
+        assert_eq!(order(&queue_list(&db).await.unwrap()), [(2, 0), (3, 1)]);
+
+        queue_pop(&db).await.unwrap();
+        queue_pop(&db).await.unwrap();
+        assert!(queue_pop(&db).await.unwrap().is_none());
+        assert!(queue_list(&db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+//! Reading statistics
+//!
+//! Aggregated in SQL over two read logs: `read` signals on news articles
+//! (see feed::relevance) and `last_read_at` on reader references. Reading
+//! minutes come from the snapshot's `reading_time_minutes`, scaled by how far
+//! the reference was read; news articles carry no reading time. Days are UTC
+//! dates on both SQLite and PostgreSQL.
+
+use chrono::{Duration, Utc};
+use schemars::JsonSchema;
+use sea_orm::{ConnectionTrait, DatabaseBackend, QueryResult, Statement, Value};
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::errors::{AppError, AppResult};
+
+const DEFAULT_DAYS: u32 = 30;
+const MAX_DAYS: u32 = 3650;
+const TOP_SOURCES: i64 = 10;
+
+/// One row per read item: (kind, item, day, source, minutes)
+///
+/// Sources are the lowercased source captured with the signal, so they
+/// survive article pruning. Days are `YYYY-MM-DD` text on either backend.
+fn reads_cte(backend: DatabaseBackend) -> String {
+    let (signal_day, read_day, source) = match backend {
+        DatabaseBackend::Postgres => (
+            "to_char(s.created_at AT TIME ZONE 'UTC', 'YYYY-MM-DD')",
+            "to_char(r.last_read_at, 'YYYY-MM-DD')",
+            "s.features::json ->> 'source'",
+        ),
+        _ => (
+            "date(s.created_at)",
+            "date(r.last_read_at)",
+            "json_extract(s.features, '$.source')",
+        ),
+    };
+    format!(
+        r#"WITH reads AS (
+    SELECT 'news_article' AS kind,
+           'a' || s.article_id AS item,
+           {signal_day} AS day,
+           {source} AS source,
+           0.0 AS minutes
+    FROM article_signals s
+    WHERE s.signal = 'read'
+    UNION ALL
+    SELECT 'reader_reference' AS kind,
+           'r' || r.id AS item,
+           {read_day} AS day,
+           NULL AS source,
+           COALESCE(
+               (SELECT sn.reading_time_minutes FROM reader_snapshots sn
+                WHERE sn.id = r.progress_snapshot_id),
+               (SELECT sn.reading_time_minutes FROM reader_snapshots sn
+                WHERE sn.reference_id = r.id
+                ORDER BY sn.fetched_at DESC LIMIT 1),
+               0
+           ) * COALESCE(r.progress_percent, 0) / 100.0 AS minutes
+    FROM reader_references r
+    WHERE r.last_read_at IS NOT NULL
+)"#
+    )
+}
+
+/// `YYYY-Www` week of a day, weeks starting on Monday as in `strftime('%W')`
+fn week_expr(backend: DatabaseBackend) -> &'static str {
+    match backend {
+        DatabaseBackend::Postgres => {
+            "to_char(day::date, 'YYYY') || '-W' || lpad(((EXTRACT(DOY FROM day::date)::int \
+             + 7 - EXTRACT(ISODOW FROM day::date)::int) / 7)::text, 2, '0')"
+        }
+        _ => "strftime('%Y-W%W', day)",
+    }
+}
+
+/// A day as a number that grows by one per day
+fn day_number_expr(backend: DatabaseBackend) -> &'static str {
+    match backend {
+        DatabaseBackend::Postgres => "(day::date - DATE '1970-01-01')",
+        _ => "julianday(day)",
+    }
+}
+
+/// PostgreSQL numbers its placeholders
+fn placeholders(backend: DatabaseBackend, sql: &str) -> String {
+    if backend != DatabaseBackend::Postgres {
+        return sql.to_string();
+    }
+    let mut numbered = String::with_capacity(sql.len());
+    let mut n = 0;
+    for ch in sql.chars() {
+        if ch == '?' {
+            n += 1;
+            numbered.push_str(&format!("${}", n));
+        } else {
+            numbered.push(ch);
+        }
+    }
+    numbered
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingStatsInput {
+    /// Window for totals, per-day/week counts and top sources (default: 30)
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingPeriodCount {
+    /// `YYYY-MM-DD` for days, `YYYY-Www` for weeks
+    pub period: String,
+    pub items_read: i64,
+    pub minutes: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingSourceCount {
+    pub source: String,
+    pub items_read: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingStatsDto {
+    pub days: u32,
+    pub articles_read: i64,
+    pub references_read: i64,
+    pub total_reading_minutes: f64,
+    pub per_day: Vec<ReadingPeriodCount>,
+    pub per_week: Vec<ReadingPeriodCount>,
+    pub top_sources: Vec<ReadingSourceCount>,
+    /// Consecutive days with reading, ending today or yesterday
+    pub current_streak_days: i64,
+    /// All-time longest run of consecutive reading days
+    pub longest_streak_days: i64,
+}
+
+/// `YYYY-MM-DD` of the day `days` before `today`
+fn days_before(today: chrono::NaiveDate, days: i64) -> String {
+    (today - Duration::days(days))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+/// Run `sql` after the reads CTE; it may start with `,` to add more CTEs
+async fn query<C: ConnectionTrait>(
+    db: &C,
+    sql: &str,
+    values: Vec<Value>,
+) -> AppResult<Vec<QueryResult>> {
+    let backend = db.get_database_backend();
+    Ok(db
+        .query_all(Statement::from_sql_and_values(
+            backend,
+            placeholders(backend, &format!("{}\n{}", reads_cte(backend), sql)),
+            values,
+        ))
+        .await?)
+}
+
+fn period_counts(rows: &[QueryResult]) -> AppResult<Vec<ReadingPeriodCount>> {
+    rows.iter()
+        .map(|row| {
+            Ok(ReadingPeriodCount {
+                period: row.try_get("", "period")?,
+                items_read: row.try_get("", "items_read")?,
+                minutes: row.try_get::<Option<f64>>("", "minutes")?.unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+/// Aggregate reading activity for the last `days` days plus all-time streaks
+pub async fn reading_stats<C: ConnectionTrait>(
+    db: &C,
+    input: ReadingStatsInput,
+) -> AppResult<ReadingStatsDto> {
+    let days = input.days.unwrap_or(DEFAULT_DAYS);
+    if days == 0 || days > MAX_DAYS {
+        return Err(AppError::validation(
+            "days",
+            format!("Must be between 1 and {}", MAX_DAYS),
+        ));
+    }
+    let backend = db.get_database_backend();
+    let today = Utc::now().date_naive();
+    // Today counts as the first day of the window
+    let since = || Value::from(days_before(today, days as i64 - 1));
+
+    let totals = query(
+        db,
+        "SELECT kind, COUNT(DISTINCT item) AS items_read, SUM(minutes) AS minutes
+         FROM reads WHERE day >= ? GROUP BY kind",
+        vec![since()],
+    )
+    .await?;
+    let mut articles_read = 0;
+    let mut references_read = 0;
+    let mut total_reading_minutes = 0.0;
+    for row in &totals {
+        let kind: String = row.try_get("", "kind")?;
+        let items: i64 = row.try_get("", "items_read")?;
+        match kind.as_str() {
+            "news_article" => articles_read = items,
+            _ => references_read = items,
+        }
+        total_reading_minutes += row.try_get::<Option<f64>>("", "minutes")?.unwrap_or(0.0);
+    }
+
+    let per_day = period_counts(
+        &query(
+            db,
+            "SELECT day AS period, COUNT(DISTINCT item) AS items_read, SUM(minutes) AS minutes
+             FROM reads WHERE day >= ? GROUP BY day ORDER BY day",
+            vec![since()],
+        )
+        .await?,
+    )?;
+
+    let per_week = period_counts(
+        &query(
+            db,
+            &format!(
+                "SELECT {} AS period, COUNT(DISTINCT item) AS items_read,
+                        SUM(minutes) AS minutes
+                 FROM reads WHERE day >= ? GROUP BY period ORDER BY period",
+                week_expr(backend)
+            ),
+            vec![since()],
+        )
+        .await?,
+    )?;
+
+    let top_sources = query(
+        db,
+        "SELECT source, COUNT(DISTINCT item) AS items_read
+         FROM reads WHERE day >= ? AND source IS NOT NULL AND source != ''
+         GROUP BY source ORDER BY items_read DESC, source LIMIT ?",
+        vec![since(), TOP_SOURCES.into()],
+    )
+    .await?
+    .iter()
+    .map(|row| {
+        Ok(ReadingSourceCount {
+            source: row.try_get("", "source")?,
+            items_read: row.try_get("", "items_read")?,
+        })
+    })
+    .collect::<AppResult<Vec<_>>>()?;
+
+    // Gaps-and-islands: consecutive days share day number - row_number
+    let streaks = query(
+        db,
+        &format!(
+            ", days AS (SELECT DISTINCT day FROM reads WHERE day IS NOT NULL),
+             islands AS (
+                 SELECT day, {} - ROW_NUMBER() OVER (ORDER BY day) AS grp FROM days
+             ),
+             runs AS (SELECT MAX(day) AS end_day, COUNT(*) AS length FROM islands GROUP BY grp)
+             SELECT COALESCE(MAX(length), 0) AS longest,
+                    COALESCE(MAX(CASE WHEN end_day >= ? THEN length END), 0) AS current
+             FROM runs",
+            day_number_expr(backend)
+        ),
+        vec![days_before(today, 1).into()],
+    )
+    .await?;
+    let (longest_streak_days, current_streak_days) = match streaks.first() {
+        Some(row) => (row.try_get("", "longest")?, row.try_get("", "current")?),
+        None => (0, 0),
+    };
+
+    Ok(ReadingStatsDto {
+        days,
+        articles_read,
+        references_read,
+        total_reading_minutes,
+        per_day,
+        per_week,
+        top_sources,
+        current_streak_days,
+        longest_streak_days,
+    })
+}
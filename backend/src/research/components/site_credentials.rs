@@ -0,0 +1,327 @@
+//! Per-domain request headers and cookies
+//!
+//! Lets the reader and connectors fetch pages behind a login or a soft
+//! paywall the user has access to. Header values and cookies are encrypted
+//! with the master key; only header names are stored in the clear. Stored
+//! credentials are sent over HTTPS only, to the domain and its subdomains,
+//! and don't follow a redirect anywhere else.
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::Url;
+use schemars::JsonSchema;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::core::components::config::HttpClientConfig;
+use crate::core::components::crypto;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::{client_builder, SiteHeaders};
+use crate::research::components::reader_site_rules::{domain_candidates, normalize_rule_domain};
+use crate::research::entities::site_credentials;
+
+/// Headers the HTTP client manages itself
+const RESERVED_HEADERS: [&str; 4] = ["host", "content-length", "transfer-encoding", "connection"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteCredentialDto {
+    pub id: i64,
+    pub domain: String,
+    /// Names only; values are never returned
+    pub header_names: Vec<String>,
+    pub has_cookies: bool,
+    pub enabled: bool,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteCredentialSaveInput {
+    /// Domain or any URL on the site
+    pub domain: String,
+    /// Replaces the stored headers and cookies when either is given
+    pub headers: Option<BTreeMap<String, String>>,
+    /// Cookie header value, e.g. "session=abc; theme=dark"
+    pub cookies: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SiteSecret {
+    headers: BTreeMap<String, String>,
+    cookies: Option<String>,
+}
+
+fn credential_to_dto(model: site_credentials::Model) -> SiteCredentialDto {
+    SiteCredentialDto {
+        id: model.id,
+        domain: model.domain,
+        header_names: model
+            .header_names_json
+            .as_deref()
+            .and_then(|j| serde_json::from_str(j).ok())
+            .unwrap_or_default(),
+        has_cookies: model.has_cookies != 0,
+        enabled: model.enabled != 0,
+        last_used_at: model.last_used_at.map(|t| t.to_string()),
+        created_at: model.created_at.to_string(),
+        updated_at: model.updated_at.to_string(),
+    }
+}
+
+/// Trim and check header names and values, lowercasing the names
+fn clean_secret(
+    headers: Option<BTreeMap<String, String>>,
+    cookies: Option<String>,
+) -> AppResult<SiteSecret> {
+    let mut cleaned = BTreeMap::new();
+    for (name, value) in headers.unwrap_or_default() {
+        let name = name.trim().to_lowercase();
+        let value = value.trim().to_string();
+        if name.is_empty() {
+            continue;
+        }
+        if name == "cookie" {
+            return Err(AppError::validation(
+                "headers",
+                "Put cookies in the cookies field",
+            ));
+        }
+        if RESERVED_HEADERS.contains(&name.as_str())
+            || HeaderName::from_bytes(name.as_bytes()).is_err()
+        {
+            return Err(AppError::validation(
+                "headers",
+                format!("Header {} is not allowed", name),
+            ));
+        }
+        if HeaderValue::from_str(&value).is_err() {
+            return Err(AppError::validation(
+                "headers",
+                format!("Header {} has an invalid value", name),
+            ));
+        }
+        cleaned.insert(name, value);
+    }
+
+    let cookies = cookies
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
+    if let Some(cookies) = &cookies {
+        if HeaderValue::from_str(cookies).is_err() {
+            return Err(AppError::validation(
+                "cookies",
+                "Cookies contain invalid characters",
+            ));
+        }
+    }
+    Ok(SiteSecret {
+        headers: cleaned,
+        cookies,
+    })
+}
+
+fn encrypt_secret(secret: &SiteSecret) -> AppResult<Vec<u8>> {
+    let plaintext = serde_json::to_string(secret).map_err(|e| AppError::other(e.to_string()))?;
+    crypto::encrypt_api_key(&plaintext).map_err(|reason| AppError::Crypto {
+        operation: "encrypt site credentials".to_string(),
+        reason,
+    })
+}
+
+fn decrypt_secret(data: &[u8]) -> AppResult<SiteSecret> {
+    let plaintext = crypto::decrypt_api_key(data).map_err(|reason| AppError::Crypto {
+        operation: "decrypt site credentials".to_string(),
+        reason,
+    })?;
+    serde_json::from_str(&plaintext).map_err(|e| AppError::other(e.to_string()))
+}
+
+pub async fn site_credentials_list(
+    db: &sea_orm::DatabaseConnection,
+) -> AppResult<Vec<SiteCredentialDto>> {
+    let credentials = site_credentials::Entity::find()
+        .order_by_asc(site_credentials::Column::Domain)
+        .all(db)
+        .await?;
+    Ok(credentials.into_iter().map(credential_to_dto).collect())
+}
+
+/// Create or replace the credentials for a domain
+pub async fn site_credential_save(
+    db: &sea_orm::DatabaseConnection,
+    input: SiteCredentialSaveInput,
+) -> AppResult<SiteCredentialDto> {
+    let domain = normalize_rule_domain(&input.domain)?;
+    let existing = site_credentials::Entity::find()
+        .filter(site_credentials::Column::Domain.eq(&domain))
+        .one(db)
+        .await?;
+    let secret = if input.headers.is_some() || input.cookies.is_some() {
+        let secret = clean_secret(input.headers, input.cookies)?;
+        if secret.headers.is_empty() && secret.cookies.is_none() {
+            return Err(AppError::validation(
+                "headers",
+                "Headers or cookies are required",
+            ));
+        }
+        Some(secret)
+    } else {
+        None
+    };
+    let now = Utc::now().naive_utc();
+
+    let mut active = match existing {
+        Some(model) => model.into_active_model(),
+        None if secret.is_none() => {
+            return Err(AppError::validation(
+                "headers",
+                "Headers or cookies are required",
+            ));
+        }
+        None => site_credentials::ActiveModel {
+            domain: Set(domain),
+            enabled: Set(1),
+            created_at: Set(now),
+            ..Default::default()
+        },
+    };
+    // Held until the save, so a key rotation can't slip in between
+    let _secrets = crypto::secret_write().await;
+    if let Some(secret) = secret {
+        let header_names: Vec<&String> = secret.headers.keys().collect();
+        active.header_names_json = Set(Some(
+            serde_json::to_string(&header_names).map_err(|e| AppError::other(e.to_string()))?,
+        ));
+        active.has_cookies = Set(secret.cookies.is_some() as i32);
+        active.secret_encrypted = Set(encrypt_secret(&secret)?);
+    }
+    if let Some(enabled) = input.enabled {
+        active.enabled = Set(enabled as i32);
+    }
+    active.updated_at = Set(now);
+
+    let saved = if active.id.is_set() {
+        active.update(db).await?
+    } else {
+        active.insert(db).await?
+    };
+    Ok(credential_to_dto(saved))
+}
+
+pub async fn site_credential_delete(db: &sea_orm::DatabaseConnection, id: i64) -> AppResult<()> {
+    site_credentials::Entity::delete_by_id(id).exec(db).await?;
+    Ok(())
+}
+
+/// Stored headers (and cookie header) to send with a request to `url`
+///
+/// `None` for plain HTTP and for domains without enabled credentials.
+pub async fn headers_for_url(
+    db: &sea_orm::DatabaseConnection,
+    url: &str,
+) -> AppResult<Option<SiteHeaders>> {
+    let Some(host) = Url::parse(url)
+        .ok()
+        .filter(|u| u.scheme() == "https")
+        .and_then(|u| u.host_str().map(str::to_string))
+    else {
+        return Ok(None);
+    };
+    let candidates = domain_candidates(&host);
+    let credentials = site_credentials::Entity::find()
+        .filter(site_credentials::Column::Enabled.eq(1))
+        .filter(site_credentials::Column::Domain.is_in(candidates.clone()))
+        .all(db)
+        .await?;
+    let Some(credential) = candidates
+        .iter()
+        .find_map(|domain| credentials.iter().find(|c| &c.domain == domain))
+    else {
+        return Ok(None);
+    };
+
+    let headers = credential_headers(credential)?;
+    site_credentials::Entity::update_many()
+        .col_expr(
+            site_credentials::Column::LastUsedAt,
+            Expr::value(Utc::now().naive_utc()),
+        )
+        .filter(site_credentials::Column::Id.eq(credential.id))
+        .exec(db)
+        .await?;
+    Ok(Some(SiteHeaders {
+        domain: credential.domain.clone(),
+        headers,
+    }))
+}
+
+/// The decrypted headers of `credential`, cookies as a `cookie` header
+pub(crate) fn credential_headers(
+    credential: &site_credentials::Model,
+) -> AppResult<Vec<(String, String)>> {
+    let secret = decrypt_secret(&credential.secret_encrypted)?;
+    let mut headers: Vec<(String, String)> = secret.headers.into_iter().collect();
+    if let Some(cookies) = secret.cookies {
+        headers.push(("cookie".to_string(), cookies));
+    }
+    Ok(headers)
+}
+
+/// A client that sends the given headers with every request
+///
+/// For connectors, which build their own requests; timeouts and proxy are
+/// those of `config`. Redirects leaving HTTPS or the headers' domain are not
+/// followed.
+pub fn client_with_headers(
+    config: &HttpClientConfig,
+    site: &SiteHeaders,
+) -> AppResult<reqwest::Client> {
+    let mut map = HeaderMap::new();
+    for (name, value) in &site.headers {
+        let name =
+            HeaderName::from_bytes(name.as_bytes()).map_err(|e| AppError::other(e.to_string()))?;
+        let mut value = HeaderValue::from_str(value).map_err(|e| AppError::other(e.to_string()))?;
+        value.set_sensitive(true);
+        map.insert(name, value);
+    }
+    client_builder(config)?
+        .default_headers(map)
+        .redirect(site.redirect_policy())
+        .build()
+        .map_err(|e| AppError::other(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_secret() {
+        let headers = BTreeMap::from([
+            (" Authorization ".to_string(), " Bearer abc ".to_string()),
+            ("".to_string(), "ignored".to_string()),
+        ]);
+        let secret = clean_secret(Some(headers), Some("  session=1 ".into())).unwrap();
+        assert_eq!(
+            secret.headers.get("authorization").map(String::as_str),
+            Some("Bearer abc")
+        );
+        assert_eq!(secret.headers.len(), 1);
+        assert_eq!(secret.cookies.as_deref(), Some("session=1"));
+
+        let host = BTreeMap::from([("Host".to_string(), "example.com".to_string())]);
+        assert!(clean_secret(Some(host), None).is_err());
+        let cookie = BTreeMap::from([("Cookie".to_string(), "a=1".to_string())]);
+        assert!(clean_secret(Some(cookie), None).is_err());
+        assert!(clean_secret(None, Some("bad\nvalue".into())).is_err());
+    }
+}
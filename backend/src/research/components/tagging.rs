@@ -0,0 +1,401 @@
+//! AI tag suggestions for news articles and reader references
+//!
+//! Suggestions are returned as candidates for the UI to accept; the batch
+//! mode can also write them straight back for backfilling untagged items.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, QuerySelect, Set,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
+
+use crate::core::components::ai::{llm_client_from_settings, suggest_tags_text, LlmClient};
+use crate::core::components::embeddings::types::{ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE};
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::components::feed::entities::articles::{
+    self as news_articles, Entity as NewsArticles,
+};
+use crate::research::components::feed::types::{parse_vec, to_json_vec};
+use crate::research::entities::{reader_references, reader_snapshots};
+use crate::AppState;
+
+const DEFAULT_MAX_TAGS: usize = 5;
+const DEFAULT_BATCH_LIMIT: u64 = 25;
+
+/// Most frequent tags shown to the model; keeps the prompt bounded
+const VOCABULARY_SIZE: usize = 150;
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestTagsInput {
+    /// "news_article" or "reader_reference"
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// Default: 5
+    pub max_tags: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestTagsBatchInput {
+    /// Restrict to one entity type (default: both)
+    pub entity_type: Option<String>,
+    /// Max untagged items processed per entity type (default: 25)
+    pub limit: Option<u64>,
+    pub max_tags: Option<usize>,
+    /// Write suggestions back as the item's tags instead of only returning them
+    pub apply: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSuggestion {
+    pub tag: String,
+    /// Already used elsewhere in the library
+    pub existing: bool,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSuggestions {
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub title: String,
+    pub current_tags: Vec<String>,
+    /// Candidates not already on the item
+    pub suggestions: Vec<TagSuggestion>,
+    pub model: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestTagsBatchResult {
+    pub processed: usize,
+    pub applied: usize,
+    pub failed: usize,
+    pub items: Vec<TagSuggestions>,
+}
+
+/// Item text and tags, independent of the underlying table
+struct Taggable {
+    entity_type: &'static str,
+    entity_id: i64,
+    title: String,
+    text: String,
+    tags: Vec<String>,
+}
+
+/// Suggest tags for a single article or reader reference
+#[instrument(
+    skip(state, input),
+    fields(entity_type = %input.entity_type, entity_id = input.entity_id)
+)]
+pub async fn suggest_tags_handler(
+    input: SuggestTagsInput,
+    state: &AppState,
+) -> AppResult<TagSuggestions> {
+    let entity_type = resolve_entity_type(&input.entity_type)?;
+    let item = load_taggable(&state.db, entity_type, input.entity_id).await?;
+    let vocabulary = tag_vocabulary(&state.db).await?;
+    let client = llm_client_from_settings(
+        &state.db,
+        &state.http_clients.api,
+        &state.config.current().ai,
+    )
+    .await?;
+
+    suggest_for(
+        client.as_ref(),
+        item,
+        &vocabulary,
+        input.max_tags.unwrap_or(DEFAULT_MAX_TAGS),
+    )
+    .await
+}
+
+/// Suggest tags for untagged items, optionally applying them
+///
+/// Items that fail are counted and skipped so one bad response doesn't stop
+/// a backfill.
+#[instrument(skip(state, input))]
+pub async fn suggest_tags_batch_handler(
+    input: SuggestTagsBatchInput,
+    state: &AppState,
+) -> AppResult<SuggestTagsBatchResult> {
+    let entity_types = match input.entity_type.as_deref() {
+        Some(t) => vec![resolve_entity_type(t)?],
+        None => vec![ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE],
+    };
+    let limit = input.limit.unwrap_or(DEFAULT_BATCH_LIMIT);
+    let max_tags = input.max_tags.unwrap_or(DEFAULT_MAX_TAGS);
+    let apply = input.apply.unwrap_or(false);
+
+    let mut vocabulary = tag_vocabulary(&state.db).await?;
+    let client = llm_client_from_settings(
+        &state.db,
+        &state.http_clients.api,
+        &state.config.current().ai,
+    )
+    .await?;
+
+    let mut result = SuggestTagsBatchResult::default();
+    for entity_type in entity_types {
+        for item in load_untagged(&state.db, entity_type, limit).await? {
+            let entity_id = item.entity_id;
+            let outcome = suggest_for(client.as_ref(), item, &vocabulary, max_tags).await;
+            let suggestions = match outcome {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!(target: "ai", entity_type, entity_id, "Tag suggestion failed: {}", e);
+                    result.failed += 1;
+                    continue;
+                }
+            };
+            result.processed += 1;
+
+            if apply && !suggestions.suggestions.is_empty() {
+                let tags: Vec<String> =
+                    suggestions.suggestions.iter().map(|s| s.tag.clone()).collect();
+                save_tags(&state.db, entity_type, entity_id, &tags).await?;
+                // Later items can reuse tags introduced earlier in the run
+                for tag in tags {
+                    if !vocabulary.contains(&tag) {
+                        vocabulary.push(tag);
+                    }
+                }
+                result.applied += 1;
+            }
+            result.items.push(suggestions);
+        }
+    }
+
+    info!(
+        target: "ai",
+        processed = result.processed,
+        applied = result.applied,
+        failed = result.failed,
+        "Tag backfill finished"
+    );
+    Ok(result)
+}
+
+async fn suggest_for(
+    client: &dyn LlmClient,
+    item: Taggable,
+    vocabulary: &[String],
+    max_tags: usize,
+) -> AppResult<TagSuggestions> {
+    if item.text.trim().is_empty() {
+        return Err(AppError::validation("content", "Nothing to tag"));
+    }
+
+    let tags = suggest_tags_text(client, &item.title, &item.text, vocabulary, max_tags).await?;
+    let suggestions = tags
+        .into_iter()
+        .filter(|t| !item.tags.iter().any(|c| c.eq_ignore_ascii_case(t)))
+        .map(|tag| TagSuggestion {
+            existing: vocabulary.contains(&tag),
+            tag,
+        })
+        .collect();
+
+    Ok(TagSuggestions {
+        entity_type: item.entity_type.to_string(),
+        entity_id: item.entity_id,
+        title: item.title,
+        current_tags: item.tags,
+        suggestions,
+        model: client.model().to_string(),
+    })
+}
+
+fn resolve_entity_type(entity_type: &str) -> AppResult<&'static str> {
+    [ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE]
+        .into_iter()
+        .find(|t| *t == entity_type)
+        .ok_or_else(|| {
+            AppError::validation(
+                "entityType",
+                format!(
+                    "Unknown entity type '{}' (expected {} or {})",
+                    entity_type, ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE
+                ),
+            )
+        })
+}
+
+/// Existing tags across articles and references, most used first
+async fn tag_vocabulary(db: &sea_orm::DatabaseConnection) -> AppResult<Vec<String>> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    let article_tags: Vec<Option<String>> = NewsArticles::find()
+        .select_only()
+        .column(news_articles::Column::Tags)
+        .filter(news_articles::Column::UserId.eq(1))
+        .filter(news_articles::Column::Tags.is_not_null())
+        .into_tuple()
+        .all(db)
+        .await?;
+    let reference_tags: Vec<Option<String>> = reader_references::Entity::find()
+        .select_only()
+        .column(reader_references::Column::TagsJson)
+        .filter(reader_references::Column::TagsJson.is_not_null())
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    for raw in article_tags.iter().chain(reference_tags.iter()) {
+        for tag in parse_vec(raw) {
+            *counts.entry(tag.trim().to_lowercase()).or_default() += 1;
+        }
+    }
+
+    let mut vocabulary: Vec<(String, usize)> =
+        counts.into_iter().filter(|(t, _)| !t.is_empty()).collect();
+    vocabulary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    vocabulary.truncate(VOCABULARY_SIZE);
+    Ok(vocabulary.into_iter().map(|(t, _)| t).collect())
+}
+
+fn article_taggable(a: news_articles::Model) -> Taggable {
+    let text = [a.excerpt.as_deref(), a.content.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Taggable {
+        entity_type: ENTITY_NEWS_ARTICLE,
+        entity_id: a.id,
+        tags: parse_vec(&a.tags),
+        title: a.title,
+        text,
+    }
+}
+
+/// Reference text comes from its latest snapshot, falling back to the excerpt
+async fn reference_taggable(
+    db: &sea_orm::DatabaseConnection,
+    r: reader_references::Model,
+) -> AppResult<Taggable> {
+    let snapshot = reader_snapshots::Entity::find()
+        .filter(reader_snapshots::Column::ReferenceId.eq(r.id))
+        .order_by_desc(reader_snapshots::Column::FetchedAt)
+        .one(db)
+        .await?;
+    let text = snapshot
+        .map(|s| s.content_md)
+        .filter(|t| !t.trim().is_empty())
+        .or(r.excerpt)
+        .unwrap_or_default();
+    Ok(Taggable {
+        entity_type: ENTITY_READER_REFERENCE,
+        entity_id: r.id,
+        tags: parse_vec(&r.tags_json),
+        title: r.title,
+        text,
+    })
+}
+
+async fn load_taggable(
+    db: &sea_orm::DatabaseConnection,
+    entity_type: &'static str,
+    entity_id: i64,
+) -> AppResult<Taggable> {
+    if entity_type == ENTITY_NEWS_ARTICLE {
+        let article = NewsArticles::find_by_id(entity_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::other(format!("Article {} not found", entity_id)))?;
+        Ok(article_taggable(article))
+    } else {
+        let reference = reader_references::Entity::find_by_id(entity_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::other(format!("Reference {} not found", entity_id)))?;
+        reference_taggable(db, reference).await
+    }
+}
+
+/// Newest items with no tags yet
+async fn load_untagged(
+    db: &sea_orm::DatabaseConnection,
+    entity_type: &'static str,
+    limit: u64,
+) -> AppResult<Vec<Taggable>> {
+    if entity_type == ENTITY_NEWS_ARTICLE {
+        let articles = NewsArticles::find()
+            .filter(news_articles::Column::UserId.eq(1))
+            .filter(news_articles::Column::IsDismissed.eq(0))
+            .filter(
+                Condition::any()
+                    .add(news_articles::Column::Tags.is_null())
+                    .add(news_articles::Column::Tags.eq("[]")),
+            )
+            .order_by_desc(news_articles::Column::FetchedAt)
+            .limit(limit)
+            .all(db)
+            .await?;
+        Ok(articles.into_iter().map(article_taggable).collect())
+    } else {
+        let references = reader_references::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(reader_references::Column::TagsJson.is_null())
+                    .add(reader_references::Column::TagsJson.eq("[]")),
+            )
+            .order_by_desc(reader_references::Column::UpdatedAt)
+            .limit(limit)
+            .all(db)
+            .await?;
+        let mut items = Vec::with_capacity(references.len());
+        for r in references {
+            items.push(reference_taggable(db, r).await?);
+        }
+        Ok(items)
+    }
+}
+
+/// Merge `tags` into the item's existing tags
+async fn save_tags(
+    db: &sea_orm::DatabaseConnection,
+    entity_type: &'static str,
+    entity_id: i64,
+    tags: &[String],
+) -> AppResult<()> {
+    let merge = |current: Vec<String>| -> Option<String> {
+        let mut merged = current;
+        for tag in tags {
+            if !merged.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                merged.push(tag.clone());
+            }
+        }
+        to_json_vec(&Some(merged))
+    };
+
+    if entity_type == ENTITY_NEWS_ARTICLE {
+        let Some(article) = NewsArticles::find_by_id(entity_id).one(db).await? else {
+            return Ok(());
+        };
+        let tags_json = merge(parse_vec(&article.tags));
+        let mut active = article.into_active_model();
+        active.tags = Set(tags_json);
+        active.updated_at = Set(Utc::now());
+        active.update(db).await?;
+    } else {
+        let Some(reference) = reader_references::Entity::find_by_id(entity_id).one(db).await?
+        else {
+            return Ok(());
+        };
+        let tags_json = merge(parse_vec(&reference.tags_json));
+        let mut active = reference.into_active_model();
+        active.tags_json = Set(tags_json);
+        active.updated_at = Set(Utc::now().naive_utc());
+        active.update(db).await?;
+    }
+    Ok(())
+}
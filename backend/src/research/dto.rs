@@ -1,9 +1,10 @@
 //! DTOs for Research connectors (accounts, streams, items, capabilities)
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ResearchCapability {
     ReadStream,
@@ -13,7 +14,7 @@ pub enum ResearchCapability {
     ReactVote,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResearchAccountDto {
     pub id: i64,
@@ -26,7 +27,7 @@ pub struct ResearchAccountDto {
     pub updated_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResearchStreamDto {
     pub id: i64,
@@ -42,7 +43,7 @@ pub struct ResearchStreamDto {
     pub updated_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ResearchItemDto {
     pub id: i64,
@@ -62,7 +63,7 @@ pub struct ResearchItemDto {
     pub updated_at: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateResearchAccountInput {
     pub provider: String,
@@ -73,7 +74,7 @@ pub struct CreateResearchAccountInput {
     pub auth: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateResearchAccountInput {
     pub id: i64,
@@ -85,7 +86,7 @@ pub struct UpdateResearchAccountInput {
     pub auth: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpsertResearchStreamInput {
     pub id: Option<i64>,
@@ -97,7 +98,7 @@ pub struct UpsertResearchStreamInput {
     pub schedule: Option<Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListResearchItemsQuery {
     pub provider: Option<String>,
@@ -134,8 +134,21 @@ pub mod reader_references {
         pub byline: Option<String>,
         pub excerpt: Option<String>,
         pub tags_json: Option<String>,
+        pub summary: Option<String>,
         pub created_at: DateTime,
         pub updated_at: DateTime,
+        /// 0-100
+        pub progress_percent: Option<f64>,
+        pub progress_scroll_offset: Option<i32>,
+        /// Snapshot the progress was recorded against
+        pub progress_snapshot_id: Option<i64>,
+        pub last_read_at: Option<DateTime>,
+        /// 1 = re-fetched on a schedule by the reader_watch_refresh task
+        pub watch: i32,
+        pub watch_interval_minutes: Option<i32>,
+        pub last_checked_at: Option<DateTime>,
+        /// Last refresh whose content differed meaningfully
+        pub last_changed_at: Option<DateTime>,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -178,6 +191,11 @@ pub mod reader_snapshots {
         pub content_md: String,
         pub word_count: Option<i32>,
         pub reading_time_minutes: Option<i32>,
+        pub summary: Option<String>,
+        pub summary_model: Option<String>,
+        pub summarized_at: Option<DateTime>,
+        pub archive_path: Option<String>,
+        pub archive_bytes: Option<i64>,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -191,6 +209,8 @@ pub mod reader_snapshots {
         Reference,
         #[sea_orm(has_many = "super::reader_clips::Entity")]
         Clips,
+        #[sea_orm(has_many = "super::reader_snapshot_assets::Entity")]
+        Assets,
     }
 
     impl Related<super::reader_references::Entity> for Entity {
@@ -205,6 +225,12 @@ pub mod reader_snapshots {
         }
     }
 
+    impl Related<super::reader_snapshot_assets::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Assets.def()
+        }
+    }
+
     impl ActiveModelBehavior for ActiveModel {}
 }
 
@@ -221,6 +247,11 @@ pub mod reader_clips {
         pub quote: String,
         pub anchor: Option<String>,
         pub created_at: DateTime,
+        /// Palette name or `#rrggbb`
+        pub color: Option<String>,
+        /// Lowercase category, e.g. "quote", "evidence", "counterpoint"
+        pub label: Option<String>,
+        pub note: Option<String>,
     }
 
     #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -255,3 +286,114 @@ pub mod reader_clips {
 
     impl ActiveModelBehavior for ActiveModel {}
 }
+
+pub mod reader_snapshot_assets {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "reader_snapshot_assets")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        pub snapshot_id: i64,
+        pub original_url: String,
+        /// Relative to the storage media dir
+        pub local_path: String,
+        pub content_type: Option<String>,
+        pub byte_size: i64,
+        pub created_at: DateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {
+        #[sea_orm(
+            belongs_to = "super::reader_snapshots::Entity",
+            from = "Column::SnapshotId",
+            to = "super::reader_snapshots::Column::Id",
+            on_delete = "Cascade"
+        )]
+        Snapshot,
+    }
+
+    impl Related<super::reader_snapshots::Entity> for Entity {
+        fn to() -> RelationDef {
+            Relation::Snapshot.def()
+        }
+    }
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod reading_queue {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "reading_queue")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        /// "news_article" or "reader_reference"
+        pub item_type: String,
+        pub item_id: i64,
+        pub position: i32,
+        pub added_at: DateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod reader_site_rules {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "reader_site_rules")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        /// Lowercase host without "www."; also matches subdomains
+        pub domain: String,
+        /// CSS selector list for the content to keep
+        pub keep_selectors: Option<String>,
+        /// CSS selector list for elements stripped before extraction
+        pub remove_selectors: Option<String>,
+        pub user_agent: Option<String>,
+        pub enabled: i32,
+        pub created_at: DateTime,
+        pub updated_at: DateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
+
+pub mod site_credentials {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+    #[sea_orm(table_name = "site_credentials")]
+    pub struct Model {
+        #[sea_orm(primary_key)]
+        pub id: i64,
+        /// Lowercase host without "www."; also matches subdomains
+        pub domain: String,
+        /// Encrypted JSON `{ headers, cookies }`
+        pub secret_encrypted: Vec<u8>,
+        pub header_names_json: Option<String>,
+        pub has_cookies: i32,
+        pub enabled: i32,
+        /// Last time a request was sent with it
+        pub last_used_at: Option<DateTime>,
+        pub created_at: DateTime,
+        pub updated_at: DateTime,
+    }
+
+    #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+    pub enum Relation {}
+
+    impl ActiveModelBehavior for ActiveModel {}
+}
@@ -1,8 +1,11 @@
 //! System domain Tauri commands
 
 use super::components::scheduler::{
-    get_task_history_handler, list_system_tasks_handler, run_system_task_now_handler,
-    update_system_task_handler, RunTaskNowResult, SystemTaskDto, TaskRunDto, UpdateTaskInput,
+    cancel_scheduled_run_handler, get_task_history_handler, list_scheduled_runs_handler,
+    list_system_tasks_handler, pause_scheduler_handler, resume_scheduler_handler,
+    run_system_task_now_handler, schedule_task_run_handler, update_system_task_handler,
+    RunTaskNowResult, ScheduledRunDto, SchedulerStatusDto, SystemTaskDto, TaskRunDto,
+    UpdateTaskInput,
 };
 use crate::AppState;
 use tauri::State;
@@ -29,11 +32,13 @@ pub async fn get_task_history(
 #[tauri::command]
 pub async fn run_system_task_now(
     task_type: String,
+    params: Option<serde_json::Value>,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<RunTaskNowResult, String> {
     run_system_task_now_handler(
         task_type,
+        params,
         &state,
         &crate::core::components::events::NoopEventEmitter,
     )
@@ -51,3 +56,45 @@ pub async fn update_system_task(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn schedule_task_run(
+    task_type: String,
+    run_at: String,
+    params: Option<serde_json::Value>,
+    state: State<'_, AppState>,
+) -> Result<ScheduledRunDto, String> {
+    schedule_task_run_handler(task_type, run_at, params, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_scheduled_runs(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledRunDto>, String> {
+    list_scheduled_runs_handler(&state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn cancel_scheduled_run(id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    cancel_scheduled_run_handler(id, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn pause_scheduler(state: State<'_, AppState>) -> Result<SchedulerStatusDto, String> {
+    pause_scheduler_handler(&state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn resume_scheduler(state: State<'_, AppState>) -> Result<SchedulerStatusDto, String> {
+    resume_scheduler_handler(&state)
+        .await
+        .map_err(|e| e.to_string())
+}
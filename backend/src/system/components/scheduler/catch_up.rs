@@ -0,0 +1,104 @@
+//! Missed-run catch-up
+//!
+//! Cron ticks that fall while the app is down are lost. A task with
+//! `catch_up = "run_once"` is checked at scheduler startup: if a tick came
+//! due after its last run (or after it was created, if it never ran), it
+//! runs once right away, however many ticks were missed. The default,
+//! `skip`, waits for the next tick.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use croner::Cron;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tracing::info;
+
+use super::entities::{Column, Entity};
+use super::executor::schedule_for_task;
+use super::types::{Schedule, SystemTask};
+use crate::core::components::errors::AppResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUp {
+    Skip,
+    RunOnce,
+}
+
+impl CatchUp {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "skip" => Some(CatchUp::Skip),
+            "run_once" => Some(CatchUp::RunOnce),
+            _ => None,
+        }
+    }
+}
+
+/// The first tick of `schedule` after `since`, if it is already past.
+/// Cron ticks are evaluated in UTC, as the scheduler does.
+pub(crate) fn missed_tick(
+    schedule: &Schedule,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let next = match schedule {
+        Schedule::Cron(expr) => {
+            let cron: Cron = expr.parse().ok()?;
+            cron.find_next_occurrence(&since, false).ok()?
+        }
+        Schedule::Interval(every) => since + chrono::Duration::from_std(*every).ok()?,
+    };
+    (next <= now).then_some(next)
+}
+
+/// Enabled `run_once` tasks that missed a tick since they last ran
+pub(crate) async fn tasks_to_catch_up(db: &DatabaseConnection) -> AppResult<HashSet<i64>> {
+    let now = Utc::now();
+    let rows = Entity::find().filter(Column::Enabled.eq(1)).all(db).await?;
+    Ok(rows
+        .into_iter()
+        .filter(|m| CatchUp::parse(&m.catch_up) == Some(CatchUp::RunOnce))
+        .filter_map(|m| {
+            let since = m.last_run_at.unwrap_or(m.created_at);
+            let (id, task_type) = (m.id, m.task_type.clone());
+            let schedule = schedule_for_task(&SystemTask::from(m))?;
+            let missed = missed_tick(&schedule, since, now)?;
+            info!(
+                target: "scheduler",
+                "Catching up missed tick at {}: type='{}'", missed, task_type
+            );
+            Some(id)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_missed_tick() {
+        let at = |h, m| Utc.with_ymd_and_hms(2025, 3, 1, h, m, 0).unwrap();
+        let cron = |expr: &str| Schedule::Cron(expr.to_string());
+        let every_45 = cron("0 0/45 * * * * *");
+
+        assert_eq!(missed_tick(&every_45, at(10, 0), at(10, 30)), None);
+        assert_eq!(
+            missed_tick(&every_45, at(10, 0), at(13, 0)),
+            Some(at(10, 45))
+        );
+        assert_eq!(
+            missed_tick(&cron("0 0 2 * * * *"), at(1, 0), at(9, 0)),
+            Some(at(2, 0))
+        );
+        assert_eq!(missed_tick(&cron("not cron"), at(1, 0), at(9, 0)), None);
+
+        let every_90m = Schedule::Interval(std::time::Duration::from_secs(90 * 60));
+        assert_eq!(missed_tick(&every_90m, at(10, 0), at(11, 0)), None);
+        assert_eq!(
+            missed_tick(&every_90m, at(10, 0), at(12, 0)),
+            Some(at(11, 30))
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Task chaining
+//!
+//! A task may list prerequisites in `depends_on`: task types, or `prefix_*`
+//! patterns such as `feed_sync_*` for every feed source. When a scheduled run
+//! succeeds, each enabled task depending on it runs right after, once all of
+//! its prerequisites have succeeded since the dependent last ran. A dependent
+//! with no schedule of its own only runs this way.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::Local;
+use sea_orm::prelude::DateTimeUtc;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use tracing::{error, info};
+
+use super::entities::{Entity, Model};
+use super::executor::run_task_once;
+use super::pause::is_paused;
+use super::types::SystemTask;
+use crate::core::components::errors::AppResult;
+use crate::core::components::events::EventEmitter;
+use crate::AppState;
+
+/// Prerequisite patterns stored as a JSON array
+pub(crate) fn parse_depends_on(raw: &Option<String>) -> Vec<String> {
+    raw.as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default()
+}
+
+fn matches(pattern: &str, task_type: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => task_type.starts_with(prefix),
+        None => pattern == task_type,
+    }
+}
+
+/// Whether `task` last ran successfully, and not before `since`
+fn succeeded_since(task: &Model, since: Option<DateTimeUtc>) -> bool {
+    task.last_status.as_deref() == Some("success")
+        && match (task.last_run_at, since) {
+            (Some(ran), Some(since)) => ran >= since,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+}
+
+/// Whether every enabled task matching the dependent's prerequisites has
+/// succeeded since the dependent last ran
+fn prerequisites_met(dependent: &Model, tasks: &[Model]) -> bool {
+    let patterns = parse_depends_on(&dependent.depends_on);
+    tasks
+        .iter()
+        .filter(|t| t.id != dependent.id && t.enabled == 1)
+        .filter(|t| patterns.iter().any(|p| matches(p, &t.task_type)))
+        .all(|t| succeeded_since(t, dependent.last_run_at))
+}
+
+/// Enabled tasks that depend on `finished` and are now ready to run
+async fn ready_dependents(
+    db: &DatabaseConnection,
+    finished: &SystemTask,
+) -> AppResult<Vec<SystemTask>> {
+    let tasks = Entity::find().all(db).await?;
+    Ok(tasks
+        .iter()
+        .filter(|t| t.enabled == 1 && t.id != finished.id)
+        .filter(|t| {
+            parse_depends_on(&t.depends_on)
+                .iter()
+                .any(|pattern| matches(pattern, &finished.task_type))
+        })
+        .filter(|t| prerequisites_met(t, &tasks))
+        .cloned()
+        .map(SystemTask::from)
+        .collect())
+}
+
+/// Run the tasks that follow a successful run of `finished`, and theirs in
+/// turn. Each task runs at most once per chain; dependents outside their
+/// run window are skipped, and pausing the scheduler ends the chain.
+pub(crate) async fn run_dependents(
+    emitter: &dyn EventEmitter,
+    state: &AppState,
+    finished: &SystemTask,
+) {
+    let mut queue = VecDeque::from([finished.clone()]);
+    let mut ran = HashSet::from([finished.id]);
+    while let Some(done) = queue.pop_front() {
+        let ready = match ready_dependents(&state.db, &done).await {
+            Ok(ready) => ready,
+            Err(e) => {
+                error!(
+                    target: "scheduler",
+                    "Failed to load dependents of {}: {}", done.task_type, e
+                );
+                continue;
+            }
+        };
+        for task in ready {
+            if !ran.insert(task.id) || state.shutdown.is_draining() {
+                continue;
+            }
+            if is_paused(&state.db).await {
+                return;
+            }
+            if task
+                .run_window
+                .is_some_and(|w| !w.contains(Local::now().time()))
+            {
+                info!(
+                    target: "scheduler",
+                    "Outside run window, skipping dependent task: name='{}', type='{}'",
+                    task.name, task.task_type
+                );
+                continue;
+            }
+            info!(
+                target: "scheduler",
+                "Running dependent task: name='{}', type='{}', after='{}'",
+                task.name, task.task_type, done.task_type
+            );
+            let result = run_task_once(emitter, state, task.clone()).await;
+            if result.status == "success" {
+                queue.push_back(task);
+            }
+        }
+    }
+}
+
+/// A task type that ends up depending on itself, if any
+pub(crate) fn find_cycle(tasks: &[(String, Vec<String>)]) -> Option<String> {
+    let prerequisites: HashMap<&str, Vec<&str>> = tasks
+        .iter()
+        .map(|(task_type, patterns)| {
+            let deps = tasks
+                .iter()
+                .map(|(other, _)| other.as_str())
+                .filter(|other| patterns.iter().any(|p| matches(p, other)))
+                .collect();
+            (task_type.as_str(), deps)
+        })
+        .collect();
+
+    // Depth-first search; a task met again while still on the path closes a cycle
+    fn visit<'a>(
+        node: &'a str,
+        prerequisites: &HashMap<&'a str, Vec<&'a str>>,
+        on_path: &mut HashSet<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<String> {
+        if done.contains(node) {
+            return None;
+        }
+        if !on_path.insert(node) {
+            return Some(node.to_string());
+        }
+        for dep in prerequisites.get(node).into_iter().flatten() {
+            if let Some(cycle) = visit(dep, prerequisites, on_path, done) {
+                return Some(cycle);
+            }
+        }
+        on_path.remove(node);
+        done.insert(node);
+        None
+    }
+
+    let mut on_path = HashSet::new();
+    let mut done = HashSet::new();
+    tasks
+        .iter()
+        .find_map(|(task_type, _)| visit(task_type, &prerequisites, &mut on_path, &mut done))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(task_type: &str, deps: &[&str]) -> (String, Vec<String>) {
+        (
+            task_type.to_string(),
+            deps.iter().map(|d| d.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let tasks = vec![
+            task("news_sources_sync", &[]),
+            task("news_sync", &["news_sources_sync"]),
+            task("feed_sync_1", &[]),
+            task("feed_sync_2", &[]),
+            task("digest", &["feed_sync_*", "news_sync"]),
+        ];
+        assert_eq!(find_cycle(&tasks), None);
+
+        let mut looped = tasks.clone();
+        looped[2] = task("feed_sync_1", &["digest"]);
+        assert!(find_cycle(&looped).is_some());
+
+        assert!(find_cycle(&[task("news_sync", &["news_*"])]).is_some());
+    }
+}
@@ -12,11 +12,22 @@ pub struct Model {
     pub component: String,
     pub frequency_cron: Option<String>,
     pub frequency_seconds: Option<i64>,
+    pub interval_seconds: Option<i64>,
     pub enabled: i32,
     pub last_run_at: Option<DateTimeUtc>,
     pub last_status: Option<String>,
     pub last_result: Option<String>,
     pub error_count: i64,
+    /// Local time of day the task may run in, e.g. `06:00-23:00`
+    pub run_window: Option<String>,
+    /// `skip` or `defer` ticks outside `run_window`
+    pub outside_window: String,
+    /// Random delay of up to this many seconds before each scheduled run
+    pub jitter_seconds: Option<i64>,
+    /// JSON array of task types (or `prefix_*`) this task runs after
+    pub depends_on: Option<String>,
+    /// `skip` or `run_once` ticks missed while the app was down
+    pub catch_up: String,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
 }
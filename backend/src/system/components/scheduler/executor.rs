@@ -4,31 +4,45 @@
 //! and preventing concurrent runs of the same task.
 
 use super::entities::{Column, Entity};
+use super::limits::load_limits;
+use super::retention;
 use super::task_runs::ActiveModel as TaskRunActiveModel;
-use super::types::{SystemTask, TaskRunResult};
+use super::types::{Schedule, SystemTask, TaskRunResult};
+use crate::core::components::db::maintenance;
+use crate::core::components::embeddings;
 use crate::core::components::errors::AppResult;
 use crate::core::components::events::EventEmitter;
+use crate::core::components::storage;
 use crate::research::components::feed as news;
+use crate::research::components::reader_watch;
+use crate::writing::components::ideas;
+use crate::writing::sync as writing_sync;
 use crate::AppState;
 use chrono::Utc;
 use sea_orm::prelude::Expr;
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
 use tracing::{error, info, warn};
 
-/// Generate cron expression from task configuration
+/// How a task is scheduled, if at all
 ///
-/// Converts frequency_seconds or uses explicit frequency_cron.
-pub(crate) fn cron_for_task(task: &SystemTask) -> Option<String> {
+/// Uses the explicit frequency_cron, else interval_seconds, else converts
+/// the older frequency_seconds to a cron expression.
+pub(crate) fn schedule_for_task(task: &SystemTask) -> Option<Schedule> {
     if let Some(expr) = &task.frequency_cron {
-        return Some(expr.clone());
+        return Some(Schedule::Cron(expr.clone()));
+    }
+    if let Some(seconds) = task.interval_seconds.filter(|s| *s > 0) {
+        return Some(Schedule::Interval(Duration::from_secs(seconds as u64)));
     }
     if let Some(seconds) = task.frequency_seconds {
         if seconds <= 59 {
-            return Some(format!("0/{seconds} * * * * * *"));
+            return Some(Schedule::Cron(format!("0/{seconds} * * * * * *")));
         }
         if seconds % 60 == 0 {
             let minutes = seconds / 60;
-            return Some(format!("0 0/{minutes} * * * * *"));
+            return Some(Schedule::Cron(format!("0 0/{minutes} * * * * *")));
         }
     }
     None
@@ -39,18 +53,20 @@ pub(crate) async fn load_enabled_tasks(
     db: &sea_orm::DatabaseConnection,
 ) -> AppResult<Vec<SystemTask>> {
     let rows = Entity::find().filter(Column::Enabled.eq(1)).all(db).await?;
-    Ok(rows
-        .into_iter()
-        .map(|m| SystemTask {
-            id: m.id,
-            name: m.name,
-            task_type: m.task_type,
-            component: m.component,
-            frequency_cron: m.frequency_cron,
-            frequency_seconds: m.frequency_seconds,
-            enabled: m.enabled == 1,
-        })
-        .collect())
+    Ok(rows.into_iter().map(SystemTask::from).collect())
+}
+
+/// A manual run's parameters as the task's own type; runs without any get
+/// the defaults
+fn task_params<T: DeserializeOwned + Default>(task: &SystemTask) -> Result<T, TaskRunResult> {
+    match &task.params {
+        None => Ok(T::default()),
+        Some(params) => serde_json::from_value(params.clone()).map_err(|e| TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(format!("Invalid parameters: {}", e)),
+        }),
+    }
 }
 
 /// Execute a task once, with concurrency protection
@@ -71,6 +87,17 @@ pub(crate) async fn run_task_once(
     // Check if already running
     {
         let mut running = state.running.lock().await;
+        if state.shutdown.is_draining() {
+            info!(
+                target: "scheduler",
+                "Shutting down, not starting task {} ({})", task.task_type, task.name
+            );
+            return TaskRunResult {
+                status: "skipped",
+                result_json: Some("{\"reason\":\"shutting down\"}".into()),
+                error_message: None,
+            };
+        }
         if running.contains(&task.id) {
             warn!(
                 target: "scheduler",
@@ -85,6 +112,21 @@ pub(crate) async fn run_task_once(
         running.insert(task.id);
     }
 
+    // Queue behind other runs when the concurrency limits are reached
+    let limits = load_limits(&state.db).await;
+    let permit = tokio::select! {
+        permit = state.task_limiter.acquire(&task.component, &limits) => Some(permit),
+        _ = state.shutdown.draining() => None,
+    };
+    let Some(_permit) = permit else {
+        state.running.lock().await.remove(&task.id);
+        return TaskRunResult {
+            status: "skipped",
+            result_json: Some("{\"reason\":\"shutting down\"}".into()),
+            error_message: None,
+        };
+    };
+
     info!(
         target: "scheduler",
         "Executing task function: task_type={}", task.task_type
@@ -93,50 +135,90 @@ pub(crate) async fn run_task_once(
     let start_time = Utc::now();
 
     // Execute task function based on type
-    let result = match task.task_type.as_str() {
-        // Legacy news tasks (backwards compatibility)
-        "news_sync" => news::run_news_sync_task(state).await,
-        "news_sources_sync" => news::run_news_sources_sync_task(state).await,
-
-        // Feed source sync tasks
-        "feed_sources_sync_all" => news::run_feed_sources_sync_all_task(state).await,
-
-        // Per-source sync tasks (pattern: feed_sync_{source_id})
-        task_type if task_type.starts_with("feed_sync_") => {
-            if let Some(source_id_str) = task_type.strip_prefix("feed_sync_") {
-                if let Ok(source_id) = source_id_str.parse::<i64>() {
-                    news::run_feed_source_sync_task(state, source_id).await
+    let run = async {
+        match task.task_type.as_str() {
+            // Legacy news tasks (backwards compatibility)
+            "news_sync" => match task_params(&task) {
+                Ok(params) => news::run_news_sync_task(state, params).await,
+                Err(invalid) => invalid,
+            },
+            "news_sources_sync" => news::run_news_sources_sync_task(state).await,
+
+            // Feed source sync tasks
+            "feed_sources_sync_all" => match task_params(&task) {
+                Ok(params) => news::run_feed_sources_sync_all_task(state, params).await,
+                Err(invalid) => invalid,
+            },
+            "saved_searches_run" => news::run_saved_searches_task(state).await,
+
+            // Reader references with watch enabled
+            "reader_watch_refresh" => reader_watch::run_reader_watch_task(emitter, state).await,
+
+            // Semantic search index
+            "embeddings_index" => embeddings::run_embeddings_index_task(state).await,
+
+            // Run history retention
+            "task_history_cleanup" => retention::run_task_history_cleanup_task(state).await,
+
+            // Log, cache, backup and export retention
+            "storage_cleanup" => storage::run_storage_cleanup_task(state).await,
+
+            // ANALYZE and, when worthwhile, VACUUM
+            "db_maintenance" => maintenance::run_db_maintenance_task(state).await,
+
+            // Writings <-> Markdown folder
+            "writing_folder_sync" => writing_sync::run_writing_folder_sync_task(state).await,
+
+            // Reminders about ideas, as events
+            "idea_reminders" => ideas::run_idea_reminders_task(emitter, state).await,
+
+            // Per-source sync tasks (pattern: feed_sync_{source_id})
+            task_type if task_type.starts_with("feed_sync_") => {
+                if let Some(source_id_str) = task_type.strip_prefix("feed_sync_") {
+                    if let Ok(source_id) = source_id_str.parse::<i64>() {
+                        news::run_feed_source_sync_task(state, source_id).await
+                    } else {
+                        warn!(
+                            target: "scheduler",
+                            "Invalid source_id in task type: {}", task.task_type
+                        );
+                        TaskRunResult {
+                            status: "error",
+                            result_json: None,
+                            error_message: Some(format!("Invalid source_id: {}", source_id_str)),
+                        }
+                    }
                 } else {
-                    warn!(
-                        target: "scheduler",
-                        "Invalid source_id in task type: {}", task.task_type
-                    );
                     TaskRunResult {
                         status: "error",
                         result_json: None,
-                        error_message: Some(format!("Invalid source_id: {}", source_id_str)),
+                        error_message: Some("Missing source_id in task type".to_string()),
                     }
                 }
-            } else {
+            }
+
+            _ => {
+                warn!(
+                    target: "scheduler",
+                    "Unknown task type: {}", task.task_type
+                );
                 TaskRunResult {
-                    status: "error",
-                    result_json: None,
-                    error_message: Some("Missing source_id in task type".to_string()),
+                    status: "skipped",
+                    result_json: Some("{\"reason\":\"unknown task\"}".into()),
+                    error_message: None,
                 }
             }
         }
+    };
 
-        _ => {
-            warn!(
-                target: "scheduler",
-                "Unknown task type: {}", task.task_type
-            );
-            TaskRunResult {
-                status: "skipped",
-                result_json: Some("{\"reason\":\"unknown task\"}".into()),
-                error_message: None,
-            }
-        }
+    // Shutdown gives in-flight runs a grace period, then aborts them here
+    let result = tokio::select! {
+        result = run => result,
+        _ = state.shutdown.aborted() => TaskRunResult {
+            status: "aborted",
+            result_json: None,
+            error_message: Some("Interrupted by shutdown".to_string()),
+        },
     };
 
     // Log completion with more details
@@ -176,6 +258,7 @@ pub(crate) async fn run_task_once(
         status: Set(result.status.to_string()),
         result: Set(result.result_json.clone()),
         error_message: Set(result.error_message.clone()),
+        params: Set(task.params.as_ref().map(|p| p.to_string())),
         ..Default::default()
     };
 
@@ -207,6 +290,17 @@ pub(crate) async fn run_task_once(
         );
     }
 
+    // Sync tasks may have flagged articles via alert rules
+    let is_sync_task = matches!(
+        task.task_type.as_str(),
+        "news_sync" | "feed_sources_sync_all"
+    ) || task.task_type.starts_with("feed_sync_");
+    if is_sync_task {
+        if let Err(e) = news::emit_news_alerts_since(emitter, &state.db, start_time).await {
+            error!(target: "scheduler", "Failed to emit news alerts: {}", e);
+        }
+    }
+
     // Emit event to frontend
     let now = chrono::Utc::now().to_rfc3339();
     let payload = serde_json::json!({
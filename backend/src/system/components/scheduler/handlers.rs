@@ -2,14 +2,25 @@
 //!
 //! Provides functions for listing, running, and updating scheduled tasks.
 
+use super::catch_up::CatchUp;
+use super::chain::{find_cycle, parse_depends_on};
 use super::entities::{Column, Entity};
 use super::executor::run_task_once;
+use super::pause::set_paused;
+use super::scheduled_runs::{
+    ActiveModel as ScheduledRunActiveModel, Column as ScheduledRunsColumn,
+    Entity as ScheduledRunsEntity, Model as ScheduledRunsModel,
+};
 use super::task_runs::{
     Column as TaskRunsColumn, Entity as TaskRunsEntity, Model as TaskRunsModel,
 };
-use super::types::{model_to_dto, RunTaskNowResult, SystemTask, SystemTaskDto, UpdateTaskInput};
+use super::types::{
+    model_to_dto, RunTaskNowResult, SchedulerStatusDto, SystemTask, SystemTaskDto, UpdateTaskInput,
+};
+use super::window::{OutsideWindow, RunWindow};
 use crate::core::components::errors::{AppError, AppResult};
 use crate::core::components::events::EventEmitter;
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
     QuerySelect, Set,
@@ -23,11 +34,19 @@ pub async fn list_system_tasks_handler(state: &crate::AppState) -> AppResult<Vec
 }
 
 /// Manually run a task now (bypasses schedule)
+///
+/// `params` is an object of overrides for tasks that take them, e.g.
+/// `{"from_date": "2025-01-01"}` for `news_sync`; other tasks ignore it. It
+/// is recorded with the run.
 pub async fn run_system_task_now_handler(
     task_type: String,
+    params: Option<serde_json::Value>,
     state: &crate::AppState,
     emitter: &(dyn EventEmitter),
 ) -> AppResult<RunTaskNowResult> {
+    if params.as_ref().is_some_and(|p| !p.is_object()) {
+        return Err(AppError::validation("params", "Must be a JSON object"));
+    }
     let maybe_task = Entity::find()
         .filter(Column::TaskType.eq(task_type.clone()))
         .one(&state.db)
@@ -36,13 +55,8 @@ pub async fn run_system_task_now_handler(
         return Err(AppError::other("Not found"));
     };
     let task = SystemTask {
-        id: model.id,
-        name: model.name,
-        task_type: model.task_type,
-        component: model.component,
-        frequency_cron: model.frequency_cron,
-        frequency_seconds: model.frequency_seconds,
-        enabled: model.enabled == 1,
+        params,
+        ..SystemTask::from(model)
     };
     let res = run_task_once(emitter, state, task).await;
     let finished_at = chrono::Utc::now().to_rfc3339();
@@ -54,7 +68,8 @@ pub async fn run_system_task_now_handler(
     })
 }
 
-/// Update task configuration (enable/disable, frequency, name)
+/// Update task configuration (enable/disable, schedule, name, run window,
+/// jitter, prerequisites, catch-up)
 pub async fn update_system_task_handler(
     task_type: String,
     input: UpdateTaskInput,
@@ -71,28 +86,129 @@ pub async fn update_system_task_handler(
     if let Some(enabled) = input.enabled {
         active.enabled = Set(if enabled { 1 } else { 0 });
     }
+    let schedules_set = [
+        matches!(input.frequency_seconds, Some(Some(_))),
+        matches!(input.frequency_cron, Some(Some(_))),
+        matches!(input.interval_seconds, Some(Some(_))),
+    ];
+    if schedules_set.iter().filter(|set| **set).count() > 1 {
+        return Err(AppError::validation(
+            "interval_seconds",
+            "Set only one of frequency_cron, interval_seconds and frequency_seconds",
+        ));
+    }
+    // Setting one schedule clears the others
     if let Some(freq) = input.frequency_seconds {
         active.frequency_seconds = Set(freq);
         if freq.is_some() {
             active.frequency_cron = Set(None);
+            active.interval_seconds = Set(None);
         }
     }
     if let Some(cron) = input.frequency_cron {
         active.frequency_cron = Set(cron.clone());
         if cron.is_some() {
             active.frequency_seconds = Set(None);
+            active.interval_seconds = Set(None);
+        }
+    }
+    if let Some(interval) = input.interval_seconds {
+        if interval.is_some_and(|i| i <= 0) {
+            return Err(AppError::validation(
+                "interval_seconds",
+                "Must be a positive number of seconds",
+            ));
+        }
+        active.interval_seconds = Set(interval);
+        if interval.is_some() {
+            active.frequency_cron = Set(None);
+            active.frequency_seconds = Set(None);
         }
     }
     if let Some(name) = input.name {
         active.name = Set(name);
     }
+    if let Some(window) = input.run_window {
+        let window = window.filter(|w| !w.trim().is_empty());
+        if let Some(w) = &window {
+            if RunWindow::parse(w).is_none() {
+                return Err(AppError::validation(
+                    "run_window",
+                    format!("'{}' is not a window like 06:00-23:00", w),
+                ));
+            }
+        }
+        active.run_window = Set(window);
+    }
+    if let Some(policy) = input.outside_window {
+        if OutsideWindow::parse(&policy).is_none() {
+            return Err(AppError::validation("outside_window", "Use skip or defer"));
+        }
+        active.outside_window = Set(policy);
+    }
+    if let Some(jitter) = input.jitter_seconds {
+        if jitter.is_some_and(|j| j < 0) {
+            return Err(AppError::validation(
+                "jitter_seconds",
+                "Must not be negative",
+            ));
+        }
+        active.jitter_seconds = Set(jitter.filter(|j| *j > 0));
+    }
+    if let Some(depends_on) = input.depends_on {
+        let depends_on: Vec<String> = depends_on
+            .iter()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect();
+        // Check the whole graph as it would be after this change
+        let all = Entity::find().all(&state.db).await?;
+        let graph: Vec<(String, Vec<String>)> = all
+            .into_iter()
+            .map(|t| {
+                let deps = if t.task_type == task_type {
+                    depends_on.clone()
+                } else {
+                    parse_depends_on(&t.depends_on)
+                };
+                (t.task_type, deps)
+            })
+            .collect();
+        if let Some(task) = find_cycle(&graph) {
+            return Err(AppError::validation(
+                "depends_on",
+                format!("Would make {} depend on itself", task),
+            ));
+        }
+        active.depends_on = Set(if depends_on.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&depends_on).unwrap_or_else(|_| "[]".into()))
+        });
+    }
+    if let Some(catch_up) = input.catch_up {
+        if CatchUp::parse(&catch_up).is_none() {
+            return Err(AppError::validation("catch_up", "Use skip or run_once"));
+        }
+        active.catch_up = Set(catch_up);
+    }
     active.updated_at = Set(chrono::Utc::now());
     let saved = active.update(&state.db).await?;
     Ok(model_to_dto(saved))
 }
 
+/// Suspend all scheduled runs until `resume_scheduler_handler`
+pub async fn pause_scheduler_handler(state: &crate::AppState) -> AppResult<SchedulerStatusDto> {
+    set_paused(&state.db, true).await
+}
+
+/// Let scheduled runs start again
+pub async fn resume_scheduler_handler(state: &crate::AppState) -> AppResult<SchedulerStatusDto> {
+    set_paused(&state.db, false).await
+}
+
 /// DTO for task run history
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TaskRunDto {
     pub id: i64,
@@ -103,6 +219,8 @@ pub struct TaskRunDto {
     pub result: Option<String>,
     pub error_message: Option<String>,
     pub duration_secs: Option<i64>,
+    /// JSON parameter overrides of a manual run
+    pub params: Option<String>,
 }
 
 /// Convert task run model to DTO
@@ -122,6 +240,7 @@ fn task_run_to_dto(m: TaskRunsModel) -> TaskRunDto {
         result: m.result,
         error_message: m.error_message,
         duration_secs,
+        params: m.params,
     }
 }
 
@@ -146,3 +265,93 @@ pub async fn get_task_history_handler(
 
     Ok(runs.into_iter().map(task_run_to_dto).collect())
 }
+
+/// DTO for a pending one-shot run
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledRunDto {
+    pub id: i64,
+    pub task_type: String,
+    pub run_at: String,
+    /// JSON parameter overrides for the run
+    pub params: Option<String>,
+    pub created_at: String,
+}
+
+fn scheduled_run_to_dto(m: ScheduledRunsModel) -> ScheduledRunDto {
+    ScheduledRunDto {
+        id: m.id,
+        task_type: m.task_type,
+        run_at: m.run_at.to_rfc3339(),
+        params: m.params,
+        created_at: m.created_at.to_rfc3339(),
+    }
+}
+
+/// Schedule a single run of `task_type` at `run_at` (RFC 3339)
+///
+/// `params` is passed to the run as for `run_system_task_now_handler`. The
+/// run happens once, whether or not the task is enabled, and is removed
+/// from the pending list when it starts.
+pub async fn schedule_task_run_handler(
+    task_type: String,
+    run_at: String,
+    params: Option<serde_json::Value>,
+    state: &crate::AppState,
+) -> AppResult<ScheduledRunDto> {
+    if params.as_ref().is_some_and(|p| !p.is_object()) {
+        return Err(AppError::validation("params", "Must be a JSON object"));
+    }
+    let run_at = chrono::DateTime::parse_from_rfc3339(&run_at)
+        .map_err(|_| {
+            AppError::validation(
+                "run_at",
+                format!("'{}' is not a time like 2025-03-01T02:00:00+01:00", run_at),
+            )
+        })?
+        .with_timezone(&chrono::Utc);
+    let now = chrono::Utc::now();
+    if run_at <= now {
+        return Err(AppError::validation("run_at", "Must be in the future"));
+    }
+    let exists = Entity::find()
+        .filter(Column::TaskType.eq(task_type.clone()))
+        .one(&state.db)
+        .await?
+        .is_some();
+    if !exists {
+        return Err(AppError::other("Not found"));
+    }
+    let saved = ScheduledRunActiveModel {
+        task_type: Set(task_type),
+        run_at: Set(run_at),
+        params: Set(params.map(|p| p.to_string())),
+        created_at: Set(now),
+        ..Default::default()
+    }
+    .insert(&state.db)
+    .await?;
+    Ok(scheduled_run_to_dto(saved))
+}
+
+/// One-shot runs still to come, soonest first
+pub async fn list_scheduled_runs_handler(
+    state: &crate::AppState,
+) -> AppResult<Vec<ScheduledRunDto>> {
+    let runs = ScheduledRunsEntity::find()
+        .order_by_asc(ScheduledRunsColumn::RunAt)
+        .all(&state.db)
+        .await?;
+    Ok(runs.into_iter().map(scheduled_run_to_dto).collect())
+}
+
+/// Drop a one-shot run before it starts
+pub async fn cancel_scheduled_run_handler(id: i64, state: &crate::AppState) -> AppResult<()> {
+    let res = ScheduledRunsEntity::delete_by_id(id)
+        .exec(&state.db)
+        .await?;
+    if res.rows_affected == 0 {
+        return Err(AppError::other("Not found"));
+    }
+    Ok(())
+}
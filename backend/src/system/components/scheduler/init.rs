@@ -3,11 +3,22 @@
 //! Sets up tokio-cron-scheduler and registers all enabled tasks
 //! to run on their configured schedules.
 
-use super::executor::{cron_for_task, load_enabled_tasks, run_task_once};
+use super::catch_up::tasks_to_catch_up;
+use super::chain::run_dependents;
+use super::executor::{load_enabled_tasks, run_task_once, schedule_for_task};
+use super::one_shot;
+use super::pause::is_paused;
+use super::types::{Schedule, SystemTask};
+use super::window::OutsideWindow;
 use crate::core::components::events::EventEmitter;
 use crate::AppState;
+use chrono::Local;
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Mutex;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info, warn};
 
@@ -53,6 +64,7 @@ async fn ensure_feed_source_tasks(db: &sea_orm::DatabaseConnection) -> Result<()
             frequency_cron: Set(Some(default_schedule)),
             frequency_seconds: Set(None),
             enabled: Set(1),
+            jitter_seconds: Set(Some(FEED_SYNC_JITTER_SECONDS)),
             ..Default::default()
         };
 
@@ -79,10 +91,124 @@ async fn ensure_feed_source_tasks(db: &sea_orm::DatabaseConnection) -> Result<()
     Ok(())
 }
 
+/// Feed sources created later get the same spread as the migration gave
+/// existing ones, since they all start on the default schedule
+const FEED_SYNC_JITTER_SECONDS: i64 = 300;
+
+/// Run a task for a scheduled tick, honouring its jitter, run window and
+/// the global pause, then the tasks that depend on it
+async fn run_scheduled(
+    state: Arc<AppState>,
+    emitter: Arc<dyn EventEmitter>,
+    task: SystemTask,
+    schedule: Schedule,
+    deferred: Arc<Mutex<HashSet<i64>>>,
+) {
+    if let Some(jitter) = task.jitter_seconds.filter(|j| *j > 0) {
+        let delay = Duration::from_secs(rand::random_range(0..=jitter as u64));
+        let waited = tokio::select! {
+            _ = tokio::time::sleep(delay) => true,
+            _ = state.shutdown.draining() => false,
+        };
+        if !waited {
+            return;
+        }
+    }
+
+    if let Some(window) = task.run_window {
+        let wait = window.until_open(Local::now());
+        if !wait.is_zero() {
+            match task.outside_window {
+                OutsideWindow::Skip => {
+                    info!(
+                        target: "scheduler",
+                        "Outside run window, skipping task: name='{}', type='{}'",
+                        task.name, task.task_type
+                    );
+                    return;
+                }
+                OutsideWindow::Defer => {
+                    // Ticks while a deferred run is pending fold into it
+                    if !deferred.lock().await.insert(task.id) {
+                        return;
+                    }
+                    info!(
+                        target: "scheduler",
+                        "Outside run window, deferring task by {}s: name='{}', type='{}'",
+                        wait.as_secs(), task.name, task.task_type
+                    );
+                    let opened = tokio::select! {
+                        _ = tokio::time::sleep(wait) => true,
+                        _ = state.shutdown.draining() => false,
+                    };
+                    deferred.lock().await.remove(&task.id);
+                    if !opened {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    if is_paused(&state.db).await {
+        info!(
+            target: "scheduler",
+            "Scheduler paused, skipping task: name='{}', type='{}'",
+            task.name, task.task_type
+        );
+        return;
+    }
+
+    info!(
+        target: "scheduler",
+        "Scheduler triggering task: name='{}', type='{}', schedule={}",
+        task.name, task.task_type, schedule
+    );
+    let result = run_task_once(emitter.as_ref(), &state, task.clone()).await;
+    if result.status == "success" {
+        run_dependents(emitter.as_ref(), &state, &task).await;
+    }
+
+    match result.status {
+        "success" => {
+            info!(
+                target: "scheduler",
+                "Scheduled task completed successfully: name='{}', type='{}'",
+                task.name, task.task_type
+            );
+        }
+        "error" => {
+            error!(
+                target: "scheduler",
+                "Scheduled task failed: name='{}', type='{}', error='{}'",
+                task.name, task.task_type,
+                result.error_message.as_deref().unwrap_or("unknown")
+            );
+        }
+        "skipped" => {
+            warn!(
+                target: "scheduler",
+                "Scheduled task skipped: name='{}', type='{}', reason='{}'",
+                task.name, task.task_type,
+                result.result_json.as_deref().unwrap_or("unknown")
+            );
+        }
+        _ => {
+            info!(
+                target: "scheduler",
+                "Scheduled task finished: name='{}', type='{}', status='{}'",
+                task.name, task.task_type, result.status
+            );
+        }
+    }
+}
+
 /// Start the task scheduler and register all enabled tasks
 ///
 /// Creates a JobScheduler, loads enabled tasks from database,
-/// registers cron jobs for each task, and keeps scheduler running.
+/// registers cron or interval jobs for each task, and keeps scheduler running.
+/// Tasks set to catch up on a missed tick run once right away, and one-shot
+/// runs are polled for.
 pub async fn start_scheduler(
     state: Arc<AppState>,
     emitter: Arc<dyn EventEmitter>,
@@ -95,68 +221,68 @@ pub async fn start_scheduler(
     let scheduler = JobScheduler::new().await.map_err(|e| e.to_string())?;
 
     let tasks = load_enabled_tasks(&state.db).await.unwrap_or_default();
+    let deferred = Arc::new(Mutex::new(HashSet::new()));
+    let catch_up = tasks_to_catch_up(&state.db).await.unwrap_or_else(|e| {
+        warn!(target: "scheduler", "Failed to check for missed runs: {}", e);
+        HashSet::new()
+    });
     for task in tasks {
-        if let Some(expr) = cron_for_task(&task) {
+        if let Some(schedule) = schedule_for_task(&task) {
+            // Missed ticks go through the same jitter, window and pause checks
+            if catch_up.contains(&task.id) {
+                tokio::spawn(run_scheduled(
+                    state.clone(),
+                    emitter.clone(),
+                    task.clone(),
+                    schedule.clone(),
+                    deferred.clone(),
+                ));
+            }
             let state_clone = state.clone();
             let emitter = emitter.clone();
-            let expr_clone = expr.clone();
-            let job = Job::new_async(expr.as_str(), move |_uuid, _l| {
+            let schedule_clone = schedule.clone();
+            let deferred = deferred.clone();
+            let run = move |_uuid, _l| -> Pin<Box<dyn Future<Output = ()> + Send>> {
                 let state_clone = state_clone.clone();
                 let emitter = emitter.clone();
                 let task = task.clone();
-                let cron_expr = expr_clone.clone();
-                Box::pin(async move {
-                    info!(
-                        target: "scheduler",
-                        "Scheduler triggering task: name='{}', type='{}', cron='{}'",
-                        task.name, task.task_type, cron_expr
-                    );
-                    let result = run_task_once(emitter.as_ref(), &state_clone, task.clone()).await;
-
-                    match result.status {
-                        "success" => {
-                            info!(
-                                target: "scheduler",
-                                "Scheduled task completed successfully: name='{}', type='{}'",
-                                task.name, task.task_type
-                            );
-                        }
-                        "error" => {
-                            error!(
-                                target: "scheduler",
-                                "Scheduled task failed: name='{}', type='{}', error='{}'",
-                                task.name, task.task_type,
-                                result.error_message.as_deref().unwrap_or("unknown")
-                            );
-                        }
-                        "skipped" => {
-                            warn!(
-                                target: "scheduler",
-                                "Scheduled task skipped: name='{}', type='{}', reason='{}'",
-                                task.name, task.task_type,
-                                result.result_json.as_deref().unwrap_or("unknown")
-                            );
-                        }
-                        _ => {
-                            info!(
-                                target: "scheduler",
-                                "Scheduled task finished: name='{}', type='{}', status='{}'",
-                                task.name, task.task_type, result.status
-                            );
-                        }
-                    }
-                })
-            })
+                let schedule = schedule_clone.clone();
+                let deferred = deferred.clone();
+                Box::pin(run_scheduled(
+                    state_clone,
+                    emitter,
+                    task,
+                    schedule,
+                    deferred,
+                ))
+            };
+            let job = match &schedule {
+                Schedule::Cron(expr) => Job::new_async(expr.as_str(), run),
+                Schedule::Interval(every) => Job::new_repeated_async(*every, run),
+            }
             .map_err(|e| e.to_string())?;
             scheduler.add(job).await.map_err(|e| e.to_string())?;
         }
     }
+    // One-shot runs are looked up on each poll, so runs scheduled after
+    // startup need no job of their own
+    let poll_state = state.clone();
+    let poll_emitter = emitter.clone();
+    let poll = Job::new_repeated_async(one_shot::POLL_INTERVAL, move |_uuid, _l| {
+        let state = poll_state.clone();
+        let emitter = poll_emitter.clone();
+        Box::pin(async move { one_shot::run_due(&state, &emitter).await })
+    })
+    .map_err(|e| e.to_string())?;
+    scheduler.add(poll).await.map_err(|e| e.to_string())?;
 
     scheduler.start().await.map_err(|e| e.to_string())?;
     tokio::spawn(async move {
-        let _scheduler = scheduler;
-        loop {
-            tokio::time::sleep(Duration::from_secs(3600)).await;
+        let mut scheduler = scheduler;
+        state.shutdown.draining().await;
+        info!(target: "scheduler", "Stopping scheduler");
+        if let Err(e) = scheduler.shutdown().await {
+            warn!(target: "scheduler", "Failed to stop scheduler cleanly: {}", e);
         }
     });
 
@@ -0,0 +1,165 @@
+//! Concurrency limits for task runs
+//!
+//! `running` keeps a task from overlapping itself; these limits cap how many
+//! tasks run at once: `scheduler.max_concurrent_tasks` overall and
+//! `scheduler.component_limits` (e.g. `{"research": 2}`) per component, so
+//! per-source feed syncs firing on the same tick queue up instead of all
+//! hitting the database together. 0, or no entry, means no limit. Limits are
+//! read as each run starts, so changes apply without a restart.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use sea_orm::DatabaseConnection;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+
+use crate::core::components::settings::handlers::get_setting_value;
+
+const MAX_CONCURRENT_SETTING: &str = "scheduler.max_concurrent_tasks";
+const COMPONENT_LIMITS_SETTING: &str = "scheduler.component_limits";
+
+/// Limits in force for one run
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Limits {
+    pub total: usize,
+    pub components: HashMap<String, usize>,
+}
+
+impl Limits {
+    fn allows(&self, counts: &Counts, component: &str) -> bool {
+        let under = |limit: usize, count: usize| limit == 0 || count < limit;
+        under(self.total, counts.total)
+            && under(
+                self.components.get(component).copied().unwrap_or(0),
+                counts.components.get(component).copied().unwrap_or(0),
+            )
+    }
+}
+
+/// Current limits from settings; unreadable settings mean no limit
+pub(crate) async fn load_limits(db: &DatabaseConnection) -> Limits {
+    let read = |key| async move {
+        get_setting_value(db, key).await.unwrap_or_else(|e| {
+            warn!(target: "scheduler", "Failed to read {}: {}", key, e);
+            None
+        })
+    };
+    Limits {
+        // Numbers are stored as f64 text ("4" or "4.0")
+        total: read(MAX_CONCURRENT_SETTING)
+            .await
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|n| n.max(0.0) as usize)
+            .unwrap_or(0),
+        components: read(COMPONENT_LIMITS_SETTING)
+            .await
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default(),
+    }
+}
+
+#[derive(Default)]
+struct Counts {
+    total: usize,
+    components: HashMap<String, usize>,
+}
+
+#[derive(Default)]
+struct Inner {
+    counts: Mutex<Counts>,
+    freed: Notify,
+}
+
+/// Counts running tasks and hands out slots under the limits
+#[derive(Clone, Default)]
+pub struct TaskLimiter {
+    inner: Arc<Inner>,
+}
+
+impl TaskLimiter {
+    fn try_acquire(&self, component: &str, limits: &Limits) -> Option<TaskPermit> {
+        let mut counts = self.inner.counts.lock().unwrap_or_else(|e| e.into_inner());
+        if !limits.allows(&counts, component) {
+            return None;
+        }
+        counts.total += 1;
+        *counts.components.entry(component.to_string()).or_default() += 1;
+        Some(TaskPermit {
+            limiter: self.clone(),
+            component: component.to_string(),
+        })
+    }
+
+    /// Wait until a task of `component` may run
+    pub async fn acquire(&self, component: &str, limits: &Limits) -> TaskPermit {
+        let mut waiting = false;
+        loop {
+            // Registered before checking, so a slot freed in between wakes us
+            let freed = self.inner.freed.notified();
+            tokio::pin!(freed);
+            freed.as_mut().enable();
+            if let Some(permit) = self.try_acquire(component, limits) {
+                return permit;
+            }
+            if !waiting {
+                waiting = true;
+                info!(
+                    target: "scheduler",
+                    "Concurrency limit reached, queueing {} task", component
+                );
+            }
+            freed.await;
+        }
+    }
+}
+
+/// A running task's slot, given back on drop
+pub struct TaskPermit {
+    limiter: TaskLimiter,
+    component: String,
+}
+
+impl Drop for TaskPermit {
+    fn drop(&mut self) {
+        let inner = &self.limiter.inner;
+        {
+            let mut counts = inner.counts.lock().unwrap_or_else(|e| e.into_inner());
+            counts.total = counts.total.saturating_sub(1);
+            if let Some(count) = counts.components.get_mut(&self.component) {
+                *count = count.saturating_sub(1);
+            }
+        }
+        inner.freed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let limits = Limits {
+            total: 3,
+            components: HashMap::from([("research".to_string(), 2)]),
+        };
+        let limiter = TaskLimiter::default();
+
+        let first = limiter.try_acquire("research", &limits).unwrap();
+        let _second = limiter.try_acquire("research", &limits).unwrap();
+        assert!(limiter.try_acquire("research", &limits).is_none());
+
+        let _other = limiter.try_acquire("system", &limits).unwrap();
+        assert!(limiter.try_acquire("system", &limits).is_none());
+
+        drop(first);
+        assert!(limiter.try_acquire("research", &limits).is_some());
+
+        let unlimited = Limits::default();
+        let permits: Vec<_> = (0..10)
+            .filter_map(|_| limiter.try_acquire("research", &unlimited))
+            .collect();
+        assert_eq!(permits.len(), 10);
+    }
+}
@@ -5,23 +5,43 @@
 //! - task_runs: Database model for system_task_runs table
 //! - types: Data structures for tasks and results
 //! - executor: Task execution with concurrency protection
+//! - limits: Global and per-component concurrency limits
 //! - handlers: API endpoints for task management
 //! - init: Scheduler startup and cron registration
+//! - window: Run windows (quiet hours)
+//! - chain: Prerequisites and follow-up runs
+//! - pause: Global pause/resume
+//! - retention: Pruning of the run history
+//! - catch_up: Runs for ticks missed while the app was down
+//! - scheduled_runs: Database model for system_task_scheduled_runs table
+//! - one_shot: Single runs at a given time
 
+pub mod catch_up;
+pub mod chain;
 pub mod entities;
 pub mod executor;
 pub mod handlers;
 pub mod init;
+pub mod limits;
+pub mod one_shot;
+pub mod pause;
+pub mod retention;
+pub mod scheduled_runs;
 pub mod task_runs;
 pub mod types;
+pub mod window;
 
 // Re-export types for use elsewhere
-pub use types::{RunTaskNowResult, SystemTaskDto, TaskRunResult, UpdateTaskInput};
+pub use types::{
+    RunTaskNowResult, SchedulerStatusDto, SystemTaskDto, TaskRunResult, UpdateTaskInput,
+};
 
 // Re-export handlers for Tauri commands
 pub use handlers::{
-    get_task_history_handler, list_system_tasks_handler, run_system_task_now_handler,
-    update_system_task_handler, TaskRunDto,
+    cancel_scheduled_run_handler, get_task_history_handler, list_scheduled_runs_handler,
+    list_system_tasks_handler, pause_scheduler_handler, resume_scheduler_handler,
+    run_system_task_now_handler, schedule_task_run_handler, update_system_task_handler,
+    ScheduledRunDto, TaskRunDto,
 };
 
 // Re-export initialization function
@@ -0,0 +1,166 @@
+//! One-shot scheduled runs
+//!
+//! `schedule_task_run` stores a single future run of a task type, e.g. an
+//! archive sync tonight at 2am, optionally with parameter overrides as for
+//! `run_system_task_now`. The scheduler looks for due runs every
+//! [`POLL_INTERVAL`]; a run's row is deleted as it starts, so it runs once.
+//! Runs that came due while the app was closed start on the first check
+//! after startup, and due runs wait while the scheduler is paused.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use tracing::{error, info, warn};
+
+use super::entities::{Column as TaskColumn, Entity as TaskEntity};
+use super::executor::run_task_once;
+use super::pause::is_paused;
+use super::scheduled_runs::{Column, Entity, Model};
+use super::types::SystemTask;
+use crate::core::components::events::EventEmitter;
+use crate::AppState;
+
+/// How often due runs are looked for
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Claim a due run by deleting its row; `false` if another check got it
+/// first or the delete failed
+async fn claim(db: &DatabaseConnection, run: &Model) -> bool {
+    match Entity::delete_by_id(run.id).exec(db).await {
+        Ok(res) => res.rows_affected == 1,
+        Err(e) => {
+            error!(
+                target: "scheduler",
+                "Failed to claim scheduled run {}: {}", run.id, e
+            );
+            false
+        }
+    }
+}
+
+/// Runs that are due, oldest first; none while the scheduler is paused
+async fn due_runs(db: &DatabaseConnection) -> Vec<Model> {
+    if is_paused(db).await {
+        return Vec::new();
+    }
+    match Entity::find()
+        .filter(Column::RunAt.lte(Utc::now()))
+        .order_by_asc(Column::RunAt)
+        .all(db)
+        .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            error!(target: "scheduler", "Failed to load scheduled runs: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Start every scheduled run that is due
+pub(crate) async fn run_due(state: &Arc<AppState>, emitter: &Arc<dyn EventEmitter>) {
+    if state.shutdown.is_draining() {
+        return;
+    }
+    for run in due_runs(&state.db).await {
+        if !claim(&state.db, &run).await {
+            continue;
+        }
+        let model = match TaskEntity::find()
+            .filter(TaskColumn::TaskType.eq(run.task_type.as_str()))
+            .one(&state.db)
+            .await
+        {
+            Ok(Some(model)) => model,
+            Ok(None) => {
+                warn!(
+                    target: "scheduler",
+                    "Dropping scheduled run of unknown task type '{}'", run.task_type
+                );
+                continue;
+            }
+            Err(e) => {
+                error!(
+                    target: "scheduler",
+                    "Failed to load task '{}' for scheduled run: {}", run.task_type, e
+                );
+                continue;
+            }
+        };
+        let task = SystemTask {
+            params: run
+                .params
+                .as_deref()
+                .and_then(|p| serde_json::from_str(p).ok()),
+            ..SystemTask::from(model)
+        };
+        info!(
+            target: "scheduler",
+            "Starting scheduled run: type='{}', run_at={}", task.task_type, run.run_at
+        );
+        let state = state.clone();
+        let emitter = emitter.clone();
+        tokio::spawn(async move {
+            let result = run_task_once(emitter.as_ref(), &state, task.clone()).await;
+            info!(
+                target: "scheduler",
+                "Scheduled run finished: type='{}', status='{}'", task.task_type, result.status
+            );
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use crate::system::components::scheduler::pause::set_paused;
+    use crate::system::components::scheduler::scheduled_runs::ActiveModel;
+    use chrono::Duration as ChronoDuration;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    async fn schedule(db: &DatabaseConnection, minutes_from_now: i64) -> Model {
+        let now = Utc::now();
+        ActiveModel {
+            task_type: Set("news_sync".to_string()),
+            run_at: Set(now + ChronoDuration::minutes(minutes_from_now)),
+            params: Set(None),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+
+    fn ids(runs: &[Model]) -> Vec<i64> {
+        runs.iter().map(|run| run.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_due_run_is_claimed_once() {
+        let db = test_db().await;
+        let due = schedule(&db, -5).await;
+        let later = schedule(&db, 60).await;
+
+        assert_eq!(ids(&due_runs(&db).await), [due.id]);
+        assert!(claim(&db, &due).await);
+        // A second check that loaded the same run doesn't start it again
+        assert!(!claim(&db, &due).await);
+        assert!(due_runs(&db).await.is_empty());
+        assert!(Entity::find_by_id(later.id).one(&db).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_due_runs_wait_while_paused() {
+        let db = test_db().await;
+        let due = schedule(&db, -5).await;
+
+        set_paused(&db, true).await.unwrap();
+        assert!(due_runs(&db).await.is_empty());
+        set_paused(&db, false).await.unwrap();
+        assert_eq!(ids(&due_runs(&db).await), [due.id]);
+    }
+}
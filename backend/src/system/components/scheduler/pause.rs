@@ -0,0 +1,47 @@
+//! Global scheduler pause
+//!
+//! While paused (the `scheduler.paused` setting), cron ticks, deferred runs
+//! and chained runs are dropped without touching each task's `enabled` flag,
+//! so imports, restores and migrations can run undisturbed. Manual runs
+//! still work. The setting survives restarts.
+
+use sea_orm::DatabaseConnection;
+use serde_json::json;
+use tracing::{info, warn};
+
+use super::types::SchedulerStatusDto;
+use crate::core::components::errors::AppResult;
+use crate::core::components::settings::handlers::get_setting_value;
+use crate::core::components::settings::{update_setting_handler, UpdateSettingInput};
+
+pub(crate) const PAUSED_SETTING: &str = "scheduler.paused";
+
+/// Whether scheduled runs are suspended; a failed read counts as running
+pub(crate) async fn is_paused(db: &DatabaseConnection) -> bool {
+    match get_setting_value(db, PAUSED_SETTING).await {
+        Ok(value) => value.as_deref() == Some("true"),
+        Err(e) => {
+            warn!(target: "scheduler", "Failed to read {}: {}", PAUSED_SETTING, e);
+            false
+        }
+    }
+}
+
+pub(crate) async fn set_paused(
+    db: &DatabaseConnection,
+    paused: bool,
+) -> AppResult<SchedulerStatusDto> {
+    update_setting_handler(
+        db,
+        UpdateSettingInput {
+            key: PAUSED_SETTING.to_string(),
+            value: json!(paused),
+        },
+    )
+    .await?;
+    info!(
+        target: "scheduler",
+        "Scheduler {}", if paused { "paused" } else { "resumed" }
+    );
+    Ok(SchedulerStatusDto { paused })
+}
@@ -0,0 +1,100 @@
+//! Task run history retention
+//!
+//! The built-in `task_history_cleanup` task prunes `system_task_runs`: a run
+//! is deleted once it is older than `scheduler.history_keep_days` or falls
+//! outside the newest `scheduler.history_keep_runs` runs of its task. Either
+//! setting can be 0 to turn that limit off.
+
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Statement,
+};
+use serde::Serialize;
+
+use super::task_runs::{Column, Entity};
+use super::types::TaskRunResult;
+use crate::core::components::errors::AppResult;
+use crate::core::components::settings::handlers::get_setting_value;
+use crate::AppState;
+
+const KEEP_RUNS_SETTING: &str = "scheduler.history_keep_runs";
+const KEEP_DAYS_SETTING: &str = "scheduler.history_keep_days";
+const DEFAULT_KEEP_RUNS: u64 = 500;
+const DEFAULT_KEEP_DAYS: u64 = 30;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PruneResult {
+    keep_runs: u64,
+    keep_days: u64,
+    deleted_by_age: u64,
+    deleted_by_count: u64,
+}
+
+async fn setting_or(db: &DatabaseConnection, key: &str, default: u64) -> AppResult<u64> {
+    let value = get_setting_value(db, key).await?;
+    // Numbers are stored as f64 text ("30" or "30.0")
+    Ok(value
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(|n| n.max(0.0) as u64)
+        .unwrap_or(default))
+}
+
+async fn prune_task_runs(db: &DatabaseConnection) -> AppResult<PruneResult> {
+    let keep_runs = setting_or(db, KEEP_RUNS_SETTING, DEFAULT_KEEP_RUNS).await?;
+    let keep_days = setting_or(db, KEEP_DAYS_SETTING, DEFAULT_KEEP_DAYS).await?;
+
+    let deleted_by_age = if keep_days > 0 {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(keep_days as i64);
+        Entity::delete_many()
+            .filter(Column::StartedAt.lt(cutoff))
+            .exec(db)
+            .await?
+            .rows_affected
+    } else {
+        0
+    };
+
+    let deleted_by_count = if keep_runs > 0 {
+        db.execute(Statement::from_string(
+            db.get_database_backend(),
+            format!(
+                "DELETE FROM system_task_runs WHERE id IN (
+                    SELECT id FROM (
+                        SELECT id, ROW_NUMBER() OVER (
+                            PARTITION BY task_id ORDER BY started_at DESC, id DESC
+                        ) AS position
+                        FROM system_task_runs
+                    ) ranked WHERE position > {}
+                )",
+                keep_runs
+            ),
+        ))
+        .await?
+        .rows_affected()
+    } else {
+        0
+    };
+
+    Ok(PruneResult {
+        keep_runs,
+        keep_days,
+        deleted_by_age,
+        deleted_by_count,
+    })
+}
+
+/// Scheduled task entry point
+pub async fn run_task_history_cleanup_task(state: &AppState) -> TaskRunResult {
+    match prune_task_runs(&state.db).await {
+        Ok(result) => TaskRunResult {
+            status: "success",
+            result_json: serde_json::to_string(&result).ok(),
+            error_message: None,
+        },
+        Err(e) => TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(e.to_string()),
+        },
+    }
+}
@@ -13,6 +13,8 @@ pub struct Model {
     pub status: String,
     pub result: Option<String>,
     pub error_message: Option<String>,
+    /// JSON parameter overrides of a manual run
+    pub params: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
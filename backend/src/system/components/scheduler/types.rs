@@ -3,6 +3,9 @@
 //! Defines data structures for system tasks, execution results,
 //! and API request/response types.
 
+use super::catch_up::CatchUp;
+use super::window::{OutsideWindow, RunWindow};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Internal representation of a system task
@@ -15,7 +18,51 @@ pub struct SystemTask {
     pub component: String,
     pub frequency_cron: Option<String>,
     pub frequency_seconds: Option<i64>,
+    pub interval_seconds: Option<i64>,
     pub enabled: bool,
+    pub run_window: Option<RunWindow>,
+    pub outside_window: OutsideWindow,
+    pub jitter_seconds: Option<i64>,
+    pub catch_up: CatchUp,
+    /// Parameter overrides for a manual run
+    pub params: Option<serde_json::Value>,
+}
+
+impl From<super::entities::Model> for SystemTask {
+    fn from(m: super::entities::Model) -> Self {
+        SystemTask {
+            id: m.id,
+            name: m.name,
+            task_type: m.task_type,
+            component: m.component,
+            frequency_cron: m.frequency_cron,
+            frequency_seconds: m.frequency_seconds,
+            interval_seconds: m.interval_seconds,
+            enabled: m.enabled == 1,
+            run_window: m.run_window.as_deref().and_then(RunWindow::parse),
+            outside_window: OutsideWindow::parse(&m.outside_window).unwrap_or(OutsideWindow::Skip),
+            jitter_seconds: m.jitter_seconds,
+            catch_up: CatchUp::parse(&m.catch_up).unwrap_or(CatchUp::Skip),
+            params: None,
+        }
+    }
+}
+
+/// When a task runs on its own
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Cron(String),
+    /// Every so often, counted from scheduler start
+    Interval(std::time::Duration),
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Schedule::Cron(expr) => write!(f, "cron '{}'", expr),
+            Schedule::Interval(every) => write!(f, "every {}s", every.as_secs()),
+        }
+    }
 }
 
 /// Result of task execution
@@ -27,7 +74,7 @@ pub struct TaskRunResult {
 }
 
 /// DTO for system task API responses
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SystemTaskDto {
     pub id: i64,
@@ -36,15 +83,27 @@ pub struct SystemTaskDto {
     pub component: String,
     pub frequency_cron: Option<String>,
     pub frequency_seconds: Option<i64>,
+    /// Runs every this many seconds instead of on a cron schedule
+    pub interval_seconds: Option<i64>,
     pub enabled: bool,
     pub last_run_at: Option<sea_orm::prelude::DateTimeUtc>,
     pub last_status: Option<String>,
     pub last_result: Option<String>,
     pub error_count: i64,
+    /// Local time of day the task may run in, e.g. `06:00-23:00`
+    pub run_window: Option<String>,
+    /// `skip` or `defer` scheduled runs outside the window
+    pub outside_window: String,
+    /// Scheduled runs start up to this many seconds after their tick
+    pub jitter_seconds: Option<i64>,
+    /// Task types (or `prefix_*` patterns) this task runs after
+    pub depends_on: Vec<String>,
+    /// `skip` or `run_once` ticks missed while the app was down
+    pub catch_up: String,
 }
 
 /// Result of manually running a task
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct RunTaskNowResult {
     pub status: String,
@@ -53,13 +112,33 @@ pub struct RunTaskNowResult {
     pub finished_at: String,
 }
 
+/// Whether the scheduler is paused
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulerStatusDto {
+    pub paused: bool,
+}
+
 /// Input for updating task configuration
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct UpdateTaskInput {
     pub enabled: Option<bool>,
     pub frequency_seconds: Option<Option<i64>>,
     pub frequency_cron: Option<Option<String>>,
+    /// Fixed interval; exclusive with `frequency_cron`, and setting one
+    /// clears the other
+    pub interval_seconds: Option<Option<i64>>,
     pub name: Option<String>,
+    /// `HH:MM-HH:MM` in local time; `null` removes the window
+    pub run_window: Option<Option<String>>,
+    /// `skip` or `defer`
+    pub outside_window: Option<String>,
+    /// `null` or `0` runs exactly on the tick
+    pub jitter_seconds: Option<Option<i64>>,
+    /// Prerequisite task types or `prefix_*` patterns; `[]` clears them
+    pub depends_on: Option<Vec<String>>,
+    /// `skip` or `run_once`
+    pub catch_up: Option<String>,
 }
 
 /// Convert database model to DTO
@@ -71,10 +150,16 @@ pub(crate) fn model_to_dto(m: super::entities::Model) -> SystemTaskDto {
         component: m.component,
         frequency_cron: m.frequency_cron,
         frequency_seconds: m.frequency_seconds,
+        interval_seconds: m.interval_seconds,
         enabled: m.enabled == 1,
         last_run_at: m.last_run_at,
         last_status: m.last_status,
         last_result: m.last_result,
         error_count: m.error_count,
+        run_window: m.run_window,
+        outside_window: m.outside_window,
+        jitter_seconds: m.jitter_seconds,
+        depends_on: super::chain::parse_depends_on(&m.depends_on),
+        catch_up: m.catch_up,
     }
 }
@@ -0,0 +1,103 @@
+//! Run windows (quiet hours) for scheduled tasks
+//!
+//! A task with a run window such as `06:00-23:00` only runs between those
+//! local times; windows may wrap past midnight (`22:00-06:00`). Cron ticks
+//! outside the window are either skipped or deferred to the window's next
+//! opening, where all deferred ticks collapse into a single run. Manual runs
+//! ignore the window.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local, NaiveTime, TimeZone};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl RunWindow {
+    /// Parse `HH:MM-HH:MM`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (start, end) = value.split_once('-')?;
+        let time = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+        let window = RunWindow {
+            start: time(start)?,
+            end: time(end)?,
+        };
+        (window.start != window.end).then_some(window)
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// How long until the window next opens; zero when it is open
+    pub fn until_open(&self, now: DateTime<Local>) -> Duration {
+        if self.contains(now.time()) {
+            return Duration::ZERO;
+        }
+        let mut day = now.date_naive();
+        if now.time() >= self.start {
+            day = day.succ_opt().unwrap_or(day);
+        }
+        // A DST gap at the opening time falls back to an hour later
+        let opens = Local
+            .from_local_datetime(&day.and_time(self.start))
+            .earliest()
+            .unwrap_or_else(|| now + chrono::Duration::hours(1));
+        (opens - now).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+/// What to do with a tick outside the run window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutsideWindow {
+    Skip,
+    Defer,
+}
+
+impl OutsideWindow {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "skip" => Some(OutsideWindow::Skip),
+            "defer" => Some(OutsideWindow::Defer),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_run_window() {
+        let day = RunWindow::parse("06:00-23:00").unwrap();
+        assert!(day.contains(at(6, 0)));
+        assert!(day.contains(at(22, 59)));
+        assert!(!day.contains(at(23, 0)));
+        assert!(!day.contains(at(3, 0)));
+
+        let night = RunWindow::parse(" 22:00 - 06:00 ").unwrap();
+        assert!(night.contains(at(23, 30)));
+        assert!(night.contains(at(5, 59)));
+        assert!(!night.contains(at(12, 0)));
+
+        assert!(RunWindow::parse("06:00").is_none());
+        assert!(RunWindow::parse("6am-11pm").is_none());
+        assert!(RunWindow::parse("06:00-06:00").is_none());
+
+        let now = Local::now();
+        assert_eq!(day.until_open(now).is_zero(), day.contains(now.time()));
+        assert!(day.until_open(now) <= Duration::from_secs(25 * 3600));
+    }
+}
@@ -2,9 +2,10 @@
 //! 
 //! Cross-domain commands that don't belong to a specific domain
 
+use schemars::JsonSchema;
 use serde::Serialize;
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct FeedItem {
     pub id: String,
@@ -15,7 +16,7 @@ pub struct FeedItem {
     pub created_at: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CalendarEvent {
     pub id: String,
@@ -26,7 +27,7 @@ pub struct CalendarEvent {
     pub location: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 pub struct ScheduledJobStub {
     pub id: u32,
     pub job_type: String,
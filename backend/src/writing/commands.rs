@@ -6,14 +6,17 @@ use super::components::ideas::{
     list_ideas_handler, get_idea_handler, create_idea_handler,
     create_idea_for_article_handler, update_idea_metadata_handler,
     update_idea_notes_handler, update_idea_article_handler, archive_idea_handler,
+    reorder_ideas_handler, merge_ideas_handler,
     list_idea_references_handler, add_reference_to_idea_handler,
     remove_reference_handler, update_reference_notes_handler,
     get_reference_reader_snapshot_handler, get_reader_snapshot_for_url_handler,
-    IdeaDto, CreateIdeaInput, CreateIdeaForArticleInput,
-    UpdateIdeaMetadataInput, UpdateIdeaNotesInput, UpdateIdeaArticleInput,
+    suggest_related_content_handler,
+    IdeaDto, IdeaMergeDto, CreateIdeaInput, CreateIdeaForArticleInput,
+    UpdateIdeaMetadataInput, UpdateIdeaNotesInput, UpdateIdeaArticleInput, ReorderIdeasInput,
     IdeaReferenceDto, AddReferenceInput, UpdateReferenceNotesInput,
-    ReaderSnapshotInput, ReferenceReaderSnapshotDto,
+    ReaderSnapshotInput, ReferenceReaderSnapshotDto, RelatedContentInput,
 };
+use crate::core::components::embeddings::SemanticSearchHit;
 
 /// List writing ideas with filtering and pagination
 /// 
@@ -21,21 +24,26 @@ use super::components::ideas::{
 /// - `status`: Filter by status ("draft", "in_progress", "completed", "archived")
 /// - `search`: Text search in title/summary
 /// - `include_removed`: Include archived ideas (default: false)
+/// - `due`: "overdue" or "this_week"
+/// - `sort_by`: "manual" for the board order set by `reorder_ideas`
 /// - `limit`: Max number of results (default: 50)
 /// - `offset`: Pagination offset (default: 0)
 /// 
 /// # Returns
-/// Paginated list of ideas sorted by last updated (newest first)
+/// Paginated list of ideas sorted by last updated (newest first) unless
+/// `sort_by` says otherwise
 #[tauri::command]
 pub async fn list_ideas(
     status: Option<String>,
     search: Option<String>,
     include_removed: Option<bool>,
+    due: Option<String>,
+    sort_by: Option<String>,
     limit: Option<u64>,
     offset: Option<u64>,
     state: State<'_, AppState>,
 ) -> Result<Vec<IdeaDto>, String> {
-    list_ideas_handler(status, search, include_removed, limit, offset, &state)
+    list_ideas_handler(status, search, include_removed, due, sort_by, limit, offset, &state)
         .await
         .map_err(|e| e.to_string())
 }
@@ -107,6 +115,30 @@ pub async fn archive_idea(id: i64, state: State<'_, AppState>) -> Result<IdeaDto
         .map_err(|e| e.to_string())
 }
 
+/// Reorder one status column of the ideas board, moving listed ideas of
+/// other statuses into it
+#[tauri::command]
+pub async fn reorder_ideas(
+    input: ReorderIdeasInput,
+    state: State<'_, AppState>,
+) -> Result<Vec<IdeaDto>, String> {
+    reorder_ideas_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Merge idea `source_id` into `target_id` and archive the source
+#[tauri::command]
+pub async fn merge_ideas(
+    source_id: i64,
+    target_id: i64,
+    state: State<'_, AppState>,
+) -> Result<IdeaMergeDto, String> {
+    merge_ideas_handler(source_id, target_id, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Open an article in a modal window with context menu for highlighting
 #[tauri::command]
 pub async fn open_article_modal(
@@ -202,6 +234,17 @@ pub async fn get_reader_snapshot_for_url(
         .map_err(|e| e.to_string())
 }
 
+/// Suggest unlinked articles and reader references similar to an idea
+#[tauri::command]
+pub async fn suggest_related_content(
+    input: RelatedContentInput,
+    state: State<'_, AppState>,
+) -> Result<Vec<SemanticSearchHit>, String> {
+    suggest_related_content_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Knowledge Graph Commands
 // ============================================================================
@@ -220,6 +263,12 @@ use super::components::knowledge_graph::{
     // Notes
     list_notes_for_entity, get_note, create_note, update_note, delete_note,
     CreateNoteInput, UpdateNoteInput, NoteDto,
+    // Publications
+    list_publications, record_publication, retry_publication,
+    RecordPublicationInput, WritingPublicationDto,
+    // Graph export
+    export_graph, GraphExportDto, GraphFormat,
+    export_bibliography, ExportBibliographyInput, BibliographyDto,
 };
 
 // Reference Items Commands
@@ -487,14 +536,77 @@ pub async fn kg_delete_note(
         .map_err(|e| e.to_string())
 }
 
+// Graph Export Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn kg_export_graph(
+    format: GraphFormat,
+    state: State<'_, AppState>,
+) -> Result<GraphExportDto, String> {
+    export_graph(&state.db, format)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn kg_export_bibliography(
+    input: ExportBibliographyInput,
+    state: State<'_, AppState>,
+) -> Result<BibliographyDto, String> {
+    export_bibliography(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Publication Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn kg_list_publications(
+    writing_id: Option<i64>,
+    platform: Option<String>,
+    status: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<WritingPublicationDto>, String> {
+    list_publications(&state.db, writing_id, platform, status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn kg_record_publication(
+    input: RecordPublicationInput,
+    state: State<'_, AppState>,
+) -> Result<WritingPublicationDto, String> {
+    record_publication(&state.db, input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn kg_retry_publication(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<WritingPublicationDto, String> {
+    retry_publication(&state, id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 // Writing System Commands (TipTap JSON Content + Draft Management)
 // ============================================================================
 
 use crate::writing::dto::{
     WritingDraftDto, CreateWritingDraftInput, SaveDraftInput, UpdateWritingDraftMetaInput,
     PublishWritingInput, LinkIdeaInput, ListWritingsQuery, GetWritingInput, ListLinkedIdeasInput,
+    ExportWritingsMarkdownInput, MarkdownExportDto, ExportEpubInput, EpubExportDto, ExportPdfInput,
+    ExportDocxInput, ImportObsidianInput, ObsidianImportDto, ImportNotionInput, NotionImportDto,
+    SyncFolderInput, FolderSyncDto,
 };
+use crate::writing::git::{commit_writing, WritingChange};
 use crate::writing::service;
+use crate::core::components::storage::ExportInfo;
 
 /// Helper to convert Writing entity to DTO
 fn writing_draft_to_dto(w: crate::writing::components::knowledge_graph::entities::writings::Model) -> WritingDraftDto {
@@ -576,6 +688,8 @@ pub async fn writing_list(
 ) -> Result<Vec<WritingDraftDto>, String> {
     use crate::writing::components::knowledge_graph::entities::writings::{WritingType, WritingStatus};
     
+    // Before the filters below move out of `input`
+    let (limit, offset) = input.limit_offset();
     let status = input.status.and_then(|s| match s.as_str() {
         "draft" => Some(WritingStatus::Draft),
         "in_progress" => Some(WritingStatus::InProgress),
@@ -599,6 +713,8 @@ pub async fn writing_list(
         input.series_name,
         input.is_pinned,
         input.is_featured,
+        limit,
+        Some(offset),
     )
     .await
     .map_err(|e| e.to_string())?;
@@ -659,6 +775,13 @@ pub async fn writing_save_draft(
     let w = service::save_draft(&state.db, input.writing_id, input.content_json)
         .await
         .map_err(|e| e.to_string())?;
+    commit_writing(
+        &state.db,
+        &state.config.current().storage,
+        &w,
+        WritingChange::SaveDraft,
+    )
+    .await;
     Ok(writing_draft_to_dto(w))
 }
 
@@ -671,6 +794,13 @@ pub async fn writing_publish(
     let w = service::publish_writing(&state.db, input.writing_id)
         .await
         .map_err(|e| e.to_string())?;
+    commit_writing(
+        &state.db,
+        &state.config.current().storage,
+        &w,
+        WritingChange::Publish,
+    )
+    .await;
     Ok(writing_draft_to_dto(w))
 }
 
@@ -707,3 +837,142 @@ pub async fn writing_list_linked_ideas(
         .map_err(|e| e.to_string())?;
     Ok(links.into_iter().map(|link| link.idea_id).collect())
 }
+
+/// Export writings as Markdown files with front matter, optionally zipped
+#[tauri::command]
+pub async fn export_writings_markdown(
+    input: ExportWritingsMarkdownInput,
+    state: State<'_, AppState>,
+) -> Result<MarkdownExportDto, String> {
+    crate::writing::export::export_writings_markdown(
+        &state.db,
+        &state.config.current().storage,
+        &input,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Assemble a book and its chapters into an EPUB
+#[tauri::command]
+pub async fn writing_export_epub(
+    input: ExportEpubInput,
+    state: State<'_, AppState>,
+) -> Result<EpubExportDto, String> {
+    crate::writing::export::export_writing_epub(
+        &state.db,
+        &state.http_clients.reader,
+        &state.config.current().storage,
+        &input,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Render a writing, or a news digest, to a PDF
+#[tauri::command]
+pub async fn writing_export_pdf(
+    input: ExportPdfInput,
+    state: State<'_, AppState>,
+) -> Result<ExportInfo, String> {
+    crate::writing::export::export_writing_pdf(&state.db, &state.config.current().storage, &input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Export a writing as a Word document
+#[tauri::command]
+pub async fn writing_export_docx(
+    input: ExportDocxInput,
+    state: State<'_, AppState>,
+) -> Result<ExportInfo, String> {
+    crate::writing::export::export_writing_docx(&state.db, &state.config.current().storage, &input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Import the notes of an Obsidian vault as writings and ideas
+#[tauri::command]
+pub async fn writing_import_obsidian(
+    input: ImportObsidianInput,
+    state: State<'_, AppState>,
+) -> Result<ObsidianImportDto, String> {
+    let vault_path = input
+        .vault_path
+        .ok_or_else(|| "vault_path is required".to_string())?;
+    crate::writing::import::import_obsidian_vault(
+        &state.db,
+        std::path::Path::new(&vault_path),
+        &input.notes_folders,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Import a Notion export as writings, references and notes
+#[tauri::command]
+pub async fn writing_import_notion(
+    input: ImportNotionInput,
+    state: State<'_, AppState>,
+) -> Result<NotionImportDto, String> {
+    let import_path = input
+        .import_path
+        .ok_or_else(|| "import_path is required".to_string())?;
+    crate::writing::import::import_notion_export(&state.db, std::path::Path::new(&import_path))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sync writings with the Markdown folder in WRITING_SYNC_DIR
+#[tauri::command]
+pub async fn writing_sync_folder(
+    input: SyncFolderInput,
+    state: State<'_, AppState>,
+) -> Result<FolderSyncDto, String> {
+    crate::writing::sync::sync_writings_folder(&state.db, &state.config.current().storage, &input)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Newsletter Commands
+// ============================================================================
+
+use super::components::newsletter::{
+    list_newsletter_sends_handler, send_news_digest_handler, send_writing_newsletter_handler,
+    ListNewsletterSendsInput, NewsletterSendDto, NewsletterSendResult, SendNewsDigestInput,
+    SendWritingNewsletterInput,
+};
+
+/// Email a writing to the recipient list over SMTP
+#[tauri::command]
+pub async fn newsletter_send_writing(
+    input: SendWritingNewsletterInput,
+    state: State<'_, AppState>,
+) -> Result<NewsletterSendResult, String> {
+    send_writing_newsletter_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Email a digest of recent news articles
+#[tauri::command]
+pub async fn newsletter_send_digest(
+    input: SendNewsDigestInput,
+    state: State<'_, AppState>,
+) -> Result<NewsletterSendResult, String> {
+    send_news_digest_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// List newsletter send log entries
+#[tauri::command]
+pub async fn newsletter_list_sends(
+    input: ListNewsletterSendsInput,
+    state: State<'_, AppState>,
+) -> Result<Vec<NewsletterSendDto>, String> {
+    list_newsletter_sends_handler(input, &state)
+        .await
+        .map_err(|e| e.to_string())
+}
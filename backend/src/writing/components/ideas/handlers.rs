@@ -7,30 +7,28 @@ use super::types::*;
 use crate::core::components::errors::{AppError, AppResult};
 use crate::research::components::feed::entities::articles as news_articles;
 use crate::AppState;
-use chrono::Utc;
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc};
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, EntityTrait, IntoActiveModel,
-    QueryFilter, QueryOrder, QuerySelect, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select, Set,
+    TransactionTrait,
 };
 use tracing::{info, instrument};
 
-/// List writing ideas with filtering, search, and pagination
-///
-/// Supports filtering by status, text search, and archived status.
-/// Returns ideas sorted by last updated (newest first).
-#[instrument(skip(state), fields(limit = ?limit, offset = ?offset))]
-pub async fn list_ideas_handler(
-    status: Option<String>,
-    search: Option<String>,
+/// Values of the `due` list filter
+const DUE_FILTERS: [&str; 2] = ["overdue", "this_week"];
+
+/// Ideas matching the list filters, before sorting and pagination
+fn filtered_ideas(
+    status: Option<&str>,
+    search: Option<&str>,
     include_removed: Option<bool>,
-    limit: Option<u64>,
-    offset: Option<u64>,
-    state: &AppState,
-) -> AppResult<Vec<IdeaDto>> {
+    due: Option<&str>,
+) -> AppResult<Select<Entity>> {
     let mut query = Entity::find();
 
     if let Some(status) = status {
-        let status = validate_status(&status)?;
+        let status = validate_status(status)?;
         query = query.filter(Column::Status.eq(status));
     }
 
@@ -48,28 +46,99 @@ pub async fn list_ideas_handler(
         );
     }
 
-    // Optimize by excluding heavy markdown columns in list view
-    // Only fetch them when viewing individual ideas
-    let results = query
-        .select_only()
-        .columns([
-            Column::Id,
-            Column::Title,
-            Column::Summary,
-            Column::Status,
-            Column::NewsArticleId,
-            Column::Target,
-            Column::Tags,
-            Column::Priority,
-            Column::IsPinned,
-            Column::DateAdded,
-            Column::DateUpdated,
-            Column::DateCompleted,
-            Column::DateRemoved,
-        ])
-        // Exclude: NotesMarkdown, ArticleTitle, ArticleMarkdown
+    // Complete ideas are neither overdue nor due
+    if let Some(due) = due {
+        let now = Utc::now();
+        let open = Column::Status.ne(IdeaStatus::Complete);
+        query = match due {
+            "overdue" => query.filter(open).filter(Column::DueAt.lt(now)),
+            "this_week" => query
+                .filter(open)
+                .filter(Column::DueAt.gte(now))
+                .filter(Column::DueAt.lt(week_end(now))),
+            _ => {
+                return Err(AppError::Validation {
+                    field: "due".to_string(),
+                    reason: format!("Expected one of: {}", DUE_FILTERS.join(", ")),
+                    invalid_value: Some(due.to_string()),
+                })
+            }
+        };
+    }
+
+    Ok(query)
+}
+
+/// Start of the next week (Monday 00:00 UTC)
+fn week_end(now: DateTime<Utc>) -> DateTime<Utc> {
+    let days_left = 7 - i64::from(now.weekday().num_days_from_monday());
+    (now.date_naive() + Duration::days(days_left))
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+}
+
+/// Columns of the list view; the heavy markdown columns (NotesMarkdown,
+/// ArticleTitle, ArticleMarkdown) are only fetched when viewing an
+/// individual idea
+const LIST_COLUMNS: [Column; 16] = [
+    Column::Id,
+    Column::Title,
+    Column::Summary,
+    Column::Status,
+    Column::NewsArticleId,
+    Column::Target,
+    Column::Tags,
+    Column::Priority,
+    Column::IsPinned,
+    Column::SortOrder,
+    Column::DueAt,
+    Column::RemindAt,
+    Column::DateAdded,
+    Column::DateUpdated,
+    Column::DateCompleted,
+    Column::DateRemoved,
+];
+
+/// Board order: manual position first, last updated among equal positions
+fn in_board_order(query: Select<Entity>) -> Select<Entity> {
+    query
+        .order_by_asc(Column::SortOrder)
         .order_by_desc(Column::DateUpdated)
         .order_by_desc(Column::DateAdded)
+}
+
+/// List writing ideas with filtering, search, and pagination
+///
+/// Supports filtering by status, text search, archived status, and due date
+/// ("overdue" or "this_week"). Returns ideas sorted by last updated (newest first), or in the order set
+/// with `reorder_ideas_handler` when `sort_by` is "manual".
+#[instrument(skip(state), fields(limit = ?limit, offset = ?offset))]
+pub async fn list_ideas_handler(
+    status: Option<String>,
+    search: Option<String>,
+    include_removed: Option<bool>,
+    due: Option<String>,
+    sort_by: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    state: &AppState,
+) -> AppResult<Vec<IdeaDto>> {
+    let filtered = filtered_ideas(
+        status.as_deref(),
+        search.as_deref(),
+        include_removed,
+        due.as_deref(),
+    )?;
+    let query = filtered.select_only().columns(LIST_COLUMNS);
+
+    let query = match sort_by.as_deref() {
+        Some("manual") => in_board_order(query),
+        _ => query
+            .order_by_desc(Column::DateUpdated)
+            .order_by_desc(Column::DateAdded),
+    };
+
+    let results = query
         .limit(limit.unwrap_or(50))
         .offset(offset.unwrap_or(0))
         .into_model::<Model>()
@@ -79,6 +148,20 @@ pub async fn list_ideas_handler(
     Ok(results.into_iter().map(idea_to_dto).collect())
 }
 
+/// Number of ideas matching the `list_ideas_handler` filters
+pub async fn count_ideas_handler(
+    status: Option<&str>,
+    search: Option<&str>,
+    include_removed: Option<bool>,
+    due: Option<&str>,
+    state: &AppState,
+) -> AppResult<u64> {
+    let total = filtered_ideas(status, search, include_removed, due)?
+        .count(&state.db)
+        .await?;
+    Ok(total)
+}
+
 /// Get a single idea by ID with full content
 pub async fn get_idea_handler(id: i64, state: &AppState) -> AppResult<IdeaDto> {
     let model = Entity::find_by_id(id)
@@ -107,6 +190,15 @@ where
     let now = Utc::now();
     let status = status_or_default(&input.status)?;
     let is_complete = status == IdeaStatus::Complete;
+    let sort_order = top_sort_order(db, &status).await?;
+    let due_at = match input.due_at.as_deref() {
+        Some(due_at) => parse_idea_time("dueAt", due_at)?,
+        None => None,
+    };
+    let remind_at = match input.remind_at.as_deref() {
+        Some(remind_at) => parse_idea_time("remindAt", remind_at)?,
+        None => None,
+    };
 
     let model = ActiveModel {
         title: Set(input.title),
@@ -124,6 +216,9 @@ where
         date_removed: Set(None),
         priority: Set(input.priority.unwrap_or(0)),
         is_pinned: Set(bool_to_int(input.is_pinned)),
+        sort_order: Set(sort_order),
+        due_at: Set(due_at),
+        remind_at: Set(remind_at),
         ..Default::default()
     };
 
@@ -166,6 +261,8 @@ pub async fn create_idea_for_article_handler(
             article_markdown: article.content.clone(),
             priority: Some(0),
             is_pinned: Some(article.is_pinned != 0),
+            due_at: None,
+            remind_at: None,
         },
         &txn,
     )
@@ -197,14 +294,15 @@ pub async fn update_idea_metadata_handler(
 ) -> AppResult<IdeaDto> {
     tracing::info!("Updating idea metadata");
 
-    let mut model: ActiveModel = Entity::find_by_id(id)
+    let existing = Entity::find_by_id(id)
         .one(&state.db)
         .await?
         .ok_or_else(|| {
             tracing::error!("Idea not found for metadata update");
             AppError::other(format!("Idea not found: {id}"))
-        })?
-        .into();
+        })?;
+    let old_status = existing.status.clone();
+    let mut model: ActiveModel = existing.into();
 
     if let Some(ref title) = input.title {
         tracing::info!(new_title = %title, "Updating idea title");
@@ -217,6 +315,10 @@ pub async fn update_idea_metadata_handler(
         let status = validate_status(status)?;
         let is_complete = matches!(status, IdeaStatus::Complete);
         tracing::info!(new_status = ?status, is_complete = %is_complete, "Updating idea status");
+        if status != old_status {
+            // Moved to another column: it goes on top there
+            model.sort_order = Set(top_sort_order(&state.db, &status).await?);
+        }
         model.status = Set(status);
         model.date_completed = Set(if is_complete { Some(Utc::now()) } else { None });
     }
@@ -234,6 +336,12 @@ pub async fn update_idea_metadata_handler(
     if let Some(is_pinned) = input.is_pinned {
         model.is_pinned = Set(bool_to_int(Some(is_pinned)));
     }
+    if let Some(ref due_at) = input.due_at {
+        model.due_at = Set(parse_idea_time("dueAt", due_at)?);
+    }
+    if let Some(ref remind_at) = input.remind_at {
+        model.remind_at = Set(parse_idea_time("remindAt", remind_at)?);
+    }
 
     model.date_updated = Set(Utc::now());
 
@@ -242,6 +350,110 @@ pub async fn update_idea_metadata_handler(
     Ok(idea_to_dto(updated))
 }
 
+/// Position that puts an idea on top of the `status` column of the board
+async fn top_sort_order<C>(db: &C, status: &IdeaStatus) -> AppResult<i32>
+where
+    C: ConnectionTrait,
+{
+    let top: Option<Option<i32>> = Entity::find()
+        .select_only()
+        .column_as(Column::SortOrder.min(), "top")
+        .filter(Column::Status.eq(status.clone()))
+        .filter(Column::DateRemoved.is_null())
+        .into_tuple()
+        .one(db)
+        .await?;
+    Ok(top.flatten().map_or(0, |top| top - 1))
+}
+
+/// Ideas of one status column of the board, in board order
+async fn board_column<C>(db: &C, status: &IdeaStatus) -> AppResult<Vec<Model>>
+where
+    C: ConnectionTrait,
+{
+    let query = Entity::find()
+        .select_only()
+        .columns(LIST_COLUMNS)
+        .filter(Column::Status.eq(status.clone()))
+        .filter(Column::DateRemoved.is_null());
+    Ok(in_board_order(query).into_model::<Model>().all(db).await?)
+}
+
+/// Reorder one status column of the ideas board
+///
+/// Ideas listed with another status are moved into the column, as when a
+/// card is dragged across columns. Positions are rewritten 0-based for the
+/// whole column, touching only ideas that moved. Returns the column in its
+/// new order.
+#[tracing::instrument(skip(state, input), fields(status = %input.status, count = input.ids.len()))]
+pub async fn reorder_ideas_handler(
+    input: ReorderIdeasInput,
+    state: &AppState,
+) -> AppResult<Vec<IdeaDto>> {
+    reorder_ideas_with_conn(input, &state.db).await
+}
+
+async fn reorder_ideas_with_conn(
+    input: ReorderIdeasInput,
+    db: &DatabaseConnection,
+) -> AppResult<Vec<IdeaDto>> {
+    let status = validate_status(&input.status)?;
+    let txn = db.begin().await?;
+    let mut remaining = board_column(&txn, &status).await?;
+
+    let mut ordered = Vec::with_capacity(remaining.len());
+    for id in input.ids {
+        if let Some(index) = remaining.iter().position(|idea| idea.id == id) {
+            ordered.push(remaining.remove(index));
+            continue;
+        }
+        let moved = Entity::find_by_id(id)
+            .select_only()
+            .columns(LIST_COLUMNS)
+            .filter(Column::DateRemoved.is_null())
+            .filter(Column::Status.ne(status.clone()))
+            .into_model::<Model>()
+            .one(&txn)
+            .await?
+            .filter(|_| !ordered.iter().any(|idea| idea.id == id));
+        // Unknown, archived, or listed twice
+        let Some(moved) = moved else {
+            return Err(AppError::Validation {
+                field: "ids".to_string(),
+                reason: "Idea not found".to_string(),
+                invalid_value: Some(id.to_string()),
+            });
+        };
+        ordered.push(moved);
+    }
+    ordered.extend(remaining);
+
+    let now = Utc::now();
+    for (index, idea) in ordered.into_iter().enumerate() {
+        let position = index as i32;
+        let moved = idea.status != status;
+        if idea.sort_order == position && !moved {
+            continue;
+        }
+        // Only the fields set here are written, so the markdown columns left
+        // out of the list view are untouched
+        let mut active = idea.into_active_model();
+        active.sort_order = Set(position);
+        if moved {
+            let is_complete = status == IdeaStatus::Complete;
+            active.status = Set(status.clone());
+            active.date_completed = Set(if is_complete { Some(now) } else { None });
+            active.date_updated = Set(now);
+        }
+        active.update(&txn).await?;
+    }
+    txn.commit().await?;
+
+    let column = board_column(db, &status).await?;
+    info!(count = column.len(), "Ideas reordered");
+    Ok(column.into_iter().map(idea_to_dto).collect())
+}
+
 /// Update idea notes markdown content
 #[tracing::instrument(skip(state, input), fields(
     idea_id = %id,
@@ -326,3 +538,98 @@ pub async fn archive_idea_handler(id: i64, state: &AppState) -> AppResult<IdeaDt
     tracing::info!(archived_at = %now, is_archived = true, "Idea archived successfully");
     Ok(idea_to_dto(updated))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use serde_json::json;
+
+    async fn create(db: &DatabaseConnection, title: &str, status: &str) -> i64 {
+        let input = serde_json::from_value(json!({ "title": title, "status": status })).unwrap();
+        create_idea_with_conn(input, db).await.unwrap().id
+    }
+
+    async fn reorder(db: &DatabaseConnection, status: &str, ids: &[i64]) -> AppResult<Vec<i64>> {
+        let input = ReorderIdeasInput {
+            status: status.to_string(),
+            ids: ids.to_vec(),
+        };
+        let column = reorder_ideas_with_conn(input, db).await?;
+        Ok(column.iter().map(|idea| idea.id).collect())
+    }
+
+    async fn column(db: &DatabaseConnection, status: &str) -> Vec<(i64, i32)> {
+        let status = validate_status(status).unwrap();
+        board_column(db, &status)
+            .await
+            .unwrap()
+            .iter()
+            .map(|idea| (idea.id, idea.sort_order))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_reorder_ideas() {
+        let db = test_db().await;
+        let a = create(&db, "A", "in_progress").await;
+        let b = create(&db, "B", "in_progress").await;
+        let c = create(&db, "C", "in_progress").await;
+        let stalled = create(&db, "Stalled", "stalled").await;
+        // New ideas go on top of their column
+        assert_eq!(column(&db, "in_progress").await, [(c, -2), (b, -1), (a, 0)]);
+
+        // Listed ideas first, pulling one in from another column; the
+        // unlisted ones follow in their previous order
+        assert_eq!(
+            reorder(&db, "in_progress", &[a, stalled]).await.unwrap(),
+            [a, stalled, c, b]
+        );
+        assert_eq!(
+            column(&db, "in_progress").await,
+            [(a, 0), (stalled, 1), (c, 2), (b, 3)]
+        );
+        assert!(column(&db, "stalled").await.is_empty());
+        let moved = Entity::find_by_id(stalled).one(&db).await.unwrap().unwrap();
+        assert_eq!(moved.status, IdeaStatus::InProgress);
+
+        // An idea moved by status change or created lands on top
+        let status = IdeaStatus::InProgress;
+        assert_eq!(top_sort_order(&db, &status).await.unwrap(), -1);
+        let d = create(&db, "D", "in_progress").await;
+        assert_eq!(column(&db, "in_progress").await[0], (d, -1));
+    }
+
+    #[tokio::test]
+    async fn test_reorder_ideas_rejects_bad_ids() {
+        let db = test_db().await;
+        let a = create(&db, "A", "in_progress").await;
+        let archived = create(&db, "Archived", "stalled").await;
+        let mut model: ActiveModel = Entity::find_by_id(archived)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        model.date_removed = Set(Some(Utc::now()));
+        model.update(&db).await.unwrap();
+
+        for ids in [vec![a, 999], vec![a, archived], vec![a, a]] {
+            let err = reorder(&db, "in_progress", &ids).await.unwrap_err();
+            assert!(matches!(err, AppError::Validation { .. }), "{:?}: {}", ids, err);
+        }
+        // Nothing changed
+        assert_eq!(column(&db, "in_progress").await, [(a, 0)]);
+        let archived = Entity::find_by_id(archived).one(&db).await.unwrap().unwrap();
+        assert_eq!(archived.status, IdeaStatus::Stalled);
+    }
+
+    #[test]
+    fn test_week_end() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let monday = at("2026-10-19T00:00:00Z");
+        assert_eq!(week_end(at("2026-10-16T10:00:00Z")), monday);
+        assert_eq!(week_end(at("2026-10-18T23:59:59Z")), monday);
+        assert_eq!(week_end(monday), at("2026-10-26T00:00:00Z"));
+    }
+}
@@ -0,0 +1,247 @@
+//! Merging duplicate ideas
+//!
+//! Ideas created from similar articles pile up as near-duplicates. Merging
+//! folds one idea into another: its references, notes and writing links move
+//! to the target, and the source is archived. Links the target already has
+//! stay with the archived source rather than being doubled.
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, QuerySelect, Set,
+    TransactionTrait,
+};
+use tracing::{info, instrument};
+
+use super::entities::idea_references::{
+    self, ActiveModel as ActiveReference, Column as ReferenceColumn, Entity as References,
+};
+use super::types::*;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::notes::components::notes::merge_notes;
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, note_links, notes, writing_idea_links,
+};
+use crate::AppState;
+
+/// Merge idea `source_id` into idea `target_id`
+///
+/// Attached and knowledge-graph references, notes and writing links move to
+/// the target; notes of a type the target already has are appended to its
+/// note after a divider, as are the source's notes markdown. Wiki links to
+/// the source lead to the target from then on. The source is archived.
+#[instrument(skip(state))]
+pub async fn merge_ideas_handler(
+    source_id: i64,
+    target_id: i64,
+    state: &AppState,
+) -> AppResult<IdeaMergeDto> {
+    if source_id == target_id {
+        return Err(AppError::validation(
+            "target_id",
+            "An idea can't be merged into itself",
+        ));
+    }
+
+    let txn = state.db.begin().await?;
+    let source = find_open_idea(&txn, source_id, "source_id").await?;
+    let target = find_open_idea(&txn, target_id, "target_id").await?;
+    let mut skipped = 0;
+
+    // References attached to the idea; the same article or URL counts once
+    let kept: Vec<idea_references::Model> = References::find()
+        .filter(ReferenceColumn::IdeaId.eq(target_id))
+        .all(&txn)
+        .await?;
+    let mut references_moved = 0;
+    for reference in References::find()
+        .filter(ReferenceColumn::IdeaId.eq(source_id))
+        .all(&txn)
+        .await?
+    {
+        if kept.iter().any(|k| same_reference(k, &reference)) {
+            skipped += 1;
+            continue;
+        }
+        let mut active: ActiveReference = reference.into();
+        active.idea_id = Set(target_id);
+        active.update(&txn).await?;
+        references_moved += 1;
+    }
+
+    // Knowledge graph references
+    let linked: HashSet<i64> = idea_reference_links::Entity::find()
+        .select_only()
+        .column(idea_reference_links::Column::ReferenceId)
+        .filter(idea_reference_links::Column::IdeaId.eq(target_id))
+        .into_tuple()
+        .all(&txn)
+        .await?
+        .into_iter()
+        .collect();
+    for link in idea_reference_links::Entity::find()
+        .filter(idea_reference_links::Column::IdeaId.eq(source_id))
+        .all(&txn)
+        .await?
+    {
+        if linked.contains(&link.reference_id) {
+            skipped += 1;
+            continue;
+        }
+        let mut active: idea_reference_links::ActiveModel = link.into();
+        active.idea_id = Set(target_id);
+        active.update(&txn).await?;
+        references_moved += 1;
+    }
+
+    let writings: HashSet<i64> = writing_idea_links::Entity::find()
+        .select_only()
+        .column(writing_idea_links::Column::WritingId)
+        .filter(writing_idea_links::Column::IdeaId.eq(target_id))
+        .into_tuple()
+        .all(&txn)
+        .await?
+        .into_iter()
+        .collect();
+    let mut writings_moved = 0;
+    for link in writing_idea_links::Entity::find()
+        .filter(writing_idea_links::Column::IdeaId.eq(source_id))
+        .all(&txn)
+        .await?
+    {
+        if writings.contains(&link.writing_id) {
+            skipped += 1;
+            continue;
+        }
+        let mut active: writing_idea_links::ActiveModel = link.into();
+        active.idea_id = Set(target_id);
+        active.update(&txn).await?;
+        writings_moved += 1;
+    }
+
+    let (notes_moved, notes_appended) = merge_notes(&txn, "idea", source_id, target_id).await?;
+    retarget_wiki_links(&txn, source_id, target_id).await?;
+
+    let now = Utc::now();
+    let notes_markdown = match (&target.notes_markdown, &source.notes_markdown) {
+        (Some(kept), Some(added)) if !kept.trim().is_empty() && !added.trim().is_empty() => {
+            Some(format!("{}\n\n---\n\n{}", kept.trim_end(), added))
+        }
+        (Some(kept), _) if !kept.trim().is_empty() => Some(kept.clone()),
+        (_, added) => added.clone(),
+    };
+    let mut merged: ActiveModel = target.into();
+    merged.notes_markdown = Set(notes_markdown);
+    merged.date_updated = Set(now);
+    let merged = merged.update(&txn).await?;
+
+    let mut archived: ActiveModel = source.into();
+    archived.date_removed = Set(Some(now));
+    archived.date_updated = Set(now);
+    archived.update(&txn).await?;
+    txn.commit().await?;
+
+    info!(
+        references_moved,
+        writings_moved, notes_moved, "Ideas merged"
+    );
+    Ok(IdeaMergeDto {
+        idea: idea_to_dto(merged),
+        references_moved,
+        notes_moved,
+        notes_appended,
+        writings_moved,
+        skipped,
+    })
+}
+
+/// An idea that exists and isn't archived
+async fn find_open_idea<C: ConnectionTrait>(db: &C, id: i64, field: &str) -> AppResult<Model> {
+    let idea = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Idea not found: {id}")))?;
+    if idea.date_removed.is_some() {
+        return Err(AppError::validation(
+            field,
+            format!("Idea {id} is archived"),
+        ));
+    }
+    Ok(idea)
+}
+
+/// Both point at the same article or URL
+fn same_reference(a: &idea_references::Model, b: &idea_references::Model) -> bool {
+    (a.news_article_id.is_some() && a.news_article_id == b.news_article_id)
+        || (a.url.is_some() && a.url == b.url)
+}
+
+/// Point wiki links to the source at the target, dropping those from the
+/// target's own notes, which would link the target to itself
+async fn retarget_wiki_links<C: ConnectionTrait>(
+    db: &C,
+    source_id: i64,
+    target_id: i64,
+) -> AppResult<()> {
+    let own_notes: Vec<i64> = notes::Entity::find()
+        .select_only()
+        .column(notes::Column::Id)
+        .filter(notes::Column::EntityType.eq(notes::EntityType::Idea))
+        .filter(notes::Column::EntityId.eq(target_id))
+        .into_tuple()
+        .all(db)
+        .await?;
+    note_links::Entity::delete_many()
+        .filter(note_links::Column::TargetType.eq("idea"))
+        .filter(note_links::Column::TargetId.eq(source_id))
+        .filter(note_links::Column::NoteId.is_in(own_notes))
+        .exec(db)
+        .await?;
+    note_links::Entity::update_many()
+        .col_expr(note_links::Column::TargetId, Expr::value(target_id))
+        .filter(note_links::Column::TargetType.eq("idea"))
+        .filter(note_links::Column::TargetId.eq(source_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_reference() {
+        let reference = |news_article_id, url: Option<&str>| idea_references::Model {
+            id: 1,
+            idea_id: 1,
+            reference_type: "url".to_string(),
+            news_article_id,
+            title: None,
+            url: url.map(str::to_string),
+            description: None,
+            notes_markdown: None,
+            source_id: None,
+            added_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+        };
+        assert!(same_reference(
+            &reference(Some(4), None),
+            &reference(Some(4), Some("https://a.example"))
+        ));
+        assert!(same_reference(
+            &reference(None, Some("https://a.example")),
+            &reference(Some(5), Some("https://a.example"))
+        ));
+        assert!(!same_reference(
+            &reference(None, None),
+            &reference(None, None)
+        ));
+        assert!(!same_reference(
+            &reference(Some(4), None),
+            &reference(Some(5), None)
+        ));
+    }
+}
@@ -3,26 +3,32 @@
 //! Refactored from monolithic ideas.rs into:
 //! - types: Database models, DTOs, enums, utility functions
 //! - handlers: CRUD operations for ideas
+//! - related: Similar unlinked articles/references (embeddings)
+//! - merge: Folding a duplicate idea into another
+//! - reminders: Scheduled reminders about ideas
 
 pub mod types;
 pub mod handlers;
 pub mod references;
 pub mod reader;
+pub mod related;
+pub mod merge;
+pub mod reminders;
 pub mod entities;
 
 // Re-export DTOs for API responses
 pub use types::{
-    CreateIdeaForArticleInput, CreateIdeaInput, IdeaDto, UpdateIdeaArticleInput,
-    UpdateIdeaMetadataInput, UpdateIdeaNotesInput,
+    CreateIdeaForArticleInput, CreateIdeaInput, IdeaDto, IdeaMergeDto, ReorderIdeasInput,
+    UpdateIdeaArticleInput, UpdateIdeaMetadataInput, UpdateIdeaNotesInput,
     IdeaReferenceDto, AddReferenceInput, UpdateReferenceNotesInput,
-    ReferenceReaderSnapshotDto, ReaderSnapshotInput,
+    ReferenceReaderSnapshotDto, ReaderSnapshotInput, RelatedContentInput,
 };
 
 // Re-export handlers for Tauri commands
 pub use handlers::{
-    archive_idea_handler, create_idea_for_article_handler, create_idea_handler, get_idea_handler,
-    list_ideas_handler, update_idea_article_handler, update_idea_metadata_handler,
-    update_idea_notes_handler,
+    archive_idea_handler, count_ideas_handler, create_idea_for_article_handler,
+    create_idea_handler, get_idea_handler, list_ideas_handler, reorder_ideas_handler,
+    update_idea_article_handler, update_idea_metadata_handler, update_idea_notes_handler,
 };
 
 // Re-export reference handlers
@@ -34,3 +40,9 @@ pub use references::{
 pub use reader::{
     get_reference_reader_snapshot_handler, get_reader_snapshot_for_url_handler,
 };
+
+pub use merge::merge_ideas_handler;
+
+pub use reminders::run_idea_reminders_task;
+
+pub use related::{idea_ids_linking_url, suggest_related_content_handler};
@@ -1,7 +1,9 @@
 //! Reader snapshot helpers for idea references
 
 use crate::core::components::errors::{AppError, AppResult};
-use crate::core::components::reader::{extract_reader_content, html_to_text, sanitize_html};
+use crate::core::components::reader::{
+    extract_reader_content, html_to_text, normalize_reader_url, sanitize_html,
+};
 use crate::AppState;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
 use std::collections::HashSet;
@@ -10,6 +12,7 @@ use super::entities::idea_references::Column as ReferenceColumn;
 use super::entities::idea_references::Entity as References;
 use super::types::{ReaderSnapshotInput, ReferenceReaderSnapshotDto};
 use crate::research::components::feed::entities::articles;
+use crate::research::components::reader_site_rules::extraction_rule_for_url;
 
 struct SnapshotParts {
     title: String,
@@ -96,7 +99,17 @@ async fn snapshot_from_url(
     url: &str,
     title_override: Option<String>,
 ) -> AppResult<SnapshotParts> {
-    let extracted = extract_reader_content(&state.http_client, url, title_override).await?;
+    let rule = match normalize_reader_url(url) {
+        Ok(normalized) => extraction_rule_for_url(&state.db, &normalized).await?,
+        Err(_) => None,
+    };
+    let extracted = extract_reader_content(
+        &state.http_clients.reader,
+        url,
+        title_override,
+        rule.as_ref(),
+    )
+    .await?;
     Ok(SnapshotParts {
         title: extracted.title,
         url: extracted.final_url,
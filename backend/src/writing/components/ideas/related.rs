@@ -0,0 +1,184 @@
+//! Related-content suggestions for ideas
+//!
+//! Embeds an idea's title, summary, and notes and ranks news articles and
+//! reader references that aren't linked to it yet by cosine similarity.
+
+use std::collections::{BTreeSet, HashSet};
+
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use tracing::{info, instrument};
+
+use crate::core::components::embeddings::index::compose_text;
+use crate::core::components::embeddings::provider::provider_from_config;
+use crate::core::components::embeddings::search::{hydrate_hits, rank_by_vector};
+use crate::core::components::embeddings::types::{ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE};
+use crate::core::components::embeddings::SemanticSearchHit;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::reader::normalize_reader_url;
+use crate::research::entities::reader_references;
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, notes, reference_items, EntityType,
+};
+use crate::AppState;
+
+use super::entities::idea_references;
+use super::types::{Entity as Ideas, RelatedContentInput};
+
+/// Suggest unlinked articles and reader references for an idea
+#[instrument(skip(state, input), fields(idea_id = input.idea_id))]
+pub async fn suggest_related_content_handler(
+    input: RelatedContentInput,
+    state: &AppState,
+) -> AppResult<Vec<SemanticSearchHit>> {
+    let idea = Ideas::find_by_id(input.idea_id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Idea {} not found", input.idea_id)))?;
+
+    // Knowledge-graph notes attached to the idea add context beyond notes_markdown
+    let note_bodies: Vec<String> = notes::Entity::find()
+        .filter(notes::Column::EntityType.eq(EntityType::Idea))
+        .filter(notes::Column::EntityId.eq(idea.id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|n| html2md::parse_html(&n.body_html))
+        .collect();
+    let note_text = note_bodies.join("\n\n");
+
+    let text = compose_text(&[
+        Some(idea.title.as_str()),
+        idea.summary.as_deref(),
+        idea.notes_markdown.as_deref(),
+        Some(note_text.as_str()),
+    ]);
+
+    let provider =
+        provider_from_config(&state.config.current().embeddings, &state.http_clients.api)?;
+    let vector = provider.embed(&[text]).await?.pop().unwrap_or_default();
+
+    let linked = linked_entities(state, &idea).await?;
+    let exclude: Vec<(&str, i64)> = linked.iter().map(|(t, id)| (*t, *id)).collect();
+
+    let mut ranked = rank_by_vector(
+        &state.db,
+        provider.model(),
+        &vector,
+        &[ENTITY_NEWS_ARTICLE, ENTITY_READER_REFERENCE],
+        &exclude,
+        input.limit.unwrap_or(10),
+    )
+    .await?;
+    if let Some(min_score) = input.min_score {
+        ranked.retain(|(_, _, score)| *score >= min_score);
+    }
+
+    info!(
+        linked = exclude.len(),
+        suggestions = ranked.len(),
+        "Ranked related content for idea"
+    );
+    hydrate_hits(&state.db, ranked).await
+}
+
+/// Articles and reader references already attached to the idea
+///
+/// Articles are matched by id; reader references have no id link to ideas,
+/// so they are matched by normalized URL against the idea's references.
+async fn linked_entities(
+    state: &AppState,
+    idea: &super::types::Model,
+) -> AppResult<HashSet<(&'static str, i64)>> {
+    let mut linked = HashSet::new();
+    let mut urls = HashSet::new();
+
+    if let Some(article_id) = idea.news_article_id {
+        linked.insert((ENTITY_NEWS_ARTICLE, article_id));
+    }
+
+    for r in idea_references::Entity::find()
+        .filter(idea_references::Column::IdeaId.eq(idea.id))
+        .all(&state.db)
+        .await?
+    {
+        if let Some(article_id) = r.news_article_id {
+            linked.insert((ENTITY_NEWS_ARTICLE, article_id));
+        }
+        urls.extend(r.url.and_then(|u| normalize_reader_url(&u).ok()));
+    }
+
+    let reference_ids: Vec<i64> = idea_reference_links::Entity::find()
+        .filter(idea_reference_links::Column::IdeaId.eq(idea.id))
+        .all(&state.db)
+        .await?
+        .into_iter()
+        .map(|l| l.reference_id)
+        .collect();
+    if !reference_ids.is_empty() {
+        for r in reference_items::Entity::find()
+            .filter(reference_items::Column::Id.is_in(reference_ids))
+            .all(&state.db)
+            .await?
+        {
+            if let Some(article_id) = r.news_article_id {
+                linked.insert((ENTITY_NEWS_ARTICLE, article_id));
+            }
+            urls.extend(r.url.and_then(|u| normalize_reader_url(&u).ok()));
+        }
+    }
+
+    if !urls.is_empty() {
+        for r in reader_references::Entity::find().all(&state.db).await? {
+            if normalize_reader_url(&r.url).is_ok_and(|u| urls.contains(&u)) {
+                linked.insert((ENTITY_READER_REFERENCE, r.id));
+            }
+        }
+    }
+
+    Ok(linked)
+}
+
+/// Ideas that link a page, by normalized URL
+///
+/// The inverse of the URL matching in `linked_entities`: covers the idea's
+/// own references and knowledge-graph reference items.
+pub async fn idea_ids_linking_url(db: &DatabaseConnection, url: &str) -> AppResult<Vec<i64>> {
+    let Ok(target) = normalize_reader_url(url) else {
+        return Ok(Vec::new());
+    };
+    let matches = |u: &Option<String>| {
+        u.as_deref()
+            .and_then(|u| normalize_reader_url(u).ok())
+            .is_some_and(|u| u == target)
+    };
+
+    let mut idea_ids: BTreeSet<i64> = idea_references::Entity::find()
+        .filter(idea_references::Column::Url.is_not_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|r| matches(&r.url))
+        .map(|r| r.idea_id)
+        .collect();
+
+    let item_ids: Vec<i64> = reference_items::Entity::find()
+        .filter(reference_items::Column::Url.is_not_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|r| matches(&r.url))
+        .map(|r| r.id)
+        .collect();
+    if !item_ids.is_empty() {
+        idea_ids.extend(
+            idea_reference_links::Entity::find()
+                .filter(idea_reference_links::Column::ReferenceId.is_in(item_ids))
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|l| l.idea_id),
+        );
+    }
+
+    Ok(idea_ids.into_iter().collect())
+}
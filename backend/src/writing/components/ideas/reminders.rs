@@ -0,0 +1,178 @@
+//! Idea reminders
+//!
+//! Once the `remind_at` of an idea has passed, the `idea_reminders` system
+//! task announces it with an `idea_reminder` event, which the UI shows as a
+//! notification. A reminder goes out once: `remind_at` is cleared when it is
+//! sent, and setting it again schedules another. Archived ideas are left out.
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use super::types::*;
+use crate::core::components::errors::AppResult;
+use crate::core::components::events::EventEmitter;
+use crate::system::components::scheduler::TaskRunResult;
+use crate::AppState;
+
+pub const IDEA_REMINDER_EVENT: &str = "idea_reminder";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeaReminderEvent {
+    pub idea_id: i64,
+    pub title: String,
+    pub status: String,
+    pub due_at: Option<String>,
+    /// Past its due time and not complete
+    pub overdue: bool,
+    pub remind_at: String,
+}
+
+/// Scheduled task: send the reminders that came due
+pub async fn run_idea_reminders_task(
+    emitter: &(dyn EventEmitter),
+    state: &AppState,
+) -> TaskRunResult {
+    match send_due_reminders(emitter, &state.db).await {
+        Ok(0) => TaskRunResult {
+            status: "skipped",
+            result_json: Some("{\"reason\":\"no reminders due\"}".into()),
+            error_message: None,
+        },
+        Ok(sent) => TaskRunResult {
+            status: "success",
+            result_json: Some(serde_json::json!({ "sent": sent }).to_string()),
+            error_message: None,
+        },
+        Err(e) => TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(e.to_string()),
+        },
+    }
+}
+
+/// Emit the reminders whose time has come, returning how many went out
+async fn send_due_reminders(
+    emitter: &(dyn EventEmitter),
+    db: &DatabaseConnection,
+) -> AppResult<usize> {
+    let now = Utc::now();
+    let due = Entity::find()
+        .filter(Column::RemindAt.lte(now))
+        .filter(Column::DateRemoved.is_null())
+        .order_by_asc(Column::RemindAt)
+        .all(db)
+        .await?;
+    if due.is_empty() {
+        return Ok(0);
+    }
+
+    // Cleared first, so a reminder is never sent twice
+    Entity::update_many()
+        .col_expr(Column::RemindAt, Expr::value(None::<DateTime<Utc>>))
+        .filter(Column::Id.is_in(due.iter().map(|idea| idea.id)))
+        .exec(db)
+        .await?;
+
+    let sent = due.len();
+    for idea in due {
+        let event = IdeaReminderEvent {
+            idea_id: idea.id,
+            overdue: idea.status != IdeaStatus::Complete && idea.due_at.is_some_and(|d| d < now),
+            status: idea.status.as_str().to_string(),
+            due_at: idea.due_at.map(|d| d.to_rfc3339()),
+            remind_at: idea.remind_at.unwrap_or(now).to_rfc3339(),
+            title: idea.title,
+        };
+        if let Err(e) = emitter.emit(IDEA_REMINDER_EVENT, event).await {
+            error!(target: "scheduler", idea_id = idea.id, "Failed to emit idea reminder: {}", e);
+        }
+    }
+    info!(target: "scheduler", sent, "Idea reminders sent");
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::components::db::migrations::test_db;
+    use crate::core::components::events::BroadcastEventEmitter;
+    use chrono::Duration;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    async fn insert_idea(
+        db: &DatabaseConnection,
+        title: &str,
+        status: IdeaStatus,
+        due_at: Option<DateTime<Utc>>,
+        remind_at: Option<DateTime<Utc>>,
+        archived: bool,
+    ) -> Model {
+        let now = Utc::now();
+        ActiveModel {
+            title: Set(title.to_string()),
+            status: Set(status),
+            date_added: Set(now),
+            date_updated: Set(now),
+            date_removed: Set(archived.then_some(now)),
+            priority: Set(0),
+            is_pinned: Set(0),
+            sort_order: Set(0),
+            due_at: Set(due_at),
+            remind_at: Set(remind_at),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap()
+    }
+
+    async fn stored_remind_at(db: &DatabaseConnection, id: i64) -> Option<DateTime<Utc>> {
+        Entity::find_by_id(id).one(db).await.unwrap().unwrap().remind_at
+    }
+
+    #[tokio::test]
+    async fn test_send_due_reminders() {
+        let db = test_db().await;
+        let now = Utc::now();
+        let past = now - Duration::hours(1);
+        let future = now + Duration::hours(1);
+
+        let overdue =
+            insert_idea(&db, "Overdue", IdeaStatus::InProgress, Some(past), Some(past), false)
+                .await;
+        let done =
+            insert_idea(&db, "Done", IdeaStatus::Complete, Some(past), Some(past), false).await;
+        let later =
+            insert_idea(&db, "Later", IdeaStatus::InProgress, None, Some(future), false).await;
+        let archived =
+            insert_idea(&db, "Archived", IdeaStatus::InProgress, None, Some(past), true).await;
+
+        let emitter = BroadcastEventEmitter::new();
+        let mut events = emitter.subscribe();
+        assert_eq!(send_due_reminders(&emitter, &db).await.unwrap(), 2);
+
+        let mut sent = Vec::new();
+        while let Ok(message) = events.try_recv() {
+            assert_eq!(message.event, IDEA_REMINDER_EVENT);
+            let event: IdeaReminderEvent = serde_json::from_value(message.payload).unwrap();
+            sent.push((event.idea_id, event.overdue));
+        }
+        sent.sort();
+        // Complete ideas are never overdue
+        assert_eq!(sent, vec![(overdue.id, true), (done.id, false)]);
+
+        assert_eq!(stored_remind_at(&db, overdue.id).await, None);
+        assert_eq!(stored_remind_at(&db, done.id).await, None);
+        assert!(stored_remind_at(&db, later.id).await.is_some());
+        assert!(stored_remind_at(&db, archived.id).await.is_some());
+
+        // Each reminder goes out once
+        assert_eq!(send_due_reminders(&emitter, &db).await.unwrap(), 0);
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -6,6 +6,7 @@
 
 use crate::core::components::errors::{AppError, AppResult};
 use crate::research::components::feed::entities::articles as news_articles;
+use schemars::JsonSchema;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -67,6 +68,9 @@ pub struct Model {
     pub date_removed: Option<DateTimeUtc>,
     pub priority: i32,
     pub is_pinned: i32,
+    pub sort_order: i32,
+    pub due_at: Option<DateTimeUtc>,
+    pub remind_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -89,7 +93,7 @@ impl Related<news_articles::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 /// DTO for idea responses
-#[derive(Serialize)]
+#[derive(Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IdeaDto {
     pub id: i64,
@@ -108,10 +112,31 @@ pub struct IdeaDto {
     pub date_removed: Option<String>,
     pub priority: i32,
     pub is_pinned: bool,
+    /// Position within its status column on the ideas board
+    pub sort_order: i32,
+    pub due_at: Option<String>,
+    /// When the next reminder goes out; cleared once it has
+    pub remind_at: Option<String>,
+}
+
+/// Result of merging one idea into another
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeaMergeDto {
+    /// The target idea after the merge
+    pub idea: IdeaDto,
+    /// Attached and knowledge-graph references
+    pub references_moved: usize,
+    pub notes_moved: usize,
+    /// Notes appended to a note of the same type on the target
+    pub notes_appended: usize,
+    pub writings_moved: usize,
+    /// References and writings the target already had, left with the source
+    pub skipped: usize,
 }
 
 /// Input for creating a new idea
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateIdeaInput {
     pub title: String,
@@ -125,17 +150,20 @@ pub struct CreateIdeaInput {
     pub article_markdown: Option<String>,
     pub priority: Option<i32>,
     pub is_pinned: Option<bool>,
+    /// RFC 3339 timestamps
+    pub due_at: Option<String>,
+    pub remind_at: Option<String>,
 }
 
 /// Input for creating an idea from an existing news article
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateIdeaForArticleInput {
     pub article_id: i64,
 }
 
 /// Input for updating idea metadata
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateIdeaMetadataInput {
     pub title: Option<String>,
@@ -145,17 +173,31 @@ pub struct UpdateIdeaMetadataInput {
     pub tags: Option<Vec<String>>,
     pub priority: Option<i32>,
     pub is_pinned: Option<bool>,
+    /// RFC 3339 timestamps; an empty string clears them
+    pub due_at: Option<String>,
+    pub remind_at: Option<String>,
+}
+
+/// Input for reordering one status column of the ideas board
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorderIdeasInput {
+    pub status: String,
+    /// Idea ids in the new order; ideas with another status move into this
+    /// one, and ideas of the column not listed keep their relative order
+    /// after the listed ones
+    pub ids: Vec<i64>,
 }
 
 /// Input for updating idea notes
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateIdeaNotesInput {
     pub notes_markdown: Option<String>,
 }
 
 /// Input for updating idea article content
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateIdeaArticleInput {
     pub article_title: Option<String>,
@@ -193,6 +235,17 @@ pub(crate) fn status_or_default(status: &Option<String>) -> AppResult<IdeaStatus
     }
 }
 
+/// Parse a due or reminder time sent as RFC 3339; an empty one means none
+pub(crate) fn parse_idea_time(field: &str, value: &str) -> AppResult<Option<DateTimeUtc>> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|time| Some(time.with_timezone(&chrono::Utc)))
+        .map_err(|_| AppError::validation(field, format!("'{value}' is not an RFC 3339 timestamp")))
+}
+
 /// Convert boolean to integer for database storage
 pub(crate) fn bool_to_int(value: Option<bool>) -> i32 {
     if value.unwrap_or(false) {
@@ -221,6 +274,9 @@ pub(crate) fn idea_to_dto(model: Model) -> IdeaDto {
         date_removed: model.date_removed.map(|d| d.to_rfc3339()),
         priority: model.priority,
         is_pinned: model.is_pinned != 0,
+        sort_order: model.sort_order,
+        due_at: model.due_at.map(|d| d.to_rfc3339()),
+        remind_at: model.remind_at.map(|d| d.to_rfc3339()),
     }
 }
 
@@ -248,7 +304,7 @@ impl ReferenceType {
 }
 
 /// DTO for idea reference (API response/request)
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IdeaReferenceDto {
     pub id: i64,
@@ -265,7 +321,7 @@ pub struct IdeaReferenceDto {
 }
 
 /// Input for adding a reference to an idea
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AddReferenceInput {
     pub idea_id: i64,
@@ -278,7 +334,7 @@ pub struct AddReferenceInput {
 }
 
 /// Input for updating reference notes
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateReferenceNotesInput {
     pub reference_id: i64,
@@ -286,7 +342,7 @@ pub struct UpdateReferenceNotesInput {
 }
 
 /// Input for fetching a reader snapshot by URL
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReaderSnapshotInput {
     pub url: String,
@@ -294,7 +350,7 @@ pub struct ReaderSnapshotInput {
 }
 
 /// DTO for a read-only reader snapshot of a reference
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReferenceReaderSnapshotDto {
     pub reference_id: i64,
@@ -304,3 +360,14 @@ pub struct ReferenceReaderSnapshotDto {
     pub content_html: String,
     pub content_text: String,
 }
+
+/// Input for related-content suggestions
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedContentInput {
+    pub idea_id: i64,
+    /// Default: 10
+    pub limit: Option<usize>,
+    /// Drop suggestions below this cosine similarity
+    pub min_score: Option<f32>,
+}
@@ -40,6 +40,7 @@ pub struct Model {
     
     pub role: ReferenceRole,
     pub notes: Option<String>,
+    #[sea_orm(column_name = "sort_order")]
     pub link_order: i32,
     
     pub created_at: DateTimeUtc,
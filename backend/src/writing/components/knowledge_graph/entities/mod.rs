@@ -7,12 +7,20 @@
 //! - idea_reference_links: Ideas ↔ References (many-to-many)
 //! - writing_idea_links: Writings ↔ Ideas (many-to-many)
 //! - notes: Polymorphic notes on any entity
+//! - note_templates: Starting content for new notes
+//! - note_links: [[Title]] links from notes to ideas, writings and references
+//! - writing_publications: Cross-post tracking per destination
+//! - writing_sync_files: Markdown folder sync state per writing
 
 pub mod reference_items;
 pub mod writings;
 pub mod idea_reference_links;
 pub mod writing_idea_links;
 pub mod notes;
+pub mod note_templates;
+pub mod note_links;
+pub mod writing_publications;
+pub mod writing_sync_files;
 
 // Re-export entities for convenient access
 pub use reference_items::Entity as ReferenceItems;
@@ -20,6 +28,10 @@ pub use writings::Entity as Writings;
 pub use idea_reference_links::Entity as IdeaReferenceLinks;
 pub use writing_idea_links::Entity as WritingIdeaLinks;
 pub use notes::Entity as Notes;
+pub use note_templates::Entity as NoteTemplates;
+pub use note_links::Entity as NoteLinks;
+pub use writing_publications::Entity as WritingPublications;
+pub use writing_sync_files::Entity as WritingSyncFiles;
 
 // Re-export enums for type safety
 pub use reference_items::ReferenceType;
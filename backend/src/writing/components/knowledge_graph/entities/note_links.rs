@@ -0,0 +1,31 @@
+//! Note Links Entity
+//!
+//! `[[Title]]` links in note bodies, resolved to the idea, writing or
+//! reference they name; read backwards they are the backlinks of an entity
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Note link model
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "note_links")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// The note whose body holds the link
+    pub note_id: i64,
+
+    /// "idea", "writing" or "reference"
+    pub target_type: String,
+    pub target_id: i64,
+    /// Text between the brackets, as written
+    pub link_text: String,
+
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
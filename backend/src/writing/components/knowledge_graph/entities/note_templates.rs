@@ -0,0 +1,31 @@
+//! Note Templates Entity
+//!
+//! Starting content for the first note of an entity, per entity type and
+//! note type
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Note template model
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "note_templates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    pub name: String,
+    /// Entity type the template is for; `None` for every type
+    pub entity_type: Option<String>,
+    pub note_type: String,
+
+    /// HTML the new note starts with
+    pub body_html: String,
+
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -77,6 +77,9 @@ pub struct Model {
     /// Column name is body_markdown for historical reasons
     #[sea_orm(column_name = "body_markdown")]
     pub body_html: String,
+
+    /// Markdown kept alongside `body_html` when the note asks for it
+    pub canonical_markdown: Option<String>,
     
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
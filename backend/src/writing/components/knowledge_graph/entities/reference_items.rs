@@ -54,7 +54,9 @@ pub struct Model {
     
     pub title: String,
     pub url: Option<String>,
+    pub source: Option<String>,
     pub author: Option<String>,
+    #[sea_orm(column_name = "published_at")]
     pub published_date: Option<DateTimeUtc>,
     pub summary: Option<String>,
     
@@ -20,6 +20,7 @@ pub struct Model {
     
     #[sea_orm(column_name = "sort_order")]
     pub link_order: i32,
+    pub notes: Option<String>,
     
     pub created_at: DateTimeUtc,
 }
@@ -0,0 +1,53 @@
+//! Writing Publications Entity
+//!
+//! Cross-post tracking: one row per destination a writing was pushed to
+//! Status workflow: pending → published | failed (failed → retry → ...)
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Writing publication model
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "writing_publications")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    pub writing_id: i64,
+
+    /// Destination identifier: "newsletter", "substack", "medium", "blog", ...
+    pub platform: String,
+    pub remote_id: Option<String>,
+    pub url: Option<String>,
+
+    /// "pending" | "published" | "failed"
+    pub status: String,
+    pub error_message: Option<String>,
+    pub attempts: i32,
+
+    /// Platform-specific data needed to retry (JSON)
+    pub payload_json: Option<String>,
+
+    pub published_at: Option<DateTimeUtc>,
+    pub last_attempt_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+    pub updated_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::writings::Entity",
+        from = "Column::WritingId",
+        to = "super::writings::Column::Id"
+    )]
+    Writing,
+}
+
+impl Related<super::writings::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Writing.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
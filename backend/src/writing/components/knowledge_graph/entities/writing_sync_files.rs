@@ -0,0 +1,33 @@
+//! Writing Sync Files Entity
+//!
+//! Markdown folder sync state: the file mirroring each writing, with hashes
+//! of the file and of the writing as of the last sync
+
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Writing sync file model
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "writing_sync_files")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+
+    /// The sync folder the row belongs to
+    pub folder: String,
+    /// Not a foreign key: the row outlives a deleted writing
+    pub writing_id: i64,
+    pub file_name: String,
+
+    /// SHA-256 of the file as last read or written
+    pub file_hash: String,
+    /// SHA-256 of the writing rendered as Markdown at the last sync
+    pub writing_hash: String,
+
+    pub synced_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,361 @@
+//! Bibliography export for citing references in external tools
+//!
+//! Collects the references linked to an idea, or to the ideas of a writing,
+//! and renders them as BibTeX or CSL-JSON from their stored metadata. A DOI
+//! is read from the reference's `metadata` object.
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::ideas::types as ideas;
+use crate::writing::components::knowledge_graph::entities::reference_items::{self, ReferenceType};
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, writing_idea_links, writings,
+};
+use chrono::Datelike;
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use tracing::instrument;
+
+/// Bibliography formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BibliographyFormat {
+    Bibtex,
+    CslJson,
+}
+
+/// DTO for requesting a bibliography; exactly one of the ids is required
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBibliographyInput {
+    pub writing_id: Option<i64>,
+    pub idea_id: Option<i64>,
+    pub format: BibliographyFormat,
+}
+
+/// DTO for an exported bibliography
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BibliographyDto {
+    pub format: BibliographyFormat,
+    /// BibTeX entries, or a CSL-JSON array
+    pub content: String,
+    pub reference_count: usize,
+}
+
+/// Export the references linked to a writing (through its ideas) or an idea
+#[instrument(skip(db))]
+pub async fn export_bibliography(
+    db: &sea_orm::DatabaseConnection,
+    input: ExportBibliographyInput,
+) -> AppResult<BibliographyDto> {
+    let idea_ids = match (input.writing_id, input.idea_id) {
+        (Some(writing_id), None) => {
+            writings::Entity::find_by_id(writing_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| AppError::other(format!("Writing not found: {}", writing_id)))?;
+            writing_idea_links::Entity::find()
+                .filter(writing_idea_links::Column::WritingId.eq(writing_id))
+                .order_by_asc(writing_idea_links::Column::LinkOrder)
+                .order_by_asc(writing_idea_links::Column::Id)
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|link| link.idea_id)
+                .collect()
+        }
+        (None, Some(idea_id)) => {
+            ideas::Entity::find_by_id(idea_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| AppError::other(format!("Idea not found: {}", idea_id)))?;
+            vec![idea_id]
+        }
+        _ => {
+            return Err(AppError::validation(
+                "writing_id",
+                "Pass either a writing id or an idea id",
+            ))
+        }
+    };
+
+    // References in link order, each once
+    let mut reference_ids = Vec::new();
+    for idea_id in idea_ids {
+        let links = idea_reference_links::Entity::find()
+            .filter(idea_reference_links::Column::IdeaId.eq(idea_id))
+            .order_by_asc(idea_reference_links::Column::LinkOrder)
+            .order_by_asc(idea_reference_links::Column::Id)
+            .all(db)
+            .await?;
+        for link in links {
+            if !reference_ids.contains(&link.reference_id) {
+                reference_ids.push(link.reference_id);
+            }
+        }
+    }
+    let mut references = reference_items::Entity::find()
+        .filter(reference_items::Column::Id.is_in(reference_ids.clone()))
+        .all(db)
+        .await?;
+    references.sort_by_key(|r| reference_ids.iter().position(|id| *id == r.id));
+
+    let content = match input.format {
+        BibliographyFormat::Bibtex => to_bibtex(&references),
+        BibliographyFormat::CslJson => {
+            let items: Vec<JsonValue> = references.iter().map(csl_item).collect();
+            serde_json::to_string_pretty(&items)
+                .map_err(|e| AppError::other(format!("Failed to serialize bibliography: {}", e)))?
+        }
+    };
+    Ok(BibliographyDto {
+        format: input.format,
+        content,
+        reference_count: references.len(),
+    })
+}
+
+/// Author names, split on `;` or ` and `
+fn authors(reference: &reference_items::Model) -> Vec<&str> {
+    reference
+        .author
+        .as_deref()
+        .unwrap_or("")
+        .split(';')
+        .flat_map(|part| part.split(" and "))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// A name as (family, given): "Family, Given" or "Given Family"
+fn split_name(name: &str) -> (&str, &str) {
+    if let Some((family, given)) = name.split_once(',') {
+        return (family.trim(), given.trim());
+    }
+    match name.rsplit_once(' ') {
+        Some((given, family)) => (family, given.trim()),
+        None => (name, ""),
+    }
+}
+
+fn doi(reference: &reference_items::Model) -> Option<String> {
+    let metadata: JsonValue = serde_json::from_str(reference.metadata.as_deref()?).ok()?;
+    metadata
+        .get("doi")
+        .or_else(|| metadata.get("DOI"))
+        .and_then(|d| d.as_str())
+        .map(str::to_string)
+}
+
+fn to_bibtex(references: &[reference_items::Model]) -> String {
+    let mut keys = HashSet::new();
+    let mut entries = Vec::new();
+    for reference in references {
+        let (entry_type, source_field) = match reference.reference_type {
+            ReferenceType::Paper => ("article", "journal"),
+            ReferenceType::NewsArticle => ("article", "journal"),
+            ReferenceType::Book => ("book", "publisher"),
+            ReferenceType::Manual => ("manual", "organization"),
+            ReferenceType::Url | ReferenceType::Tweet | ReferenceType::Pdf => {
+                ("misc", "howpublished")
+            }
+        };
+
+        let mut fields = vec![("title", reference.title.clone())];
+        let authors = authors(reference);
+        if !authors.is_empty() {
+            fields.push(("author", authors.join(" and ")));
+        }
+        if let Some(date) = reference.published_date {
+            fields.push(("year", date.year().to_string()));
+            fields.push(("month", date.format("%b").to_string().to_lowercase()));
+        }
+        if let Some(source) = &reference.source {
+            fields.push((source_field, source.clone()));
+        }
+        if let Some(doi) = doi(reference) {
+            fields.push(("doi", doi));
+        }
+        if let Some(url) = &reference.url {
+            fields.push(("url", url.clone()));
+        }
+
+        let mut entry = format!("@{}{{{},\n", entry_type, bibtex_key(reference, &mut keys));
+        for (name, value) in fields {
+            // Months are BibTeX macros, everything else is braced text
+            let value = match name {
+                "month" => value,
+                "url" | "doi" => format!("{{{}}}", value),
+                _ => format!("{{{}}}", escape_bibtex(&value)),
+            };
+            entry.push_str(&format!("  {} = {},\n", name, value));
+        }
+        entry.push('}');
+        entries.push(entry);
+    }
+    let mut out = entries.join("\n\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// `<family><year><first title word>`, with a letter appended when taken
+fn bibtex_key(reference: &reference_items::Model, taken: &mut HashSet<String>) -> String {
+    let ascii = |text: &str| -> String {
+        text.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let family = authors(reference)
+        .first()
+        .map(|name| ascii(split_name(name).0))
+        .unwrap_or_default();
+    let year = reference
+        .published_date
+        .map(|d| d.year().to_string())
+        .unwrap_or_default();
+    let word = reference
+        .title
+        .split_whitespace()
+        .map(ascii)
+        .find(|w| !w.is_empty())
+        .unwrap_or_default();
+
+    let mut key = format!("{}{}{}", family, year, word);
+    if key.is_empty() {
+        key = format!("ref{}", reference.id);
+    }
+    let mut unique = key.clone();
+    let mut suffix = b'a';
+    while taken.contains(&unique) && suffix <= b'z' {
+        unique = format!("{}{}", key, suffix as char);
+        suffix += 1;
+    }
+    if taken.contains(&unique) {
+        unique = format!("{}{}", key, reference.id);
+    }
+    taken.insert(unique.clone());
+    unique
+}
+
+fn escape_bibtex(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '\\' => out.push_str("\\textbackslash{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn csl_item(reference: &reference_items::Model) -> JsonValue {
+    let csl_type = match reference.reference_type {
+        ReferenceType::Paper => "article-journal",
+        ReferenceType::NewsArticle => "article-newspaper",
+        ReferenceType::Book => "book",
+        ReferenceType::Url => "webpage",
+        ReferenceType::Tweet => "post",
+        ReferenceType::Pdf | ReferenceType::Manual => "document",
+    };
+    let mut item = json!({
+        "id": format!("ref{}", reference.id),
+        "type": csl_type,
+        "title": reference.title,
+    });
+    let authors: Vec<JsonValue> = authors(reference)
+        .into_iter()
+        .map(|name| match split_name(name) {
+            (family, "") => json!({ "literal": family }),
+            (family, given) => json!({ "family": family, "given": given }),
+        })
+        .collect();
+    if !authors.is_empty() {
+        item["author"] = authors.into();
+    }
+    if let Some(date) = reference.published_date {
+        item["issued"] = json!({ "date-parts": [[date.year(), date.month(), date.day()]] });
+    }
+    if let Some(source) = &reference.source {
+        let field = match reference.reference_type {
+            ReferenceType::Book | ReferenceType::Manual => "publisher",
+            _ => "container-title",
+        };
+        item[field] = source.as_str().into();
+    }
+    if let Some(doi) = doi(reference) {
+        item["DOI"] = doi.into();
+    }
+    if let Some(url) = &reference.url {
+        item["URL"] = url.as_str().into();
+    }
+    if let Some(summary) = &reference.summary {
+        item["abstract"] = summary.as_str().into();
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn paper() -> reference_items::Model {
+        reference_items::Model {
+            id: 4,
+            reference_type: ReferenceType::Paper,
+            news_article_id: None,
+            title: "Attention & Memory".to_string(),
+            url: Some("https://example.com/paper".to_string()),
+            source: Some("Journal of Tests".to_string()),
+            author: Some("Smith, Jane; Bob Lee".to_string()),
+            published_date: Some(Utc.with_ymd_and_hms(2021, 3, 9, 0, 0, 0).unwrap()),
+            summary: None,
+            metadata: Some(r#"{"doi": "10.1000/xyz"}"#.to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_bibtex() {
+        assert_eq!(
+            to_bibtex(&[paper(), paper()]),
+            "@article{smith2021attention,\n  title = {Attention \\& Memory},\n  \
+             author = {Smith, Jane and Bob Lee},\n  year = {2021},\n  month = mar,\n  \
+             journal = {Journal of Tests},\n  doi = {10.1000/xyz},\n  \
+             url = {https://example.com/paper},\n}\n\n@article{smith2021attentiona,\n  \
+             title = {Attention \\& Memory},\n  author = {Smith, Jane and Bob Lee},\n  \
+             year = {2021},\n  month = mar,\n  journal = {Journal of Tests},\n  \
+             doi = {10.1000/xyz},\n  url = {https://example.com/paper},\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_csl_item() {
+        let item = csl_item(&paper());
+        assert_eq!(item["type"], "article-journal");
+        assert_eq!(
+            item["author"],
+            json!([
+                { "family": "Smith", "given": "Jane" },
+                { "family": "Lee", "given": "Bob" }
+            ])
+        );
+        assert_eq!(item["issued"], json!({ "date-parts": [[2021, 3, 9]] }));
+        assert_eq!(item["container-title"], "Journal of Tests");
+        assert_eq!(item["DOI"], "10.1000/xyz");
+    }
+}
@@ -0,0 +1,326 @@
+//! Export of the whole knowledge graph for graph tools
+//!
+//! Ideas, references and writings become nodes; idea-reference and
+//! writing-idea links become directed edges carrying their role or purpose,
+//! and `[[Title]]` links in an entity's notes become `note_link` edges.
+//! GraphML opens in Gephi, yEd and Cytoscape, DOT in Graphviz.
+
+use crate::core::components::errors::AppResult;
+use crate::writing::components::ideas::types as ideas;
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, note_links, notes, reference_items, writing_idea_links, writings,
+};
+use schemars::JsonSchema;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::instrument;
+
+/// Graph file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    Graphml,
+    Dot,
+}
+
+/// DTO for an exported graph
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphExportDto {
+    pub format: GraphFormat,
+    /// The GraphML document or DOT source
+    pub content: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+}
+
+struct GraphNode {
+    /// `idea:<id>`, `reference:<id>` or `writing:<id>`
+    id: String,
+    kind: &'static str,
+    label: String,
+    /// Reference or writing type
+    node_type: Option<String>,
+    status: Option<String>,
+}
+
+struct GraphEdge {
+    source: String,
+    target: String,
+    relation: &'static str,
+    /// Role of a reference for an idea, or purpose of an idea for a writing
+    role: Option<String>,
+}
+
+/// Export every idea, reference and writing with the links between them,
+/// those written in their notes included
+#[instrument(skip(db))]
+pub async fn export_graph(
+    db: &sea_orm::DatabaseConnection,
+    format: GraphFormat,
+) -> AppResult<GraphExportDto> {
+    let mut nodes = Vec::new();
+    for idea in ideas::Entity::find()
+        .filter(ideas::Column::DateRemoved.is_null())
+        .order_by_asc(ideas::Column::Id)
+        .all(db)
+        .await?
+    {
+        nodes.push(GraphNode {
+            id: format!("idea:{}", idea.id),
+            kind: "idea",
+            label: idea.title,
+            node_type: None,
+            status: Some(idea.status.to_string()),
+        });
+    }
+    for reference in reference_items::Entity::find()
+        .order_by_asc(reference_items::Column::Id)
+        .all(db)
+        .await?
+    {
+        nodes.push(GraphNode {
+            id: format!("reference:{}", reference.id),
+            kind: "reference",
+            label: reference.title,
+            node_type: Some(reference.reference_type.to_string()),
+            status: None,
+        });
+    }
+    for writing in writings::Entity::find()
+        .order_by_asc(writings::Column::Id)
+        .all(db)
+        .await?
+    {
+        nodes.push(GraphNode {
+            id: format!("writing:{}", writing.id),
+            kind: "writing",
+            label: writing.title,
+            node_type: Some(writing.r#type.to_string()),
+            status: Some(writing.status.to_string()),
+        });
+    }
+
+    let mut edges = Vec::new();
+    for link in idea_reference_links::Entity::find()
+        .order_by_asc(idea_reference_links::Column::Id)
+        .all(db)
+        .await?
+    {
+        edges.push(GraphEdge {
+            source: format!("idea:{}", link.idea_id),
+            target: format!("reference:{}", link.reference_id),
+            relation: "idea_reference",
+            role: Some(link.role.to_string()),
+        });
+    }
+    for link in writing_idea_links::Entity::find()
+        .order_by_asc(writing_idea_links::Column::Id)
+        .all(db)
+        .await?
+    {
+        edges.push(GraphEdge {
+            source: format!("writing:{}", link.writing_id),
+            target: format!("idea:{}", link.idea_id),
+            relation: "writing_idea",
+            role: link.purpose,
+        });
+    }
+    let note_entities: Vec<(i64, notes::EntityType, i64)> = notes::Entity::find()
+        .select_only()
+        .column(notes::Column::Id)
+        .column(notes::Column::EntityType)
+        .column(notes::Column::EntityId)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let note_entities: HashMap<i64, String> = note_entities
+        .into_iter()
+        .map(|(id, entity_type, entity_id)| (id, format!("{}:{}", entity_type, entity_id)))
+        .collect();
+    let mut prose_links = HashSet::new();
+    for link in note_links::Entity::find()
+        .order_by_asc(note_links::Column::Id)
+        .all(db)
+        .await?
+    {
+        let Some(source) = note_entities.get(&link.note_id) else {
+            continue;
+        };
+        let target = format!("{}:{}", link.target_type, link.target_id);
+        // Several notes of one entity may link the same target
+        if prose_links.insert((source.clone(), target.clone())) {
+            edges.push(GraphEdge {
+                source: source.clone(),
+                target,
+                relation: "note_link",
+                role: None,
+            });
+        }
+    }
+
+    // Links to removed ideas would point at nodes that aren't there
+    let ids: HashSet<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+    edges.retain(|e| ids.contains(e.source.as_str()) && ids.contains(e.target.as_str()));
+
+    let content = match format {
+        GraphFormat::Graphml => to_graphml(&nodes, &edges),
+        GraphFormat::Dot => to_dot(&nodes, &edges),
+    };
+    Ok(GraphExportDto {
+        format,
+        content,
+        node_count: nodes.len(),
+        edge_count: edges.len(),
+    })
+}
+
+fn to_graphml(nodes: &[GraphNode], edges: &[GraphEdge]) -> String {
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+        "  <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+        "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+        "  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n",
+        "  <key id=\"status\" for=\"node\" attr.name=\"status\" attr.type=\"string\"/>\n",
+        "  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n",
+        "  <key id=\"role\" for=\"edge\" attr.name=\"role\" attr.type=\"string\"/>\n",
+        "  <graph id=\"knowledge_graph\" edgedefault=\"directed\">\n",
+    ));
+    let data =
+        |key: &str, value: &str| format!("<data key=\"{}\">{}</data>", key, escape_xml(value));
+
+    for node in nodes {
+        let mut attrs = data("kind", node.kind) + &data("label", &node.label);
+        if let Some(node_type) = &node.node_type {
+            attrs += &data("type", node_type);
+        }
+        if let Some(status) = &node.status {
+            attrs += &data("status", status);
+        }
+        out.push_str(&format!("    <node id=\"{}\">{}</node>\n", node.id, attrs));
+    }
+    for (i, edge) in edges.iter().enumerate() {
+        let mut attrs = data("relation", edge.relation);
+        if let Some(role) = &edge.role {
+            attrs += &data("role", role);
+        }
+        out.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">{}</edge>\n",
+            i, edge.source, edge.target, attrs
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn to_dot(nodes: &[GraphNode], edges: &[GraphEdge]) -> String {
+    let mut out = String::from("digraph knowledge_graph {\n");
+    for node in nodes {
+        let shape = match node.kind {
+            "idea" => "ellipse",
+            "reference" => "box",
+            _ => "note",
+        };
+        let mut attrs = vec![
+            format!("label={}", quote_dot(&node.label)),
+            format!("kind={}", quote_dot(node.kind)),
+            format!("shape={}", shape),
+        ];
+        if let Some(node_type) = &node.node_type {
+            attrs.push(format!("type={}", quote_dot(node_type)));
+        }
+        if let Some(status) = &node.status {
+            attrs.push(format!("status={}", quote_dot(status)));
+        }
+        out.push_str(&format!(
+            "  {} [{}];\n",
+            quote_dot(&node.id),
+            attrs.join(", ")
+        ));
+    }
+    for edge in edges {
+        let mut attrs = vec![format!("relation={}", quote_dot(edge.relation))];
+        if let Some(role) = &edge.role {
+            attrs.push(format!("role={}", quote_dot(role)));
+            attrs.push(format!("label={}", quote_dot(role)));
+        }
+        out.push_str(&format!(
+            "  {} -> {} [{}];\n",
+            quote_dot(&edge.source),
+            quote_dot(&edge.target),
+            attrs.join(", ")
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A double-quoted DOT ID
+fn quote_dot(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> (Vec<GraphNode>, Vec<GraphEdge>) {
+        let nodes = vec![
+            GraphNode {
+                id: "idea:1".into(),
+                kind: "idea",
+                label: "Cats & \"dogs\"".into(),
+                node_type: None,
+                status: Some("in_progress".into()),
+            },
+            GraphNode {
+                id: "reference:2".into(),
+                kind: "reference",
+                label: "Paper".into(),
+                node_type: Some("paper".into()),
+                status: None,
+            },
+        ];
+        let edges = vec![GraphEdge {
+            source: "idea:1".into(),
+            target: "reference:2".into(),
+            relation: "idea_reference",
+            role: Some("supporting".into()),
+        }];
+        (nodes, edges)
+    }
+
+    #[test]
+    fn test_graph_formats() {
+        let (nodes, edges) = sample();
+
+        let graphml = to_graphml(&nodes, &edges);
+        assert!(graphml.contains(
+            "<node id=\"idea:1\"><data key=\"kind\">idea</data>\
+             <data key=\"label\">Cats &amp; &quot;dogs&quot;</data>"
+        ));
+        assert!(graphml.contains(
+            "<edge id=\"e0\" source=\"idea:1\" target=\"reference:2\">\
+             <data key=\"relation\">idea_reference</data><data key=\"role\">supporting</data></edge>"
+        ));
+
+        let dot = to_dot(&nodes, &edges);
+        assert!(dot.contains("\"idea:1\" [label=\"Cats & \\\"dogs\\\"\", kind=\"idea\""));
+        assert!(dot.contains(
+            "\"idea:1\" -> \"reference:2\" [relation=\"idea_reference\", role=\"supporting\""
+        ));
+    }
+}
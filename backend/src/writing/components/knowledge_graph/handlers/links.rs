@@ -7,6 +7,7 @@ use crate::writing::components::knowledge_graph::entities::{
     idea_reference_links, writing_idea_links,
 };
 use chrono::Utc;
+use schemars::JsonSchema;
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
@@ -16,7 +17,7 @@ use tracing::instrument;
 // ============================================================================
 
 /// DTO for creating idea-reference link
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LinkIdeaReferenceInput {
     pub idea_id: i64,
@@ -26,7 +27,7 @@ pub struct LinkIdeaReferenceInput {
 }
 
 /// DTO for idea-reference link response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IdeaReferenceLinkDto {
     pub id: i64,
@@ -134,7 +135,7 @@ pub async fn list_ideas_for_reference(
 // ============================================================================
 
 /// DTO for creating writing-idea link
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LinkWritingIdeaInput {
     pub writing_id: i64,
@@ -144,7 +145,7 @@ pub struct LinkWritingIdeaInput {
 }
 
 /// DTO for writing-idea link response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WritingIdeaLinkDto {
     pub id: i64,
@@ -6,13 +6,22 @@
 //! - idea_reference_links: Many-to-many links between ideas and references
 //! - writing_idea_links: Many-to-many links between writings and ideas
 //! - notes: Polymorphic notes attached to ideas, references, or writings
+//! - publications: Cross-post tracking for writings (list/record/retry)
+//! - graph: Export of the whole graph as GraphML or DOT
+//! - bibliography: BibTeX / CSL-JSON for the references of a writing or idea
 
+pub mod bibliography;
+pub mod graph;
 pub mod links;
 pub mod notes;
+pub mod publications;
 pub mod reference_items;
 pub mod writings;
 
+pub use bibliography::*;
+pub use graph::*;
 pub use links::*;
 pub use notes::*;
+pub use publications::*;
 pub use reference_items::*;
 pub use writings::*;
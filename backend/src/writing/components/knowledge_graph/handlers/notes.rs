@@ -4,8 +4,11 @@
 //! Notes are polymorphic and can be attached to ideas, references, or writings.
 
 use crate::core::components::errors::{AppError, AppResult};
+use crate::notes::components::links::sync_note_links;
+use crate::notes::components::markdown::html_to_markdown;
 use crate::writing::components::knowledge_graph::entities::notes::*;
 use chrono::Utc;
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
 };
@@ -13,7 +16,7 @@ use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 /// DTO for creating a note
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateNoteInput {
     pub entity_type: String, // "idea", "reference", "writing"
@@ -23,7 +26,7 @@ pub struct CreateNoteInput {
 }
 
 /// DTO for updating a note
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateNoteInput {
     pub body_html: Option<String>,
@@ -31,7 +34,7 @@ pub struct UpdateNoteInput {
 }
 
 /// DTO for note response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NoteDto {
     pub id: i64,
@@ -115,6 +118,7 @@ pub async fn create_note(
     };
 
     let result = active.insert(db).await?;
+    sync_note_links(db, &result).await?;
     Ok(note_to_dto(result))
 }
 
@@ -130,9 +134,14 @@ pub async fn update_note(
         .await?
         .ok_or_else(|| AppError::other(format!("Note not found: {}", id)))?;
 
+    let keeps_markdown = model.canonical_markdown.is_some();
     let mut active: ActiveModel = model.into_active_model();
 
     if let Some(body_html) = input.body_html {
+        // Keep the stored Markdown in step with the HTML
+        if keeps_markdown {
+            active.canonical_markdown = Set(Some(html_to_markdown(&body_html)));
+        }
         active.body_html = Set(body_html);
     }
     if let Some(note_type_str) = input.note_type {
@@ -142,6 +151,7 @@ pub async fn update_note(
     active.updated_at = Set(Utc::now());
 
     let result = active.update(db).await?;
+    sync_note_links(db, &result).await?;
     Ok(note_to_dto(result))
 }
 
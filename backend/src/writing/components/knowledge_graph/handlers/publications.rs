@@ -0,0 +1,266 @@
+//! Handlers for writing publications (cross-post tracking)
+//!
+//! Records every destination a writing was pushed to and retries failed
+//! pushes for platforms the backend can publish to itself.
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::knowledge_graph::entities::writing_publications::*;
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::AppState;
+use chrono::Utc;
+use schemars::JsonSchema;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder, Set,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tracing::{info, instrument};
+
+/// Publication status values
+pub const PUBLICATION_PENDING: &str = "pending";
+pub const PUBLICATION_PUBLISHED: &str = "published";
+pub const PUBLICATION_FAILED: &str = "failed";
+
+/// DTO for recording a publication
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordPublicationInput {
+    pub writing_id: i64,
+    pub platform: String,
+    pub remote_id: Option<String>,
+    pub url: Option<String>,
+    pub status: Option<String>, // "pending", "published" (default), "failed"
+    pub error_message: Option<String>,
+    pub payload: Option<JsonValue>,
+}
+
+/// DTO for publication response
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WritingPublicationDto {
+    pub id: i64,
+    pub writing_id: i64,
+    pub platform: String,
+    pub remote_id: Option<String>,
+    pub url: Option<String>,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub attempts: i32,
+    pub payload: Option<JsonValue>,
+    pub published_at: Option<String>,
+    pub last_attempt_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Convert Model to DTO
+fn publication_to_dto(model: Model) -> WritingPublicationDto {
+    WritingPublicationDto {
+        id: model.id,
+        writing_id: model.writing_id,
+        platform: model.platform,
+        remote_id: model.remote_id,
+        url: model.url,
+        status: model.status,
+        error_message: model.error_message,
+        attempts: model.attempts,
+        payload: model
+            .payload_json
+            .and_then(|p| serde_json::from_str(&p).ok()),
+        published_at: model.published_at.map(|dt| dt.to_rfc3339()),
+        last_attempt_at: model.last_attempt_at.map(|dt| dt.to_rfc3339()),
+        created_at: model.created_at.to_rfc3339(),
+        updated_at: model.updated_at.to_rfc3339(),
+    }
+}
+
+/// List publications, optionally filtered by writing, platform, or status
+#[instrument(skip(db))]
+pub async fn list_publications(
+    db: &sea_orm::DatabaseConnection,
+    writing_id: Option<i64>,
+    platform: Option<String>,
+    status: Option<String>,
+) -> AppResult<Vec<WritingPublicationDto>> {
+    let mut query = Entity::find();
+
+    if let Some(writing_id) = writing_id {
+        query = query.filter(Column::WritingId.eq(writing_id));
+    }
+    if let Some(platform) = platform {
+        query = query.filter(Column::Platform.eq(platform));
+    }
+    if let Some(status) = status {
+        query = query.filter(Column::Status.eq(status));
+    }
+
+    let results = query
+        .order_by_desc(Column::CreatedAt)
+        .order_by_desc(Column::Id)
+        .all(db)
+        .await?;
+
+    Ok(results.into_iter().map(publication_to_dto).collect())
+}
+
+/// Record a push of a writing to a destination
+#[instrument(skip(db, input), fields(writing_id = input.writing_id, platform = %input.platform))]
+pub async fn record_publication(
+    db: &sea_orm::DatabaseConnection,
+    input: RecordPublicationInput,
+) -> AppResult<WritingPublicationDto> {
+    writings::Entity::find_by_id(input.writing_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Writing not found: {}", input.writing_id)))?;
+
+    let platform = input.platform.trim().to_lowercase();
+    if platform.is_empty() {
+        return Err(AppError::validation("platform", "Platform is required"));
+    }
+
+    let status = input.status.unwrap_or_else(|| PUBLICATION_PUBLISHED.to_string());
+    let valid = [PUBLICATION_PENDING, PUBLICATION_PUBLISHED, PUBLICATION_FAILED];
+    if !valid.contains(&status.as_str()) {
+        return Err(AppError::other(format!("Invalid status: {}", status)));
+    }
+
+    let now = Utc::now();
+    let active = ActiveModel {
+        writing_id: Set(input.writing_id),
+        platform: Set(platform),
+        remote_id: Set(input.remote_id),
+        url: Set(input.url),
+        published_at: Set((status == PUBLICATION_PUBLISHED).then_some(now)),
+        status: Set(status),
+        error_message: Set(input.error_message),
+        attempts: Set(1),
+        payload_json: Set(input.payload.map(|p| p.to_string())),
+        last_attempt_at: Set(Some(now)),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    };
+
+    let result = active.insert(db).await?;
+    Ok(publication_to_dto(result))
+}
+
+/// Retry a failed or pending publication
+///
+/// Only platforms the backend publishes to itself can be retried; results
+/// from external tools should be recorded again with `record_publication`.
+#[instrument(skip(state))]
+pub async fn retry_publication(state: &AppState, id: i64) -> AppResult<WritingPublicationDto> {
+    let model = Entity::find_by_id(id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Publication not found: {}", id)))?;
+
+    if model.status == PUBLICATION_PUBLISHED {
+        return Err(AppError::validation(
+            "status",
+            format!("Publication {} is already published", id),
+        ));
+    }
+
+    let payload: JsonValue = model
+        .payload_json
+        .as_deref()
+        .and_then(|p| serde_json::from_str(p).ok())
+        .unwrap_or(JsonValue::Null);
+
+    let outcome = match model.platform.as_str() {
+        "newsletter" => retry_newsletter(state, model.writing_id, &payload).await?,
+        other => {
+            return Err(AppError::validation(
+                "platform",
+                format!("Retry is not supported for platform '{}'", other),
+            ))
+        }
+    };
+
+    info!(
+        publication_id = id,
+        status = outcome.status,
+        "Publication retry finished"
+    );
+
+    let now = Utc::now();
+    let attempts = model.attempts + 1;
+    let mut active = model.into_active_model();
+    active.status = Set(outcome.status.to_string());
+    active.error_message = Set(outcome.error_message);
+    active.payload_json = Set(Some(outcome.payload.to_string()));
+    active.attempts = Set(attempts);
+    active.last_attempt_at = Set(Some(now));
+    if outcome.status == PUBLICATION_PUBLISHED {
+        active.published_at = Set(Some(now));
+    }
+    active.updated_at = Set(now);
+
+    let result = active.update(&state.db).await?;
+    Ok(publication_to_dto(result))
+}
+
+struct RetryOutcome {
+    status: &'static str,
+    error_message: Option<String>,
+    payload: JsonValue,
+}
+
+/// Re-send a newsletter to the recipients that failed last time
+async fn retry_newsletter(
+    state: &AppState,
+    writing_id: i64,
+    payload: &JsonValue,
+) -> AppResult<RetryOutcome> {
+    use crate::writing::components::newsletter::handlers::{
+        newsletter_publication_payload, send_writing_email,
+    };
+
+    let strings = |key: &str| -> Vec<String> {
+        payload
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|r| r.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let all_recipients = strings("recipients");
+    let failed = strings("failedRecipients");
+    let recipients = if failed.is_empty() {
+        all_recipients.clone()
+    } else {
+        failed
+    };
+    if recipients.is_empty() {
+        return Err(AppError::validation(
+            "payload",
+            "Publication has no recipients to retry",
+        ));
+    }
+    let subject = payload
+        .get("subject")
+        .and_then(|s| s.as_str())
+        .map(String::from);
+
+    let result = send_writing_email(state, writing_id, recipients, subject).await?;
+    let (status, error_message) = if result.failed == 0 {
+        (PUBLICATION_PUBLISHED, None)
+    } else {
+        (
+            PUBLICATION_FAILED,
+            Some(format!("{} of {} recipients failed", result.failed, result.sends.len())),
+        )
+    };
+
+    Ok(RetryOutcome {
+        status,
+        error_message,
+        payload: newsletter_publication_payload(&result, &all_recipients),
+    })
+}
@@ -6,15 +6,16 @@
 use crate::core::components::errors::{AppError, AppResult};
 use crate::writing::components::knowledge_graph::entities::reference_items::*;
 use chrono::Utc;
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
-    QueryOrder, Set,
+    QueryOrder, QuerySelect, Select, Set,
 };
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 /// DTO for creating a reference
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateReferenceInput {
     pub reference_type: String, // "news_article", "url", "tweet", "paper", "book", "pdf", "manual"
@@ -28,7 +29,7 @@ pub struct CreateReferenceInput {
 }
 
 /// DTO for updating a reference
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateReferenceInput {
     pub title: Option<String>,
@@ -40,7 +41,7 @@ pub struct UpdateReferenceInput {
 }
 
 /// DTO for reference response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ReferenceDto {
     pub id: i64,
@@ -73,15 +74,8 @@ fn reference_to_dto(model: Model) -> ReferenceDto {
     }
 }
 
-/// List reference items with filtering
-#[instrument(skip(db))]
-pub async fn list_references(
-    db: &sea_orm::DatabaseConnection,
-    reference_type: Option<String>,
-    search: Option<String>,
-    limit: Option<u64>,
-    offset: Option<u64>,
-) -> AppResult<Vec<ReferenceDto>> {
+/// References matching the list filters, before sorting and pagination
+fn filtered_references(reference_type: Option<&str>, search: Option<&str>) -> Select<Entity> {
     let mut query = Entity::find();
 
     if let Some(ref_type) = reference_type {
@@ -97,15 +91,38 @@ pub async fn list_references(
         );
     }
 
-    let results = query
+    query
+}
+
+/// List reference items with filtering
+#[instrument(skip(db))]
+pub async fn list_references(
+    db: &sea_orm::DatabaseConnection,
+    reference_type: Option<String>,
+    search: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+) -> AppResult<Vec<ReferenceDto>> {
+    let results = filtered_references(reference_type.as_deref(), search.as_deref())
         .order_by_desc(Column::CreatedAt)
-        .paginate(db, limit.unwrap_or(50))
-        .fetch_page(offset.unwrap_or(0))
+        .limit(limit.unwrap_or(50))
+        .offset(offset.unwrap_or(0))
+        .all(db)
         .await?;
 
     Ok(results.into_iter().map(reference_to_dto).collect())
 }
 
+/// Number of references matching the `list_references` filters
+pub async fn count_references(
+    db: &sea_orm::DatabaseConnection,
+    reference_type: Option<&str>,
+    search: Option<&str>,
+) -> AppResult<u64> {
+    let total = filtered_references(reference_type, search).count(db).await?;
+    Ok(total)
+}
+
 /// Get a single reference by ID
 pub async fn get_reference(db: &sea_orm::DatabaseConnection, id: i64) -> AppResult<ReferenceDto> {
     let model = Entity::find_by_id(id)
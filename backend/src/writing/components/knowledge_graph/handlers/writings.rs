@@ -6,6 +6,7 @@
 use crate::core::components::errors::{AppError, AppResult};
 use crate::writing::components::knowledge_graph::entities::writings::*;
 use chrono::Utc;
+use schemars::JsonSchema;
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, PaginatorTrait, QueryFilter,
     QueryOrder, Set,
@@ -14,7 +15,7 @@ use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 /// DTO for creating a writing
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWritingInput {
     pub r#type: Option<String>, // "article", "chapter", "book"
@@ -29,7 +30,7 @@ pub struct CreateWritingInput {
 }
 
 /// DTO for updating a writing
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateWritingInput {
     pub title: Option<String>,
@@ -46,7 +47,7 @@ pub struct UpdateWritingInput {
 }
 
 /// DTO for writing response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WritingDto {
     pub id: i64,
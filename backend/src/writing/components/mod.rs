@@ -3,3 +3,4 @@
 pub mod ideas;
 pub mod article_viewer;
 pub mod knowledge_graph;
+pub mod newsletter;
@@ -0,0 +1,3 @@
+//! Database entities for newsletter delivery
+
+pub mod newsletter_sends;
@@ -0,0 +1,25 @@
+//! Newsletter Sends Entity
+//! Delivery log: one row per recipient per send (writing or digest)
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "newsletter_sends")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub kind: String, // 'writing' | 'digest'
+    pub writing_id: Option<i64>,
+    pub recipient: String,
+    pub subject: String,
+    pub status: String, // 'pending' | 'sent' | 'failed'
+    pub error_message: Option<String>,
+    pub smtp_response: Option<String>,
+    pub sent_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
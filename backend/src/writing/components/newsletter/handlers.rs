@@ -0,0 +1,292 @@
+//! Newsletter delivery handlers
+//!
+//! Sends a writing or a digest of recent news articles to a recipient list,
+//! logging every recipient in `newsletter_sends`.
+
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, QueryOrder,
+    QuerySelect, Set,
+};
+use tracing::{error, info, instrument, warn};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::research::components::feed::entities::articles::{
+    self as news_articles, Entity as NewsArticles,
+};
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::writing::components::knowledge_graph::handlers::publications::{
+    record_publication, RecordPublicationInput, PUBLICATION_FAILED, PUBLICATION_PUBLISHED,
+};
+use crate::writing::text::content_to_html;
+use crate::AppState;
+
+use super::entities::newsletter_sends::{
+    self, ActiveModel as ActiveSend, Entity as NewsletterSends,
+};
+use super::mailer::Mailer;
+use super::template::{load_template, render_digest_body, render_email, DigestItem, RenderedEmail};
+use super::types::{
+    send_to_dto, ListNewsletterSendsInput, NewsletterSendDto, NewsletterSendResult,
+    SendNewsDigestInput, SendWritingNewsletterInput,
+};
+
+/// Email a writing to the given (or configured) recipients
+///
+/// Each send is also recorded as a `newsletter` writing publication so it
+/// shows up in cross-post tracking and can be retried.
+#[instrument(skip(state, input), fields(writing_id = input.writing_id))]
+pub async fn send_writing_newsletter_handler(
+    input: SendWritingNewsletterInput,
+    state: &AppState,
+) -> AppResult<NewsletterSendResult> {
+    let recipients = resolve_recipients(input.recipients, state)?;
+    let mut result =
+        send_writing_email(state, input.writing_id, recipients.clone(), input.subject).await?;
+
+    let status = if result.failed == 0 { PUBLICATION_PUBLISHED } else { PUBLICATION_FAILED };
+    let publication = record_publication(
+        &state.db,
+        RecordPublicationInput {
+            writing_id: input.writing_id,
+            platform: "newsletter".to_string(),
+            remote_id: None,
+            url: None,
+            status: Some(status.to_string()),
+            error_message: (result.failed > 0).then(|| {
+                format!("{} of {} recipients failed", result.failed, result.sends.len())
+            }),
+            payload: Some(newsletter_publication_payload(&result, &recipients)),
+        },
+    )
+    .await?;
+    result.publication_id = Some(publication.id);
+
+    Ok(result)
+}
+
+/// Render a writing and send it to `recipients`, logging each attempt
+pub(crate) async fn send_writing_email(
+    state: &AppState,
+    writing_id: i64,
+    recipients: Vec<String>,
+    subject: Option<String>,
+) -> AppResult<NewsletterSendResult> {
+    let writing = writings::Entity::find_by_id(writing_id)
+        .one(&state.db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Writing {} not found", writing_id)))?;
+
+    let mailer = Mailer::from_config(&state.config.current().email)?;
+
+    let subject = subject.unwrap_or_else(|| writing.title.clone());
+    let body_html = content_to_html(&writing.content_markdown);
+    // Markdown reads well as the plain-text alternative
+    let body_text = html2md::parse_html(&body_html);
+    let preheader = writing.excerpt.clone().unwrap_or_default();
+
+    let template = load_template(state.config.current().email.template_path.as_deref());
+    let email = render_email(&template, &writing.title, &preheader, &body_html, &body_text);
+
+    info!(recipients = recipients.len(), "Sending writing newsletter");
+    deliver(state, &mailer, "writing", Some(writing.id), &subject, &email, recipients).await
+}
+
+/// Retry data stored on the `newsletter` publication row
+pub(crate) fn newsletter_publication_payload(
+    result: &NewsletterSendResult,
+    recipients: &[String],
+) -> serde_json::Value {
+    let failed: Vec<&str> = result
+        .sends
+        .iter()
+        .filter(|s| s.status == "failed")
+        .map(|s| s.recipient.as_str())
+        .collect();
+    serde_json::json!({
+        "subject": result.subject,
+        "recipients": recipients,
+        "failedRecipients": failed,
+    })
+}
+
+/// Email a digest of recent news articles
+#[instrument(skip(state, input))]
+pub async fn send_news_digest_handler(
+    input: SendNewsDigestInput,
+    state: &AppState,
+) -> AppResult<NewsletterSendResult> {
+    let recipients = resolve_recipients(input.recipients, state)?;
+    let mailer = Mailer::from_config(&state.config.current().email)?;
+
+    let days = input.days.unwrap_or(7).max(1);
+    let items = digest_items(
+        &state.db,
+        days,
+        input.limit.unwrap_or(20),
+        input.starred_only == Some(true),
+    )
+    .await?;
+
+    let title = input
+        .subject
+        .clone()
+        .unwrap_or_else(|| format!("News digest — {}", Utc::now().format("%B %-d, %Y")));
+    let preheader = format!("{} articles from the last {} days", items.len(), days);
+    let (body_html, body_text) = render_digest_body(&items);
+
+    let template = load_template(state.config.current().email.template_path.as_deref());
+    let email = render_email(&template, &title, &preheader, &body_html, &body_text);
+
+    info!(recipients = recipients.len(), articles = items.len(), "Sending news digest");
+    deliver(state, &mailer, "digest", None, &title, &email, recipients).await
+}
+
+/// Articles fetched in the last `days` days, starred first
+pub(crate) async fn digest_items(
+    db: &sea_orm::DatabaseConnection,
+    days: i64,
+    limit: u64,
+    starred_only: bool,
+) -> AppResult<Vec<DigestItem>> {
+    let since = Utc::now() - chrono::Duration::days(days);
+
+    let mut query = NewsArticles::find()
+        .filter(news_articles::Column::UserId.eq(1))
+        .filter(news_articles::Column::IsDismissed.eq(0))
+        .filter(news_articles::Column::FetchedAt.gte(since));
+    if starred_only {
+        query = query.filter(news_articles::Column::IsStarred.eq(1));
+    }
+    let articles = query
+        .order_by_desc(news_articles::Column::IsStarred)
+        .order_by_desc(news_articles::Column::PublishedAt)
+        .limit(limit)
+        .all(db)
+        .await?;
+
+    if articles.is_empty() {
+        return Err(AppError::validation(
+            "articles",
+            format!("No articles in the last {} days to include in a digest", days),
+        ));
+    }
+
+    Ok(articles
+        .into_iter()
+        .map(|a| DigestItem {
+            title: a.title,
+            url: a.url,
+            source: a.source_name.or(a.source_domain),
+            excerpt: a.excerpt,
+        })
+        .collect())
+}
+
+/// List newsletter send log entries (newest first)
+#[instrument(skip(state))]
+pub async fn list_newsletter_sends_handler(
+    input: ListNewsletterSendsInput,
+    state: &AppState,
+) -> AppResult<Vec<NewsletterSendDto>> {
+    let mut query = NewsletterSends::find();
+    if let Some(writing_id) = input.writing_id {
+        query = query.filter(newsletter_sends::Column::WritingId.eq(writing_id));
+    }
+    if let Some(status) = input.status {
+        query = query.filter(newsletter_sends::Column::Status.eq(status));
+    }
+
+    let rows = query
+        .order_by_desc(newsletter_sends::Column::CreatedAt)
+        .order_by_desc(newsletter_sends::Column::Id)
+        .limit(input.limit.unwrap_or(100))
+        .all(&state.db)
+        .await?;
+
+    Ok(rows.into_iter().map(send_to_dto).collect())
+}
+
+/// Use explicit recipients if given, otherwise NEWSLETTER_RECIPIENTS
+fn resolve_recipients(explicit: Option<Vec<String>>, state: &AppState) -> AppResult<Vec<String>> {
+    let mut recipients: Vec<String> = explicit
+        .unwrap_or_else(|| state.config.current().email.default_recipients.clone())
+        .into_iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    recipients.retain(|r| seen.insert(r.to_lowercase()));
+
+    if recipients.is_empty() {
+        return Err(AppError::ConfigValidation {
+            field: "NEWSLETTER_RECIPIENTS".to_string(),
+            reason: "No recipients given".to_string(),
+            suggestion: Some(
+                "Pass recipients explicitly or set NEWSLETTER_RECIPIENTS in ~/.cockpit/.env"
+                    .to_string(),
+            ),
+        });
+    }
+    Ok(recipients)
+}
+
+/// Send to each recipient individually, recording a log row per attempt
+async fn deliver(
+    state: &AppState,
+    mailer: &Mailer,
+    kind: &str,
+    writing_id: Option<i64>,
+    subject: &str,
+    email: &RenderedEmail,
+    recipients: Vec<String>,
+) -> AppResult<NewsletterSendResult> {
+    let mut sends = Vec::with_capacity(recipients.len());
+    let mut sent = 0;
+    let mut failed = 0;
+
+    for recipient in recipients {
+        let row = ActiveSend {
+            kind: Set(kind.to_string()),
+            writing_id: Set(writing_id),
+            recipient: Set(recipient.clone()),
+            subject: Set(subject.to_string()),
+            status: Set("pending".to_string()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&state.db)
+        .await?;
+
+        let mut active = row.into_active_model();
+        match mailer.send(&recipient, subject, &email.html, &email.text).await {
+            Ok(response) => {
+                sent += 1;
+                active.status = Set("sent".to_string());
+                active.smtp_response = Set(Some(response));
+                active.sent_at = Set(Some(Utc::now()));
+            }
+            Err(e) => {
+                failed += 1;
+                error!(target: "newsletter", recipient = %recipient, "Send failed: {}", e);
+                active.status = Set("failed".to_string());
+                active.error_message = Set(Some(e.to_string()));
+            }
+        }
+        sends.push(send_to_dto(active.update(&state.db).await?));
+    }
+
+    if failed > 0 {
+        warn!(target: "newsletter", sent, failed, "Newsletter delivered with failures");
+    } else {
+        info!(target: "newsletter", sent, "Newsletter delivered");
+    }
+
+    Ok(NewsletterSendResult {
+        subject: subject.to_string(),
+        sent,
+        failed,
+        sends,
+        publication_id: None,
+    })
+}
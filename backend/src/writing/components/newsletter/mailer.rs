@@ -0,0 +1,90 @@
+//! SMTP transport
+//!
+//! Thin wrapper around lettre's async SMTP transport driven by `EmailConfig`.
+
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::config::{EmailConfig, SmtpTls};
+
+pub(crate) struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    /// Build a transport from config; fails fast when SMTP isn't configured
+    pub(crate) fn from_config(config: &EmailConfig) -> AppResult<Self> {
+        let (Some(host), Some(from)) = (&config.smtp_host, &config.from_address) else {
+            return Err(AppError::ConfigValidation {
+                field: "SMTP_HOST".to_string(),
+                reason: "SMTP is not configured".to_string(),
+                suggestion: Some("Set SMTP_HOST and SMTP_FROM in ~/.cockpit/.env".to_string()),
+            });
+        };
+
+        let from: Mailbox = from.parse().map_err(|e| AppError::ConfigValidation {
+            field: "SMTP_FROM".to_string(),
+            reason: format!("Invalid sender address: {}", e),
+            suggestion: Some("Use 'Name <user@example.com>' or 'user@example.com'".to_string()),
+        })?;
+
+        let builder = match config.smtp_tls {
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+        }
+        .map_err(|e| AppError::config(format!("SMTP transport setup failed: {}", e)))?;
+
+        let mut builder = builder
+            .port(config.smtp_port)
+            .timeout(Some(config.send_timeout));
+        if let (Some(user), Some(pass)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(user.clone(), pass.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+
+    /// Send a multipart (plain + HTML) message to a single recipient
+    ///
+    /// Returns the server's response text on success.
+    pub(crate) async fn send(
+        &self,
+        to: &str,
+        subject: &str,
+        html: &str,
+        text: &str,
+    ) -> AppResult<String> {
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| AppError::validation("recipient", format!("{}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .multipart(MultiPart::alternative_plain_html(
+                text.to_string(),
+                html.to_string(),
+            ))
+            .map_err(|e| AppError::other(format!("Failed to build email: {}", e)))?;
+
+        let response = self
+            .transport
+            .send(message)
+            .await
+            .map_err(|e| AppError::other(format!("SMTP send failed: {}", e)))?;
+
+        Ok(format!(
+            "{} {}",
+            response.code(),
+            response.message().collect::<Vec<_>>().join(" ")
+        ))
+    }
+}
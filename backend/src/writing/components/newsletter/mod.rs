@@ -0,0 +1,23 @@
+//! Newsletter delivery module
+//!
+//! Sends published writings or news digests by email over SMTP:
+//! - entities: `newsletter_sends` delivery log
+//! - types: Inputs and DTOs
+//! - template: HTML layout with `{{placeholder}}` slots
+//! - mailer: SMTP transport (lettre)
+//! - handlers: Send + log listing
+
+pub mod entities;
+pub mod types;
+pub mod template;
+pub mod mailer;
+pub mod handlers;
+
+pub use types::{
+    ListNewsletterSendsInput, NewsletterSendDto, NewsletterSendResult, SendNewsDigestInput,
+    SendWritingNewsletterInput,
+};
+
+pub use handlers::{
+    list_newsletter_sends_handler, send_news_digest_handler, send_writing_newsletter_handler,
+};
@@ -0,0 +1,138 @@
+//! Newsletter templating
+//!
+//! A template is an HTML document with `{{placeholder}}` slots:
+//! - `{{title}}`: email heading (escaped)
+//! - `{{preheader}}`: inbox preview line (escaped)
+//! - `{{content}}`: rendered body HTML (inserted as-is)
+//! - `{{date}}`: send date, e.g. "March 4, 2025"
+//!
+//! The built-in layout can be replaced via NEWSLETTER_TEMPLATE_PATH.
+
+use std::path::Path;
+use tracing::warn;
+
+use crate::writing::text::escape_html;
+
+const DEFAULT_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{title}}</title>
+</head>
+<body style="margin:0;padding:0;background:#f4f4f5;">
+<span style="display:none;max-height:0;overflow:hidden;">{{preheader}}</span>
+<table role="presentation" width="100%" cellpadding="0" cellspacing="0" style="background:#f4f4f5;">
+<tr><td align="center" style="padding:24px 12px;">
+<table role="presentation" width="100%" cellpadding="0" cellspacing="0" style="max-width:640px;background:#ffffff;border-radius:8px;">
+<tr><td style="padding:32px;font-family:Georgia,'Times New Roman',serif;font-size:17px;line-height:1.6;color:#18181b;">
+<h1 style="margin:0 0 8px;font-size:28px;line-height:1.25;">{{title}}</h1>
+<p style="margin:0 0 24px;font-size:13px;color:#71717a;">{{date}}</p>
+{{content}}
+</td></tr>
+</table>
+</td></tr>
+</table>
+</body>
+</html>
+"#;
+
+/// Rendered email ready for sending
+pub(crate) struct RenderedEmail {
+    pub html: String,
+    pub text: String,
+}
+
+/// Load the configured template, falling back to the built-in layout
+pub(crate) fn load_template(path: Option<&Path>) -> String {
+    if let Some(p) = path {
+        match std::fs::read_to_string(p) {
+            Ok(t) => return t,
+            Err(e) => warn!(
+                target: "newsletter",
+                "Failed to read template {:?}, using default: {}", p, e
+            ),
+        }
+    }
+    DEFAULT_TEMPLATE.to_string()
+}
+
+/// Fill template placeholders
+pub(crate) fn render_template(
+    template: &str,
+    title: &str,
+    preheader: &str,
+    content_html: &str,
+) -> String {
+    let date = chrono::Utc::now().format("%B %-d, %Y").to_string();
+    // Content last so placeholders inside user content are left alone
+    template
+        .replace("{{title}}", &escape_html(title))
+        .replace("{{preheader}}", &escape_html(preheader))
+        .replace("{{date}}", &date)
+        .replace("{{content}}", content_html)
+}
+
+/// Build the HTML + plain-text parts for an email
+pub(crate) fn render_email(
+    template: &str,
+    title: &str,
+    preheader: &str,
+    content_html: &str,
+    content_text: &str,
+) -> RenderedEmail {
+    RenderedEmail {
+        html: render_template(template, title, preheader, content_html),
+        text: format!("{}\n\n{}\n", title, content_text.trim()),
+    }
+}
+
+/// Digest entry used for rendering the news digest body
+pub(crate) struct DigestItem {
+    pub title: String,
+    pub url: Option<String>,
+    pub source: Option<String>,
+    pub excerpt: Option<String>,
+}
+
+/// Render the body of a news digest as (html, text)
+pub(crate) fn render_digest_body(items: &[DigestItem]) -> (String, String) {
+    let mut html = String::new();
+    let mut text = String::new();
+
+    for item in items {
+        html.push_str("<div style=\"margin:0 0 20px;\">");
+        match &item.url {
+            Some(url) => html.push_str(&format!(
+                "<h3 style=\"margin:0 0 4px;font-size:19px;\"><a href=\"{}\" style=\"color:#1d4ed8;text-decoration:none;\">{}</a></h3>",
+                escape_html(url),
+                escape_html(&item.title)
+            )),
+            None => html.push_str(&format!(
+                "<h3 style=\"margin:0 0 4px;font-size:19px;\">{}</h3>",
+                escape_html(&item.title)
+            )),
+        }
+        if let Some(source) = &item.source {
+            html.push_str(&format!(
+                "<p style=\"margin:0 0 4px;font-size:13px;color:#71717a;\">{}</p>",
+                escape_html(source)
+            ));
+        }
+        if let Some(excerpt) = &item.excerpt {
+            html.push_str(&format!("<p style=\"margin:0;\">{}</p>", escape_html(excerpt)));
+        }
+        html.push_str("</div>");
+
+        text.push_str(&format!("- {}\n", item.title));
+        if let Some(url) = &item.url {
+            text.push_str(&format!("  {}\n", url));
+        }
+        if let Some(excerpt) = &item.excerpt {
+            text.push_str(&format!("  {}\n", excerpt));
+        }
+        text.push('\n');
+    }
+
+    (html, text)
+}
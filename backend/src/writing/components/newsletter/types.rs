@@ -0,0 +1,83 @@
+//! DTOs for newsletter delivery
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::entities::newsletter_sends;
+
+/// Input for emailing a writing
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SendWritingNewsletterInput {
+    pub writing_id: i64,
+    /// Overrides NEWSLETTER_RECIPIENTS when provided
+    pub recipients: Option<Vec<String>>,
+    /// Defaults to the writing title
+    pub subject: Option<String>,
+}
+
+/// Input for emailing a digest of recent news articles
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SendNewsDigestInput {
+    pub recipients: Option<Vec<String>>,
+    pub subject: Option<String>,
+    /// Look-back window in days (default: 7)
+    pub days: Option<i64>,
+    /// Max articles included (default: 20)
+    pub limit: Option<u64>,
+    /// Only include starred articles
+    pub starred_only: Option<bool>,
+}
+
+/// Query for the send log
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListNewsletterSendsInput {
+    pub writing_id: Option<i64>,
+    pub status: Option<String>,
+    pub limit: Option<u64>,
+}
+
+/// Single send log entry
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsletterSendDto {
+    pub id: i64,
+    pub kind: String,
+    pub writing_id: Option<i64>,
+    pub recipient: String,
+    pub subject: String,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub smtp_response: Option<String>,
+    pub sent_at: Option<String>,
+    pub created_at: String,
+}
+
+/// Result of a send operation across all recipients
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewsletterSendResult {
+    pub subject: String,
+    pub sent: i32,
+    pub failed: i32,
+    pub sends: Vec<NewsletterSendDto>,
+    /// `writing_publications` row recorded for writing sends
+    pub publication_id: Option<i64>,
+}
+
+pub(crate) fn send_to_dto(m: newsletter_sends::Model) -> NewsletterSendDto {
+    NewsletterSendDto {
+        id: m.id,
+        kind: m.kind,
+        writing_id: m.writing_id,
+        recipient: m.recipient,
+        subject: m.subject,
+        status: m.status,
+        error_message: m.error_message,
+        smtp_response: m.smtp_response,
+        sent_at: m.sent_at.map(|d| d.to_rfc3339()),
+        created_at: m.created_at.to_rfc3339(),
+    }
+}
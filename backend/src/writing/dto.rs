@@ -2,11 +2,12 @@
 //!
 //! Data Transfer Objects for frontend consumption with camelCase serialization
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 /// Writing DTO for frontend consumption (TipTap JSON version)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WritingDraftDto {
     pub id: i64,
@@ -51,7 +52,7 @@ pub struct WritingVersionDto {
 }
 
 /// Input for creating a new writing (TipTap JSON version)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateWritingDraftInput {
     pub title: String,
@@ -67,7 +68,7 @@ pub struct CreateWritingDraftInput {
 }
 
 /// Input for saving draft content
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveDraftInput {
     pub writing_id: i64,
@@ -77,7 +78,7 @@ pub struct SaveDraftInput {
 }
 
 /// Input for updating writing metadata (TipTap JSON version)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateWritingDraftMetaInput {
     pub writing_id: i64,
@@ -94,14 +95,14 @@ pub struct UpdateWritingDraftMetaInput {
 }
 
 /// Input for publishing a writing
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PublishWritingInput {
     pub writing_id: i64,
 }
 
 /// Input for linking/unlinking idea to writing
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct LinkIdeaInput {
     pub writing_id: i64,
@@ -109,32 +110,244 @@ pub struct LinkIdeaInput {
 }
 
 /// Query filters for listing writings
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
-// TODO: remove allow(dead_code) once pagination is implemented for listings
-#[allow(dead_code)]
 pub struct ListWritingsQuery {
     pub status: Option<String>,
     pub writing_type: Option<String>,
     pub series_name: Option<String>,
     pub is_pinned: Option<bool>,
     pub is_featured: Option<bool>,
+    /// 1-based; ignored unless `per_page` is set
     pub page: Option<u64>,
+    /// Omit to list every match
     pub per_page: Option<u64>,
+    /// Wrap the result in a `Page` with the total count
+    pub envelope: Option<bool>,
 }
 
-#[derive(Debug, serde::Deserialize)]
+impl ListWritingsQuery {
+    /// `page`/`per_page` as a limit and offset
+    pub fn limit_offset(&self) -> (Option<u64>, u64) {
+        match self.per_page {
+            Some(per_page) => {
+                let page = self.page.unwrap_or(1).max(1);
+                (Some(per_page), (page - 1) * per_page)
+            }
+            None => (None, 0),
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetWritingInput {
     pub writing_id: i64,
 }
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, serde::Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListLinkedIdeasInput {
     pub writing_id: i64,
 }
 
+/// Input for exporting writings as Markdown files
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportWritingsMarkdownInput {
+    /// Writings to export; omit to export all of them
+    pub writing_ids: Option<Vec<i64>>,
+    /// Pack the files into a zip archive instead of a folder
+    #[serde(default)]
+    pub zip: bool,
+}
+
+/// Result of a Markdown export
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownExportDto {
+    /// Folder of Markdown files, or the zip archive holding it
+    pub path: String,
+    pub writing_count: usize,
+    pub zipped: bool,
+}
+
+/// Input for exporting a book as EPUB
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportEpubInput {
+    /// A writing of type `book`
+    pub writing_id: i64,
+}
+
+/// Result of an EPUB export
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EpubExportDto {
+    pub file_path: String,
+    pub file_size: u64,
+    pub chapter_count: usize,
+    /// Images embedded in the book
+    pub image_count: usize,
+    /// Images left out because they couldn't be downloaded
+    pub skipped_images: usize,
+}
+
+/// Paper sizes for PDF exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PageSize {
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+}
+
+/// Input for exporting a writing, or a digest of recent news articles, as PDF
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPdfInput {
+    /// Writing to render; a news digest when omitted
+    pub writing_id: Option<i64>,
+    /// Digest look-back window in days (default: 7)
+    pub days: Option<i64>,
+    /// Max digest articles (default: 20)
+    pub limit: Option<u64>,
+    /// Only include starred articles in the digest
+    pub starred_only: Option<bool>,
+    /// Paper size (default: A4)
+    pub page_size: Option<PageSize>,
+    /// Page margin in millimetres (default: 20)
+    pub margin_mm: Option<f32>,
+    /// CSS font stack for body text (default: Georgia, serif)
+    pub font_family: Option<String>,
+    /// Body text size in points (default: 11)
+    pub font_size_pt: Option<f32>,
+    /// Line height as a multiple of the font size (default: 1.5)
+    pub line_height: Option<f32>,
+}
+
+/// Input for exporting a writing as a Word document
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDocxInput {
+    pub writing_id: i64,
+}
+
+/// Input for importing an Obsidian vault
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportObsidianInput {
+    /// The vault folder, or a zip of it
+    pub vault_path: Option<String>,
+    /// A zipped vault staged through `POST /upload`, in place of `vault_path`
+    pub upload_handle: Option<String>,
+    /// Vault folders whose notes become ideas with a main note rather than
+    /// writings; `/` for the whole vault
+    #[serde(default)]
+    pub notes_folders: Vec<String>,
+}
+
+/// Result of an Obsidian vault import
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsidianImportDto {
+    pub writings_created: usize,
+    pub ideas_created: usize,
+    /// Notes whose writing slug or idea title already exists
+    pub skipped: usize,
+    /// Writing-idea and idea-reference links made from wiki-links
+    pub links_created: usize,
+    /// Wiki-link targets matching no note, idea or reference
+    pub unresolved_links: Vec<String>,
+}
+
+/// Input for importing a Notion export
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportNotionInput {
+    /// The export zip, or the folder it was unpacked into
+    pub import_path: Option<String>,
+    /// A zip staged through `POST /upload`, in place of `import_path`
+    pub upload_handle: Option<String>,
+}
+
+/// Result of a Notion export import
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NotionImportDto {
+    /// From pages and database rows
+    pub writings_created: usize,
+    /// From database rows with a URL
+    pub references_created: usize,
+    /// Reference page bodies and writings' leftover columns
+    pub notes_created: usize,
+    pub databases: usize,
+    /// Writings whose slug or references whose URL already exist
+    pub skipped: usize,
+}
+
+/// What folder sync does when a writing and its file both changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictStrategy {
+    /// Leave both as they are and report the conflict
+    #[default]
+    Report,
+    /// The file wins
+    PreferFile,
+    /// The writing wins
+    PreferApp,
+}
+
+/// Input for syncing writings with the Markdown folder
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncFolderInput {
+    /// Default: report conflicts
+    pub conflict_strategy: Option<SyncConflictStrategy>,
+}
+
+/// How a writing and its file diverged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictKind {
+    /// Both edited since the last sync, or differing on the first one
+    BothChanged,
+    /// File deleted, writing edited
+    DeletedInFolder,
+    /// Writing deleted or archived, file edited
+    DeletedInApp,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflictDto {
+    pub file_name: String,
+    pub writing_id: Option<i64>,
+    pub kind: SyncConflictKind,
+}
+
+/// Result of a folder sync
+#[derive(Debug, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderSyncDto {
+    pub folder: String,
+    /// Files written from writings
+    pub files_written: usize,
+    /// Files deleted with their writing
+    pub files_removed: usize,
+    /// Writings updated from edited files
+    pub writings_updated: usize,
+    /// Writings created from new files
+    pub writings_created: usize,
+    /// Writings archived as their file was deleted
+    pub writings_archived: usize,
+    /// Conflicts left for the user under the `report` strategy
+    pub conflicts: Vec<SyncConflictDto>,
+}
+
 // Future: Version management DTOs for migration 007
 /*
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,715 @@
+//! DOCX export for handing drafts to Word users
+//!
+//! The writing is walked as TipTap JSON (Markdown content is parsed first)
+//! and written as WordprocessingML. Headings use Word's built-in heading
+//! styles, so they show in the navigation pane; marks become run formatting
+//! and links hyperlinks; blockquotes use the Quote style and lists Word
+//! numbering. Footnote references become Word footnotes. Images aren't
+//! embedded; a placeholder with their alt text keeps their place.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Utc;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use serde_json::{json, Value as JsonValue};
+use tracing::{info, instrument};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::storage::{ExportCounts, ExportFormat, ExportInfo};
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::writing::dto::ExportDocxInput;
+use crate::writing::text::{attr, children, content_to_tiptap, node_type};
+
+use super::{export_dir, slugify, timestamp};
+
+const W_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const R_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+  <Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+  <Default Extension="xml" ContentType="application/xml"/>
+  <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+  <Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
+  <Override PartName="/word/numbering.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"/>
+  <Override PartName="/word/footnotes.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.footnotes+xml"/>
+  <Override PartName="/word/settings.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.settings+xml"/>
+  <Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/>
+</Types>
+"#;
+
+const ROOT_RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+  <Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>
+  <Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/>
+</Relationships>
+"#;
+
+/// Word expects the separator footnotes to be declared in the settings
+const SETTINGS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:settings xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:footnotePr><w:footnote w:id="-1"/><w:footnote w:id="0"/></w:footnotePr>
+</w:settings>
+"#;
+
+/// Relationships of the document part before the hyperlinks
+const FIXED_RELATIONSHIPS: [(&str, &str); 4] = [
+    ("styles", "styles.xml"),
+    ("numbering", "numbering.xml"),
+    ("footnotes", "footnotes.xml"),
+    ("settings", "settings.xml"),
+];
+
+/// Numbering instance of bullet lists; ordered lists get one each after it
+const BULLET_NUM_ID: usize = 1;
+
+/// Paragraph properties passed down to nested blocks
+#[derive(Clone, Copy, Default)]
+struct Context {
+    style: Option<&'static str>,
+    /// Numbering instance and level of a list item's first paragraph
+    numbering: Option<(usize, usize)>,
+    /// List level the paragraph is indented to, after the first
+    indent: Option<usize>,
+    /// List nesting depth
+    depth: usize,
+}
+
+#[derive(Default)]
+struct DocxWriter {
+    /// Footnote definitions by id
+    definitions: HashMap<String, JsonValue>,
+    /// Ids of referenced footnotes, in order; Word numbers them from 1
+    referenced: Vec<String>,
+    /// Hyperlink targets, numbered after the fixed relationships
+    links: Vec<String>,
+    /// (start, level) of each ordered list
+    ordered_lists: Vec<(u64, usize)>,
+}
+
+/// Export a writing as a Word document in the export directory
+#[instrument(skip(db, storage_config))]
+pub async fn export_writing_docx(
+    db: &DatabaseConnection,
+    storage_config: &StorageConfig,
+    input: &ExportDocxInput,
+) -> AppResult<ExportInfo> {
+    let writing = writings::Entity::find_by_id(input.writing_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Writing not found: {}", input.writing_id)))?;
+
+    let doc = content_to_tiptap(&writing.content_markdown);
+    let mut writer = DocxWriter::default();
+    collect_footnotes(&doc, &mut writer.definitions);
+    let mut body = paragraph(Some("Title"), "", &run(&writing.title, ""));
+    writer.blocks(&doc, Context::default(), &mut body);
+    let footnotes = writer.footnotes_xml();
+
+    let name = match slugify(writing.slug.as_deref().unwrap_or(&writing.title)) {
+        slug if slug.is_empty() => format!("writing-{}", writing.id),
+        slug => slug,
+    };
+    let timestamp = timestamp();
+    let path = export_dir(storage_config)?.join(format!("{}_{}.docx", name, timestamp));
+    let parts = [
+        ("[Content_Types].xml", CONTENT_TYPES_XML.to_string()),
+        ("_rels/.rels", ROOT_RELS_XML.to_string()),
+        ("docProps/core.xml", core_xml(&writing)),
+        ("word/document.xml", document_xml(&body)),
+        (
+            "word/_rels/document.xml.rels",
+            writer.relationships_xml(true),
+        ),
+        ("word/styles.xml", styles_xml()),
+        ("word/numbering.xml", writer.numbering_xml()),
+        ("word/footnotes.xml", footnotes),
+        (
+            "word/_rels/footnotes.xml.rels",
+            writer.relationships_xml(false),
+        ),
+        ("word/settings.xml", SETTINGS_XML.to_string()),
+    ];
+    write_docx(&path, &parts)
+        .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+    let file_size = fs::metadata(&path)
+        .map_err(|e| AppError::file_operation("read metadata", path.to_string_lossy(), e))?
+        .len();
+
+    info!(
+        path = %path.display(),
+        footnotes = writer.referenced.len(),
+        links = writer.links.len(),
+        "Exported writing as DOCX"
+    );
+
+    Ok(ExportInfo {
+        file_path: path.to_string_lossy().to_string(),
+        file_size,
+        timestamp,
+        format: ExportFormat::Docx,
+        manifest_path: None,
+        record_counts: ExportCounts {
+            ideas: 0,
+            news_articles: 0,
+            app_settings: 0,
+        },
+        group_counts: BTreeMap::from([("writings".to_string(), 1)]),
+        encrypted: false,
+    })
+}
+
+fn write_docx(path: &Path, parts: &[(&str, String)]) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in parts {
+        zip.start_file(*name, options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Footnote definitions anywhere in the document, by id
+fn collect_footnotes(node: &JsonValue, definitions: &mut HashMap<String, JsonValue>) {
+    if node_type(node) == "footnote" {
+        if let Some(id) = footnote_id(node) {
+            definitions.insert(id, node.clone());
+        }
+        return;
+    }
+    for child in children(node) {
+        collect_footnotes(child, definitions);
+    }
+}
+
+/// The `id` of a footnote or footnote reference, string or number
+fn footnote_id(node: &JsonValue) -> Option<String> {
+    match attr(node, "id")? {
+        JsonValue::String(id) => Some(id.clone()),
+        JsonValue::Null => None,
+        id => Some(id.to_string()),
+    }
+}
+
+impl DocxWriter {
+    fn blocks(&mut self, node: &JsonValue, ctx: Context, out: &mut String) {
+        for child in children(node) {
+            self.block(child, ctx, out);
+        }
+    }
+
+    fn block(&mut self, node: &JsonValue, ctx: Context, out: &mut String) {
+        match node_type(node) {
+            "paragraph" => {
+                let runs = self.runs(node);
+                out.push_str(&paragraph(ctx.style, &list_properties(ctx), &runs));
+            }
+            "heading" => {
+                let level = attr(node, "level")
+                    .and_then(|l| l.as_u64())
+                    .unwrap_or(2)
+                    .clamp(1, 6);
+                let runs = self.runs(node);
+                out.push_str(&paragraph(
+                    Some(HEADING_STYLES[level as usize - 1]),
+                    &list_properties(ctx),
+                    &runs,
+                ));
+            }
+            "blockquote" => self.blocks(
+                node,
+                Context {
+                    style: Some("Quote"),
+                    ..ctx
+                },
+                out,
+            ),
+            "bulletList" | "orderedList" => {
+                let num_id = if node_type(node) == "bulletList" {
+                    BULLET_NUM_ID
+                } else {
+                    let start = attr(node, "start").and_then(|s| s.as_u64()).unwrap_or(1);
+                    self.ordered_lists.push((start, ctx.depth));
+                    BULLET_NUM_ID + self.ordered_lists.len()
+                };
+                for item in children(node) {
+                    // The first paragraph carries the number, the rest line up
+                    for (i, child) in children(item).iter().enumerate() {
+                        let item_ctx = Context {
+                            numbering: (i == 0).then_some((num_id, ctx.depth)),
+                            indent: (i > 0).then_some(ctx.depth),
+                            depth: ctx.depth + 1,
+                            ..ctx
+                        };
+                        self.block(child, item_ctx, out);
+                    }
+                }
+            }
+            "codeBlock" => {
+                let code: String = children(node)
+                    .iter()
+                    .filter_map(|t| t.get("text").and_then(|t| t.as_str()))
+                    .collect();
+                out.push_str(&paragraph(Some("Code"), &list_properties(ctx), &run(&code, "")));
+            }
+            "horizontalRule" => out.push_str(&paragraph(
+                ctx.style,
+                r#"<w:pBdr><w:bottom w:val="single" w:sz="6" w:space="1" w:color="auto"/></w:pBdr>"#,
+                "",
+            )),
+            "image" => {
+                let runs = self.runs(&json!({ "content": [node] }));
+                out.push_str(&paragraph(ctx.style, &list_properties(ctx), &runs));
+            }
+            // Written to the footnotes part when referenced
+            "footnotes" | "footnote" => {}
+            "text" | "hardBreak" | "footnoteReference" => {
+                let runs = self.runs(&json!({ "content": [node] }));
+                out.push_str(&paragraph(ctx.style, &list_properties(ctx), &runs));
+            }
+            _ => self.blocks(node, ctx, out),
+        }
+    }
+
+    fn runs(&mut self, node: &JsonValue) -> String {
+        let mut out = String::new();
+        for child in children(node) {
+            self.inline(child, &mut out);
+        }
+        out
+    }
+
+    fn inline(&mut self, node: &JsonValue, out: &mut String) {
+        match node_type(node) {
+            "text" => {
+                let text = node.get("text").and_then(|t| t.as_str()).unwrap_or("");
+                let marks = node
+                    .get("marks")
+                    .and_then(|m| m.as_array())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let has = |name: &str| marks.iter().any(|m| node_type(m) == name);
+                let href = marks
+                    .iter()
+                    .find(|m| node_type(m) == "link")
+                    .and_then(|m| attr(m, "href"))
+                    .and_then(|h| h.as_str());
+
+                // Run properties in schema order
+                let mut properties = String::new();
+                if href.is_some() {
+                    properties.push_str(r#"<w:rStyle w:val="Hyperlink"/>"#);
+                }
+                if has("code") {
+                    properties.push_str(
+                        r#"<w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Consolas"/>"#,
+                    );
+                }
+                for (mark, tag) in [
+                    ("bold", "<w:b/>"),
+                    ("italic", "<w:i/>"),
+                    ("strike", "<w:strike/>"),
+                ] {
+                    if has(mark) {
+                        properties.push_str(tag);
+                    }
+                }
+                if has("underline") {
+                    properties.push_str(r#"<w:u w:val="single"/>"#);
+                }
+
+                let text_run = run(text, &properties);
+                match href {
+                    Some(href) => out.push_str(&format!(
+                        "<w:hyperlink r:id=\"{}\">{}</w:hyperlink>",
+                        self.link(href),
+                        text_run
+                    )),
+                    None => out.push_str(&text_run),
+                }
+            }
+            "hardBreak" => out.push_str("<w:r><w:br/></w:r>"),
+            "footnoteReference" => {
+                let id = footnote_id(node).unwrap_or_default();
+                if !self.definitions.contains_key(&id) {
+                    out.push_str(&run(
+                        &format!("[{}]", id),
+                        r#"<w:vertAlign w:val="superscript"/>"#,
+                    ));
+                    return;
+                }
+                let number = match self.referenced.iter().position(|r| *r == id) {
+                    Some(i) => i + 1,
+                    None => {
+                        self.referenced.push(id);
+                        self.referenced.len()
+                    }
+                };
+                out.push_str(&format!(
+                    r#"<w:r><w:rPr><w:rStyle w:val="FootnoteReference"/></w:rPr><w:footnoteReference w:id="{}"/></w:r>"#,
+                    number
+                ));
+            }
+            "image" => {
+                let label = attr(node, "alt")
+                    .or_else(|| attr(node, "src"))
+                    .and_then(|a| a.as_str())
+                    .unwrap_or("");
+                out.push_str(&run(&format!("[Image: {}]", label), "<w:i/>"));
+            }
+            _ => {
+                for child in children(node) {
+                    self.inline(child, out);
+                }
+            }
+        }
+    }
+
+    /// Relationship id of a hyperlink target
+    fn link(&mut self, href: &str) -> String {
+        let index = match self.links.iter().position(|l| l == href) {
+            Some(i) => i,
+            None => {
+                self.links.push(href.to_string());
+                self.links.len() - 1
+            }
+        };
+        format!("rId{}", FIXED_RELATIONSHIPS.len() + index + 1)
+    }
+
+    /// Referenced footnotes, including those referenced from other footnotes
+    fn footnotes_xml(&mut self) -> String {
+        let mut notes = String::new();
+        let mut i = 0;
+        while i < self.referenced.len() {
+            let definition = self.definitions[&self.referenced[i]].clone();
+            let mut body = String::new();
+            self.blocks(
+                &definition,
+                Context {
+                    style: Some("FootnoteText"),
+                    ..Context::default()
+                },
+                &mut body,
+            );
+            // The footnote's own number opens its first paragraph
+            let mark = r#"<w:r><w:rPr><w:rStyle w:val="FootnoteReference"/></w:rPr><w:footnoteRef/></w:r><w:r><w:t xml:space="preserve"> </w:t></w:r>"#;
+            match body.find("</w:pPr>") {
+                Some(at) => body.insert_str(at + "</w:pPr>".len(), mark),
+                None => body = paragraph(Some("FootnoteText"), "", mark),
+            }
+            i += 1;
+            notes.push_str(&format!("<w:footnote w:id=\"{}\">{}</w:footnote>", i, body));
+        }
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:footnotes xmlns:w=\"{}\" xmlns:r=\"{}\">\
+             <w:footnote w:type=\"separator\" w:id=\"-1\"><w:p><w:pPr><w:spacing w:after=\"0\" w:line=\"240\" w:lineRule=\"auto\"/></w:pPr><w:r><w:separator/></w:r></w:p></w:footnote>\
+             <w:footnote w:type=\"continuationSeparator\" w:id=\"0\"><w:p><w:pPr><w:spacing w:after=\"0\" w:line=\"240\" w:lineRule=\"auto\"/></w:pPr><w:r><w:continuationSeparator/></w:r></w:p></w:footnote>\
+             {}</w:footnotes>\n",
+            W_NS, R_NS, notes
+        )
+    }
+
+    /// Relationships of the document part, or of the footnotes part, which
+    /// only needs the hyperlinks
+    fn relationships_xml(&self, document: bool) -> String {
+        let mut relationships = Vec::new();
+        if document {
+            for (i, (kind, target)) in FIXED_RELATIONSHIPS.iter().enumerate() {
+                relationships.push(format!(
+                    "<Relationship Id=\"rId{}\" Type=\"{}/{}\" Target=\"{}\"/>",
+                    i + 1,
+                    R_NS,
+                    kind,
+                    target
+                ));
+            }
+        }
+        for (i, href) in self.links.iter().enumerate() {
+            relationships.push(format!(
+                "<Relationship Id=\"rId{}\" Type=\"{}/hyperlink\" Target=\"{}\" TargetMode=\"External\"/>",
+                FIXED_RELATIONSHIPS.len() + i + 1,
+                R_NS,
+                escape_xml(href)
+            ));
+        }
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\n  {}\n</Relationships>\n",
+            relationships.join("\n  ")
+        )
+    }
+
+    /// A bullet and a numbered list definition, and an instance per ordered
+    /// list so each starts over
+    fn numbering_xml(&self) -> String {
+        let levels = |ordered: bool| -> String {
+            (0..9)
+                .map(|level| {
+                    let (format, text) = if ordered {
+                        let format = ["decimal", "lowerLetter", "lowerRoman"][level % 3];
+                        (format, format!("%{}.", level + 1))
+                    } else {
+                        ("bullet", ["•", "◦", "▪"][level % 3].to_string())
+                    };
+                    format!(
+                        "<w:lvl w:ilvl=\"{}\"><w:start w:val=\"1\"/><w:numFmt w:val=\"{}\"/>\
+                         <w:lvlText w:val=\"{}\"/><w:lvlJc w:val=\"left\"/>\
+                         <w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+                        level,
+                        format,
+                        text,
+                        720 * (level + 1)
+                    )
+                })
+                .collect()
+        };
+        let mut instances = format!(
+            "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"0\"/></w:num>",
+            BULLET_NUM_ID
+        );
+        for (i, (start, level)) in self.ordered_lists.iter().enumerate() {
+            instances.push_str(&format!(
+                "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"1\"/>\
+                 <w:lvlOverride w:ilvl=\"{}\"><w:startOverride w:val=\"{}\"/></w:lvlOverride></w:num>",
+                BULLET_NUM_ID + i + 1,
+                level,
+                start
+            ));
+        }
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:numbering xmlns:w=\"{}\">\
+             <w:abstractNum w:abstractNumId=\"0\"><w:multiLevelType w:val=\"hybridMultilevel\"/>{}</w:abstractNum>\
+             <w:abstractNum w:abstractNumId=\"1\"><w:multiLevelType w:val=\"hybridMultilevel\"/>{}</w:abstractNum>\
+             {}</w:numbering>\n",
+            W_NS,
+            levels(false),
+            levels(true),
+            instances
+        )
+    }
+}
+
+const HEADING_STYLES: [&str; 6] = [
+    "Heading1", "Heading2", "Heading3", "Heading4", "Heading5", "Heading6",
+];
+
+/// Numbering or indentation of a paragraph inside a list
+fn list_properties(ctx: Context) -> String {
+    match (ctx.numbering, ctx.indent) {
+        (Some((num_id, level)), _) => format!(
+            "<w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{}\"/></w:numPr>",
+            level, num_id
+        ),
+        (None, Some(level)) => format!("<w:ind w:left=\"{}\"/>", 720 * (level + 1)),
+        (None, None) => String::new(),
+    }
+}
+
+/// A paragraph; `properties` follow the style in schema order
+fn paragraph(style: Option<&str>, properties: &str, runs: &str) -> String {
+    let style = style
+        .map(|s| format!("<w:pStyle w:val=\"{}\"/>", s))
+        .unwrap_or_default();
+    if style.is_empty() && properties.is_empty() {
+        return format!("<w:p>{}</w:p>", runs);
+    }
+    format!("<w:p><w:pPr>{}{}</w:pPr>{}</w:p>", style, properties, runs)
+}
+
+/// A run of text, with line breaks and tabs as Word elements
+fn run(text: &str, properties: &str) -> String {
+    let mut content = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            content.push_str("<w:br/>");
+        }
+        for (j, part) in line.split('\t').enumerate() {
+            if j > 0 {
+                content.push_str("<w:tab/>");
+            }
+            if !part.is_empty() {
+                content.push_str(&format!(
+                    "<w:t xml:space=\"preserve\">{}</w:t>",
+                    escape_xml(part)
+                ));
+            }
+        }
+    }
+    if properties.is_empty() {
+        format!("<w:r>{}</w:r>", content)
+    } else {
+        format!("<w:r><w:rPr>{}</w:rPr>{}</w:r>", properties, content)
+    }
+}
+
+/// Escapes XML text, leaving out control characters XML can't hold
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(ch),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn document_xml(body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <w:document xmlns:w=\"{}\" xmlns:r=\"{}\"><w:body>{}</w:body></w:document>\n",
+        W_NS, R_NS, body
+    )
+}
+
+fn core_xml(writing: &writings::Model) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\
+         <dc:title>{}</dc:title>\
+         <dcterms:created xsi:type=\"dcterms:W3CDTF\">{}</dcterms:created>\
+         <dcterms:modified xsi:type=\"dcterms:W3CDTF\">{}</dcterms:modified>\
+         </cp:coreProperties>\n",
+        escape_xml(&writing.title),
+        writing.created_at.format("%Y-%m-%dT%H:%M:%SZ"),
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+    )
+}
+
+/// Word's built-in names for the styles used, so they map onto the user's
+fn styles_xml() -> String {
+    let paragraph_style = |id: &str, name: &str, ppr: &str, rpr: &str| {
+        format!(
+            "<w:style w:type=\"paragraph\" w:styleId=\"{}\"><w:name w:val=\"{}\"/>\
+             <w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/><w:qFormat/>\
+             <w:pPr>{}</w:pPr><w:rPr>{}</w:rPr></w:style>",
+            id, name, ppr, rpr
+        )
+    };
+    let mut styles = vec![
+        r#"<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style>"#.to_string(),
+        paragraph_style(
+            "Title",
+            "Title",
+            r#"<w:spacing w:after="240"/>"#,
+            r#"<w:sz w:val="52"/><w:szCs w:val="52"/>"#,
+        ),
+    ];
+    for (i, size) in [36, 32, 28, 26, 24, 22].into_iter().enumerate() {
+        styles.push(paragraph_style(
+            HEADING_STYLES[i],
+            &format!("heading {}", i + 1),
+            &format!(
+                "<w:keepNext/><w:spacing w:before=\"240\" w:after=\"80\"/><w:outlineLvl w:val=\"{}\"/>",
+                i
+            ),
+            &format!("<w:b/><w:sz w:val=\"{0}\"/><w:szCs w:val=\"{0}\"/>", size),
+        ));
+    }
+    styles.push(paragraph_style(
+        "Quote",
+        "Quote",
+        r#"<w:ind w:left="720" w:right="720"/>"#,
+        r#"<w:i/><w:color w:val="595959"/>"#,
+    ));
+    styles.push(paragraph_style(
+        "Code",
+        "Code",
+        r#"<w:shd w:val="clear" w:color="auto" w:fill="F4F4F5"/><w:spacing w:after="160" w:line="240" w:lineRule="auto"/>"#,
+        r#"<w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:cs="Consolas"/><w:sz w:val="20"/><w:szCs w:val="20"/>"#,
+    ));
+    styles.push(paragraph_style(
+        "FootnoteText",
+        "footnote text",
+        r#"<w:spacing w:after="0" w:line="240" w:lineRule="auto"/>"#,
+        r#"<w:sz w:val="20"/><w:szCs w:val="20"/>"#,
+    ));
+    styles.push(
+        r#"<w:style w:type="character" w:styleId="FootnoteReference"><w:name w:val="footnote reference"/><w:rPr><w:vertAlign w:val="superscript"/></w:rPr></w:style>"#.to_string(),
+    );
+    styles.push(
+        r#"<w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:rPr><w:color w:val="0563C1"/><w:u w:val="single"/></w:rPr></w:style>"#.to_string(),
+    );
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <w:styles xmlns:w=\"{}\"><w:docDefaults>\
+         <w:rPrDefault><w:rPr><w:rFonts w:ascii=\"Calibri\" w:hAnsi=\"Calibri\" w:eastAsia=\"Calibri\" w:cs=\"Calibri\"/>\
+         <w:sz w:val=\"22\"/><w:szCs w:val=\"22\"/><w:lang w:val=\"en-US\"/></w:rPr></w:rPrDefault>\
+         <w:pPrDefault><w:pPr><w:spacing w:after=\"160\" w:line=\"276\" w:lineRule=\"auto\"/></w:pPr></w:pPrDefault>\
+         </w:docDefaults>{}</w:styles>\n",
+        W_NS,
+        styles.join("")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_body() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                { "type": "heading", "attrs": { "level": 2 }, "content": [{ "type": "text", "text": "Intro" }] },
+                {
+                    "type": "blockquote",
+                    "content": [{
+                        "type": "paragraph",
+                        "content": [
+                            { "type": "text", "text": "A & B", "marks": [{ "type": "italic" }, { "type": "link", "attrs": { "href": "https://example.com" } }] },
+                            { "type": "footnoteReference", "attrs": { "id": "n" } }
+                        ]
+                    }]
+                },
+                {
+                    "type": "orderedList",
+                    "attrs": { "start": 3 },
+                    "content": [{ "type": "listItem", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Three" }] }] }]
+                },
+                {
+                    "type": "footnotes",
+                    "content": [{ "type": "footnote", "attrs": { "id": "n" }, "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "Note" }] }] }]
+                }
+            ]
+        });
+        let mut writer = DocxWriter::default();
+        collect_footnotes(&doc, &mut writer.definitions);
+        let mut body = String::new();
+        writer.blocks(&doc, Context::default(), &mut body);
+
+        assert_eq!(
+            body,
+            "<w:p><w:pPr><w:pStyle w:val=\"Heading2\"/></w:pPr><w:r><w:t xml:space=\"preserve\">Intro</w:t></w:r></w:p>\
+             <w:p><w:pPr><w:pStyle w:val=\"Quote\"/></w:pPr><w:hyperlink r:id=\"rId5\"><w:r><w:rPr><w:rStyle w:val=\"Hyperlink\"/><w:i/></w:rPr>\
+             <w:t xml:space=\"preserve\">A &amp; B</w:t></w:r></w:hyperlink>\
+             <w:r><w:rPr><w:rStyle w:val=\"FootnoteReference\"/></w:rPr><w:footnoteReference w:id=\"1\"/></w:r></w:p>\
+             <w:p><w:pPr><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"2\"/></w:numPr></w:pPr><w:r><w:t xml:space=\"preserve\">Three</w:t></w:r></w:p>"
+        );
+        assert!(writer.footnotes_xml().contains(
+            "<w:footnote w:id=\"1\"><w:p><w:pPr><w:pStyle w:val=\"FootnoteText\"/></w:pPr>\
+             <w:r><w:rPr><w:rStyle w:val=\"FootnoteReference\"/></w:rPr><w:footnoteRef/></w:r>"
+        ));
+        assert!(writer.numbering_xml().contains(
+            "<w:lvlOverride w:ilvl=\"0\"><w:startOverride w:val=\"3\"/></w:lvlOverride>"
+        ));
+    }
+}
@@ -0,0 +1,403 @@
+//! EPUB assembly for books
+//!
+//! A book's chapters are the chapter writings of its series (the book's
+//! `series_name`, or else its title), ordered by `series_part`. The book's
+//! own content, if any, opens it. Each part becomes an XHTML document;
+//! images are downloaded into the book, as EPUB readers don't load remote
+//! images, and left out when that fails.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Utc;
+use regex::{Captures, Regex};
+use reqwest::Url;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde_json::Value as JsonValue;
+use tracing::{info, instrument, warn};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::http::HttpClient;
+use crate::research::components::reader_media::{download_resource, image_urls};
+use crate::writing::components::knowledge_graph::entities::writings::{self, WritingType};
+use crate::writing::dto::{EpubExportDto, ExportEpubInput};
+use crate::writing::text::{content_to_html, escape_html, extract_plain_text};
+
+use super::{export_dir, slugify, timestamp};
+
+const MAX_IMAGES: usize = 200;
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const STYLE_CSS: &str = "body { font-family: serif; line-height: 1.5; }
+h1, h2, h3 { font-family: sans-serif; }
+img { max-width: 100%; }
+pre { white-space: pre-wrap; }
+blockquote { margin-left: 1.5em; font-style: italic; }
+";
+
+struct Chapter {
+    title: String,
+    html: String,
+}
+
+struct Image {
+    href: String,
+    media_type: &'static str,
+    bytes: Vec<u8>,
+}
+
+/// Assemble a book and its chapters into an EPUB in the export directory
+#[instrument(skip(db, http_client, storage_config))]
+pub async fn export_writing_epub(
+    db: &DatabaseConnection,
+    http_client: &HttpClient,
+    storage_config: &StorageConfig,
+    input: &ExportEpubInput,
+) -> AppResult<EpubExportDto> {
+    let book = writings::Entity::find_by_id(input.writing_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::other(format!("Writing not found: {}", input.writing_id)))?;
+    if book.r#type != WritingType::Book {
+        return Err(AppError::validation(
+            "writing_id",
+            format!("\"{}\" is a {}, not a book", book.title, book.r#type),
+        ));
+    }
+
+    let series = book
+        .series_name
+        .clone()
+        .unwrap_or_else(|| book.title.clone());
+    let mut rows = writings::Entity::find()
+        .filter(writings::Column::Type.eq(WritingType::Chapter))
+        .filter(writings::Column::SeriesName.eq(series.as_str()))
+        .all(db)
+        .await?;
+    if rows.is_empty() {
+        return Err(AppError::validation(
+            "writing_id",
+            format!("No chapters in the series \"{}\"", series),
+        ));
+    }
+    // Numbered chapters first
+    rows.sort_by_key(|w| (w.series_part.is_none(), w.series_part, w.id));
+
+    let mut chapters = Vec::new();
+    if has_text(&book.content_markdown) {
+        chapters.push(Chapter {
+            title: book.title.clone(),
+            html: content_to_html(&book.content_markdown),
+        });
+    }
+    chapters.extend(rows.into_iter().map(|w| Chapter {
+        html: content_to_html(&w.content_markdown),
+        title: w.title,
+    }));
+
+    let mut images = Vec::new();
+    let mut hrefs = HashMap::new();
+    for src in chapters.iter().flat_map(|c| image_urls(&c.html)) {
+        if hrefs.contains_key(&src) || images.len() >= MAX_IMAGES {
+            continue;
+        }
+        match fetch_image(http_client, &src).await {
+            Ok((bytes, media_type, extension)) => {
+                let href = format!("images/image-{}.{}", images.len() + 1, extension);
+                hrefs.insert(src, href.clone());
+                images.push(Image {
+                    href,
+                    media_type,
+                    bytes,
+                });
+            }
+            Err(e) => warn!(writing_id = book.id, src = %src, "Image left out of EPUB: {}", e),
+        }
+    }
+    let mut skipped_images = 0;
+    for chapter in &mut chapters {
+        chapter.html = embed_images(&chapter.html, &hrefs, &mut skipped_images);
+    }
+
+    let name = match slugify(book.slug.as_deref().unwrap_or(&book.title)) {
+        slug if slug.is_empty() => format!("book-{}", book.id),
+        slug => slug,
+    };
+    let path = export_dir(storage_config)?.join(format!("{}_{}.epub", name, timestamp()));
+    write_epub(&path, &book, &chapters, &images)
+        .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+    let file_size = fs::metadata(&path)
+        .map_err(|e| AppError::file_operation("read metadata", path.to_string_lossy(), e))?
+        .len();
+
+    info!(
+        path = %path.display(),
+        chapters = chapters.len(),
+        images = images.len(),
+        skipped_images,
+        "Exported book as EPUB"
+    );
+
+    Ok(EpubExportDto {
+        file_path: path.to_string_lossy().to_string(),
+        file_size,
+        chapter_count: chapters.len(),
+        image_count: images.len(),
+        skipped_images,
+    })
+}
+
+/// Whether stored content has any text, TipTap JSON or Markdown
+fn has_text(content: &str) -> bool {
+    match serde_json::from_str::<JsonValue>(content) {
+        Ok(doc @ JsonValue::Object(_)) => !extract_plain_text(&doc).is_empty(),
+        _ => !content.trim().is_empty(),
+    }
+}
+
+/// Download an image, returning its bytes, media type and file extension
+async fn fetch_image(
+    http_client: &HttpClient,
+    src: &str,
+) -> AppResult<(Vec<u8>, &'static str, &'static str)> {
+    let url = Url::parse(&unescape_html(src))
+        .map_err(|e| AppError::other(format!("Invalid image URL: {}", e)))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(AppError::other("Only http(s) images can be embedded"));
+    }
+    let (bytes, content_type) = download_resource(http_client, &url).await?;
+    // Core media types of EPUB 3.3
+    let (media_type, extension) = match content_type.split(';').next().unwrap_or("").trim() {
+        "image/jpeg" | "image/jpg" => ("image/jpeg", "jpg"),
+        "image/png" => ("image/png", "png"),
+        "image/gif" => ("image/gif", "gif"),
+        "image/svg+xml" => ("image/svg+xml", "svg"),
+        "image/webp" => ("image/webp", "webp"),
+        other => {
+            return Err(AppError::other(format!(
+                "Unsupported image type ({})",
+                other
+            )))
+        }
+    };
+    Ok((bytes, media_type, extension))
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Points `<img>` tags at the downloaded copies, as XHTML, and drops those
+/// without one, counting them in `skipped`
+fn embed_images(html: &str, hrefs: &HashMap<String, String>, skipped: &mut usize) -> String {
+    let img = Regex::new(r"<img\b[^>]*>").expect("valid img regex");
+    let attr = |name: &str| {
+        Regex::new(&format!(r#"\s{}\s*=\s*["']([^"']*)["']"#, name)).expect("valid attr regex")
+    };
+    let (src, alt) = (attr("src"), attr("alt"));
+    img.replace_all(html, |caps: &Captures| {
+        let tag = &caps[0];
+        let href = src.captures(tag).and_then(|c| hrefs.get(&c[1]));
+        match href {
+            Some(href) => {
+                let alt = alt
+                    .captures(tag)
+                    .map(|c| c[1].to_string())
+                    .unwrap_or_default();
+                format!("<img src=\"{}\" alt=\"{}\" />", href, alt)
+            }
+            None => {
+                *skipped += 1;
+                String::new()
+            }
+        }
+    })
+    .into_owned()
+}
+
+fn write_epub(
+    path: &Path,
+    book: &writings::Model,
+    chapters: &[Chapter],
+    images: &[Image],
+) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    // The mimetype must come first, uncompressed
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("mimetype", stored)?;
+    zip.write_all(b"application/epub+zip")?;
+
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("META-INF/container.xml".into(), CONTAINER_XML.into()),
+        (
+            "OEBPS/content.opf".into(),
+            package_opf(book, chapters, images).into(),
+        ),
+        (
+            "OEBPS/nav.xhtml".into(),
+            nav_xhtml(&book.title, chapters).into(),
+        ),
+        ("OEBPS/style.css".into(), STYLE_CSS.into()),
+    ];
+    for (i, chapter) in chapters.iter().enumerate() {
+        files.push((
+            format!("OEBPS/chapter-{}.xhtml", i + 1),
+            chapter_xhtml(chapter).into(),
+        ));
+    }
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in files {
+        zip.start_file(name, deflated)?;
+        zip.write_all(&contents)?;
+    }
+    for image in images {
+        zip.start_file(format!("OEBPS/{}", image.href), deflated)?;
+        zip.write_all(&image.bytes)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn package_opf(book: &writings::Model, chapters: &[Chapter], images: &[Image]) -> String {
+    let mut metadata = vec![
+        format!(
+            "<dc:identifier id=\"book-id\">urn:cockpit:writing:{}</dc:identifier>",
+            book.id
+        ),
+        format!("<dc:title>{}</dc:title>", escape_html(&book.title)),
+        "<dc:language>en</dc:language>".to_string(),
+        format!(
+            "<meta property=\"dcterms:modified\">{}</meta>",
+            Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+        ),
+    ];
+    if let Some(excerpt) = &book.excerpt {
+        metadata.push(format!(
+            "<dc:description>{}</dc:description>",
+            escape_html(excerpt)
+        ));
+    }
+    if let Some(published_at) = book.published_at {
+        metadata.push(format!(
+            "<dc:date>{}</dc:date>",
+            published_at.format("%Y-%m-%d")
+        ));
+    }
+
+    let mut manifest = vec![
+        r#"<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>"#
+            .to_string(),
+        r#"<item id="style" href="style.css" media-type="text/css"/>"#.to_string(),
+    ];
+    let mut spine = Vec::new();
+    for i in 1..=chapters.len() {
+        manifest.push(format!(
+            "<item id=\"chapter-{i}\" href=\"chapter-{i}.xhtml\" media-type=\"application/xhtml+xml\"/>"
+        ));
+        spine.push(format!("<itemref idref=\"chapter-{i}\"/>"));
+    }
+    for (i, image) in images.iter().enumerate() {
+        manifest.push(format!(
+            "<item id=\"image-{}\" href=\"{}\" media-type=\"{}\"/>",
+            i + 1,
+            image.href,
+            image.media_type
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" \
+         unique-identifier=\"book-id\" xml:lang=\"en\">\n\
+         <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n  {}\n</metadata>\n\
+         <manifest>\n  {}\n</manifest>\n\
+         <spine>\n  {}\n</spine>\n\
+         </package>\n",
+        metadata.join("\n  "),
+        manifest.join("\n  "),
+        spine.join("\n  ")
+    )
+}
+
+fn xhtml_document(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" \
+         xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"en\" xml:lang=\"en\">\n\
+         <head>\n<title>{}</title>\n\
+         <link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n</head>\n\
+         <body>\n{}\n</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+fn nav_xhtml(book_title: &str, chapters: &[Chapter]) -> String {
+    let items: Vec<String> = chapters
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            format!(
+                "<li><a href=\"chapter-{}.xhtml\">{}</a></li>",
+                i + 1,
+                escape_html(&chapter.title)
+            )
+        })
+        .collect();
+    let body = format!(
+        "<nav epub:type=\"toc\" id=\"toc\">\n<h1>Contents</h1>\n<ol>\n{}\n</ol>\n</nav>",
+        items.join("\n")
+    );
+    xhtml_document(book_title, &body)
+}
+
+fn chapter_xhtml(chapter: &Chapter) -> String {
+    let body = format!(
+        "<section epub:type=\"chapter\">\n<h1>{}</h1>\n{}\n</section>",
+        escape_html(&chapter.title),
+        chapter.html
+    );
+    xhtml_document(&chapter.title, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_images() {
+        let hrefs = HashMap::from([(
+            "https://example.com/a.png?x=1&amp;y=2".to_string(),
+            "images/image-1.png".to_string(),
+        )]);
+        let mut skipped = 0;
+        let html = "<p><img src=\"https://example.com/a.png?x=1&amp;y=2\" alt=\"A\"><br />\
+                    <img alt=\"gone\" src=\"https://example.com/b.png\" /></p>";
+        assert_eq!(
+            embed_images(html, &hrefs, &mut skipped),
+            "<p><img src=\"images/image-1.png\" alt=\"A\" /><br /></p>"
+        );
+        assert_eq!(skipped, 1);
+        assert_eq!(
+            unescape_html("https://example.com/a.png?x=1&amp;y=2"),
+            "https://example.com/a.png?x=1&y=2"
+        );
+    }
+}
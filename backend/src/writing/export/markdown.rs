@@ -0,0 +1,218 @@
+//! Markdown export of writings
+//!
+//! Writes one Markdown file per writing, with its metadata as YAML front
+//! matter, into a folder under the export directory, or into a zip archive
+//! holding that folder.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use tracing::{info, instrument};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::writing::dto::{ExportWritingsMarkdownInput, MarkdownExportDto};
+use crate::writing::text::content_to_markdown;
+
+use super::{export_dir, slugify, timestamp};
+
+/// Export the writings selected by `input` as Markdown files
+#[instrument(skip(db, storage_config))]
+pub async fn export_writings_markdown(
+    db: &DatabaseConnection,
+    storage_config: &StorageConfig,
+    input: &ExportWritingsMarkdownInput,
+) -> AppResult<MarkdownExportDto> {
+    let mut query = writings::Entity::find().order_by_asc(writings::Column::Id);
+    if let Some(ids) = &input.writing_ids {
+        query = query.filter(writings::Column::Id.is_in(ids.iter().copied()));
+    }
+    let rows = query.all(db).await?;
+    if rows.is_empty() {
+        return Err(AppError::validation("writing_ids", "No writings to export"));
+    }
+
+    let export_dir = export_dir(storage_config)?;
+
+    let mut taken = HashSet::new();
+    let files: Vec<(String, String)> = rows
+        .iter()
+        .map(|w| (file_name(w, &mut taken), markdown_file(w, true)))
+        .collect();
+
+    let folder = format!("writings_{}", timestamp());
+    let path = if input.zip {
+        let path = export_dir.join(format!("{}.zip", folder));
+        write_zip(&path, &folder, &files)
+            .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+        path
+    } else {
+        let dir = export_dir.join(&folder);
+        fs::create_dir_all(&dir)
+            .map_err(|e| AppError::file_operation("create directory", dir.to_string_lossy(), e))?;
+        for (name, contents) in &files {
+            let path = dir.join(name);
+            fs::write(&path, contents)
+                .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+        }
+        dir
+    };
+
+    info!(
+        path = %path.display(),
+        writings = files.len(),
+        zipped = input.zip,
+        "Exported writings as Markdown"
+    );
+
+    Ok(MarkdownExportDto {
+        path: path.to_string_lossy().to_string(),
+        writing_count: files.len(),
+        zipped: input.zip,
+    })
+}
+
+pub(crate) fn write_zip(
+    path: &Path,
+    folder: &str,
+    files: &[(String, String)],
+) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(File::create(path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in files {
+        zip.start_file(format!("{}/{}", folder, name), options)?;
+        zip.write_all(contents.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// File name from the slug, or else the title; a name already taken gets
+/// the writing id appended
+pub(crate) fn file_name(writing: &writings::Model, taken: &mut HashSet<String>) -> String {
+    let source = writing
+        .slug
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or(&writing.title);
+    let mut name = slugify(source);
+    if name.is_empty() {
+        name = format!("writing-{}", writing.id);
+    }
+    if taken.contains(&name) {
+        name = format!("{}-{}", name, writing.id);
+    }
+    taken.insert(name.clone());
+    format!("{}.md", name)
+}
+
+/// The writing as front matter followed by its content; folder sync leaves
+/// out the timestamps so that saves without changes don't touch the file
+pub(crate) fn markdown_file(writing: &writings::Model, timestamps: bool) -> String {
+    let mut out = String::from("---\n");
+    push_field(&mut out, "title", &yaml_string(&writing.title));
+    if let Some(slug) = &writing.slug {
+        push_field(&mut out, "slug", &yaml_string(slug));
+    }
+    push_field(&mut out, "type", &writing.r#type.to_string());
+    push_field(&mut out, "status", &writing.status.to_string());
+    if let Some(tags) = writing.tags.as_deref().map(tag_list) {
+        push_field(
+            &mut out,
+            "tags",
+            &serde_json::to_string(&tags).unwrap_or_default(),
+        );
+    }
+    if let Some(excerpt) = &writing.excerpt {
+        push_field(&mut out, "excerpt", &yaml_string(excerpt));
+    }
+    if let Some(series) = &writing.series_name {
+        push_field(&mut out, "series", &yaml_string(series));
+    }
+    if let Some(part) = writing.series_part {
+        push_field(&mut out, "series_part", &part.to_string());
+    }
+    if timestamps {
+        push_field(&mut out, "created_at", &writing.created_at.to_rfc3339());
+        push_field(&mut out, "updated_at", &writing.updated_at.to_rfc3339());
+    }
+    if let Some(published_at) = writing.published_at {
+        push_field(&mut out, "published_at", &published_at.to_rfc3339());
+    }
+    out.push_str("---\n\n");
+
+    let body = content_to_markdown(&writing.content_markdown);
+    out.push_str(body.trim_end());
+    out.push('\n');
+    out
+}
+
+/// Tags are stored as a JSON array, or comma-separated by older clients
+fn tag_list(tags: &str) -> Vec<String> {
+    serde_json::from_str(tags).unwrap_or_else(|_| {
+        tags.split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+pub(crate) fn push_field(out: &mut String, key: &str, value: &str) {
+    out.push_str(key);
+    out.push_str(": ");
+    out.push_str(value);
+    out.push('\n');
+}
+
+/// A double-quoted YAML scalar; JSON string escapes are valid in YAML
+pub(crate) fn yaml_string(text: &str) -> String {
+    serde_json::to_string(text).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_file_names() {
+        let mut taken = HashSet::new();
+        let writing = |id, slug: Option<&str>, title: &str| writings::Model {
+            id,
+            r#type: writings::WritingType::Article,
+            title: title.to_string(),
+            slug: slug.map(str::to_string),
+            content_markdown: String::new(),
+            excerpt: None,
+            status: writings::WritingStatus::Draft,
+            tags: None,
+            word_count: 0,
+            series_name: None,
+            series_part: None,
+            is_pinned: 0,
+            is_featured: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            published_at: None,
+        };
+        assert_eq!(
+            file_name(&writing(1, Some("intro"), "Intro"), &mut taken),
+            "intro.md"
+        );
+        assert_eq!(
+            file_name(&writing(2, None, "Intro"), &mut taken),
+            "intro-2.md"
+        );
+        assert_eq!(
+            file_name(&writing(3, Some(" "), "???"), &mut taken),
+            "writing-3.md"
+        );
+    }
+}
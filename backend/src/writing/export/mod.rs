@@ -0,0 +1,62 @@
+//! Exports of writings to files in the export directory
+//!
+//! - **markdown**: One Markdown file per writing, as a folder or zip
+//! - **epub**: An EPUB book assembled from a book's chapters
+//! - **pdf**: A writing or news digest printed by a headless browser
+//! - **docx**: A Word document of a writing, with footnotes
+
+pub mod docx;
+pub mod epub;
+pub mod markdown;
+pub mod pdf;
+
+pub use docx::export_writing_docx;
+pub use epub::export_writing_epub;
+pub use markdown::export_writings_markdown;
+pub use pdf::export_writing_pdf;
+
+use std::fs;
+use std::path::Path;
+
+use chrono::Utc;
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+
+/// The export directory, created if missing
+pub(crate) fn export_dir(storage_config: &StorageConfig) -> AppResult<&Path> {
+    let export_dir = &storage_config.export_dir;
+    fs::create_dir_all(export_dir).map_err(|e| {
+        AppError::file_operation("create directory", export_dir.to_string_lossy(), e)
+    })?;
+    Ok(export_dir)
+}
+
+/// Timestamp for export file names, as used by database exports
+pub(crate) fn timestamp() -> String {
+    Utc::now().format("%Y%m%d_%H%M%S").to_string()
+}
+
+/// Lowercase letters and digits, with runs of anything else as one `-`
+pub(crate) fn slugify(text: &str) -> String {
+    let mut out = String::new();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() {
+            out.push(ch);
+        } else if !out.is_empty() && !out.ends_with('-') {
+            out.push('-');
+        }
+    }
+    out.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World: Part 2!"), "hello-world-part-2");
+        assert_eq!(slugify("???"), "");
+    }
+}
@@ -0,0 +1,340 @@
+//! PDF export through a headless browser
+//!
+//! A writing, or a digest of recent news articles, is laid out as a print
+//! HTML document; the page size, margins and typography are CSS `@page` and
+//! body rules. Chrome, Chromium or Edge then prints it with
+//! `--print-to-pdf`. The browser is found on `PATH` or in the usual install
+//! locations, or set with PDF_BROWSER_PATH.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::Url;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use tokio::process::Command;
+use tracing::{info, instrument};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::core::components::storage::{ExportCounts, ExportFormat, ExportInfo};
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::writing::components::newsletter::handlers::digest_items;
+use crate::writing::components::newsletter::template::render_digest_body;
+use crate::writing::dto::{ExportPdfInput, PageSize};
+use crate::writing::text::{content_to_html, escape_html};
+
+use super::{export_dir, slugify, timestamp};
+
+const RENDER_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Executables tried on `PATH`, in order
+const BROWSER_NAMES: [&str; 6] = [
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+    "brave-browser",
+];
+
+const BROWSER_PATHS: [&str; 5] = [
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+    "/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge",
+    r"C:\Program Files\Google\Chrome\Application\chrome.exe",
+    r"C:\Program Files (x86)\Microsoft\Edge\Application\msedge.exe",
+];
+
+/// Page and typography settings, validated from the input
+struct Layout {
+    page_size: PageSize,
+    margin_mm: f32,
+    font_family: String,
+    font_size_pt: f32,
+    line_height: f32,
+}
+
+impl Layout {
+    fn from_input(input: &ExportPdfInput) -> AppResult<Self> {
+        let in_range = |field: &str, value: f32, min: f32, max: f32| {
+            if (min..=max).contains(&value) {
+                Ok(value)
+            } else {
+                Err(AppError::validation(
+                    field,
+                    format!("Must be between {} and {}", min, max),
+                ))
+            }
+        };
+        let font_family = input
+            .font_family
+            .as_deref()
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .unwrap_or("Georgia, 'Times New Roman', serif");
+        // Enough for any font stack, too little to leave the declaration
+        let allowed = |c: char| c.is_alphanumeric() || " ,-_'\"".contains(c);
+        if !font_family.chars().all(allowed) {
+            return Err(AppError::validation(
+                "font_family",
+                "Use font names separated by commas",
+            ));
+        }
+
+        Ok(Layout {
+            page_size: input.page_size.unwrap_or_default(),
+            margin_mm: in_range("margin_mm", input.margin_mm.unwrap_or(20.0), 0.0, 50.0)?,
+            font_family: font_family.to_string(),
+            font_size_pt: in_range(
+                "font_size_pt",
+                input.font_size_pt.unwrap_or(11.0),
+                6.0,
+                24.0,
+            )?,
+            line_height: in_range("line_height", input.line_height.unwrap_or(1.5), 1.0, 3.0)?,
+        })
+    }
+}
+
+/// Content to print
+struct Document {
+    title: String,
+    subtitle: Option<String>,
+    body_html: String,
+    file_stem: String,
+}
+
+/// Render a writing, or a news digest when no writing is given, to a PDF in
+/// the export directory
+#[instrument(skip(db, storage_config))]
+pub async fn export_writing_pdf(
+    db: &DatabaseConnection,
+    storage_config: &StorageConfig,
+    input: &ExportPdfInput,
+) -> AppResult<ExportInfo> {
+    let layout = Layout::from_input(input)?;
+
+    let (document, group, count) = match input.writing_id {
+        Some(writing_id) => {
+            let writing = writings::Entity::find_by_id(writing_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| AppError::other(format!("Writing not found: {}", writing_id)))?;
+            let file_stem = match slugify(writing.slug.as_deref().unwrap_or(&writing.title)) {
+                slug if slug.is_empty() => format!("writing-{}", writing.id),
+                slug => slug,
+            };
+            let document = Document {
+                body_html: content_to_html(&writing.content_markdown),
+                subtitle: writing.excerpt,
+                title: writing.title,
+                file_stem,
+            };
+            (document, "writings", 1)
+        }
+        None => {
+            let days = input.days.unwrap_or(7).max(1);
+            let limit = input.limit.unwrap_or(20);
+            let items = digest_items(db, days, limit, input.starred_only == Some(true)).await?;
+            let document = Document {
+                title: format!("News digest — {}", Utc::now().format("%B %-d, %Y")),
+                subtitle: Some(format!(
+                    "{} articles from the last {} days",
+                    items.len(),
+                    days
+                )),
+                body_html: render_digest_body(&items).0,
+                file_stem: "digest".to_string(),
+            };
+            (document, "articles", items.len())
+        }
+    };
+
+    let export_dir = export_dir(storage_config)?;
+    let timestamp = timestamp();
+    let path = export_dir.join(format!("{}_{}.pdf", document.file_stem, timestamp));
+
+    // The page and the browser profile live in the cache, not among exports
+    let work_dir = storage_config.cache_dir.join("pdf");
+    fs::create_dir_all(&work_dir)
+        .map_err(|e| AppError::file_operation("create directory", work_dir.to_string_lossy(), e))?;
+    let html_path = work_dir.join(format!("{}_{}.html", document.file_stem, timestamp));
+    fs::write(&html_path, render_html(&document, &layout))
+        .map_err(|e| AppError::file_operation("write", html_path.to_string_lossy(), e))?;
+    let printed = print_to_pdf(&html_path, &path, &work_dir.join("profile")).await;
+    let _ = fs::remove_file(&html_path);
+    printed?;
+
+    let file_size = fs::metadata(&path)
+        .map_err(|e| AppError::file_operation("read metadata", path.to_string_lossy(), e))?
+        .len();
+    info!(path = %path.display(), file_size, "Exported PDF");
+
+    Ok(ExportInfo {
+        file_path: path.to_string_lossy().to_string(),
+        file_size,
+        timestamp,
+        format: ExportFormat::Pdf,
+        manifest_path: None,
+        record_counts: ExportCounts {
+            ideas: 0,
+            news_articles: if group == "articles" { count } else { 0 },
+            app_settings: 0,
+        },
+        group_counts: BTreeMap::from([(group.to_string(), count)]),
+        encrypted: false,
+    })
+}
+
+/// A standalone print document
+fn render_html(document: &Document, layout: &Layout) -> String {
+    let page_size = match layout.page_size {
+        PageSize::A4 => "A4",
+        PageSize::A5 => "A5",
+        PageSize::Letter => "letter",
+        PageSize::Legal => "legal",
+    };
+    let subtitle = document
+        .subtitle
+        .as_deref()
+        .map(|s| format!("<p class=\"subtitle\">{}</p>\n", escape_html(s)))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+@page {{ size: {page_size}; margin: {margin}mm; }}
+body {{ font-family: {font}; font-size: {size}pt; line-height: {line_height}; color: #18181b; }}
+h1, h2, h3, h4 {{ line-height: 1.25; break-after: avoid; }}
+h1.title {{ margin: 0 0 0.25em; }}
+p.subtitle {{ margin: 0 0 2em; color: #71717a; }}
+img {{ max-width: 100%; break-inside: avoid; }}
+pre {{ white-space: pre-wrap; font-size: 0.9em; }}
+blockquote {{ margin-left: 1.5em; font-style: italic; }}
+a {{ color: inherit; }}
+</style>
+</head>
+<body>
+<h1 class="title">{title}</h1>
+{subtitle}{body}
+</body>
+</html>
+"#,
+        title = escape_html(&document.title),
+        page_size = page_size,
+        margin = layout.margin_mm,
+        font = layout.font_family,
+        size = layout.font_size_pt,
+        line_height = layout.line_height,
+        subtitle = subtitle,
+        body = document.body_html,
+    )
+}
+
+/// Print `html` to `pdf` with a headless browser using its own profile
+async fn print_to_pdf(html: &Path, pdf: &Path, profile: &Path) -> AppResult<()> {
+    let browser = find_browser()?;
+    let url = Url::from_file_path(html)
+        .map_err(|_| AppError::other(format!("Not an absolute path: {}", html.display())))?;
+
+    let output = Command::new(&browser)
+        .arg("--headless")
+        .arg("--disable-gpu")
+        .arg("--no-first-run")
+        .arg(format!("--user-data-dir={}", profile.display()))
+        .arg("--no-pdf-header-footer")
+        .arg("--print-to-pdf-no-header")
+        // Time for remote images to load before printing
+        .arg("--virtual-time-budget=10000")
+        .arg(format!("--print-to-pdf={}", pdf.display()))
+        .arg(url.as_str())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(RENDER_TIMEOUT, output)
+        .await
+        .map_err(|_| AppError::other("PDF rendering timed out"))?
+        .map_err(|e| AppError::other(format!("Failed to run {}: {}", browser.display(), e)))?;
+
+    if !output.status.success() || !pdf.is_file() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("no output");
+        return Err(AppError::other(format!(
+            "PDF rendering failed ({}): {}",
+            output.status, reason
+        )));
+    }
+    Ok(())
+}
+
+/// PDF_BROWSER_PATH, or the first Chromium-based browser installed
+fn find_browser() -> AppResult<PathBuf> {
+    if let Some(path) = env::var("PDF_BROWSER_PATH")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+    {
+        return Ok(PathBuf::from(path));
+    }
+    let on_path = env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|dir| BROWSER_NAMES.iter().map(move |name| dir.join(name)));
+    on_path
+        .chain(BROWSER_PATHS.iter().map(PathBuf::from))
+        .find(|path| path.is_file())
+        .ok_or_else(|| AppError::ConfigValidation {
+            field: "PDF_BROWSER_PATH".to_string(),
+            reason: "No Chrome, Chromium or Edge installation found".to_string(),
+            suggestion: Some(
+                "Install Chromium or set PDF_BROWSER_PATH in ~/.cockpit/.env".to_string(),
+            ),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        let input = ExportPdfInput {
+            page_size: Some(PageSize::Letter),
+            font_family: Some("Inter, sans-serif".to_string()),
+            font_size_pt: Some(12.0),
+            ..Default::default()
+        };
+        let layout = Layout::from_input(&input).unwrap();
+        let document = Document {
+            title: "Cats & dogs".to_string(),
+            subtitle: None,
+            body_html: "<p>Hi</p>".to_string(),
+            file_stem: "cats-dogs".to_string(),
+        };
+        let html = render_html(&document, &layout);
+        assert!(html.contains("@page { size: letter; margin: 20mm; }"));
+        assert!(html.contains("font-family: Inter, sans-serif; font-size: 12pt; line-height: 1.5;"));
+        assert!(html.contains("<h1 class=\"title\">Cats &amp; dogs</h1>\n<p>Hi</p>"));
+
+        let input = ExportPdfInput {
+            font_family: Some("serif; } body { display: none".to_string()),
+            ..Default::default()
+        };
+        assert!(Layout::from_input(&input).is_err());
+        let input = ExportPdfInput {
+            margin_mm: Some(80.0),
+            ..Default::default()
+        };
+        assert!(Layout::from_input(&input).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! Git history of writings
+//!
+//! With WRITING_GIT_AUTOCOMMIT set, every draft save and publish writes the
+//! writing's file in the sync folder (see [`crate::writing::sync`]) and
+//! commits it, so `git log` on the folder is the writing's version history.
+//! The folder is made a repository on the first commit if it isn't one, and
+//! each commit is pushed to WRITING_GIT_REMOTE in the background when set.
+//!
+//! This is a side effect of saving: failures are logged, never returned.
+
+use std::path::Path;
+use std::process::Output;
+use std::time::Duration;
+
+use sea_orm::DatabaseConnection;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::knowledge_graph::entities::writings;
+use crate::writing::sync::mirror_writing;
+
+/// Local git commands; a push gets longer
+const GIT_TIMEOUT: Duration = Duration::from_secs(30);
+const PUSH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Why a writing is committed, for the commit message
+#[derive(Debug, Clone, Copy)]
+pub enum WritingChange {
+    SaveDraft,
+    Publish,
+}
+
+/// Mirror and commit a saved writing, if autocommit is on
+pub async fn commit_writing(
+    db: &DatabaseConnection,
+    storage_config: &StorageConfig,
+    writing: &writings::Model,
+    change: WritingChange,
+) {
+    if !storage_config.writing_git_autocommit {
+        return;
+    }
+    let Some(folder) = storage_config.writing_sync_dir.as_deref() else {
+        return;
+    };
+
+    match try_commit(db, folder, writing, change).await {
+        Ok(true) => {
+            if let Some(remote) = storage_config.writing_git_remote.clone() {
+                let folder = folder.to_path_buf();
+                tokio::spawn(async move {
+                    if let Err(e) = push(&folder, &remote).await {
+                        warn!(remote = %remote, error = %e, "Failed to push writings");
+                    }
+                });
+            }
+        }
+        Ok(false) => {}
+        Err(e) => warn!(writing_id = writing.id, error = %e, "Failed to commit writing"),
+    }
+}
+
+/// Whether a commit was made; none when the file is unchanged or was left
+/// for the folder sync
+async fn try_commit(
+    db: &DatabaseConnection,
+    folder: &Path,
+    writing: &writings::Model,
+    change: WritingChange,
+) -> AppResult<bool> {
+    let Some(name) = mirror_writing(db, folder, writing).await? else {
+        return Ok(false);
+    };
+
+    if !folder.join(".git").exists() {
+        git(folder, &["init", "--quiet"], GIT_TIMEOUT).await?;
+    }
+    git(folder, &["add", "--", &name], GIT_TIMEOUT).await?;
+    let staged = run(
+        folder,
+        &["diff", "--cached", "--quiet", "--", &name],
+        GIT_TIMEOUT,
+    )
+    .await?;
+    if staged.status.success() {
+        return Ok(false);
+    }
+
+    let message = match change {
+        WritingChange::SaveDraft => format!("Save draft: {}", writing.title),
+        WritingChange::Publish => format!("Publish: {}", writing.title),
+    };
+    let mut args = Vec::new();
+    // A repository without an author of its own still takes commits
+    let has_author = run(folder, &["config", "user.email"], GIT_TIMEOUT).await?;
+    if !has_author.status.success() {
+        args.extend([
+            "-c",
+            "user.name=Cockpit",
+            "-c",
+            "user.email=cockpit@localhost",
+        ]);
+    }
+    args.extend(["commit", "--quiet", "-m", &message, "--", &name]);
+    git(folder, &args, GIT_TIMEOUT).await?;
+
+    info!(writing_id = writing.id, file = %name, "Committed writing");
+    Ok(true)
+}
+
+async fn push(folder: &Path, remote: &str) -> AppResult<()> {
+    git(folder, &["push", "--quiet", remote, "HEAD"], PUSH_TIMEOUT).await?;
+    Ok(())
+}
+
+/// Run git, failing on a non-zero exit
+async fn git(folder: &Path, args: &[&str], timeout: Duration) -> AppResult<Output> {
+    let output = run(folder, args, timeout).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("no output");
+        return Err(AppError::other(format!(
+            "git {} failed ({}): {}",
+            args.iter()
+                .find(|a| !a.starts_with('-') && !a.contains('='))
+                .unwrap_or(&""),
+            output.status,
+            reason
+        )));
+    }
+    Ok(output)
+}
+
+/// Run git in `folder`, never prompting for credentials
+async fn run(folder: &Path, args: &[&str], timeout: Duration) -> AppResult<Output> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(folder)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .kill_on_drop(true)
+        .output();
+    tokio::time::timeout(timeout, output)
+        .await
+        .map_err(|_| AppError::other("git timed out"))?
+        .map_err(|e| AppError::other(format!("Failed to run git: {}", e)))
+}
@@ -0,0 +1,218 @@
+//! Imports of Markdown from other note-taking tools
+//!
+//! - **obsidian**: An Obsidian vault as writings and idea notes, with its
+//!   wiki-links as knowledge-graph links
+//! - **notion**: A Notion Markdown & CSV export as writings, series of
+//!   chapters, references and notes
+
+pub mod notion;
+pub mod obsidian;
+
+pub use notion::import_notion_export;
+pub use obsidian::import_obsidian_vault;
+
+use std::collections::BTreeMap;
+
+/// A front-matter value: a scalar, or a flow or block sequence
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+/// The YAML front matter of a Markdown file
+///
+/// Covers what note apps write: `key: value` scalars, plain or quoted,
+/// `[a, b]` flow sequences and `- item` block sequences. Nested maps and
+/// multi-line strings are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct FrontMatter {
+    fields: BTreeMap<String, FieldValue>,
+}
+
+impl FrontMatter {
+    /// Splits `text` into its front matter and the Markdown after it
+    pub(crate) fn parse(text: &str) -> (FrontMatter, &str) {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let Some(rest) = text
+            .strip_prefix("---\n")
+            .or_else(|| text.strip_prefix("---\r\n"))
+        else {
+            return (FrontMatter::default(), text);
+        };
+
+        let mut fields = BTreeMap::new();
+        let mut current: Option<String> = None;
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            offset += line.len();
+            let line = line.trim_end();
+            if line == "---" || line == "..." {
+                return (FrontMatter { fields }, &rest[offset..]);
+            }
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                if let Some(FieldValue::List(items)) =
+                    current.as_ref().and_then(|key| fields.get_mut(key))
+                {
+                    items.push(unquote(item.trim()));
+                }
+                continue;
+            }
+            current = None;
+            if line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_string();
+            let value = value.trim();
+            if value.starts_with(['|', '>']) {
+                continue;
+            }
+            let value = if value.is_empty() {
+                current = Some(key.clone());
+                FieldValue::List(Vec::new())
+            } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                FieldValue::List(
+                    split_flow(inner)
+                        .into_iter()
+                        .map(|item| unquote(&item))
+                        .filter(|item| !item.is_empty())
+                        .collect(),
+                )
+            } else {
+                FieldValue::Text(unquote(value))
+            };
+            fields.insert(key, value);
+        }
+
+        // No closing fence: not front matter after all
+        (FrontMatter::default(), text)
+    }
+
+    /// A scalar field, if set and not empty
+    pub(crate) fn text(&self, key: &str) -> Option<&str> {
+        match self.fields.get(key) {
+            Some(FieldValue::Text(text)) if !text.is_empty() => Some(text),
+            _ => None,
+        }
+    }
+
+    /// A sequence field; a scalar is split on commas
+    pub(crate) fn list(&self, key: &str) -> Vec<String> {
+        match self.fields.get(key) {
+            Some(FieldValue::List(items)) => items.clone(),
+            Some(FieldValue::Text(text)) => text
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Applies `f` to the Markdown outside fenced code blocks and inline code
+/// spans, keeping code as written
+pub(crate) fn map_outside_code(markdown: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut fence: Option<&str> = None;
+
+    for line in markdown.split_inclusive('\n') {
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|m| line.trim_start().starts_with(m));
+        match (fence, marker) {
+            (None, Some(m)) => fence = Some(m),
+            (Some(open), Some(m)) if open == m => fence = None,
+            _ => {}
+        }
+        if fence.is_some() || marker.is_some() {
+            out.push_str(line);
+            continue;
+        }
+
+        // Even segments are outside inline code spans
+        for (i, segment) in line.split('`').enumerate() {
+            if i > 0 {
+                out.push('`');
+            }
+            if i % 2 == 1 {
+                out.push_str(segment);
+            } else {
+                out.push_str(&f(segment));
+            }
+        }
+    }
+    out
+}
+
+/// Items of a flow sequence, splitting on commas outside quotes
+fn split_flow(inner: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut quote = None;
+    let mut chars = inner.chars();
+    while let Some(ch) = chars.next() {
+        match (ch, quote) {
+            ('\\', Some('"')) => {
+                item.push(ch);
+                item.extend(chars.next());
+            }
+            ('"' | '\'', None) => {
+                quote = Some(ch);
+                item.push(ch);
+            }
+            (c, Some(q)) if c == q => {
+                quote = None;
+                item.push(ch);
+            }
+            (',', None) => items.push(std::mem::take(&mut item).trim().to_string()),
+            _ => item.push(ch),
+        }
+    }
+    items.push(item.trim().to_string());
+    items
+}
+
+/// A scalar without its quotes; double-quoted scalars take JSON escapes
+fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        serde_json::from_str(value).unwrap_or_else(|_| value[1..value.len() - 1].to_string())
+    } else if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1].replace("''", "'")
+    } else {
+        // A trailing comment ends a plain scalar
+        match value.find(" #") {
+            Some(i) => value[..i].trim_end().to_string(),
+            None => value.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_front_matter() {
+        let text = "---\ntitle: \"Cats: a \\\"history\\\"\"\naliases: ['Felines', \"Cats, big\"]\ntags:\n  - pets\n  - '#animals'\ntype: chapter # draft\nmeta:\n  nested: 1\n---\n# Body\n";
+        let (front, body) = FrontMatter::parse(text);
+        assert_eq!(front.text("title"), Some("Cats: a \"history\""));
+        assert_eq!(front.list("aliases"), vec!["Felines", "Cats, big"]);
+        assert_eq!(front.list("tags"), vec!["pets", "#animals"]);
+        assert_eq!(front.text("type"), Some("chapter"));
+        assert_eq!(front.text("nested"), None);
+        assert_eq!(body, "# Body\n");
+
+        let (front, body) = FrontMatter::parse("---\nno closing fence\n");
+        assert_eq!(front.text("title"), None);
+        assert_eq!(body, "---\nno closing fence\n");
+    }
+}
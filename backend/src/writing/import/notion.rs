@@ -0,0 +1,796 @@
+//! Notion workspace import
+//!
+//! Reads a Notion "Markdown & CSV" export, as the zip Notion hands out
+//! (including the part zips of large exports) or unpacked into a folder.
+//!
+//! - **Pages** become draft writings. A top-level page with subpages is a
+//!   book, and every page below it a chapter of its series, numbered in
+//!   export order; a page on its own is an article.
+//! - **Database rows** become writings, or references when the row has a
+//!   URL. Columns are read into the matching fields (title, tags, status,
+//!   type, author, dates, ...); the rest go to a reference's metadata, or to
+//!   a main note on the writing. A reference's page body becomes its main
+//!   note.
+//!
+//! Links between pages are kept as their text, and attachments are left
+//! out. Writings whose slug and references whose URL already exist are
+//! skipped, so importing the same export twice adds nothing.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, EntityTrait, QuerySelect, Set, TransactionTrait,
+};
+use tracing::{debug, info, instrument, warn};
+use zip::ZipArchive;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::ideas::types::tags_to_json;
+use crate::writing::components::knowledge_graph::entities::{notes, reference_items, writings};
+use crate::writing::dto::NotionImportDto;
+use crate::writing::export::slugify;
+use crate::writing::text::{
+    escape_html, extract_plain_text, markdown_to_tiptap, tiptap_to_html, word_count,
+};
+
+use super::map_outside_code;
+
+/// Columns read into fields, by lowercase name
+const TAG_COLUMNS: &[&str] = &["tags", "tag", "labels", "topics", "keywords"];
+const SUMMARY_COLUMNS: &[&str] = &["summary", "description", "excerpt", "abstract"];
+const PUBLISHED_COLUMNS: &[&str] = &["published", "published at", "publish date", "date"];
+const CREATED_COLUMNS: &[&str] = &["created", "created time", "created at"];
+const URL_COLUMNS: &[&str] = &["url", "link", "source url"];
+
+/// A page of the export, with its place in the page tree
+#[derive(Debug)]
+struct NotionPage {
+    title: String,
+    markdown: String,
+    writing_type: writings::WritingType,
+    /// Series name and part of a chapter
+    series: Option<(String, i32)>,
+}
+
+/// A database of the export: its CSV, with each row's page body
+#[derive(Debug)]
+struct NotionDatabase {
+    columns: Vec<String>,
+    rows: Vec<NotionRow>,
+}
+
+#[derive(Debug)]
+struct NotionRow {
+    values: Vec<String>,
+    markdown: String,
+}
+
+#[derive(Debug, Default)]
+struct NotionExport {
+    pages: Vec<NotionPage>,
+    databases: Vec<NotionDatabase>,
+}
+
+/// Import a Notion export zip or folder
+#[instrument(skip(db))]
+pub async fn import_notion_export(
+    db: &DatabaseConnection,
+    export_path: &Path,
+) -> AppResult<NotionImportDto> {
+    let mut files = BTreeMap::new();
+    if export_path.is_dir() {
+        read_folder(export_path, "", &mut files).map_err(|e| {
+            AppError::file_operation("read directory", export_path.to_string_lossy(), e)
+        })?;
+    } else if export_path.is_file() {
+        let file = File::open(export_path)
+            .map_err(|e| AppError::file_operation("open", export_path.to_string_lossy(), e))?;
+        read_zip(file, &mut files, true)?;
+    } else {
+        return Err(AppError::validation(
+            "import_path",
+            "Notion export not found",
+        ));
+    }
+    let export = parse_export(&files);
+
+    let mut taken_slugs: HashSet<String> = writings::Entity::find()
+        .select_only()
+        .column(writings::Column::Slug)
+        .into_tuple::<Option<String>>()
+        .all(db)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut taken_urls: HashSet<String> = reference_items::Entity::find()
+        .select_only()
+        .column(reference_items::Column::Url)
+        .into_tuple::<Option<String>>()
+        .all(db)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let tx = db.begin().await?;
+    let now = Utc::now();
+    let mut result = NotionImportDto {
+        writings_created: 0,
+        references_created: 0,
+        notes_created: 0,
+        databases: export.databases.len(),
+        skipped: 0,
+    };
+
+    for page in &export.pages {
+        let slug = match &page.series {
+            Some((series, _)) => slugify(&format!("{} {}", series, page.title)),
+            None => slugify(&page.title),
+        };
+        if !slug.is_empty() && !taken_slugs.insert(slug.clone()) {
+            debug!(title = %page.title, slug = %slug, "Writing slug already taken");
+            result.skipped += 1;
+            continue;
+        }
+        let content = markdown_to_tiptap(&page.markdown);
+        writings::ActiveModel {
+            r#type: Set(page.writing_type.clone()),
+            title: Set(page.title.clone()),
+            slug: Set(Some(slug).filter(|s| !s.is_empty())),
+            content_markdown: Set(content.to_string()),
+            excerpt: Set(None),
+            status: Set(writings::WritingStatus::Draft),
+            tags: Set(None),
+            word_count: Set(word_count(&extract_plain_text(&content))),
+            series_name: Set(page.series.as_ref().map(|(name, _)| name.clone())),
+            series_part: Set(page.series.as_ref().map(|(_, part)| *part)),
+            is_pinned: Set(0),
+            is_featured: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+            published_at: Set(None),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await?;
+        result.writings_created += 1;
+    }
+
+    for database in &export.databases {
+        for row in &database.rows {
+            let mut fields = RowFields::new(&database.columns, &row.values);
+            let title = fields.title();
+            let created_at = fields.take(CREATED_COLUMNS).and_then(parse_date);
+
+            if let Some(url) = fields.url() {
+                if !taken_urls.insert(url.clone()) {
+                    debug!(url = %url, "Reference already exists");
+                    result.skipped += 1;
+                    continue;
+                }
+                let reference_type = fields
+                    .take(&["type", "kind"])
+                    .and_then(|kind| reference_type(&kind))
+                    .unwrap_or(reference_items::ReferenceType::Url);
+                let source = fields
+                    .take(&["source", "publisher", "site", "publication"])
+                    .filter(|source| !is_url(source));
+                let reference = reference_items::ActiveModel {
+                    reference_type: Set(reference_type),
+                    news_article_id: Set(None),
+                    title: Set(title),
+                    url: Set(Some(url)),
+                    source: Set(source),
+                    author: Set(fields.take(&["author", "authors", "by", "creator"])),
+                    published_date: Set(fields.take(PUBLISHED_COLUMNS).and_then(parse_date)),
+                    summary: Set(fields.take(SUMMARY_COLUMNS)),
+                    metadata: Set(fields.rest_json()),
+                    created_at: Set(created_at.unwrap_or(now)),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&tx)
+                .await?;
+                result.references_created += 1;
+
+                if !row.markdown.trim().is_empty() {
+                    let body_html = tiptap_to_html(&markdown_to_tiptap(&row.markdown));
+                    main_note(notes::EntityType::Reference, reference.id, body_html)
+                        .insert(&tx)
+                        .await?;
+                    result.notes_created += 1;
+                }
+                continue;
+            }
+
+            let slug = fields.take(&["slug"]).unwrap_or_else(|| slugify(&title));
+            if !slug.is_empty() && !taken_slugs.insert(slug.clone()) {
+                debug!(title = %title, slug = %slug, "Writing slug already taken");
+                result.skipped += 1;
+                continue;
+            }
+            let tags: Vec<String> = fields
+                .take(TAG_COLUMNS)
+                .map(|tags| {
+                    tags.split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let writing_type = match fields.take(&["type"]).map(|t| t.to_lowercase()).as_deref() {
+                Some("chapter") => writings::WritingType::Chapter,
+                Some("book") => writings::WritingType::Book,
+                _ => writings::WritingType::Article,
+            };
+            let status = fields
+                .take(&["status", "stage"])
+                .map(|status| writing_status(&status))
+                .unwrap_or(writings::WritingStatus::Draft);
+            let content = markdown_to_tiptap(&row.markdown);
+            let writing = writings::ActiveModel {
+                r#type: Set(writing_type),
+                title: Set(title),
+                slug: Set(Some(slug).filter(|s| !s.is_empty())),
+                content_markdown: Set(content.to_string()),
+                excerpt: Set(fields.take(SUMMARY_COLUMNS)),
+                status: Set(status),
+                tags: Set(tags_to_json(&tags)),
+                word_count: Set(word_count(&extract_plain_text(&content))),
+                series_name: Set(fields.take(&["series"])),
+                series_part: Set(fields
+                    .take(&["series part", "part"])
+                    .and_then(|part| part.parse().ok())),
+                is_pinned: Set(0),
+                is_featured: Set(0),
+                created_at: Set(created_at.unwrap_or(now)),
+                updated_at: Set(now),
+                published_at: Set(fields.take(PUBLISHED_COLUMNS).and_then(parse_date)),
+                ..Default::default()
+            }
+            .insert(&tx)
+            .await?;
+            result.writings_created += 1;
+
+            let rest = fields.rest();
+            if !rest.is_empty() {
+                let items: String = rest
+                    .iter()
+                    .map(|(column, value)| {
+                        format!(
+                            "<li><strong>{}</strong>: {}</li>",
+                            escape_html(column),
+                            escape_html(value)
+                        )
+                    })
+                    .collect();
+                main_note(
+                    notes::EntityType::Writing,
+                    writing.id,
+                    format!("<ul>{}</ul>", items),
+                )
+                .insert(&tx)
+                .await?;
+                result.notes_created += 1;
+            }
+        }
+    }
+
+    tx.commit().await?;
+
+    info!(
+        export = %export_path.display(),
+        writings = result.writings_created,
+        references = result.references_created,
+        notes = result.notes_created,
+        databases = result.databases,
+        skipped = result.skipped,
+        "Imported Notion export"
+    );
+    Ok(result)
+}
+
+fn main_note(
+    entity_type: notes::EntityType,
+    entity_id: i64,
+    body_html: String,
+) -> notes::ActiveModel {
+    let now = Utc::now();
+    notes::ActiveModel {
+        entity_type: Set(entity_type),
+        entity_id: Set(entity_id),
+        note_type: Set(Some(notes::NoteType::Main.to_string())),
+        body_html: Set(body_html),
+        created_at: Set(now),
+        updated_at: Set(now),
+        ..Default::default()
+    }
+}
+
+/// The Markdown and CSV files under `dir` by export path
+fn read_folder(
+    dir: &Path,
+    prefix: &str,
+    out: &mut BTreeMap<String, String>,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            read_folder(&entry.path(), &format!("{}/", path), out)?;
+        } else if is_export_file(&path) {
+            match fs::read_to_string(entry.path()) {
+                Ok(text) => {
+                    out.insert(path, text);
+                }
+                Err(e) => warn!(path = %path, error = %e, "Skipping unreadable file"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The Markdown and CSV files of a zip by export path, unpacking the part
+/// zips of a large export
+fn read_zip<R: Read + Seek>(
+    reader: R,
+    out: &mut BTreeMap<String, String>,
+    outer: bool,
+) -> AppResult<()> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| AppError::validation("import_path", format!("Not a zip archive: {}", e)))?;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| AppError::other(format!("Failed to read zip entry: {}", e)))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let path = entry.name().trim_start_matches("./").to_string();
+        if outer && path.to_lowercase().ends_with(".zip") {
+            let mut bytes = Vec::new();
+            entry
+                .read_to_end(&mut bytes)
+                .map_err(|e| AppError::file_operation("read", path.clone(), e))?;
+            read_zip(Cursor::new(bytes), out, false)?;
+        } else if is_export_file(&path) {
+            let mut text = String::new();
+            match entry.read_to_string(&mut text) {
+                Ok(_) => {
+                    out.insert(path, text);
+                }
+                Err(e) => warn!(path = %path, error = %e, "Skipping unreadable file"),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_export_file(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".csv")
+}
+
+/// Sort the export's files into pages and databases
+fn parse_export<'a>(files: &'a BTreeMap<String, String>) -> NotionExport {
+    // Newer exports add `<name>_all.csv` next to the view's `<name>.csv`
+    let mut csvs: BTreeMap<&str, &str> = BTreeMap::new();
+    for (path, text) in files {
+        let Some(stem) = strip_suffix_ignore_case(path, ".csv") else {
+            continue;
+        };
+        match stem.strip_suffix("_all") {
+            Some(dir) => {
+                csvs.insert(dir, text);
+            }
+            None => {
+                csvs.entry(stem).or_insert(text);
+            }
+        }
+    }
+
+    // Row pages by database folder; other pages by path without `.md`
+    let mut row_pages: HashMap<&str, Vec<(String, String)>> = HashMap::new();
+    let mut pages: BTreeMap<&str, (String, String)> = BTreeMap::new();
+    for (path, text) in files {
+        let Some(key) = strip_suffix_ignore_case(path, ".md") else {
+            continue;
+        };
+        let dir = key.rsplit_once('/').map_or("", |(dir, _)| dir);
+        let (heading, body) = split_heading(text);
+        let title = heading.unwrap_or_else(|| display_name(key));
+        if csvs.contains_key(dir) {
+            row_pages
+                .entry(dir)
+                .or_default()
+                .push((title, body.to_string()));
+        } else {
+            pages.insert(key, (title, clean_links(body)));
+        }
+    }
+
+    let mut export = NotionExport::default();
+    for (dir, text) in &csvs {
+        let mut records = parse_csv(text).into_iter();
+        let Some(columns) = records.next() else {
+            continue;
+        };
+        let mut bodies = row_pages.remove(dir).unwrap_or_default();
+        let rows = records
+            .filter(|values| values.first().is_some_and(|title| !title.trim().is_empty()))
+            .map(|values| {
+                let title = values[0].trim();
+                let markdown = bodies
+                    .iter()
+                    .position(|(page_title, _)| page_title == title)
+                    .map(|i| bodies.remove(i).1)
+                    .map(|body| clean_links(strip_properties(&body, &columns)))
+                    .unwrap_or_default();
+                NotionRow { values, markdown }
+            })
+            .collect();
+        export.databases.push(NotionDatabase { columns, rows });
+    }
+
+    // A page's parent is the page whose folder holds it
+    let parent_of = |key: &'a str| {
+        key.rsplit_once('/')
+            .map(|(dir, _)| dir)
+            .filter(|dir| pages.contains_key(dir))
+    };
+    let mut children: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for key in pages.keys() {
+        if let Some(parent) = parent_of(key) {
+            children.entry(parent).or_default().push(key);
+        }
+    }
+    for key in pages.keys().filter(|key| parent_of(key).is_none()) {
+        let (title, markdown) = &pages[key];
+        let mut descendants = Vec::new();
+        let mut stack: Vec<&str> = children.get(key).cloned().unwrap_or_default();
+        stack.reverse();
+        while let Some(child) = stack.pop() {
+            descendants.push(child);
+            if let Some(grandchildren) = children.get(child) {
+                stack.extend(grandchildren.iter().rev());
+            }
+        }
+
+        export.pages.push(NotionPage {
+            title: title.clone(),
+            markdown: markdown.clone(),
+            writing_type: if descendants.is_empty() {
+                writings::WritingType::Article
+            } else {
+                writings::WritingType::Book
+            },
+            series: None,
+        });
+        for (part, child) in descendants.into_iter().enumerate() {
+            let (child_title, child_markdown) = &pages[child];
+            export.pages.push(NotionPage {
+                title: child_title.clone(),
+                markdown: child_markdown.clone(),
+                writing_type: writings::WritingType::Chapter,
+                series: Some((title.clone(), part as i32 + 1)),
+            });
+        }
+    }
+    export
+}
+
+fn strip_suffix_ignore_case<'a>(path: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = path.len().checked_sub(suffix.len())?;
+    (path.is_char_boundary(split) && path[split..].eq_ignore_ascii_case(suffix))
+        .then(|| &path[..split])
+}
+
+/// A file or folder name without the page id Notion appends
+fn display_name(key: &str) -> String {
+    let name = key.rsplit('/').next().unwrap_or(key);
+    let id = Regex::new(r"\s+[0-9a-f]{32}$").expect("valid page id regex");
+    id.replace(name, "").trim().to_string()
+}
+
+/// The page's `# Title` heading, and the Markdown after it
+fn split_heading(text: &str) -> (Option<String>, &str) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let trimmed = text.trim_start();
+    match trimmed.strip_prefix("# ") {
+        Some(rest) => {
+            let (heading, body) = rest.split_once('\n').unwrap_or((rest, ""));
+            (Some(heading.trim().to_string()), body)
+        }
+        None => (None, text),
+    }
+}
+
+/// A row page's body without the `Column: value` lines Notion opens it with
+fn strip_properties<'a>(body: &'a str, columns: &[String]) -> &'a str {
+    let mut rest = body.trim_start_matches(['\r', '\n']);
+    while let Some(line) = rest.lines().next() {
+        let is_property = line
+            .split_once(':')
+            .is_some_and(|(name, _)| columns.iter().any(|column| column == name));
+        if !is_property {
+            break;
+        }
+        rest = rest[line.len()..].trim_start_matches('\r');
+        rest = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+    rest.trim_start_matches(['\r', '\n'])
+}
+
+/// Links to other pages of the export become their text; attachments,
+/// which aren't imported, are dropped
+fn clean_links(markdown: &str) -> String {
+    let link = Regex::new(r"(!?)\[([^\]\n]*)\]\(([^)\s]+)\)").expect("valid link regex");
+    map_outside_code(markdown, |segment| {
+        link.replace_all(segment, |caps: &regex::Captures| {
+            let target = &caps[3];
+            if target.contains("://") || target.starts_with("mailto:") {
+                caps[0].to_string()
+            } else if caps[1].is_empty() {
+                caps[2].to_string()
+            } else {
+                String::new()
+            }
+        })
+        .into_owned()
+    })
+}
+
+/// Records of a CSV file; quoted fields may hold commas, quotes and newlines
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match (ch, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|record| record.iter().any(|field| !field.is_empty()));
+    records
+}
+
+/// A database row's values by column, each read into at most one field
+struct RowFields<'a> {
+    columns: &'a [String],
+    values: &'a [String],
+    used: Vec<bool>,
+}
+
+impl<'a> RowFields<'a> {
+    fn new(columns: &'a [String], values: &'a [String]) -> Self {
+        // The first column is the page title
+        let mut used = vec![false; columns.len()];
+        if let Some(first) = used.first_mut() {
+            *first = true;
+        }
+        RowFields {
+            columns,
+            values,
+            used,
+        }
+    }
+
+    fn title(&self) -> String {
+        self.values
+            .first()
+            .map(|title| title.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    /// The first unread, non-empty column named one of `names`
+    fn take(&mut self, names: &[&str]) -> Option<String> {
+        let i = (0..self.columns.len()).find(|&i| {
+            !self.used[i]
+                && names.contains(&self.columns[i].trim().to_lowercase().as_str())
+                && self.values.get(i).is_some_and(|v| !v.trim().is_empty())
+        })?;
+        self.used[i] = true;
+        Some(self.values[i].trim().to_string())
+    }
+
+    /// A URL column, or else the first column holding a URL
+    fn url(&mut self) -> Option<String> {
+        if let Some(url) = self.take(URL_COLUMNS).filter(|url| is_url(url)) {
+            return Some(url);
+        }
+        let i = (0..self.columns.len())
+            .find(|&i| !self.used[i] && self.values.get(i).is_some_and(|v| is_url(v.trim())))?;
+        self.used[i] = true;
+        Some(self.values[i].trim().to_string())
+    }
+
+    /// Unread, non-empty columns
+    fn rest(&self) -> Vec<(String, String)> {
+        (0..self.columns.len())
+            .filter(|&i| !self.used[i])
+            .filter_map(|i| {
+                let value = self.values.get(i)?.trim();
+                (!value.is_empty()).then(|| (self.columns[i].clone(), value.to_string()))
+            })
+            .collect()
+    }
+
+    fn rest_json(&self) -> Option<String> {
+        let rest = self.rest();
+        if rest.is_empty() {
+            return None;
+        }
+        let object: serde_json::Map<String, serde_json::Value> = rest
+            .into_iter()
+            .map(|(column, value)| (column, serde_json::Value::String(value)))
+            .collect();
+        Some(serde_json::Value::Object(object).to_string())
+    }
+}
+
+fn is_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+fn reference_type(kind: &str) -> Option<reference_items::ReferenceType> {
+    use reference_items::ReferenceType;
+    match kind.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+        "url" | "link" | "website" | "web" | "article" | "blog" | "post" => {
+            Some(ReferenceType::Url)
+        }
+        "tweet" => Some(ReferenceType::Tweet),
+        "paper" => Some(ReferenceType::Paper),
+        "book" => Some(ReferenceType::Book),
+        "pdf" => Some(ReferenceType::Pdf),
+        "manual" => Some(ReferenceType::Manual),
+        _ => None,
+    }
+}
+
+/// A writing status from the names Notion boards commonly use
+fn writing_status(status: &str) -> writings::WritingStatus {
+    use writings::WritingStatus;
+    match status
+        .trim()
+        .to_lowercase()
+        .replace([' ', '-'], "_")
+        .as_str()
+    {
+        "in_progress" | "writing" | "doing" | "drafting" => WritingStatus::InProgress,
+        "review" | "in_review" | "editing" => WritingStatus::Review,
+        "published" | "done" | "complete" | "completed" | "live" => WritingStatus::Published,
+        "archived" => WritingStatus::Archived,
+        _ => WritingStatus::Draft,
+    }
+}
+
+/// A date as Notion exports it, e.g. `March 5, 2024 3:00 PM`; a range
+/// yields its start
+fn parse_date(value: String) -> Option<DateTime<Utc>> {
+    let value = value.split(" → ").next().unwrap_or(&value).trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in ["%B %d, %Y %I:%M %p", "%B %d, %Y %H:%M", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(date.and_utc());
+        }
+    }
+    ["%B %d, %Y", "%Y-%m-%d", "%Y/%m/%d"]
+        .into_iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_export() {
+        let id = "0123456789abcdef0123456789abcdef";
+        let files: BTreeMap<String, String> = [
+            (format!("Novel {id}.md"), "# Novel\n\nBlurb\n".to_string()),
+            (format!("Novel {id}/One {id}.md"), "# One\n\nSee [Two](Two%20{id}.md) ![](One/pic.png) and [site](https://x.org).\n".replace("{id}", id)),
+            (format!("Novel {id}/One {id}/Deep {id}.md"), "# Deep\n\nDeeper\n".to_string()),
+            (format!("Novel {id}/Two {id}.md"), "# Two\n\nSecond\n".to_string()),
+            (format!("Loose {id}.md"), "No heading\n".to_string()),
+            (format!("Reading {id}.csv"), "Old,view\n".to_string()),
+            (format!("Reading {id}_all.csv"), "\u{feff}Name,URL,Tags,Notes\n\"Paper, the\",https://p.org,\"a, b\",\"says \"\"hi\"\"\nacross lines\"\nDraft,,x,\n".to_string()),
+            (format!("Reading {id}/Paper, the {id}.md"), "# Paper, the\n\nURL: https://p.org\nTags: a, b\n\nMy notes.\n".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        let export = parse_export(&files);
+        let pages: Vec<_> = export
+            .pages
+            .iter()
+            .map(|p| (p.title.as_str(), p.writing_type.clone(), p.series.clone()))
+            .collect();
+        assert_eq!(
+            pages,
+            vec![
+                ("Loose", writings::WritingType::Article, None),
+                ("Novel", writings::WritingType::Book, None),
+                (
+                    "One",
+                    writings::WritingType::Chapter,
+                    Some(("Novel".to_string(), 1))
+                ),
+                (
+                    "Deep",
+                    writings::WritingType::Chapter,
+                    Some(("Novel".to_string(), 2))
+                ),
+                (
+                    "Two",
+                    writings::WritingType::Chapter,
+                    Some(("Novel".to_string(), 3))
+                ),
+            ]
+        );
+        assert_eq!(
+            export.pages[2].markdown,
+            "\nSee Two  and [site](https://x.org).\n"
+        );
+
+        assert_eq!(export.databases.len(), 1);
+        let database = &export.databases[0];
+        assert_eq!(database.columns, vec!["Name", "URL", "Tags", "Notes"]);
+        assert_eq!(database.rows.len(), 2);
+        assert_eq!(database.rows[0].values[3], "says \"hi\"\nacross lines");
+        assert_eq!(database.rows[0].markdown, "My notes.\n");
+
+        let mut fields = RowFields::new(&database.columns, &database.rows[0].values);
+        assert_eq!(fields.url().as_deref(), Some("https://p.org"));
+        assert_eq!(fields.take(TAG_COLUMNS).as_deref(), Some("a, b"));
+        assert_eq!(
+            fields.rest_json().as_deref(),
+            Some(r#"{"Notes":"says \"hi\"\nacross lines"}"#)
+        );
+        let mut fields = RowFields::new(&database.columns, &database.rows[1].values);
+        assert_eq!(fields.url(), None);
+
+        assert_eq!(
+            parse_date("March 5, 2024 3:00 PM → March 6, 2024".to_string()),
+            Some(
+                NaiveDate::from_ymd_opt(2024, 3, 5)
+                    .unwrap()
+                    .and_hms_opt(15, 0, 0)
+                    .unwrap()
+                    .and_utc()
+            )
+        );
+    }
+}
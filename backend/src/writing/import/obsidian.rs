@@ -0,0 +1,575 @@
+//! Obsidian vault import
+//!
+//! Every Markdown file in the vault becomes a draft writing, or an idea with
+//! the file as its main note when it sits in one of the notes folders. Front
+//! matter sets the title, aliases, tags, slug, excerpt and writing type.
+//!
+//! Wiki-links are kept as their text and become knowledge-graph links where
+//! the graph has an edge for them: a writing linking an idea gets a
+//! `mention` link, an idea linking a reference a `background` one. Targets
+//! are matched by note name, title or alias against the vault and against
+//! existing ideas and references. Hidden folders such as `.obsidian` and
+//! embedded attachments are left out.
+//!
+//! The vault is read from its folder or from a zip of it, such as one staged
+//! through `POST /upload`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use regex::Regex;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, Set,
+    TransactionTrait,
+};
+use tracing::{debug, info, instrument, warn};
+use zip::ZipArchive;
+
+use crate::core::components::errors::{AppError, AppResult};
+use crate::writing::components::ideas::types::{self as ideas, tags_to_json, IdeaStatus};
+use crate::writing::components::knowledge_graph::entities::{
+    idea_reference_links, notes, reference_items, writing_idea_links, writings,
+};
+use crate::writing::dto::ObsidianImportDto;
+use crate::writing::export::slugify;
+use crate::writing::text::{extract_plain_text, markdown_to_tiptap, tiptap_to_html, word_count};
+
+use super::{map_outside_code, FrontMatter};
+
+/// Embeds with these extensions are attachments, not notes
+const ATTACHMENT_EXTENSIONS: [&str; 16] = [
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "bmp", "avif", "pdf", "mp3", "wav", "ogg", "m4a",
+    "mp4", "webm", "mov",
+];
+
+/// A Markdown file of the vault, parsed
+struct VaultNote {
+    path: String,
+    title: String,
+    /// Note name, title and aliases, normalized for link matching
+    names: Vec<String>,
+    tags: Vec<String>,
+    /// `None` for an idea note
+    writing_type: Option<writings::WritingType>,
+    slug: Option<String>,
+    excerpt: Option<String>,
+    markdown: String,
+    /// Wiki-link targets as note names
+    links: Vec<String>,
+}
+
+/// What a wiki-link target resolved to
+#[derive(Clone, Copy)]
+enum Target {
+    Idea(i64),
+    Reference(i64),
+    /// A writing of the vault, which no link table has an edge to
+    Writing,
+}
+
+/// Import the Markdown files of an Obsidian vault, a folder or a zip of it
+#[instrument(skip(db))]
+pub async fn import_obsidian_vault(
+    db: &DatabaseConnection,
+    vault: &Path,
+    notes_folders: &[String],
+) -> AppResult<ObsidianImportDto> {
+    let files = if vault.is_dir() {
+        read_folder(vault)
+            .map_err(|e| AppError::file_operation("read directory", vault.to_string_lossy(), e))?
+    } else if vault.is_file() {
+        let file = File::open(vault)
+            .map_err(|e| AppError::file_operation("open", vault.to_string_lossy(), e))?;
+        read_zip(file)?
+    } else {
+        return Err(AppError::validation("vault_path", "Vault not found"));
+    };
+    let notes_folders: Vec<String> = notes_folders
+        .iter()
+        .map(|folder| {
+            folder
+                .trim()
+                .trim_matches('/')
+                .trim_start_matches("./")
+                .to_string()
+        })
+        .map(|folder| if folder == "." { String::new() } else { folder })
+        .collect();
+
+    let vault_notes: Vec<VaultNote> = files
+        .into_iter()
+        .map(|(path, text)| parse_note(path, &text, &notes_folders))
+        .collect();
+
+    // Link targets already in the graph; vault ideas take precedence
+    let mut targets: HashMap<String, Target> = HashMap::new();
+    let references: Vec<(i64, String)> = reference_items::Entity::find()
+        .select_only()
+        .columns([reference_items::Column::Id, reference_items::Column::Title])
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (id, title) in references {
+        targets.insert(normalize(&title), Target::Reference(id));
+    }
+    let existing_ideas: Vec<(i64, String)> = ideas::Entity::find()
+        .filter(ideas::Column::DateRemoved.is_null())
+        .select_only()
+        .columns([ideas::Column::Id, ideas::Column::Title])
+        .into_tuple()
+        .all(db)
+        .await?;
+    for (id, title) in existing_ideas {
+        targets.insert(normalize(&title), Target::Idea(id));
+    }
+    let mut taken_slugs: HashSet<String> = writings::Entity::find()
+        .select_only()
+        .column(writings::Column::Slug)
+        .into_tuple::<Option<String>>()
+        .all(db)
+        .await?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    let tx = db.begin().await?;
+    let now = Utc::now();
+    let mut result = ObsidianImportDto {
+        writings_created: 0,
+        ideas_created: 0,
+        skipped: 0,
+        links_created: 0,
+        unresolved_links: Vec::new(),
+    };
+
+    // Ideas first, so writings can link them; `None` marks an existing idea
+    let mut created_ideas = Vec::new();
+    for note in vault_notes.iter().filter(|n| n.writing_type.is_none()) {
+        let idea_id = match targets.get(&normalize(&note.title)) {
+            Some(Target::Idea(id)) => {
+                debug!(path = %note.path, "Idea already exists");
+                result.skipped += 1;
+                created_ideas.push(None);
+                *id
+            }
+            _ => {
+                let idea = ideas::ActiveModel {
+                    title: Set(note.title.clone()),
+                    summary: Set(note.excerpt.clone()),
+                    status: Set(IdeaStatus::InProgress),
+                    tags: Set(tags_to_json(&note.tags)),
+                    date_added: Set(now),
+                    date_updated: Set(now),
+                    priority: Set(0),
+                    is_pinned: Set(0),
+                    ..Default::default()
+                }
+                .insert(&tx)
+                .await?;
+                notes::ActiveModel {
+                    entity_type: Set(notes::EntityType::Idea),
+                    entity_id: Set(idea.id),
+                    note_type: Set(Some(notes::NoteType::Main.to_string())),
+                    body_html: Set(tiptap_to_html(&markdown_to_tiptap(&note.markdown))),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&tx)
+                .await?;
+                result.ideas_created += 1;
+                created_ideas.push(Some(idea.id));
+                idea.id
+            }
+        };
+        for name in &note.names {
+            targets.insert(name.clone(), Target::Idea(idea_id));
+        }
+    }
+    for note in vault_notes.iter().filter(|n| n.writing_type.is_some()) {
+        for name in &note.names {
+            targets.entry(name.clone()).or_insert(Target::Writing);
+        }
+    }
+
+    let mut unresolved = BTreeSet::new();
+    let mut resolve = |note: &VaultNote| -> Vec<Target> {
+        let mut found = Vec::new();
+        for link in &note.links {
+            match targets.get(&normalize(link)) {
+                Some(target) => found.push(*target),
+                None => {
+                    unresolved.insert(link.clone());
+                }
+            }
+        }
+        found
+    };
+
+    let vault_ideas = vault_notes.iter().filter(|n| n.writing_type.is_none());
+    for (note, idea_id) in vault_ideas.zip(created_ideas) {
+        let links = resolve(note);
+        let Some(idea_id) = idea_id else { continue };
+        let mut linked = HashSet::new();
+        for target in links {
+            let Target::Reference(reference_id) = target else {
+                continue;
+            };
+            if !linked.insert(reference_id) {
+                continue;
+            }
+            idea_reference_links::ActiveModel {
+                idea_id: Set(idea_id),
+                reference_id: Set(reference_id),
+                role: Set(idea_reference_links::ReferenceRole::Background),
+                notes: Set(None),
+                link_order: Set(linked.len() as i32 - 1),
+                created_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&tx)
+            .await?;
+            result.links_created += 1;
+        }
+    }
+
+    for note in &vault_notes {
+        let Some(writing_type) = note.writing_type.clone() else {
+            continue;
+        };
+        let links = resolve(note);
+        let slug = note.slug.clone().unwrap_or_else(|| slugify(&note.title));
+        if !slug.is_empty() && !taken_slugs.insert(slug.clone()) {
+            debug!(path = %note.path, slug = %slug, "Writing slug already taken");
+            result.skipped += 1;
+            continue;
+        }
+
+        let content = markdown_to_tiptap(&note.markdown);
+        let writing = writings::ActiveModel {
+            r#type: Set(writing_type),
+            title: Set(note.title.clone()),
+            slug: Set(Some(slug).filter(|s| !s.is_empty())),
+            content_markdown: Set(content.to_string()),
+            excerpt: Set(note.excerpt.clone()),
+            status: Set(writings::WritingStatus::Draft),
+            tags: Set(tags_to_json(&note.tags)),
+            word_count: Set(word_count(&extract_plain_text(&content))),
+            series_name: Set(None),
+            series_part: Set(None),
+            is_pinned: Set(0),
+            is_featured: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+            published_at: Set(None),
+            ..Default::default()
+        }
+        .insert(&tx)
+        .await?;
+        result.writings_created += 1;
+
+        let mut linked = HashSet::new();
+        for target in links {
+            let Target::Idea(idea_id) = target else {
+                continue;
+            };
+            if !linked.insert(idea_id) {
+                continue;
+            }
+            writing_idea_links::ActiveModel {
+                writing_id: Set(writing.id),
+                idea_id: Set(idea_id),
+                purpose: Set(Some("mention".to_string())),
+                link_order: Set(linked.len() as i32 - 1),
+                created_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&tx)
+            .await?;
+            result.links_created += 1;
+        }
+    }
+
+    tx.commit().await?;
+    result.unresolved_links = unresolved.into_iter().collect();
+
+    info!(
+        vault = %vault.display(),
+        writings = result.writings_created,
+        ideas = result.ideas_created,
+        skipped = result.skipped,
+        links = result.links_created,
+        unresolved = result.unresolved_links.len(),
+        "Imported Obsidian vault"
+    );
+    Ok(result)
+}
+
+/// Markdown files under `dir`, in name order, skipping hidden entries
+/// The Markdown files of a vault folder by vault path
+fn read_folder(vault: &Path) -> std::io::Result<BTreeMap<String, String>> {
+    let mut paths = Vec::new();
+    collect_markdown(vault, &mut paths)?;
+    let mut files = BTreeMap::new();
+    for file in &paths {
+        let text = match fs::read_to_string(file) {
+            Ok(text) => text,
+            Err(e) => {
+                warn!(path = %file.display(), error = %e, "Skipping unreadable note");
+                continue;
+            }
+        };
+        let path = file
+            .strip_prefix(vault)
+            .unwrap_or(file)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.insert(path, text);
+    }
+    Ok(files)
+}
+
+/// The Markdown files of a zipped vault by vault path
+///
+/// A zip of the vault folder itself is read from inside that folder.
+fn read_zip<R: Read + Seek>(reader: R) -> AppResult<BTreeMap<String, String>> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| AppError::validation("vault_path", format!("Not a zip archive: {}", e)))?;
+    let mut files = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| AppError::other(format!("Failed to read zip entry: {}", e)))?;
+        let path = entry.name().trim_start_matches("./").to_string();
+        let hidden = path.split('/').any(|part| part.starts_with('.'));
+        if entry.is_dir() || hidden || !path.to_lowercase().ends_with(".md") {
+            continue;
+        }
+        let mut text = String::new();
+        match entry.read_to_string(&mut text) {
+            Ok(_) => {
+                files.insert(path, text);
+            }
+            Err(e) => warn!(path = %path, error = %e, "Skipping unreadable note"),
+        }
+    }
+
+    let root = files
+        .keys()
+        .next()
+        .and_then(|path| path.split_once('/'))
+        .map(|(root, _)| format!("{}/", root));
+    match root.filter(|root| files.keys().all(|path| path.starts_with(root.as_str()))) {
+        Some(root) => Ok(files
+            .into_iter()
+            .map(|(path, text)| (path[root.len()..].to_string(), text))
+            .collect()),
+        None => Ok(files),
+    }
+}
+
+fn collect_markdown(dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_markdown(&path, out)?;
+        } else if file_type.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn parse_note(path: String, text: &str, notes_folders: &[String]) -> VaultNote {
+    let (front, body) = FrontMatter::parse(text);
+    let stem = path
+        .rsplit('/')
+        .next()
+        .unwrap_or(&path)
+        .trim_end_matches(".md")
+        .trim_end_matches(".MD")
+        .to_string();
+    let title = front.text("title").unwrap_or(&stem).to_string();
+
+    let mut names = vec![normalize(&stem), normalize(&title)];
+    names.extend(
+        front
+            .list("aliases")
+            .into_iter()
+            .chain(front.list("alias"))
+            .map(|alias| normalize(&alias)),
+    );
+    names.dedup();
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in front.list("tags").into_iter().chain(front.list("tag")) {
+        for tag in tag.split_whitespace() {
+            let tag = tag.trim_start_matches('#').to_string();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+
+    let in_notes_folder = notes_folders.iter().any(|folder| {
+        folder.is_empty()
+            || path
+                .strip_prefix(folder.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    });
+    let writing_type = if in_notes_folder {
+        None
+    } else {
+        Some(match front.text("type") {
+            Some("chapter") => writings::WritingType::Chapter,
+            Some("book") => writings::WritingType::Book,
+            _ => writings::WritingType::Article,
+        })
+    };
+
+    let (markdown, links) = replace_wiki_links(body);
+    VaultNote {
+        title,
+        names,
+        tags,
+        writing_type,
+        slug: front.text("slug").map(str::to_string),
+        excerpt: front
+            .text("excerpt")
+            .or_else(|| front.text("description"))
+            .or_else(|| front.text("summary"))
+            .map(str::to_string),
+        markdown,
+        links,
+        path,
+    }
+}
+
+/// Replaces wiki-links outside code with their text and returns the note
+/// names they target; attachment embeds are dropped
+fn replace_wiki_links(markdown: &str) -> (String, Vec<String>) {
+    let wiki_link = Regex::new(r"(!?)\[\[([^\[\]\n]+)\]\]").expect("valid wiki-link regex");
+    let mut links = Vec::new();
+    let out = map_outside_code(markdown, |segment| {
+        wiki_link
+            .replace_all(segment, |caps: &regex::Captures| {
+                let embed = !caps[1].is_empty();
+                let (target, alias) = match caps[2].split_once('|') {
+                    Some((target, alias)) => (target.trim(), Some(alias.trim())),
+                    None => (caps[2].trim(), None),
+                };
+                let name = note_name(target);
+                if embed && is_attachment(name) {
+                    return String::new();
+                }
+                if !name.is_empty() {
+                    links.push(name.to_string());
+                }
+                alias.unwrap_or(target).replace('#', " > ")
+            })
+            .into_owned()
+    });
+    (out, links)
+}
+
+/// The note a link target points at: no heading or block, folder or `.md`
+fn note_name(target: &str) -> &str {
+    let target = target.split('#').next().unwrap_or_default();
+    let name = target.rsplit('/').next().unwrap_or(target).trim();
+    name.strip_suffix(".md").unwrap_or(name)
+}
+
+fn is_attachment(name: &str) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, ext)| {
+        ATTACHMENT_EXTENSIONS
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
+
+/// Case-insensitive key for matching link targets to titles
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note() {
+        let text = "---\ntitle: Cats\naliases: [Felines]\ntags: [pets, \"#animals nature\"]\n---\nSee [[Dogs|the dogs]], [[ideas/Fur#Shedding]] and `[[code]]`.\n![[photo.png]]\n![[Transcluded]]\n```\n[[fenced]]\n```\n";
+        let note = parse_note(
+            "Research/Cats.md".to_string(),
+            text,
+            &["Research".to_string()],
+        );
+        assert_eq!(note.title, "Cats");
+        assert_eq!(note.names, vec!["cats", "felines"]);
+        assert_eq!(note.tags, vec!["pets", "animals", "nature"]);
+        assert!(note.writing_type.is_none());
+        assert_eq!(note.links, vec!["Dogs", "Fur", "Transcluded"]);
+        assert_eq!(
+            note.markdown,
+            "See the dogs, ideas/Fur > Shedding and `[[code]]`.\n\nTranscluded\n```\n[[fenced]]\n```\n"
+        );
+
+        let note = parse_note(
+            "Drafts/Intro.md".to_string(),
+            "---\ntype: chapter\n---\nHi",
+            &[],
+        );
+        assert_eq!(note.title, "Intro");
+        assert!(matches!(
+            note.writing_type,
+            Some(writings::WritingType::Chapter)
+        ));
+    }
+
+    #[test]
+    fn test_read_zip() {
+        use std::io::{Cursor, Write};
+        use zip::write::SimpleFileOptions;
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (path, text) in [
+            ("Vault/Ideas/Cats.md", "Cats"),
+            ("Vault/Intro.MD", "Hi"),
+            ("Vault/.obsidian/notes.md", "hidden"),
+            ("Vault/photo.png", "png"),
+        ] {
+            zip.start_file(path, SimpleFileOptions::default()).unwrap();
+            zip.write_all(text.as_bytes()).unwrap();
+        }
+        let files = read_zip(zip.finish().unwrap()).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec!["Ideas/Cats.md", "Intro.MD"]
+        );
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for path in ["Vault/Cats.md", "Dogs.md"] {
+            zip.start_file(path, SimpleFileOptions::default()).unwrap();
+        }
+        let files = read_zip(zip.finish().unwrap()).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec!["Dogs.md", "Vault/Cats.md"]
+        );
+    }
+}
@@ -6,8 +6,12 @@
 pub mod components;
 pub mod commands;
 pub mod dto;
+pub mod export;
+pub mod git;
+pub mod import;
 pub mod text;
 pub mod service;
+pub mod sync;
 
 // Re-export commonly used types
 // Re-export components when needed
@@ -3,8 +3,8 @@
 //! Business logic for creating, updating, and managing writings with TipTap JSON content
 
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter,
-    QueryOrder, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Set, TransactionTrait,
 };
 use chrono::Utc;
 use serde_json::Value as JsonValue;
@@ -102,15 +102,14 @@ pub async fn get_writing(
         )))
 }
 
-/// List writings with optional filters
-pub async fn list_writings(
-    db: &DatabaseConnection,
+/// Writings matching the list filters, before sorting and pagination
+fn filtered_writings(
     status: Option<writings::WritingStatus>,
     writing_type: Option<writings::WritingType>,
     series_name: Option<String>,
     is_pinned: Option<bool>,
     is_featured: Option<bool>,
-) -> Result<Vec<writings::Model>, DbErr> {
+) -> Select<writings::Entity> {
     let mut query = writings::Entity::find();
 
     if let Some(s) = status {
@@ -130,8 +129,40 @@ pub async fn list_writings(
     }
 
     query
-        .order_by_desc(writings::Column::UpdatedAt)
-        .all(db)
+}
+
+/// List writings with optional filters; `limit: None` returns every match
+#[allow(clippy::too_many_arguments)]
+pub async fn list_writings(
+    db: &DatabaseConnection,
+    status: Option<writings::WritingStatus>,
+    writing_type: Option<writings::WritingType>,
+    series_name: Option<String>,
+    is_pinned: Option<bool>,
+    is_featured: Option<bool>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+) -> Result<Vec<writings::Model>, DbErr> {
+    let mut query = filtered_writings(status, writing_type, series_name, is_pinned, is_featured)
+        .order_by_desc(writings::Column::UpdatedAt);
+    // SQLite only accepts OFFSET alongside LIMIT
+    if let Some(limit) = limit {
+        query = query.limit(limit).offset(offset.unwrap_or(0));
+    }
+    query.all(db).await
+}
+
+/// Number of writings matching the `list_writings` filters
+pub async fn count_writings(
+    db: &DatabaseConnection,
+    status: Option<writings::WritingStatus>,
+    writing_type: Option<writings::WritingType>,
+    series_name: Option<String>,
+    is_pinned: Option<bool>,
+    is_featured: Option<bool>,
+) -> Result<u64, DbErr> {
+    filtered_writings(status, writing_type, series_name, is_pinned, is_featured)
+        .count(db)
         .await
 }
 
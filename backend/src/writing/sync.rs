@@ -0,0 +1,582 @@
+//! Two-way sync of writings with a folder of Markdown files
+//!
+//! Every writing but archived ones is mirrored as one Markdown file in
+//! WRITING_SYNC_DIR, named after its slug, in the Markdown export format
+//! without timestamps, so the folder can be a Git repository or be edited in
+//! any editor. Each sync compares a file and its writing with the hashes
+//! recorded at the last sync:
+//!
+//! - Only the writing changed: the file is rewritten
+//! - Only the file changed: the writing is updated from it
+//! - A new file becomes a new writing; a deleted file archives its writing,
+//!   and a writing deleted or archived in the app takes its file along
+//! - Both changed: a conflict, settled by the [`SyncConflictStrategy`].
+//!   Under `report` nothing is overwritten; the writing is written next to
+//!   the file as `<name>.conflict.md`, to merge into the file before syncing
+//!   again with `prefer_file`
+//!
+//! Files keep the name they were first written with. The scheduled
+//! `writing_folder_sync` task runs a sync every minute when enabled.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set,
+};
+use sha2::{Digest, Sha256};
+use tracing::{info, instrument, warn};
+
+use crate::core::components::config::StorageConfig;
+use crate::core::components::errors::{AppError, AppResult};
+use crate::system::components::scheduler::TaskRunResult;
+use crate::writing::components::ideas::types::tags_to_json;
+use crate::writing::components::knowledge_graph::entities::{writing_sync_files, writings};
+use crate::writing::dto::{
+    FolderSyncDto, SyncConflictDto, SyncConflictKind, SyncConflictStrategy, SyncFolderInput,
+};
+use crate::writing::export::markdown::{file_name, markdown_file};
+use crate::writing::import::FrontMatter;
+use crate::writing::text::{extract_plain_text, markdown_to_tiptap, word_count};
+use crate::AppState;
+
+/// Suffix of the app's side of a conflict, next to the file
+const CONFLICT_SUFFIX: &str = ".conflict.md";
+
+/// Sync the writings with the folder in WRITING_SYNC_DIR
+#[instrument(skip(db, storage_config))]
+pub async fn sync_writings_folder(
+    db: &DatabaseConnection,
+    storage_config: &StorageConfig,
+    input: &SyncFolderInput,
+) -> AppResult<FolderSyncDto> {
+    let folder =
+        storage_config
+            .writing_sync_dir
+            .as_deref()
+            .ok_or_else(|| AppError::ConfigValidation {
+                field: "WRITING_SYNC_DIR".to_string(),
+                reason: "No writing sync folder configured".to_string(),
+                suggestion: Some("Set WRITING_SYNC_DIR in ~/.cockpit/.env".to_string()),
+            })?;
+    fs::create_dir_all(folder)
+        .map_err(|e| AppError::file_operation("create directory", folder.to_string_lossy(), e))?;
+    let folder_key = folder.to_string_lossy().to_string();
+
+    // A new folder starts over rather than reading deletions into it
+    writing_sync_files::Entity::delete_many()
+        .filter(writing_sync_files::Column::Folder.ne(folder_key.as_str()))
+        .exec(db)
+        .await?;
+    let states = writing_sync_files::Entity::find()
+        .filter(writing_sync_files::Column::Folder.eq(folder_key.as_str()))
+        .order_by_asc(writing_sync_files::Column::Id)
+        .all(db)
+        .await?;
+    let all_writings: HashMap<i64, writings::Model> = writings::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|w| (w.id, w))
+        .collect();
+    let files = read_folder(folder)?;
+
+    let mut sync = FolderSync {
+        db,
+        folder,
+        folder_key,
+        strategy: input.conflict_strategy.unwrap_or_default(),
+        result: FolderSyncDto::default(),
+    };
+    sync.result.folder = sync.folder_key.clone();
+
+    let mut taken: HashSet<String> = states.iter().map(|s| s.file_name.clone()).collect();
+    let tracked_files = taken.clone();
+    let tracked_ids: HashSet<i64> = states.iter().map(|s| s.writing_id).collect();
+    for state in states {
+        let writing = all_writings.get(&state.writing_id);
+        let file = files.get(&state.file_name).map(String::as_str);
+        sync.reconcile(state, writing, file).await?;
+    }
+
+    // Writings new to the folder, paired with a file of their name if any
+    let mut untracked: Vec<&writings::Model> = all_writings
+        .values()
+        .filter(|w| is_mirrored(w) && !tracked_ids.contains(&w.id))
+        .collect();
+    untracked.sort_by_key(|w| w.id);
+    let mut paired = HashSet::new();
+    for writing in untracked {
+        let name = file_name(writing, &mut taken);
+        let app_text = markdown_file(writing, false);
+        match files.get(&name) {
+            Some(file_text) => {
+                paired.insert(name.clone());
+                if *file_text == app_text {
+                    sync.track(None, writing.id, &name, file_text, &app_text)
+                        .await?;
+                } else {
+                    sync.both_changed(None, writing, &name, file_text, &app_text)
+                        .await?;
+                }
+            }
+            None => {
+                sync.write_file(&name, &app_text)?;
+                sync.track(None, writing.id, &name, &app_text, &app_text)
+                    .await?;
+            }
+        }
+    }
+
+    // Files new to the folder become writings
+    for (name, text) in &files {
+        if !tracked_files.contains(name) && !paired.contains(name) {
+            sync.create_writing(None, name, text).await?;
+        }
+    }
+
+    let result = sync.result;
+    info!(
+        folder = %result.folder,
+        written = result.files_written,
+        removed = result.files_removed,
+        updated = result.writings_updated,
+        created = result.writings_created,
+        archived = result.writings_archived,
+        conflicts = result.conflicts.len(),
+        "Synced writings folder"
+    );
+    Ok(result)
+}
+
+/// Scheduled task: sync the folder, reporting conflicts
+pub async fn run_writing_folder_sync_task(state: &AppState) -> TaskRunResult {
+    let input = SyncFolderInput::default();
+    match sync_writings_folder(&state.db, &state.config.current().storage, &input).await {
+        Ok(result) => TaskRunResult {
+            status: "success",
+            result_json: serde_json::to_string(&result).ok(),
+            error_message: None,
+        },
+        Err(e) => TaskRunResult {
+            status: "error",
+            result_json: None,
+            error_message: Some(e.to_string()),
+        },
+    }
+}
+
+/// Write one writing's file, as a sync would, and record it as in sync
+///
+/// Leaves alone a file edited outside the app, or an untracked file already
+/// holding the writing's name, for the next sync to reconcile. Returns the
+/// file's name unless it was left alone.
+pub async fn mirror_writing(
+    db: &DatabaseConnection,
+    folder: &Path,
+    writing: &writings::Model,
+) -> AppResult<Option<String>> {
+    if !is_mirrored(writing) {
+        return Ok(None);
+    }
+    fs::create_dir_all(folder)
+        .map_err(|e| AppError::file_operation("create directory", folder.to_string_lossy(), e))?;
+    let folder_key = folder.to_string_lossy().to_string();
+    let states = writing_sync_files::Entity::find()
+        .filter(writing_sync_files::Column::Folder.eq(folder_key.as_str()))
+        .all(db)
+        .await?;
+
+    let app_text = markdown_file(writing, false);
+    let mut taken: HashSet<String> = states.iter().map(|s| s.file_name.clone()).collect();
+    let state = states.into_iter().find(|s| s.writing_id == writing.id);
+    let name = match &state {
+        Some(state) => state.file_name.clone(),
+        None => file_name(writing, &mut taken),
+    };
+    let file = fs::read_to_string(folder.join(&name)).ok();
+    let untouched = match (&state, &file) {
+        (Some(state), Some(file_text)) => sha256(file_text) == state.file_hash,
+        (Some(_), None) => false,
+        (None, file_text) => file_text.is_none(),
+    };
+    if !untouched {
+        return Ok(None);
+    }
+    if file.as_deref() == Some(app_text.as_str()) {
+        return Ok(Some(name));
+    }
+
+    let mut sync = FolderSync {
+        db,
+        folder,
+        folder_key,
+        strategy: SyncConflictStrategy::default(),
+        result: FolderSyncDto::default(),
+    };
+    sync.write_file(&name, &app_text)?;
+    sync.track(state, writing.id, &name, &app_text, &app_text)
+        .await?;
+    Ok(Some(name))
+}
+
+struct FolderSync<'a> {
+    db: &'a DatabaseConnection,
+    folder: &'a Path,
+    folder_key: String,
+    strategy: SyncConflictStrategy,
+    result: FolderSyncDto,
+}
+
+impl FolderSync<'_> {
+    /// Bring a tracked writing and its file back in line
+    async fn reconcile(
+        &mut self,
+        state: writing_sync_files::Model,
+        writing: Option<&writings::Model>,
+        file: Option<&str>,
+    ) -> AppResult<()> {
+        let name = state.file_name.clone();
+        match (writing.filter(|w| is_mirrored(w)), file) {
+            (Some(writing), Some(file_text)) => {
+                let app_text = markdown_file(writing, false);
+                let app_changed = sha256(&app_text) != state.writing_hash;
+                let file_changed = sha256(file_text) != state.file_hash;
+                match (app_changed, file_changed) {
+                    (false, false) => {}
+                    (true, false) => {
+                        self.write_file(&name, &app_text)?;
+                        self.track(Some(state), writing.id, &name, &app_text, &app_text)
+                            .await?;
+                    }
+                    (false, true) => self.pull(state, writing, file_text).await?,
+                    (true, true) if app_text == file_text => {
+                        self.track(Some(state), writing.id, &name, file_text, &app_text)
+                            .await?;
+                    }
+                    (true, true) => {
+                        self.both_changed(Some(state), writing, &name, file_text, &app_text)
+                            .await?;
+                    }
+                }
+            }
+            (Some(writing), None) => {
+                let app_text = markdown_file(writing, false);
+                let app_changed = sha256(&app_text) != state.writing_hash;
+                match self.strategy {
+                    SyncConflictStrategy::Report if app_changed => {
+                        self.conflict(&name, Some(writing.id), SyncConflictKind::DeletedInFolder);
+                    }
+                    SyncConflictStrategy::PreferApp if app_changed => {
+                        self.write_file(&name, &app_text)?;
+                        self.track(Some(state), writing.id, &name, &app_text, &app_text)
+                            .await?;
+                    }
+                    _ => {
+                        let mut active = writing.clone().into_active_model();
+                        active.status = Set(writings::WritingStatus::Archived);
+                        active.updated_at = Set(Utc::now());
+                        active.update(self.db).await?;
+                        writing_sync_files::Entity::delete_by_id(state.id)
+                            .exec(self.db)
+                            .await?;
+                        self.result.writings_archived += 1;
+                    }
+                }
+            }
+            (None, Some(file_text)) => {
+                let file_changed = sha256(file_text) != state.file_hash;
+                match self.strategy {
+                    SyncConflictStrategy::Report if file_changed => {
+                        self.conflict(&name, writing.map(|w| w.id), SyncConflictKind::DeletedInApp);
+                    }
+                    // An archived writing comes back; a deleted one is created anew
+                    SyncConflictStrategy::PreferFile if file_changed => match writing {
+                        Some(writing) => self.pull(state, writing, file_text).await?,
+                        None => self.create_writing(Some(state), &name, file_text).await?,
+                    },
+                    _ => {
+                        self.remove_file(&name)?;
+                        writing_sync_files::Entity::delete_by_id(state.id)
+                            .exec(self.db)
+                            .await?;
+                        self.result.files_removed += 1;
+                    }
+                }
+            }
+            (None, None) => {
+                writing_sync_files::Entity::delete_by_id(state.id)
+                    .exec(self.db)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Settle a writing and file that both changed, per the strategy
+    async fn both_changed(
+        &mut self,
+        state: Option<writing_sync_files::Model>,
+        writing: &writings::Model,
+        name: &str,
+        file_text: &str,
+        app_text: &str,
+    ) -> AppResult<()> {
+        match self.strategy {
+            SyncConflictStrategy::Report => {
+                let copy = conflict_name(name);
+                if fs::read_to_string(self.folder.join(&copy)).ok().as_deref() != Some(app_text) {
+                    self.write_file(&copy, app_text)?;
+                }
+                self.conflict(name, Some(writing.id), SyncConflictKind::BothChanged);
+            }
+            SyncConflictStrategy::PreferFile => match state {
+                Some(state) => self.pull(state, writing, file_text).await?,
+                None => {
+                    let updated = self.update_writing(writing, name, file_text).await?;
+                    let app_text = markdown_file(&updated, false);
+                    self.track(None, writing.id, name, file_text, &app_text)
+                        .await?;
+                }
+            },
+            SyncConflictStrategy::PreferApp => {
+                self.write_file(name, app_text)?;
+                self.track(state, writing.id, name, app_text, app_text)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Update a writing from its edited file
+    async fn pull(
+        &mut self,
+        state: writing_sync_files::Model,
+        writing: &writings::Model,
+        file_text: &str,
+    ) -> AppResult<()> {
+        let name = state.file_name.clone();
+        let updated = self.update_writing(writing, &name, file_text).await?;
+        let app_text = markdown_file(&updated, false);
+        self.track(Some(state), writing.id, &name, file_text, &app_text)
+            .await
+    }
+
+    async fn update_writing(
+        &mut self,
+        writing: &writings::Model,
+        name: &str,
+        file_text: &str,
+    ) -> AppResult<writings::Model> {
+        let mut active = writing.clone().into_active_model();
+        apply_file(&mut active, name, file_text);
+        let updated = active.update(self.db).await?;
+        self.result.writings_updated += 1;
+        Ok(updated)
+    }
+
+    /// A new writing from a file, tracked by `state` if given
+    async fn create_writing(
+        &mut self,
+        state: Option<writing_sync_files::Model>,
+        name: &str,
+        file_text: &str,
+    ) -> AppResult<()> {
+        let now = Utc::now();
+        let mut active = writings::ActiveModel {
+            is_pinned: Set(0),
+            is_featured: Set(0),
+            created_at: Set(now),
+            ..Default::default()
+        };
+        apply_file(&mut active, name, file_text);
+        let created = active.insert(self.db).await?;
+        self.result.writings_created += 1;
+        let app_text = markdown_file(&created, false);
+        self.track(state, created.id, name, file_text, &app_text)
+            .await
+    }
+
+    /// Record a writing and its file as in sync
+    async fn track(
+        &mut self,
+        state: Option<writing_sync_files::Model>,
+        writing_id: i64,
+        name: &str,
+        file_text: &str,
+        app_text: &str,
+    ) -> AppResult<()> {
+        let mut active = match state {
+            Some(state) => state.into_active_model(),
+            None => writing_sync_files::ActiveModel {
+                folder: Set(self.folder_key.clone()),
+                file_name: Set(name.to_string()),
+                ..Default::default()
+            },
+        };
+        active.writing_id = Set(writing_id);
+        active.file_hash = Set(sha256(file_text));
+        active.writing_hash = Set(sha256(app_text));
+        active.synced_at = Set(Utc::now());
+        active.save(self.db).await?;
+
+        // A settled conflict leaves no copy behind
+        let copy = self.folder.join(conflict_name(name));
+        if copy.is_file() {
+            self.remove_file(&conflict_name(name))?;
+        }
+        Ok(())
+    }
+
+    fn conflict(&mut self, name: &str, writing_id: Option<i64>, kind: SyncConflictKind) {
+        warn!(file = %name, ?kind, "Writing sync conflict");
+        self.result.conflicts.push(SyncConflictDto {
+            file_name: name.to_string(),
+            writing_id,
+            kind,
+        });
+    }
+
+    fn write_file(&mut self, name: &str, text: &str) -> AppResult<()> {
+        let path = self.folder.join(name);
+        fs::write(&path, text)
+            .map_err(|e| AppError::file_operation("write", path.to_string_lossy(), e))?;
+        if !name.ends_with(CONFLICT_SUFFIX) {
+            self.result.files_written += 1;
+        }
+        Ok(())
+    }
+
+    fn remove_file(&self, name: &str) -> AppResult<()> {
+        let path = self.folder.join(name);
+        fs::remove_file(&path)
+            .map_err(|e| AppError::file_operation("delete", path.to_string_lossy(), e))
+    }
+}
+
+/// Archived writings leave the folder
+fn is_mirrored(writing: &writings::Model) -> bool {
+    writing.status != writings::WritingStatus::Archived
+}
+
+/// The Markdown files of the folder by name, leaving out conflict copies
+fn read_folder(folder: &Path) -> AppResult<BTreeMap<String, String>> {
+    let entries = fs::read_dir(folder)
+        .map_err(|e| AppError::file_operation("read directory", folder.to_string_lossy(), e))?;
+    let mut files = BTreeMap::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || !name.ends_with(".md") || name.ends_with(CONFLICT_SUFFIX) {
+            continue;
+        }
+        if !entry.file_type().is_ok_and(|t| t.is_file()) {
+            continue;
+        }
+        match fs::read_to_string(entry.path()) {
+            Ok(text) => {
+                files.insert(name, text);
+            }
+            Err(e) => warn!(file = %name, error = %e, "Skipping unreadable file"),
+        }
+    }
+    Ok(files)
+}
+
+/// Set a writing's metadata and content from a Markdown file; a file without
+/// a title is titled after its name
+fn apply_file(active: &mut writings::ActiveModel, name: &str, file_text: &str) {
+    let (front, body) = FrontMatter::parse(file_text);
+    let title = front
+        .text("title")
+        .unwrap_or_else(|| name.strip_suffix(".md").unwrap_or(name));
+    let content = markdown_to_tiptap(body);
+
+    active.title = Set(title.to_string());
+    active.slug = Set(front.text("slug").map(str::to_string));
+    active.r#type = Set(match front.text("type") {
+        Some("chapter") => writings::WritingType::Chapter,
+        Some("book") => writings::WritingType::Book,
+        _ => writings::WritingType::Article,
+    });
+    // A file only exists for writings that aren't archived
+    active.status = Set(match front.text("status") {
+        Some("in_progress") => writings::WritingStatus::InProgress,
+        Some("review") => writings::WritingStatus::Review,
+        Some("published") => writings::WritingStatus::Published,
+        _ => writings::WritingStatus::Draft,
+    });
+    active.tags = Set(tags_to_json(&front.list("tags")));
+    active.excerpt = Set(front.text("excerpt").map(str::to_string));
+    active.series_name = Set(front.text("series").map(str::to_string));
+    active.series_part = Set(front.text("series_part").and_then(|p| p.parse().ok()));
+    active.published_at = Set(front
+        .text("published_at")
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc)));
+    active.word_count = Set(word_count(&extract_plain_text(&content)));
+    active.content_markdown = Set(content.to_string());
+    active.updated_at = Set(Utc::now());
+}
+
+fn conflict_name(name: &str) -> String {
+    format!(
+        "{}{}",
+        name.strip_suffix(".md").unwrap_or(name),
+        CONFLICT_SUFFIX
+    )
+}
+
+fn sha256(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_file() {
+        let writing = writings::Model {
+            id: 7,
+            r#type: writings::WritingType::Book,
+            title: "Cats & \"dogs\"".to_string(),
+            slug: Some("cats".to_string()),
+            content_markdown: "# Intro\n\nHello *there*.".to_string(),
+            excerpt: Some("Two\nlines".to_string()),
+            status: writings::WritingStatus::Review,
+            tags: Some("[\"pets\",\"a, b\"]".to_string()),
+            word_count: 3,
+            series_name: Some("Animals".to_string()),
+            series_part: Some(2),
+            is_pinned: 0,
+            is_featured: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            published_at: None,
+        };
+        let text = markdown_file(&writing, false);
+        assert!(!text.contains("updated_at"));
+
+        // The exported file reads back as the same writing
+        let mut active = writing.clone().into_active_model();
+        apply_file(&mut active, "cats.md", &text);
+        assert_eq!(active.title.as_ref(), &writing.title);
+        assert_eq!(active.slug.as_ref(), &writing.slug);
+        assert_eq!(active.r#type.as_ref(), &writing.r#type);
+        assert_eq!(active.status.as_ref(), &writing.status);
+        assert_eq!(active.tags.as_ref(), &writing.tags);
+        assert_eq!(active.excerpt.as_ref(), &writing.excerpt);
+        assert_eq!(active.series_part.as_ref(), &Some(2));
+        let mut round_trip = writing.clone();
+        round_trip.content_markdown = active.content_markdown.as_ref().clone();
+        assert_eq!(markdown_file(&round_trip, false), text);
+
+        apply_file(&mut active, "notes.md", "Just text");
+        assert_eq!(*active.title.as_ref(), "notes");
+        assert_eq!(active.status.as_ref(), &writings::WritingStatus::Draft);
+        assert_eq!(conflict_name("notes.md"), "notes.conflict.md");
+    }
+}
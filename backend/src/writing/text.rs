@@ -1,9 +1,12 @@
 //! Text extraction utilities for TipTap editor JSON
 //!
 //! Provides functions to extract plain text from TipTap/ProseMirror JSON
-//! for search indexing and word count calculations.
+//! for search indexing and word count calculations, and to render stored
+//! writing content as HTML or Markdown for outbound formats (email, exports).
+//! Markdown can also be parsed into TipTap JSON, for formats that walk the
+//! document tree.
 
-use serde_json::Value as JsonValue;
+use serde_json::{json, Value as JsonValue};
 
 /// Extracts plain text from TipTap editor JSON format
 ///
@@ -67,6 +70,687 @@ pub fn word_count(text: &str) -> i32 {
     text.split_whitespace().count() as i32
 }
 
+/// Renders stored writing content as HTML
+///
+/// Writings created through the draft editor store TipTap JSON in
+/// `content_markdown`; knowledge-graph writings store plain Markdown.
+/// Valid TipTap JSON is rendered node-by-node, anything else is treated
+/// as Markdown.
+pub fn content_to_html(content: &str) -> String {
+    match serde_json::from_str::<JsonValue>(content) {
+        Ok(doc @ JsonValue::Object(_)) => tiptap_to_html(&doc),
+        _ => markdown_to_html(content),
+    }
+}
+
+/// Renders Markdown to HTML (CommonMark + tables, strikethrough, footnotes)
+pub fn markdown_to_html(markdown: &str) -> String {
+    use pulldown_cmark::{html, Options, Parser};
+
+    let mut opts = Options::empty();
+    opts.insert(Options::ENABLE_TABLES);
+    opts.insert(Options::ENABLE_STRIKETHROUGH);
+    opts.insert(Options::ENABLE_FOOTNOTES);
+
+    let mut out = String::new();
+    html::push_html(&mut out, Parser::new_ext(markdown, opts));
+    out
+}
+
+/// Renders a TipTap JSON document to HTML
+///
+/// Covers the node and mark types enabled in the editor (StarterKit + Link).
+/// Unknown nodes are rendered as their children so no text is lost.
+pub fn tiptap_to_html(doc: &JsonValue) -> String {
+    let mut out = String::new();
+    render_node(doc, &mut out);
+    out
+}
+
+/// Escapes text for safe inclusion in HTML bodies and attributes
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn render_children(node: &JsonValue, out: &mut String) {
+    if let Some(JsonValue::Array(content)) = node.get("content") {
+        for child in content {
+            render_node(child, out);
+        }
+    }
+}
+
+fn render_node(node: &JsonValue, out: &mut String) {
+    let node_type = node.get("type").and_then(|t| t.as_str()).unwrap_or("");
+    let attrs = node.get("attrs");
+
+    let wrap = |tag: &str, out: &mut String| {
+        out.push_str(&format!("<{}>", tag));
+        render_children(node, out);
+        out.push_str(&format!("</{}>", tag));
+    };
+
+    match node_type {
+        "text" => render_text(node, out),
+        "paragraph" => wrap("p", out),
+        "heading" => {
+            let level = attrs
+                .and_then(|a| a.get("level"))
+                .and_then(|l| l.as_u64())
+                .unwrap_or(2)
+                .clamp(1, 6);
+            wrap(&format!("h{}", level), out);
+        }
+        "bulletList" => wrap("ul", out),
+        "orderedList" => wrap("ol", out),
+        "listItem" => wrap("li", out),
+        "blockquote" => wrap("blockquote", out),
+        "codeBlock" => {
+            out.push_str("<pre><code>");
+            render_children(node, out);
+            out.push_str("</code></pre>");
+        }
+        "hardBreak" => out.push_str("<br />"),
+        "horizontalRule" => out.push_str("<hr />"),
+        "image" => {
+            if let Some(src) = attrs.and_then(|a| a.get("src")).and_then(|s| s.as_str()) {
+                let alt = attrs
+                    .and_then(|a| a.get("alt"))
+                    .and_then(|s| s.as_str())
+                    .unwrap_or("");
+                out.push_str(&format!(
+                    "<img src=\"{}\" alt=\"{}\" />",
+                    escape_html(src),
+                    escape_html(alt)
+                ));
+            }
+        }
+        _ => render_children(node, out),
+    }
+}
+
+fn render_text(node: &JsonValue, out: &mut String) {
+    let Some(text) = node.get("text").and_then(|t| t.as_str()) else {
+        return;
+    };
+    let marks: Vec<&JsonValue> = node
+        .get("marks")
+        .and_then(|m| m.as_array())
+        .map(|m| m.iter().collect())
+        .unwrap_or_default();
+
+    let mut open = String::new();
+    let mut close = Vec::new();
+    for mark in &marks {
+        let (start, end) = match mark.get("type").and_then(|t| t.as_str()).unwrap_or("") {
+            "bold" => ("<strong>".to_string(), "</strong>"),
+            "italic" => ("<em>".to_string(), "</em>"),
+            "strike" => ("<s>".to_string(), "</s>"),
+            "underline" => ("<u>".to_string(), "</u>"),
+            "code" => ("<code>".to_string(), "</code>"),
+            "link" => {
+                let href = mark
+                    .get("attrs")
+                    .and_then(|a| a.get("href"))
+                    .and_then(|h| h.as_str())
+                    .unwrap_or("#");
+                (format!("<a href=\"{}\">", escape_html(href)), "</a>")
+            }
+            _ => continue,
+        };
+        open.push_str(&start);
+        close.push(end);
+    }
+
+    out.push_str(&open);
+    out.push_str(&escape_html(text));
+    for end in close.iter().rev() {
+        out.push_str(end);
+    }
+}
+
+/// Renders stored writing content as Markdown
+///
+/// TipTap JSON is converted; content that is not TipTap JSON is already
+/// Markdown and returned unchanged.
+pub fn content_to_markdown(content: &str) -> String {
+    match serde_json::from_str::<JsonValue>(content) {
+        Ok(doc @ JsonValue::Object(_)) => tiptap_to_markdown(&doc),
+        _ => content.to_string(),
+    }
+}
+
+/// Renders a TipTap JSON document to Markdown
+///
+/// Covers the same nodes and marks as [`tiptap_to_html`]. Underline has no
+/// Markdown syntax and is dropped; unknown nodes keep their children.
+pub fn tiptap_to_markdown(doc: &JsonValue) -> String {
+    let mut out = md_block(doc);
+    out.push('\n');
+    out
+}
+
+pub(crate) fn node_type(node: &JsonValue) -> &str {
+    node.get("type").and_then(|t| t.as_str()).unwrap_or("")
+}
+
+pub(crate) fn attr<'a>(node: &'a JsonValue, name: &str) -> Option<&'a JsonValue> {
+    node.get("attrs").and_then(|a| a.get(name))
+}
+
+pub(crate) fn children(node: &JsonValue) -> &[JsonValue] {
+    node.get("content")
+        .and_then(|c| c.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Block children separated by blank lines; inside list items a nested list
+/// follows its paragraph directly so the list stays tight
+fn md_blocks(node: &JsonValue) -> String {
+    let in_item = node_type(node) == "listItem";
+    let mut out = String::new();
+    for child in children(node) {
+        let block = md_block(child);
+        if block.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            let nested = matches!(node_type(child), "bulletList" | "orderedList");
+            out.push_str(if in_item && nested { "\n" } else { "\n\n" });
+        }
+        out.push_str(&block);
+    }
+    out
+}
+
+fn md_block(node: &JsonValue) -> String {
+    match node_type(node) {
+        "paragraph" => md_inline(node),
+        "heading" => {
+            let level = attr(node, "level")
+                .and_then(|l| l.as_u64())
+                .unwrap_or(2)
+                .clamp(1, 6);
+            format!("{} {}", "#".repeat(level as usize), md_inline(node))
+        }
+        "bulletList" => md_list(node, |_| "- ".to_string()),
+        "orderedList" => {
+            let start = attr(node, "start").and_then(|s| s.as_u64()).unwrap_or(1);
+            md_list(node, |i| format!("{}. ", start + i as u64))
+        }
+        "blockquote" => prefix_lines(&md_blocks(node), "> ", ">"),
+        "codeBlock" => {
+            let code: String = children(node)
+                .iter()
+                .filter_map(|c| c.get("text").and_then(|t| t.as_str()))
+                .collect();
+            let mut fence = "```".to_string();
+            while code.contains(&fence) {
+                fence.push('`');
+            }
+            let language = attr(node, "language")
+                .and_then(|l| l.as_str())
+                .unwrap_or("");
+            format!("{fence}{language}\n{code}\n{fence}")
+        }
+        "horizontalRule" => "---".to_string(),
+        "image" => md_image(node),
+        "text" | "hardBreak" => md_inline_node(node),
+        _ => md_blocks(node),
+    }
+}
+
+/// A list with each item's continuation lines indented under its marker
+fn md_list(node: &JsonValue, marker: impl Fn(usize) -> String) -> String {
+    children(node)
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let marker = marker(i);
+            let body = md_blocks(item);
+            let (first, rest) = body.split_once('\n').unwrap_or((&body, ""));
+            let mut out = format!("{}{}", marker, first);
+            if !rest.is_empty() {
+                out.push('\n');
+                out.push_str(&prefix_lines(rest, &" ".repeat(marker.len()), ""));
+            }
+            out
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prefixes every line, using `empty` for blank ones
+fn prefix_lines(text: &str, prefix: &str, empty: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                empty.to_string()
+            } else {
+                format!("{}{}", prefix, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn md_inline(node: &JsonValue) -> String {
+    children(node).iter().map(md_inline_node).collect()
+}
+
+fn md_inline_node(node: &JsonValue) -> String {
+    match node_type(node) {
+        "text" => md_text(node),
+        "hardBreak" => "\\\n".to_string(),
+        "image" => md_image(node),
+        _ => md_inline(node),
+    }
+}
+
+fn md_image(node: &JsonValue) -> String {
+    let Some(src) = attr(node, "src").and_then(|s| s.as_str()) else {
+        return String::new();
+    };
+    let alt = attr(node, "alt").and_then(|a| a.as_str()).unwrap_or("");
+    match attr(node, "title").and_then(|t| t.as_str()) {
+        Some(title) => format!(
+            "![{}]({} \"{}\")",
+            escape_markdown(alt),
+            src,
+            title.replace('"', "\\\"")
+        ),
+        None => format!("![{}]({})", escape_markdown(alt), src),
+    }
+}
+
+fn md_text(node: &JsonValue) -> String {
+    let Some(text) = node.get("text").and_then(|t| t.as_str()) else {
+        return String::new();
+    };
+    let marks = node.get("marks").and_then(|m| m.as_array());
+    let marks = marks.map(Vec::as_slice).unwrap_or_default();
+
+    if marks.iter().any(|m| node_type(m) == "code") {
+        let ticks = if text.contains('`') { "``" } else { "`" };
+        return wrap_marks(&format!("{ticks}{text}{ticks}"), marks);
+    }
+    // Emphasis markers must hug the text, so surrounding spaces stay outside
+    let inner = text.trim();
+    if inner.is_empty() {
+        return text.to_string();
+    }
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    format!(
+        "{}{}{}",
+        leading,
+        wrap_marks(&escape_markdown(inner), marks),
+        trailing
+    )
+}
+
+fn wrap_marks(text: &str, marks: &[JsonValue]) -> String {
+    marks
+        .iter()
+        .fold(text.to_string(), |inner, mark| match node_type(mark) {
+            "bold" => format!("**{}**", inner),
+            "italic" => format!("*{}*", inner),
+            "strike" => format!("~~{}~~", inner),
+            "link" => {
+                let href = attr(mark, "href").and_then(|h| h.as_str()).unwrap_or("#");
+                format!("[{}]({})", inner, href)
+            }
+            _ => inner,
+        })
+}
+
+/// Escapes characters Markdown would read as inline syntax
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Parses stored writing content as a TipTap JSON document
+///
+/// TipTap JSON is returned as is; anything else is read as Markdown.
+pub fn content_to_tiptap(content: &str) -> JsonValue {
+    match serde_json::from_str::<JsonValue>(content) {
+        Ok(doc @ JsonValue::Object(_)) => doc,
+        _ => markdown_to_tiptap(content),
+    }
+}
+
+/// Parses Markdown into a TipTap JSON document
+///
+/// Produces the nodes and marks of [`tiptap_to_html`], with images lifted out
+/// of paragraphs as the editor keeps them as blocks. Footnote references
+/// become `footnoteReference` nodes, and their definitions `footnote` nodes
+/// in a closing `footnotes` node, all with the label as `id`. Table rows
+/// become paragraphs with cells separated by ` | `; raw HTML is dropped.
+pub fn markdown_to_tiptap(markdown: &str) -> JsonValue {
+    use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+    let mut opts = Options::empty();
+    opts.insert(Options::ENABLE_TABLES);
+    opts.insert(Options::ENABLE_STRIKETHROUGH);
+    opts.insert(Options::ENABLE_FOOTNOTES);
+
+    let mut doc = TiptapBuilder::new();
+    for event in Parser::new_ext(markdown, opts) {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Paragraph | Tag::TableHead | Tag::TableRow => doc.open("paragraph", None),
+                Tag::Heading { level, .. } => {
+                    doc.open("heading", Some(json!({ "level": level as u8 })))
+                }
+                Tag::BlockQuote(_) => doc.open("blockquote", None),
+                Tag::CodeBlock(kind) => {
+                    let language = match &kind {
+                        CodeBlockKind::Fenced(info) => info.split_whitespace().next(),
+                        CodeBlockKind::Indented => None,
+                    };
+                    doc.open("codeBlock", Some(json!({ "language": language })));
+                }
+                Tag::List(Some(start)) => doc.open("orderedList", Some(json!({ "start": start }))),
+                Tag::List(None) => doc.open("bulletList", None),
+                Tag::Item => doc.open("listItem", None),
+                Tag::FootnoteDefinition(label) => {
+                    doc.open("footnote", Some(json!({ "id": label.as_ref() })))
+                }
+                Tag::TableCell if !doc.current_is_empty() => doc.text(" | ", None),
+                Tag::Emphasis => doc.marks.push(json!({ "type": "italic" })),
+                Tag::Strong => doc.marks.push(json!({ "type": "bold" })),
+                Tag::Strikethrough => doc.marks.push(json!({ "type": "strike" })),
+                Tag::Link { dest_url, .. } => doc
+                    .marks
+                    .push(json!({ "type": "link", "attrs": { "href": dest_url.as_ref() } })),
+                Tag::Image {
+                    dest_url, title, ..
+                } => doc.image = Some((dest_url.to_string(), title.to_string(), String::new())),
+                _ => {}
+            },
+            Event::End(tag) => match tag {
+                TagEnd::Paragraph
+                | TagEnd::TableHead
+                | TagEnd::TableRow
+                | TagEnd::Heading(_)
+                | TagEnd::BlockQuote(_)
+                | TagEnd::CodeBlock
+                | TagEnd::List(_)
+                | TagEnd::Item => doc.close(),
+                TagEnd::FootnoteDefinition => {
+                    let footnote = doc.pop();
+                    doc.footnotes.push(footnote);
+                }
+                TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link => {
+                    doc.marks.pop();
+                }
+                TagEnd::Image => doc.close_image(),
+                _ => {}
+            },
+            Event::Text(text) | Event::InlineMath(text) | Event::DisplayMath(text) => {
+                match &mut doc.image {
+                    Some((_, _, alt)) => alt.push_str(&text),
+                    None => doc.text(&text, None),
+                }
+            }
+            Event::Code(text) => match &mut doc.image {
+                Some((_, _, alt)) => alt.push_str(&text),
+                None => doc.text(&text, Some(json!({ "type": "code" }))),
+            },
+            Event::SoftBreak => doc.text(" ", None),
+            Event::HardBreak => doc.inline(json!({ "type": "hardBreak" })),
+            Event::Rule => doc.block(json!({ "type": "horizontalRule" })),
+            Event::FootnoteReference(label) => doc.inline(json!({
+                "type": "footnoteReference",
+                "attrs": { "id": label.as_ref() }
+            })),
+            Event::Html(_) | Event::InlineHtml(_) | Event::TaskListMarker(_) => {}
+        }
+    }
+    doc.finish()
+}
+
+/// An open node while building a TipTap document
+struct Frame {
+    node: JsonValue,
+    /// Opened for bare inline content, so closed without an end event
+    auto: bool,
+    /// Left out if still empty when closed
+    drop_empty: bool,
+}
+
+/// Builds a TipTap document from a stream of open, content and close calls
+struct TiptapBuilder {
+    stack: Vec<Frame>,
+    marks: Vec<JsonValue>,
+    /// Source, title and alt text of the image being read
+    image: Option<(String, String, String)>,
+    footnotes: Vec<JsonValue>,
+}
+
+impl TiptapBuilder {
+    fn new() -> Self {
+        TiptapBuilder {
+            stack: vec![Frame {
+                node: json!({ "type": "doc", "content": [] }),
+                auto: false,
+                drop_empty: false,
+            }],
+            marks: Vec::new(),
+            image: None,
+            footnotes: Vec::new(),
+        }
+    }
+
+    fn open(&mut self, node_type: &str, attrs: Option<JsonValue>) {
+        self.close_auto();
+        let mut node = json!({ "type": node_type, "content": [] });
+        if let Some(attrs) = attrs {
+            node["attrs"] = attrs;
+        }
+        self.stack.push(Frame {
+            node,
+            auto: false,
+            drop_empty: false,
+        });
+    }
+
+    fn close(&mut self) {
+        let node = self.pop();
+        self.append(node);
+    }
+
+    /// Closes the innermost node, returning it instead of adding it to its
+    /// parent
+    fn pop(&mut self) -> JsonValue {
+        self.close_auto();
+        self.pop_frame().unwrap_or(JsonValue::Null)
+    }
+
+    fn pop_frame(&mut self) -> Option<JsonValue> {
+        if self.stack.len() < 2 {
+            return None;
+        }
+        self.stack.pop().and_then(Self::finish_frame)
+    }
+
+    fn finish_frame(frame: Frame) -> Option<JsonValue> {
+        let mut node = frame.node;
+        if node_type(&node) == "codeBlock" {
+            // One text node, without the newline closing the block
+            let code: String = children(&node)
+                .iter()
+                .filter_map(|t| t.get("text").and_then(|t| t.as_str()))
+                .collect();
+            let code = code.strip_suffix('\n').unwrap_or(&code);
+            node["content"] = json!([{ "type": "text", "text": code }]);
+        }
+        if children(&node).is_empty() {
+            if frame.drop_empty {
+                return None;
+            }
+            if let Some(node) = node.as_object_mut() {
+                node.remove("content");
+            }
+        }
+        Some(node)
+    }
+
+    fn close_auto(&mut self) {
+        while self.stack.last().is_some_and(|f| f.auto) {
+            if let Some(node) = self.pop_frame() {
+                self.append(node);
+            }
+        }
+    }
+
+    fn append(&mut self, node: JsonValue) {
+        if node.is_null() {
+            return;
+        }
+        if let Some(JsonValue::Array(content)) = self
+            .stack
+            .last_mut()
+            .and_then(|f| f.node.get_mut("content"))
+        {
+            content.push(node);
+        }
+    }
+
+    fn current_is_empty(&self) -> bool {
+        self.stack
+            .last()
+            .is_some_and(|f| children(&f.node).is_empty())
+    }
+
+    fn in_inline_container(&self) -> bool {
+        self.stack
+            .last()
+            .is_some_and(|f| matches!(node_type(&f.node), "paragraph" | "heading" | "codeBlock"))
+    }
+
+    /// Adds an inline node, opening a paragraph if the parent holds blocks
+    fn inline(&mut self, node: JsonValue) {
+        if !self.in_inline_container() {
+            self.stack.push(Frame {
+                node: json!({ "type": "paragraph", "content": [] }),
+                auto: true,
+                drop_empty: true,
+            });
+        }
+        self.append(node);
+    }
+
+    fn text(&mut self, text: &str, mark: Option<JsonValue>) {
+        if text.is_empty() {
+            return;
+        }
+        let mut marks = self.marks.clone();
+        marks.extend(mark);
+        // Continue the previous text node when its marks match
+        if self.in_inline_container() {
+            let last = self
+                .stack
+                .last_mut()
+                .and_then(|f| f.node.get_mut("content"))
+                .and_then(|c| c.as_array_mut())
+                .and_then(|c| c.last_mut());
+            if let Some(last) = last {
+                let last_marks = last.get("marks").cloned().unwrap_or_else(|| json!([]));
+                if node_type(last) == "text" && last_marks == json!(marks) {
+                    let joined = format!("{}{}", last["text"].as_str().unwrap_or(""), text);
+                    last["text"] = joined.into();
+                    return;
+                }
+            }
+        }
+        let mut node = json!({ "type": "text", "text": text });
+        if !marks.is_empty() {
+            node["marks"] = marks.into();
+        }
+        self.inline(node);
+    }
+
+    fn block(&mut self, node: JsonValue) {
+        self.close_auto();
+        self.append(node);
+    }
+
+    /// Adds the image being read as a block, splitting the paragraph or
+    /// heading it was found in
+    fn close_image(&mut self) {
+        let Some((src, title, alt)) = self.image.take() else {
+            return;
+        };
+        let image = json!({
+            "type": "image",
+            "attrs": {
+                "src": src,
+                "alt": (!alt.is_empty()).then_some(alt),
+                "title": (!title.is_empty()).then_some(title),
+            }
+        });
+        if !self.in_inline_container() {
+            self.append(image);
+            return;
+        }
+        let Some(frame) = self.stack.pop() else {
+            return;
+        };
+        let mut rest = json!({ "type": node_type(&frame.node), "content": [] });
+        if let Some(attrs) = frame.node.get("attrs") {
+            rest["attrs"] = attrs.clone();
+        }
+        let auto = frame.auto;
+        if let Some(node) = Self::finish_frame(Frame {
+            drop_empty: true,
+            ..frame
+        }) {
+            self.append(node);
+        }
+        self.append(image);
+        self.stack.push(Frame {
+            node: rest,
+            auto,
+            drop_empty: true,
+        });
+    }
+
+    fn finish(mut self) -> JsonValue {
+        while self.stack.len() > 1 {
+            if let Some(node) = self.pop_frame() {
+                self.append(node);
+            }
+        }
+        let mut doc = self.stack.pop().map(|f| f.node).unwrap_or_default();
+        if !self.footnotes.is_empty() {
+            if let Some(JsonValue::Array(content)) = doc.get_mut("content") {
+                content.push(json!({ "type": "footnotes", "content": self.footnotes }));
+            }
+        }
+        doc
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +802,165 @@ mod tests {
         assert_eq!(word_count("  Multiple   spaces  "), 2);
         assert_eq!(word_count(""), 0);
     }
+
+    #[test]
+    fn test_tiptap_to_html_marks_and_blocks() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": { "level": 2 },
+                    "content": [{ "type": "text", "text": "Title" }]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        { "type": "text", "text": "Read " },
+                        {
+                            "type": "text",
+                            "text": "this & that",
+                            "marks": [
+                                { "type": "bold" },
+                                { "type": "link", "attrs": { "href": "https://example.com" } }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        });
+
+        assert_eq!(
+            tiptap_to_html(&doc),
+            "<h2>Title</h2><p>Read <strong><a href=\"https://example.com\">this &amp; that</a></strong></p>"
+        );
+    }
+
+    #[test]
+    fn test_content_to_html_falls_back_to_markdown() {
+        assert_eq!(content_to_html("# Hi\n\nPlain *text*"), "<h1>Hi</h1>\n<p>Plain <em>text</em></p>\n");
+    }
+
+    #[test]
+    fn test_tiptap_to_markdown_blocks() {
+        let doc = json!({
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": { "level": 1 },
+                    "content": [{ "type": "text", "text": "Notes" }]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        { "type": "text", "text": "See " },
+                        {
+                            "type": "text",
+                            "text": "the docs ",
+                            "marks": [
+                                { "type": "bold" },
+                                { "type": "link", "attrs": { "href": "https://example.com" } }
+                            ]
+                        },
+                        { "type": "text", "text": "for *details*" }
+                    ]
+                },
+                {
+                    "type": "orderedList",
+                    "attrs": { "start": 1 },
+                    "content": [
+                        {
+                            "type": "listItem",
+                            "content": [
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "One" }] },
+                                {
+                                    "type": "bulletList",
+                                    "content": [{
+                                        "type": "listItem",
+                                        "content": [
+                                            { "type": "paragraph", "content": [{ "type": "text", "text": "Nested" }] }
+                                        ]
+                                    }]
+                                }
+                            ]
+                        },
+                        {
+                            "type": "listItem",
+                            "content": [
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "Two" }] }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": { "language": "rust" },
+                    "content": [{ "type": "text", "text": "let x = 1;" }]
+                },
+                { "type": "image", "attrs": { "src": "https://example.com/a.png", "alt": "A chart" } }
+            ]
+        });
+
+        assert_eq!(
+            tiptap_to_markdown(&doc),
+            "# Notes\n\nSee [**the docs**](https://example.com) for \\*details\\*\n\n\
+             1. One\n   - Nested\n2. Two\n\n```rust\nlet x = 1;\n```\n\n\
+             ![A chart](https://example.com/a.png)\n"
+        );
+    }
+
+    #[test]
+    fn test_content_to_markdown_keeps_markdown() {
+        assert_eq!(
+            content_to_markdown("# Hi\n\nPlain *text*"),
+            "# Hi\n\nPlain *text*"
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_tiptap() {
+        let doc = markdown_to_tiptap(
+            "Hi *there*[^n]\n\n- a ![x](https://example.com/x.png)\n\n[^n]: Note\n",
+        );
+        assert_eq!(
+            doc,
+            json!({
+                "type": "doc",
+                "content": [
+                    {
+                        "type": "paragraph",
+                        "content": [
+                            { "type": "text", "text": "Hi " },
+                            { "type": "text", "text": "there", "marks": [{ "type": "italic" }] },
+                            { "type": "footnoteReference", "attrs": { "id": "n" } }
+                        ]
+                    },
+                    {
+                        "type": "bulletList",
+                        "content": [{
+                            "type": "listItem",
+                            "content": [
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "a " }] },
+                                {
+                                    "type": "image",
+                                    "attrs": { "src": "https://example.com/x.png", "alt": "x", "title": null }
+                                }
+                            ]
+                        }]
+                    },
+                    {
+                        "type": "footnotes",
+                        "content": [{
+                            "type": "footnote",
+                            "attrs": { "id": "n" },
+                            "content": [
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "Note" }] }
+                            ]
+                        }]
+                    }
+                ]
+            })
+        );
+    }
 }
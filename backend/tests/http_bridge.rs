@@ -3,7 +3,8 @@ use axum::http::{Request, StatusCode};
 use axum::Router;
 use cockpit::bridge::dispatch::{BridgeContext, CommandRequest};
 use cockpit::core::components::config::types::{
-    AppConfig, CryptoConfig, DatabaseConfig, LoggingConfig, NewsDataConfig, StorageConfig,
+    AiConfig, AppConfig, CryptoConfig, DatabaseConfig, EmailConfig, EmbeddingsConfig,
+    EmbeddingsProvider, LoggingConfig, NewsDataConfig, SmtpTls, StorageConfig,
 };
 use cockpit::core::components::events::{EventEmitter, NoopEventEmitter};
 use cockpit::system::scheduler::start_scheduler;
@@ -64,6 +65,31 @@ fn dummy_config(root: &PathBuf) -> AppConfig {
         crypto: CryptoConfig {
             master_key: "0000000000000000000000000000000000000000000000000000000000000000".into(),
         },
+        email: EmailConfig {
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_tls: SmtpTls::StartTls,
+            from_address: None,
+            default_recipients: vec![],
+            template_path: None,
+            send_timeout: std::time::Duration::from_secs(5),
+        },
+        embeddings: EmbeddingsConfig {
+            provider: EmbeddingsProvider::Disabled,
+            model: String::new(),
+            openai_api_key: None,
+            openai_base_url: "https://api.openai.com/v1".to_string(),
+            ollama_base_url: "http://localhost:11434".to_string(),
+            batch_size: 32,
+            request_timeout: std::time::Duration::from_secs(5),
+        },
+        ai: AiConfig {
+            ollama_base_url: "http://localhost:11434".to_string(),
+            ollama_model: "llama3.2".to_string(),
+            request_timeout: std::time::Duration::from_secs(5),
+        },
     }
 }
 